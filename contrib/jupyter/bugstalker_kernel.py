@@ -0,0 +1,188 @@
+"""Jupyter kernel for BugStalker debugger.
+
+Kernel starts `bs` with a remote control socket and executes cell lines as debugger commands.
+Command results (JSON mode) are displayed as JSON trees, debugee output is shown as a stream.
+"""
+
+import base64
+import contextlib
+import io
+import json
+import os
+import shlex
+import shutil
+import socket
+import subprocess
+import sys
+import tempfile
+import threading
+import time
+
+from ipykernel.kernelapp import IPKernelApp
+from ipykernel.kernelbase import Kernel
+
+DEBUGGER = os.environ.get('BUGSTALKER', 'bs')
+CONNECT_TIMEOUT = 10
+# figures are rendered into images and sent to a notebook
+os.environ.setdefault('MPLBACKEND', 'Agg')
+
+
+class DebuggerError(Exception):
+    pass
+
+
+class Session:
+    """Debugger process and a remote control connection to it."""
+
+    def __init__(self, program, args, on_output):
+        self.dir = tempfile.mkdtemp(prefix='bugstalker-')
+        socket_path = os.path.join(self.dir, 'bs.sock')
+        self.process = subprocess.Popen(
+            [DEBUGGER, '--json', '-t', 'none', '--remote', socket_path, program, '--', *args],
+            stdin=subprocess.PIPE, stdout=subprocess.PIPE, stderr=subprocess.STDOUT)
+        threading.Thread(target=self._forward, args=(on_output,), daemon=True).start()
+
+        deadline = time.monotonic() + CONNECT_TIMEOUT
+        while not os.path.exists(socket_path):
+            if self.process.poll() is not None or time.monotonic() > deadline:
+                self.close()
+                raise DebuggerError('debugger is not started')
+            time.sleep(0.05)
+        self.client = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+        self.client.connect(socket_path)
+        self.responses = self.client.makefile('r')
+        self.seq = 0
+
+    def _forward(self, on_output):
+        for line in iter(self.process.stdout.readline, b''):
+            on_output(line.decode(errors='replace'))
+
+    def call(self, method, **params):
+        self.seq += 1
+        request = {'jsonrpc': '2.0', 'id': self.seq, 'method': method, 'params': params}
+        self.client.sendall((json.dumps(request) + '\n').encode())
+        line = self.responses.readline()
+        if not line:
+            raise DebuggerError('debugger is terminated')
+        response = json.loads(line)
+        if 'error' in response:
+            raise DebuggerError(response['error']['message'])
+        return response['result']
+
+    def close(self):
+        if self.process.poll() is None:
+            self.process.stdin.close()
+            try:
+                self.process.wait(timeout=CONNECT_TIMEOUT)
+            except subprocess.TimeoutExpired:
+                self.process.kill()
+        shutil.rmtree(self.dir, ignore_errors=True)
+
+
+class BugStalkerKernel(Kernel):
+    implementation = 'bugstalker'
+    implementation_version = '0.1'
+    language = 'bugstalker'
+    language_info = {
+        'name': 'bugstalker',
+        'mimetype': 'text/plain',
+        'file_extension': '.txt',
+    }
+    banner = 'BugStalker - modern debugger for Linux x86-64, written in Rust for Rust programs'
+
+    def __init__(self, **kwargs):
+        super().__init__(**kwargs)
+        self.session = None
+        self.namespace = {'data': None}
+
+    def stream(self, text, name='stdout'):
+        self.send_response(self.iopub_socket, 'stream', {'name': name, 'text': text})
+
+    def display(self, data):
+        self.send_response(self.iopub_socket, 'display_data', {'data': data, 'metadata': {}})
+
+    def show_output(self, output):
+        """Display command output, JSON lines are shown as trees."""
+        text = []
+        for line in output.splitlines():
+            try:
+                value = json.loads(line)
+            except ValueError:
+                text.append(line)
+                continue
+            if text:
+                self.stream('\n'.join(text) + '\n')
+                text = []
+            if isinstance(value, (dict, list)):
+                self.display({'application/json': value, 'text/plain': json.dumps(value, indent=2)})
+            else:
+                text.append(line)
+        if text:
+            self.stream('\n'.join(text) + '\n')
+
+    def execute_python(self, code):
+        """Execute python code, show printed text and matplotlib figures (if any)."""
+        output = io.StringIO()
+        with contextlib.redirect_stdout(output):
+            exec(compile(code, '<cell>', 'exec'), self.namespace)
+        if output.getvalue():
+            self.stream(output.getvalue())
+
+        plt = sys.modules.get('matplotlib.pyplot')
+        if plt is None:
+            return
+        for number in plt.get_fignums():
+            image = io.BytesIO()
+            plt.figure(number).savefig(image, format='png')
+            self.display({'image/png': base64.b64encode(image.getvalue()).decode(),
+                          'text/plain': f'<figure {number}>'})
+        plt.close('all')
+
+    def execute_line(self, line):
+        if line.startswith('%debugee'):
+            program, *args = shlex.split(line[len('%debugee'):])
+            if self.session:
+                self.session.close()
+            self.session = Session(program, args, self.stream)
+            return
+        if line.startswith('%python'):
+            self.execute_python(line[len('%python'):])
+            return
+
+        if not self.session:
+            raise DebuggerError('no debugee, start a session with `%debugee <program> [args]`')
+        if line.startswith('%data'):
+            expression = line[len('%data'):].strip()
+            kind, _, expression = expression.partition(' ')
+            if kind not in ('var', 'arg'):
+                raise DebuggerError('usage: `%data var|arg <expression>`')
+            method = 'variables' if kind == 'var' else 'arguments'
+            params = {'expression': expression} if expression else {}
+            self.namespace['data'] = self.session.call(method, **params)
+            return
+        self.show_output(self.session.call('command', command=line, capture=True)['output'])
+
+    def do_execute(self, code, silent, store_history=True, user_expressions=None,
+                   allow_stdin=False):
+        for line in code.splitlines():
+            line = line.strip()
+            if not line or line.startswith('#'):
+                continue
+            try:
+                self.execute_line(line)
+            except Exception as e:
+                self.stream(f'{line}: {e}\n', name='stderr')
+                return {'status': 'error', 'execution_count': self.execution_count,
+                        'ename': type(e).__name__, 'evalue': str(e), 'traceback': []}
+        return {'status': 'ok', 'execution_count': self.execution_count,
+                'payload': [], 'user_expressions': {}}
+
+    def do_shutdown(self, restart):
+        if self.session:
+            self.session.close()
+            self.session = None
+        return {'status': 'ok', 'restart': restart}
+
+
+if __name__ == '__main__':
+    IPKernelApp.launch_instance(kernel_class=BugStalkerKernel)
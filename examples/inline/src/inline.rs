@@ -0,0 +1,14 @@
+#[inline(always)]
+fn double(v: u64) -> u64 {
+    v * 2
+}
+
+fn twice_plus_one<T: Into<u64>>(v: T) -> u64 {
+    let v = v.into();
+    double(v) + 1
+}
+
+fn main() {
+    println!("{}", twice_plus_one(1u8));
+    println!("{}", twice_plus_one(2u32));
+}
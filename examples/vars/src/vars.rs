@@ -550,4 +550,49 @@ pub fn main() {
     inner_static();
     shadowing();
     uuid();
+    option_result_time();
+    locks();
+    ffi_strings();
+}
+
+fn option_result_time() {
+    let opt_some: Option<i32> = Some(1);
+    let opt_none: Option<i32> = None;
+    let opt_str = Some("abc");
+    let res_ok: Result<u8, String> = Ok(2);
+    let res_err: Result<u8, String> = Err("fail".to_string());
+    let duration = std::time::Duration::from_millis(1500);
+    let system_time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+
+    let nop: Option<u8> = None;
+}
+
+fn locks() {
+    use std::sync::atomic;
+    use std::sync::{Mutex, RwLock};
+
+    let mutex = Mutex::new(1);
+    let mutex_guard = mutex.lock().unwrap();
+    let rwlock = RwLock::new(vec![1, 2]);
+    let rwlock_guard_1 = rwlock.read().unwrap();
+    let rwlock_guard_2 = rwlock.read().unwrap();
+    let bool_atomic = atomic::AtomicBool::new(true);
+    let usize_atomic = atomic::AtomicUsize::new(3);
+
+    let nop: Option<u8> = None;
+}
+
+fn ffi_strings() {
+    use std::ffi::{CStr, CString, OsString};
+    use std::os::raw::c_char;
+    use std::path::PathBuf;
+
+    let c_string = CString::new("c string").unwrap();
+    let c_str: &CStr = c_string.as_c_str();
+    let c_ptr: *const c_char = c_string.as_ptr();
+    let null_c_ptr: *const c_char = std::ptr::null();
+    let os_string = OsString::from("os string");
+    let path_buf = PathBuf::from("/tmp/file.txt");
+
+    let nop: Option<u8> = None;
 }
@@ -0,0 +1,222 @@
+//! `cargo bugstalker` subcommand: build a cargo target and debug it.
+
+use bugstalker::ui::cargo::{build, Target};
+use clap::{Args, Parser, Subcommand};
+use std::env;
+use std::os::unix::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{exit, Command};
+
+#[derive(Parser, Debug)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum Cargo {
+    /// Build a target with full debug information and start BugStalker
+    #[command(version)]
+    Bugstalker {
+        #[command(subcommand)]
+        command: TargetCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TargetCommand {
+    /// Debug a binary (or an example)
+    Run {
+        /// Binary name
+        #[clap(long)]
+        bin: Option<String>,
+
+        /// Example name
+        #[clap(long)]
+        example: Option<String>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+
+        /// Arguments are passed to debugee
+        #[arg(raw(true))]
+        args: Vec<String>,
+    },
+    /// Debug a test harness, tests are filtered by name and executed in a single thread
+    Test {
+        /// Run only tests whose names contain this string
+        filter: Option<String>,
+
+        /// Run only a test with exactly this name
+        #[clap(long)]
+        #[arg(default_value_t = false)]
+        exact: bool,
+
+        /// Debug unit tests of a library
+        #[clap(long)]
+        #[arg(default_value_t = false)]
+        lib: bool,
+
+        /// Debug unit tests of a binary
+        #[clap(long)]
+        bin: Option<String>,
+
+        /// Debug an integration test
+        #[clap(long)]
+        test: Option<String>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+
+        /// Arguments are passed to test harness
+        #[arg(raw(true))]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+struct CommonArgs {
+    /// Package to build
+    #[clap(short, long)]
+    package: Option<String>,
+
+    /// Space or comma separated list of features to activate
+    #[clap(short = 'F', long)]
+    features: Option<String>,
+
+    /// Activate all available features
+    #[clap(long)]
+    #[arg(default_value_t = false)]
+    all_features: bool,
+
+    /// Do not activate the `default` feature
+    #[clap(long)]
+    #[arg(default_value_t = false)]
+    no_default_features: bool,
+
+    /// Build in release mode (with debug information)
+    #[clap(short, long)]
+    #[arg(default_value_t = false)]
+    release: bool,
+
+    /// Path to Cargo.toml
+    #[clap(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Start with terminal ui
+    #[clap(long)]
+    #[arg(default_value_t = false)]
+    tui: bool,
+
+    /// Discover a specific oracle (maybe more than one)
+    #[clap(short, long)]
+    oracle: Vec<String>,
+}
+
+impl CommonArgs {
+    fn cargo_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(ref package) = self.package {
+            args.extend(["--package".to_string(), package.clone()]);
+        }
+        if let Some(ref features) = self.features {
+            args.extend(["--features".to_string(), features.clone()]);
+        }
+        if self.all_features {
+            args.push("--all-features".to_string());
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+        if self.release {
+            args.push("--release".to_string());
+        }
+        if let Some(ref path) = self.manifest_path {
+            args.extend([
+                "--manifest-path".to_string(),
+                path.to_string_lossy().to_string(),
+            ]);
+        }
+        args
+    }
+
+    fn debugger_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if self.tui {
+            args.push("--tui".to_string());
+        }
+        for oracle in &self.oracle {
+            args.extend(["--oracle".to_string(), oracle.clone()]);
+        }
+        args
+    }
+}
+
+fn option_arg(name: &str, value: Option<String>) -> Vec<String> {
+    value
+        .map(|value| vec![format!("--{name}"), value])
+        .unwrap_or_default()
+}
+
+/// Return path to `bs` executable, installed next to this one or available in `PATH`.
+fn debugger_path() -> PathBuf {
+    env::current_exe()
+        .ok()
+        .map(|exe| exe.with_file_name("bs"))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from("bs"))
+}
+
+fn main() {
+    let Cargo::Bugstalker { command } = Cargo::parse();
+
+    let (target, mut cargo_args, common, debugee_args) = match command {
+        TargetCommand::Run {
+            bin,
+            example,
+            common,
+            args,
+        } => {
+            let mut cargo_args = option_arg("bin", bin);
+            cargo_args.extend(option_arg("example", example));
+            (Target::Run, cargo_args, common, args)
+        }
+        TargetCommand::Test {
+            filter,
+            exact,
+            lib,
+            bin,
+            test,
+            common,
+            args,
+        } => {
+            let mut cargo_args = option_arg("bin", bin);
+            cargo_args.extend(option_arg("test", test));
+            if lib {
+                cargo_args.push("--lib".to_string());
+            }
+
+            // test threads are hard to follow in a debugger, and test output is useful
+            let mut harness_args: Vec<String> = filter.into_iter().collect();
+            if exact {
+                harness_args.push("--exact".to_string());
+            }
+            harness_args.extend(["--test-threads=1".to_string(), "--nocapture".to_string()]);
+            harness_args.extend(args);
+            (Target::Test, cargo_args, common, harness_args)
+        }
+    };
+    cargo_args.extend(common.cargo_args());
+
+    let executable = match build(target, &cargo_args) {
+        Ok(executable) => executable,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            exit(1);
+        }
+    };
+
+    let e = Command::new(debugger_path())
+        .args(common.debugger_args())
+        .arg(executable)
+        .arg("--")
+        .args(debugee_args)
+        .exec();
+    eprintln!("error: start debugger: {e}");
+    exit(1);
+}
@@ -90,7 +90,7 @@ impl GlobalAddress {
         debugee: &Debugee,
         segment: &DebugInformation,
     ) -> Result<RelocatedAddress, Error> {
-        let offset = debugee.mapping_offset_for_file(segment)?;
+        let offset = debugee.relocation_offset(segment, self)?;
         Ok(self.relocate(offset))
     }
 
@@ -104,7 +104,7 @@ impl GlobalAddress {
         debugee: &Debugee,
         pc: RelocatedAddress,
     ) -> Result<RelocatedAddress, Error> {
-        let offset = debugee.mapping_offset_for_pc(pc)?;
+        let offset = debugee.relocation_offset_by_pc(pc, self)?;
         Ok(self.relocate(offset))
     }
 
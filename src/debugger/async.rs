@@ -0,0 +1,535 @@
+//! Async runtime inspection: tasks of a tokio runtime.
+//!
+//! Tasks are enumerated by walking an owned task list of a runtime scheduler.
+//! A scheduler is found by a frame of a runtime thread, `multi_thread::worker::run`
+//! for worker threads of a multi-thread runtime and `current_thread::CurrentThread::block_on`
+//! for a current-thread runtime. Every task in a list starts with a
+//! `tokio::runtime::task::core::Header`, task state is read from a header, a type of wrapped future
+//! is taken from a name of a `poll::<F, S>` function in the task vtable.
+//!
+//! Futures of async fns (and async blocks) are state machines, rust compiler describes them
+//! as enums with a variant per state: `Unresumed`, `Returned`, `Panicked` and `SuspendN` for
+//! each await point. A variant contains locals that are alive in this state, awaited future is
+//! stored in the `__awaitee` member, and a variant declaration place is the await point itself.
+//! Following `__awaitee` members from a task future gives a logical async backtrace.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::error::Error;
+use crate::debugger::variable::render::{RenderRepr, ValueLayout};
+use crate::debugger::variable::select::{VariableSelector, DQE};
+use crate::debugger::variable::{SpecializedVariableIR, VariableIR};
+use crate::debugger::{Debugger, ThreadSelector};
+use crate::oracle::builtin::{find_member, pointer_value, usize_value};
+use crate::weak_error;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+
+/// Max count of tasks in a single task list, protects from a broken (or concurrently modified)
+/// list in debugee memory.
+const MAX_TASKS: usize = 100_000;
+
+/// Max count of frames in an async backtrace.
+const MAX_ASYNC_FRAMES: usize = 1024;
+
+/// Name of a state machine member that holds an awaited future.
+const AWAITEE_MEMBER: &str = "__awaitee";
+
+/// Runtime functions with an argument that points to a scheduler handle.
+/// Each pair is a function name and an expression that selects owned tasks of the scheduler.
+fn scheduler_roots() -> [(&'static str, DQE); 2] {
+    let arg = |name: &str| {
+        DQE::Variable(VariableSelector::Name {
+            var_name: name.to_string(),
+            only_local: true,
+        })
+    };
+    let field = |expr: DQE, name: &str| DQE::Field(expr.boxed(), name.to_string());
+    let deref = |expr: DQE| DQE::Deref(expr.boxed());
+
+    [
+        (
+            "tokio::runtime::scheduler::multi_thread::worker::run",
+            field(
+                field(deref(field(deref(arg("worker")), "handle")), "shared"),
+                "owned",
+            ),
+        ),
+        (
+            "tokio::runtime::scheduler::current_thread::CurrentThread::block_on",
+            field(
+                field(deref(field(deref(arg("handle")), "0")), "shared"),
+                "owned",
+            ),
+        ),
+    ]
+}
+
+/// State of an async task.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AsyncTaskState {
+    /// Task waits for a wake-up.
+    Idle,
+    /// Task is woken and waits in a scheduler queue.
+    Scheduled,
+    /// Task is polled right now.
+    Running,
+    /// Task future is complete.
+    Complete,
+    /// Task is cancelled.
+    Cancelled,
+}
+
+impl AsyncTaskState {
+    /// Decode a tokio task state word.
+    ///
+    /// # Arguments
+    ///
+    /// * `state`: value of `tokio::runtime::task::state::State`
+    fn from_tokio(state: usize) -> Self {
+        const RUNNING: usize = 0b0001;
+        const COMPLETE: usize = 0b0010;
+        const NOTIFIED: usize = 0b0100;
+        const CANCELLED: usize = 0b10_0000;
+
+        if state & RUNNING != 0 {
+            AsyncTaskState::Running
+        } else if state & COMPLETE != 0 {
+            AsyncTaskState::Complete
+        } else if state & CANCELLED != 0 {
+            AsyncTaskState::Cancelled
+        } else if state & NOTIFIED != 0 {
+            AsyncTaskState::Scheduled
+        } else {
+            AsyncTaskState::Idle
+        }
+    }
+}
+
+impl Display for AsyncTaskState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let state = match self {
+            AsyncTaskState::Idle => "idle",
+            AsyncTaskState::Scheduled => "scheduled",
+            AsyncTaskState::Running => "running",
+            AsyncTaskState::Complete => "complete",
+            AsyncTaskState::Cancelled => "cancelled",
+        };
+        f.write_str(state)
+    }
+}
+
+/// Task of an async runtime.
+#[derive(Debug, Clone)]
+pub struct AsyncTask {
+    /// Task id (`tokio::task::Id`), `None` if id is unreadable.
+    pub id: Option<u64>,
+    /// Address of a task header.
+    pub header: RelocatedAddress,
+    pub state: AsyncTaskState,
+    /// Type name of a future wrapped by a task.
+    pub future_type: Option<String>,
+    /// Logical async backtrace of a task future, innermost future first.
+    pub frames: Vec<AsyncFrame>,
+}
+
+/// A future in a logical async backtrace.
+#[derive(Debug, Clone)]
+pub struct AsyncFrame {
+    /// Type name of a future.
+    pub future_type: Option<String>,
+    /// Current state of a state machine (like `Suspend0` or `Unresumed`),
+    /// `None` if future is not an async fn state machine.
+    pub state: Option<String>,
+    /// Function of an await point.
+    pub function: Option<String>,
+    /// File and line of an await point.
+    pub place: Option<(PathBuf, u64)>,
+    /// Locals that are alive in current state, awaited future is not included.
+    pub locals: Vec<VariableIR>,
+}
+
+/// Return true if type is a state machine of an async fn or an async block.
+fn is_state_machine(type_name: &str) -> bool {
+    [
+        "{async_fn_env#",
+        "{async_block_env#",
+        "{generator_env#",
+        "{coroutine_env#",
+    ]
+    .iter()
+    .any(|prefix| type_name.contains(prefix))
+}
+
+/// Return generic arguments of a function name,
+/// for example `F` and `S` for `poll<F, S>`.
+///
+/// # Arguments
+///
+/// * `fn_name`: function name with generic arguments
+fn generic_args(fn_name: &str) -> Vec<&str> {
+    let Some(start) = fn_name.find('<').map(|idx| idx + 1) else {
+        return vec![];
+    };
+    let mut args = vec![];
+    let mut arg_start = start;
+    let mut depth = 0;
+    for (i, c) in fn_name[start..].char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' if depth > 0 => depth -= 1,
+            ',' | '>' if depth == 0 => {
+                args.push(fn_name[arg_start..start + i].trim());
+                if c == '>' {
+                    return args;
+                }
+                arg_start = start + i + 1;
+            }
+            _ => {}
+        }
+    }
+    vec![]
+}
+
+impl Debugger {
+    /// Return tasks of tokio runtimes in a debugee. Empty list is returned if debugee
+    /// has no running tokio runtime.
+    pub fn async_tasks(&mut self) -> Result<Vec<AsyncTask>, Error> {
+        let lists = self.async_task_lists()?;
+
+        let mut tasks = vec![];
+        let mut seen = HashSet::new();
+        for head in lists {
+            let mut next = Some(head);
+            while let Some(header) = next {
+                if !seen.insert(header) || seen.len() > MAX_TASKS {
+                    break;
+                }
+                let Some((task, trailer_next)) = weak_error!(self.read_task(header)) else {
+                    break;
+                };
+                tasks.push(task);
+                next = trailer_next;
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// Return heads of owned task lists of all runtime schedulers.
+    /// Exploration context is restored after reading.
+    fn async_task_lists(&mut self) -> Result<Vec<usize>, Error> {
+        let threads = self.thread_state()?;
+        let ctx = self.exploration_ctx().clone();
+
+        let mut roots = vec![];
+        for mut thread in threads {
+            // scheduler roots are at the bottom of a stack
+            weak_error!(thread.fetch_backtrace(self, None));
+            let Some(bt) = thread.bt else {
+                continue;
+            };
+            for (frame_num, frame) in bt.iter().enumerate() {
+                let Some(fn_name) = frame.func_name.as_deref() else {
+                    continue;
+                };
+                let Some((_, expr)) = scheduler_roots()
+                    .into_iter()
+                    // generic arguments (like in `block_on<F>`) are ignored
+                    .find(|(root_fn, _)| fn_name.split('<').next() == Some(root_fn))
+                else {
+                    continue;
+                };
+
+                self.set_thread_focus(ThreadSelector::Tid(thread.thread.pid))?;
+                self.set_frame_into_focus(frame_num as u32)?;
+                if let Some(owned) = weak_error!(self.read_argument(expr)) {
+                    roots.extend(owned);
+                }
+                break;
+            }
+        }
+
+        self.set_thread_focus(ThreadSelector::Tid(ctx.pid_on_focus()))?;
+        self.set_frame_into_focus(ctx.frame())?;
+
+        let mut heads: Vec<_> = roots.iter().flat_map(list_heads).collect();
+        heads.sort_unstable();
+        heads.dedup();
+        Ok(heads)
+    }
+
+    /// Read a task by its header address, return task and an address of next task in owned list.
+    fn read_task(&self, header: usize) -> Result<(AsyncTask, Option<usize>), Error> {
+        let header_var = self.read_struct_at(header, "tokio::runtime::task::core::Header")?;
+        let state = find_member(&header_var, "state")
+            .and_then(usize_value)
+            .map(AsyncTaskState::from_tokio)
+            .unwrap_or(AsyncTaskState::Idle);
+
+        let vtable_addr = find_member(&header_var, "vtable").and_then(pointer_value);
+        let vtable = vtable_addr.and_then(|addr| {
+            weak_error!(self.read_struct_at(addr, "tokio::runtime::task::raw::Vtable"))
+        });
+        let vtable_member = |name: &str| vtable.as_ref().and_then(|v| find_member(v, name));
+
+        let id = vtable_member("id_offset")
+            .and_then(usize_value)
+            .and_then(|offset| self.read_memory(header + offset, size_of::<u64>()).ok())
+            .map(|bytes| u64::from_ne_bytes(bytes.try_into().expect("8 bytes are read")));
+
+        let poll_fn = vtable_member("poll")
+            .and_then(pointer_value)
+            .and_then(|poll_fn| self.function_name_at(poll_fn));
+        let poll_args = poll_fn.as_deref().map(generic_args).unwrap_or_default();
+        let future_type = poll_args.first().map(ToString::to_string);
+
+        let frames = match poll_args.as_slice() {
+            [future, scheduler] if state != AsyncTaskState::Complete => {
+                let cell_type = format!("tokio::runtime::task::core::Cell<{future}, {scheduler}>");
+                let cell = weak_error!(self.read_struct_at(header, &cell_type));
+                cell.as_ref()
+                    .and_then(|cell| running_future(find_member(cell, "core")?))
+                    .map(|future| self.future_backtrace(future))
+                    .unwrap_or_default()
+            }
+            _ => vec![],
+        };
+
+        let next = vtable_member("trailer_offset")
+            .and_then(usize_value)
+            .and_then(|offset| {
+                self.read_struct_at(header + offset, "tokio::runtime::task::core::Trailer")
+                    .ok()
+            })
+            .and_then(|trailer| {
+                let owned = find_member(&trailer, "owned")?;
+                find_member(owned, "next").and_then(ptr_value)
+            })
+            .filter(|&next| next != 0);
+
+        let task = AsyncTask {
+            id,
+            header: RelocatedAddress::from(header),
+            state,
+            future_type,
+            frames,
+        };
+        Ok((task, next))
+    }
+
+    /// Decode a future and all futures awaited by it, return a logical async backtrace,
+    /// innermost future first.
+    ///
+    /// # Arguments
+    ///
+    /// * `future`: future object, typically a state machine of an async fn
+    pub fn future_backtrace(&self, future: &VariableIR) -> Vec<AsyncFrame> {
+        let mut frames = vec![];
+        let mut next = Some(future);
+        while let Some(future) = next {
+            if frames.len() >= MAX_ASYNC_FRAMES {
+                break;
+            }
+            let (frame, awaitee) = self.decode_future(future);
+            frames.push(frame);
+            next = awaitee;
+        }
+        frames.reverse();
+        frames
+    }
+
+    /// Read futures selected by an expression and return their logical async backtraces.
+    ///
+    /// # Arguments
+    ///
+    /// * `select_expr`: data query expression
+    pub fn read_future(&self, select_expr: DQE) -> Result<Vec<Vec<AsyncFrame>>, Error> {
+        Ok(self
+            .read_variable(select_expr)?
+            .iter()
+            .map(|future| self.future_backtrace(future))
+            .collect())
+    }
+
+    /// Decode a single future, return a frame and an awaited future if future is suspended
+    /// at an await point.
+    fn decode_future<'a>(&self, future: &'a VariableIR) -> (AsyncFrame, Option<&'a VariableIR>) {
+        let future_type = Some(future.r#type().to_string()).filter(|t| !t.is_empty());
+        let state_machine = match future {
+            VariableIR::RustEnum(r#enum) if is_state_machine(future.r#type()) => r#enum,
+            // a hand-written future (or a wrapper) is a leaf of a logical backtrace
+            _ => {
+                let frame = AsyncFrame {
+                    future_type,
+                    state: None,
+                    function: None,
+                    place: None,
+                    locals: vec![],
+                };
+                return (frame, None);
+            }
+        };
+
+        let variant = state_machine.value.as_deref();
+        let members = match variant.and_then(|v| v.value()) {
+            Some(ValueLayout::Structure { members }) => members,
+            _ => &[],
+        };
+        let awaitee = members.iter().find(|m| m.name() == AWAITEE_MEMBER);
+        let locals = members
+            .iter()
+            .filter(|m| m.name() != AWAITEE_MEMBER)
+            .cloned()
+            .collect();
+
+        let place = state_machine.variant_decl.clone();
+        let function = place
+            .as_ref()
+            .and_then(|(file, line)| self.function_name_at_line(file, *line));
+
+        let frame = AsyncFrame {
+            future_type,
+            state: variant.map(|v| v.name()),
+            function,
+            place,
+            locals,
+        };
+        (frame, awaitee)
+    }
+
+    /// Return full name of a function that contains a source line.
+    fn function_name_at_line(&self, file: &Path, line: u64) -> Option<String> {
+        let file = file.to_str()?;
+        self.debugee
+            .debug_info_all()
+            .into_iter()
+            .find_map(|debug_info| {
+                let places = debug_info.find_closest_place(file, line).ok()?;
+                places.iter().find_map(|place| {
+                    debug_info
+                        .find_function_by_pc(place.address)
+                        .ok()??
+                        .full_name()
+                })
+            })
+    }
+
+    /// Read a structure of a type placed at address.
+    fn read_struct_at(&self, addr: usize, type_name: &str) -> Result<VariableIR, Error> {
+        let expr = DQE::Deref(DQE::PtrCast(addr, format!("*const {type_name}")).boxed());
+        self.read_variable(expr)?.pop().ok_or(Error::TypeNotFound)
+    }
+
+    /// Return full name of a function that contains an address.
+    fn function_name_at(&self, addr: usize) -> Option<String> {
+        let addr = RelocatedAddress::from(addr);
+        let debug_info = self.debugee.debug_info(addr).ok()?;
+        let global = addr.into_global(&self.debugee).ok()?;
+        let function = debug_info.find_function_by_pc(global).ok()??;
+        function.full_name()
+    }
+}
+
+/// Return value of a pointer, unfolds `Option<NonNull<T>>` like pointers.
+fn ptr_value(var: &VariableIR) -> Option<usize> {
+    match var {
+        VariableIR::Specialized(SpecializedVariableIR::Option { value, .. }) => {
+            ptr_value(value.as_ref()?.as_ref()?)
+        }
+        VariableIR::RustEnum(r#enum) => ptr_value(r#enum.value.as_ref()?),
+        _ => pointer_value(var),
+    }
+}
+
+/// Return a future of a running task from a task core, future is stored in a `Running`
+/// variant of a task stage.
+fn running_future(core: &VariableIR) -> Option<&VariableIR> {
+    if let VariableIR::RustEnum(stage) = core {
+        let variant = stage.value.as_deref()?;
+        if variant.name() == "Running" {
+            return find_member(variant, "0");
+        }
+        return None;
+    }
+    match core.value()? {
+        ValueLayout::Wrapped(inner) => running_future(inner),
+        ValueLayout::Structure { members } => members.iter().find_map(running_future),
+        _ => None,
+    }
+}
+
+/// Return addresses of first tasks of linked lists (members named `head`) in owned tasks.
+fn list_heads(owned: &VariableIR) -> Vec<usize> {
+    let mut heads = vec![];
+    collect_heads(owned, &mut heads);
+    heads
+}
+
+fn collect_heads(var: &VariableIR, heads: &mut Vec<usize>) {
+    if var.name() == "head" {
+        if let Some(head) = ptr_value(var).filter(|&head| head != 0) {
+            heads.push(head);
+        }
+        return;
+    }
+    match var.value() {
+        Some(ValueLayout::Wrapped(inner)) => collect_heads(inner, heads),
+        Some(ValueLayout::Structure { members }) | Some(ValueLayout::List { members, .. }) => {
+            members
+                .iter()
+                .for_each(|member| collect_heads(member, heads))
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_task_state() {
+        assert_eq!(AsyncTaskState::from_tokio(0b0000), AsyncTaskState::Idle);
+        assert_eq!(
+            AsyncTaskState::from_tokio(0b0100),
+            AsyncTaskState::Scheduled
+        );
+        assert_eq!(AsyncTaskState::from_tokio(0b0101), AsyncTaskState::Running);
+        assert_eq!(AsyncTaskState::from_tokio(0b0010), AsyncTaskState::Complete);
+        assert_eq!(
+            AsyncTaskState::from_tokio(0b10_0000),
+            AsyncTaskState::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_generic_args() {
+        assert_eq!(
+            generic_args(
+                "tokio::runtime::task::raw::poll<app::main::{async_block_env#0}, alloc::sync::Arc<tokio::runtime::scheduler::multi_thread::handle::Handle, alloc::alloc::Global>>"
+            ),
+            vec![
+                "app::main::{async_block_env#0}",
+                "alloc::sync::Arc<tokio::runtime::scheduler::multi_thread::handle::Handle, alloc::alloc::Global>"
+            ]
+        );
+        assert_eq!(
+            generic_args("poll<core::pin::Pin<alloc::boxed::Box<dyn core::future::future::Future<Output=()>>>, S>"),
+            vec![
+                "core::pin::Pin<alloc::boxed::Box<dyn core::future::future::Future<Output=()>>>",
+                "S"
+            ]
+        );
+        assert!(generic_args("poll").is_empty());
+        assert!(generic_args("poll<F").is_empty());
+    }
+
+    #[test]
+    fn test_is_state_machine() {
+        assert!(is_state_machine("{async_fn_env#0}"));
+        assert!(is_state_machine("{async_block_env#1}<i32>"));
+        assert!(!is_state_machine("Sleep"));
+        assert!(!is_state_machine("Option<i32>"));
+    }
+}
@@ -1,18 +1,27 @@
-use crate::debugger::address::{Address, RelocatedAddress};
+use crate::debugger::address::{Address, GlobalAddress, RelocatedAddress};
 use crate::debugger::debugee::dwarf::unit::PlaceDescriptorOwned;
 use crate::debugger::debugee::dwarf::DebugInformation;
 use crate::debugger::debugee::Debugee;
 use crate::debugger::error::Error;
-use crate::debugger::error::Error::{NoDebugInformation, NoSuitablePlace, PlaceNotFound};
-use crate::debugger::Debugger;
-use nix::libc::c_void;
-use nix::sys;
+use crate::debugger::error::Error::{
+    AddressNotExecutable, HardwareBreakpointLimit, MappingOffsetNotFound, NoDebugInformation,
+    NoSuitablePlace, SymbolOffsetOutOfRange,
+};
+use crate::debugger::variable::expr::Expr;
+use crate::debugger::variable::VariableIR;
+use crate::debugger::{memory, read_memory_by_pid, watchpoint, write_memory_by_pid, Debugger};
+use log::{debug, warn};
+use nix::libc::pid_t;
 use nix::unistd::Pid;
+use regex::Regex;
 use std::borrow::Cow;
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
+use std::fs::{File, OpenOptions};
 use std::mem;
+use std::os::unix::fs::FileExt;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicU32, Ordering};
@@ -26,6 +35,8 @@ enum BrkptsToAddRequest {
 pub enum CreateTransparentBreakpointRequest {
     Line(String, u64, Rc<dyn Fn(&mut Debugger)>),
     Function(String, Rc<dyn Fn(&mut Debugger)>),
+    Symbol(String, Rc<dyn Fn(&mut Debugger)>),
+    Address(RelocatedAddress, Rc<dyn Fn(&mut Debugger)>),
 }
 
 impl CreateTransparentBreakpointRequest {
@@ -50,17 +61,42 @@ impl CreateTransparentBreakpointRequest {
         Self::Line(file.to_string(), line, Rc::new(cb))
     }
 
+    /// Create request for transparent breakpoints at symbols from a symbol table,
+    /// useful for functions without debug information (like `__rust_alloc`).
+    ///
+    /// # Arguments
+    ///
+    /// * `regex`: regular expression for demangled symbol names
+    /// * `cb`: callback that invoked when breakpoint is heat
+    pub fn symbol(regex: impl ToString, cb: impl Fn(&mut Debugger) + 'static) -> Self {
+        Self::Symbol(regex.to_string(), Rc::new(cb))
+    }
+
+    /// Create request for transparent breakpoint at address.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: address in debugee address space
+    /// * `cb`: callback that invoked when breakpoint is heat
+    pub fn address(addr: RelocatedAddress, cb: impl Fn(&mut Debugger) + 'static) -> Self {
+        Self::Address(addr, Rc::new(cb))
+    }
+
     /// Return underline callback.
     fn callback(&self) -> Rc<dyn Fn(&mut Debugger)> {
         match self {
             CreateTransparentBreakpointRequest::Line(_, _, cb) => cb.clone(),
             CreateTransparentBreakpointRequest::Function(_, cb) => cb.clone(),
+            CreateTransparentBreakpointRequest::Symbol(_, cb) => cb.clone(),
+            CreateTransparentBreakpointRequest::Address(_, cb) => cb.clone(),
         }
     }
 }
 
 impl Debugger {
-    /// Create and enable breakpoint at debugee address space
+    /// Create and enable breakpoint at debugee address space.
+    /// Address may belong to code without debug information (place of a breakpoint is unknown
+    /// in this case), but it must lie inside an executable memory mapping.
     ///
     /// # Arguments
     ///
@@ -68,30 +104,30 @@ impl Debugger {
     ///
     /// # Errors
     ///
-    /// Return [`SetupError::PlaceNotFound`] if no place found for address,
-    /// return [`BreakpointError::DebugInformation`] if errors occur while fetching debug information.
+    /// Return [`Error::AddressNotExecutable`] if address is outside of executable mappings,
+    /// return [`Error::NoDebugInformation`] if address doesn't belong to any known object file.
     pub fn set_breakpoint_at_addr(
         &mut self,
         addr: RelocatedAddress,
     ) -> Result<BreakpointView, Error> {
         if self.debugee.is_in_progress() {
+            if !self.is_executable_addr(addr)? {
+                return Err(AddressNotExecutable(addr));
+            }
+
             let dwarf = self
                 .debugee
                 .debug_info(addr)
                 .map_err(|_| NoDebugInformation("current place"))?;
             let global_addr = addr.into_global(&self.debugee)?;
 
-            let place = dwarf
-                .find_place_from_pc(global_addr)?
-                .map(|p| p.to_owned())
-                .ok_or(PlaceNotFound(global_addr))?;
-
-            return self.breakpoints.add_and_enable(Breakpoint::new(
-                dwarf.pathname(),
-                addr,
-                self.process.pid(),
-                Some(place),
-            ));
+            let place = dwarf.find_place_from_pc(global_addr)?.map(|p| p.to_owned());
+
+            let threads = self.thread_pids();
+            return self.breakpoints.add_and_enable(
+                Breakpoint::new(dwarf.pathname(), addr, self.process.pid(), place),
+                &threads,
+            );
         }
 
         Ok(self.breakpoints.add_uninit(UninitBreakpoint::new(
@@ -102,16 +138,126 @@ impl Debugger {
         )))
     }
 
+    /// Return true if an address lies inside an executable memory mapping of debugee process.
+    fn is_executable_addr(&self, addr: RelocatedAddress) -> Result<bool, Error> {
+        let maps = proc_maps::get_process_maps(self.process.pid().as_raw())?;
+        let addr = usize::from(addr);
+        Ok(maps
+            .iter()
+            .any(|map| map.is_exec() && map.start() <= addr && addr < map.start() + map.size()))
+    }
+
+    /// Return global addresses of a function symbol (from ELF symbol table) plus an offset
+    /// in all object files that contain such symbol.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: symbol name (raw or demangled)
+    /// * `offset`: offset from a symbol start in bytes
+    fn search_symbol(
+        &self,
+        name: &str,
+        offset: u64,
+    ) -> Result<Vec<(&DebugInformation, GlobalAddress)>, Error> {
+        let mut result = vec![];
+        for dwarf in self.debugee.debug_info_all() {
+            let Some(symbol) = dwarf.find_text_symbol(name) else {
+                continue;
+            };
+            // symbol size may be unknown (zero), in this case offset isn't checked
+            if symbol.size != 0 && offset >= symbol.size {
+                return Err(SymbolOffsetOutOfRange(name.to_string(), symbol.size));
+            }
+            result.push((dwarf, GlobalAddress::from(u64::from(symbol.addr) + offset)));
+        }
+        if result.is_empty() {
+            return Err(NoSuitablePlace);
+        }
+        Ok(result)
+    }
+
+    /// Create and enable breakpoint at an address of a function symbol plus offset (like `main+16`),
+    /// symbols are taken from ELF symbol table, so debug information isn't required.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: symbol name (raw or demangled)
+    /// * `offset`: offset from a symbol start in bytes
+    pub fn set_breakpoint_at_symbol(
+        &mut self,
+        name: &str,
+        offset: u64,
+    ) -> Result<Vec<BreakpointView>, Error> {
+        let addresses = self.search_symbol(name, offset)?;
+
+        if self.debugee.is_in_progress() {
+            let addresses = addresses
+                .into_iter()
+                .map(|(dwarf, addr)| addr.relocate_to_segment(&self.debugee, dwarf))
+                .collect::<Result<Vec<_>, _>>()?;
+            for &addr in &addresses {
+                self.set_breakpoint_at_addr(addr)?;
+            }
+            return Ok(addresses
+                .into_iter()
+                .filter_map(|addr| self.breakpoints.get_enabled(addr))
+                .map(BreakpointView::from)
+                .collect());
+        }
+
+        let brkpts = addresses
+            .into_iter()
+            .map(|(dwarf, addr)| {
+                let place = dwarf
+                    .find_place_from_pc(addr)
+                    .ok()
+                    .flatten()
+                    .map(|place| place.to_owned());
+                UninitBreakpoint::new(
+                    Some(dwarf.pathname()),
+                    Address::Global(addr),
+                    self.process.pid(),
+                    place,
+                )
+            })
+            .collect();
+        self.add_breakpoints(BrkptsToAddRequest::Uninit(brkpts))
+    }
+
+    /// Disable and remove breakpoints at an address of a function symbol plus offset.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: symbol name (raw or demangled)
+    /// * `offset`: offset from a symbol start in bytes
+    pub fn remove_breakpoint_at_symbol(
+        &mut self,
+        name: &str,
+        offset: u64,
+    ) -> Result<Vec<BreakpointView>, Error> {
+        let addresses = self.search_symbol(name, offset)?;
+        let mut to_remove = vec![];
+        for (dwarf, addr) in addresses {
+            if self.debugee.is_in_progress() {
+                let addr = addr.relocate_to_segment(&self.debugee, dwarf)?;
+                to_remove.push(Address::Relocated(addr));
+            }
+            to_remove.push(Address::Global(addr));
+        }
+        self.remove_breakpoints_at_addresses(to_remove.into_iter())
+    }
+
     /// Disable and remove a breakpoint by it address.
     ///
     /// # Arguments
     ///
     /// * `addr`: breakpoint address
     pub fn remove_breakpoint(&mut self, addr: Address) -> Result<Option<BreakpointView>, Error> {
-        self.breakpoints.remove_by_addr(addr)
+        let threads = self.thread_pids();
+        self.breakpoints.remove_by_addr(addr, &threads)
     }
 
-    /// Disable and remove a breakpoint by it number.
+    /// Disable and remove all locations of a breakpoint by it number.
     ///
     /// # Arguments
     ///
@@ -119,24 +265,148 @@ impl Debugger {
     pub fn remove_breakpoint_by_number(
         &mut self,
         number: u32,
+    ) -> Result<Vec<BreakpointView>, Error> {
+        let threads = self.thread_pids();
+        self.breakpoints.remove_by_num(number, &threads)
+    }
+
+    /// Enable a breakpoint previously disabled by [`Debugger::disable_breakpoint`].
+    /// Return `None` if breakpoint not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `number`: breakpoint number
+    pub fn enable_breakpoint(&mut self, number: u32) -> Option<BreakpointView> {
+        self.breakpoints
+            .update_options(number, |options| options.disabled = false)
+    }
+
+    /// Disable a breakpoint, disabled breakpoint stay in a breakpoint list but never stops a debugee.
+    /// Return `None` if breakpoint not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `number`: breakpoint number
+    pub fn disable_breakpoint(&mut self, number: u32) -> Option<BreakpointView> {
+        self.breakpoints
+            .update_options(number, |options| options.disabled = true)
+    }
+
+    /// Set or remove a breakpoint condition. Return `None` if breakpoint not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `number`: breakpoint number
+    /// * `condition`: new condition, `None` for remove current condition
+    pub fn set_breakpoint_condition(
+        &mut self,
+        number: u32,
+        condition: Option<BreakpointCondition>,
+    ) -> Option<BreakpointView> {
+        self.breakpoints
+            .update_options(number, |options| options.condition = condition.clone())
+    }
+
+    /// Set a number of next breakpoint hits that don't stop a debugee, `0` for stop at next hit.
+    /// Return `None` if breakpoint not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `number`: breakpoint number
+    /// * `count`: number of ignored hits
+    pub fn set_breakpoint_ignore_count(
+        &mut self,
+        number: u32,
+        count: u32,
+    ) -> Option<BreakpointView> {
+        self.breakpoints
+            .update_options(number, |options| options.ignore_count = count)
+    }
+
+    /// Make a breakpoint hardware (set with a debug register, without debugee memory patching)
+    /// or software (INT3 instruction). Up to four hardware breakpoints may exist at once,
+    /// debug registers are shared with watchpoints, each location of a breakpoint
+    /// takes its own register. Return `None` if breakpoint not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `number`: breakpoint number
+    /// * `hardware`: true for hardware breakpoint, false for software breakpoint
+    pub fn set_breakpoint_hardware(
+        &mut self,
+        number: u32,
+        hardware: bool,
     ) -> Result<Option<BreakpointView>, Error> {
-        self.breakpoints.remove_by_num(number)
+        let free_regs: Vec<_> = if hardware {
+            let mut used = self.breakpoints.hardware_regs();
+            used.extend(self.watchpoints.used_regs());
+            (0..watchpoint::ADDRESS_REG_COUNT)
+                .filter(|reg| !used.contains(reg))
+                .collect()
+        } else {
+            vec![]
+        };
+        let threads = self.thread_pids();
+        self.breakpoints
+            .set_hardware(number, hardware, &free_regs, &threads)
+    }
+
+    /// Check that debugee must stop at user defined breakpoint (breakpoint is not disabled,
+    /// condition is satisfied and hit is not ignored). Breakpoint hit counter is increased
+    /// if condition is satisfied, even if hit is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: breakpoint address
+    pub(super) fn check_breakpoint_hit(&mut self, addr: RelocatedAddress) -> bool {
+        let Some(options) = self
+            .breakpoints
+            .get_enabled(addr)
+            .map(|b| b.options.clone())
+        else {
+            return true;
+        };
+
+        if options.disabled {
+            return false;
+        }
+
+        if let Some(condition) = options.condition {
+            let satisfied = match self.evaluate_expression(&condition.expr) {
+                Ok(var) => matches!(var, VariableIR::Scalar(s) if s.is_truthy()),
+                Err(e) => {
+                    // stop a debugee, user must know that condition is broken
+                    warn!(target: "debugger", "breakpoint condition `{}`: {e:#}", condition.source);
+                    true
+                }
+            };
+            if !satisfied {
+                return false;
+            }
+        }
+
+        self.breakpoints.register_hit(addr)
     }
 
+    /// Create a breakpoint with a location at each place, all locations share one number,
+    /// so they are enabled, disabled, removed and conditioned together.
     fn create_breakpoint_at_places(
         &self,
         places: Vec<(&DebugInformation, Vec<PlaceDescriptorOwned>)>,
     ) -> Result<BrkptsToAddRequest, Error> {
+        let number = next_number();
         let brkpts_to_add = if self.debugee.is_in_progress() {
             let mut to_add = Vec::new();
             for (dwarf, places) in places {
                 for place in places {
                     let addr = place.address.relocate_to_segment(&self.debugee, dwarf)?;
-                    to_add.push(Breakpoint::new(
-                        dwarf.pathname(),
+                    to_add.push(Breakpoint::new_inner(
                         addr,
                         self.process.pid(),
+                        number,
                         Some(place),
+                        BrkptType::UserDefined,
+                        dwarf.pathname().into(),
                     ));
                 }
             }
@@ -145,11 +415,13 @@ impl Debugger {
             let mut to_add = Vec::new();
             for (dwarf, places) in places {
                 for place in places {
-                    to_add.push(UninitBreakpoint::new(
-                        Some(dwarf.pathname()),
+                    to_add.push(UninitBreakpoint::new_inner(
                         Address::Global(place.address),
                         self.process.pid(),
+                        number,
                         Some(place),
+                        BrkptType::UserDefined,
+                        Some(dwarf.pathname().into()),
                     ));
                 }
             }
@@ -164,12 +436,9 @@ impl Debugger {
     ) -> Result<Vec<BreakpointView>, Error> {
         let result: Vec<_> = match brkpts_to_add {
             BrkptsToAddRequest::Init(init_brkpts) => {
-                let mut result_addrs = Vec::with_capacity(init_brkpts.len());
-                for brkpt in init_brkpts {
-                    let addr = brkpt.addr;
-                    self.breakpoints.add_and_enable(brkpt)?;
-                    result_addrs.push(addr);
-                }
+                let result_addrs: Vec<_> = init_brkpts.iter().map(|brkpt| brkpt.addr).collect();
+                let threads = self.thread_pids();
+                self.breakpoints.add_and_enable_all(init_brkpts, &threads)?;
                 result_addrs
                     .iter()
                     .map(|addr| {
@@ -237,15 +506,16 @@ impl Debugger {
         addresses: impl Iterator<Item = Address>,
     ) -> Result<Vec<BreakpointView>, Error> {
         let mut result = vec![];
+        let threads = self.thread_pids();
         for to_rem in addresses {
-            if let Some(view) = self.breakpoints.remove_by_addr(to_rem)? {
+            if let Some(view) = self.breakpoints.remove_by_addr(to_rem, &threads)? {
                 result.push(view)
             }
         }
         Ok(result)
     }
 
-    fn search_functions(
+    pub(super) fn search_functions(
         &self,
         tpl: &str,
     ) -> Result<Vec<(&DebugInformation, Vec<PlaceDescriptorOwned>)>, Error> {
@@ -305,7 +575,7 @@ impl Debugger {
         Ok(places.into_iter().map(|p| p.to_owned()).collect())
     }
 
-    fn search_lines(
+    pub(super) fn search_lines(
         &self,
         fine_tpl: &str,
         line: u64,
@@ -322,7 +592,20 @@ impl Debugger {
             .collect()
     }
 
+    /// Return the nearest to a target line which has a statement (a line table row
+    /// with `is_stmt` flag), a line after the target is preferred over a line before it.
+    fn nearest_stmt_line(&self, fine_tpl: &str, line: u64) -> Result<Option<u64>, Error> {
+        Ok(self
+            .known_lines(fine_tpl)?
+            .into_iter()
+            .filter(|place| place.is_stmt && place.line_number != 0)
+            .map(|place| place.line_number)
+            .min_by_key(|&l| (l.abs_diff(line), l < line)))
+    }
+
     /// Create and enable breakpoint at the following file and line number.
+    /// If there is no code at the line (and at the next one), breakpoint is set at the nearest
+    /// line with a statement.
     ///
     /// # Arguments
     ///
@@ -338,9 +621,16 @@ impl Debugger {
         fine_path_tpl: &str,
         line: u64,
     ) -> Result<Vec<BreakpointView>, Error> {
-        let places = self.search_lines(fine_path_tpl, line)?;
+        let mut places = self.search_lines(fine_path_tpl, line)?;
         if places.iter().all(|(_, places)| places.is_empty()) {
-            return Err(NoSuitablePlace);
+            let Some(nearest) = self.nearest_stmt_line(fine_path_tpl, line)? else {
+                return Err(NoSuitablePlace);
+            };
+            warn!(target: "debugger", "no code at line {line}, breakpoint moved to line {nearest}");
+            places = self.search_lines(fine_path_tpl, nearest)?;
+            if places.iter().all(|(_, places)| places.is_empty()) {
+                return Err(NoSuitablePlace);
+            }
         }
 
         let brkpts = self.create_breakpoint_at_places(places)?;
@@ -372,34 +662,55 @@ impl Debugger {
         &mut self,
         request: CreateTransparentBreakpointRequest,
     ) -> Result<(), Error> {
+        if let CreateTransparentBreakpointRequest::Address(addr, cb) = request {
+            let debug_info = self.debugee.debug_info(addr)?;
+            let brkpt =
+                Breakpoint::new_transparent(debug_info.pathname(), addr, self.process.pid(), cb);
+            let threads = self.thread_pids();
+            self.breakpoints.add_and_enable(brkpt, &threads)?;
+            return Ok(());
+        }
+
         // transparent breakpoint currently may be set only at main object file instructions
         let debug_info = self.debugee.program_debug_info()?;
 
-        let places: Vec<_> = match &request {
-            CreateTransparentBreakpointRequest::Line(file, line, _) => {
-                self.search_lines_in_file(debug_info, file, *line)?
-            }
+        let addresses: Vec<_> = match &request {
+            CreateTransparentBreakpointRequest::Line(file, line, _) => self
+                .search_lines_in_file(debug_info, file, *line)?
+                .into_iter()
+                .map(|place| place.address)
+                .collect(),
             CreateTransparentBreakpointRequest::Function(tpl, _) => {
                 if debug_info.has_debug_info() && debug_info.tpl_in_pub_names(tpl) != Some(false) {
-                    debug_info.search_places_for_fn_tpl(tpl)?
+                    debug_info
+                        .search_places_for_fn_tpl(tpl)?
+                        .into_iter()
+                        .map(|place| place.address)
+                        .collect()
                 } else {
                     vec![]
                 }
             }
+            CreateTransparentBreakpointRequest::Symbol(regex, _) => {
+                let regex = Regex::new(regex)?;
+                debug_info
+                    .find_symbols(&regex)
+                    .into_iter()
+                    .map(|symbol| symbol.addr)
+                    .collect()
+            }
+            CreateTransparentBreakpointRequest::Address(..) => unreachable!("handled above"),
         };
 
-        if places.is_empty() {
+        if addresses.is_empty() {
             return Err(NoSuitablePlace);
         }
 
         let callback = request.callback();
-        let breakpoints: Vec<_> = places
+        let breakpoints: Vec<_> = addresses
             .into_iter()
-            .flat_map(|place| {
-                let addr = place
-                    .address
-                    .relocate_to_segment(&self.debugee, debug_info)
-                    .ok()?;
+            .flat_map(|addr| {
+                let addr = addr.relocate_to_segment(&self.debugee, debug_info).ok()?;
                 Some(Breakpoint::new_transparent(
                     debug_info.pathname(),
                     addr,
@@ -409,8 +720,9 @@ impl Debugger {
             })
             .collect();
 
+        let threads = self.thread_pids();
         for brkpt in breakpoints {
-            self.breakpoints.add_and_enable(brkpt)?;
+            self.breakpoints.add_and_enable(brkpt, &threads)?;
         }
 
         Ok(())
@@ -442,6 +754,13 @@ impl Debugger {
             .push(DeferredBreakpoint::at_line(file, line));
     }
 
+    /// Add new deferred breakpoint by symbol and offset.
+    pub fn add_deferred_at_symbol(&mut self, symbol: &str, offset: u64) {
+        self.breakpoints
+            .deferred_breakpoints
+            .push(DeferredBreakpoint::at_symbol(symbol, offset));
+    }
+
     /// Refresh deferred breakpoints. Trying to set breakpoint if success - remove
     /// breakpoint from a deferred list.
     pub fn refresh_deferred(&mut self) -> Vec<Error> {
@@ -455,6 +774,9 @@ impl Debugger {
                     self.set_breakpoint_at_line(file, *line).err()
                 }
                 DeferredBreakpoint::Function(function) => self.set_breakpoint_at_fn(function).err(),
+                DeferredBreakpoint::Symbol(symbol, offset) => {
+                    self.set_breakpoint_at_symbol(symbol, *offset).err()
+                }
             };
 
             match mb_error {
@@ -524,6 +846,36 @@ impl PartialEq for BrkptType {
 
 static GLOBAL_BP_COUNTER: AtomicU32 = AtomicU32::new(1);
 
+/// Return a new breakpoint number, watchpoints are numbered together with breakpoints.
+pub(super) fn next_number() -> u32 {
+    GLOBAL_BP_COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Breakpoint condition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakpointCondition {
+    /// Condition as it was entered by user.
+    pub source: String,
+    /// Debugee stops at breakpoint only if expression result is `true` or not a zero integer.
+    pub expr: Expr,
+}
+
+/// Options of user defined breakpoint, preserved when debugee restarts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BreakpointOptions {
+    /// Disabled breakpoint never stops a debugee.
+    pub disabled: bool,
+    pub condition: Option<BreakpointCondition>,
+    /// Number of times a debugee reaches breakpoint with satisfied condition
+    /// (including ignored hits).
+    pub hit_count: u32,
+    /// Number of next hits that don't stop a debugee.
+    pub ignore_count: u32,
+    /// Index of a debug register (0-3) used by a hardware breakpoint,
+    /// `None` for a software (INT3) breakpoint.
+    pub hardware: Option<usize>,
+}
+
 /// Breakpoint representation.
 #[derive(Debug, Clone)]
 pub struct Breakpoint {
@@ -537,6 +889,7 @@ pub struct Breakpoint {
     enabled: Cell<bool>,
     r#type: BrkptType,
     pub debug_info_file: PathBuf,
+    options: BreakpointOptions,
 }
 
 impl Breakpoint {
@@ -565,6 +918,7 @@ impl Breakpoint {
             saved_data: Default::default(),
             r#type,
             debug_info_file,
+            options: BreakpointOptions::default(),
         }
     }
 
@@ -577,7 +931,7 @@ impl Breakpoint {
         Self::new_inner(
             addr,
             pid,
-            GLOBAL_BP_COUNTER.fetch_add(1, Ordering::Relaxed),
+            next_number(),
             place,
             BrkptType::UserDefined,
             debug_info_file.into(),
@@ -677,33 +1031,166 @@ impl Breakpoint {
         matches!(self.r#type, BrkptType::Temporary)
     }
 
-    pub fn enable(&self) -> Result<(), Error> {
-        let addr = self.addr.as_usize() as *mut c_void;
-        let data = sys::ptrace::read(self.pid, addr).map_err(Error::Ptrace)?;
-        self.saved_data.set((data & 0xff) as u8);
-        let data_with_pb = (data & !0xff) as u64 | Self::INT3;
-        unsafe {
-            sys::ptrace::write(self.pid, addr, data_with_pb as *mut c_void)
-                .map_err(Error::Ptrace)?;
+    /// Write a breakpoint instruction into debugee memory. Memory is patched through
+    /// `/proc/<pid>/mem` if possible, so the breakpoint thread may be running (in non-stop mode).
+    ///
+    /// # Arguments
+    ///
+    /// * `threads`: debugee threads, hardware breakpoint is set in their debug registers
+    pub fn enable(&self, threads: &[Pid]) -> Result<(), Error> {
+        patch_all(&[self], Patch::Enable, threads)
+    }
+
+    /// Restore original debugee memory, like [`Breakpoint::enable`] thread may be running.
+    ///
+    /// # Arguments
+    ///
+    /// * `threads`: debugee threads, hardware breakpoint is removed from their debug registers
+    pub fn disable(&self, threads: &[Pid]) -> Result<(), Error> {
+        patch_all(&[self], Patch::Disable, threads)
+    }
+
+    /// Return index of a debug register if breakpoint is a hardware breakpoint.
+    pub fn hardware_reg(&self) -> Option<usize> {
+        self.options.hardware
+    }
+
+    /// Set or remove a hardware breakpoint in debug registers of threads,
+    /// debugee memory isn't changed.
+    fn patch_hardware(&self, reg: usize, patch: Patch, threads: &[Pid]) -> Result<(), Error> {
+        if self.is_enabled() == (patch == Patch::Enable) {
+            return Ok(());
         }
+        watchpoint::set_exec_breakpoint(threads, reg, self.addr, patch == Patch::Enable)?;
+        self.enabled.set(patch == Patch::Enable);
+        Ok(())
+    }
+
+    /// Write a breakpoint instruction with ptrace (or with a target backend of the thread).
+    fn enable_ptrace(&self) -> Result<(), Error> {
+        let addr = self.addr.as_usize();
+        let data = read_memory_by_pid(self.pid, addr, 1).map_err(Error::Ptrace)?;
+        self.saved_data.set(data[0]);
+        write_memory_by_pid(self.pid, addr, &[Self::INT3 as u8]).map_err(Error::Ptrace)?;
         self.enabled.set(true);
 
         Ok(())
     }
 
-    pub fn disable(&self) -> Result<(), Error> {
-        let addr = self.addr.as_usize() as *mut c_void;
-        let data = sys::ptrace::read(self.pid, addr).map_err(Error::Ptrace)? as u64;
-        let restored: u64 = (data & !0xff) | self.saved_data.get() as u64;
-        unsafe {
-            sys::ptrace::write(self.pid, addr, restored as *mut c_void).map_err(Error::Ptrace)?;
-        }
+    /// Restore original debugee memory with ptrace (or with a target backend of the thread).
+    fn disable_ptrace(&self) -> Result<(), Error> {
+        write_memory_by_pid(self.pid, self.addr.as_usize(), &[self.saved_data.get()])
+            .map_err(Error::Ptrace)?;
         self.enabled.set(false);
 
         Ok(())
     }
 }
 
+/// Breakpoint state change.
+#[derive(Clone, Copy, PartialEq)]
+enum Patch {
+    Enable,
+    Disable,
+}
+
+const PAGE_SIZE: usize = 4096;
+
+/// Enable or disable a set of breakpoints at once. Instead of a pair of ptrace calls
+/// for each breakpoint, patches are grouped by memory pages and each page (a part of a page
+/// between the first and the last patched byte) is read and written once through
+/// `/proc/<pid>/mem`. Breakpoints that already are in a required state are ignored.
+///
+/// Breakpoints patched before an error occurs keep their new state.
+fn patch_batch(brkpts: &[&Breakpoint], patch: Patch) -> Result<(), Error> {
+    let mut pages: BTreeMap<(pid_t, usize), Vec<&Breakpoint>> = BTreeMap::new();
+    for brkpt in brkpts {
+        if brkpt.is_enabled() == (patch == Patch::Enable) {
+            continue;
+        }
+        let page = brkpt.addr.as_usize() / PAGE_SIZE;
+        pages
+            .entry((brkpt.pid.as_raw(), page))
+            .or_default()
+            .push(brkpt);
+    }
+
+    let mut mem_files: HashMap<pid_t, File> = HashMap::new();
+    for ((pid, _), page_brkpts) in pages {
+        let file = match mem_files.entry(pid) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(
+                OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .open(format!("/proc/{pid}/mem"))?,
+            ),
+        };
+
+        let addresses = page_brkpts.iter().map(|brkpt| brkpt.addr.as_usize());
+        let from = addresses.clone().min().expect("at least one breakpoint");
+        let to = addresses.max().expect("at least one breakpoint");
+        let mut data = vec![0; to - from + 1];
+        file.read_exact_at(&mut data, from as u64)?;
+        // several breakpoints may share an address, each of them must save an original byte
+        let original = data.clone();
+
+        for brkpt in &page_brkpts {
+            let offset = brkpt.addr.as_usize() - from;
+            match patch {
+                Patch::Enable => {
+                    brkpt.saved_data.set(original[offset]);
+                    data[offset] = Breakpoint::INT3 as u8;
+                }
+                Patch::Disable => data[offset] = brkpt.saved_data.get(),
+            }
+        }
+
+        file.write_all_at(&data, from as u64)?;
+        page_brkpts
+            .iter()
+            .for_each(|brkpt| brkpt.enabled.set(patch == Patch::Enable));
+    }
+    Ok(())
+}
+
+/// Same as [`patch_batch`], but if `/proc/<pid>/mem` is unavailable
+/// then breakpoints are patched one by one with ptrace.
+/// Memory of threads with an attached target backend is patched through the backend.
+/// Hardware breakpoints are set in debug registers of `threads` instead of memory patching.
+fn patch_all(brkpts: &[&Breakpoint], patch: Patch, threads: &[Pid]) -> Result<(), Error> {
+    let (hardware, brkpts): (Vec<&Breakpoint>, Vec<&Breakpoint>) = brkpts
+        .iter()
+        .copied()
+        .partition(|brkpt| brkpt.options.hardware.is_some());
+    for brkpt in hardware {
+        let reg = brkpt.options.hardware.expect("hardware breakpoint");
+        brkpt.patch_hardware(reg, patch, threads)?;
+    }
+    let brkpts = brkpts.as_slice();
+
+    let with_backend = brkpts
+        .iter()
+        .any(|brkpt| memory::backend(brkpt.pid).is_some());
+    if !with_backend {
+        match patch_batch(brkpts, patch) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                debug!(target: "debugger", "batch breakpoint patching fail, fallback to ptrace: {e}")
+            }
+        }
+    }
+
+    for brkpt in brkpts {
+        match patch {
+            Patch::Enable if !brkpt.is_enabled() => brkpt.enable_ptrace()?,
+            Patch::Disable if brkpt.is_enabled() => brkpt.disable_ptrace()?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 /// User defined breakpoint template,
 /// may create if debugee program not running and
 /// there is no, and there is no way to determine the relocated address.
@@ -715,6 +1202,7 @@ pub struct UninitBreakpoint {
     place: Option<PlaceDescriptorOwned>,
     r#type: BrkptType,
     debug_info_file: Option<PathBuf>,
+    options: BreakpointOptions,
 }
 
 impl UninitBreakpoint {
@@ -733,6 +1221,7 @@ impl UninitBreakpoint {
             place,
             r#type,
             debug_info_file,
+            options: BreakpointOptions::default(),
         }
     }
 
@@ -745,7 +1234,7 @@ impl UninitBreakpoint {
         Self::new_inner(
             addr,
             pid,
-            GLOBAL_BP_COUNTER.fetch_add(1, Ordering::Relaxed),
+            next_number(),
             place,
             BrkptType::UserDefined,
             debug_info_file.map(|path| path.into()),
@@ -793,25 +1282,25 @@ impl UninitBreakpoint {
             if self.place.is_some() {
                 self.place
             } else {
-                Some(
-                    dwarf
-                        .find_place_from_pc(global_addr)?
-                        .ok_or(PlaceNotFound(global_addr))?
-                        .to_owned(),
-                )
+                // place is unknown for code without debug information
+                dwarf
+                    .find_place_from_pc(global_addr)?
+                    .map(|place| place.to_owned())
             }
         } else {
             None
         };
 
-        Ok(Breakpoint::new_inner(
+        let mut brkpt = Breakpoint::new_inner(
             global_addr.relocate_to_segment(debugee, dwarf)?,
             self.pid,
             self.number,
             place,
             self.r#type,
             dwarf.pathname().into(),
-        ))
+        );
+        brkpt.options = self.options;
+        Ok(brkpt)
     }
 }
 
@@ -819,6 +1308,7 @@ pub struct BreakpointView<'a> {
     pub addr: Address,
     pub number: u32,
     pub place: Option<Cow<'a, PlaceDescriptorOwned>>,
+    pub options: Cow<'a, BreakpointOptions>,
 }
 
 impl<'a> From<Breakpoint> for BreakpointView<'a> {
@@ -827,6 +1317,7 @@ impl<'a> From<Breakpoint> for BreakpointView<'a> {
             addr: Address::Relocated(brkpt.addr),
             number: brkpt.number,
             place: brkpt.place.map(Cow::Owned),
+            options: Cow::Owned(brkpt.options),
         }
     }
 }
@@ -837,6 +1328,7 @@ impl<'a> From<&'a Breakpoint> for BreakpointView<'a> {
             addr: Address::Relocated(brkpt.addr),
             number: brkpt.number,
             place: brkpt.place.as_ref().map(Cow::Borrowed),
+            options: Cow::Borrowed(&brkpt.options),
         }
     }
 }
@@ -847,6 +1339,7 @@ impl<'a> From<UninitBreakpoint> for BreakpointView<'a> {
             addr: brkpt.addr,
             number: brkpt.number,
             place: brkpt.place.map(Cow::Owned),
+            options: Cow::Owned(brkpt.options),
         }
     }
 }
@@ -857,6 +1350,7 @@ impl<'a> From<&'a UninitBreakpoint> for BreakpointView<'a> {
             addr: brkpt.addr,
             number: brkpt.number,
             place: brkpt.place.as_ref().map(Cow::Borrowed),
+            options: Cow::Borrowed(&brkpt.options),
         }
     }
 }
@@ -866,6 +1360,7 @@ pub struct BreakpointViewOwned {
     pub addr: Address,
     pub number: u32,
     pub place: Option<PlaceDescriptorOwned>,
+    pub options: BreakpointOptions,
 }
 
 impl<'a> BreakpointView<'a> {
@@ -874,6 +1369,7 @@ impl<'a> BreakpointView<'a> {
             addr: self.addr,
             number: self.number,
             place: self.place.clone().map(|p| p.into_owned()),
+            options: self.options.clone().into_owned(),
         }
     }
 }
@@ -883,6 +1379,7 @@ pub enum DeferredBreakpoint {
     Address(RelocatedAddress),
     Line(String, u64),
     Function(String),
+    Symbol(String, u64),
 }
 
 impl DeferredBreakpoint {
@@ -897,6 +1394,10 @@ impl DeferredBreakpoint {
     pub fn at_function(function: &str) -> DeferredBreakpoint {
         DeferredBreakpoint::Function(function.to_string())
     }
+
+    pub fn at_symbol(symbol: &str, offset: u64) -> DeferredBreakpoint {
+        DeferredBreakpoint::Symbol(symbol.to_string(), offset)
+    }
 }
 
 /// Container for application breakpoints.
@@ -913,17 +1414,65 @@ pub struct BreakpointRegistry {
 
 impl BreakpointRegistry {
     /// Add a new breakpoint to registry and enable it.
-    pub fn add_and_enable(&mut self, brkpt: Breakpoint) -> Result<BreakpointView, Error> {
+    /// Hardware breakpoints are set in debug registers of `threads` (debugee tracees).
+    pub fn add_and_enable(
+        &mut self,
+        brkpt: Breakpoint,
+        threads: &[Pid],
+    ) -> Result<BreakpointView, Error> {
         if let Some(existed) = self.breakpoints.get(&brkpt.addr) {
-            existed.disable()?;
+            existed.disable(threads)?;
         }
-        brkpt.enable()?;
+        brkpt.enable(threads)?;
 
         let addr = brkpt.addr;
         self.breakpoints.insert(addr, brkpt);
         Ok((&self.breakpoints[&addr]).into())
     }
 
+    /// Add a list of new breakpoints to registry and enable them all at once,
+    /// much faster than [`BreakpointRegistry::add_and_enable`] for each breakpoint.
+    pub fn add_and_enable_all(
+        &mut self,
+        mut brkpts: Vec<Breakpoint>,
+        threads: &[Pid],
+    ) -> Result<(), Error> {
+        // only the last breakpoint at an address is kept in a registry, others aren't patched,
+        // otherwise a kept breakpoint may save an INT3 written by a dropped one
+        let mut seen = HashSet::new();
+        brkpts.reverse();
+        brkpts.retain(|brkpt| seen.insert(brkpt.addr));
+        brkpts.reverse();
+
+        let existed: Vec<_> = brkpts
+            .iter()
+            .filter_map(|brkpt| self.breakpoints.get(&brkpt.addr))
+            .collect();
+        patch_all(&existed, Patch::Disable, threads)?;
+
+        let to_enable: Vec<_> = brkpts.iter().collect();
+        patch_all(&to_enable, Patch::Enable, threads)?;
+
+        for brkpt in brkpts {
+            self.breakpoints.insert(brkpt.addr, brkpt);
+        }
+        Ok(())
+    }
+
+    /// Disable and remove all breakpoints at given addresses,
+    /// much faster than [`BreakpointRegistry::remove_by_addr`] for each address.
+    pub fn remove_all_by_addr(
+        &mut self,
+        addresses: &[RelocatedAddress],
+        threads: &[Pid],
+    ) -> Result<(), Error> {
+        let brkpts: Vec<_> = addresses
+            .iter()
+            .filter_map(|addr| self.breakpoints.remove(addr))
+            .collect();
+        patch_all(&brkpts.iter().collect::<Vec<_>>(), Patch::Disable, threads)
+    }
+
     pub fn get_enabled(&self, addr: RelocatedAddress) -> Option<&Breakpoint> {
         self.breakpoints.get(&addr)
     }
@@ -940,9 +1489,11 @@ impl BreakpointRegistry {
     }
 
     /// Remove breakpoint or uninit breakpoint from registry.
+    /// Hardware breakpoint is removed from debug registers of `threads` (debugee tracees).
     pub fn remove_by_addr(
         &mut self,
         addr: Address,
+        threads: &[Pid],
     ) -> Result<Option<BreakpointView<'static>>, Error> {
         if let Some(brkpt) = self.disabled_breakpoints.remove(&addr) {
             return Ok(Some(brkpt.into()));
@@ -950,7 +1501,7 @@ impl BreakpointRegistry {
         if let Address::Relocated(addr) = addr {
             if let Some(brkpt) = self.breakpoints.remove(&addr) {
                 if brkpt.is_enabled() {
-                    brkpt.disable()?;
+                    brkpt.disable(threads)?;
                 }
                 return Ok(Some(brkpt.into()));
             }
@@ -958,45 +1509,204 @@ impl BreakpointRegistry {
         Ok(None)
     }
 
-    /// Remove enabled breakpoint from registry by it number.
-    pub fn remove_by_num(&mut self, number: u32) -> Result<Option<BreakpointView<'static>>, Error> {
-        if let Some(addr) = self.disabled_breakpoints.iter().find_map(|(addr, brkpt)| {
-            if brkpt.number == number {
-                return Some(addr);
+    /// Remove all locations of user defined breakpoint from registry by it number.
+    pub fn remove_by_num(
+        &mut self,
+        number: u32,
+        threads: &[Pid],
+    ) -> Result<Vec<BreakpointView<'static>>, Error> {
+        let is_target = |brkpt_num: u32, r#type: &BrkptType| {
+            brkpt_num == number && r#type == &BrkptType::UserDefined
+        };
+
+        let addresses: Vec<_> = self
+            .disabled_breakpoints
+            .iter()
+            .filter(|(_, b)| is_target(b.number, &b.r#type))
+            .map(|(&addr, _)| addr)
+            .chain(
+                self.breakpoints
+                    .iter()
+                    .filter(|(_, b)| is_target(b.number, &b.r#type))
+                    .map(|(&addr, _)| Address::Relocated(addr)),
+            )
+            .collect();
+
+        let mut removed = vec![];
+        for addr in addresses {
+            if let Some(view) = self.remove_by_addr(addr, threads)? {
+                removed.push(view);
             }
-            None
-        }) {
-            return self.remove_by_addr(*addr);
+        }
+        Ok(removed)
+    }
+
+    /// Change options of all locations of user defined breakpoint.
+    /// Return `None` if breakpoint not found, otherwise return one of locations.
+    pub fn update_options(
+        &mut self,
+        number: u32,
+        f: impl Fn(&mut BreakpointOptions),
+    ) -> Option<BreakpointView> {
+        let is_target = |brkpt_num: u32, r#type: &BrkptType| {
+            brkpt_num == number && r#type == &BrkptType::UserDefined
+        };
+
+        self.breakpoints
+            .values_mut()
+            .filter(|b| is_target(b.number, &b.r#type))
+            .for_each(|b| f(&mut b.options));
+        self.disabled_breakpoints
+            .values_mut()
+            .filter(|b| is_target(b.number, &b.r#type))
+            .for_each(|b| f(&mut b.options));
+
+        if let Some(brkpt) = self
+            .breakpoints
+            .values()
+            .find(|b| is_target(b.number, &b.r#type))
+        {
+            return Some(brkpt.into());
+        }
+        self.disabled_breakpoints
+            .values()
+            .find(|b| is_target(b.number, &b.r#type))
+            .map(BreakpointView::from)
+    }
+
+    /// Return indexes of debug registers used by hardware breakpoints
+    /// (including breakpoints that are not enabled yet).
+    pub fn hardware_regs(&self) -> Vec<usize> {
+        self.breakpoints
+            .values()
+            .filter_map(|brkpt| brkpt.options.hardware)
+            .chain(
+                self.disabled_breakpoints
+                    .values()
+                    .filter_map(|brkpt| brkpt.options.hardware),
+            )
+            .collect()
+    }
+
+    /// Make all locations of a user defined breakpoint hardware or software.
+    /// Each new hardware location takes one of free debug registers, enabled locations
+    /// are reinstalled. Return `None` if breakpoint not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `number`: breakpoint number
+    /// * `hardware`: true for hardware breakpoint, false for software breakpoint
+    /// * `free_regs`: indexes of debug registers that are not used by breakpoints or watchpoints
+    /// * `threads`: debugee threads (tracees)
+    pub fn set_hardware(
+        &mut self,
+        number: u32,
+        hardware: bool,
+        free_regs: &[usize],
+        threads: &[Pid],
+    ) -> Result<Option<BreakpointView>, Error> {
+        let is_target = |brkpt_num: u32, r#type: &BrkptType, options: &BreakpointOptions| {
+            brkpt_num == number
+                && r#type == &BrkptType::UserDefined
+                && options.hardware.is_some() != hardware
+        };
+
+        let to_change = self
+            .breakpoints
+            .values()
+            .filter(|b| is_target(b.number, &b.r#type, &b.options))
+            .count()
+            + self
+                .disabled_breakpoints
+                .values()
+                .filter(|b| is_target(b.number, &b.r#type, &b.options))
+                .count();
+        if hardware && to_change > free_regs.len() {
+            return Err(HardwareBreakpointLimit);
         }
 
-        if let Some(addr) = self.breakpoints.iter().find_map(|(addr, brkpt)| {
-            if brkpt.number == number {
-                return Some(addr);
+        let mut regs = free_regs.iter().copied();
+        for brkpt in self
+            .breakpoints
+            .values_mut()
+            .filter(|b| is_target(b.number, &b.r#type, &b.options))
+        {
+            let reg = if hardware { regs.next() } else { None };
+            let enabled = brkpt.is_enabled();
+            if enabled {
+                brkpt.disable(threads)?;
             }
-            None
-        }) {
-            return self.remove_by_addr(Address::Relocated(*addr));
+            let prev = mem::replace(&mut brkpt.options.hardware, reg);
+            if enabled {
+                if let Err(e) = brkpt.enable(threads) {
+                    brkpt.options.hardware = prev;
+                    brkpt.enable(threads)?;
+                    return Err(e);
+                }
+            }
+        }
+        for brkpt in self
+            .disabled_breakpoints
+            .values_mut()
+            .filter(|b| is_target(b.number, &b.r#type, &b.options))
+        {
+            brkpt.options.hardware = if hardware { regs.next() } else { None };
         }
 
-        Ok(None)
+        Ok(self.update_options(number, |_| {}))
+    }
+
+    /// Increase hit counter of breakpoint at address. Return `false` if this hit
+    /// must be ignored, ignore counter is decreased in this case.
+    /// Counters are shared by all locations of a user defined breakpoint.
+    pub fn register_hit(&mut self, addr: RelocatedAddress) -> bool {
+        let Some(brkpt) = self.breakpoints.get(&addr) else {
+            return true;
+        };
+        let ignore = brkpt.options.ignore_count > 0;
+        let number = brkpt.number;
+        let in_group = |brkpt_num: u32, r#type: &BrkptType| {
+            number != 0 && brkpt_num == number && r#type == &BrkptType::UserDefined
+        };
+
+        let register = |options: &mut BreakpointOptions| {
+            options.hit_count += 1;
+            if ignore {
+                options.ignore_count = options.ignore_count.saturating_sub(1);
+            }
+        };
+        self.breakpoints
+            .iter_mut()
+            .filter(|(&b_addr, b)| b_addr == addr || in_group(b.number, &b.r#type))
+            .for_each(|(_, b)| register(&mut b.options));
+        self.disabled_breakpoints
+            .values_mut()
+            .filter(|b| in_group(b.number, &b.r#type))
+            .for_each(|b| register(&mut b.options));
+
+        !ignore
     }
 
-    /// Enable currently disabled breakpoints.
+    /// Enable currently disabled breakpoints. Breakpoints in shared libraries that are not
+    /// loaded yet (after debugee restart) stay disabled until a library is loaded.
     pub fn enable_all_breakpoints(&mut self, debugee: &Debugee) -> Vec<Error> {
         let mut errors = vec![];
         let mut disabled_breakpoints = mem::take(&mut self.disabled_breakpoints);
-        for (_, uninit_brkpt) in disabled_breakpoints.drain() {
-            let brkpt = match uninit_brkpt.try_into_brkpt(debugee) {
-                Ok(b) => b,
-                Err(e) => {
-                    errors.push(e);
-                    continue;
+        let mut brkpts = Vec::with_capacity(disabled_breakpoints.len());
+        for (key, uninit_brkpt) in disabled_breakpoints.drain() {
+            match uninit_brkpt.clone().try_into_brkpt(debugee) {
+                Ok(b) => brkpts.push(b),
+                // library is not loaded yet
+                Err(MappingOffsetNotFound(_) | NoDebugInformation(_)) => {
+                    self.disabled_breakpoints.insert(key, uninit_brkpt);
                 }
+                Err(e) => errors.push(e),
             };
+        }
 
-            if let Err(e) = self.add_and_enable(brkpt) {
-                errors.push(e);
-            }
+        let threads = debugee.tracee_ctl().tracee_pids();
+        if let Err(e) = self.add_and_enable_all(brkpts, &threads) {
+            errors.push(e);
         }
         errors
     }
@@ -1014,7 +1724,7 @@ impl BreakpointRegistry {
         let uninit_entry_point_brkpt = self.disabled_breakpoints.remove(&key).unwrap();
 
         let brkpt = uninit_entry_point_brkpt.try_into_brkpt(debugee)?;
-        self.add_and_enable(brkpt)?;
+        self.add_and_enable(brkpt, &debugee.tracee_ctl().tracee_pids())?;
 
         Ok(())
     }
@@ -1023,12 +1733,23 @@ impl BreakpointRegistry {
     pub fn disable_all_breakpoints(&mut self, debugee: &Debugee) -> Result<Vec<Error>, Error> {
         let mut errors = vec![];
         let mut breakpoints = std::mem::take(&mut self.breakpoints);
+        let threads = debugee.tracee_ctl().tracee_pids();
+        if let Err(e) = patch_all(
+            &breakpoints.values().collect::<Vec<_>>(),
+            Patch::Disable,
+            &threads,
+        ) {
+            errors.push(e);
+        }
         for (_, brkpt) in breakpoints.drain() {
-            if let Err(e) = brkpt.disable() {
-                errors.push(e);
-            }
-
-            let addr = Address::Global(brkpt.addr.into_global(debugee)?);
+            // a breakpoint that can't be converted is lost, but others are kept
+            let addr = match brkpt.addr.into_global(debugee) {
+                Ok(addr) => Address::Global(addr),
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
             match brkpt.r#type {
                 BrkptType::EntryPoint => {
                     self.add_uninit(UninitBreakpoint::new_entry_point(
@@ -1038,12 +1759,17 @@ impl BreakpointRegistry {
                     ));
                 }
                 BrkptType::UserDefined => {
-                    self.add_uninit(UninitBreakpoint::new(
-                        Some(brkpt.debug_info_file),
+                    // number is kept, so locations of a breakpoint stay together
+                    let mut uninit_brkpt = UninitBreakpoint::new_inner(
                         addr,
                         brkpt.pid,
+                        brkpt.number,
                         brkpt.place,
-                    ));
+                        BrkptType::UserDefined,
+                        Some(brkpt.debug_info_file),
+                    );
+                    uninit_brkpt.options = brkpt.options;
+                    self.add_uninit(uninit_brkpt);
                 }
                 BrkptType::Temporary | BrkptType::LinkerMapFn | BrkptType::Transparent(_) => {}
             }
@@ -1061,6 +1787,20 @@ impl BreakpointRegistry {
             .for_each(|(_, brkpt)| brkpt.pid = new_pid);
     }
 
+    /// Move enabled breakpoints into a copy of debugee process with the same memory layout,
+    /// memory of a copy must contain original code (without breakpoint instructions).
+    ///
+    /// # Arguments
+    ///
+    /// * `new_pid`: pid of a process copy
+    /// * `threads`: threads of a process copy
+    pub fn move_to_process(&mut self, new_pid: Pid, threads: &[Pid]) -> Result<(), Error> {
+        self.update_pid(new_pid);
+        let brkpts = self.breakpoints.values().collect::<Vec<_>>();
+        brkpts.iter().for_each(|brkpt| brkpt.enabled.set(false));
+        patch_all(&brkpts, Patch::Enable, threads)
+    }
+
     /// Return vector of currently enabled breakpoints.
     pub fn active_breakpoints(&self) -> Vec<&Breakpoint> {
         self.breakpoints.values().collect()
@@ -1085,3 +1825,76 @@ impl BreakpointRegistry {
         snap
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_patch_batch() {
+        // patch a memory of current process, spread across two pages
+        let data: Vec<u8> = (0..PAGE_SIZE * 2).map(|i| (i % 200) as u8).collect();
+        let pid = Pid::this();
+        let offsets = [0, 1, 100, PAGE_SIZE + 7, PAGE_SIZE * 2 - 1];
+        let brkpts: Vec<_> = offsets
+            .iter()
+            .map(|&offset| {
+                let addr = RelocatedAddress::from(data.as_ptr() as usize + offset);
+                Breakpoint::new_temporary("", addr, pid)
+            })
+            .collect();
+        let brkpts: Vec<_> = brkpts.iter().collect();
+
+        patch_batch(&brkpts, Patch::Enable).unwrap();
+        for (i, byte) in data.iter().enumerate() {
+            let expected = if offsets.contains(&i) {
+                0xCC
+            } else {
+                (i % 200) as u8
+            };
+            assert_eq!(unsafe { std::ptr::read_volatile(byte) }, expected);
+        }
+        assert!(brkpts.iter().all(|brkpt| brkpt.is_enabled()));
+
+        patch_batch(&brkpts, Patch::Disable).unwrap();
+        for (i, byte) in data.iter().enumerate() {
+            assert_eq!(unsafe { std::ptr::read_volatile(byte) }, (i % 200) as u8);
+        }
+        assert!(brkpts.iter().all(|brkpt| !brkpt.is_enabled()));
+    }
+
+    #[test]
+    fn test_enable_same_address_twice() {
+        let data: Vec<u8> = vec![0x55; 16];
+        let pid = Pid::this();
+        let addr = RelocatedAddress::from(data.as_ptr() as usize + 3);
+        let read = || unsafe { std::ptr::read_volatile(&data[3]) };
+
+        let mut registry = BreakpointRegistry::default();
+        registry
+            .add_and_enable_all(
+                vec![
+                    Breakpoint::new_temporary("", addr, pid),
+                    Breakpoint::new_temporary("", addr, pid),
+                ],
+                &[],
+            )
+            .unwrap();
+        assert_eq!(read(), 0xCC);
+        assert_eq!(registry.get_enabled(addr).unwrap().saved_data.get(), 0x55);
+
+        registry.remove_all_by_addr(&[addr], &[]).unwrap();
+        assert_eq!(read(), 0x55);
+
+        // duplicates in a single batch save an original byte too
+        let brkpts = [
+            Breakpoint::new_temporary("", addr, pid),
+            Breakpoint::new_temporary("", addr, pid),
+        ];
+        let brkpts: Vec<_> = brkpts.iter().collect();
+        patch_batch(&brkpts, Patch::Enable).unwrap();
+        assert!(brkpts.iter().all(|brkpt| brkpt.saved_data.get() == 0x55));
+        patch_batch(&brkpts[1..], Patch::Disable).unwrap();
+        assert_eq!(read(), 0x55);
+    }
+}
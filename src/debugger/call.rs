@@ -0,0 +1,605 @@
+//! Injection of function calls into a debugee.
+//!
+//! A call is made in the thread in focus according to System V AMD64 calling convention:
+//! registers are saved, arguments are placed into argument registers (or onto the stack),
+//! the return address points to a trap near the program entry point. When the trap is hit
+//! the return value is read and the original registers are restored.
+//!
+//! A forced return from a function in focus follows the same convention: caller frame registers
+//! are restored by unwinding and a return value is placed into a return register.
+
+use crate::ctx_resolve_unit_call;
+use crate::debugger::address::Address;
+use crate::debugger::breakpoint::Breakpoint;
+use crate::debugger::debugee::dwarf::r#type::{
+    ComplexType, EvaluationContext, TypeDeclaration, TypeIdentity, TypeParser,
+};
+use crate::debugger::debugee::dwarf::unit::FunctionDie;
+use crate::debugger::debugee::dwarf::{ContextualDieRef, NamespaceHierarchy};
+use crate::debugger::debugee::tracer::StopReason;
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::{
+    FunctionCall, FunctionNotFound, ProcessExit, ProcessNotStarted, Ptrace, UnwindNoContext,
+};
+use crate::debugger::register::{DwarfRegisterMap, RegisterMap};
+use crate::debugger::variable::select::Literal;
+use crate::debugger::variable::{write, VariableIR, VariableIdentity, VariableParser};
+use crate::debugger::{read_memory_by_pid, register, write_memory_by_pid, Debugger};
+use bytes::Bytes;
+use gimli::{DW_ATE_float, DW_ATE_signed, DW_ATE_signed_char};
+use itertools::Itertools;
+use nix::libc::{user_fpregs_struct, user_regs_struct};
+use nix::sys::ptrace;
+use nix::unistd::Pid;
+use std::mem;
+
+/// Count of general purpose registers used for arguments passing.
+const INTEGER_ARG_REGISTERS: usize = 6;
+/// Count of SSE registers used for arguments passing.
+const SSE_ARG_REGISTERS: usize = 8;
+/// Area below the stack pointer that may be used by a function without moving stack pointer.
+const RED_ZONE_SIZE: u64 = 128;
+/// Maximum nesting of aggregate types taken into account when a value is classified.
+const CLASSIFY_DEPTH_LIMIT: u32 = 16;
+
+/// Class of a value, determines how the value is passed or returned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueClass {
+    /// Value passed in general purpose registers.
+    Integer,
+    /// Value passed in SSE registers.
+    Sse,
+    /// Value passed in memory.
+    Memory,
+}
+
+/// Classify a value of a type, return `None` if this value is not supported
+/// (an aggregate that contains both integer and floating point fields).
+///
+/// # Arguments
+///
+/// * `r#type`: complex type
+/// * `type_id`: identity of a value type in a complex type
+/// * `size`: size of a value in bytes
+fn classify(r#type: &ComplexType, type_id: TypeIdentity, size: u64) -> Option<ValueClass> {
+    if size > 16 {
+        return Some(ValueClass::Memory);
+    }
+
+    let (mut has_integer, mut has_sse) = (false, false);
+    collect_leaf_classes(r#type, type_id, &mut has_integer, &mut has_sse, 0)?;
+    match (has_integer, has_sse) {
+        (true, true) => None,
+        (false, true) => Some(ValueClass::Sse),
+        _ => Some(ValueClass::Integer),
+    }
+}
+
+fn collect_leaf_classes(
+    r#type: &ComplexType,
+    type_id: TypeIdentity,
+    has_integer: &mut bool,
+    has_sse: &mut bool,
+    depth: u32,
+) -> Option<()> {
+    if depth > CLASSIFY_DEPTH_LIMIT {
+        return None;
+    }
+    let mut visit = |type_id: Option<TypeIdentity>| {
+        collect_leaf_classes(r#type, type_id?, has_integer, has_sse, depth + 1)
+    };
+
+    match r#type.types.get(&type_id)? {
+        TypeDeclaration::Scalar(scalar) => {
+            if scalar.encoding == Some(DW_ATE_float) {
+                *has_sse = true;
+            } else {
+                *has_integer = true;
+            }
+        }
+        TypeDeclaration::Pointer { .. }
+        | TypeDeclaration::Subroutine { .. }
+        | TypeDeclaration::CStyleEnum { .. } => *has_integer = true,
+        TypeDeclaration::Array(array) => visit(array.element_type)?,
+        TypeDeclaration::Structure { members, .. } | TypeDeclaration::Union { members, .. } => {
+            for member in members {
+                visit(member.type_ref)?;
+            }
+        }
+        TypeDeclaration::RustEnum {
+            discr_type,
+            enumerators,
+            ..
+        } => {
+            if let Some(discr) = discr_type {
+                visit(discr.type_ref)?;
+            }
+            for member in enumerators.values() {
+                visit(member.type_ref)?;
+            }
+        }
+        TypeDeclaration::ModifiedType { inner, .. } => visit(*inner)?,
+    }
+    Some(())
+}
+
+/// Return `true` if the value of a type is a signed integer and must be sign-extended
+/// when it's placed into a register.
+#[allow(non_upper_case_globals)]
+fn is_signed(r#type: &ComplexType, type_id: TypeIdentity) -> bool {
+    match r#type.types.get(&type_id) {
+        Some(TypeDeclaration::Scalar(scalar)) => {
+            matches!(scalar.encoding, Some(DW_ATE_signed | DW_ATE_signed_char))
+        }
+        Some(TypeDeclaration::ModifiedType {
+            inner: Some(inner), ..
+        }) => is_signed(r#type, *inner),
+        _ => false,
+    }
+}
+
+/// Place bytes of a value into an eightbyte, with sign extension if needed.
+fn into_eightbyte(bytes: &[u8], signed: bool) -> u64 {
+    let negative = signed && bytes.last().is_some_and(|b| b & 0x80 != 0);
+    let mut word = if negative { [0xFF; 8] } else { [0; 8] };
+    word[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(word)
+}
+
+fn xmm_low(fpregs: &user_fpregs_struct, idx: usize) -> u64 {
+    let lo = fpregs.xmm_space[idx * 4] as u64;
+    let hi = fpregs.xmm_space[idx * 4 + 1] as u64;
+    (hi << 32) | lo
+}
+
+fn set_xmm(fpregs: &mut user_fpregs_struct, idx: usize, value: u64) {
+    fpregs.xmm_space[idx * 4] = value as u32;
+    fpregs.xmm_space[idx * 4 + 1] = (value >> 32) as u32;
+    fpregs.xmm_space[idx * 4 + 2] = 0;
+    fpregs.xmm_space[idx * 4 + 3] = 0;
+}
+
+/// Registers of a thread before an injected call.
+struct SavedState {
+    pid: Pid,
+    regs: user_regs_struct,
+    fpregs: user_fpregs_struct,
+}
+
+impl SavedState {
+    fn save(pid: Pid) -> Result<Self, Error> {
+        Ok(Self {
+            pid,
+            regs: ptrace::getregs(pid).map_err(Ptrace)?,
+            fpregs: register::fp_registers(pid)?,
+        })
+    }
+
+    fn restore(&self) -> Result<(), Error> {
+        ptrace::setregs(self.pid, self.regs).map_err(Ptrace)?;
+        register::set_fp_registers(self.pid, &self.fpregs)
+    }
+}
+
+/// Return value of a called function and the way it is returned.
+struct ReturnValue {
+    r#type: ComplexType,
+    size: u64,
+    class: ValueClass,
+}
+
+impl Debugger {
+    /// Return a function that may be called by name.
+    fn call_target(&self, fn_name: &str) -> Result<ContextualDieRef<'_, FunctionDie>, Error> {
+        let mut candidates = vec![];
+        for dwarf in self.debugee.debug_info_all() {
+            if !dwarf.has_debug_info() {
+                continue;
+            }
+            candidates.extend(
+                dwarf
+                    .search_functions(fn_name)?
+                    .into_iter()
+                    .filter(|func| !func.ranges().is_empty()),
+            );
+        }
+
+        let names = candidates
+            .iter()
+            .filter_map(|func| func.full_name())
+            .unique()
+            .collect::<Vec<_>>();
+        if names.len() > 1 {
+            return Err(FunctionCall(format!(
+                "`{fn_name}` is ambiguous, candidates: {}",
+                names.join(", ")
+            )));
+        }
+
+        candidates
+            .into_iter()
+            .next()
+            .ok_or_else(|| FunctionCall(format!("function `{fn_name}` not found")))
+    }
+
+    /// Call a function of a debugee in the thread in focus and return its result,
+    /// `None` if function returns nothing. Registers of the thread are restored after the call,
+    /// other threads are running while the function executes.
+    ///
+    /// Arguments must be scalars, C-style enums or pointers,
+    /// aggregates are supported as return values only.
+    ///
+    /// # Arguments
+    ///
+    /// * `fn_name`: function name (full function path or part of this path)
+    /// * `args`: function arguments
+    pub fn call_function(
+        &mut self,
+        fn_name: &str,
+        args: &[Literal],
+    ) -> Result<Option<VariableIR>, Error> {
+        if !self.debugee.is_in_progress() {
+            return Err(ProcessNotStarted);
+        }
+        if self.process.core().is_some() {
+            return Err(Error::PostMortem);
+        }
+
+        let pid = self.exploration_ctx().pid_on_focus();
+        let func = self.call_target(fn_name)?;
+        let params = func.parameters();
+        if params.len() != args.len() {
+            return Err(FunctionCall(format!(
+                "`{fn_name}` takes {} argument(s) but {} were supplied",
+                params.len(),
+                args.len()
+            )));
+        }
+
+        let mut int_args = vec![];
+        let mut sse_args = vec![];
+        let mut stack_args = vec![];
+        for (param, arg) in params.iter().zip(args) {
+            let r#type = param
+                .r#type()
+                .ok_or_else(|| FunctionCall(format!("unknown type of argument `{arg}`")))?;
+            let bytes = write::literal_into_bytes(&r#type, r#type.root, arg)
+                .map_err(|e| FunctionCall(e.to_string()))?;
+            if bytes.len() > mem::size_of::<u64>() {
+                return Err(FunctionCall(format!(
+                    "argument `{arg}` is larger than 8 bytes, this is not supported"
+                )));
+            }
+            let eightbyte = into_eightbyte(&bytes, is_signed(&r#type, r#type.root));
+
+            match classify(&r#type, r#type.root, bytes.len() as u64) {
+                Some(ValueClass::Sse) if sse_args.len() < SSE_ARG_REGISTERS => {
+                    sse_args.push(eightbyte)
+                }
+                Some(ValueClass::Integer) if int_args.len() < INTEGER_ARG_REGISTERS => {
+                    int_args.push(eightbyte)
+                }
+                _ => stack_args.push(eightbyte),
+            }
+        }
+
+        let evaluator = ctx_resolve_unit_call!(func, evaluator, &self.debugee);
+        let eval_ctx = &EvaluationContext {
+            evaluator: &evaluator,
+            expl_ctx: self.exploration_ctx(),
+        };
+        let ret = match func.die.return_type_ref {
+            None => None,
+            Some(type_ref) => {
+                let r#type = TypeParser::new().parse(func, type_ref);
+                let size = r#type
+                    .type_size_in_bytes(eval_ctx, r#type.root)
+                    .ok_or_else(|| FunctionCall("unknown size of return value".to_string()))?;
+                let class = classify(&r#type, r#type.root, size).ok_or_else(|| {
+                    FunctionCall("return value of this type is not supported".to_string())
+                })?;
+                Some(ReturnValue {
+                    r#type,
+                    size,
+                    class,
+                })
+            }
+        };
+        if ret.as_ref().map(|r| r.class) == Some(ValueClass::Memory) {
+            // address of a returned value is passed as a hidden first argument
+            if int_args.len() == INTEGER_ARG_REGISTERS {
+                stack_args.insert(0, int_args.pop().expect("infallible"));
+            }
+        }
+
+        let fn_addr = func
+            .start_instruction()?
+            .relocate_to_segment(&self.debugee, func.debug_info)?;
+
+        // `_start` function never returns, so its code is used as a trap for a return,
+        // a trap is placed at the second byte to not interfere with an entry point breakpoint
+        let program = self.debugee.program_debug_info()?;
+        let trap_addr = self
+            .debugee
+            .entry_point()
+            .relocate_to_segment(&self.debugee, program)?
+            .offset(1);
+        if self.breakpoints.get_enabled(trap_addr).is_some() {
+            return Err(FunctionCall(format!(
+                "breakpoint at {trap_addr} prevents a function call"
+            )));
+        }
+        let trap = Breakpoint::new_temporary(program.pathname(), trap_addr, pid);
+
+        let saved = SavedState::save(pid)?;
+        let mut regs = saved.regs;
+        let mut fpregs = saved.fpregs;
+
+        let mut sp = regs.rsp - RED_ZONE_SIZE;
+        let ret_buf = match ret.as_ref() {
+            Some(ret) if ret.class == ValueClass::Memory => {
+                sp = (sp - ret.size) & !0xF;
+                int_args.insert(0, sp);
+                Some(sp)
+            }
+            _ => None,
+        };
+        sp = (sp - stack_args.len() as u64 * 8) & !0xF;
+        let stack_data = stack_args
+            .iter()
+            .flat_map(|arg| arg.to_le_bytes())
+            .collect::<Vec<_>>();
+        write_memory_by_pid(pid, sp as usize, &stack_data).map_err(Ptrace)?;
+        sp -= 8;
+        write_memory_by_pid(pid, sp as usize, &u64::from(trap_addr).to_le_bytes())
+            .map_err(Ptrace)?;
+
+        let int_registers = [
+            &mut regs.rdi,
+            &mut regs.rsi,
+            &mut regs.rdx,
+            &mut regs.rcx,
+            &mut regs.r8,
+            &mut regs.r9,
+        ];
+        for (reg, arg) in int_registers.into_iter().zip(int_args) {
+            *reg = arg;
+        }
+        for (idx, arg) in sse_args.iter().enumerate() {
+            set_xmm(&mut fpregs, idx, *arg);
+        }
+        // count of used vector registers for variadic functions
+        regs.rax = sse_args.len() as u64;
+        regs.rsp = sp;
+        regs.rip = u64::from(fn_addr);
+        // prevent a syscall restart if a thread stopped in a syscall
+        regs.orig_rax = u64::MAX;
+
+        ptrace::setregs(pid, regs).map_err(Ptrace)?;
+        register::set_fp_registers(pid, &fpregs)?;
+        let threads = self.thread_pids();
+        self.breakpoints.add_and_enable(trap, &threads)?;
+
+        let stop = self.continue_execution();
+        if self.debugee.is_exited() {
+            return Err(ProcessExit(0));
+        }
+        self.remove_breakpoint(Address::Relocated(trap_addr))?;
+
+        let returned = matches!(stop, Ok(StopReason::Breakpoint(stop_pid, pc)) if stop_pid == pid && pc == trap_addr);
+        if !returned {
+            saved.restore()?;
+            self.expl_ctx_switch_thread(pid)?;
+            stop?;
+            return Err(FunctionCall(format!(
+                "program stopped in a function called from the debugger, `{fn_name}` call abandoned"
+            )));
+        }
+
+        let result_regs = ptrace::getregs(pid).map_err(Ptrace)?;
+        let result_fpregs = register::fp_registers(pid)?;
+        saved.restore()?;
+        self.expl_ctx_switch_thread(pid)?;
+
+        let Some(ret) = ret else {
+            return Ok(None);
+        };
+        let data = match ret.class {
+            ValueClass::Integer => [result_regs.rax, result_regs.rdx]
+                .iter()
+                .flat_map(|reg| reg.to_le_bytes())
+                .take(ret.size as usize)
+                .collect(),
+            ValueClass::Sse => [xmm_low(&result_fpregs, 0), xmm_low(&result_fpregs, 1)]
+                .iter()
+                .flat_map(|reg| reg.to_le_bytes())
+                .take(ret.size as usize)
+                .collect(),
+            ValueClass::Memory => read_memory_by_pid(
+                pid,
+                ret_buf.expect("infallible") as usize,
+                ret.size as usize,
+            )
+            .map_err(Ptrace)?,
+        };
+
+        // exploration context changed, so function is searched again
+        let func = self.call_target(fn_name)?;
+        let evaluator = ctx_resolve_unit_call!(func, evaluator, &self.debugee);
+        let eval_ctx = &EvaluationContext {
+            evaluator: &evaluator,
+            expl_ctx: self.exploration_ctx(),
+        };
+        let identity = VariableIdentity::new(
+            NamespaceHierarchy::default(),
+            Some(format!("{fn_name}({})", args.iter().join(", "))),
+        );
+        let parser = VariableParser::new(&ret.r#type).with_pretty_printers(&self.pretty_printers);
+        Ok(Some(parser.parse(
+            eval_ctx,
+            identity,
+            Some(Bytes::from(data)),
+        )))
+    }
+
+    /// Force a function of a selected frame to return immediately (like gdb `return`),
+    /// selected frame and frames above it are popped. Function code after a current
+    /// instruction is not executed, so destructors of local variables are not called.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: return value, if `None` then return register is not changed
+    pub fn force_return(&mut self, value: Option<Literal>) -> Result<(), Error> {
+        if !self.debugee.is_in_progress() {
+            return Err(ProcessNotStarted);
+        }
+        if self.process.core().is_some() {
+            return Err(Error::PostMortem);
+        }
+
+        let ctx = self.exploration_ctx().clone();
+        let pid = ctx.pid_on_focus();
+        let location = ctx.location();
+        let dwarf = self.debugee.debug_info(location.pc)?;
+        let func = dwarf
+            .find_function_by_pc(location.global_pc)?
+            .ok_or(FunctionNotFound(location.global_pc))?;
+
+        let ret_value = match value {
+            None => None,
+            Some(value) => {
+                let type_ref = func.die.return_type_ref.ok_or_else(|| {
+                    FunctionCall("function has no return value, value is not expected".to_string())
+                })?;
+                let r#type = TypeParser::new().parse(func, type_ref);
+                let bytes = write::literal_into_bytes(&r#type, r#type.root, &value)
+                    .map_err(|e| FunctionCall(e.to_string()))?;
+                if bytes.len() > mem::size_of::<u64>() {
+                    return Err(FunctionCall(format!(
+                        "return value `{value}` is larger than 8 bytes, this is not supported"
+                    )));
+                }
+                let eightbyte = into_eightbyte(&bytes, is_signed(&r#type, r#type.root));
+                match classify(&r#type, r#type.root, bytes.len() as u64) {
+                    Some(ValueClass::Integer) => Some((ValueClass::Integer, eightbyte)),
+                    Some(ValueClass::Sse) => Some((ValueClass::Sse, eightbyte)),
+                    _ => {
+                        return Err(FunctionCall(
+                            "return value of this type is not supported".to_string(),
+                        ))
+                    }
+                }
+            }
+        };
+
+        // registers of a caller frame: stack pointer is a CFA, program counter is
+        // a return address, callee-saved registers are restored from a stack
+        let mut caller_registers = DwarfRegisterMap::from(RegisterMap::current(pid)?);
+        self.debugee
+            .restore_registers_at_frame(pid, &mut caller_registers, ctx.frame() + 1)?;
+        let mut regs = ptrace::getregs(pid).map_err(Ptrace)?;
+        let return_addr = caller_registers.value(gimli::Register(16))?;
+        // registers are not changed if a caller frame can't be unwound
+        if return_addr == regs.rip {
+            return Err(UnwindNoContext);
+        }
+        for (num, reg) in [
+            (3, &mut regs.rbx),
+            (6, &mut regs.rbp),
+            (7, &mut regs.rsp),
+            (12, &mut regs.r12),
+            (13, &mut regs.r13),
+            (14, &mut regs.r14),
+            (15, &mut regs.r15),
+        ] {
+            if let Ok(value) = caller_registers.value(gimli::Register(num)) {
+                *reg = value;
+            }
+        }
+        regs.rip = return_addr;
+        // prevent a syscall restart if a thread stopped in a syscall
+        regs.orig_rax = u64::MAX;
+
+        match ret_value {
+            Some((ValueClass::Integer, value)) => regs.rax = value,
+            Some((_, value)) => {
+                let mut fpregs = register::fp_registers(pid)?;
+                set_xmm(&mut fpregs, 0, value);
+                register::set_fp_registers(pid, &fpregs)?;
+            }
+            None => {}
+        }
+        ptrace::setregs(pid, regs).map_err(Ptrace)?;
+
+        self.expl_ctx_switch_thread(pid)?;
+        self.execute_on_step_hook()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debugger::debugee::dwarf::r#type::{ScalarType, StructureMember};
+    use crate::debugger::debugee::dwarf::unit::DieRef;
+    use gimli::{DW_ATE_unsigned, DwAte, UnitOffset};
+    use std::collections::HashMap;
+
+    fn scalar(name: &str, size: u64, encoding: DwAte) -> TypeDeclaration {
+        TypeDeclaration::Scalar(ScalarType {
+            namespaces: NamespaceHierarchy::default(),
+            name: Some(name.to_string()),
+            byte_size: Some(size),
+            encoding: Some(encoding),
+        })
+    }
+
+    fn structure(size: u64, members: &[TypeIdentity]) -> TypeDeclaration {
+        TypeDeclaration::Structure {
+            namespaces: NamespaceHierarchy::default(),
+            name: Some("S".to_string()),
+            byte_size: Some(size),
+            members: members
+                .iter()
+                .map(|&type_ref| StructureMember {
+                    in_struct_location: None,
+                    name: None,
+                    type_ref: Some(type_ref),
+                    decl: None,
+                })
+                .collect(),
+            type_params: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let id = |offset| DieRef::Unit(UnitOffset(offset));
+        let r#type = ComplexType {
+            types: HashMap::from([
+                (id(1), scalar("i32", 4, DW_ATE_signed)),
+                (id(2), scalar("f64", 8, DW_ATE_float)),
+                (id(3), scalar("u64", 8, DW_ATE_unsigned)),
+                (id(4), structure(16, &[id(2), id(2)])),
+                (id(5), structure(16, &[id(1), id(3)])),
+                (id(6), structure(16, &[id(2), id(3)])),
+                (id(7), structure(24, &[id(3), id(3), id(3)])),
+            ]),
+            root: id(1),
+        };
+
+        assert_eq!(classify(&r#type, id(1), 4), Some(ValueClass::Integer));
+        assert_eq!(classify(&r#type, id(2), 8), Some(ValueClass::Sse));
+        assert_eq!(classify(&r#type, id(4), 16), Some(ValueClass::Sse));
+        assert_eq!(classify(&r#type, id(5), 16), Some(ValueClass::Integer));
+        assert_eq!(classify(&r#type, id(6), 16), None);
+        assert_eq!(classify(&r#type, id(7), 24), Some(ValueClass::Memory));
+    }
+
+    #[test]
+    fn test_into_eightbyte() {
+        assert_eq!(into_eightbyte(&[0xFF], true), u64::MAX);
+        assert_eq!(into_eightbyte(&[0xFF], false), 0xFF);
+        assert_eq!(into_eightbyte(&[1, 2], true), 0x0201);
+        assert_eq!(into_eightbyte(&(-2i32).to_le_bytes(), true), -2i64 as u64);
+    }
+}
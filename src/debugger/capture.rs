@@ -0,0 +1,228 @@
+//! Capture of debugee standard streams.
+//!
+//! Debugee stdout and stderr are redirected into pipes owned by the debugger. Background threads
+//! read the pipes (so a debugee never blocks on a full pipe) and collect output chunks, chunks are
+//! delivered into [`EventHook::on_debugee_output`] and oracles right before every stop report.
+//! Debugee stdin may be redirected too, then an input is sent by [`Debugger::write_stdin`].
+//!
+//! [`EventHook::on_debugee_output`]: crate::debugger::EventHook::on_debugee_output
+
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::StdinNotCaptured;
+use crate::debugger::process::{Child, Template};
+use crate::debugger::Debugger;
+use nix::libc;
+use os_pipe::{PipeReader, PipeWriter};
+use std::io::{ErrorKind, Read, Write};
+use std::os::fd::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use strum_macros::Display;
+
+/// How long a reader thread waits for data before checking that capture is dropped.
+const POLL_TIMEOUT_MS: libc::c_int = 100;
+
+/// Debugee output stream.
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Read end of an output pipe, reads are serialized by a mutex,
+/// so the data read by a reader thread and by a final drain keep their order.
+struct OutputPipe {
+    stream: OutputStream,
+    fd: RawFd,
+    reader: Mutex<PipeReader>,
+}
+
+impl OutputPipe {
+    fn new(stream: OutputStream, reader: PipeReader) -> Result<Self, Error> {
+        // SAFETY: fd is owned by a reader, only file status flags are changed
+        let fd = reader.as_raw_fd();
+        let res = unsafe {
+            let flags = libc::fcntl(fd, libc::F_GETFL);
+            libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK)
+        };
+        if res < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        Ok(Self {
+            stream,
+            fd,
+            reader: Mutex::new(reader),
+        })
+    }
+
+    /// Read all available data without blocking, return `false` if a pipe is closed.
+    fn drain(&self, chunks: &Mutex<Vec<(OutputStream, Vec<u8>)>>) -> bool {
+        let mut reader = self.reader.lock().expect("unexpected poisoned mutex");
+        let mut buf = [0; 4096];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => return false,
+                Ok(n) => chunks
+                    .lock()
+                    .expect("unexpected poisoned mutex")
+                    .push((self.stream, buf[..n].to_vec())),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return true,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return false,
+            }
+        }
+    }
+
+    /// Wait (at most [`POLL_TIMEOUT_MS`]) until a pipe has data to read.
+    fn poll(&self) {
+        let mut fd = libc::pollfd {
+            fd: self.fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: a single valid pollfd structure is passed
+        unsafe { libc::poll(&mut fd, 1, POLL_TIMEOUT_MS) };
+    }
+}
+
+/// Debugee standard streams captured by the debugger.
+pub struct IoCapture {
+    stdout: PipeWriter,
+    stderr: PipeWriter,
+    /// Read end is passed to a debugee, write end is `None` after stdin is closed.
+    stdin: Option<(PipeReader, Option<PipeWriter>)>,
+    pipes: Vec<Arc<OutputPipe>>,
+    chunks: Arc<Mutex<Vec<(OutputStream, Vec<u8>)>>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl IoCapture {
+    /// Create pipes for debugee stdout and stderr and start reading them.
+    ///
+    /// # Arguments
+    ///
+    /// * `capture_stdin`: if true, debugee stdin is redirected too, otherwise it's inherited
+    pub fn new(capture_stdin: bool) -> Result<Self, Error> {
+        let (stdout_reader, stdout) = os_pipe::pipe()?;
+        let (stderr_reader, stderr) = os_pipe::pipe()?;
+        let stdin = if capture_stdin {
+            let (reader, writer) = os_pipe::pipe()?;
+            Some((reader, Some(writer)))
+        } else {
+            None
+        };
+
+        let capture = Self {
+            stdout,
+            stderr,
+            stdin,
+            pipes: vec![
+                Arc::new(OutputPipe::new(OutputStream::Stdout, stdout_reader)?),
+                Arc::new(OutputPipe::new(OutputStream::Stderr, stderr_reader)?),
+            ],
+            chunks: Arc::default(),
+            stopped: Arc::default(),
+        };
+
+        for pipe in &capture.pipes {
+            let pipe = pipe.clone();
+            let chunks = capture.chunks.clone();
+            let stopped = capture.stopped.clone();
+            thread::spawn(move || {
+                while !stopped.load(Ordering::Relaxed) && pipe.drain(&chunks) {
+                    pipe.poll();
+                }
+            });
+        }
+
+        Ok(capture)
+    }
+
+    /// Create a debugee process template with captured standard streams.
+    ///
+    /// # Arguments
+    ///
+    /// * `program`: program name
+    /// * `args`: program arguments
+    pub fn process<ARGS: IntoIterator<Item = I>, I: Into<String>>(
+        &self,
+        program: impl Into<String>,
+        args: ARGS,
+    ) -> Result<Child<Template>, Error> {
+        let child = Child::new(
+            program,
+            args,
+            self.stdout.try_clone()?,
+            self.stderr.try_clone()?,
+        );
+        Ok(match self.stdin {
+            Some((ref reader, _)) => child.with_stdin(reader.try_clone()?),
+            None => child,
+        })
+    }
+
+    /// Return all output read since the previous call.
+    fn take_output(&self) -> Vec<(OutputStream, Vec<u8>)> {
+        for pipe in &self.pipes {
+            pipe.drain(&self.chunks);
+        }
+        std::mem::take(&mut self.chunks.lock().expect("unexpected poisoned mutex"))
+    }
+}
+
+impl Drop for IoCapture {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Debugger {
+    /// Deliver captured debugee output into hooks and oracles, then flush the output
+    /// (see [`crate::debugger::EventHook::on_output_flush`]).
+    pub(super) fn flush_output(&self) {
+        if let Some(ref capture) = self.io_capture {
+            for (stream, data) in capture.take_output() {
+                self.hooks.on_debugee_output(stream, &data);
+                for (oracle, _) in self.oracles.values().filter(|(_, ready)| *ready) {
+                    oracle.on_debugee_output(self, stream, &data);
+                }
+            }
+        }
+        self.hooks.on_output_flush();
+    }
+
+    /// Send data into debugee stdin.
+    ///
+    /// # Arguments
+    ///
+    /// * `data`: input data
+    ///
+    /// # Errors
+    ///
+    /// Return error if debugee stdin isn't captured or already closed.
+    pub fn write_stdin(&mut self, data: &[u8]) -> Result<(), Error> {
+        let writer = self
+            .io_capture
+            .as_mut()
+            .and_then(|capture| capture.stdin.as_mut())
+            .and_then(|(_, writer)| writer.as_mut())
+            .ok_or(StdinNotCaptured)?;
+        writer.write_all(data)?;
+        Ok(())
+    }
+
+    /// Close debugee stdin, debugee reads an end of file after all sent data.
+    pub fn close_stdin(&mut self) -> Result<(), Error> {
+        let writer = self
+            .io_capture
+            .as_mut()
+            .and_then(|capture| capture.stdin.as_mut())
+            .map(|(_, writer)| writer)
+            .ok_or(StdinNotCaptured)?;
+        *writer = None;
+        Ok(())
+    }
+}
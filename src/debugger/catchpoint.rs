@@ -0,0 +1,184 @@
+//! Syscall catchpoints, stop debugee at entry or exit of selected syscalls.
+//!
+//! While a catchpoint is set, debugee threads are resumed with `PTRACE_SYSCALL` (like with
+//! a syscall tracing oracle), so each of them stops at every syscall entry and exit.
+//! Stops that match a catchpoint filter are reported to the user, others are continued.
+
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::UnknownSyscall;
+use crate::debugger::{Debugger, SyscallStop};
+use crate::oracle::builtin::syscall::{syscall_name, syscall_number};
+use nix::unistd::Pid;
+use std::collections::{BTreeSet, HashMap};
+
+/// Caught syscalls.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SyscallFilter {
+    /// Any syscall.
+    #[default]
+    All,
+    /// Syscalls with specified numbers.
+    Numbers(BTreeSet<u64>),
+}
+
+impl SyscallFilter {
+    /// Create a filter from syscall names or numbers, an empty list means any syscall.
+    ///
+    /// # Arguments
+    ///
+    /// * `syscalls`: syscall names (like `write`) or numbers
+    pub fn from_names<S: AsRef<str>>(syscalls: impl IntoIterator<Item = S>) -> Result<Self, Error> {
+        let numbers = syscalls
+            .into_iter()
+            .map(|syscall| {
+                let syscall = syscall.as_ref();
+                syscall
+                    .parse::<u64>()
+                    .ok()
+                    .or_else(|| syscall_number(syscall))
+                    .ok_or_else(|| UnknownSyscall(syscall.to_string()))
+            })
+            .collect::<Result<BTreeSet<_>, _>>()?;
+
+        if numbers.is_empty() {
+            return Ok(SyscallFilter::All);
+        }
+        Ok(SyscallFilter::Numbers(numbers))
+    }
+
+    fn matches(&self, nr: u64) -> bool {
+        match self {
+            SyscallFilter::All => true,
+            SyscallFilter::Numbers(numbers) => numbers.contains(&nr),
+        }
+    }
+}
+
+/// Debugee thread stop at a caught syscall.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyscallEvent {
+    /// Thread id.
+    pub pid: Pid,
+    /// Syscall number.
+    pub nr: u64,
+    /// Syscall name, `None` if syscall number is unknown.
+    pub name: Option<&'static str>,
+    /// Syscall entry (with argument registers) or exit (with a return value).
+    pub stop: SyscallStop,
+}
+
+/// Syscall catchpoint state.
+pub(super) struct SyscallCatchpoint {
+    filter: SyscallFilter,
+    /// Numbers of syscalls that are in progress, there is no syscall number at exit stop.
+    in_progress: HashMap<Pid, u64>,
+}
+
+impl SyscallCatchpoint {
+    fn new(filter: SyscallFilter) -> Self {
+        Self {
+            filter,
+            in_progress: HashMap::new(),
+        }
+    }
+
+    /// Forget syscalls in progress (used when debugee restarts).
+    pub(super) fn reset(&mut self) {
+        self.in_progress.clear();
+    }
+
+    /// Return an event if a syscall stop matches catchpoint filter.
+    /// Exit from a syscall whose entry wasn't traced is never matched.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid`: thread id
+    /// * `stop`: syscall entry or exit
+    pub(super) fn on_syscall(&mut self, pid: Pid, stop: SyscallStop) -> Option<SyscallEvent> {
+        let nr = match stop {
+            SyscallStop::Entry { nr, .. } => {
+                self.in_progress.insert(pid, nr);
+                nr
+            }
+            SyscallStop::Exit { .. } => self.in_progress.remove(&pid)?,
+        };
+
+        self.filter.matches(nr).then(|| SyscallEvent {
+            pid,
+            nr,
+            name: syscall_name(nr),
+            stop,
+        })
+    }
+}
+
+impl Debugger {
+    /// Stop debugee at entry and exit of syscalls that match a filter.
+    /// Replaces a previous catchpoint, takes effect when debugee continues.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter`: caught syscalls
+    pub fn enable_syscall_catchpoint(&mut self, filter: SyscallFilter) {
+        self.syscall_catchpoint = Some(SyscallCatchpoint::new(filter));
+    }
+
+    /// Remove a syscall catchpoint.
+    pub fn disable_syscall_catchpoint(&mut self) {
+        self.syscall_catchpoint = None;
+    }
+
+    /// Return a filter of a syscall catchpoint, `None` if catchpoint is not set.
+    pub fn syscall_catchpoint(&self) -> Option<&SyscallFilter> {
+        self.syscall_catchpoint.as_ref().map(|catch| &catch.filter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_syscall_filter() {
+        let filter = SyscallFilter::from_names(["write", "0"]).unwrap();
+        assert_eq!(filter, SyscallFilter::Numbers(BTreeSet::from([0, 1])));
+        assert!(filter.matches(1));
+        assert!(!filter.matches(2));
+
+        assert_eq!(
+            SyscallFilter::from_names(Vec::<String>::new()).unwrap(),
+            SyscallFilter::All
+        );
+        assert!(SyscallFilter::from_names(["no_such_syscall"]).is_err());
+    }
+
+    #[test]
+    fn test_catchpoint_exit_matching() {
+        let pid = Pid::from_raw(1);
+        let mut catch = SyscallCatchpoint::new(SyscallFilter::from_names(["write"]).unwrap());
+
+        // exit without traced entry is ignored
+        let exit = SyscallStop::Exit {
+            rval: 1,
+            is_error: false,
+        };
+        assert!(catch.on_syscall(pid, exit).is_none());
+
+        let entry = SyscallStop::Entry {
+            nr: 1,
+            args: [0; 6],
+        };
+        let event = catch.on_syscall(pid, entry).unwrap();
+        assert_eq!(event.name, Some("write"));
+        let event = catch.on_syscall(pid, exit).unwrap();
+        assert_eq!(event.nr, 1);
+        assert_eq!(event.stop, exit);
+
+        let entry = SyscallStop::Entry {
+            nr: 0,
+            args: [0; 6],
+        };
+        assert!(catch.on_syscall(pid, entry).is_none());
+        assert!(catch.on_syscall(pid, exit).is_none());
+    }
+}
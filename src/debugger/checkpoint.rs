@@ -0,0 +1,268 @@
+//! Checkpoints, suspended copies of a debugee process.
+//!
+//! A checkpoint is created by a `fork` syscall injected into the thread in focus: a syscall
+//! instruction is written near the program entry point (like a trap of an injected function
+//! call), the thread executes it and registers of the thread are restored. A process copy is
+//! traced by the debugger, but never continued. When debugee restarts from a checkpoint,
+//! the copy is forked again, so a checkpoint may be used any number of times.
+//!
+//! Only the thread in focus exists in a copy. File descriptors are shared between debugee and
+//! its copies, so effects outside of debugee memory (files, sockets) are not reverted.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::debugee::tracer::TraceContext;
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::{
+    Checkpoint, CheckpointNotFound, ProcessNotStarted, Ptrace, Syscall, Waitpid,
+};
+use crate::debugger::process::TRACE_OPTIONS;
+use crate::debugger::{read_memory_by_pid, write_memory_by_pid, Debugger, ExplorationContext};
+use crate::print_warns;
+use log::warn;
+use nix::errno::Errno;
+use nix::libc;
+use nix::sys::ptrace;
+use nix::sys::signal;
+use nix::sys::signal::{SIGKILL, SIGTRAP};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+
+/// `syscall` instruction.
+const SYSCALL_INSN: [u8; 2] = [0x0f, 0x05];
+
+/// Information about a checkpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointInfo {
+    /// Checkpoint number.
+    pub id: u32,
+    /// Pid of a suspended debugee copy.
+    pub pid: Pid,
+    /// Program counter at which debugee is restarted from the checkpoint.
+    pub pc: RelocatedAddress,
+}
+
+/// Existed checkpoints.
+#[derive(Default)]
+pub(super) struct Checkpoints {
+    last_id: u32,
+    list: Vec<CheckpointInfo>,
+}
+
+impl Checkpoints {
+    /// Kill all debugee copies.
+    pub(super) fn kill_all(&mut self) {
+        for checkpoint in self.list.drain(..) {
+            if let Err(e) = kill_copy(checkpoint.pid) {
+                warn!(target: "debugger", "kill checkpoint {}: {e}", checkpoint.id);
+            }
+        }
+    }
+}
+
+/// Kill a suspended debugee copy and wait until it exits.
+fn kill_copy(pid: Pid) -> Result<(), Error> {
+    signal::kill(pid, SIGKILL).map_err(|e| Syscall("kill", e))?;
+    loop {
+        match waitpid(pid, Some(WaitPidFlag::__WALL)) {
+            Ok(WaitStatus::Exited(..) | WaitStatus::Signaled(..)) | Err(Errno::ECHILD) => {
+                return Ok(())
+            }
+            // exit stop
+            Ok(_) => _ = ptrace::cont(pid, None),
+            Err(e) => return Err(Waitpid(e)),
+        }
+    }
+}
+
+/// Execute a `fork` syscall in a stopped thread and return pid of a stopped child process.
+/// Registers of the thread and memory at `syscall_addr` are restored in both processes.
+/// Signals that arrive while `fork` is executed are discarded.
+///
+/// # Arguments
+///
+/// * `pid`: thread id
+/// * `syscall_addr`: address where a syscall instruction is temporarily placed
+fn fork_process(pid: Pid, syscall_addr: RelocatedAddress) -> Result<Pid, Error> {
+    let addr = syscall_addr.as_usize();
+    let saved_regs = ptrace::getregs(pid).map_err(Ptrace)?;
+    let saved_code = read_memory_by_pid(pid, addr, SYSCALL_INSN.len()).map_err(Ptrace)?;
+
+    let mut regs = saved_regs;
+    regs.rip = addr as u64;
+    regs.rax = libc::SYS_fork as u64;
+    // prevent a syscall restart if a thread stopped in a syscall
+    regs.orig_rax = u64::MAX;
+
+    write_memory_by_pid(pid, addr, &SYSCALL_INSN).map_err(Ptrace)?;
+    ptrace::setregs(pid, regs).map_err(Ptrace)?;
+
+    // fork events are traced by default (see `TRACE_OPTIONS`)
+    let forked = step_over_fork(pid);
+
+    write_memory_by_pid(pid, addr, &saved_code).map_err(Ptrace)?;
+    ptrace::setregs(pid, saved_regs).map_err(Ptrace)?;
+    let child = forked?;
+
+    // child process is a copy of a parent, so it must be restored too
+    ptrace::setoptions(child, TRACE_OPTIONS).map_err(Ptrace)?;
+    write_memory_by_pid(child, addr, &saved_code).map_err(Ptrace)?;
+    ptrace::setregs(child, saved_regs).map_err(Ptrace)?;
+    Ok(child)
+}
+
+/// Step over a `fork` syscall instruction, return pid of a child process.
+fn step_over_fork(pid: Pid) -> Result<Pid, Error> {
+    let mut child = None;
+    ptrace::step(pid, None).map_err(Ptrace)?;
+    loop {
+        match waitpid(pid, Some(WaitPidFlag::__WALL)).map_err(Waitpid)? {
+            WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_FORK) => {
+                let child_pid = ptrace::getevent(pid).map_err(Ptrace)?;
+                child = Some(Pid::from_raw(child_pid as libc::pid_t));
+            }
+            WaitStatus::Stopped(_, SIGTRAP) => break,
+            WaitStatus::Stopped(_, signal) => {
+                warn!(target: "debugger", "signal {signal} discarded while debugee is forked");
+            }
+            WaitStatus::Exited(_, code) => return Err(Error::ProcessExit(code)),
+            WaitStatus::Signaled(_, signal, _) => {
+                return Err(Checkpoint(format!("debugee killed by {signal}")))
+            }
+            _ => {}
+        }
+        ptrace::step(pid, None).map_err(Ptrace)?;
+    }
+
+    let Some(child) = child else {
+        let errno = Errno::from_i32(-(ptrace::getregs(pid).map_err(Ptrace)?.rax as i64) as i32);
+        return Err(Checkpoint(format!("fork fail: {errno}")));
+    };
+
+    // new process starts with a stop
+    match waitpid(child, Some(WaitPidFlag::__WALL)).map_err(Waitpid)? {
+        WaitStatus::Stopped(..) | WaitStatus::PtraceEvent(..) => Ok(child),
+        status => Err(Checkpoint(format!("unexpected child status: {status:?}"))),
+    }
+}
+
+impl Debugger {
+    /// Return an address where a syscall instruction for a `fork` is placed.
+    /// `_start` function never returns, so its code is used, like for a trap of a function call.
+    fn fork_addr(&self) -> Result<RelocatedAddress, Error> {
+        let program = self.debugee.program_debug_info()?;
+        Ok(self
+            .debugee
+            .entry_point()
+            .relocate_to_segment(&self.debugee, program)?
+            .offset(1))
+    }
+
+    /// Create a checkpoint: a suspended copy of a stopped debugee. Debugee may be restarted
+    /// from the checkpoint later with [`Debugger::restart`].
+    /// Only the thread in focus is copied.
+    pub fn checkpoint(&mut self) -> Result<CheckpointInfo, Error> {
+        if !self.debugee.is_in_progress() {
+            return Err(ProcessNotStarted);
+        }
+        if self.process.core().is_some() {
+            return Err(Error::PostMortem);
+        }
+
+        let location = self.exploration_ctx().location();
+        let fork_addr = self.fork_addr()?;
+        let copy = fork_process(location.pid, fork_addr)?;
+
+        // copy memory must contain the original code, breakpoints are enabled at restart
+        for brkpt in self.breakpoints.active_breakpoints() {
+            if let Err(e) =
+                write_memory_by_pid(copy, brkpt.addr.as_usize(), &[brkpt.saved_data.get()])
+            {
+                _ = kill_copy(copy);
+                return Err(Ptrace(e));
+            }
+        }
+
+        self.checkpoints.last_id += 1;
+        let info = CheckpointInfo {
+            id: self.checkpoints.last_id,
+            pid: copy,
+            pc: location.pc,
+        };
+        self.checkpoints.list.push(info.clone());
+        Ok(info)
+    }
+
+    /// Return existed checkpoints.
+    pub fn checkpoints(&self) -> &[CheckpointInfo] {
+        &self.checkpoints.list
+    }
+
+    /// Remove a checkpoint and kill a debugee copy.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: checkpoint number
+    pub fn delete_checkpoint(&mut self, id: u32) -> Result<(), Error> {
+        let idx = self
+            .checkpoints
+            .list
+            .iter()
+            .position(|checkpoint| checkpoint.id == id)
+            .ok_or(CheckpointNotFound(id))?;
+        let checkpoint = self.checkpoints.list.remove(idx);
+        kill_copy(checkpoint.pid)
+    }
+
+    /// Restart debugee from a checkpoint. Current debugee process is killed and debugger
+    /// switches to a new copy of a checkpoint process, checkpoint itself is kept and may be used
+    /// again. Watchpoints and recorded states are dropped, breakpoints are kept.
+    ///
+    /// **! change exploration context**
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: checkpoint number
+    pub fn restart(&mut self, id: u32) -> Result<(), Error> {
+        let checkpoint_pid = self
+            .checkpoints
+            .list
+            .iter()
+            .find(|checkpoint| checkpoint.id == id)
+            .map(|checkpoint| checkpoint.pid)
+            .ok_or(CheckpointNotFound(id))?;
+        let copy = fork_process(checkpoint_pid, self.fork_addr()?)?;
+
+        let in_progress = self.debugee.is_in_progress();
+        if in_progress {
+            let proc_pid = self.process.pid();
+            signal::kill(proc_pid, SIGKILL).map_err(|e| Syscall("kill", e))?;
+            self.debugee.tracer_mut().set_trace_syscalls(false);
+            _ = self.debugee.tracer_mut().resume(TraceContext::new(&[]));
+        }
+
+        self.process = self.process.forked(copy)?;
+        self.watchpoints.clear();
+        self.stop_recording();
+        self.pending_step_over.clear();
+        if let Some(catch) = self.syscall_catchpoint.as_mut() {
+            catch.reset();
+        }
+
+        let new_debugee = self.debugee.fork(copy)?;
+        self.debugee = new_debugee;
+        self.debugee.tracer_mut().set_non_stop(self.non_stop);
+        if self.non_stop {
+            self.debugee.tracee_ctl_mut().park(copy);
+        }
+        self.breakpoints.move_to_process(copy, &[copy])?;
+        if !in_progress {
+            // breakpoints are disabled when debugee exits
+            print_warns!(self.breakpoints.enable_all_breakpoints(&self.debugee));
+        }
+
+        self.hooks.on_process_install(copy, None);
+        self.expl_context = ExplorationContext::new_non_running(copy);
+        self.expl_ctx_switch_thread(copy)?;
+        self.execute_on_step_hook()
+    }
+}
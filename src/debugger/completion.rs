@@ -0,0 +1,78 @@
+//! Completion providers.
+//!
+//! Candidates for a user input are taken from debug information (function and file names)
+//! and from a current debugee state (variables in scope), so a ui can offer context-aware
+//! completion instead of completion of command names only.
+
+use crate::debugger::error::Error;
+use crate::debugger::register::Register;
+use crate::debugger::variable::select::{VariableSelector, DQE};
+use crate::debugger::Debugger;
+use strum::IntoEnumIterator;
+
+impl Debugger {
+    /// Return full names (with namespace) of functions from debug information,
+    /// a function name or its full name must start with a prefix. Names are sorted.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix`: beginning of a function name, like `sum` or `calc::su`
+    pub fn complete_function(&self, prefix: &str) -> Result<Vec<String>, Error> {
+        let regex = format!("(^|::){}", regex::escape(prefix));
+        let mut names: Vec<_> = self
+            .find_functions_info(&regex)?
+            .into_iter()
+            .map(|func| func.name)
+            .collect();
+        names.dedup();
+        Ok(names)
+    }
+
+    /// Return names of source files (from line tables) that start with a prefix, sorted.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix`: beginning of a file name (without directories)
+    pub fn complete_file(&self, prefix: &str) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .known_files()
+            .filter_map(|path| path.file_name()?.to_str())
+            .filter(|name| name.starts_with(prefix))
+            .map(ToString::to_string)
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Return names of arguments and variables visible at the current place
+    /// (in a selected frame) that start with a prefix, sorted.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix`: beginning of a variable name
+    ///
+    /// # Errors
+    ///
+    /// Return error if debugee is not started.
+    pub fn complete_variable(&self, prefix: &str) -> Result<Vec<String>, Error> {
+        let mut names = self.read_argument_names(DQE::Variable(VariableSelector::Any))?;
+        names.extend(self.read_variable_names(DQE::Variable(VariableSelector::Any))?);
+        names.retain(|name| name.starts_with(prefix));
+        names.sort_unstable();
+        names.dedup();
+        Ok(names)
+    }
+
+    /// Return names of registers that start with a prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix`: beginning of a register name
+    pub fn complete_register(&self, prefix: &str) -> Vec<String> {
+        Register::iter()
+            .map(|register| register.to_string())
+            .filter(|name| name.starts_with(prefix))
+            .collect()
+    }
+}
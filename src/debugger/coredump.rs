@@ -0,0 +1,421 @@
+//! Post-mortem debugging with ELF core files.
+//!
+//! Core file contains memory segments (`PT_LOAD`), registers of each thread (`NT_PRSTATUS` notes)
+//! and a list of mapped files (`NT_FILE` note). File-backed memory that isn't dumped into
+//! a core (like a program code) is read from mapped files.
+//!
+//! Core dump is used as a read-only [`TargetBackend`] for its threads, see [`CoreDump::attach`].
+//! Debugger for a dumped process is built from [`Child::from_core`], it has no tracer,
+//! so debugee execution commands return [`Error::PostMortem`].
+//!
+//! [`Child::from_core`]: crate::debugger::process::Child::from_core
+//! [`Error::PostMortem`]: crate::debugger::Error::PostMortem
+
+use crate::debugger::memory;
+use crate::debugger::memory::TargetBackend;
+use memmap2::Mmap;
+use nix::libc::user_regs_struct;
+use nix::unistd::Pid;
+use object::elf::{FileHeader64, ET_CORE, NT_FILE, NT_PRSTATUS, PT_LOAD};
+use object::read::elf::{FileHeader, ProgramHeader};
+use object::Endianness;
+use std::fs::File;
+use std::mem;
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CoreDumpError {
+    #[error("read core file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("parse core file: {0}")]
+    Parse(#[from] object::read::Error),
+    #[error("not a core file")]
+    NotCore,
+    #[error("core file contains no threads")]
+    NoThreads,
+}
+
+/// Offset of `pr_pid` field in `elf_prstatus` structure.
+const PRSTATUS_PID_OFFSET: usize = 32;
+/// Offset of `pr_cursig` field in `elf_prstatus` structure.
+const PRSTATUS_CURSIG_OFFSET: usize = 12;
+/// Offset of `pr_reg` field in `elf_prstatus` structure.
+const PRSTATUS_REGS_OFFSET: usize = 112;
+
+/// Thread of a dumped process.
+#[derive(Clone)]
+pub struct CoreThread {
+    pub tid: Pid,
+    /// Signal that caused a dump (or 0 for other threads).
+    pub signal: i32,
+    pub regs: user_regs_struct,
+}
+
+/// File mapped into a dumped process address space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileMapping {
+    pub start: usize,
+    pub end: usize,
+    /// Offset in a file.
+    pub offset: u64,
+    pub path: PathBuf,
+}
+
+/// Memory segment of a core file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    vaddr: usize,
+    memsz: usize,
+    offset: usize,
+    filesz: usize,
+}
+
+/// Segments, threads and mappings parsed from a core file.
+struct CoreLayout {
+    segments: Vec<Segment>,
+    threads: Vec<CoreThread>,
+    mappings: Vec<FileMapping>,
+}
+
+impl CoreLayout {
+    fn parse(data: &[u8]) -> Result<Self, CoreDumpError> {
+        let header = FileHeader64::<Endianness>::parse(data)?;
+        let endian = header.endian()?;
+        if header.e_type(endian) != ET_CORE {
+            return Err(CoreDumpError::NotCore);
+        }
+
+        let mut layout = CoreLayout {
+            segments: vec![],
+            threads: vec![],
+            mappings: vec![],
+        };
+        for phdr in header.program_headers(endian, data)? {
+            if phdr.p_type(endian) == PT_LOAD {
+                layout.segments.push(Segment {
+                    vaddr: phdr.p_vaddr(endian) as usize,
+                    memsz: phdr.p_memsz(endian) as usize,
+                    offset: phdr.p_offset(endian) as usize,
+                    filesz: phdr.p_filesz(endian) as usize,
+                });
+                continue;
+            }
+
+            let Some(mut notes) = phdr.notes(endian, data)? else {
+                continue;
+            };
+            while let Some(note) = notes.next()? {
+                if note.name() != b"CORE" {
+                    continue;
+                }
+                match note.n_type(endian) {
+                    NT_PRSTATUS => layout.threads.extend(parse_prstatus(note.desc())),
+                    NT_FILE => layout.mappings.extend(parse_file_note(note.desc())),
+                    _ => {}
+                }
+            }
+        }
+
+        if layout.threads.is_empty() {
+            return Err(CoreDumpError::NoThreads);
+        }
+        layout.segments.sort_by_key(|segment| segment.vaddr);
+        Ok(layout)
+    }
+
+    /// Read memory of a dumped process.
+    ///
+    /// # Arguments
+    ///
+    /// * `data`: core file content
+    /// * `addr`: start address
+    /// * `read_n`: read byte count
+    fn read(&self, data: &[u8], addr: usize, read_n: usize) -> Result<Vec<u8>, nix::Error> {
+        let mut result = Vec::with_capacity(read_n);
+        let mut addr = addr;
+        while result.len() < read_n {
+            let segment = self
+                .segments
+                .iter()
+                .find(|s| s.vaddr <= addr && addr < s.vaddr + s.memsz)
+                .ok_or(nix::Error::EFAULT)?;
+            let in_segment = addr - segment.vaddr;
+            let len = (read_n - result.len()).min(segment.memsz - in_segment);
+
+            // part of a segment is dumped into a core file
+            let dumped = segment.filesz.saturating_sub(in_segment).min(len);
+            if dumped > 0 {
+                let start = segment.offset + in_segment;
+                let bytes = data.get(start..start + dumped).ok_or(nix::Error::EIO)?;
+                result.extend_from_slice(bytes);
+            }
+
+            // rest of a segment is a not dumped file content or zeroed memory
+            let rest = len - dumped;
+            if rest > 0 {
+                let rest_addr = addr + dumped;
+                match self.mapping(rest_addr) {
+                    Some(mapping) => {
+                        let mut buf = vec![0; rest];
+                        File::open(&mapping.path)
+                            .and_then(|file| {
+                                let offset = mapping.offset + (rest_addr - mapping.start) as u64;
+                                file.read_exact_at(&mut buf, offset)
+                            })
+                            .map_err(|_| nix::Error::EIO)?;
+                        result.extend(buf);
+                    }
+                    None => result.resize(result.len() + rest, 0),
+                }
+            }
+
+            addr += len;
+        }
+        Ok(result)
+    }
+
+    fn mapping(&self, addr: usize) -> Option<&FileMapping> {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.start <= addr && addr < mapping.end)
+    }
+}
+
+/// Parse a `NT_PRSTATUS` note (x86_64 `elf_prstatus` structure).
+fn parse_prstatus(desc: &[u8]) -> Option<CoreThread> {
+    let regs =
+        desc.get(PRSTATUS_REGS_OFFSET..PRSTATUS_REGS_OFFSET + mem::size_of::<user_regs_struct>())?;
+    let tid = i32::from_ne_bytes(
+        desc[PRSTATUS_PID_OFFSET..PRSTATUS_PID_OFFSET + 4]
+            .try_into()
+            .ok()?,
+    );
+    let signal = i16::from_ne_bytes(
+        desc[PRSTATUS_CURSIG_OFFSET..PRSTATUS_CURSIG_OFFSET + 2]
+            .try_into()
+            .ok()?,
+    );
+    // SAFETY: `pr_reg` has the same layout as `user_regs_struct`, size is checked above
+    let regs = unsafe { (regs.as_ptr() as *const user_regs_struct).read_unaligned() };
+
+    Some(CoreThread {
+        tid: Pid::from_raw(tid),
+        signal: signal as i32,
+        regs,
+    })
+}
+
+/// Parse a `NT_FILE` note: count and page size, then (start, end, page offset)
+/// for each mapping, then null-terminated file names.
+fn parse_file_note(desc: &[u8]) -> Vec<FileMapping> {
+    let word = |idx: usize| -> Option<u64> {
+        let bytes = desc.get(idx * 8..idx * 8 + 8)?;
+        Some(u64::from_ne_bytes(bytes.try_into().ok()?))
+    };
+    let (Some(count), Some(page_size)) = (word(0), word(1)) else {
+        return vec![];
+    };
+    let count = count as usize;
+
+    let names_offset = (2 + count * 3) * 8;
+    let Some(names) = desc.get(names_offset..) else {
+        return vec![];
+    };
+
+    names
+        .split(|&b| b == 0)
+        .take(count)
+        .enumerate()
+        .filter_map(|(i, name)| {
+            Some(FileMapping {
+                start: word(2 + i * 3)? as usize,
+                end: word(2 + i * 3 + 1)? as usize,
+                offset: word(2 + i * 3 + 2)? * page_size,
+                path: PathBuf::from(String::from_utf8_lossy(name).as_ref()),
+            })
+        })
+        .collect()
+}
+
+/// ELF core file of a crashed (or dumped with `gcore`) process.
+pub struct CoreDump {
+    data: Mmap,
+    layout: CoreLayout,
+}
+
+impl CoreDump {
+    /// Open and parse a core file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: path to a core file
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, CoreDumpError> {
+        let file = File::open(path)?;
+        // SAFETY: core file is not expected to change while debugging
+        let data = unsafe { Mmap::map(&file) }?;
+        let layout = CoreLayout::parse(&data)?;
+        Ok(Self { data, layout })
+    }
+
+    /// Return dumped threads, the first one is a thread that receives a signal.
+    pub fn threads(&self) -> &[CoreThread] {
+        &self.layout.threads
+    }
+
+    /// Return files mapped into a dumped process.
+    pub fn mappings(&self) -> &[FileMapping] {
+        &self.layout.mappings
+    }
+
+    /// Use core dump as a memory and registers backend for all its threads. After this, debugger
+    /// functions that take a thread id read a core image instead of using ptrace.
+    pub fn attach(self) -> Arc<Self> {
+        let core = Arc::new(self);
+        for thread in core.threads() {
+            memory::attach_backend(thread.tid, core.clone());
+        }
+        core
+    }
+
+    /// Stop using core dump as a memory and registers backend.
+    pub fn detach(&self) {
+        for thread in self.threads() {
+            memory::detach_backend(thread.tid);
+        }
+    }
+}
+
+impl TargetBackend for CoreDump {
+    fn read_memory(&self, addr: usize, read_n: usize) -> Result<Vec<u8>, nix::Error> {
+        self.layout.read(&self.data, addr, read_n)
+    }
+
+    fn registers(&self, tid: Pid) -> Option<user_regs_struct> {
+        self.threads()
+            .iter()
+            .find(|thread| thread.tid == tid)
+            .map(|thread| thread.regs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ELF_HEADER_SIZE: usize = 64;
+    const PHDR_SIZE: usize = 56;
+
+    fn push_note(buf: &mut Vec<u8>, n_type: u32, desc: &[u8]) {
+        buf.extend(5_u32.to_le_bytes());
+        buf.extend((desc.len() as u32).to_le_bytes());
+        buf.extend(n_type.to_le_bytes());
+        buf.extend(b"CORE\0\0\0\0");
+        buf.extend(desc);
+        buf.resize(buf.len().next_multiple_of(4), 0);
+    }
+
+    fn push_phdr(
+        buf: &mut Vec<u8>,
+        p_type: u32,
+        offset: usize,
+        vaddr: usize,
+        filesz: usize,
+        memsz: usize,
+    ) {
+        buf.extend(p_type.to_le_bytes());
+        buf.extend(0_u32.to_le_bytes());
+        for value in [offset, vaddr, vaddr, filesz, memsz, 4] {
+            buf.extend((value as u64).to_le_bytes());
+        }
+    }
+
+    /// Build a core file with a single thread, a mapping and a data segment.
+    fn make_core() -> Vec<u8> {
+        let mut prstatus = vec![0; 336];
+        prstatus[PRSTATUS_CURSIG_OFFSET..PRSTATUS_CURSIG_OFFSET + 2]
+            .copy_from_slice(&11_i16.to_le_bytes());
+        prstatus[PRSTATUS_PID_OFFSET..PRSTATUS_PID_OFFSET + 4]
+            .copy_from_slice(&42_i32.to_le_bytes());
+        // rip is 17th register
+        let rip_offset = PRSTATUS_REGS_OFFSET + 16 * 8;
+        prstatus[rip_offset..rip_offset + 8].copy_from_slice(&0x401000_u64.to_le_bytes());
+
+        let mut file_note = vec![];
+        for value in [1_u64, 0x1000, 0x400000, 0x401000, 2] {
+            file_note.extend(value.to_le_bytes());
+        }
+        file_note.extend(b"/bin/true\0");
+
+        let mut notes = vec![];
+        push_note(&mut notes, NT_PRSTATUS, &prstatus);
+        push_note(&mut notes, NT_FILE, &file_note);
+
+        let notes_offset = ELF_HEADER_SIZE + 2 * PHDR_SIZE;
+        let data_offset = notes_offset + notes.len();
+
+        let mut core = vec![0x7f, b'E', b'L', b'F', 2, 1, 1];
+        core.resize(16, 0);
+        core.extend(ET_CORE.to_le_bytes());
+        core.extend(62_u16.to_le_bytes());
+        core.extend(1_u32.to_le_bytes());
+        core.extend(0_u64.to_le_bytes());
+        core.extend((ELF_HEADER_SIZE as u64).to_le_bytes());
+        core.extend(0_u64.to_le_bytes());
+        core.extend(0_u32.to_le_bytes());
+        for value in [ELF_HEADER_SIZE, PHDR_SIZE, 2, 0, 0, 0] {
+            core.extend((value as u16).to_le_bytes());
+        }
+        push_phdr(
+            &mut core,
+            object::elf::PT_NOTE,
+            notes_offset,
+            0,
+            notes.len(),
+            0,
+        );
+        push_phdr(&mut core, PT_LOAD, data_offset, 0x1000, 8, 16);
+        core.extend(notes);
+        core.extend(0x1122334455667788_u64.to_le_bytes());
+        core
+    }
+
+    #[test]
+    fn test_parse_core() {
+        let core = make_core();
+        let layout = CoreLayout::parse(&core).unwrap();
+
+        assert_eq!(layout.threads.len(), 1);
+        assert_eq!(layout.threads[0].tid, Pid::from_raw(42));
+        assert_eq!(layout.threads[0].signal, 11);
+        assert_eq!(layout.threads[0].regs.rip, 0x401000);
+
+        assert_eq!(
+            layout.mappings,
+            vec![FileMapping {
+                start: 0x400000,
+                end: 0x401000,
+                offset: 0x2000,
+                path: PathBuf::from("/bin/true"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_read_core_memory() {
+        let core = make_core();
+        let layout = CoreLayout::parse(&core).unwrap();
+
+        assert_eq!(
+            layout.read(&core, 0x1000, 8).unwrap(),
+            0x1122334455667788_u64.to_le_bytes()
+        );
+        // not dumped part of a segment is zeroed
+        assert_eq!(
+            layout.read(&core, 0x1006, 4).unwrap(),
+            vec![0x22, 0x11, 0, 0]
+        );
+        assert_eq!(layout.read(&core, 0x1010, 1), Err(nix::Error::EFAULT));
+    }
+}
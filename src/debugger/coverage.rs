@@ -0,0 +1,242 @@
+//! Line coverage tracking.
+//!
+//! Ephemeral breakpoints are planted on every statement of selected files or functions.
+//! Breakpoint is removed after the first hit, so covered code runs at full speed.
+
+use crate::debugger::address::{Address, GlobalAddress};
+use crate::debugger::breakpoint::{Breakpoint, BrkptType};
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::NoSuitablePlace;
+use crate::debugger::Debugger;
+use crate::weak_error;
+use log::warn;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Code selected for coverage tracking.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CoverageTarget {
+    /// All statements of files relevant to template (full path or part of a file path).
+    File(String),
+    /// All statements of functions relevant to template (full function path or part of this path).
+    Function(String),
+}
+
+/// Coverage of a single source file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileCoverage {
+    pub file: PathBuf,
+    /// Tracked lines (lines contains at least one statement).
+    pub lines: BTreeSet<u64>,
+    /// Executed lines.
+    pub hits: BTreeSet<u64>,
+}
+
+impl FileCoverage {
+    /// Return tracked lines that have never been executed.
+    pub fn untouched(&self) -> impl Iterator<Item = u64> + '_ {
+        self.lines.difference(&self.hits).copied()
+    }
+
+    /// Return percent of executed lines.
+    pub fn percent(&self) -> f64 {
+        if self.lines.is_empty() {
+            return 100.0;
+        }
+        self.hits.len() as f64 * 100.0 / self.lines.len() as f64
+    }
+}
+
+/// Statement tracked by a coverage breakpoint.
+#[derive(Debug, Clone, PartialEq)]
+struct Statement {
+    debug_info_file: PathBuf,
+    address: GlobalAddress,
+    file: Rc<Path>,
+    line: u64,
+}
+
+/// Coverage tracking state, survives debugee restarts.
+#[derive(Default)]
+pub(super) struct Coverage {
+    statements: Vec<Statement>,
+    files: BTreeMap<PathBuf, FileCoverage>,
+}
+
+impl Coverage {
+    fn is_hit(&self, file: &Path, line: u64) -> bool {
+        self.files
+            .get(file)
+            .map(|coverage| coverage.hits.contains(&line))
+            .unwrap_or_default()
+    }
+}
+
+impl Debugger {
+    /// Start coverage tracking of a selected code. Breakpoints are planted immediately
+    /// if debugee is running, or at debugee start otherwise.
+    /// Return number of new tracked lines.
+    /// Statements that already have another breakpoint are not tracked.
+    ///
+    /// # Arguments
+    ///
+    /// * `target`: code selected for coverage tracking
+    ///
+    /// # Errors
+    ///
+    /// Return [`Error::NoSuitablePlace`] if there are no statements for the target.
+    pub fn track_coverage(&mut self, target: CoverageTarget) -> Result<usize, Error> {
+        let mut statements = vec![];
+        for dwarf in self.debugee.debug_info_all() {
+            if !dwarf.has_debug_info() {
+                continue;
+            }
+            let places = match target {
+                CoverageTarget::File(ref tpl) => dwarf.search_stmt_places_for_file_tpl(tpl),
+                CoverageTarget::Function(ref tpl) => {
+                    if dwarf.tpl_in_pub_names(tpl) == Some(false) {
+                        continue;
+                    }
+                    dwarf.search_stmt_places_for_fn_tpl(tpl)?
+                }
+            };
+            statements.extend(places.into_iter().map(|place| Statement {
+                debug_info_file: dwarf.pathname().to_path_buf(),
+                address: place.address,
+                file: Rc::from(place.file.as_path()),
+                line: place.line_number,
+            }));
+        }
+        if statements.is_empty() {
+            return Err(NoSuitablePlace);
+        }
+
+        let mut new_lines = 0;
+        let mut new_statements = vec![];
+        for stmt in statements {
+            if self.coverage.statements.contains(&stmt) || new_statements.contains(&stmt) {
+                continue;
+            }
+            let file_coverage = self
+                .coverage
+                .files
+                .entry(stmt.file.to_path_buf())
+                .or_insert_with(|| FileCoverage {
+                    file: stmt.file.to_path_buf(),
+                    ..FileCoverage::default()
+                });
+            if file_coverage.lines.insert(stmt.line) {
+                new_lines += 1;
+            }
+            new_statements.push(stmt);
+        }
+
+        if self.debugee.is_in_progress() {
+            for stmt in &new_statements {
+                weak_error!(self.plant_coverage_breakpoint(stmt));
+            }
+        }
+        self.coverage.statements.extend(new_statements);
+
+        Ok(new_lines)
+    }
+
+    /// Plant breakpoints at all statements that have not been executed yet.
+    /// Called when debugee starts.
+    pub(super) fn plant_coverage_breakpoints(&mut self) {
+        let statements = self
+            .coverage
+            .statements
+            .iter()
+            .filter(|stmt| !self.coverage.is_hit(&stmt.file, stmt.line))
+            .cloned()
+            .collect::<Vec<_>>();
+        for stmt in statements {
+            weak_error!(self.plant_coverage_breakpoint(&stmt));
+        }
+    }
+
+    fn plant_coverage_breakpoint(&mut self, stmt: &Statement) -> Result<(), Error> {
+        let dwarf = self.debugee.debug_info_from_file(&stmt.debug_info_file)?;
+        let addr = stmt.address.relocate_to_segment(&self.debugee, dwarf)?;
+        if self.breakpoints.get_enabled(addr).is_some() {
+            return Ok(());
+        }
+
+        let file = stmt.file.clone();
+        let line = stmt.line;
+        let callback = move |debugger: &mut Debugger| {
+            if let Some(coverage) = debugger.coverage.files.get_mut(&*file) {
+                coverage.hits.insert(line);
+            }
+            // breakpoint is no longer needed, debugee continues without step over it
+            let pc = debugger.exploration_ctx().location().pc;
+            let threads = debugger.thread_pids();
+            if let Err(e) = debugger
+                .breakpoints
+                .remove_by_addr(Address::Relocated(pc), &threads)
+            {
+                warn!(target: "debugger", "remove coverage breakpoint: {e:#}");
+            }
+        };
+
+        let threads = self.thread_pids();
+        self.breakpoints.add_and_enable(
+            Breakpoint::new_transparent(
+                dwarf.pathname(),
+                addr,
+                self.process.pid(),
+                Rc::new(callback),
+            ),
+            &threads,
+        )?;
+        Ok(())
+    }
+
+    /// Return coverage of all tracked files.
+    pub fn coverage(&self) -> Vec<FileCoverage> {
+        self.coverage.files.values().cloned().collect()
+    }
+
+    /// Stop coverage tracking, remove all coverage breakpoints and forget collected data.
+    pub fn clear_coverage(&mut self) -> Result<(), Error> {
+        let coverage = std::mem::take(&mut self.coverage);
+        if !self.debugee.is_in_progress() {
+            return Ok(());
+        }
+
+        let threads = self.thread_pids();
+        for stmt in coverage.statements {
+            let dwarf = self.debugee.debug_info_from_file(&stmt.debug_info_file)?;
+            let addr = stmt.address.relocate_to_segment(&self.debugee, dwarf)?;
+            // do not touch breakpoints of other types at the same address
+            if self
+                .breakpoints
+                .get_enabled(addr)
+                .is_some_and(|brkpt| matches!(brkpt.r#type(), BrkptType::Transparent(_)))
+            {
+                self.breakpoints
+                    .remove_by_addr(Address::Relocated(addr), &threads)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_file_coverage() {
+        let coverage = FileCoverage {
+            file: PathBuf::from("main.rs"),
+            lines: BTreeSet::from([1, 2, 3, 5]),
+            hits: BTreeSet::from([2, 5]),
+        };
+        assert_eq!(coverage.untouched().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(coverage.percent(), 50.0);
+        assert_eq!(FileCoverage::default().percent(), 100.0);
+    }
+}
@@ -61,28 +61,54 @@ impl Disassembler {
             .end_instruction()?
             .relocate_to_segment(debugee, debug_info)?;
 
-        let cache_key = (fn_reloc_pc_start, fn_reloc_pc_end);
+        self.disasm_range(
+            debugee,
+            fn_glob_pc_start,
+            fn_reloc_pc_start,
+            fn_reloc_pc_end,
+            breakpoints,
+        )
+    }
+
+    /// Return disassembled representation of a memory range.
+    ///
+    /// # Arguments
+    ///
+    /// * `debugee`: debugee instance
+    /// * `glob_start`: global address of the range start
+    /// * `start`: range start (relocated address)
+    /// * `end`: range end (relocated address, exclusive)
+    /// * `breakpoints`: list of active breakpoints
+    pub fn disasm_range(
+        &self,
+        debugee: &Debugee,
+        glob_start: GlobalAddress,
+        start: RelocatedAddress,
+        end: RelocatedAddress,
+        breakpoints: &[&Breakpoint],
+    ) -> Result<Vec<Instruction>, Error> {
+        let cache_key = (start, end);
         let mut cache = self.cache.borrow_mut();
         let instructions = cache.try_get_or_insert(cache_key, || -> Result<_, Error> {
-            let text_len = usize::from(fn_reloc_pc_end) - usize::from(fn_reloc_pc_start);
+            let text_len = usize::from(end) - usize::from(start);
             let mut text = debugger::read_memory_by_pid(
                 debugee.tracee_ctl().proc_pid(),
-                fn_reloc_pc_start.into(),
+                start.into(),
                 text_len,
             )
             .map_err(Error::Ptrace)?;
 
             breakpoints
                 .iter()
-                .filter(|brkpt| brkpt.addr >= fn_reloc_pc_start && brkpt.addr <= fn_reloc_pc_end)
+                .filter(|brkpt| brkpt.addr >= start && brkpt.addr < end)
                 .for_each(|brkpt| {
-                    let byte_idx = usize::from(brkpt.addr) - usize::from(fn_reloc_pc_start);
+                    let byte_idx = usize::from(brkpt.addr) - usize::from(start);
                     text[byte_idx] = brkpt.saved_data.get();
                 });
 
             let instructions = self
                 .cs
-                .disasm_all(&text, fn_glob_pc_start.into())
+                .disasm_all(&text, glob_start.into())
                 .map_err(Error::DisAsm)?
                 .iter()
                 .map(|i| Instruction {
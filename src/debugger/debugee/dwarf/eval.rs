@@ -72,8 +72,10 @@ impl<'a> RequirementsResolver<'a> {
         }
     }
 
-    fn relocation_addr(&self, ctx: &ExplorationContext) -> Result<usize, Error> {
-        self.debugee.mapping_offset_for_pc(ctx.location().pc)
+    fn relocate(&self, ctx: &ExplorationContext, addr: u64) -> Result<u64, Error> {
+        let addr =
+            GlobalAddress::from(addr).relocate_to_segment_by_pc(self.debugee, ctx.location().pc)?;
+        Ok(addr.as_u64())
     }
 
     fn resolve_tls(&self, pid: Pid, offset: u64) -> Result<RelocatedAddress, Error> {
@@ -99,7 +101,7 @@ impl<'a> RequirementsResolver<'a> {
             .ok_or(FunctionNotFound(current_loc.global_pc))?;
         let entry_pc: GlobalAddress = current_fn.start_instruction()?;
 
-        let backtrace = self.debugee.unwind(ctx.pid_on_focus())?;
+        let backtrace = self.debugee.unwind(ctx.pid_on_focus(), None)?;
         let entry_pc_rel = entry_pc.relocate_to_segment_by_pc(self.debugee, ctx.location().pc)?;
         backtrace
             .iter()
@@ -291,8 +293,8 @@ impl<'a> ExpressionEvaluator<'a> {
                     result = eval.resume_with_memory(value)?;
                 }
                 EvaluationResult::RequiresRelocatedAddress(addr) => {
-                    let relocation_addr = self.resolver.relocation_addr(ctx)?;
-                    result = eval.resume_with_relocated_address(addr + relocation_addr as u64)?;
+                    let addr = self.resolver.relocate(ctx, addr)?;
+                    result = eval.resume_with_relocated_address(addr)?;
                 }
                 EvaluationResult::RequiresTls(offset) => {
                     let addr = self.resolver.resolve_tls(ctx.pid_on_focus(), offset)?;
@@ -306,7 +308,7 @@ impl<'a> ExpressionEvaluator<'a> {
                         index,
                     )?;
                     if relocate {
-                        addr += self.resolver.relocation_addr(ctx)? as u64;
+                        addr = self.resolver.relocate(ctx, addr)?;
                     }
                     result = eval.resume_with_indexed_address(addr)?;
                 }
@@ -359,6 +361,18 @@ pub enum AddressKind {
 }
 
 impl<'a> CompletedResult<'a> {
+    /// Return an address of a value if the value is entirely located in debugee memory.
+    pub fn memory_address(&self) -> Option<usize> {
+        match self.inner.as_slice() {
+            [Piece {
+                location: Location::Address { address },
+                size_in_bits: None,
+                ..
+            }] => Some(*address as usize),
+            _ => None,
+        }
+    }
+
     pub fn into_scalar<T: Copy>(self, address_kind: AddressKind) -> Result<T, Error> {
         let bytes = self.into_raw_buffer(mem::size_of::<T>(), address_kind)?;
         Ok(scalar_from_bytes(&bytes))
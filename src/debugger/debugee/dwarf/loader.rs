@@ -41,6 +41,21 @@ pub fn load_section(
     Ok(gimli::EndianArcSlice::new(Arc::from(&*data), endian))
 }
 
+/// Load a section of a split debug information file (`.dwo` or `.dwp`),
+/// an empty section is returned if section not exists.
+pub fn load_dwo_section(
+    id: SectionId,
+    file: &File,
+    endian: RunTimeEndian,
+) -> Result<EndianArcSlice, Error> {
+    let data = id
+        .dwo_name()
+        .and_then(|name| file.section_by_name(name))
+        .and_then(|section| section.uncompressed_data().ok())
+        .unwrap_or(Cow::Borrowed(&[]));
+    Ok(gimli::EndianArcSlice::new(Arc::from(&*data), endian))
+}
+
 /// Create a function that load section and put in [`Sections`] struct in right place.
 macro_rules! make_sect_loader {
     ($file: expr, $endian: expr, $field: tt) => {{
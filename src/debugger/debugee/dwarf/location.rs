@@ -26,21 +26,37 @@ impl<'a> Location<'a> {
             return Some(expr);
         }
 
+        // location lists of a split unit are placed in a `.dwo` file
+        let locations = match unit.split() {
+            Some(split) => &split.dwarf().locations,
+            None => dwarf_ctx.locations(),
+        };
+
         let offset = match self.0.value() {
             AttributeValue::LocationListsRef(offset) => offset,
-            AttributeValue::DebugLocListsIndex(index) => weak_error!(dwarf_ctx
-                .locations()
-                .get_offset(unit.encoding(), unit.loclists_base(), index))?,
+            AttributeValue::DebugLocListsIndex(index) => {
+                weak_error!(locations.get_offset(unit.encoding(), unit.loclists_base(), index))?
+            }
             _ => return None,
         };
 
-        let mut iter = weak_error!(dwarf_ctx.locations().locations(
-            offset,
-            unit.encoding(),
-            unit.low_pc(),
-            dwarf_ctx.debug_addr(),
-            unit.addr_base(),
-        ))?;
+        let mut iter = if unit.split().is_some() {
+            weak_error!(locations.locations_dwo(
+                offset,
+                unit.encoding(),
+                unit.low_pc(),
+                dwarf_ctx.debug_addr(),
+                unit.addr_base(),
+            ))?
+        } else {
+            weak_error!(locations.locations(
+                offset,
+                unit.encoding(),
+                unit.low_pc(),
+                dwarf_ctx.debug_addr(),
+                unit.addr_base(),
+            ))?
+        };
 
         let pc = u64::from(pc);
         let entry = iter
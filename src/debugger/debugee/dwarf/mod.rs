@@ -1,7 +1,8 @@
 pub mod eval;
 mod loader;
 mod location;
-mod symbol;
+mod split;
+pub mod symbol;
 pub mod r#type;
 pub mod unit;
 pub mod unwind;
@@ -14,31 +15,34 @@ use crate::debugger::debugee::dwarf::eval::AddressKind;
 use crate::debugger::debugee::dwarf::location::Location as DwarfLocation;
 use crate::debugger::debugee::dwarf::r#type::ComplexType;
 use crate::debugger::debugee::dwarf::r#type::EvaluationContext;
+use crate::debugger::debugee::dwarf::split::SplitDwarfLoader;
 use crate::debugger::debugee::dwarf::symbol::SymbolTab;
 use crate::debugger::debugee::dwarf::unit::{
     DieRef, DieVariant, DwarfUnitParser, Entry, FunctionDie, Node, ParameterDie,
     PlaceDescriptorOwned, Unit, VariableDie,
 };
-use crate::debugger::debugee::dwarf::utils::PathSearchIndex;
+use crate::debugger::debugee::dwarf::utils::{NameIndex, PathSearchIndex};
 use crate::debugger::debugee::{Debugee, Location};
 use crate::debugger::error::Error;
 use crate::debugger::error::Error::{
     DebugIDFormat, FBANotAnExpression, FunctionNotFound, NoFBA, NoFunctionRanges, UnitNotFound,
 };
+use crate::debugger::progress::Task;
 use crate::debugger::register::{DwarfRegisterMap, RegisterMap};
-use crate::debugger::ExplorationContext;
+use crate::debugger::{debuginfod, ExplorationContext};
 use crate::{muted_error, resolve_unit_call, weak_error};
 use bytes::Bytes;
 use fallible_iterator::FallibleIterator;
 use gimli::CfaRule::RegisterAndOffset;
 use gimli::{
-    Attribute, BaseAddresses, CfaRule, DebugAddr, DebugInfoOffset, DebugPubTypes, Dwarf, EhFrame,
-    Expression, LocationLists, Range, Reader, RunTimeEndian, Section, UnitOffset, UnwindContext,
-    UnwindSection, UnwindTableRow,
+    Attribute, BaseAddresses, CfaRule, DebugAddr, DebugFrame, DebugInfoOffset, DebugPubTypes,
+    Dwarf, EhFrame, Expression, FrameDescriptionEntry, LocationLists, Range, Reader, RunTimeEndian,
+    Section, UnitOffset, UnwindContext, UnwindSection, UnwindTableRow,
 };
 use log::debug;
 use memmap2::Mmap;
-use object::{Object, ObjectSection};
+use object::{Object, ObjectSection, ObjectSegment, SymbolKind};
+use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 use regex::Regex;
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -46,16 +50,33 @@ use std::ops::{Add, Deref};
 use std::path::{Path, PathBuf};
 use std::{fs, path};
 pub use symbol::Symbol;
+use symbol::SymbolNameStyle;
 use trie_rs::Trie;
 use unit::PlaceDescriptor;
 use walkdir::WalkDir;
 
 pub type EndianArcSlice = gimli::EndianArcSlice<gimli::RunTimeEndian>;
 
+/// Loadable segment of an object file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadSegment {
+    /// Segment offset in the object file.
+    pub file_offset: u64,
+    /// Segment size in the object file.
+    pub file_size: u64,
+    /// Segment address, may differ from the file offset.
+    pub address: GlobalAddress,
+}
+
 pub struct DebugInformation<R: gimli::Reader = EndianArcSlice> {
     file: PathBuf,
+    /// Loadable segments ordered by file offset.
+    segments: Vec<LoadSegment>,
     inner: Dwarf<R>,
     eh_frame: EhFrame<R>,
+    /// `.debug_frame` section, used for addresses not covered by `.eh_frame`
+    /// (for example, code built with `-fno-asynchronous-unwind-tables`).
+    debug_frame: DebugFrame<R>,
     bases: BaseAddresses,
     units: Option<Vec<Unit>>,
     symbol_table: Option<SymbolTab>,
@@ -65,12 +86,17 @@ pub struct DebugInformation<R: gimli::Reader = EndianArcSlice> {
     /// indexes of lines in [`Unit::lines`] vector that belongs to a file, indexes are ordered by
     /// line number, column number and address.
     files_index: PathSearchIndex<(usize, Vec<usize>)>,
+    /// Index of function names, built on first search by name.
+    function_names: OnceCell<NameIndex>,
+    /// Index of variable names, built on first search by name.
+    variable_names: OnceCell<NameIndex>,
 }
 
 impl Clone for DebugInformation {
     fn clone(&self) -> Self {
         Self {
             file: self.file.clone(),
+            segments: self.segments.clone(),
             inner: Dwarf {
                 debug_abbrev: self.inner.debug_abbrev.clone(),
                 debug_addr: self.inner.debug_addr.clone(),
@@ -88,6 +114,7 @@ impl Clone for DebugInformation {
                 abbreviations_cache: Default::default(),
             },
             eh_frame: self.eh_frame.clone(),
+            debug_frame: self.debug_frame.clone(),
             bases: self.bases.clone(),
             units: self.units.clone(),
             symbol_table: self.symbol_table.clone(),
@@ -95,6 +122,8 @@ impl Clone for DebugInformation {
             pub_names: None,
             pub_types: self.pub_types.clone(),
             files_index: self.files_index.clone(),
+            function_names: self.function_names.clone(),
+            variable_names: self.variable_names.clone(),
         }
     }
 }
@@ -115,6 +144,11 @@ impl DebugInformation {
         self.file.as_path()
     }
 
+    /// Return loadable segments of an object file, ordered by file offset.
+    pub fn load_segments(&self) -> &[LoadSegment] {
+        &self.segments
+    }
+
     /// The location lists in the .debug_loc and .debug_loclists sections.
     pub fn locations(&self) -> &LocationLists<EndianArcSlice> {
         &self.inner.locations
@@ -154,6 +188,42 @@ impl DebugInformation {
             .unwrap_or_default()
     }
 
+    /// Return index of function names. Index is built on first call, all units are parsed
+    /// at this moment.
+    fn function_names(&self) -> &NameIndex {
+        self.function_names.get_or_init(|| {
+            let units = self.units.as_deref().unwrap_or_default();
+            let names: Vec<_> = units
+                .par_iter()
+                .flat_map(|unit| {
+                    let names = resolve_unit_call!(self.dwarf(), unit, function_names);
+                    names
+                        .map(|name| (name.to_string(), unit.idx()))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            NameIndex::new(names)
+        })
+    }
+
+    /// Return index of variable names. Index is built on first call, all units are parsed
+    /// at this moment.
+    fn variable_names(&self) -> &NameIndex {
+        self.variable_names.get_or_init(|| {
+            let units = self.units.as_deref().unwrap_or_default();
+            let names: Vec<_> = units
+                .par_iter()
+                .flat_map(|unit| {
+                    let names = resolve_unit_call!(self.dwarf(), unit, variable_names);
+                    names
+                        .map(|name| (name.to_string(), unit.idx()))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            NameIndex::new(names)
+        })
+    }
+
     /// Return `Some(true)` if .debug_pubnames section contains template last part (for example
     /// this may be a function name), `Some(false)` if not contains and `None` if no .debug_pubnames
     /// section in debug information file.
@@ -204,12 +274,9 @@ impl DebugInformation {
         expl_ctx: &ExplorationContext,
     ) -> Result<RelocatedAddress, Error> {
         let mut ctx = Box::new(UnwindContext::new());
-        let row = self.eh_frame.unwind_info_for_address(
-            &self.bases,
-            &mut ctx,
-            expl_ctx.location().global_pc.into(),
-            EhFrame::cie_from_offset,
-        )?;
+        let (_, row) = self
+            .unwind_info_for_address(&mut ctx, expl_ctx.location().global_pc)?
+            .ok_or(gimli::Error::NoUnwindInfoForAddress)?;
         self.evaluate_cfa(
             debugee,
             &DwarfRegisterMap::from(RegisterMap::current(expl_ctx.pid_on_focus())?),
@@ -218,6 +285,56 @@ impl DebugInformation {
         )
     }
 
+    /// Return a frame description entry and an unwind table row for an address.
+    /// Call frame information is searched in `.eh_frame` section first, then in `.debug_frame`.
+    /// Return `None` if there is no call frame information for an address.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`: unwind context, a returned row is stored in it
+    /// * `pc`: program counter
+    pub fn unwind_info_for_address<'ctx>(
+        &self,
+        ctx: &'ctx mut UnwindContext<EndianArcSlice>,
+        pc: GlobalAddress,
+    ) -> Result<
+        Option<(
+            FrameDescriptionEntry<EndianArcSlice>,
+            &'ctx UnwindTableRow<EndianArcSlice>,
+        )>,
+        Error,
+    > {
+        let fde =
+            match self
+                .eh_frame
+                .fde_for_address(&self.bases, pc.into(), EhFrame::cie_from_offset)
+            {
+                Ok(fde) => fde,
+                Err(gimli::Error::NoUnwindInfoForAddress) => {
+                    match self.debug_frame.fde_for_address(
+                        &self.bases,
+                        pc.into(),
+                        DebugFrame::cie_from_offset,
+                    ) {
+                        Ok(fde) => {
+                            let row = fde.unwind_info_for_address(
+                                &self.debug_frame,
+                                &self.bases,
+                                ctx,
+                                pc.into(),
+                            )?;
+                            return Ok(Some((fde, row)));
+                        }
+                        Err(gimli::Error::NoUnwindInfoForAddress) => return Ok(None),
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            };
+        let row = fde.unwind_info_for_address(&self.eh_frame, &self.bases, ctx, pc.into())?;
+        Ok(Some((fde, row)))
+    }
+
     pub fn debug_addr(&self) -> &DebugAddr<EndianArcSlice> {
         &self.inner.debug_addr
     }
@@ -227,6 +344,36 @@ impl DebugInformation {
         Ok(self.get_units()?.iter().flat_map(|unit| unit.files()))
     }
 
+    /// Return places of all line table rows of files matched by a template,
+    /// rows are ordered by file, line and column numbers.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_tpl`: file name template (full path or part of a file path)
+    pub fn known_lines(&self, file_tpl: &str) -> Result<Vec<PlaceDescriptor<'_>>, Error> {
+        self.get_units()?;
+        let mut places: Vec<_> = self
+            .files_index
+            .get(file_tpl)
+            .into_iter()
+            .flat_map(|(unit_idx, file_lines)| {
+                let unit = self.unit_ensure(*unit_idx);
+                file_lines
+                    .iter()
+                    .filter_map(|&line_idx| unit.find_place_by_idx(line_idx))
+            })
+            .collect();
+        places.sort_by_key(|place| {
+            (
+                place.file,
+                place.line_number,
+                place.column_number,
+                u64::from(place.address),
+            )
+        });
+        Ok(places)
+    }
+
     /// Searches for a unit by occurrences of PC in its range.
     ///
     /// # Arguments
@@ -315,9 +462,12 @@ impl DebugInformation {
         template: &str,
     ) -> Result<Vec<ContextualDieRef<FunctionDie>>, Error> {
         let units = self.get_units()?;
-        let result: Vec<_> = units
-            .par_iter()
-            .flat_map(|unit| {
+        let name = template.rsplit("::").next().unwrap_or(template);
+        let candidates: Vec<_> = self.function_names().get(name).collect();
+        let result: Vec<_> = candidates
+            .into_par_iter()
+            .flat_map(|unit_idx| {
+                let unit = &units[unit_idx];
                 let entries = resolve_unit_call!(self.dwarf(), unit, search_functions, template);
                 entries
                     .iter()
@@ -337,6 +487,49 @@ impl DebugInformation {
         Ok(result)
     }
 
+    /// Return functions which full name (with namespace) matches a regular expression.
+    /// Function declarations (without address ranges) are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `regex`: regular expression
+    pub fn search_functions_by_regex(
+        &self,
+        regex: &Regex,
+    ) -> Result<Vec<ContextualDieRef<FunctionDie>>, Error> {
+        let units = self.get_units()?;
+        let result: Vec<_> = units
+            .par_iter()
+            .flat_map(|unit| {
+                let entries = resolve_unit_call!(self.dwarf(), unit, entries_it);
+                entries
+                    .filter_map(|entry| {
+                        let DieVariant::Function(func) = &entry.die else {
+                            return None;
+                        };
+                        if func.base_attributes.ranges.is_empty() {
+                            return None;
+                        }
+                        let name = func.base_attributes.name.as_deref()?;
+                        let full_name = if func.namespace.is_empty() {
+                            name.to_string()
+                        } else {
+                            format!("{}::{name}", func.namespace.join("::"))
+                        };
+                        regex.is_match(&full_name).then_some(ContextualDieRef {
+                            debug_info: self,
+                            unit_idx: unit.idx(),
+                            node: &entry.node,
+                            die: func,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        Ok(result)
+    }
+
     /// Return closest [`PlaceDescriptor`] for given file and line.
     /// Closest means that returns descriptor for target line or, if no descriptor for target line,
     /// place for next line after target.
@@ -456,6 +649,8 @@ impl DebugInformation {
     }
 
     /// Search all places for functions that relevant to template.
+    /// Every instantiation of a generic function and every inline expansion
+    /// of a function is a separate place.
     ///
     /// # Arguments
     ///
@@ -464,13 +659,124 @@ impl DebugInformation {
         &self,
         template: &str,
     ) -> Result<Vec<PlaceDescriptorOwned>, Error> {
-        Ok(self
-            .search_functions(template)?
-            .into_iter()
+        let functions = self.search_functions(template)?;
+        let mut places: Vec<_> = functions
+            .iter()
             .filter_map(|fn_die| {
                 weak_error!(fn_die.prolog_end_place()).map(|place| place.to_owned())
             })
-            .collect())
+            .collect();
+
+        for place in self.search_inline_places(template)? {
+            if !places.iter().any(|p| p.address == place.address) {
+                places.push(place);
+            }
+        }
+
+        Ok(places)
+    }
+
+    /// Return start places of inline expansions of functions relevant to template.
+    /// An abstract origin of inline expansion (a function without address ranges,
+    /// like `#[inline(always)]` one) isn't in a function index, so origins are matched
+    /// with a template directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `template`: search template (full function path or part of this path).
+    fn search_inline_places(&self, template: &str) -> Result<Vec<PlaceDescriptorOwned>, Error> {
+        let units = self.get_units()?;
+        let is_target_origin = |unit: &Unit, origin: DieRef| match self.deref_die(unit, origin) {
+            Some((entry, _)) => {
+                matches!(&entry.die, DieVariant::Function(func) if func.match_template(template))
+            }
+            None => false,
+        };
+        let places = units
+            .par_iter()
+            .flat_map(|unit| {
+                // inline expansions of a function share an origin, so origin is checked once
+                let mut origins: HashMap<DebugInfoOffset, bool> = HashMap::new();
+                let entries = resolve_unit_call!(self.dwarf(), unit, entries_it);
+                entries
+                    .filter_map(|entry| {
+                        let DieVariant::InlineSubroutine(inline) = &entry.die else {
+                            return None;
+                        };
+                        let origin = inline.origin?;
+                        let is_target = *origins
+                            .entry(origin.section_offset(unit))
+                            .or_insert_with(|| is_target_origin(unit, origin));
+                        if !is_target {
+                            return None;
+                        }
+                        let start = inline
+                            .base_attributes
+                            .ranges
+                            .iter()
+                            .map(|r| r.begin)
+                            .min()?;
+                        unit.find_place_by_pc(GlobalAddress::from(start))
+                            .map(|place| place.to_owned())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Ok(places)
+    }
+
+    /// Return all statement places (line rows marked as a beginning of a statement)
+    /// of files relevant to template.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_tpl`: file name template (full path or part of a file path)
+    pub fn search_stmt_places_for_file_tpl(&self, file_tpl: &str) -> Vec<PlaceDescriptorOwned> {
+        self.files_index
+            .get(file_tpl)
+            .into_iter()
+            .flat_map(|(unit_idx, file_lines)| {
+                let unit = self.unit_ensure(*unit_idx);
+                file_lines
+                    .iter()
+                    .filter_map(|&line_idx| unit.find_place_by_idx(line_idx))
+                    .filter(|place| place.is_stmt && place.line_number != 0)
+                    .map(|place| place.to_owned())
+            })
+            .collect()
+    }
+
+    /// Return all statement places of functions relevant to template.
+    /// Code inlined from other files is not included.
+    ///
+    /// # Arguments
+    ///
+    /// * `template`: search template (full function path or part of this path).
+    pub fn search_stmt_places_for_fn_tpl(
+        &self,
+        template: &str,
+    ) -> Result<Vec<PlaceDescriptorOwned>, Error> {
+        let mut result = vec![];
+        for fn_die in self.search_functions(template)? {
+            let Some(start_place) = weak_error!(fn_die.prolog_start_place()) else {
+                continue;
+            };
+            for range in fn_die.ranges() {
+                result.extend(
+                    fn_die
+                        .unit()
+                        .find_lines_for_range(range)
+                        .into_iter()
+                        .filter(|place| {
+                            place.is_stmt
+                                && place.line_number != 0
+                                && place.file == start_place.file
+                        })
+                        .map(|place| place.to_owned()),
+                );
+            }
+        }
+        Ok(result)
     }
 
     pub fn find_symbols(&self, regex: &Regex) -> Vec<&Symbol> {
@@ -487,6 +793,36 @@ impl DebugInformation {
         symbols
     }
 
+    /// Return a function symbol with a name equal to a template. Template is compared with
+    /// a raw (mangled) name, a demangled name and a demangled name without a hash suffix.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: symbol name
+    pub fn find_text_symbol(&self, name: &str) -> Option<&Symbol> {
+        let table = self.symbol_table.as_ref()?;
+        let is_text = |symbol: &&Symbol| symbol.kind == SymbolKind::Text;
+        table.get(name).filter(is_text).or_else(|| {
+            table.values().filter(is_text).find(|symbol| {
+                symbol.raw_name == name
+                    || format!("{:#}", rustc_demangle::demangle(&symbol.raw_name)) == name
+            })
+        })
+    }
+
+    /// Return a function symbol which address range contains a program counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc`: program counter value
+    pub fn find_symbol_by_pc(&self, pc: GlobalAddress) -> Option<&Symbol> {
+        let pc = u64::from(pc);
+        self.symbol_table.as_ref()?.values().find(|symbol| {
+            let start = u64::from(symbol.addr);
+            symbol.kind == SymbolKind::Text && start <= pc && pc < start + symbol.size
+        })
+    }
+
     pub fn deref_die<'this>(
         &'this self,
         default_unit: &'this Unit,
@@ -514,7 +850,8 @@ impl DebugInformation {
         let units = self.get_units()?;
 
         let mut found = vec![];
-        for unit in units {
+        for unit_idx in self.variable_names().get(name) {
+            let unit = &units[unit_idx];
             let mb_var_locations = resolve_unit_call!(self.dwarf(), unit, locate_var_die, name);
             if let Some(vars) = mb_var_locations {
                 vars.iter().for_each(|(_, entry_idx)| {
@@ -539,7 +876,8 @@ impl DebugInformation {
         // for rust we expect that tls variable represents in dwarf like
         // variable with name "__KEY" and namespace like [.., variable_name, __getit]
         let tls_ns_part = &[name, "__getit"];
-        for unit in units {
+        for unit_idx in self.variable_names().get("__KEY") {
+            let unit = &units[unit_idx];
             let mb_var_locations = resolve_unit_call!(self.dwarf(), unit, locate_var_die, "__KEY");
             if let Some(vars) = mb_var_locations {
                 vars.iter().for_each(|(namespaces, entry_idx)| {
@@ -624,8 +962,19 @@ impl DebugInformationBuilder {
     // todo configure this path
     const DEBUG_FILES_DIR: &'static str = "/usr/lib/debug";
 
+    fn map_file(path: &Path) -> Result<Mmap, Error> {
+        let file = fs::File::open(path)?;
+        Ok(unsafe { memmap2::Mmap::map(&file)? })
+    }
+
+    /// Search a separate debug information file by build-id
+    /// (`/usr/lib/debug/.build-id/xx/yyyy.debug`) or by `.gnu_debuglink` section
+    /// (like gdb, near the object file, in `.debug` subdirectory and in `/usr/lib/debug`).
+    /// Objects without any local debug information are requested by build-id from
+    /// debuginfod servers at last.
     fn get_dwarf_from_separate_debug_file<'a, 'b, OBJ>(
         &self,
+        obj_path: &Path,
         obj_file: &'a OBJ,
     ) -> Result<Option<(PathBuf, Mmap)>, Error>
     where
@@ -634,29 +983,30 @@ impl DebugInformationBuilder {
     {
         // try build-id
         let debug_id_sect = obj_file.section_by_name(".note.gnu.build-id");
+        let mut build_id_hex = None;
         if let Some(build_id) = debug_id_sect {
             let data = build_id.data()?;
             // skip 16 byte header
-            let note = &data[16..];
+            let note = data.get(16..).unwrap_or_default();
             if note.len() < 2 {
                 return Err(DebugIDFormat);
             }
 
-            let dir = format!("{:02x}", note[0]);
-            let file = note[1..]
+            let hex = note
                 .iter()
                 .map(|&b| format!("{:02x}", b))
                 .collect::<Vec<String>>()
-                .join("")
-                .add(".debug");
-
+                .join("");
+            let (dir, file) = hex.split_at(2);
             let path = PathBuf::from(Self::DEBUG_FILES_DIR)
                 .join(".build-id")
                 .join(dir)
-                .join(file);
-            let file = fs::File::open(path.as_path())?;
-            let mmap = unsafe { memmap2::Mmap::map(&file)? };
-            return Ok(Some((path, mmap)));
+                .join(file.to_string().add(".debug"));
+            build_id_hex = Some(hex);
+            if path.exists() {
+                let mmap = Self::map_file(&path)?;
+                return Ok(Some((path, mmap)));
+            }
         }
 
         // try debug link
@@ -666,19 +1016,41 @@ impl DebugInformationBuilder {
             let data: Vec<u8> = data.iter().take_while(|&&b| b != 0).copied().collect();
             let debug_link = std::str::from_utf8(&data)?;
 
+            let obj_dir = obj_path.parent().unwrap_or(Path::new("/"));
+            let candidates = [
+                obj_dir.join(debug_link),
+                obj_dir.join(".debug").join(debug_link),
+                PathBuf::from(Self::DEBUG_FILES_DIR)
+                    .join(obj_dir.strip_prefix("/").unwrap_or(obj_dir))
+                    .join(debug_link),
+            ];
+            for path in candidates {
+                // debug link may point to the object file itself
+                if path.is_file() && path != obj_path {
+                    let mmap = Self::map_file(&path)?;
+                    return Ok(Some((path, mmap)));
+                }
+            }
+
             for entry in WalkDir::new(Self::DEBUG_FILES_DIR)
                 .into_iter()
                 .filter_map(|e| e.ok())
             {
-                let name = entry.file_name().to_string_lossy();
-                if name.contains(debug_link) {
-                    let file = fs::File::open(entry.path())?;
-                    let mmap = unsafe { memmap2::Mmap::map(&file)? };
+                if entry.file_name().to_string_lossy() == debug_link {
+                    let mmap = Self::map_file(entry.path())?;
                     return Ok(Some((entry.path().to_path_buf(), mmap)));
                 }
             }
         }
 
+        // try debuginfod servers, only for objects without their own debug information
+        if obj_file.section_by_name(".debug_info").is_none() {
+            if let Some(path) = build_id_hex.as_deref().and_then(debuginfod::find_debuginfo) {
+                let mmap = Self::map_file(&path)?;
+                return Ok(Some((path, mmap)));
+            }
+        }
+
         Ok(None)
     }
 
@@ -715,23 +1087,49 @@ impl DebugInformationBuilder {
             bases = bases.set_eh_frame_hdr(eh_frame_hdr);
         }
 
+        let mut segments: Vec<_> = file
+            .segments()
+            .map(|segment| {
+                let (file_offset, file_size) = segment.file_range();
+                LoadSegment {
+                    file_offset,
+                    file_size,
+                    address: GlobalAddress::from(segment.address()),
+                }
+            })
+            .collect();
+        segments.sort_unstable_by_key(|segment| segment.file_offset);
+
         let debug_split_file_data;
         let debug_split_file;
-        let debug_info_file =
-            if let Ok(Some((path, debug_file))) = self.get_dwarf_from_separate_debug_file(file) {
-                debug!(target: "dwarf-loader", "{obj_path:?} has separate debug information file");
-                debug!(target: "dwarf-loader", "load debug information from {path:?}");
-                debug_split_file_data = debug_file;
-                debug_split_file = object::File::parse(&*debug_split_file_data)?;
-                &debug_split_file
-            } else {
-                debug!(target: "dwarf-loader", "load debug information from {obj_path:?}");
-                file
-            };
+        let debug_info_file = if let Ok(Some((path, debug_file))) =
+            self.get_dwarf_from_separate_debug_file(obj_path, file)
+        {
+            debug!(target: "dwarf-loader", "{obj_path:?} has separate debug information file");
+            debug!(target: "dwarf-loader", "load debug information from {path:?}");
+            debug_split_file_data = debug_file;
+            debug_split_file = object::File::parse(&*debug_split_file_data)?;
+            &debug_split_file
+        } else {
+            debug!(target: "dwarf-loader", "load debug information from {obj_path:?}");
+            file
+        };
 
         let dwarf = loader::load_par(debug_info_file, endian)?;
         let symbol_table = SymbolTab::new(debug_info_file);
 
+        // `.debug_frame` may be stripped into a separate debug information file
+        let load_debug_frame = |file: &object::File| {
+            DebugFrame::load(|id| -> Result<EndianArcSlice, Error> {
+                loader::load_section(id, file, endian)
+            })
+        };
+        let mut debug_frame = load_debug_frame(debug_info_file)?;
+        if debug_frame.reader().is_empty() {
+            debug_frame = load_debug_frame(file)?;
+        }
+        debug_frame.set_address_size(if file.is_64() { 8 } else { 4 });
+
         // let mb_pub_names_sect = muted_error!(DebugPubNames::load(|id| {
         //     loader::load_section(id, debug_info_file, endian)
         // }));
@@ -767,7 +1165,8 @@ impl DebugInformationBuilder {
                 .ok()
         });
 
-        let parser = DwarfUnitParser::new(&dwarf);
+        let split_loader = SplitDwarfLoader::new(obj_path, endian);
+        let parser = DwarfUnitParser::new(&dwarf).with_split_loader(&split_loader);
         let headers = dwarf.units().collect::<Vec<_>>()?;
 
         if headers.is_empty() {
@@ -776,24 +1175,31 @@ impl DebugInformationBuilder {
 
             return Ok(DebugInformation {
                 file: obj_path.to_path_buf(),
+                segments,
                 inner: dwarf,
                 eh_frame,
+                debug_frame,
                 bases,
                 units: None,
                 symbol_table,
                 pub_names,
                 pub_types: pub_types.unwrap_or_default(),
                 files_index: PathSearchIndex::new(""),
+                function_names: OnceCell::new(),
+                variable_names: OnceCell::new(),
             });
         }
 
+        let task = Task::start("parse debug information", headers.len() as u64);
         let mut units = headers
             .into_par_iter()
-            .map(|header| -> gimli::Result<Unit> {
+            .map(|header| -> Result<Unit, Error> {
+                task.advance(1)?;
                 let unit = parser.parse(header)?;
                 Ok(unit)
             })
-            .collect::<gimli::Result<Vec<_>>>()?;
+            .collect::<Result<Vec<_>, Error>>()?;
+        drop(task);
 
         units.sort_unstable_by_key(|u| u.offset());
         units.iter_mut().enumerate().for_each(|(i, u)| u.set_idx(i));
@@ -808,14 +1214,18 @@ impl DebugInformationBuilder {
 
         Ok(DebugInformation {
             file: obj_path.to_path_buf(),
+            segments,
             inner: dwarf,
             eh_frame,
+            debug_frame,
             bases,
             units: Some(units),
             symbol_table,
             pub_names,
             pub_types: pub_types.unwrap_or_default(),
             files_index,
+            function_names: OnceCell::new(),
+            variable_names: OnceCell::new(),
         })
     }
 }
@@ -968,6 +1378,15 @@ impl<'ctx> ContextualDieRef<'ctx, FunctionDie> {
             .map(|name| format!("{}::{}", self.die.namespace.0.join("::"), name))
     }
 
+    /// Return a function name in current symbol name representation
+    /// (see [`symbol::set_name_style`]), raw names are taken from a linkage name.
+    pub fn display_name(&self) -> Option<String> {
+        match (symbol::name_style(), &self.die.linkage_name) {
+            (SymbolNameStyle::Demangled, _) | (_, None) => self.full_name(),
+            (_, Some(linkage_name)) => Some(symbol::display_name(linkage_name)),
+        }
+    }
+
     pub fn frame_base_addr(
         &self,
         ctx: &ExplorationContext,
@@ -1184,6 +1603,29 @@ impl<'ctx, D: AsAllocatedData> ContextualDieRef<'ctx, D> {
                 weak_error!(eval_result.into_raw_buffer(type_size, AddressKind::MemoryAddress))
             })
     }
+
+    /// Return an address and a size of a value in debugee memory,
+    /// `None` if value isn't located in memory (in registers, for example).
+    pub fn memory_location(
+        &self,
+        ctx: &ExplorationContext,
+        debugee: &Debugee,
+        r#type: &ComplexType,
+    ) -> Option<(usize, usize)> {
+        let expr =
+            self.die
+                .location_expr(self.debug_info, self.unit(), ctx.location().global_pc)?;
+        let evaluator = ctx_resolve_unit_call!(self, evaluator, debugee);
+        let eval_result = weak_error!(evaluator.evaluate(ctx, expr))?;
+        let type_size = r#type.type_size_in_bytes(
+            &EvaluationContext {
+                evaluator: &evaluator,
+                expl_ctx: ctx,
+            },
+            r#type.root,
+        )? as usize;
+        Some((eval_result.memory_address()?, type_size))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,174 @@
+//! Split debug information (`-C split-debuginfo=packed|unpacked`).
+//!
+//! A skeleton unit of an object file contains only a line table and address ranges,
+//! debug information entries are placed in a split unit. Split units are loaded from
+//! a `.dwp` package near the object file (`{object file}.dwp`) or from a `.dwo` file
+//! which name is stored in the skeleton unit.
+
+use crate::debugger::debugee::dwarf::{loader, EndianArcSlice};
+use crate::debugger::error::Error;
+use crate::weak_error;
+use fallible_iterator::FallibleIterator;
+use gimli::{
+    DebugAddrBase, DebugRngListsBase, Dwarf, DwarfPackage, Reader, RunTimeEndian, UnitHeader,
+};
+use log::debug;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// DWARF sections of a split unit and attributes of its skeleton unit
+/// that are subject to relocation.
+#[derive(Debug, Clone)]
+pub struct SplitUnit {
+    dwarf: Arc<Dwarf<EndianArcSlice>>,
+    low_pc: u64,
+    addr_base: DebugAddrBase,
+    rnglists_base: DebugRngListsBase,
+}
+
+impl SplitUnit {
+    /// Return DWARF sections of a `.dwo` file (or of a part of `.dwp` package).
+    pub fn dwarf(&self) -> &Dwarf<EndianArcSlice> {
+        &self.dwarf
+    }
+
+    /// Copy relocated attributes of a skeleton unit into a split unit.
+    pub fn relocate(&self, unit: &mut gimli::Unit<EndianArcSlice>) {
+        unit.low_pc = self.low_pc;
+        unit.addr_base = self.addr_base;
+        if unit.header.version() < 5 {
+            unit.rnglists_base = self.rnglists_base;
+        }
+    }
+}
+
+/// Loader of split units for skeleton units of an object file.
+pub struct SplitDwarfLoader {
+    obj_dir: PathBuf,
+    endian: RunTimeEndian,
+    package: Option<DwarfPackage<EndianArcSlice>>,
+}
+
+impl SplitDwarfLoader {
+    /// Create a loader for an object file, a `.dwp` package is loaded if exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `obj_path`: path to object file
+    /// * `endian`: object file endian
+    pub fn new(obj_path: &Path, endian: RunTimeEndian) -> Self {
+        let mut dwp_path = OsString::from(obj_path);
+        dwp_path.push(".dwp");
+        let dwp_path = PathBuf::from(dwp_path);
+
+        let package = if dwp_path.exists() {
+            debug!(target: "dwarf-loader", "load split debug information from {dwp_path:?}");
+            weak_error!(Self::load_package(&dwp_path, endian))
+        } else {
+            None
+        };
+
+        Self {
+            obj_dir: obj_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            endian,
+            package,
+        }
+    }
+
+    fn load_package(
+        path: &Path,
+        endian: RunTimeEndian,
+    ) -> Result<DwarfPackage<EndianArcSlice>, Error> {
+        let file = fs::File::open(path)?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let object = object::File::parse(&*mmap)?;
+        let empty = EndianArcSlice::new(Arc::from(&[][..]), endian);
+        DwarfPackage::load(|id| loader::load_dwo_section(id, &object, endian), empty)
+    }
+
+    fn load_dwo(
+        &self,
+        parent: &Dwarf<EndianArcSlice>,
+        skeleton: &gimli::Unit<EndianArcSlice>,
+    ) -> Result<Option<Dwarf<EndianArcSlice>>, Error> {
+        let Some(name) = skeleton.dwo_name()? else {
+            return Ok(None);
+        };
+        let name = parent.attr_string(skeleton, name)?;
+        let name = PathBuf::from(name.to_string_lossy()?.as_ref());
+        let comp_dir = skeleton
+            .comp_dir
+            .as_ref()
+            .map(|dir| dir.to_string_lossy().map(|dir| PathBuf::from(dir.as_ref())))
+            .transpose()?;
+
+        // a `.dwo` file is searched relative to a compilation directory
+        // and then near the object file (for moved build directories)
+        let candidates = [
+            comp_dir.map(|dir| dir.join(&name)),
+            name.file_name()
+                .map(|file_name| self.obj_dir.join(file_name)),
+        ];
+        for path in candidates.into_iter().flatten() {
+            if !path.exists() {
+                continue;
+            }
+            debug!(target: "dwarf-loader", "load split unit from {path:?}");
+
+            let file = fs::File::open(&path)?;
+            let mmap = unsafe { memmap2::Mmap::map(&file)? };
+            let object = object::File::parse(&*mmap)?;
+            let mut dwarf = Dwarf::load(|id| loader::load_dwo_section(id, &object, self.endian))?;
+            dwarf.make_dwo(parent);
+            return Ok(Some(dwarf));
+        }
+
+        Ok(None)
+    }
+
+    /// Return a split unit and its header for a skeleton unit,
+    /// `None` if unit is not a skeleton or split unit not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent`: DWARF sections of an object file
+    /// * `skeleton`: skeleton unit
+    pub fn load(
+        &self,
+        parent: &Dwarf<EndianArcSlice>,
+        skeleton: &gimli::Unit<EndianArcSlice>,
+    ) -> Result<Option<(SplitUnit, UnitHeader<EndianArcSlice>)>, Error> {
+        let Some(dwo_id) = skeleton.dwo_id else {
+            return Ok(None);
+        };
+
+        let from_package = match self.package {
+            Some(ref package) => package.find_cu(dwo_id, parent)?,
+            None => None,
+        };
+        let dwarf = match from_package {
+            Some(dwarf) => dwarf,
+            None => match self.load_dwo(parent, skeleton)? {
+                Some(dwarf) => dwarf,
+                None => return Ok(None),
+            },
+        };
+
+        let headers = dwarf.units().collect::<Vec<_>>()?;
+        for header in headers {
+            if dwarf.unit(header.clone())?.dwo_id == Some(dwo_id) {
+                let split = SplitUnit {
+                    dwarf: Arc::new(dwarf),
+                    low_pc: skeleton.low_pc,
+                    addr_base: skeleton.addr_base,
+                    rnglists_base: skeleton.rnglists_base,
+                };
+                return Ok(Some((split, header)));
+            }
+        }
+
+        Ok(None)
+    }
+}
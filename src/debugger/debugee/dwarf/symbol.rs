@@ -2,12 +2,85 @@ use crate::debugger::address::GlobalAddress;
 use object::{Object, ObjectSymbol, ObjectSymbolTable, SymbolKind};
 use std::collections::HashMap;
 use std::ops::Deref;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Representation of symbol names (for example, function names of backtrace frames
+/// without debug information or symbol lookup results).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymbolNameStyle {
+    /// Demangled name without a hash suffix (ex. `std::rt::lang_start`).
+    #[default]
+    Demangled,
+    /// Name from a symbol table as is (ex. `_ZN3std2rt10lang_start17h6f8a4ee1b6e9aa4eE`).
+    Raw,
+    /// Demangled name followed by a raw one.
+    Both,
+}
+
+impl FromStr for SymbolNameStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "demangled" => Ok(SymbolNameStyle::Demangled),
+            "raw" => Ok(SymbolNameStyle::Raw),
+            "both" => Ok(SymbolNameStyle::Both),
+            _ => Err(format!("unknown symbol name style: {s}")),
+        }
+    }
+}
+
+static NAME_STYLE: AtomicU8 = AtomicU8::new(SymbolNameStyle::Demangled as u8);
+
+/// Set representation of symbol names.
+pub fn set_name_style(style: SymbolNameStyle) {
+    NAME_STYLE.store(style as u8, Ordering::Relaxed);
+}
+
+/// Return current representation of symbol names.
+pub fn name_style() -> SymbolNameStyle {
+    match NAME_STYLE.load(Ordering::Relaxed) {
+        1 => SymbolNameStyle::Raw,
+        2 => SymbolNameStyle::Both,
+        _ => SymbolNameStyle::Demangled,
+    }
+}
+
+/// Return a symbol name in current representation (see [`set_name_style`]).
+/// Both legacy (`_ZN...`) and v0 (`_R...`) rust manglings are supported,
+/// other names are returned as is.
+///
+/// # Arguments
+///
+/// * `raw_name`: name from a symbol table
+pub fn display_name(raw_name: &str) -> String {
+    let demangled = format!("{:#}", rustc_demangle::demangle(raw_name));
+    match name_style() {
+        SymbolNameStyle::Demangled => demangled,
+        SymbolNameStyle::Raw => raw_name.to_string(),
+        SymbolNameStyle::Both if demangled == raw_name => demangled,
+        SymbolNameStyle::Both => format!("{demangled} ({raw_name})"),
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Symbol {
+    /// Demangled name (with a hash suffix).
     pub name: String,
+    /// Name from a symbol table.
+    pub raw_name: String,
     pub kind: SymbolKind,
     pub addr: GlobalAddress,
+    /// Symbol size in bytes, zero if unknown.
+    pub size: u64,
+}
+
+impl Symbol {
+    /// Return symbol name in current representation (see [`set_name_style`]).
+    pub fn display_name(&self) -> String {
+        display_name(&self.raw_name)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -32,14 +105,16 @@ impl SymbolTab {
                 sym_table
                     .symbols()
                     .map(|symbol| {
-                        let name = symbol.name().unwrap_or_default();
-                        let name = rustc_demangle::demangle(name).to_string();
+                        let raw_name = symbol.name().unwrap_or_default();
+                        let name = rustc_demangle::demangle(raw_name).to_string();
                         (
                             name.clone(),
                             Symbol {
                                 name,
+                                raw_name: raw_name.to_string(),
                                 kind: symbol.kind(),
                                 addr: symbol.address().into(),
+                                size: symbol.size(),
                             },
                         )
                     })
@@ -48,3 +123,31 @@ impl SymbolTab {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display_name() {
+        let legacy = "_ZN3std2rt10lang_start17h6f8a4ee1b6e9aa4eE";
+        let v0 = "_RNvCs1234_7mycrate3foo";
+
+        set_name_style(SymbolNameStyle::Demangled);
+        assert_eq!(display_name(legacy), "std::rt::lang_start");
+        assert_eq!(display_name(v0), "mycrate::foo");
+        assert_eq!(display_name("memcpy"), "memcpy");
+
+        set_name_style(SymbolNameStyle::Raw);
+        assert_eq!(display_name(legacy), legacy);
+
+        set_name_style(SymbolNameStyle::Both);
+        assert_eq!(
+            display_name(legacy),
+            format!("std::rt::lang_start ({legacy})")
+        );
+        assert_eq!(display_name("memcpy"), "memcpy");
+
+        set_name_style(SymbolNameStyle::Demangled);
+    }
+}
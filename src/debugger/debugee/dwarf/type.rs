@@ -15,6 +15,7 @@ use log::warn;
 use std::cell::Cell;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::mem;
+use std::path::PathBuf;
 use strum_macros::Display;
 use uuid::Uuid;
 
@@ -62,6 +63,9 @@ pub struct StructureMember {
     pub in_struct_location: Option<MemberLocation>,
     pub name: Option<String>,
     pub type_ref: Option<TypeIdentity>,
+    /// Declaration place (file and line) of a member, set for suspend point variants
+    /// of async fn state machines.
+    pub decl: Option<(PathBuf, u64)>,
 }
 
 impl StructureMember {
@@ -683,10 +687,16 @@ impl TypeParser {
             self.parse_inner(ctx_die, reference);
         }
 
+        let decl = ctx_die.die.decl_file_line.and_then(|(file, line)| {
+            let file = ctx_die.unit().files().get(file as usize)?;
+            Some((file.clone(), line))
+        });
+
         StructureMember {
             in_struct_location,
             name: ctx_die.die.base_attributes.name.clone(),
             type_ref: mb_type_ref,
+            decl,
         }
     }
 
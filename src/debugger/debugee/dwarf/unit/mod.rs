@@ -4,6 +4,7 @@ pub use parser::DwarfUnitParser;
 
 use crate::debugger::address::GlobalAddress;
 use crate::debugger::debugee::dwarf::eval::ExpressionEvaluator;
+use crate::debugger::debugee::dwarf::split::SplitUnit;
 use crate::debugger::debugee::dwarf::utils::PathSearchIndex;
 use crate::debugger::debugee::dwarf::{EndianArcSlice, NamespaceHierarchy};
 use crate::debugger::debugee::Debugee;
@@ -123,9 +124,36 @@ pub struct FunctionDie {
     pub decl_file_line: Option<(u64, u64)>,
     pub base_attributes: DieAttributes,
     pub fb_addr: Option<Attribute<EndianArcSlice>>,
+    /// Type of a returned value, `None` if function returns nothing.
+    pub return_type_ref: Option<DieRef>,
 }
 
 impl FunctionDie {
+    /// Return true if function path (namespace and name) ends with a template,
+    /// the same way as function index matches a template.
+    ///
+    /// # Arguments
+    ///
+    /// * `template`: search template (full function path or part of this path).
+    pub fn match_template(&self, template: &str) -> bool {
+        let mut tail: Vec<_> = template.split("::").collect();
+        let Some(head) = tail.pop() else {
+            return false;
+        };
+        let name = self
+            .linkage_name
+            .as_ref()
+            .or(self.base_attributes.name.as_ref());
+        name.is_some_and(|name| name == head)
+            && self.namespace.len() >= tail.len()
+            && self
+                .namespace
+                .iter()
+                .rev()
+                .zip(tail.iter().rev())
+                .all(|(ns, part)| ns == part)
+    }
+
     /// If subprogram die contains a `DW_AT_specification` attribute than this subprogram have
     /// a declaration part in another die. This function will complete complete subprogram with
     /// information from it declaration (typically this is a name and linkage_name).
@@ -142,6 +170,10 @@ impl FunctionDie {
         if self.decl_file_line.is_none() {
             self.decl_file_line = declaration.decl_file_line;
         }
+
+        if self.return_type_ref.is_none() {
+            self.return_type_ref = declaration.return_type_ref;
+        }
     }
 }
 
@@ -194,6 +226,9 @@ pub struct TypeMemberDie {
     pub byte_size: Option<u64>,
     pub location: Option<Attribute<EndianArcSlice>>,
     pub type_ref: Option<DieRef>,
+    /// Declaration file index and line, rust compiler set it for variants of
+    /// async fn state machines (a suspend point).
+    pub decl_file_line: Option<(u64, u64)>,
 }
 
 #[derive(Debug, Clone)]
@@ -263,6 +298,8 @@ pub struct SubroutineDie {
 #[derive(Debug, Clone)]
 pub struct InlineSubroutineDie {
     pub base_attributes: DieAttributes,
+    /// Reference to an inlined function.
+    pub origin: Option<DieRef>,
     pub call_file: Option<u64>,
     pub call_line: Option<u64>,
     pub call_column: Option<u64>,
@@ -387,6 +424,20 @@ impl DieRef {
             _ => None,
         }
     }
+
+    /// Return an offset of referenced die within `.debug_info` section.
+    ///
+    /// # Arguments
+    ///
+    /// * `unit`: unit where reference is found
+    pub fn section_offset(self, unit: &Unit) -> DebugInfoOffset {
+        match self {
+            DieRef::Unit(offset) => {
+                DebugInfoOffset(unit.offset().unwrap_or(DebugInfoOffset(0)).0 + offset.0)
+            }
+            DieRef::Global(offset) => offset,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -477,6 +528,8 @@ pub struct Unit {
     lazy_part: OnceCell<UnitLazyPart>,
     language: Option<DwLang>,
     producer: Option<String>,
+    /// Split unit sections, `None` if unit is not a skeleton unit.
+    split: Option<SplitUnit>,
 }
 
 impl Clone for Unit {
@@ -494,6 +547,7 @@ impl Clone for Unit {
             lazy_part: self.lazy_part.clone(),
             language: self.language,
             producer: self.producer.clone(),
+            split: self.split.clone(),
         }
     }
 }
@@ -502,19 +556,30 @@ impl Unit {
     /// Update unit to full state.
     /// Note: this method will panic if called twice.
     pub fn reload(&self, parser: DwarfUnitParser) -> Result<(), Error> {
-        let additional = parser.parse_additional(
-            self.header
-                .lock()
-                .unwrap()
-                .take()
-                .expect("unreachable: header must exists"),
-        )?;
+        let header = self
+            .header
+            .lock()
+            .unwrap()
+            .take()
+            .expect("unreachable: header must exists");
+        let additional = match self.split {
+            Some(ref split) => {
+                DwarfUnitParser::new(split.dwarf()).parse_additional(header, Some(split))?
+            }
+            None => parser.parse_additional(header, None)?,
+        };
         self.lazy_part
             .set(additional)
             .expect("unreachable: lazy part must be empty");
         Ok(())
     }
 
+    /// Return split unit sections if unit is a skeleton unit
+    /// (debug information entries are placed in a `.dwo` file or a `.dwp` package).
+    pub fn split(&self) -> Option<&SplitUnit> {
+        self.split.as_ref()
+    }
+
     /// Return unit index in unit registry.
     /// See [`crate::debugger::debugee::dwarf::DebugInformation`]
     pub fn idx(&self) -> usize {
@@ -709,6 +774,26 @@ impl Unit {
         }
     }
 
+    /// Return names of all functions in a unit (last parts of function paths).
+    /// Note: this method requires a full unit.
+    pub fn function_names(&self) -> UnitResult<impl Iterator<Item = &str>> {
+        match self.lazy_part.get() {
+            None => UnitResult::Reload,
+            Some(additional) => UnitResult::Ok(additional.function_index.heads()),
+        }
+    }
+
+    /// Return names of all variables in a unit.
+    /// Note: this method requires a full unit.
+    pub fn variable_names(&self) -> UnitResult<impl Iterator<Item = &str>> {
+        match self.lazy_part.get() {
+            None => UnitResult::Reload,
+            Some(additional) => {
+                UnitResult::Ok(additional.variable_index.keys().map(String::as_str))
+            }
+        }
+    }
+
     /// Return iterator for debug entries.
     /// Note: this method requires a full unit.
     pub fn entries_it(&self) -> UnitResult<impl Iterator<Item = &Entry>> {
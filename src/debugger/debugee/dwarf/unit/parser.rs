@@ -1,3 +1,4 @@
+use crate::debugger::debugee::dwarf::split::{SplitDwarfLoader, SplitUnit};
 use crate::debugger::debugee::dwarf::unit::{
     ArrayDie, ArraySubrangeDie, AtomicDie, BaseTypeDie, ConstTypeDie, DieAttributes, DieRange,
     DieRef, DieVariant, Entry, EnumTypeDie, EnumeratorDie, FunctionDie, InlineSubroutineDie,
@@ -12,12 +13,13 @@ use crate::debugger::rust::Environment;
 use crate::weak_error;
 use fallible_iterator::FallibleIterator;
 use gimli::{
-    AttributeValue, DW_AT_address_class, DW_AT_byte_size, DW_AT_call_column, DW_AT_call_file,
-    DW_AT_call_line, DW_AT_const_value, DW_AT_count, DW_AT_data_member_location, DW_AT_decl_file,
-    DW_AT_decl_line, DW_AT_declaration, DW_AT_discr, DW_AT_discr_value, DW_AT_encoding,
-    DW_AT_frame_base, DW_AT_language, DW_AT_linkage_name, DW_AT_location, DW_AT_lower_bound,
-    DW_AT_name, DW_AT_producer, DW_AT_specification, DW_AT_type, DW_AT_upper_bound,
-    DebuggingInformationEntry, DwAt, Range, Reader, UnitHeader, UnitOffset,
+    AttributeValue, DW_AT_abstract_origin, DW_AT_address_class, DW_AT_byte_size, DW_AT_call_column,
+    DW_AT_call_file, DW_AT_call_line, DW_AT_const_value, DW_AT_count, DW_AT_data_member_location,
+    DW_AT_decl_file, DW_AT_decl_line, DW_AT_declaration, DW_AT_discr, DW_AT_discr_value,
+    DW_AT_encoding, DW_AT_frame_base, DW_AT_language, DW_AT_linkage_name, DW_AT_location,
+    DW_AT_lower_bound, DW_AT_name, DW_AT_producer, DW_AT_specification, DW_AT_type,
+    DW_AT_upper_bound, DebuggingInformationEntry, DwAt, DwLang, Range, Reader, UnitHeader,
+    UnitOffset,
 };
 use log::warn;
 use once_cell::sync::OnceCell;
@@ -29,11 +31,23 @@ use uuid::Uuid;
 
 pub struct DwarfUnitParser<'a> {
     dwarf: &'a gimli::Dwarf<EndianArcSlice>,
+    split_loader: Option<&'a SplitDwarfLoader>,
 }
 
 impl<'a> DwarfUnitParser<'a> {
     pub fn new(dwarf: &'a gimli::Dwarf<EndianArcSlice>) -> Self {
-        Self { dwarf }
+        Self {
+            dwarf,
+            split_loader: None,
+        }
+    }
+
+    /// Load split units (from `.dwo` files or `.dwp` package) for skeleton units.
+    pub fn with_split_loader(self, loader: &'a SplitDwarfLoader) -> Self {
+        Self {
+            split_loader: Some(loader),
+            ..self
+        }
     }
 
     fn attr_to_string(
@@ -68,19 +82,9 @@ impl<'a> DwarfUnitParser<'a> {
         let mut ranges = self.dwarf.unit_ranges(&unit)?.collect::<Vec<_>>()?;
         ranges.sort_unstable_by_key(|r| r.begin);
 
-        let mut cursor = unit.header.entries(&unit.abbreviations);
-        cursor.next_dfs()?;
-        let root = cursor.current().ok_or(gimli::Error::MissingUnitDie)?;
-
-        let language = root.attr(DW_AT_language)?.and_then(|attr| {
-            if let AttributeValue::Language(lang) = attr.value() {
-                return Some(lang);
-            }
-            None
-        });
-        let producer = self.attr_to_string(&unit, root, DW_AT_producer)?;
+        let (language, producer) = self.root_attributes(&unit)?;
 
-        Ok(Unit {
+        let mut result = Unit {
             header: Mutex::new(Some(header)),
             idx: usize::MAX,
             properties: UnitProperties {
@@ -99,14 +103,65 @@ impl<'a> DwarfUnitParser<'a> {
             lazy_part: OnceCell::new(),
             language,
             producer,
-        })
+            split: None,
+        };
+
+        // line table and ranges are taken from a skeleton unit, other data from a split unit
+        if let Some(loader) = self.split_loader {
+            match loader.load(self.dwarf, &unit) {
+                Ok(Some((split, split_header))) => {
+                    let mut split_unit = split.dwarf().unit(split_header.clone())?;
+                    split.relocate(&mut split_unit);
+                    let (language, producer) =
+                        DwarfUnitParser::new(split.dwarf()).root_attributes(&split_unit)?;
+                    result.language = language.or(result.language);
+                    result.producer = producer.or(result.producer);
+                    result.properties.encoding = split_unit.encoding();
+                    result.properties.loclists_base = split_unit.loclists_base;
+                    result.header = Mutex::new(Some(split_header));
+                    result.split = Some(split);
+                }
+                Ok(None) if unit.dwo_id.is_some() => {
+                    warn!(target: "dwarf-loader", "split unit for {:?} not found", result.name);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(target: "dwarf-loader", "load split unit for {:?}: {e:#}", result.name);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Return language and producer of a unit.
+    fn root_attributes(
+        &self,
+        unit: &gimli::Unit<EndianArcSlice, usize>,
+    ) -> gimli::Result<(Option<DwLang>, Option<String>)> {
+        let mut cursor = unit.header.entries(&unit.abbreviations);
+        cursor.next_dfs()?;
+        let root = cursor.current().ok_or(gimli::Error::MissingUnitDie)?;
+
+        let language = root.attr(DW_AT_language)?.and_then(|attr| {
+            if let AttributeValue::Language(lang) = attr.value() {
+                return Some(lang);
+            }
+            None
+        });
+        let producer = self.attr_to_string(unit, root, DW_AT_producer)?;
+        Ok((language, producer))
     }
 
     pub(super) fn parse_additional(
         &self,
         header: UnitHeader<EndianArcSlice>,
+        split: Option<&SplitUnit>,
     ) -> Result<UnitLazyPart, Error> {
-        let unit = self.dwarf.unit(header)?;
+        let mut unit = self.dwarf.unit(header)?;
+        if let Some(split) = split {
+            split.relocate(&mut unit);
+        }
 
         let mut entries: Vec<Entry> = vec![];
         let mut die_ranges: Vec<DieRange> = vec![];
@@ -206,6 +261,7 @@ impl<'a> DwarfUnitParser<'a> {
                         fb_addr: die.attr(DW_AT_frame_base)?,
                         decl_file_line,
                         linkage_name,
+                        return_type_ref: die.attr(DW_AT_type)?.and_then(DieRef::from_attr),
                     };
 
                     let specification = die.attr(DW_AT_specification)?.and_then(|attr| {
@@ -252,6 +308,7 @@ impl<'a> DwarfUnitParser<'a> {
                 gimli::DW_TAG_inlined_subroutine => {
                     DieVariant::InlineSubroutine(InlineSubroutineDie {
                         base_attributes: base_attrs,
+                        origin: die.attr(DW_AT_abstract_origin)?.and_then(DieRef::from_attr),
                         call_file: die.attr(DW_AT_call_file)?.and_then(|v| match v.value() {
                             AttributeValue::FileIndex(idx) => Some(idx),
                             _ => None,
@@ -341,12 +398,22 @@ impl<'a> DwarfUnitParser<'a> {
                         byte_size: die.attr(DW_AT_byte_size)?.and_then(|val| val.udata_value()),
                     })
                 }
-                gimli::DW_TAG_member => DieVariant::TypeMember(TypeMemberDie {
-                    base_attributes: base_attrs,
-                    byte_size: die.attr(DW_AT_byte_size)?.and_then(|val| val.udata_value()),
-                    location: die.attr(DW_AT_data_member_location)?,
-                    type_ref: die.attr(DW_AT_type)?.and_then(DieRef::from_attr),
-                }),
+                gimli::DW_TAG_member => {
+                    let mb_file = die
+                        .attr(DW_AT_decl_file)?
+                        .and_then(|attr| attr.udata_value());
+                    let mb_line = die
+                        .attr(DW_AT_decl_line)?
+                        .and_then(|attr| attr.udata_value());
+
+                    DieVariant::TypeMember(TypeMemberDie {
+                        base_attributes: base_attrs,
+                        byte_size: die.attr(DW_AT_byte_size)?.and_then(|val| val.udata_value()),
+                        location: die.attr(DW_AT_data_member_location)?,
+                        type_ref: die.attr(DW_AT_type)?.and_then(DieRef::from_attr),
+                        decl_file_line: mb_file.and_then(|file_idx| Some((file_idx, mb_line?))),
+                    })
+                }
                 gimli::DW_TAG_union_type => {
                     if let Some(ref name) = base_attrs.name {
                         type_index.insert(name.to_string(), die.offset());
@@ -1,5 +1,7 @@
 use crate::debugger::address::RelocatedAddress;
 use crate::debugger::debugee::dwarf::eval::{AddressKind, ExpressionEvaluator};
+use crate::debugger::debugee::dwarf::symbol::Symbol;
+use crate::debugger::debugee::dwarf::unit::PlaceDescriptorOwned;
 use crate::debugger::debugee::dwarf::EndianArcSlice;
 use crate::debugger::debugee::{Debugee, Location};
 use crate::debugger::error::Error;
@@ -8,9 +10,10 @@ use crate::debugger::error::Error::{
 };
 use crate::debugger::register::{DwarfRegisterMap, RegisterMap};
 use crate::debugger::utils::TryGetOrInsert;
+use crate::debugger::variable::VariableIR;
 use crate::debugger::ExplorationContext;
 use crate::{debugger, resolve_unit_call, weak_error};
-use gimli::{EhFrame, FrameDescriptionEntry, RegisterRule, UnwindSection};
+use gimli::{FrameDescriptionEntry, RegisterRule};
 use nix::unistd::Pid;
 use std::mem;
 
@@ -20,6 +23,11 @@ pub struct FrameSpan {
     pub func_name: Option<String>,
     pub fn_start_ip: Option<RelocatedAddress>,
     pub ip: RelocatedAddress,
+    /// Place in source code (file, line and column) of a frame instruction,
+    /// for caller frames it is a place of a call instruction.
+    pub place: Option<PlaceDescriptorOwned>,
+    /// Frame arguments, `None` if they are not read (see [`crate::debugger::Debugger::frame_arguments`]).
+    pub args: Option<Vec<VariableIR>>,
 }
 
 pub type Backtrace = Vec<FrameSpan>;
@@ -30,15 +38,16 @@ pub type Backtrace = Vec<FrameSpan>;
 ///
 /// * `debugee`: debugee instance
 /// * `pid`: thread for unwinding
+/// * `max_frames`: max count of frames from the top of the stack, `None` for a full backtrace
 #[allow(unused)]
-pub fn unwind(debugee: &Debugee, pid: Pid) -> Result<Backtrace, Error> {
+pub fn unwind(debugee: &Debugee, pid: Pid, max_frames: Option<usize>) -> Result<Backtrace, Error> {
     #[cfg(not(feature = "libunwind"))]
     {
         let unwinder = DwarfUnwinder::new(debugee);
-        unwinder.unwind(pid)
+        unwinder.unwind(pid, max_frames)
     }
     #[cfg(feature = "libunwind")]
-    libunwind::unwind(pid)
+    libunwind::unwind(pid, max_frames)
 }
 
 /// Restore registers at chosen frame.
@@ -100,25 +109,13 @@ impl<'a> UnwindContext<'a> {
         let dwarf = &debugee.debug_info(expl_ctx.location().pc)?;
         let mut next_registers = registers.clone();
         let registers_snap = registers;
-        let fde = match dwarf.eh_frame.fde_for_address(
-            &dwarf.bases,
-            expl_ctx.location().global_pc.into(),
-            EhFrame::cie_from_offset,
-        ) {
-            Ok(fde) => fde,
-            Err(gimli::Error::NoUnwindInfoForAddress) => {
-                return Ok(None);
-            }
-            Err(e) => return Err(e.into()),
-        };
 
         let mut ctx = Box::new(gimli::UnwindContext::new());
-        let row = fde.unwind_info_for_address(
-            &dwarf.eh_frame,
-            &dwarf.bases,
-            &mut ctx,
-            expl_ctx.location().global_pc.into(),
-        )?;
+        let Some((fde, row)) =
+            dwarf.unwind_info_for_address(&mut ctx, expl_ctx.location().global_pc)?
+        else {
+            return Ok(None);
+        };
         let cfa = dwarf.evaluate_cfa(debugee, &registers_snap, row, expl_ctx)?;
 
         let mut lazy_evaluator = None;
@@ -238,7 +235,13 @@ impl<'a> DwarfUnwinder<'a> {
     /// # Arguments
     ///
     /// * pid: thread for unwinding
-    pub fn unwind(&self, pid: Pid) -> Result<Vec<FrameSpan>, Error> {
+    /// * max_frames: max count of frames from the top of the stack, `None` for a full backtrace
+    pub fn unwind(&self, pid: Pid, max_frames: Option<usize>) -> Result<Vec<FrameSpan>, Error> {
+        let max_frames = max_frames.unwrap_or(usize::MAX);
+        if max_frames == 0 {
+            return Ok(vec![]);
+        }
+
         let frame_0_location = self
             .debugee
             .tracee_ctl()
@@ -255,10 +258,8 @@ impl<'a> DwarfUnwinder<'a> {
             return Ok(vec![]);
         };
 
-        let function = self
-            .debugee
-            .debug_info(ctx.location().pc)?
-            .find_function_by_pc(ctx.location().global_pc)?;
+        let debug_info = self.debugee.debug_info(ctx.location().pc)?;
+        let function = debug_info.find_function_by_pc(ctx.location().global_pc)?;
         let fn_start_at = function
             .and_then(|func| {
                 func.prolog_start_place().ok().map(|prolog| {
@@ -270,13 +271,21 @@ impl<'a> DwarfUnwinder<'a> {
             .transpose()?;
 
         let mut bt = vec![FrameSpan {
-            func_name: function.and_then(|func| func.full_name()),
+            func_name: function.and_then(|func| func.display_name()).or_else(|| {
+                debug_info
+                    .find_symbol_by_pc(ctx.location().global_pc)
+                    .map(Symbol::display_name)
+            }),
             fn_start_ip: fn_start_at,
             ip: ctx.location().pc,
+            ..Default::default()
         }];
 
         // start unwind
         while let Some(return_addr) = unwind_ctx.return_address() {
+            if bt.len() >= max_frames {
+                break;
+            }
             let prev_loc = bt.last().expect("backtrace len > 0");
             if prev_loc.ip == return_addr {
                 break;
@@ -294,10 +303,8 @@ impl<'a> DwarfUnwinder<'a> {
                 Some(ctx) => ctx,
             };
 
-            let function = self
-                .debugee
-                .debug_info(next_location.pc)?
-                .find_function_by_pc(next_location.global_pc)?;
+            let debug_info = self.debugee.debug_info(next_location.pc)?;
+            let function = debug_info.find_function_by_pc(next_location.global_pc)?;
             let fn_start_at = function
                 .and_then(|func| {
                     func.prolog_start_place().ok().map(|prolog| {
@@ -309,9 +316,14 @@ impl<'a> DwarfUnwinder<'a> {
                 .transpose()?;
 
             let span = FrameSpan {
-                func_name: function.and_then(|func| func.full_name()),
+                func_name: function.and_then(|func| func.display_name()).or_else(|| {
+                    debug_info
+                        .find_symbol_by_pc(next_location.global_pc)
+                        .map(Symbol::display_name)
+                }),
                 fn_start_ip: fn_start_at,
                 ip: next_location.pc,
+                ..Default::default()
             };
             bt.push(span);
         }
@@ -358,11 +370,13 @@ impl<'a> DwarfUnwinder<'a> {
             unwind_ctx = UnwindContext::next(unwind_ctx, &ctx)?.ok_or(UnwindNoContext)?;
         }
 
-        if let Ok(ip) = unwind_ctx.registers().value(gimli::Register(16)) {
-            registers.update(gimli::Register(16), ip);
-        }
-        if let Ok(sp) = unwind_ctx.registers().value(gimli::Register(7)) {
-            registers.update(gimli::Register(7), sp);
+        // copy all registers recovered by call frame information (ip, sp and callee-saved
+        // registers), so variables stored in registers are read correctly at selected frame
+        let frame_registers = unwind_ctx.registers();
+        for reg in (0..=16).map(gimli::Register) {
+            if let Ok(value) = frame_registers.value(reg) {
+                registers.update(reg, value);
+            }
         }
 
         Ok(())
@@ -411,6 +425,7 @@ impl<'a> DwarfUnwinder<'a> {
 mod libunwind {
     use super::FrameSpan;
     use crate::debugger::address::RelocatedAddress;
+    use crate::debugger::debugee::dwarf::symbol;
     use crate::debugger::error::Error;
     use crate::debugger::register::DwarfRegisterMap;
     use crate::debugger::unwind::Backtrace;
@@ -422,22 +437,25 @@ mod libunwind {
     /// # Arguments
     ///
     /// * `pid`: thread for unwinding.
-    pub(super) fn unwind(pid: Pid) -> Result<Backtrace, Error> {
+    /// * `max_frames`: max count of frames from the top of the stack, `None` for a full backtrace.
+    pub(super) fn unwind(pid: Pid, max_frames: Option<usize>) -> Result<Backtrace, Error> {
+        let max_frames = max_frames.unwrap_or(usize::MAX);
         let state = PTraceState::new(pid.as_raw() as u32)?;
         let address_space = AddressSpace::new(Accessors::ptrace(), Byteorder::DEFAULT)?;
         let mut cursor = Cursor::remote(&address_space, &state)?;
         let mut backtrace = vec![];
 
-        loop {
+        while backtrace.len() < max_frames {
             let ip = cursor.register(RegNum::IP)?;
             match (cursor.procedure_info(), cursor.procedure_name()) {
                 (Ok(ref info), Ok(ref name)) if ip == info.start_ip() + name.offset() => {
-                    let fn_name = format!("{:#}", rustc_demangle::demangle(name.name()));
+                    let fn_name = symbol::display_name(name.name());
 
                     backtrace.push(FrameSpan {
                         func_name: Some(fn_name),
                         fn_start_ip: Some(info.start_ip().into()),
                         ip: ip.into(),
+                        ..Default::default()
                     });
                 }
                 _ => {
@@ -445,6 +463,7 @@ mod libunwind {
                         func_name: None,
                         fn_start_ip: None,
                         ip: ip.into(),
+                        ..Default::default()
                     });
                 }
             }
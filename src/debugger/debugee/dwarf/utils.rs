@@ -136,6 +136,46 @@ impl<T> PathSearchIndex<T> {
             .filter_map(|tail_idx| self.index.data.get(&(*head_nonce, *tail_idx)))
             .collect()
     }
+
+    /// Return all last path parts (heads) in the index.
+    pub fn heads(&self) -> impl Iterator<Item = &str> {
+        self.index.heads.keys().map(String::as_str)
+    }
+}
+
+/// Sorted multimap of names into indexes of units that declare these names.
+/// It is built once for all units of a debug information file, so a search by name
+/// visits only the relevant units instead of all of them.
+#[derive(Clone, Debug, Default)]
+pub struct NameIndex {
+    names: Vec<(String, usize)>,
+}
+
+impl NameIndex {
+    /// Create a new name index.
+    ///
+    /// # Arguments
+    ///
+    /// * `names`: pairs of a name and an index of unit where this name is declared
+    pub fn new(names: impl IntoIterator<Item = (String, usize)>) -> Self {
+        let mut names: Vec<_> = names.into_iter().collect();
+        names.sort_unstable();
+        names.dedup();
+        Self { names }
+    }
+
+    /// Return indexes of units that declare a name, in ascending order.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: needle name
+    pub fn get<'a>(&'a self, name: &'a str) -> impl Iterator<Item = usize> + 'a {
+        let start = self.names.partition_point(|(n, _)| n.as_str() < name);
+        self.names[start..]
+            .iter()
+            .take_while(move |(n, _)| n == name)
+            .map(|(_, unit_idx)| *unit_idx)
+    }
 }
 
 #[cfg(test)]
@@ -231,4 +271,21 @@ mod test {
 
         assert_eq!(index.get("/home/bs/.cargo/registry/src/index.crates.io-6f17d22bba15001f/tokio-1.28.1/src/runtime/task/raw.rs"), vec![&1]);
     }
+
+    #[test]
+    pub fn test_name_index() {
+        let index = NameIndex::new([
+            ("fn2".to_string(), 3),
+            ("fn1".to_string(), 2),
+            ("fn1".to_string(), 0),
+            ("fn2".to_string(), 3),
+            ("fn10".to_string(), 1),
+        ]);
+
+        assert_eq!(index.get("fn1").collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(index.get("fn2").collect::<Vec<_>>(), vec![3]);
+        assert_eq!(index.get("fn10").collect::<Vec<_>>(), vec![1]);
+        assert_eq!(index.get("fn").count(), 0);
+        assert_eq!(index.get("fn3").count(), 0);
+    }
 }
@@ -11,30 +11,36 @@ pub use rendezvous::RendezvousError;
 
 use crate::debugger::address::{GlobalAddress, RelocatedAddress};
 use crate::debugger::breakpoint::{Breakpoint, BrkptType};
+use crate::debugger::coredump::CoreDump;
 use crate::debugger::debugee::disasm::Disassembler;
 use crate::debugger::debugee::dwarf::unit::PlaceDescriptorOwned;
 use crate::debugger::debugee::dwarf::unwind;
 use crate::debugger::debugee::dwarf::unwind::Backtrace;
 use crate::debugger::debugee::dwarf::DebugInformation;
 use crate::debugger::debugee::registry::DwarfRegistry;
-use crate::debugger::debugee::rendezvous::Rendezvous;
-use crate::debugger::debugee::tracee::{Tracee, TraceeCtl};
+use crate::debugger::debugee::rendezvous::{LinkMapState, Rendezvous};
+use crate::debugger::debugee::tracee::{ThreadStat, Tracee, TraceeCtl};
 use crate::debugger::debugee::tracer::{StopReason, TraceContext, Tracer};
 use crate::debugger::error::Error;
-use crate::debugger::error::Error::{FunctionNotFound, MappingOffsetNotFound, TraceeNotFound};
+use crate::debugger::error::Error::{
+    DisAsmRange, FunctionNotFound, MappingOffsetNotFound, TraceeNotFound,
+};
+use crate::debugger::memory;
 use crate::debugger::process::{Child, Installed};
+use crate::debugger::progress::Task;
 use crate::debugger::register::DwarfRegisterMap;
 use crate::debugger::unwind::FrameSpan;
 use crate::debugger::Error::FunctionRangeNotFound;
-use crate::debugger::{ExplorationContext, PlaceDescriptor};
+use crate::debugger::{Debugger, ExplorationContext, PlaceDescriptor};
 use crate::{muted_error, print_warns, weak_error};
 use log::{info, warn};
 use nix::unistd::Pid;
 use nix::NixPath;
-use object::{Object, ObjectSection};
+use object::{Object, ObjectSection, SymbolKind};
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::fs;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -51,18 +57,71 @@ pub struct FrameInfo {
     pub return_addr: Option<RelocatedAddress>,
 }
 
+/// Thread, process or shared library event, observed while debugee is running.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebugeeEvent {
+    /// New thread is created.
+    ThreadCreated(Pid),
+    /// Thread exited (except the main thread, its exit is a debugee exit).
+    ThreadExited(Pid),
+    /// Shared library is loaded by the dynamic linker, contains a library path.
+    SharedLibLoaded(PathBuf),
+    /// Debugee thread created a child process with `fork` or `vfork`,
+    /// a child process is not debugged.
+    ProcessForked { parent: Pid, child: Pid },
+}
+
 /// Debugee thread description.
 pub struct ThreadSnapshot {
     /// Running thread info - pid, number and status.
     pub thread: Tracee,
-    /// Backtrace
+    /// Backtrace, contains only top frames if `bt_truncated` is true
+    /// (see [`ThreadSnapshot::fetch_backtrace`]).
     pub bt: Option<Backtrace>,
+    /// True if backtrace is limited by a depth and there are more frames at the stack.
+    pub bt_truncated: bool,
     /// Place in source code where thread is stopped
     pub place: Option<PlaceDescriptorOwned>,
     /// On focus frame number (if focus on this thread)
     pub focus_frame: Option<usize>,
     /// True if thread in focus, false elsewhere
     pub in_focus: bool,
+    /// Thread name (from `/proc/<pid>/task/<tid>/comm`).
+    pub name: Option<String>,
+    /// Kernel state, CPU and times of a thread.
+    pub stat: Option<ThreadStat>,
+}
+
+impl ThreadSnapshot {
+    /// Unwind further frames of a truncated backtrace, nothing happens if backtrace
+    /// is complete or thread is running.
+    ///
+    /// # Arguments
+    ///
+    /// * `debugger`: debugger instance
+    /// * `max_frames`: max count of frames from the top of the stack, `None` for a full backtrace
+    pub fn fetch_backtrace(
+        &mut self,
+        debugger: &Debugger,
+        max_frames: Option<usize>,
+    ) -> Result<(), Error> {
+        let len = self.bt.as_ref().map(Vec::len).unwrap_or_default();
+        if !self.bt_truncated || max_frames.is_some_and(|max| max <= len) {
+            return Ok(());
+        }
+        let (bt, truncated) = match max_frames {
+            None => (debugger.backtrace(self.thread.pid)?, false),
+            Some(max) => {
+                let mut bt = debugger.backtrace_limited(self.thread.pid, max + 1)?;
+                let truncated = bt.len() > max;
+                bt.truncate(max);
+                (bt, truncated)
+            }
+        };
+        self.bt = Some(bt);
+        self.bt_truncated = truncated;
+        Ok(())
+    }
 }
 
 /// Thread position.
@@ -87,6 +146,67 @@ pub struct FunctionAssembly {
     pub instructions: Vec<disasm::Instruction>,
 }
 
+/// Max size of a memory range that can be disassembled at once.
+const DISASM_RANGE_LIMIT: usize = 64 * 1024;
+/// Max length of x86-64 instruction in bytes.
+const MAX_INSTRUCTION_LEN: usize = 15;
+/// Count of top frames unwound for a thread snapshot.
+pub const SNAPSHOT_BT_FRAMES: usize = 64;
+
+/// Code for disassemble.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisasmTarget {
+    /// Function in focus.
+    CurrentFunction,
+    /// Memory range, end address is exclusive.
+    Range(RelocatedAddress, RelocatedAddress),
+}
+
+/// Assembly instruction annotated with a source code place and debugger state.
+#[derive(Debug, Clone)]
+pub struct AnnotatedInstruction {
+    pub address: RelocatedAddress,
+    /// Instruction mnemonic.
+    pub mnemonic: Option<String>,
+    /// Operands string representation.
+    pub operands: Option<String>,
+    /// Source code place to which the instruction belongs, if known.
+    pub place: Option<PlaceDescriptorOwned>,
+    /// True if instruction is located at the program counter of a frame in focus.
+    pub is_pc: bool,
+    /// Number of a user defined breakpoint installed at the instruction.
+    pub breakpoint: Option<u32>,
+}
+
+pub struct Disassembly {
+    /// Function name, `None` if arbitrary memory range is disassembled.
+    pub name: Option<String>,
+    pub instructions: Vec<AnnotatedInstruction>,
+}
+
+/// Function found in debug information.
+#[derive(Debug, Clone)]
+pub struct FunctionInfo {
+    /// Full function name (with namespace).
+    pub name: String,
+    pub linkage_name: Option<String>,
+    /// Declaration file and line.
+    pub decl: Option<(PathBuf, u64)>,
+    /// Function code address ranges.
+    pub ranges: Vec<Range<GlobalAddress>>,
+}
+
+/// Symbol found in symbol table.
+#[derive(Debug, Clone)]
+pub struct SymbolInfo {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Symbol address range, empty if symbol size is unknown.
+    pub range: Range<GlobalAddress>,
+    /// Source file of a symbol (for symbols in code with debug information).
+    pub file: Option<PathBuf>,
+}
+
 pub struct FunctionRange<'a> {
     pub name: Option<String>,
     pub stop_place: PlaceDescriptor<'a>,
@@ -105,6 +225,8 @@ pub struct Debugee {
     path: PathBuf,
     /// elf file sections (name => address).
     object_sections: HashMap<String, u64>,
+    /// program entry point (`_start` function) address.
+    entry_point: GlobalAddress,
     /// rendezvous struct maintained by dyn linker.
     rendezvous: Option<Rendezvous>,
     /// Registry for dwarf information of program and shared libraries.
@@ -113,6 +235,8 @@ pub struct Debugee {
     disassembly: Disassembler,
     /// Loaded libthread_db.
     libthread_db: Arc<thread_db::Lib>,
+    /// Shared libraries from the last read of the link map list.
+    linked_libs: Vec<PathBuf>,
 }
 
 impl Debugee {
@@ -140,11 +264,13 @@ impl Debugee {
                 .sections()
                 .filter_map(|section| Some((section.name().ok()?.to_string(), section.address())))
                 .collect(),
+            entry_point: GlobalAddress::from(object.entry()),
             rendezvous: None,
             tracer: Tracer::new(process.pid()),
             dwarf_registry: registry,
             disassembly: Disassembler::new()?,
             libthread_db: Arc::new(thread_db::Lib::try_load()?),
+            linked_libs: vec![],
         })
     }
 
@@ -161,13 +287,13 @@ impl Debugee {
         let main_dwarf = registry
             .find_main_program_dwarf()
             .ok_or(Error::NoDebugInformation("executable object"))?;
-        let main_dwarf_offset = registry
-            .find_mapping_offset_for_file(main_dwarf)
-            .ok_or(MappingOffsetNotFound("unknown segment"))?;
         let object_sections = object
             .sections()
             .filter_map(|section| Some((section.name().ok()?.to_string(), section.address())))
             .collect();
+        let main_dwarf_offset = registry
+            .find_relocation_offset(main_dwarf.pathname(), dynamic_sect_addr(&object_sections))
+            .ok_or(MappingOffsetNotFound("unknown segment"))?;
 
         let mut debugee = Self {
             execution_status: ExecutionStatus::InProgress,
@@ -178,6 +304,7 @@ impl Debugee {
                 &object_sections,
             )?),
             object_sections,
+            entry_point: GlobalAddress::from(object.entry()),
             tracer: Tracer::new_external(
                 process.pid(),
                 &process
@@ -188,6 +315,7 @@ impl Debugee {
             dwarf_registry: registry,
             disassembly: Disassembler::new()?,
             libthread_db: Arc::new(thread_db::Lib::try_load()?),
+            linked_libs: vec![],
         };
 
         debugee.attach_libthread_db();
@@ -196,6 +324,56 @@ impl Debugee {
         Ok(debugee)
     }
 
+    /// Create [`Debugee`] for a dumped process, VAS mappings are taken from a core file.
+    /// Shared libraries are found by the dynamic linker link map from a core image.
+    /// libthread_db isn't used, it requires a live process.
+    pub fn new_from_core(
+        path: &Path,
+        process: &Child<Installed>,
+        object: &object::File,
+        core: &CoreDump,
+    ) -> Result<Self, Error> {
+        let dwarf_builder = dwarf::DebugInformationBuilder;
+        let dwarf = dwarf_builder.build(path, object)?;
+        let mut registry = DwarfRegistry::new(process.pid(), path.to_path_buf(), dwarf);
+        registry.set_core_mappings(core.mappings().to_vec());
+        print_warns!(registry.update_mappings(true)?);
+
+        let main_dwarf = registry
+            .find_main_program_dwarf()
+            .ok_or(Error::NoDebugInformation("executable object"))?;
+        let object_sections = object
+            .sections()
+            .filter_map(|section| Some((section.name().ok()?.to_string(), section.address())))
+            .collect();
+        let main_dwarf_offset = registry
+            .find_relocation_offset(main_dwarf.pathname(), dynamic_sect_addr(&object_sections))
+            .ok_or(MappingOffsetNotFound("unknown segment"))?;
+
+        let threads: Vec<Pid> = core.threads().iter().map(|thread| thread.tid).collect();
+        let mut debugee = Self {
+            execution_status: ExecutionStatus::InProgress,
+            path: path.into(),
+            rendezvous: Some(Rendezvous::new(
+                process.pid(),
+                main_dwarf_offset,
+                &object_sections,
+            )?),
+            object_sections,
+            entry_point: GlobalAddress::from(object.entry()),
+            tracer: Tracer::new_external(process.pid(), &threads),
+            dwarf_registry: registry,
+            disassembly: Disassembler::new()?,
+            libthread_db: Arc::new(thread_db::Lib::try_load()?),
+            linked_libs: vec![],
+        };
+        debugee.update_debug_info_registry(true)?;
+        // shared library events are not reported for a dumped process
+        debugee.tracer.events.clear();
+
+        Ok(debugee)
+    }
+
     /// Create new [`Debugee`] with same dwarf context.
     ///
     /// # Arguments
@@ -206,14 +384,62 @@ impl Debugee {
             execution_status: ExecutionStatus::Unload,
             path: self.path.clone(),
             object_sections: self.object_sections.clone(),
+            entry_point: self.entry_point,
             rendezvous: None,
             tracer: Tracer::new(proc),
             dwarf_registry: self.dwarf_registry.extend(proc),
             disassembly: Disassembler::new().expect("infallible"),
             libthread_db: self.libthread_db.clone(),
+            linked_libs: vec![],
         }
     }
 
+    /// Create [`Debugee`] for a copy of a running debugee process created by `fork` call.
+    /// A copy has the same memory layout and debug information, but only one thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `proc`: pid of a process copy.
+    pub fn fork(&self, proc: Pid) -> Result<Self, Error> {
+        let mut registry = self.dwarf_registry.extend(proc);
+        print_warns!(registry.update_mappings(false)?);
+
+        let main_dwarf = registry
+            .find_main_program_dwarf()
+            .ok_or(Error::NoDebugInformation("executable object"))?;
+        let main_dwarf_offset = registry
+            .find_relocation_offset(
+                main_dwarf.pathname(),
+                dynamic_sect_addr(&self.object_sections),
+            )
+            .ok_or(MappingOffsetNotFound("unknown segment"))?;
+
+        let mut debugee = Self {
+            execution_status: ExecutionStatus::InProgress,
+            path: self.path.clone(),
+            rendezvous: Some(Rendezvous::new(
+                proc,
+                main_dwarf_offset,
+                &self.object_sections,
+            )?),
+            object_sections: self.object_sections.clone(),
+            entry_point: self.entry_point,
+            tracer: Tracer::new(proc),
+            dwarf_registry: registry,
+            disassembly: Disassembler::new()?,
+            libthread_db: self.libthread_db.clone(),
+            linked_libs: self.linked_libs.clone(),
+        };
+        debugee.attach_libthread_db();
+
+        Ok(debugee)
+    }
+
+    /// Return program entry point address (not relocated).
+    pub fn entry_point(&self) -> GlobalAddress {
+        self.entry_point
+    }
+
     pub fn execution_status(&self) -> ExecutionStatus {
         self.execution_status
     }
@@ -237,6 +463,11 @@ impl Debugee {
         self.rendezvous.as_ref().expect("rendezvous must exists")
     }
 
+    /// Return debugee [`Tracer`]
+    pub fn tracer(&self) -> &Tracer {
+        &self.tracer
+    }
+
     /// Return debugee [`Tracer`]
     pub fn tracer_mut(&mut self) -> &mut Tracer {
         &mut self.tracer
@@ -276,14 +507,20 @@ impl Debugee {
                         let main_dwarf = self.program_debug_info()?;
                         self.rendezvous = Some(Rendezvous::new(
                             tid,
-                            self.mapping_offset_for_file(main_dwarf)?,
+                            self.relocation_offset(
+                                main_dwarf,
+                                dynamic_sect_addr(&self.object_sections),
+                            )?,
                             &self.object_sections,
                         )?);
                         self.attach_libthread_db();
                         self.update_debug_info_registry(true)?;
                     }
                     Some(BrkptType::LinkerMapFn) => {
-                        self.update_debug_info_registry(false)?;
+                        // link map list may be incomplete until mapping change is complete
+                        if self.rendezvous().state()? == LinkMapState::Consistent {
+                            self.update_debug_info_registry(false)?;
+                        }
                     }
                     _ => {}
                 }
@@ -304,11 +541,20 @@ impl Debugee {
     /// * `quite`: true for enable logging of library names
     fn update_debug_info_registry(&mut self, quite: bool) -> Result<(), Error> {
         let lmaps = self.rendezvous().link_maps()?;
-        let current_deps = lmaps
+        let current_deps: Vec<_> = lmaps
             .into_iter()
             .map(|lm| PathBuf::from(&lm.name))
             .collect();
 
+        for lib in &current_deps {
+            if !lib.as_os_str().is_empty() && !self.linked_libs.contains(lib) {
+                self.tracer
+                    .events
+                    .push(DebugeeEvent::SharedLibLoaded(lib.clone()));
+            }
+        }
+        self.linked_libs.clone_from(&current_deps);
+
         let reload_plan = self.dwarf_registry.reload_plan(current_deps);
 
         for lib_to_del in reload_plan.to_del {
@@ -325,11 +571,20 @@ impl Debugee {
         Ok(())
     }
 
+    /// Return thread, process and shared library events observed since the previous call.
+    pub fn take_events(&mut self) -> Vec<DebugeeEvent> {
+        self.tracer.take_events()
+    }
+
     #[inline(always)]
     pub fn tracee_ctl(&self) -> &TraceeCtl {
         &self.tracer.tracee_ctl
     }
 
+    pub fn tracee_ctl_mut(&mut self) -> &mut TraceeCtl {
+        &mut self.tracer.tracee_ctl
+    }
+
     pub fn frame_info(&self, ctx: &ExplorationContext) -> Result<FrameInfo, Error> {
         let dwarf = self.debug_info(ctx.location().pc)?;
         let func = dwarf
@@ -338,7 +593,8 @@ impl Debugee {
 
         let base_addr = func.frame_base_addr(ctx, self)?;
         let cfa = dwarf.get_cfa(self, ctx)?;
-        let backtrace = self.unwind(ctx.pid_on_focus())?;
+        // frame in focus and its caller frame are enough
+        let backtrace = self.unwind(ctx.pid_on_focus(), Some(ctx.frame() as usize + 2))?;
         let (bt_frame_num, frame) = backtrace
             .iter()
             .enumerate()
@@ -359,6 +615,37 @@ impl Debugee {
         Ok(threads
             .into_iter()
             .filter_map(|tracee| {
+                // there is no procfs entry for a thread of a core file or a remote target
+                let is_local = memory::backend(tracee.pid).is_none();
+                let name = is_local
+                    .then(|| {
+                        fs::read_to_string(format!(
+                            "/proc/{}/task/{}/comm",
+                            self.tracee_ctl().proc_pid(),
+                            tracee.pid
+                        ))
+                        .ok()
+                    })
+                    .flatten()
+                    .map(|name| name.trim_end().to_string());
+                let stat = is_local
+                    .then(|| ThreadStat::read(self.tracee_ctl().proc_pid(), tracee.pid))
+                    .flatten();
+
+                // running thread (in non-stop mode) has no location and can't be unwound
+                if !tracee.is_stopped() {
+                    return Some(ThreadSnapshot {
+                        name,
+                        stat,
+                        in_focus: false,
+                        thread: tracee,
+                        bt: None,
+                        bt_truncated: false,
+                        place: None,
+                        focus_frame: None,
+                    });
+                }
+
                 let _tracee_ctx;
                 let tracee_ctx = if tracee.pid == ctx.pid_on_focus() {
                     ctx
@@ -368,7 +655,19 @@ impl Debugee {
                     &_tracee_ctx
                 };
 
-                let mb_bt = weak_error!(self.unwind(tracee_ctx.pid_on_focus()));
+                // only top frames are unwound, it is too slow to unwind deep stacks of all threads,
+                // one more frame is unwound to know if backtrace is truncated
+                let max_frames = if tracee.pid == ctx.pid_on_focus() {
+                    SNAPSHOT_BT_FRAMES.max(ctx.frame() as usize + 1)
+                } else {
+                    SNAPSHOT_BT_FRAMES
+                };
+                let mut mb_bt =
+                    weak_error!(self.unwind(tracee_ctx.pid_on_focus(), Some(max_frames + 1)));
+                let bt_truncated = mb_bt.as_ref().is_some_and(|bt| bt.len() > max_frames);
+                if let Some(bt) = mb_bt.as_mut() {
+                    bt.truncate(max_frames);
+                }
                 let frame_num = mb_bt.as_ref().and_then(|bt| {
                     bt.iter()
                         .enumerate()
@@ -385,9 +684,12 @@ impl Debugee {
                 });
 
                 Some(ThreadSnapshot {
+                    name,
+                    stat,
                     in_focus: tracee.pid == ctx.pid_on_focus(),
                     thread: tracee,
                     bt: mb_bt,
+                    bt_truncated,
                     place: place.map(|p| p.to_owned()),
                     focus_frame: frame_num,
                 })
@@ -464,24 +766,60 @@ impl Debugee {
             .ok_or(MappingOffsetNotFound("address out of bounds"))
     }
 
-    /// Return mapped memory region offset for region.
+    /// Return offset of mapped memory region to which an object file address belongs.
     ///
     /// # Arguments
     ///
-    /// * `dwarf`: debug information (with file path inside) for determine memory region.
-    pub fn mapping_offset_for_file(&self, dwarf: &DebugInformation) -> Result<usize, Error> {
+    /// * `dwarf`: debug information (with file path inside) for determine an object file.
+    /// * `addr`: object file address
+    pub fn relocation_offset(
+        &self,
+        dwarf: &DebugInformation,
+        addr: GlobalAddress,
+    ) -> Result<usize, Error> {
         self.dwarf_registry
-            .find_mapping_offset_for_file(dwarf)
+            .find_relocation_offset(dwarf.pathname(), addr)
             .ok_or(MappingOffsetNotFound("unknown segment"))
     }
 
+    /// Return offset of mapped memory region to which an object file address belongs.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc`: VAS address, determine an object file.
+    /// * `addr`: object file address
+    pub fn relocation_offset_by_pc(
+        &self,
+        pc: RelocatedAddress,
+        addr: GlobalAddress,
+    ) -> Result<usize, Error> {
+        self.dwarf_registry
+            .find_relocation_offset_by_pc(pc, addr)
+            .ok_or(MappingOffsetNotFound("address out of bounds"))
+    }
+
     /// Unwind debugee thread stack and return a backtrace.
     ///
     /// # Arguments
     ///
     /// * `pid`: thread for unwinding
-    pub fn unwind(&self, pid: Pid) -> Result<Backtrace, Error> {
-        unwind::unwind(self, pid)
+    /// * `max_frames`: max count of frames from the top of the stack, `None` for a full backtrace
+    pub fn unwind(&self, pid: Pid, max_frames: Option<usize>) -> Result<Backtrace, Error> {
+        let mut bt = unwind::unwind(self, pid, max_frames)?;
+        for (num, frame) in bt.iter_mut().enumerate() {
+            // ip of a caller frame is a return address, that may belong to the next line,
+            // so a place is searched for a call instruction
+            let pc = if num == 0 {
+                frame.ip
+            } else {
+                frame.ip.offset(-1)
+            };
+            frame.place = self.debug_info(pc).ok().and_then(|debug_info| {
+                let place = debug_info.find_place_from_pc(pc.into_global(self).ok()?);
+                Some(weak_error!(place)??.to_owned())
+            });
+        }
+        Ok(bt)
     }
 
     /// Restore registers at chosen frame.
@@ -538,6 +876,116 @@ impl Debugee {
         })
     }
 
+    /// Return a list of disassembled instructions annotated with source code places,
+    /// a program counter and installed breakpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`: exploration context
+    /// * `target`: function in focus or a memory range
+    /// * `breakpoints`: list of active breakpoints
+    pub fn disassemble(
+        &self,
+        ctx: &ExplorationContext,
+        target: DisasmTarget,
+        breakpoints: &[&Breakpoint],
+    ) -> Result<Disassembly, Error> {
+        let (name, glob_start, start, end) = match target {
+            DisasmTarget::CurrentFunction => {
+                let debug_information = self.debug_info(ctx.location().pc)?;
+                let function = debug_information
+                    .find_function_by_pc(ctx.location().global_pc)?
+                    .ok_or(FunctionNotFound(ctx.location().global_pc))?;
+                let glob_start = function.start_instruction()?;
+                (
+                    function.full_name(),
+                    glob_start,
+                    glob_start.relocate_to_segment(self, debug_information)?,
+                    function
+                        .end_instruction()?
+                        .relocate_to_segment(self, debug_information)?,
+                )
+            }
+            DisasmTarget::Range(start, end) => {
+                if start >= end {
+                    return Err(DisAsmRange(
+                        "range end must be greater than range start".to_string(),
+                    ));
+                }
+                if usize::from(end) - usize::from(start) > DISASM_RANGE_LIMIT {
+                    return Err(DisAsmRange(format!(
+                        "range is larger than {DISASM_RANGE_LIMIT} bytes"
+                    )));
+                }
+                (None, start.into_global(self)?, start, end)
+            }
+        };
+
+        let mapping_offset = usize::from(start) - usize::from(glob_start);
+        let debug_information = self.debug_info(start).ok();
+        let instructions = self
+            .disassembly
+            .disasm_range(self, glob_start, start, end, breakpoints)?
+            .into_iter()
+            .map(|instruction| {
+                let address =
+                    RelocatedAddress::from(usize::from(instruction.address) + mapping_offset);
+                let place = debug_information
+                    .and_then(|dwarf| dwarf.find_place_from_pc(instruction.address).ok()?)
+                    .map(|place| place.to_owned());
+                let breakpoint = breakpoints
+                    .iter()
+                    .find(|brkpt| {
+                        brkpt.addr == address && matches!(brkpt.r#type(), BrkptType::UserDefined)
+                    })
+                    .map(|brkpt| brkpt.number());
+
+                AnnotatedInstruction {
+                    address,
+                    mnemonic: instruction.mnemonic,
+                    operands: instruction.operands,
+                    place,
+                    is_pc: address == ctx.location().pc,
+                    breakpoint,
+                }
+            })
+            .collect();
+
+        Ok(Disassembly { name, instructions })
+    }
+
+    /// Return an instruction at the program counter of a frame in focus and up to `count`
+    /// instructions before and after it. Instructions before the program counter are returned
+    /// only if a function in focus is known from debug information.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`: exploration context
+    /// * `count`: max count of instructions before and after the program counter
+    /// * `breakpoints`: list of active breakpoints
+    pub fn disassemble_around_pc(
+        &self,
+        ctx: &ExplorationContext,
+        count: usize,
+        breakpoints: &[&Breakpoint],
+    ) -> Result<Disassembly, Error> {
+        let function =
+            muted_error!(self.disassemble(ctx, DisasmTarget::CurrentFunction, breakpoints));
+        if let Some(mut disasm) = function {
+            if let Some(pc_idx) = disasm.instructions.iter().position(|ins| ins.is_pc) {
+                disasm.instructions.truncate(pc_idx + count + 1);
+                disasm.instructions.drain(..pc_idx.saturating_sub(count));
+                return Ok(disasm);
+            }
+        }
+
+        let pc = ctx.location().pc;
+        let end = pc.offset(((count + 1) * MAX_INSTRUCTION_LEN) as isize);
+        let mut disasm = self.disassemble(ctx, DisasmTarget::Range(pc, end), breakpoints)?;
+        disasm.instructions.truncate(count + 1);
+        Ok(disasm)
+    }
+
     /// Return two place descriptors, at the start and at the end of the current function.
     pub fn function_range(&self, ctx: &ExplorationContext) -> Result<FunctionRange, Error> {
         let debug_information = self.debug_info(ctx.location().pc)?;
@@ -582,7 +1030,14 @@ impl Debugee {
     }
 }
 
+/// Return address of `.dynamic` section in the object file, a rendezvous structure is searched
+/// starting from this section.
+fn dynamic_sect_addr(sections: &HashMap<String, u64>) -> GlobalAddress {
+    GlobalAddress::from(sections.get(".dynamic").copied().unwrap_or_default())
+}
+
 /// Parse dwarf information from new dependency.
+
 fn parse_dependency(dep_file: impl Into<PathBuf>) -> Result<Option<DebugInformation>, Error> {
     let dep_file = dep_file.into();
 
@@ -607,11 +1062,13 @@ fn parse_dependencies_into_registry(
     deps: impl Iterator<Item = impl Into<PathBuf>>,
     quiet: bool,
 ) {
+    let deps: Vec<PathBuf> = deps.map(|dep| dep.into()).collect();
+    let task = Task::start("load shared libraries", deps.len() as u64);
     let dwarfs: Vec<_> = deps
-        .map(|dep| dep.into())
-        .collect::<Vec<_>>()
         .into_par_iter()
         .filter_map(|dep| {
+            // rest of libraries are skipped if loading is cancelled
+            task.advance(1).ok()?;
             let parse_result = parse_dependency(&dep);
             match parse_result {
                 Ok(mb_dep) => mb_dep.map(|dwarf| {
@@ -629,6 +1086,10 @@ fn parse_dependencies_into_registry(
         })
         .collect();
 
+    if task.is_cancelled() {
+        warn!(target: "debugger", "shared libraries loading cancelled, debug information may be incomplete");
+    }
+
     dwarfs.into_iter().for_each(|(dep_name, dwarf)| {
         if let Err(e) = registry.add(&dep_name, dwarf) {
             warn!(target: "debugger", "broken dependency {:?}: {:#}", dep_name, e);
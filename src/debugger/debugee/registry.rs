@@ -1,9 +1,9 @@
-use crate::debugger::address::RelocatedAddress;
-use crate::debugger::debugee::dwarf::{DebugInformation, EndianArcSlice};
+use crate::debugger::address::{GlobalAddress, RelocatedAddress};
+use crate::debugger::coredump::FileMapping;
+use crate::debugger::debugee::dwarf::{DebugInformation, EndianArcSlice, LoadSegment};
 use crate::debugger::error::Error;
 use crate::debugger::error::Error::MappingNotFound;
 use nix::unistd::Pid;
-use proc_maps::MapRange;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -15,6 +15,93 @@ pub struct RegionRange {
     pub to: RelocatedAddress,
 }
 
+/// Size of a memory page, VAS regions start at page boundaries.
+const PAGE_SIZE: u64 = 4096;
+
+/// Part of an object file mapped into VAS. Each region (typically one loadable segment)
+/// has its own offset between VAS and object file addresses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MappedRegion {
+    /// Lowest object file address of the region.
+    global_from: GlobalAddress,
+    /// Lowest VAS address of the region.
+    from: RelocatedAddress,
+    /// Difference between VAS and object file addresses.
+    offset: usize,
+}
+
+impl MappedRegion {
+    /// Create a region from a VAS mapping of an object file.
+    /// Region address is calculated by a loadable segment that contains mapped file part,
+    /// if there is no such segment, file offset is used as an address.
+    ///
+    /// Segments of some linkers (like lld) share a file page, so a mapped page may contain
+    /// several segments. In this case a segment with the same load bias as the lowest mapping
+    /// of the file is preferred, the lowest mapping itself uses the first one.
+    ///
+    /// # Arguments
+    ///
+    /// * `map_offset`: offset of mapped part in the object file
+    /// * `map_start`: VAS address of mapping
+    /// * `segments`: object file loadable segments ordered by file offset
+    /// * `bias`: offset of the lowest region of the file, `None` for the lowest region itself
+    fn new(
+        map_offset: u64,
+        map_start: usize,
+        segments: &[LoadSegment],
+        bias: Option<usize>,
+    ) -> Self {
+        // a mapping starts at a page boundary, so segments that start
+        // in or before the mapped page are candidates
+        let candidates: Vec<_> = segments
+            .iter()
+            .filter(|segment| {
+                segment.file_offset - segment.file_offset % PAGE_SIZE <= map_offset
+                    && map_offset < segment.file_offset + segment.file_size
+            })
+            .map(|segment| {
+                usize::from(segment.address) + map_offset as usize - segment.file_offset as usize
+            })
+            .collect();
+        let global_from = match bias {
+            None => candidates.first().copied(),
+            Some(bias) => candidates
+                .iter()
+                .copied()
+                .find(|&global_from| map_start.wrapping_sub(global_from) == bias)
+                .or(candidates.last().copied()),
+        }
+        .unwrap_or(map_offset as usize);
+
+        Self {
+            global_from: GlobalAddress::from(global_from),
+            from: RelocatedAddress::from(map_start),
+            offset: map_start.wrapping_sub(global_from),
+        }
+    }
+}
+
+/// Return offset of the region that contains (or is nearest below) an address,
+/// addresses that are below all regions belong to the lowest region.
+///
+/// # Arguments
+///
+/// * `regions`: regions of a single object file
+/// * `key`: region lower bound, object file or VAS address
+/// * `addr`: address for region searching
+fn region_offset<T: Ord>(
+    regions: &[MappedRegion],
+    key: impl Fn(&MappedRegion) -> T,
+    addr: T,
+) -> Option<usize> {
+    regions
+        .iter()
+        .filter(|region| key(region) <= addr)
+        .max_by_key(|region| key(region))
+        .or_else(|| regions.first())
+        .map(|region| region.offset)
+}
+
 /// Information about loaded in VAS region.
 pub struct RegionInfo {
     pub path: PathBuf,
@@ -38,8 +125,11 @@ pub struct DwarfRegistry {
     files: HashMap<PathBuf, DebugInformation>,
     /// ordered .text section address ranges, calculates by dwarf units ranges
     ranges: Vec<(PathBuf, RegionRange)>,
-    /// regions map addresses, each region is a shared lib or debugee program
-    mappings: HashMap<PathBuf, usize>,
+    /// mapped regions of each object file (shared lib or debugee program),
+    /// ordered by object file address
+    mappings: HashMap<PathBuf, Vec<MappedRegion>>,
+    /// VAS mappings of a dumped process, `None` if mappings are read from `/proc/<pid>/maps`.
+    core_mappings: Option<Vec<FileMapping>>,
 }
 
 impl DwarfRegistry {
@@ -61,9 +151,38 @@ impl DwarfRegistry {
             files: HashMap::from([(program_path, program_dwarf)]),
             ranges: vec![],
             mappings: HashMap::new(),
+            core_mappings: None,
         }
     }
 
+    /// Use mappings of a dumped process (from a core file) instead of `/proc/<pid>/maps`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mappings`: files mapped into a dumped process
+    pub fn set_core_mappings(&mut self, mappings: Vec<FileMapping>) {
+        self.core_mappings = Some(mappings);
+    }
+
+    /// Return file-backed VAS mappings of a process.
+    fn file_mappings(&self) -> Result<Vec<FileMapping>, Error> {
+        if let Some(ref mappings) = self.core_mappings {
+            return Ok(mappings.clone());
+        }
+        let proc_maps = proc_maps::get_process_maps(self.pid.as_raw())?;
+        Ok(proc_maps
+            .iter()
+            .filter_map(|map| {
+                Some(FileMapping {
+                    start: map.start(),
+                    end: map.start() + map.size(),
+                    offset: map.offset as u64,
+                    path: map.filename()?.to_path_buf(),
+                })
+            })
+            .collect())
+    }
+
     /// Update ranges with respect of VAS segments addresses.
     /// Must be called after program is loaded into memory.
     ///
@@ -71,7 +190,7 @@ impl DwarfRegistry {
     ///
     /// * `only_main`: if true - update mappings only for main executable file, false - update all
     pub fn update_mappings(&mut self, only_main: bool) -> Result<Vec<Error>, Error> {
-        let proc_maps: Vec<MapRange> = proc_maps::get_process_maps(self.pid.as_raw())?;
+        let file_maps = self.file_mappings()?;
 
         let mut mappings = HashMap::with_capacity(self.files.len());
         let mut ranges = vec![];
@@ -89,14 +208,15 @@ impl DwarfRegistry {
             &mut full_it
         };
 
-        iter.for_each(|(file, _)| {
+        iter.for_each(|(file, dwarf)| {
             let absolute_debugee_path_buf =
                 file.canonicalize().expect("canonicalize path must exists");
             let absolute_debugee_path = absolute_debugee_path_buf.as_path();
-            let maps = proc_maps
+            let mut maps = file_maps
                 .iter()
-                .filter(|map| map.filename() == Some(absolute_debugee_path))
+                .filter(|map| map.path == absolute_debugee_path)
                 .collect::<Vec<_>>();
+            maps.sort_unstable_by_key(|map| map.start);
 
             if maps.is_empty() {
                 errors.push(MappingNotFound(file.to_string_lossy().to_string()));
@@ -105,21 +225,27 @@ impl DwarfRegistry {
 
             let lower_sect = maps
                 .iter()
-                .min_by(|map1, map2| map1.start().cmp(&map2.start()))
+                .min_by(|map1, map2| map1.start.cmp(&map2.start))
                 .expect("at least one mapping must exists");
             let higher_sect = maps
                 .iter()
-                .max_by(|map1, map2| map1.start().cmp(&map2.start()))
+                .max_by(|map1, map2| map1.start.cmp(&map2.start))
                 .expect("at least one mapping must exists");
 
-            let mapping = lower_sect.start();
+            let mut regions: Vec<MappedRegion> = vec![];
+            for map in &maps {
+                let bias = regions.first().map(|region| region.offset);
+                let region = MappedRegion::new(map.offset, map.start, dwarf.load_segments(), bias);
+                regions.push(region);
+            }
+            regions.sort_unstable_by_key(|region| usize::from(region.global_from));
 
             let range = RegionRange {
-                from: RelocatedAddress::from(lower_sect.start()),
-                to: RelocatedAddress::from(higher_sect.start() + higher_sect.size()),
+                from: RelocatedAddress::from(lower_sect.start),
+                to: RelocatedAddress::from(higher_sect.end),
             };
 
-            mappings.insert(file.clone(), mapping);
+            mappings.insert(file.clone(), regions);
             ranges.push((file.clone(), range));
         });
 
@@ -244,16 +370,37 @@ impl DwarfRegistry {
     /// * `pc`: address for determine VAS region.
     pub fn find_mapping_offset(&self, addr: RelocatedAddress) -> Option<usize> {
         let (path, _) = self.find_range(addr)?;
-        self.mappings.get(path).copied()
+        region_offset(self.mappings.get(path)?, |region| region.from, addr)
     }
 
-    /// Return offset of mapped memory region.
+    /// Return offset of memory region to which an object file address is mapped.
     ///
     /// # Arguments
     ///
-    /// * `dwarf`: debug information for determine memory region.
-    pub fn find_mapping_offset_for_file(&self, dwarf: &DebugInformation) -> Option<usize> {
-        self.mappings.get(dwarf.pathname()).copied()
+    /// * `file`: path to object file
+    /// * `addr`: object file address
+    pub fn find_relocation_offset(&self, file: &Path, addr: GlobalAddress) -> Option<usize> {
+        region_offset(
+            self.mappings.get(file)?,
+            |region| usize::from(region.global_from),
+            usize::from(addr),
+        )
+    }
+
+    /// Return offset of memory region to which an object file address is mapped, object file
+    /// is determined by another VAS address.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc`: VAS address, determine object file
+    /// * `addr`: object file address
+    pub fn find_relocation_offset_by_pc(
+        &self,
+        pc: RelocatedAddress,
+        addr: GlobalAddress,
+    ) -> Option<usize> {
+        let (path, _) = self.find_range(pc)?;
+        self.find_relocation_offset(path, addr)
     }
 
     /// Find main executable object debug information.
@@ -274,6 +421,7 @@ impl DwarfRegistry {
             // mappings and ranges must be redefined
             ranges: vec![],
             mappings: HashMap::default(),
+            core_mappings: None,
         }
     }
 
@@ -301,3 +449,55 @@ impl DwarfRegistry {
         regions
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mapped_regions() {
+        let segment = |file_offset: u64, file_size: u64, address: usize| LoadSegment {
+            file_offset,
+            file_size,
+            address: GlobalAddress::from(address),
+        };
+        // segment addresses differ from file offsets, like in lld linked executables
+        let segments = [
+            segment(0, 0x600, 0),
+            segment(0x600, 0x1000, 0x1600),
+            segment(0x1600, 0x200, 0x3600),
+        ];
+
+        let first = MappedRegion::new(0, 0x5555_0000, &segments, None);
+        let bias = Some(first.offset);
+        let regions = [
+            first,
+            MappedRegion::new(0, 0x5555_1000, &segments, bias),
+            MappedRegion::new(0x1000, 0x7777_0000, &segments, bias),
+        ];
+        assert_eq!(regions[0].global_from, GlobalAddress::from(0_usize));
+        assert_eq!(regions[0].offset, 0x5555_0000);
+        assert_eq!(regions[1].global_from, GlobalAddress::from(0x1000_usize));
+        assert_eq!(regions[1].offset, 0x5555_0000);
+        assert_eq!(regions[2].global_from, GlobalAddress::from(0x3000_usize));
+        assert_eq!(regions[2].offset, 0x7777_0000 - 0x3000);
+
+        let by_global = |addr: usize| region_offset(&regions, |r| usize::from(r.global_from), addr);
+        assert_eq!(by_global(0x1700), Some(0x5555_0000));
+        assert_eq!(by_global(0x3650), Some(0x7777_0000 - 0x3000));
+        // .bss is placed after the last mapped file part
+        assert_eq!(by_global(0x3900), Some(0x7777_0000 - 0x3000));
+
+        let by_pc = |addr: usize| region_offset(&regions, |r| r.from, RelocatedAddress::from(addr));
+        assert_eq!(by_pc(0x5555_1010), Some(0x5555_0000));
+        assert_eq!(by_pc(0x7777_0100), Some(0x7777_0000 - 0x3000));
+
+        // without segments file offset is used as an address
+        let region = MappedRegion::new(0x2000, 0x5555_2000, &[], None);
+        assert_eq!(region.offset, 0x5555_0000);
+        assert_eq!(
+            region_offset(&[], |r| r.from, RelocatedAddress::from(0_usize)),
+            None
+        );
+    }
+}
@@ -22,10 +22,23 @@ pub enum RendezvousError {
     NotFound,
 }
 
+/// State of a link map list, list is consistent only if no library is mapping or unmapping now.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkMapState {
+    /// Mapping change is complete.
+    Consistent,
+    /// Library is being added.
+    Add,
+    /// Library is being removed.
+    Delete,
+}
+
 /// Rendezvous structure maintained by dynamic linker.
 /// This structure maintains a list of shared library descriptors.
 pub struct Rendezvous {
     pid: Pid,
+    /// Address of rendezvous structure in debugee memory.
+    addr: usize,
     inner: ffi::r_debug,
 }
 
@@ -47,10 +60,11 @@ impl Rendezvous {
 
         while val != 0 {
             if val == DT_DEBUG as usize {
-                let mut rend_addr = ffi::read_val::<usize>(proc_pid, &mut addr)?;
-                let rendezvous = ffi::read_val::<ffi::r_debug>(proc_pid, &mut rend_addr)?;
+                let rend_addr = ffi::read_val::<usize>(proc_pid, &mut addr)?;
+                let rendezvous = ffi::read_val::<ffi::r_debug>(proc_pid, &mut { rend_addr })?;
                 return Ok(Self {
                     pid: proc_pid,
+                    addr: rend_addr,
                     inner: rendezvous,
                 });
             }
@@ -90,11 +104,23 @@ impl Rendezvous {
     pub fn r_brk(&self) -> RelocatedAddress {
         RelocatedAddress::from(self.inner.r_brk)
     }
+
+    /// Return current state of a link map list. Linker updates this state before
+    /// and after each mapping change, so the state is read from debugee memory on each call.
+    pub fn state(&self) -> Result<LinkMapState, RendezvousError> {
+        let rendezvous = ffi::read_val::<ffi::r_debug>(self.pid, &mut { self.addr })?;
+        Ok(match rendezvous.r_state {
+            ffi::RT_ADD => LinkMapState::Add,
+            ffi::RT_DELETE => LinkMapState::Delete,
+            _ => LinkMapState::Consistent,
+        })
+    }
 }
 
 mod ffi {
     #![allow(non_camel_case_types)]
 
+    use crate::debugger::memory;
     use nix::libc;
     use nix::sys::uio;
     use nix::sys::uio::RemoteIoVec;
@@ -115,8 +141,15 @@ mod ffi {
         /// The debugger can set a breakpoint at this address if it wants to
         /// notice shared object mapping changes.
         pub(super) r_brk: usize,
+        /// State of a link map list, one of `RT_CONSISTENT`, `RT_ADD` or `RT_DELETE`.
+        pub(super) r_state: i32,
+        /// Base address the linker is loaded at.
+        pub(super) r_ldbase: usize,
     }
 
+    pub(super) const RT_ADD: i32 = 1;
+    pub(super) const RT_DELETE: i32 = 2;
+
     #[derive(Debug, Clone, Copy)]
     #[repr(C)]
     pub(super) struct link_map {
@@ -133,20 +166,24 @@ mod ffi {
 
     pub(super) fn read_val<T: Copy>(pid: Pid, addr: &mut usize) -> nix::Result<T> {
         let size = mem::size_of::<T>();
-        let mut buff = vec![0; size];
-        let local_iov = IoSliceMut::new(buff.as_mut_slice());
-        let remote_iov = RemoteIoVec {
-            base: *addr,
-            len: size,
+        let buff = match memory::backend(pid) {
+            Some(backend) => backend.read_memory(*addr, size)?,
+            None => {
+                let mut buff = vec![0; size];
+                let local_iov = IoSliceMut::new(buff.as_mut_slice());
+                let remote_iov = RemoteIoVec {
+                    base: *addr,
+                    len: size,
+                };
+                let local_iov_slice = &mut [local_iov];
+                let _reads =
+                    uio::process_vm_readv(pid, local_iov_slice.as_mut_slice(), &[remote_iov])?;
+                buff
+            }
         };
-        let local_iov_slice = &mut [local_iov];
-
-        let _reads = uio::process_vm_readv(pid, local_iov_slice.as_mut_slice(), &[remote_iov])?;
-
-        let ptr = local_iov_slice[0].as_ptr();
 
-        let val_ptr: *const T = ptr.cast::<T>();
-        let val = unsafe { *val_ptr };
+        let val_ptr: *const T = buff.as_ptr().cast::<T>();
+        let val = unsafe { val_ptr.read_unaligned() };
 
         *addr += size;
 
@@ -7,14 +7,17 @@ use crate::debugger::error::Error::{MultipleErrors, NoThreadDB, Ptrace, ThreadDB
 use crate::debugger::register::{Register, RegisterMap};
 use log::{debug, warn};
 use nix::errno::Errno;
+use nix::libc;
 use nix::sys;
 use nix::sys::signal::Signal;
 use nix::sys::wait::{waitpid, WaitStatus};
 use nix::unistd::Pid;
 use ouroboros::self_referencing;
 use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use thread_db;
 
 #[self_referencing]
@@ -96,6 +99,22 @@ impl Tracee {
             .map_err(Ptrace)
     }
 
+    /// Resume tracee like [`Tracee::r#continue`], but stop it at next syscall entry or exit.
+    pub fn continue_to_syscall(&mut self, sig: Option<Signal>) -> Result<(), Error> {
+        debug!(
+            target: "tracer",
+            "continue tracee execution until syscall with signal {sig:?}, thread: {pid}",
+            pid = self.pid,
+        );
+
+        sys::ptrace::syscall(self.pid, sig)
+            .map(|ok| {
+                self.update_status(Running);
+                ok
+            })
+            .map_err(Ptrace)
+    }
+
     /// Set tracee status into stop.
     ///
     /// Note: this function does not actually stop the tracee.
@@ -132,10 +151,68 @@ impl Tracee {
     }
 }
 
+/// Kernel information about a thread (from `/proc/<pid>/task/<tid>/stat`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThreadStat {
+    /// Scheduler state: `R` (running), `S` (sleeping), `D` (uninterruptible wait),
+    /// `t` (stopped by a debugger), etc. See proc(5) for the full list.
+    pub state: char,
+    /// Number of CPU the thread last executed on.
+    pub cpu: u32,
+    /// Time spent in user mode.
+    pub user_time: Duration,
+    /// Time spent in kernel mode.
+    pub system_time: Duration,
+}
+
+impl ThreadStat {
+    /// Read a thread stat, return `None` if thread no longer exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `proc_pid`: process id
+    /// * `tid`: thread id
+    pub fn read(proc_pid: Pid, tid: Pid) -> Option<Self> {
+        let stat = fs::read_to_string(format!("/proc/{proc_pid}/task/{tid}/stat")).ok()?;
+        // SAFETY: sysconf has no preconditions
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        Self::parse(&stat, u64::try_from(ticks).ok().filter(|&t| t > 0)?)
+    }
+
+    /// Parse a content of stat file.
+    ///
+    /// # Arguments
+    ///
+    /// * `stat`: stat file content
+    /// * `ticks_per_sec`: clock ticks per second (`_SC_CLK_TCK`)
+    fn parse(stat: &str, ticks_per_sec: u64) -> Option<Self> {
+        // thread name may contain spaces and parentheses, so fields are counted from the last `)`
+        let (_, fields) = stat.rsplit_once(')')?;
+        // first field after a name is a field number 3 (state), see proc(5)
+        let fields: Vec<_> = fields.split_whitespace().collect();
+        let field = |num: usize| fields.get(num - 3).copied();
+        let ticks = |num: usize| -> Option<Duration> {
+            let ticks: u64 = field(num)?.parse().ok()?;
+            Some(Duration::from_nanos(ticks * 1_000_000_000 / ticks_per_sec))
+        };
+
+        Some(Self {
+            state: field(3)?.chars().next()?,
+            cpu: field(39)?.parse().ok()?,
+            user_time: ticks(14)?,
+            system_time: ticks(15)?,
+        })
+    }
+}
+
 pub struct TraceeCtl {
     process_pid: Pid,
     threads_state: HashMap<Pid, Tracee>,
     thread_db_proc: Option<ThreadDBProcess>,
+    /// If true, stopped tracees are continued until next syscall entry or exit.
+    trace_syscalls: bool,
+    /// Stopped tracees that are not continued with other tracees (used in non-stop mode).
+    parked: HashSet<Pid>,
 }
 
 impl TraceeCtl {
@@ -144,6 +221,8 @@ impl TraceeCtl {
             process_pid: proc_pid,
             threads_state: HashMap::from([(proc_pid, Tracee::new_stopped(proc_pid))]),
             thread_db_proc: None,
+            trace_syscalls: false,
+            parked: HashSet::new(),
         }
     }
 
@@ -161,10 +240,37 @@ impl TraceeCtl {
                 .map(|tid| (*tid, Tracee::new_stopped(*tid)))
                 .collect(),
             thread_db_proc: None,
+            trace_syscalls: false,
+            parked: HashSet::new(),
         }
     }
 
-    pub(crate) fn tracee(&mut self, pid: Pid) -> Option<&Tracee> {
+    /// Enable or disable syscall stops of continued tracees.
+    pub fn set_trace_syscalls(&mut self, enable: bool) {
+        self.trace_syscalls = enable;
+    }
+
+    /// Keep a stopped tracee stopped when other tracees are continued.
+    pub fn park(&mut self, pid: Pid) {
+        self.parked.insert(pid);
+    }
+
+    /// Allow a parked tracee to be continued with other tracees.
+    pub fn unpark(&mut self, pid: Pid) {
+        self.parked.remove(&pid);
+    }
+
+    /// Allow all parked tracees to be continued.
+    pub fn unpark_all(&mut self) {
+        self.parked.clear();
+    }
+
+    /// Return true if tracee is parked.
+    pub fn is_parked(&self, pid: Pid) -> bool {
+        self.parked.contains(&pid)
+    }
+
+    pub(crate) fn tracee(&self, pid: Pid) -> Option<&Tracee> {
         self.threads_state.get(&pid)
     }
 
@@ -196,19 +302,27 @@ impl TraceeCtl {
     /// Remove thread from budge.
     pub fn remove(&mut self, pid: Pid) -> Option<Tracee> {
         debug!(target: "tracer", "try to remove tracee, thread: {pid}");
+        self.parked.remove(&pid);
         self.threads_state.remove(&pid)
     }
 
-    /// Continue all currently stopped tracees.
+    /// Continue all currently stopped tracees (except parked).
     pub fn cont_stopped(&mut self) -> Result<(), Vec<Error>> {
         let mut errors = vec![];
+        let trace_syscalls = self.trace_syscalls;
+        let parked = &self.parked;
 
         self.threads_state.iter_mut().for_each(|(_, tracee)| {
-            if !tracee.is_stopped() {
+            if !tracee.is_stopped() || parked.contains(&tracee.pid) {
                 return;
             }
 
-            if let Err(e) = tracee.r#continue(None) {
+            let result = if trace_syscalls {
+                tracee.continue_to_syscall(None)
+            } else {
+                tracee.r#continue(None)
+            };
+            if let Err(e) = result {
                 // if no such process - continue, it will be removed later, on PTRACE_EVENT_EXIT event.
                 if matches!(e, Ptrace(err) if err == Errno::ESRCH) {
                     //warn!("thread {} not found, ESRCH", tracee.pid);
@@ -225,7 +339,7 @@ impl TraceeCtl {
         Ok(())
     }
 
-    /// Continue all currently stopped tracees.
+    /// Continue all currently stopped tracees (except parked).
     ///
     /// # Arguments
     ///
@@ -238,9 +352,11 @@ impl TraceeCtl {
     ) -> Result<(), Error> {
         let mut errors = vec![];
         let (signal, pid) = (inject_request.map(|s| s.1), inject_request.map(|s| s.0));
+        let trace_syscalls = self.trace_syscalls;
+        let parked = &self.parked;
 
         self.threads_state.iter_mut().for_each(|(_, tracee)| {
-            if exclude.contains(&tracee.pid) {
+            if exclude.contains(&tracee.pid) || parked.contains(&tracee.pid) {
                 return;
             }
 
@@ -254,7 +370,12 @@ impl TraceeCtl {
                 None
             };
 
-            if let Err(e) = tracee.r#continue(resume_sign) {
+            let result = if trace_syscalls {
+                tracee.continue_to_syscall(resume_sign)
+            } else {
+                tracee.r#continue(resume_sign)
+            };
+            if let Err(e) = result {
                 // if no such process - continue, it will be removed later, on PTRACE_EVENT_EXIT event.
                 if matches!(e, Ptrace(err) if err == Errno::ESRCH) {
                     warn!("thread {} not found, ESRCH", tracee.pid);
@@ -275,6 +396,11 @@ impl TraceeCtl {
         self.threads_state.values().cloned().collect()
     }
 
+    /// Return ids of all tracees.
+    pub fn tracee_pids(&self) -> Vec<Pid> {
+        self.threads_state.keys().copied().collect()
+    }
+
     /// Attach libthread_db to process.
     /// At least one thread must be created before process is attached to libthread_db.
     pub(super) fn attach_thread_db(&mut self, lib: Arc<thread_db::Lib>) -> Result<(), Error> {
@@ -304,3 +430,28 @@ impl TraceeCtl {
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_thread_stat_parse() {
+        let stat = "1234 (tokio-runtime) worker) S 1 1234 1234 0 -1 4194368 1200 0 0 0 \
+            250 30 0 0 20 0 8 0 4821 1190000000 2000 18446744073709551615 1 1 0 0 0 0 0 4096 \
+            17922 0 0 0 -1 3 0 0 0 0 0";
+        let stat = ThreadStat::parse(stat, 100).unwrap();
+        assert_eq!(
+            stat,
+            ThreadStat {
+                state: 'S',
+                cpu: 3,
+                user_time: Duration::from_millis(2500),
+                system_time: Duration::from_millis(300),
+            }
+        );
+
+        assert!(ThreadStat::parse("1234 (name) R 1", 100).is_none());
+        assert!(ThreadStat::parse("", 100).is_none());
+    }
+}
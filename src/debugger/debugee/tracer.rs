@@ -2,16 +2,21 @@ use crate::debugger::address::RelocatedAddress;
 use crate::debugger::breakpoint::Breakpoint;
 use crate::debugger::code;
 use crate::debugger::debugee::tracee::{StopType, TraceeCtl, TraceeStatus};
+use crate::debugger::debugee::DebugeeEvent;
 use crate::debugger::error::Error;
 use crate::debugger::error::Error::{MultipleErrors, ProcessExit, Ptrace, Waitpid};
+use crate::debugger::watchpoint;
+use crate::weak_error;
+use chrono::{DateTime, Local};
 use log::{debug, warn};
 use nix::errno::Errno;
 use nix::libc::pid_t;
 use nix::sys::signal::{Signal, SIGSTOP};
-use nix::sys::wait::{waitpid, WaitStatus};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
 use nix::unistd::Pid;
 use nix::{libc, sys};
 use std::collections::VecDeque;
+use strum_macros::Display;
 
 /// List of signals that dont interrupt a debugging process and send
 /// to debugee directly on fire.
@@ -28,6 +33,55 @@ static QUIET_SIGNALS: &[Signal] = &[
 /// List of signals that may interrupt a debugging process but debugger will not inject it into.
 static TRANSPARENT_SIGNALS: &[Signal] = &[Signal::SIGINT];
 
+/// Max number of records in a signal history.
+const SIGNAL_HISTORY_LEN: usize = 1024;
+
+/// What debugger does with a signal delivered to debugee.
+#[derive(Debug, Display, Clone, Copy, PartialEq)]
+pub enum SignalDisposition {
+    /// Signal is passed to debugee without interrupting a debugging process.
+    #[strum(serialize = "pass")]
+    Pass,
+    /// Debugee stops, signal is passed to debugee when it continues.
+    #[strum(serialize = "stop")]
+    Stop,
+    /// Debugee stops, signal is not passed to debugee.
+    #[strum(serialize = "stop, suppress")]
+    Suppress,
+}
+
+impl SignalDisposition {
+    /// Return a decision that debugger makes when a signal is delivered to debugee.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal`: delivered signal
+    pub fn of(signal: Signal) -> Self {
+        if QUIET_SIGNALS.contains(&signal) {
+            SignalDisposition::Pass
+        } else if TRANSPARENT_SIGNALS.contains(&signal) {
+            SignalDisposition::Suppress
+        } else {
+            SignalDisposition::Stop
+        }
+    }
+}
+
+/// Signal delivered to debugee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalRecord {
+    pub signal: Signal,
+    /// Thread that receives a signal.
+    pub thread: Pid,
+    /// Sender process, if signal sent by a process (`kill`, `tgkill`, `sigqueue`)
+    /// or by a child process state change.
+    pub sender: Option<Pid>,
+    /// `si_code` field of a signal information.
+    pub code: i32,
+    pub time: DateTime<Local>,
+    pub disposition: SignalDisposition,
+}
+
 #[derive(Debug)]
 pub enum StopReason {
     /// Whole debugee process exited with code
@@ -36,10 +90,75 @@ pub enum StopReason {
     DebugeeStart,
     /// Debugee stopped at breakpoint
     Breakpoint(Pid, RelocatedAddress),
+    /// Debugee stopped at watchpoint (after watched memory access)
+    Watchpoint(Pid, RelocatedAddress),
     /// Debugee stopped with OS signal
     SignalStop(Pid, Signal),
     /// Debugee stopped with Errno::ESRCH
     NoSuchProcess(Pid),
+    /// Debugee thread stopped at syscall entry or exit (only if syscall tracing is enabled)
+    SyscallStop(Pid, SyscallStop),
+}
+
+/// Syscall entry or exit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyscallStop {
+    Entry { nr: u64, args: [u64; 6] },
+    Exit { rval: i64, is_error: bool },
+}
+
+impl SyscallStop {
+    const PTRACE_GET_SYSCALL_INFO: libc::c_uint = 0x420e;
+    const PTRACE_SYSCALL_INFO_ENTRY: u8 = 1;
+    const PTRACE_SYSCALL_INFO_EXIT: u8 = 2;
+
+    /// Read syscall stop information with `PTRACE_GET_SYSCALL_INFO` (linux 5.3+),
+    /// return `None` if tracee isn't in a syscall stop.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid`: tracee pid
+    fn read(pid: Pid) -> Result<Option<Self>, Error> {
+        /// `struct ptrace_syscall_info` from `linux/ptrace.h`.
+        #[repr(C)]
+        #[derive(Default)]
+        struct PtraceSyscallInfo {
+            op: u8,
+            pad: [u8; 3],
+            arch: u32,
+            instruction_pointer: u64,
+            stack_pointer: u64,
+            /// Union of `entry` (nr and 6 args), `exit` (rval and is_error)
+            /// and `seccomp` data.
+            data: [u64; 8],
+        }
+
+        let mut info = PtraceSyscallInfo::default();
+        // SAFETY: kernel writes no more than passed size into `info`
+        let res = unsafe {
+            libc::ptrace(
+                Self::PTRACE_GET_SYSCALL_INFO,
+                pid.as_raw(),
+                std::mem::size_of::<PtraceSyscallInfo>(),
+                &mut info as *mut PtraceSyscallInfo,
+            )
+        };
+        if res < 0 {
+            return Err(Ptrace(Errno::last()));
+        }
+
+        Ok(match info.op {
+            Self::PTRACE_SYSCALL_INFO_ENTRY => Some(SyscallStop::Entry {
+                nr: info.data[0],
+                args: info.data[1..7].try_into().expect("infallible"),
+            }),
+            Self::PTRACE_SYSCALL_INFO_EXIT => Some(SyscallStop::Exit {
+                rval: info.data[0] as i64,
+                is_error: info.data[1] & 0xff != 0,
+            }),
+            _ => None,
+        })
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -59,6 +178,13 @@ pub struct Tracer {
 
     inject_signal_queue: VecDeque<(Pid, Signal)>,
     group_stop_guard: bool,
+    /// Last signals delivered to debugee, the oldest first.
+    signal_history: VecDeque<SignalRecord>,
+    /// If true, only a thread that reaches a breakpoint, watchpoint or receives a signal
+    /// is stopped, other threads keep running.
+    non_stop: bool,
+    /// Thread and process events observed since the last [`Tracer::take_events`] call.
+    pub(super) events: Vec<DebugeeEvent>,
 }
 
 impl Tracer {
@@ -72,6 +198,9 @@ impl Tracer {
             tracee_ctl: TraceeCtl::new(proc_pid),
             inject_signal_queue: VecDeque::new(),
             group_stop_guard: false,
+            signal_history: VecDeque::new(),
+            non_stop: false,
+            events: vec![],
         }
     }
 
@@ -86,7 +215,73 @@ impl Tracer {
             tracee_ctl: TraceeCtl::new_external(proc_pid, threads),
             inject_signal_queue: VecDeque::new(),
             group_stop_guard: false,
+            signal_history: VecDeque::new(),
+            non_stop: false,
+            events: vec![],
+        }
+    }
+
+    /// Enable or disable syscall tracing, if enabled, debugee threads stop at every
+    /// syscall entry and exit with [`StopReason::SyscallStop`].
+    ///
+    /// # Arguments
+    ///
+    /// * `enable`: enable or disable tracing
+    pub fn set_trace_syscalls(&mut self, enable: bool) {
+        self.tracee_ctl.set_trace_syscalls(enable);
+    }
+
+    /// Enable or disable non-stop mode. In non-stop mode a stop of one thread
+    /// doesn't interrupt other threads, and parked threads are not continued by
+    /// [`Tracer::resume`].
+    ///
+    /// # Arguments
+    ///
+    /// * `enable`: enable or disable non-stop mode
+    pub fn set_non_stop(&mut self, enable: bool) {
+        self.non_stop = enable;
+    }
+
+    /// Stop all running tracees (used when non-stop mode is disabled).
+    pub fn stop_all(&mut self, ctx: TraceContext) -> Result<(), Error> {
+        self.group_stop_interrupt(ctx, Pid::from_raw(-1))
+    }
+
+    /// Stop all tracees except initiator, do nothing in non-stop mode.
+    pub fn stop_others(&mut self, ctx: TraceContext, initiator_pid: Pid) -> Result<(), Error> {
+        if self.non_stop {
+            return Ok(());
         }
+        self.group_stop_interrupt(ctx, initiator_pid)
+    }
+
+    /// Return last signals delivered to debugee, the oldest first.
+    pub fn signal_history(&self) -> impl Iterator<Item = &SignalRecord> {
+        self.signal_history.iter()
+    }
+
+    /// Return thread and process events observed since the previous call.
+    pub fn take_events(&mut self) -> Vec<DebugeeEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn record_signal(&mut self, pid: Pid, signal: Signal, info: &libc::siginfo_t) {
+        // signal sent by a process or a child state change, otherwise `si_pid` is meaningless
+        let from_process = info.si_code <= 0 || signal == Signal::SIGCHLD;
+        // SAFETY: `si_pid` is set for signals with such codes
+        let sender = from_process.then(|| Pid::from_raw(unsafe { info.si_pid() }));
+
+        if self.signal_history.len() == SIGNAL_HISTORY_LEN {
+            self.signal_history.pop_front();
+        }
+        self.signal_history.push_back(SignalRecord {
+            signal,
+            thread: pid,
+            sender,
+            code: info.si_code,
+            time: Local::now(),
+            disposition: SignalDisposition::of(signal),
+        });
     }
 
     /// Continue debugee execution until stop happened.
@@ -103,7 +298,7 @@ impl Tracer {
 
                 if let Some((pid, sign)) = self.inject_signal_queue.front().copied() {
                     // if there is more signal stop debugee again
-                    self.group_stop_interrupt(ctx, Pid::from_raw(-1))?;
+                    self.stop_others(ctx, Pid::from_raw(-1))?;
                     return Ok(StopReason::SignalStop(pid, sign));
                 }
             } else {
@@ -238,6 +433,13 @@ impl Tracer {
                             // expect that tracee will be removed later
                             break;
                         }
+                        Some(StopReason::SyscallStop(pid, _))
+                        | Some(StopReason::Watchpoint(pid, _)) => {
+                            // tracee already stopped at syscall or watchpoint
+                            if pid == tracee.pid {
+                                break;
+                            }
+                        }
                     }
 
                     // reload tracee, it states must be changed after handle signal
@@ -273,6 +475,43 @@ impl Tracer {
         Ok(())
     }
 
+    /// Detach a child process created by `fork` or `vfork`. A forked child has a copy of debugee
+    /// memory, so software breakpoints are removed from it first.
+    ///
+    /// # Arguments
+    ///
+    /// * `child`: child process pid
+    /// * `shared_memory`: true if a child shares memory with debugee (`vfork`)
+    fn detach_child(
+        &mut self,
+        ctx: TraceContext,
+        child: Pid,
+        shared_memory: bool,
+    ) -> Result<(), Error> {
+        // PTRACE_EVENT_STOP of a child may be received first, then a child is
+        // registered as a thread at this point
+        if self.tracee_ctl.remove(child).is_none() {
+            let status = waitpid(child, Some(WaitPidFlag::__WALL)).map_err(Waitpid)?;
+            debug_assert!(
+                matches!(
+                    status,
+                    WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_STOP)
+                ),
+                "the child process must start with PTRACE_EVENT_STOP, got {status:?}"
+            );
+        }
+
+        let breakpoints = ctx.breakpoints.iter().filter(|_| !shared_memory);
+        for brkpt in breakpoints.filter(|brkpt| brkpt.is_enabled()) {
+            let mut child_brkpt = (*brkpt).clone();
+            child_brkpt.pid = child;
+            // debug registers aren't inherited by a child, only software breakpoints are removed
+            weak_error!(child_brkpt.disable(&[]));
+        }
+
+        sys::ptrace::detach(child, None).map_err(Ptrace)
+    }
+
     /// Handle tracee event fired by `wait` syscall.
     /// After this function ends tracee_ctl must be in consistent state.
     /// If debugee process stop detected - returns a stop reason.
@@ -288,10 +527,13 @@ impl Tracer {
         match status {
             WaitStatus::Exited(pid, code) => {
                 // Thread exited with tread id
-                self.tracee_ctl.remove(pid);
+                let tracee = self.tracee_ctl.remove(pid);
                 if pid == self.tracee_ctl.proc_pid() {
                     return Ok(Some(StopReason::DebugeeExit(code)));
                 }
+                if tracee.is_some() {
+                    self.events.push(DebugeeEvent::ThreadExited(pid));
+                }
                 Ok(None)
             }
             WaitStatus::PtraceEvent(pid, _signal, code) => {
@@ -322,6 +564,23 @@ impl Tracer {
                                 "the newly cloned thread must start with PTRACE_EVENT_STOP (cause PTRACE_SEIZE was used), got {new_trace_status:?}"
                             )
                         }
+
+                        // debug registers aren't inherited by a new thread
+                        weak_error!(watchpoint::copy_debug_regs(pid, new_thread_id));
+                        self.events.push(DebugeeEvent::ThreadCreated(new_thread_id));
+                    }
+                    libc::PTRACE_EVENT_FORK | libc::PTRACE_EVENT_VFORK => {
+                        // fire just before a child process is created, a child process isn't
+                        // debugged, so it detached right after the start
+                        self.tracee_ctl
+                            .tracee_ensure_mut(pid)
+                            .set_stop(StopType::Interrupt);
+                        let child =
+                            Pid::from_raw(sys::ptrace::getevent(pid).map_err(Ptrace)? as pid_t);
+                        let shared_memory = code == libc::PTRACE_EVENT_VFORK;
+                        self.detach_child(ctx, child, shared_memory)?;
+                        self.events
+                            .push(DebugeeEvent::ProcessForked { parent: pid, child });
                     }
                     libc::PTRACE_EVENT_STOP => {
                         // fire right after new thread started or PTRACE_INTERRUPT called.
@@ -337,6 +596,9 @@ impl Tracer {
                         let tracee = self.tracee_ctl.remove(pid);
                         if let Some(mut tracee) = tracee {
                             tracee.r#continue(None)?;
+                            if pid != self.tracee_ctl.proc_pid() {
+                                self.events.push(DebugeeEvent::ThreadExited(pid));
+                            }
                         }
                     }
                     _ => {
@@ -378,9 +640,9 @@ impl Tracer {
                                     let mut unusual_brkpt = (*brkpt).clone();
                                     unusual_brkpt.pid = pid;
                                     if unusual_brkpt.is_enabled() {
-                                        unusual_brkpt.disable()?;
+                                        unusual_brkpt.disable(&[pid])?;
                                         while self.single_step(ctx, pid)?.is_some() {}
-                                        unusual_brkpt.enable()?;
+                                        unusual_brkpt.enable(&[pid])?;
                                     }
                                     self.tracee_ctl
                                         .tracee_ensure_mut(pid)
@@ -393,10 +655,74 @@ impl Tracer {
                             self.tracee_ctl
                                 .tracee_ensure_mut(pid)
                                 .set_stop(StopType::Interrupt);
-                            self.group_stop_interrupt(ctx, pid)?;
+                            self.stop_others(ctx, pid)?;
 
                             Ok(Some(StopReason::Breakpoint(pid, current_pc)))
                         }
+                        code::TRAP_HWBKPT => {
+                            // hardware breakpoint stops a thread before an instruction executes,
+                            // so there is no need to move pc back like for INT3
+                            let current_pc = self.tracee_ctl.tracee_ensure(pid).pc()?;
+                            let hw_brkpt = ctx
+                                .breakpoints
+                                .iter()
+                                .filter(|brkpt| brkpt.addr == current_pc && brkpt.is_enabled())
+                                .find_map(|brkpt| brkpt.hardware_reg());
+                            if let Some(reg) = hw_brkpt {
+                                if watchpoint::take_debug_status(pid, reg)? {
+                                    let has_tmp_breakpoints =
+                                        ctx.breakpoints.iter().any(|b| b.is_temporary());
+                                    if has_tmp_breakpoints {
+                                        // hardware breakpoint is always user defined, skip it
+                                        // like an unusual INT3 while a step is in progress
+                                        watchpoint::set_exec_breakpoint(
+                                            &[pid],
+                                            reg,
+                                            current_pc,
+                                            false,
+                                        )?;
+                                        while self.single_step(ctx, pid)?.is_some() {}
+                                        watchpoint::set_exec_breakpoint(
+                                            &[pid],
+                                            reg,
+                                            current_pc,
+                                            true,
+                                        )?;
+                                        self.tracee_ctl
+                                            .tracee_ensure_mut(pid)
+                                            .set_stop(StopType::Interrupt);
+
+                                        return Ok(None);
+                                    }
+
+                                    self.tracee_ctl
+                                        .tracee_ensure_mut(pid)
+                                        .set_stop(StopType::Interrupt);
+                                    self.stop_others(ctx, pid)?;
+
+                                    return Ok(Some(StopReason::Breakpoint(pid, current_pc)));
+                                }
+                            }
+
+                            self.tracee_ctl
+                                .tracee_ensure_mut(pid)
+                                .set_stop(StopType::Interrupt);
+                            let hw_regs: Vec<_> = ctx
+                                .breakpoints
+                                .iter()
+                                .filter_map(|brkpt| brkpt.hardware_reg())
+                                .collect();
+                            if !watchpoint::has_watch_status(pid, &hw_regs)? {
+                                debug!(
+                                    target: "tracer",
+                                    "debug exception without a triggered watchpoint",
+                                );
+                                return Ok(None);
+                            }
+                            self.stop_others(ctx, pid)?;
+
+                            Ok(Some(StopReason::Watchpoint(pid, current_pc)))
+                        }
                         code => {
                             debug!(
                                 target: "tracer",
@@ -406,6 +732,8 @@ impl Tracer {
                         }
                     },
                     _ => {
+                        self.record_signal(pid, signal, &info);
+
                         if !TRANSPARENT_SIGNALS.contains(&signal) {
                             self.inject_signal_queue.push_back((pid, signal));
                         }
@@ -415,13 +743,25 @@ impl Tracer {
                             .set_stop(StopType::SignalStop(signal));
 
                         if !QUIET_SIGNALS.contains(&signal) {
-                            self.group_stop_interrupt(ctx, pid)?;
+                            self.stop_others(ctx, pid)?;
                         }
 
                         Ok(Some(StopReason::SignalStop(pid, signal)))
                     }
                 }
             }
+            WaitStatus::PtraceSyscall(pid) => {
+                let stop = match SyscallStop::read(pid) {
+                    Ok(Some(stop)) => stop,
+                    Ok(None) => return Ok(None),
+                    Err(Ptrace(Errno::ESRCH)) => return Ok(Some(StopReason::NoSuchProcess(pid))),
+                    Err(e) => return Err(e),
+                };
+                self.tracee_ctl
+                    .tracee_ensure_mut(pid)
+                    .set_stop(StopType::Interrupt);
+                Ok(Some(StopReason::SyscallStop(pid, stop)))
+            }
             WaitStatus::Signaled(_, _, _) => Ok(None),
             _ => {
                 warn!("unexpected wait status: {status:?}");
@@ -454,10 +794,11 @@ impl Tracer {
             let info = sys::ptrace::getsiginfo(pid).map_err(Ptrace)?;
 
             // check that debugee step into an expected trap
-            // (breakpoints ignored and are also considered as a trap)
+            // (breakpoints and watchpoints ignored and are also considered as a trap)
             let in_trap = matches!(status, WaitStatus::Stopped(_, Signal::SIGTRAP))
                 && (info.si_code == code::TRAP_TRACE
                     || info.si_code == code::TRAP_BRKPT
+                    || info.si_code == code::TRAP_HWBKPT
                     || info.si_code == code::SI_KERNEL);
             if in_trap {
                 // check that we aren't on original pc value
@@ -477,7 +818,7 @@ impl Tracer {
                 let syscall_status = tracee.wait_one()?;
                 debug_assert!(matches!(
                     syscall_status,
-                    WaitStatus::Stopped(_, Signal::SIGTRAP)
+                    WaitStatus::Stopped(_, Signal::SIGTRAP) | WaitStatus::PtraceSyscall(_)
                 ));
 
                 // then do step again
@@ -497,8 +838,8 @@ impl Tracer {
             let stop = self.apply_new_status(ctx, status)?;
             match stop {
                 None => {}
-                Some(StopReason::Breakpoint(_, _)) => {
-                    unreachable!("breakpoints must be ignore");
+                Some(StopReason::Breakpoint(_, _)) | Some(StopReason::Watchpoint(_, _)) => {
+                    unreachable!("breakpoints and watchpoints must be ignore");
                 }
                 Some(StopReason::DebugeeExit(code)) => return Err(ProcessExit(code)),
                 Some(StopReason::DebugeeStart) => {
@@ -517,6 +858,11 @@ impl Tracer {
                     // expect that tracee will be removed later
                     break None;
                 }
+                Some(StopReason::SyscallStop(_, _)) => {
+                    // syscall stops are not interesting while stepping
+                    self.tracee_ctl.tracee_ensure(pid).step(None)?;
+                    continue;
+                }
             }
         };
         Ok(reason)
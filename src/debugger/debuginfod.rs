@@ -0,0 +1,317 @@
+//! Debuginfod client.
+//!
+//! When a shared library (or an executable) has no local debug information, its debug
+//! information file is requested by build-id from debuginfod servers listed in the
+//! `DEBUGINFOD_URLS` environment variable (space separated, like in elfutils and gdb).
+//! Client is disabled if variable is not set or empty.
+//!
+//! Downloaded files are stored in a cache directory (`DEBUGINFOD_CACHE_PATH`,
+//! `$XDG_CACHE_HOME/debuginfod_client` or `~/.cache/debuginfod_client`) and reused later.
+//! Plain `http://` servers are queried directly, `https://` servers require `debuginfod-find`
+//! utility (elfutils) in `PATH`.
+
+use log::debug;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+use std::{env, fs, io};
+
+/// Default timeout of a server connection and of a single read from it.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Return list of configured debuginfod servers.
+pub fn servers() -> Vec<String> {
+    env::var("DEBUGINFOD_URLS")
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|url| url.trim_end_matches('/').to_string())
+        .collect()
+}
+
+/// Return true if at least one debuginfod server is configured.
+pub fn enabled() -> bool {
+    !servers().is_empty()
+}
+
+/// Return a cache directory for downloaded files.
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("DEBUGINFOD_CACHE_PATH") {
+        return Some(PathBuf::from(path));
+    }
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(cache.join("debuginfod_client"))
+}
+
+/// Return path to a debug information file, download it if it is not in cache.
+/// Return `None` if client is disabled or no server knows this build-id.
+///
+/// # Arguments
+///
+/// * `build_id`: hex encoded build-id of an object file
+pub fn find_debuginfo(build_id: &str) -> Option<PathBuf> {
+    find(build_id, "debuginfo", "debuginfo")
+}
+
+/// Return path to a source file of an object file, download it if it is not in cache.
+/// Return `None` if client is disabled or no server knows this build-id or file.
+///
+/// # Arguments
+///
+/// * `build_id`: hex encoded build-id of an object file
+/// * `file`: absolute path of a source file from debug information
+pub fn find_source(build_id: &str, file: &Path) -> Option<PathBuf> {
+    let file = file.to_string_lossy();
+    let artifact = format!("source{file}");
+    let cache_name = format!("source{}", file.replace('/', "#"));
+    find(build_id, &artifact, &cache_name)
+}
+
+fn find(build_id: &str, artifact: &str, cache_name: &str) -> Option<PathBuf> {
+    let servers = servers();
+    if servers.is_empty() || build_id.is_empty() {
+        return None;
+    }
+
+    let cache_dir = cache_dir()?.join(build_id);
+    let target = cache_dir.join(cache_name);
+    if target.is_file() {
+        return Some(target);
+    }
+
+    let timeout = env::var("DEBUGINFOD_TIMEOUT")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TIMEOUT);
+
+    for server in servers {
+        let result = if server.starts_with("http://") {
+            fs::create_dir_all(&cache_dir)
+                .and_then(|_| fetch(&server, build_id, artifact, &target, timeout))
+        } else {
+            find_with_external_client(build_id, artifact)
+        };
+        match result {
+            Ok(Some(path)) => {
+                debug!(target: "debuginfod", "{artifact} for {build_id} found at {path:?}");
+                return Some(path);
+            }
+            Ok(None) => {}
+            Err(e) => debug!(target: "debuginfod", "server {server}: {e}"),
+        }
+    }
+    None
+}
+
+/// Split `http://host[:port][/prefix]` url into a socket address and a path prefix.
+fn parse_url(url: &str) -> Option<(String, &str, &str)> {
+    let rest = url.strip_prefix("http://")?;
+    let (authority, prefix) = match rest.find('/') {
+        Some(idx) => rest.split_at(idx),
+        None => (rest, ""),
+    };
+    let host = authority.split(':').next()?;
+    if host.is_empty() {
+        return None;
+    }
+    let addr = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{authority}:80")
+    };
+    Some((addr, host, prefix.trim_end_matches('/')))
+}
+
+/// Download an artifact from a plain http server into a target file.
+/// Return `None` if server responds with non-success status.
+fn fetch(
+    server: &str,
+    build_id: &str,
+    artifact: &str,
+    target: &Path,
+    timeout: Duration,
+) -> io::Result<Option<PathBuf>> {
+    let (addr, host, prefix) = parse_url(server)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid server url"))?;
+    let addr = addr
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "unknown host"))?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    write!(
+        stream,
+        "GET {prefix}/buildid/{build_id}/{artifact} HTTP/1.1\r\n\
+         Host: {host}\r\n\
+         User-Agent: bugstalker\r\n\
+         Connection: close\r\n\r\n"
+    )?;
+
+    let mut reader = BufReader::new(stream);
+    let mut status = String::new();
+    reader.read_line(&mut status)?;
+    let code = status.split_whitespace().nth(1);
+    if code != Some("200") {
+        debug!(target: "debuginfod", "{server} responds: {}", status.trim_end());
+        return Ok(None);
+    }
+
+    let mut content_len = None;
+    let mut chunked = false;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_len = value.parse::<u64>().ok();
+            } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            }
+        }
+    }
+
+    let body = if chunked {
+        read_chunked(&mut reader)?
+    } else {
+        let mut body = vec![];
+        match content_len {
+            Some(len) => reader.take(len).read_to_end(&mut body)?,
+            None => reader.read_to_end(&mut body)?,
+        };
+        if content_len.is_some_and(|len| len != body.len() as u64) {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+        body
+    };
+
+    // write into temporary file first, so an interrupted download never appears in cache
+    let tmp = target.with_extension("part");
+    fs::write(&tmp, body)?;
+    fs::rename(&tmp, target)?;
+    Ok(Some(target.to_path_buf()))
+}
+
+fn read_chunked(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut body = vec![];
+    loop {
+        let mut size_line = String::new();
+        reader.read_line(&mut size_line)?;
+        let size = size_line.trim().split(';').next().unwrap_or_default();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid chunk size"))?;
+        if size == 0 {
+            return Ok(body);
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        let mut crlf = [0; 2];
+        reader.read_exact(&mut crlf)?;
+    }
+}
+
+/// Ask `debuginfod-find` utility for an artifact, it uses the same environment variables
+/// and cache layout.
+fn find_with_external_client(build_id: &str, artifact: &str) -> io::Result<Option<PathBuf>> {
+    let (kind, rest) = match artifact.strip_prefix("source") {
+        Some(file) => ("source", Some(file)),
+        None => (artifact, None),
+    };
+    let mut cmd = Command::new("debuginfod-find");
+    cmd.arg(kind).arg(build_id);
+    if let Some(file) = rest {
+        cmd.arg(file);
+    }
+    let output = cmd.output()?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Ok(path.is_file().then_some(path))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(
+            parse_url("http://localhost:8002"),
+            Some(("localhost:8002".to_string(), "localhost", ""))
+        );
+        assert_eq!(
+            parse_url("http://debuginfod.example.org/prefix/"),
+            Some((
+                "debuginfod.example.org:80".to_string(),
+                "debuginfod.example.org",
+                "/prefix"
+            ))
+        );
+        assert_eq!(parse_url("https://debuginfod.example.org"), None);
+        assert_eq!(parse_url("http://"), None);
+    }
+
+    fn serve_once(response: &'static [u8]) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim_end().is_empty() {
+                    break;
+                }
+            }
+            (&stream).write_all(response).unwrap();
+            request
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_fetch() {
+        let dir = env::temp_dir().join(format!("bs_debuginfod_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("debuginfo");
+        let timeout = Duration::from_secs(5);
+
+        let (url, server) = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello");
+        let path = fetch(&url, "abcd", "debuginfo", &target, timeout).unwrap();
+        assert_eq!(path.as_deref(), Some(target.as_path()));
+        assert_eq!(fs::read(&target).unwrap(), b"hello");
+        assert_eq!(
+            server.join().unwrap(),
+            "GET /buildid/abcd/debuginfo HTTP/1.1\r\n"
+        );
+
+        let (url, server) = serve_once(
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n",
+        );
+        fetch(&url, "abcd", "debuginfo", &target, timeout).unwrap();
+        assert_eq!(fs::read(&target).unwrap(), b"abcde");
+        server.join().unwrap();
+
+        let (url, server) = serve_once(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+        assert!(fetch(&url, "ef01", "debuginfo", &target, timeout)
+            .unwrap()
+            .is_none());
+        server.join().unwrap();
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
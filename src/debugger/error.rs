@@ -1,11 +1,14 @@
-use crate::debugger::address::GlobalAddress;
+use crate::debugger::address::{GlobalAddress, RelocatedAddress};
+use crate::debugger::coredump::CoreDumpError;
 use crate::debugger::debugee::dwarf::unit::DieRef;
 use crate::debugger::debugee::RendezvousError;
+use crate::debugger::progress::Cancelled;
 use crate::debugger::variable::ParsingError;
 use gimli::UnitOffset;
 use nix::unistd::Pid;
 use std::str::Utf8Error;
 use std::string::FromUtf8Error;
+use strum_macros::Display;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -13,6 +16,8 @@ pub enum Error {
     #[error("debugee already run")]
     AlreadyRun,
     #[error(transparent)]
+    Cancelled(#[from] Cancelled),
+    #[error(transparent)]
     IO(#[from] std::io::Error),
     #[error(transparent)]
     Utf8(#[from] Utf8Error),
@@ -42,6 +47,10 @@ pub enum Error {
     FrameNotFound(u32),
     #[error("tracee number {0} not found")]
     TraceeNotFound(u32),
+    #[error("thread number {0} is running")]
+    ThreadRunning(u32),
+    #[error("thread with id {0} not found")]
+    ThreadNotFound(Pid),
     #[error("debug information entry (die) not found, reference: {0:?}")]
     DieNotFound(DieRef),
     #[error("section \"{0}\" not found")]
@@ -132,18 +141,86 @@ pub enum Error {
     DisAsmInit(capstone::Error),
     #[error("instructions disassembly error: {0}")]
     DisAsm(capstone::Error),
+    #[error("invalid disassembly range: {0}")]
+    DisAsmRange(String),
+
+    // --------------------------------- catchpoints -----------------------------------------------
+    #[error("unknown syscall: {0}")]
+    UnknownSyscall(String),
     #[error("error to determine current function start/end place")]
     FunctionRangeNotFound,
 
+    // --------------------------------- watchpoints -----------------------------------------------
+    #[error("`{0}` not found or not located in memory")]
+    WatchpointNoMemory(String),
+    #[error("not enough free debug registers to watch {0} bytes")]
+    WatchpointLimit(usize),
+    #[error("invalid size of watched memory: {0}")]
+    WatchpointSize(usize),
+    #[error("no free debug registers for a hardware breakpoint")]
+    HardwareBreakpointLimit,
+
+    // --------------------------------- expressions -----------------------------------------------
+    #[error("expression evaluation: {0}")]
+    ExprEvaluation(String),
+    #[error("write variable: {0}")]
+    VariableWrite(String),
+    #[error("examine memory: {0}")]
+    ExamineFormat(String),
+    #[error("function call: {0}")]
+    FunctionCall(String),
+
+    // --------------------------------- record and replay -----------------------------------------
+    #[error("recording is not started, use `record` command first")]
+    RecordingNotStarted,
+    #[error("no recorded state before the current one")]
+    NoRecordedState,
+
+    // --------------------------------- breakpoint at address -------------------------------------
+    #[error("address {0} is outside of executable memory mappings")]
+    AddressNotExecutable(RelocatedAddress),
+    #[error("offset is out of symbol `{0}` range (symbol size is {1} bytes)")]
+    SymbolOffsetOutOfRange(String, u64),
+
+    // --------------------------------- skip rules ------------------------------------------------
+    #[error("invalid skip rule pattern: {0}")]
+    InvalidSkipRule(String),
+    #[error("skip rule {0} not found")]
+    SkipRuleNotFound(usize),
+
+    // --------------------------------- step into target ------------------------------------------
+    #[error("no call to `{0}` at current line")]
+    StepTargetNotFound(String),
+
+    // --------------------------------- jump ------------------------------------------------------
+    #[error("jump target is outside of current function, force a jump to leave it")]
+    JumpOutsideFunction,
+
+    // --------------------------------- io capture ------------------------------------------------
+    #[error("debugee stdin is not captured or already closed")]
+    StdinNotCaptured,
+
+    // --------------------------------- checkpoints -----------------------------------------------
+    #[error("checkpoint {0} not found")]
+    CheckpointNotFound(u32),
+    #[error("create a debugee copy: {0}")]
+    Checkpoint(String),
+
     // --------------------------------- third party errors ----------------------------------------
     #[error("hook: {0}")]
-    Hook(anyhow::Error),
+    Hook(Box<dyn std::error::Error + Send + Sync>),
 
     // --------------------------------- attach debugee errors -------------------------------------
     #[error("process pid {0} not found")]
     AttachedProcessNotFound(Pid),
     #[error("attach a running process: {0}")]
     Attach(nix::Error),
+
+    // --------------------------------- post-mortem debugging -------------------------------------
+    #[error(transparent)]
+    CoreDump(#[from] CoreDumpError),
+    #[error("debugee is loaded from a core file and can't be executed")]
+    PostMortem,
 }
 
 impl Error {
@@ -151,6 +228,7 @@ impl Error {
     pub fn is_fatal(&self) -> bool {
         match self {
             Error::AlreadyRun => false,
+            Error::Cancelled(_) => false,
             Error::IO(_) => false,
             Error::Utf8(_) => false,
             Error::FromUtf8(_) => false,
@@ -165,6 +243,8 @@ impl Error {
             Error::TypeNotFound => false,
             Error::FrameNotFound(_) => false,
             Error::TraceeNotFound(_) => false,
+            Error::ThreadRunning(_) => false,
+            Error::ThreadNotFound(_) => false,
             Error::DieNotFound(_) => false,
             Error::TypeBinaryRepr(_, _) => false,
             Error::UnknownAddress => false,
@@ -195,8 +275,30 @@ impl Error {
             Error::Hook(_) => false,
             Error::SectionNotFound(_) => false,
             Error::DisAsm(_) => false,
+            Error::DisAsmRange(_) => false,
+            Error::UnknownSyscall(_) => false,
             Error::InvalidSpecification(_) => false,
             Error::FunctionRangeNotFound => false,
+            Error::WatchpointNoMemory(_) => false,
+            Error::WatchpointLimit(_) => false,
+            Error::WatchpointSize(_) => false,
+            Error::HardwareBreakpointLimit => false,
+            Error::ExprEvaluation(_) => false,
+            Error::VariableWrite(_) => false,
+            Error::ExamineFormat(_) => false,
+            Error::FunctionCall(_) => false,
+            Error::JumpOutsideFunction => false,
+            Error::StepTargetNotFound(_) => false,
+            Error::InvalidSkipRule(_) => false,
+            Error::AddressNotExecutable(_) => false,
+            Error::SymbolOffsetOutOfRange(_, _) => false,
+            Error::SkipRuleNotFound(_) => false,
+            Error::RecordingNotStarted => false,
+            Error::NoRecordedState => false,
+            Error::StdinNotCaptured => false,
+            Error::CheckpointNotFound(_) => false,
+            Error::Checkpoint(_) => false,
+            Error::PostMortem => false,
 
             // currently fatal errors
             Error::DwarfParsing(_) => true,
@@ -206,6 +308,7 @@ impl Error {
             Error::DisAsmInit(_) => true,
             Error::AttachedProcessNotFound(_) => true,
             Error::Attach(_) => true,
+            Error::CoreDump(_) => true,
         }
     }
 }
@@ -263,3 +366,134 @@ macro_rules! print_warns {
         })
     };
 }
+
+/// Category of an error, lets an interface react on errors programmatically
+/// (in batch mode, for example, category is converted into exit code).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ErrorCategory {
+    /// Internal debugger or environment error.
+    Internal,
+    /// Invalid command or command argument.
+    Usage,
+    /// Requested entity (place, function, frame, etc.) not found.
+    NotFound,
+    /// Debugee process is in an unsuitable state (not started, exited, etc.).
+    Process,
+    /// System call or remote memory access error.
+    System,
+    /// Debug information is broken or unsupported.
+    DebugInfo,
+    /// Operation cancelled by user.
+    Cancelled,
+}
+
+impl ErrorCategory {
+    /// Return process exit code for this category.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorCategory::Internal => 1,
+            ErrorCategory::Usage => 2,
+            ErrorCategory::NotFound => 3,
+            ErrorCategory::Process => 4,
+            ErrorCategory::System => 5,
+            ErrorCategory::DebugInfo => 6,
+            ErrorCategory::Cancelled => 130,
+        }
+    }
+}
+
+impl Error {
+    /// Return error category.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Cancelled(_) => ErrorCategory::Cancelled,
+            Error::VariableParsing(ParsingError::Cancelled(_)) => ErrorCategory::Cancelled,
+
+            Error::RegEx(_)
+            | Error::WatchpointLimit(_)
+            | Error::WatchpointSize(_)
+            | Error::HardwareBreakpointLimit
+            | Error::ExprEvaluation(_)
+            | Error::VariableWrite(_)
+            | Error::ExamineFormat(_)
+            | Error::FunctionCall(_)
+            | Error::RecordingNotStarted
+            | Error::JumpOutsideFunction
+            | Error::StepTargetNotFound(_)
+            | Error::InvalidSkipRule(_)
+            | Error::AddressNotExecutable(_)
+            | Error::SymbolOffsetOutOfRange(_, _)
+            | Error::DisAsmRange(_)
+            | Error::ThreadRunning(_)
+            | Error::StdinNotCaptured
+            | Error::PostMortem
+            | Error::UnknownSyscall(_) => ErrorCategory::Usage,
+
+            Error::NoDebugInformation(_)
+            | Error::RegisterNotFound(_)
+            | Error::RegisterNameNotFound(_)
+            | Error::PlaceNotFound(_)
+            | Error::NoSuitablePlace
+            | Error::UnitNotFound(_)
+            | Error::FunctionNotFound(_)
+            | Error::TypeNotFound
+            | Error::FrameNotFound(_)
+            | Error::TraceeNotFound(_)
+            | Error::ThreadNotFound(_)
+            | Error::DieNotFound(_)
+            | Error::SectionNotFound(_)
+            | Error::FunctionRangeNotFound
+            | Error::WatchpointNoMemory(_)
+            | Error::NoRecordedState
+            | Error::CheckpointNotFound(_)
+            | Error::SkipRuleNotFound(_) => ErrorCategory::NotFound,
+
+            Error::AlreadyRun
+            | Error::ProcessExit(_)
+            | Error::ProcessNotStarted
+            | Error::Checkpoint(_)
+            | Error::AttachedProcessNotFound(_)
+            | Error::Attach(_)
+            | Error::CoreDump(_) => ErrorCategory::Process,
+
+            Error::TypeBinaryRepr(_, _)
+            | Error::UnknownAddress
+            | Error::MappingOffsetNotFound(_)
+            | Error::MappingNotFound(_)
+            | Error::Waitpid(_)
+            | Error::Ptrace(_)
+            | Error::Syscall(_, _)
+            | Error::MultipleErrors(_) => ErrorCategory::System,
+
+            Error::DwarfParsing(_)
+            | Error::DebugIDFormat
+            | Error::ObjParsing(_)
+            | Error::VariableParsing(_)
+            | Error::InvalidSpecification(_)
+            | Error::UnwindNoContext
+            | Error::UnwindTooDeepFrame
+            | Error::EvalOptionRequired(_)
+            | Error::EvalUnsupportedRequire(_)
+            | Error::NoFBA
+            | Error::FBANotAnExpression
+            | Error::NoFunctionRanges(_)
+            | Error::NoDieType
+            | Error::ImplicitPointer => ErrorCategory::DebugInfo,
+            #[cfg(feature = "libunwind")]
+            Error::LibUnwind(_) => ErrorCategory::DebugInfo,
+
+            Error::IO(_)
+            | Error::Utf8(_)
+            | Error::FromUtf8(_)
+            | Error::NoThreadDB
+            | Error::ThreadDB(_)
+            | Error::Rendezvous(_)
+            | Error::DefaultToolchainNotFound
+            | Error::UnrecognizedRustupOut
+            | Error::DisAsmInit(_)
+            | Error::DisAsm(_)
+            | Error::Hook(_) => ErrorCategory::Internal,
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Channel-based stream of debugger events.
+//!
+//! An alternative to a custom [`EventHook`] implementation for applications that embed
+//! the debugger: events are delivered into a channel and may be consumed by any thread
+//! (or by an async runtime, using a blocking task).
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::capture::OutputStream;
+use crate::debugger::catchpoint::SyscallEvent;
+use crate::debugger::watchpoint::WatchValue;
+use crate::debugger::{
+    Error, EventHook, FunctionDie, PlaceDescriptor, PlaceDescriptorOwned, Tracee,
+};
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::mpsc::{Receiver, Sender};
+
+/// Debugger event, an owned copy of [`EventHook`] callback arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebuggerEvent {
+    /// Debugee stopped at a breakpoint.
+    Breakpoint {
+        pc: RelocatedAddress,
+        number: u32,
+        place: Option<PlaceDescriptorOwned>,
+        /// Full function name (with namespace).
+        function: Option<String>,
+    },
+    /// One of step commands is done.
+    Step {
+        pc: RelocatedAddress,
+        place: Option<PlaceDescriptorOwned>,
+        /// Full function name (with namespace).
+        function: Option<String>,
+    },
+    /// Watched memory is accessed.
+    Watchpoint {
+        pc: RelocatedAddress,
+        number: u32,
+        place: Option<PlaceDescriptorOwned>,
+        /// Full function name (with namespace).
+        function: Option<String>,
+        old: WatchValue,
+        new: WatchValue,
+    },
+    /// Debugee received an OS signal.
+    Signal(Signal),
+    /// Debugee thread stopped at a caught syscall entry or exit.
+    Syscall {
+        pc: RelocatedAddress,
+        event: SyscallEvent,
+    },
+    /// A single thread is stopped in non-stop mode, other threads keep running.
+    ThreadStop { pid: Pid, number: u32 },
+    /// Debugee exited with a code.
+    Exit(i32),
+    /// Debugee process is (re)installed.
+    ProcessInstall(Pid),
+    /// A chunk of captured debugee output.
+    Output { stream: OutputStream, data: Vec<u8> },
+    /// New debugee thread is created.
+    ThreadCreated(Pid),
+    /// Debugee thread exited.
+    ThreadExited(Pid),
+    /// Shared library is loaded.
+    SharedLibLoaded(PathBuf),
+    /// Debugee thread created a child process.
+    ProcessForked { parent: Pid, child: Pid },
+}
+
+fn function_name(function: Option<&FunctionDie>) -> Option<String> {
+    let function = function?;
+    let name = function.base_attributes.name.as_deref()?;
+    let mut namespace = function.namespace.join("::");
+    if !namespace.is_empty() {
+        namespace.push_str("::");
+    }
+    Some(namespace + name)
+}
+
+/// Event hook that sends all events into a channel.
+/// Events are silently dropped if a receiver is disconnected.
+///
+/// # Example
+///
+/// ```no_run
+/// use bugstalker::debugger::event::{DebuggerEvent, EventStream};
+/// use bugstalker::debugger::process::Child;
+/// use bugstalker::debugger::DebuggerBuilder;
+///
+/// let (stdout_reader, stdout) = os_pipe::pipe().unwrap();
+/// let (stderr_reader, stderr) = os_pipe::pipe().unwrap();
+/// let process = Child::new("./my_program", Vec::<String>::new(), stdout, stderr)
+///     .install()
+///     .unwrap();
+///
+/// let (hooks, events) = EventStream::new();
+/// let mut debugger = DebuggerBuilder::new().with_hooks(hooks).build(process).unwrap();
+/// debugger.set_breakpoint_at_fn("main").unwrap();
+/// debugger.start_debugee().unwrap();
+///
+/// if let Ok(DebuggerEvent::Breakpoint { function, .. }) = events.recv() {
+///     println!("stopped at {function:?}");
+/// }
+/// ```
+pub struct EventStream {
+    sender: Sender<DebuggerEvent>,
+}
+
+impl EventStream {
+    /// Create a hook and a receiver of its events.
+    pub fn new() -> (Self, Receiver<DebuggerEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        (Self { sender }, receiver)
+    }
+
+    fn send(&self, event: DebuggerEvent) {
+        _ = self.sender.send(event);
+    }
+}
+
+impl EventHook for EventStream {
+    fn on_breakpoint(
+        &self,
+        pc: RelocatedAddress,
+        num: u32,
+        place: Option<PlaceDescriptor>,
+        function: Option<&FunctionDie>,
+    ) -> Result<(), Error> {
+        self.send(DebuggerEvent::Breakpoint {
+            pc,
+            number: num,
+            place: place.map(|place| place.to_owned()),
+            function: function_name(function),
+        });
+        Ok(())
+    }
+
+    fn on_step(
+        &self,
+        pc: RelocatedAddress,
+        place: Option<PlaceDescriptor>,
+        function: Option<&FunctionDie>,
+    ) -> Result<(), Error> {
+        self.send(DebuggerEvent::Step {
+            pc,
+            place: place.map(|place| place.to_owned()),
+            function: function_name(function),
+        });
+        Ok(())
+    }
+
+    fn on_watchpoint(
+        &self,
+        pc: RelocatedAddress,
+        num: u32,
+        place: Option<PlaceDescriptor>,
+        function: Option<&FunctionDie>,
+        old: &WatchValue,
+        new: &WatchValue,
+    ) -> Result<(), Error> {
+        self.send(DebuggerEvent::Watchpoint {
+            pc,
+            number: num,
+            place: place.map(|place| place.to_owned()),
+            function: function_name(function),
+            old: old.clone(),
+            new: new.clone(),
+        });
+        Ok(())
+    }
+
+    fn on_signal(&self, signal: Signal) {
+        self.send(DebuggerEvent::Signal(signal));
+    }
+
+    fn on_syscall_catch(&self, pc: RelocatedAddress, event: &SyscallEvent) {
+        self.send(DebuggerEvent::Syscall {
+            pc,
+            event: event.clone(),
+        });
+    }
+
+    fn on_thread_stop(&self, thread: &Tracee) {
+        self.send(DebuggerEvent::ThreadStop {
+            pid: thread.pid,
+            number: thread.number,
+        });
+    }
+
+    fn on_exit(&self, code: i32) {
+        self.send(DebuggerEvent::Exit(code));
+    }
+
+    fn on_process_install(&self, pid: Pid, _: Option<&object::File>) {
+        self.send(DebuggerEvent::ProcessInstall(pid));
+    }
+
+    fn on_output_flush(&self) {}
+
+    fn on_debugee_output(&self, stream: OutputStream, data: &[u8]) {
+        self.send(DebuggerEvent::Output {
+            stream,
+            data: data.to_vec(),
+        });
+    }
+
+    fn on_thread_created(&self, pid: Pid) {
+        self.send(DebuggerEvent::ThreadCreated(pid));
+    }
+
+    fn on_thread_exited(&self, pid: Pid) {
+        self.send(DebuggerEvent::ThreadExited(pid));
+    }
+
+    fn on_shared_lib_loaded(&self, path: &Path) {
+        self.send(DebuggerEvent::SharedLibLoaded(path.to_path_buf()));
+    }
+
+    fn on_process_forked(&self, parent: Pid, child: Pid) {
+        self.send(DebuggerEvent::ProcessForked { parent, child });
+    }
+}
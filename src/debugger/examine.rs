@@ -0,0 +1,263 @@
+//! Formatted examination of debugee memory (like `x` command in gdb).
+//!
+//! Memory is split into units of the same size, units are interpreted as scalars
+//! of a requested format and grouped into rows of [`ROW_SIZE`] bytes.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::{ExamineFormat as InvalidFormat, ProcessNotStarted};
+use crate::debugger::variable::{scalar_from_bytes, SupportedScalar};
+use crate::debugger::Debugger;
+use bytes::Bytes;
+
+/// Count of bytes in a single row.
+pub const ROW_SIZE: usize = 16;
+
+/// Interpretation of memory units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExamineFormat {
+    /// Unsigned hexadecimal integer.
+    #[default]
+    Hex,
+    /// Signed decimal integer.
+    Decimal,
+    /// Unsigned decimal integer.
+    Unsigned,
+    /// Unsigned octal integer.
+    Octal,
+    /// Unsigned binary integer.
+    Binary,
+    /// Floating point number, unit size must be 4 or 8 bytes.
+    Float,
+    /// ASCII character, unit size must be 1 byte.
+    Char,
+}
+
+impl ExamineFormat {
+    /// Return a format by its letter (`x`, `d`, `u`, `o`, `t`, `f` or `c`, like in gdb).
+    pub fn from_letter(letter: char) -> Option<Self> {
+        Some(match letter {
+            'x' => ExamineFormat::Hex,
+            'd' => ExamineFormat::Decimal,
+            'u' => ExamineFormat::Unsigned,
+            'o' => ExamineFormat::Octal,
+            't' => ExamineFormat::Binary,
+            'f' => ExamineFormat::Float,
+            'c' => ExamineFormat::Char,
+            _ => return None,
+        })
+    }
+
+    /// Render a unit value, integers are rendered according to a format.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: unit value returned by [`Debugger::examine_memory`]
+    pub fn render(self, value: &SupportedScalar) -> String {
+        let (unsigned, size) = match *value {
+            SupportedScalar::U8(v) => (v as u64, 1),
+            SupportedScalar::U16(v) => (v as u64, 2),
+            SupportedScalar::U32(v) => (v as u64, 4),
+            SupportedScalar::U64(v) => (v, 8),
+            SupportedScalar::Char(c) => return format!("{c:?}"),
+            _ => return value.to_string(),
+        };
+        match self {
+            ExamineFormat::Hex => format!("{unsigned:#0width$x}", width = 2 + size * 2),
+            ExamineFormat::Octal => format!("{unsigned:#o}"),
+            ExamineFormat::Binary => format!("{unsigned:0width$b}", width = size * 8),
+            _ => unsigned.to_string(),
+        }
+    }
+}
+
+/// Size of a memory unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnitSize {
+    /// 1 byte.
+    Byte,
+    /// 2 bytes.
+    HalfWord,
+    /// 4 bytes.
+    #[default]
+    Word,
+    /// 8 bytes.
+    Giant,
+}
+
+impl UnitSize {
+    /// Return a unit size by its letter (`b`, `h`, `w` or `g`, like in gdb).
+    pub fn from_letter(letter: char) -> Option<Self> {
+        Some(match letter {
+            'b' => UnitSize::Byte,
+            'h' => UnitSize::HalfWord,
+            'w' => UnitSize::Word,
+            'g' => UnitSize::Giant,
+            _ => return None,
+        })
+    }
+
+    /// Return unit size in bytes.
+    pub fn bytes(self) -> usize {
+        match self {
+            UnitSize::Byte => 1,
+            UnitSize::HalfWord => 2,
+            UnitSize::Word => 4,
+            UnitSize::Giant => 8,
+        }
+    }
+}
+
+/// A row of examined memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryRow {
+    /// Address of the first byte of a row.
+    pub address: RelocatedAddress,
+    /// Raw bytes of a row.
+    pub bytes: Vec<u8>,
+    /// Interpreted units of a row.
+    pub values: Vec<SupportedScalar>,
+}
+
+impl MemoryRow {
+    /// Return row bytes as ASCII characters, non-printable bytes are replaced with `.`.
+    pub fn ascii(&self) -> String {
+        self.bytes
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect()
+    }
+}
+
+/// Interpret a memory unit as a scalar of a given format.
+fn unit_value(format: ExamineFormat, size: UnitSize, unit: &[u8]) -> SupportedScalar {
+    let unit = Bytes::copy_from_slice(unit);
+    match (format, size) {
+        (ExamineFormat::Char, _) => SupportedScalar::Char(scalar_from_bytes::<u8>(&unit) as char),
+        (ExamineFormat::Float, UnitSize::Giant) => SupportedScalar::F64(scalar_from_bytes(&unit)),
+        (ExamineFormat::Float, _) => SupportedScalar::F32(scalar_from_bytes(&unit)),
+        (ExamineFormat::Decimal, UnitSize::Byte) => SupportedScalar::I8(scalar_from_bytes(&unit)),
+        (ExamineFormat::Decimal, UnitSize::HalfWord) => {
+            SupportedScalar::I16(scalar_from_bytes(&unit))
+        }
+        (ExamineFormat::Decimal, UnitSize::Word) => SupportedScalar::I32(scalar_from_bytes(&unit)),
+        (ExamineFormat::Decimal, UnitSize::Giant) => SupportedScalar::I64(scalar_from_bytes(&unit)),
+        (_, UnitSize::Byte) => SupportedScalar::U8(scalar_from_bytes(&unit)),
+        (_, UnitSize::HalfWord) => SupportedScalar::U16(scalar_from_bytes(&unit)),
+        (_, UnitSize::Word) => SupportedScalar::U32(scalar_from_bytes(&unit)),
+        (_, UnitSize::Giant) => SupportedScalar::U64(scalar_from_bytes(&unit)),
+    }
+}
+
+/// Split memory into rows of interpreted units.
+///
+/// # Arguments
+///
+/// * `addr`: address of the first byte
+/// * `data`: memory content
+/// * `format`: units format
+/// * `size`: unit size
+fn into_rows(addr: usize, data: &[u8], format: ExamineFormat, size: UnitSize) -> Vec<MemoryRow> {
+    data.chunks(ROW_SIZE)
+        .enumerate()
+        .map(|(i, row)| MemoryRow {
+            address: RelocatedAddress::from(addr + i * ROW_SIZE),
+            bytes: row.to_vec(),
+            values: row
+                .chunks(size.bytes())
+                .map(|unit| unit_value(format, size, unit))
+                .collect(),
+        })
+        .collect()
+}
+
+impl Debugger {
+    /// Read `count` units of debugee memory and interpret them according to a format.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: address of the first unit
+    /// * `count`: count of units
+    /// * `format`: units format
+    /// * `size`: unit size
+    pub fn examine_memory(
+        &self,
+        addr: usize,
+        count: usize,
+        format: ExamineFormat,
+        size: UnitSize,
+    ) -> Result<Vec<MemoryRow>, Error> {
+        if !self.debugee.is_in_progress() {
+            return Err(ProcessNotStarted);
+        }
+        match (format, size) {
+            (ExamineFormat::Float, UnitSize::Byte | UnitSize::HalfWord) => {
+                return Err(InvalidFormat(
+                    "float units must be 4 or 8 bytes long".to_string(),
+                ))
+            }
+            (ExamineFormat::Char, UnitSize::HalfWord | UnitSize::Word | UnitSize::Giant) => {
+                return Err(InvalidFormat("char units must be 1 byte long".to_string()))
+            }
+            _ => {}
+        }
+
+        let data = self.read_memory(addr, count * size.bytes())?;
+        Ok(into_rows(addr, &data, format, size))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_into_rows() {
+        let data = (0..20u8).collect::<Vec<_>>();
+        let rows = into_rows(0x100, &data, ExamineFormat::Hex, UnitSize::Word);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].address, RelocatedAddress::from(0x100usize));
+        assert_eq!(rows[0].values.len(), 4);
+        assert_eq!(rows[0].values[0], SupportedScalar::U32(0x03020100));
+        assert_eq!(rows[1].address, RelocatedAddress::from(0x110usize));
+        assert_eq!(rows[1].bytes, vec![16, 17, 18, 19]);
+
+        let rows = into_rows(0, &[0xff, 0x41], ExamineFormat::Decimal, UnitSize::Byte);
+        assert_eq!(
+            rows[0].values,
+            vec![SupportedScalar::I8(-1), SupportedScalar::I8(0x41)]
+        );
+        assert_eq!(rows[0].ascii(), ".A");
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            ExamineFormat::Hex.render(&SupportedScalar::U16(0xa)),
+            "0x000a"
+        );
+        assert_eq!(ExamineFormat::Octal.render(&SupportedScalar::U8(8)), "0o10");
+        assert_eq!(
+            ExamineFormat::Binary.render(&SupportedScalar::U8(5)),
+            "00000101"
+        );
+        assert_eq!(
+            ExamineFormat::Unsigned.render(&SupportedScalar::U32(7)),
+            "7"
+        );
+        assert_eq!(
+            ExamineFormat::Decimal.render(&SupportedScalar::I8(-1)),
+            "-1"
+        );
+        assert_eq!(
+            ExamineFormat::Char.render(&SupportedScalar::Char('\n')),
+            "'\\n'"
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Open file descriptors of a debugee process.
+//!
+//! Descriptors are read from `/proc/<pid>/fd` (descriptor target) and `/proc/<pid>/fdinfo`
+//! (flags and offset). Socket descriptors are resolved into addresses
+//! with `/proc/<pid>/net/{tcp,tcp6,udp,udp6,unix}` tables.
+
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::ProcessNotStarted;
+use crate::debugger::Debugger;
+use nix::libc;
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::PathBuf;
+use std::{fs, io};
+
+/// What an open file descriptor refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FdTarget {
+    /// Regular file, directory or device.
+    File(PathBuf),
+    /// Socket, `description` contains protocol and addresses if socket is found
+    /// in network tables.
+    Socket {
+        inode: u64,
+        description: Option<String>,
+    },
+    /// Pipe or FIFO.
+    Pipe(u64),
+    /// Anonymous inode (eventfd, epoll, timerfd, etc.), contains a kind of the inode.
+    AnonInode(String),
+    /// Any other link target.
+    Other(String),
+}
+
+impl Display for FdTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FdTarget::File(path) => write!(f, "{}", path.display()),
+            FdTarget::Socket {
+                description: Some(description),
+                ..
+            } => write!(f, "socket {description}"),
+            FdTarget::Socket { inode, .. } => write!(f, "socket:[{inode}]"),
+            FdTarget::Pipe(inode) => write!(f, "pipe:[{inode}]"),
+            FdTarget::AnonInode(kind) => write!(f, "anon_inode:{kind}"),
+            FdTarget::Other(target) => f.write_str(target),
+        }
+    }
+}
+
+/// Single open file descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDescriptor {
+    pub fd: i32,
+    pub target: FdTarget,
+    /// File status flags and access mode (`O_*` flags).
+    pub flags: i32,
+    /// File offset.
+    pub pos: u64,
+}
+
+impl FileDescriptor {
+    /// Return flags as a list of `O_*` constants names.
+    pub fn flag_names(&self) -> Vec<&'static str> {
+        let mode = match self.flags & libc::O_ACCMODE {
+            libc::O_WRONLY => "O_WRONLY",
+            libc::O_RDWR => "O_RDWR",
+            _ => "O_RDONLY",
+        };
+        let known = [
+            (libc::O_APPEND, "O_APPEND"),
+            (libc::O_NONBLOCK, "O_NONBLOCK"),
+            (libc::O_CLOEXEC, "O_CLOEXEC"),
+            (libc::O_DIRECT, "O_DIRECT"),
+            (libc::O_DIRECTORY, "O_DIRECTORY"),
+            (libc::O_NOATIME, "O_NOATIME"),
+            (libc::O_SYNC, "O_SYNC"),
+            (libc::O_DSYNC, "O_DSYNC"),
+            (libc::O_ASYNC, "O_ASYNC"),
+            (libc::O_PATH, "O_PATH"),
+        ];
+
+        let mut names = vec![mode];
+        for (flag, name) in known {
+            // O_SYNC includes O_DSYNC bit
+            if self.flags & flag == flag && !(flag == libc::O_DSYNC && names.contains(&"O_SYNC")) {
+                names.push(name);
+            }
+        }
+        names
+    }
+}
+
+/// Parse a `/proc/<pid>/fd/<fd>` link target.
+fn parse_target(link: &str, sockets: &HashMap<u64, String>) -> FdTarget {
+    let inode = |prefix: &str| {
+        link.strip_prefix(prefix)
+            .and_then(|s| s.strip_prefix('['))
+            .and_then(|s| s.strip_suffix(']'))
+            .and_then(|s| s.parse::<u64>().ok())
+    };
+
+    if let Some(inode) = inode("socket:") {
+        return FdTarget::Socket {
+            inode,
+            description: sockets.get(&inode).cloned(),
+        };
+    }
+    if let Some(inode) = inode("pipe:") {
+        return FdTarget::Pipe(inode);
+    }
+    if let Some(kind) = link.strip_prefix("anon_inode:") {
+        return FdTarget::AnonInode(kind.to_string());
+    }
+    if link.starts_with('/') {
+        return FdTarget::File(PathBuf::from(link));
+    }
+    FdTarget::Other(link.to_string())
+}
+
+/// Parse `/proc/<pid>/fdinfo/<fd>` content, return flags and offset.
+fn parse_fdinfo(content: &str) -> (i32, u64) {
+    let mut flags = 0;
+    let mut pos = 0;
+    for line in content.lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            match key {
+                "flags" => flags = i32::from_str_radix(value.trim(), 8).unwrap_or_default(),
+                "pos" => pos = value.trim().parse().unwrap_or_default(),
+                _ => {}
+            }
+        }
+    }
+    (flags, pos)
+}
+
+/// Parse an address from a `/proc/net/{tcp,udp}` table, like `0100007F:1F90`.
+fn parse_inet_addr(addr: &str) -> Option<SocketAddr> {
+    let (ip, port) = addr.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let ip = match ip.len() {
+        8 => Ipv4Addr::from(u32::from_be(u32::from_str_radix(ip, 16).ok()?)).into(),
+        32 => {
+            // four 32-bit words, each in host byte order
+            let mut octets = [0; 16];
+            for (i, chunk) in octets.chunks_mut(4).enumerate() {
+                let word = u32::from_str_radix(&ip[i * 8..i * 8 + 8], 16).ok()?;
+                chunk.copy_from_slice(&u32::from_be(word).to_be_bytes());
+            }
+            Ipv6Addr::from(octets).into()
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+/// Parse a `/proc/net/{tcp,tcp6,udp,udp6}` table, return socket descriptions by inode.
+fn parse_inet_table<'a>(content: &'a str, proto: &str) -> impl Iterator<Item = (u64, String)> + 'a {
+    let proto = proto.to_string();
+    content.lines().skip(1).filter_map(move |line| {
+        let columns: Vec<_> = line.split_whitespace().collect();
+        let local = parse_inet_addr(columns.get(1)?)?;
+        let remote = parse_inet_addr(columns.get(2)?)?;
+        let inode = columns.get(9)?.parse().ok()?;
+        let description = if remote.port() == 0 {
+            format!("{proto} {local}")
+        } else {
+            format!("{proto} {local} -> {remote}")
+        };
+        Some((inode, description))
+    })
+}
+
+/// Parse a `/proc/net/unix` table, return socket descriptions by inode.
+fn parse_unix_table(content: &str) -> impl Iterator<Item = (u64, String)> + '_ {
+    content.lines().skip(1).filter_map(|line| {
+        let columns: Vec<_> = line.split_whitespace().collect();
+        let inode = columns.get(6)?.parse().ok()?;
+        let description = match columns.get(7) {
+            Some(path) => format!("unix {path}"),
+            None => "unix".to_string(),
+        };
+        Some((inode, description))
+    })
+}
+
+/// Return descriptions of all sockets visible from a process network namespace.
+fn sockets(pid: Pid) -> HashMap<u64, String> {
+    let mut sockets = HashMap::new();
+    let read_table = |name: &str| fs::read_to_string(format!("/proc/{pid}/net/{name}")).ok();
+    for proto in ["tcp", "tcp6", "udp", "udp6"] {
+        if let Some(content) = read_table(proto) {
+            sockets.extend(parse_inet_table(&content, proto));
+        }
+    }
+    if let Some(content) = read_table("unix") {
+        sockets.extend(parse_unix_table(&content));
+    }
+    sockets
+}
+
+/// Return open file descriptors of a process, sorted by descriptor number.
+///
+/// # Arguments
+///
+/// * `pid`: process id
+pub fn open_fds(pid: Pid) -> Result<Vec<FileDescriptor>, io::Error> {
+    let sockets = sockets(pid);
+
+    let mut fds = vec![];
+    for entry in fs::read_dir(format!("/proc/{pid}/fd"))? {
+        let entry = entry?;
+        let Some(fd) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+            continue;
+        };
+        // descriptor may be closed while directory is read
+        let Ok(link) = fs::read_link(entry.path()) else {
+            continue;
+        };
+        let (flags, pos) = fs::read_to_string(format!("/proc/{pid}/fdinfo/{fd}"))
+            .map(|content| parse_fdinfo(&content))
+            .unwrap_or_default();
+
+        fds.push(FileDescriptor {
+            fd,
+            target: parse_target(&link.to_string_lossy(), &sockets),
+            flags,
+            pos,
+        });
+    }
+    fds.sort_by_key(|fd| fd.fd);
+    Ok(fds)
+}
+
+impl Debugger {
+    /// Return open file descriptors of a debugee process.
+    pub fn open_fds(&self) -> Result<Vec<FileDescriptor>, Error> {
+        if !self.debugee.is_in_progress() {
+            return Err(ProcessNotStarted);
+        }
+        Ok(open_fds(self.process.pid())?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        let sockets = HashMap::from([(42, "tcp 127.0.0.1:8080".to_string())]);
+        assert_eq!(
+            parse_target("/tmp/file.txt", &sockets),
+            FdTarget::File(PathBuf::from("/tmp/file.txt"))
+        );
+        assert_eq!(parse_target("pipe:[123]", &sockets), FdTarget::Pipe(123));
+        assert_eq!(
+            parse_target("socket:[42]", &sockets),
+            FdTarget::Socket {
+                inode: 42,
+                description: Some("tcp 127.0.0.1:8080".to_string())
+            }
+        );
+        assert_eq!(
+            parse_target("anon_inode:[eventfd]", &sockets),
+            FdTarget::AnonInode("[eventfd]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_fdinfo() {
+        let content = "pos:\t12\nflags:\t02004002\nmnt_id:\t29\nino:\t1234\n";
+        let (flags, pos) = parse_fdinfo(content);
+        assert_eq!(pos, 12);
+        let fd = FileDescriptor {
+            fd: 3,
+            target: FdTarget::Other(String::new()),
+            flags,
+            pos,
+        };
+        assert_eq!(fd.flag_names(), vec!["O_RDWR", "O_NONBLOCK", "O_CLOEXEC"]);
+    }
+
+    #[test]
+    fn test_parse_inet_table() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+            0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 111 1 0000000000000000 100 0 0 10 0\n   \
+            1: 0100007F:C350 0100007F:1F90 01 00000000:00000000 00:00000000 00000000  1000        0 222 1 0000000000000000 20 4 30 10 -1\n";
+        let sockets: HashMap<_, _> = parse_inet_table(tcp, "tcp").collect();
+        assert_eq!(sockets[&111], "tcp 127.0.0.1:8080");
+        assert_eq!(sockets[&222], "tcp 127.0.0.1:50000 -> 127.0.0.1:8080");
+
+        assert_eq!(
+            parse_inet_addr("00000000000000000000000001000000:0050"),
+            Some("[::1]:80".parse().unwrap())
+        );
+    }
+}
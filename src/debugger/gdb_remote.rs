@@ -0,0 +1,711 @@
+//! GDB remote serial protocol client.
+//!
+//! [`GdbRemote`] connects to a `gdbserver` (or a QEMU gdb stub) over TCP. Connected target
+//! is used as a [`TargetBackend`] for its threads (see [`GdbRemote::attach`]), so memory
+//! and register access of the debugger (data evaluation, unwinding, disassembler, breakpoints)
+//! goes through the remote stub instead of local ptrace.
+//!
+//! Execution of a remote target is controlled explicitly: [`GdbRemote::cont`] and
+//! [`GdbRemote::step`] resume a thread and wait for a stop reply. Local tracer (and so
+//! [`crate::debugger::Debugger::continue_debugee`]) still requires a ptrace-controlled process.
+//!
+//! Only x86_64 targets are supported.
+
+use crate::debugger::memory;
+use crate::debugger::memory::TargetBackend;
+use log::debug;
+use nix::libc::user_regs_struct;
+use nix::unistd::Pid;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::{io, mem};
+
+#[derive(thiserror::Error, Debug)]
+pub enum RemoteError {
+    #[error("remote connection: {0}")]
+    Io(#[from] io::Error),
+    #[error("remote protocol: {0}")]
+    Protocol(String),
+    #[error("remote target error {0:#04x}")]
+    ErrorReply(u8),
+    #[error("packet `{0}` is not supported by remote target")]
+    Unsupported(String),
+}
+
+/// Max count of bytes in a single memory read or write packet.
+const MEMORY_CHUNK_SIZE: usize = 0x800;
+/// Max count of attempts to send a packet (a stub asks to retransmit a packet with `-`).
+const SEND_ATTEMPTS: usize = 3;
+
+/// Reason of a remote target stop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReply {
+    /// Thread stopped by a signal (`S` and `T` replies).
+    Signal { signal: u8, thread: Option<Pid> },
+    /// Process exited (`W` reply).
+    Exited { code: u8 },
+    /// Process terminated by a signal (`X` reply).
+    Terminated { signal: u8 },
+}
+
+impl StopReply {
+    fn parse(reply: &str) -> Result<Self, RemoteError> {
+        let unexpected = || RemoteError::Protocol(format!("unexpected stop reply: {reply}"));
+        let kind = reply.chars().next().ok_or_else(unexpected)?;
+        let code = reply
+            .get(1..3)
+            .and_then(|code| u8::from_str_radix(code, 16).ok())
+            .ok_or_else(unexpected)?;
+
+        match kind {
+            'S' => Ok(StopReply::Signal {
+                signal: code,
+                thread: None,
+            }),
+            'T' => {
+                let thread = reply[3..]
+                    .split(';')
+                    .find_map(|pair| pair.strip_prefix("thread:"))
+                    .and_then(parse_thread_id);
+                Ok(StopReply::Signal {
+                    signal: code,
+                    thread,
+                })
+            }
+            'W' => Ok(StopReply::Exited { code }),
+            'X' => Ok(StopReply::Terminated { signal: code }),
+            _ => Err(unexpected()),
+        }
+    }
+}
+
+/// Parse a thread id (`tid` or `ppid.tid` in multiprocess mode), ids are hex numbers.
+fn parse_thread_id(id: &str) -> Option<Pid> {
+    let tid = match id.strip_prefix('p') {
+        Some(id) => id.split('.').nth(1)?,
+        None => id,
+    };
+    i32::from_str_radix(tid, 16).ok().map(Pid::from_raw)
+}
+
+fn checksum(payload: &[u8]) -> u8 {
+    payload.iter().fold(0_u8, |sum, b| sum.wrapping_add(*b))
+}
+
+/// Escape special characters and wrap payload into a packet (`$payload#checksum`).
+fn encode_packet(payload: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(payload.len());
+    for &b in payload {
+        if matches!(b, b'$' | b'#' | b'}' | b'*') {
+            escaped.extend([b'}', b ^ 0x20]);
+        } else {
+            escaped.push(b);
+        }
+    }
+
+    let mut packet = Vec::with_capacity(escaped.len() + 4);
+    packet.push(b'$');
+    packet.extend(&escaped);
+    packet.extend(format!("#{:02x}", checksum(&escaped)).as_bytes());
+    packet
+}
+
+/// Expand run-length encoding and escaped characters of a received packet body.
+fn decode_payload(body: &[u8]) -> Result<Vec<u8>, RemoteError> {
+    let mut payload = Vec::with_capacity(body.len());
+    let mut iter = body.iter();
+    while let Some(&b) = iter.next() {
+        match b {
+            b'}' => {
+                let escaped = iter
+                    .next()
+                    .ok_or_else(|| RemoteError::Protocol("unterminated escape".to_string()))?;
+                payload.push(escaped ^ 0x20);
+            }
+            b'*' => {
+                let last = *payload
+                    .last()
+                    .ok_or_else(|| RemoteError::Protocol("run-length without data".to_string()))?;
+                let count = iter
+                    .next()
+                    .ok_or_else(|| RemoteError::Protocol("unterminated run-length".to_string()))?;
+                let repeat = count.checked_sub(29).ok_or_else(|| {
+                    RemoteError::Protocol(format!("invalid run-length count {count}"))
+                })?;
+                payload.extend(std::iter::repeat(last).take(repeat as usize));
+            }
+            _ => payload.push(b),
+        }
+    }
+    Ok(payload)
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, RemoteError> {
+    if hex.len() % 2 != 0 {
+        return Err(RemoteError::Protocol(format!("odd length hex data: {hex}")));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            let byte = &hex[i..i + 2];
+            // unavailable register bytes are `xx`
+            if byte == "xx" {
+                return Ok(0);
+            }
+            u8::from_str_radix(byte, 16)
+                .map_err(|_| RemoteError::Protocol(format!("invalid hex data: {hex}")))
+        })
+        .collect()
+}
+
+fn encode_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Offset of `orig_rax`, `fs_base` and `gs_base` registers in a `g` packet of amd64-linux
+/// target (after general purpose, x87 and sse registers).
+const LINUX_REGISTERS_OFFSET: usize = 536;
+
+/// Return general purpose registers in `g` packet order (amd64 target description) with
+/// their sizes in bytes.
+fn gp_registers(regs: &mut user_regs_struct) -> [(&mut u64, usize); 24] {
+    [
+        (&mut regs.rax, 8),
+        (&mut regs.rbx, 8),
+        (&mut regs.rcx, 8),
+        (&mut regs.rdx, 8),
+        (&mut regs.rsi, 8),
+        (&mut regs.rdi, 8),
+        (&mut regs.rbp, 8),
+        (&mut regs.rsp, 8),
+        (&mut regs.r8, 8),
+        (&mut regs.r9, 8),
+        (&mut regs.r10, 8),
+        (&mut regs.r11, 8),
+        (&mut regs.r12, 8),
+        (&mut regs.r13, 8),
+        (&mut regs.r14, 8),
+        (&mut regs.r15, 8),
+        (&mut regs.rip, 8),
+        (&mut regs.eflags, 4),
+        (&mut regs.cs, 4),
+        (&mut regs.ss, 4),
+        (&mut regs.ds, 4),
+        (&mut regs.es, 4),
+        (&mut regs.fs, 4),
+        (&mut regs.gs, 4),
+    ]
+}
+
+fn linux_registers(regs: &mut user_regs_struct) -> [&mut u64; 3] {
+    [&mut regs.orig_rax, &mut regs.fs_base, &mut regs.gs_base]
+}
+
+fn read_le(data: &[u8]) -> u64 {
+    let mut bytes = [0; 8];
+    bytes[..data.len()].copy_from_slice(data);
+    u64::from_le_bytes(bytes)
+}
+
+/// Parse registers from a `g` packet data, registers missing in data are zeroed.
+fn parse_registers(data: &[u8]) -> Result<user_regs_struct, RemoteError> {
+    // SAFETY: user_regs_struct contains only integers
+    let mut regs: user_regs_struct = unsafe { mem::zeroed() };
+
+    let mut offset = 0;
+    for (reg, size) in gp_registers(&mut regs) {
+        let value = data
+            .get(offset..offset + size)
+            .ok_or_else(|| RemoteError::Protocol("truncated registers packet".to_string()))?;
+        *reg = read_le(value);
+        offset += size;
+    }
+
+    for (i, reg) in linux_registers(&mut regs).into_iter().enumerate() {
+        let offset = LINUX_REGISTERS_OFFSET + i * 8;
+        if let Some(value) = data.get(offset..offset + 8) {
+            *reg = read_le(value);
+        }
+    }
+
+    Ok(regs)
+}
+
+/// Write registers into a `g` packet data, registers that aren't present in data are ignored.
+fn write_registers(data: &mut [u8], mut regs: user_regs_struct) {
+    let mut offset = 0;
+    for (reg, size) in gp_registers(&mut regs) {
+        if let Some(place) = data.get_mut(offset..offset + size) {
+            place.copy_from_slice(&reg.to_le_bytes()[..size]);
+        }
+        offset += size;
+    }
+
+    for (i, reg) in linux_registers(&mut regs).into_iter().enumerate() {
+        let offset = LINUX_REGISTERS_OFFSET + i * 8;
+        if let Some(place) = data.get_mut(offset..offset + 8) {
+            place.copy_from_slice(&reg.to_le_bytes());
+        }
+    }
+}
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    no_ack: bool,
+}
+
+impl Connection {
+    fn send(&mut self, payload: &str) -> Result<(), RemoteError> {
+        let packet = encode_packet(payload.as_bytes());
+        for _ in 0..SEND_ATTEMPTS {
+            self.writer.write_all(&packet)?;
+            if self.no_ack {
+                return Ok(());
+            }
+            match self.read_byte()? {
+                b'+' => return Ok(()),
+                b'-' => continue,
+                b => {
+                    return Err(RemoteError::Protocol(format!(
+                        "unexpected acknowledgment `{}`",
+                        b as char
+                    )))
+                }
+            }
+        }
+        Err(RemoteError::Protocol(format!(
+            "packet `{payload}` rejected by remote target"
+        )))
+    }
+
+    fn read_byte(&mut self) -> Result<u8, RemoteError> {
+        let mut byte = [0];
+        self.reader.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn receive(&mut self) -> Result<String, RemoteError> {
+        loop {
+            // skip everything (like acknowledgments) before a packet start
+            let mut skipped = vec![];
+            self.reader.read_until(b'$', &mut skipped)?;
+            if skipped.last() != Some(&b'$') {
+                return Err(RemoteError::Io(io::ErrorKind::UnexpectedEof.into()));
+            }
+
+            let mut body = vec![];
+            self.reader.read_until(b'#', &mut body)?;
+            if body.pop() != Some(b'#') {
+                return Err(RemoteError::Io(io::ErrorKind::UnexpectedEof.into()));
+            }
+            let mut expected = [0; 2];
+            self.reader.read_exact(&mut expected)?;
+            let expected = std::str::from_utf8(&expected)
+                .ok()
+                .and_then(|cs| u8::from_str_radix(cs, 16).ok());
+
+            if !self.no_ack {
+                if expected != Some(checksum(&body)) {
+                    debug!(target: "debugger", "remote packet checksum mismatch, retransmit");
+                    self.writer.write_all(b"-")?;
+                    continue;
+                }
+                self.writer.write_all(b"+")?;
+            }
+
+            let payload = decode_payload(&body)?;
+            return String::from_utf8(payload)
+                .map_err(|_| RemoteError::Protocol("non utf-8 packet".to_string()));
+        }
+    }
+
+    /// Send a packet and return a reply, error replies (`Enn`) are returned as errors,
+    /// empty reply means that a packet is not supported.
+    fn request(&mut self, payload: &str) -> Result<String, RemoteError> {
+        self.send(payload)?;
+        let reply = self.receive()?;
+        if reply.is_empty() {
+            return Err(RemoteError::Unsupported(payload.to_string()));
+        }
+        if reply.len() == 3 && reply.starts_with('E') {
+            if let Ok(code) = u8::from_str_radix(&reply[1..], 16) {
+                return Err(RemoteError::ErrorReply(code));
+            }
+        }
+        Ok(reply)
+    }
+
+    fn request_ok(&mut self, payload: &str) -> Result<(), RemoteError> {
+        match self.request(payload)?.as_str() {
+            "OK" => Ok(()),
+            reply => Err(RemoteError::Protocol(format!(
+                "unexpected reply to `{payload}`: {reply}"
+            ))),
+        }
+    }
+}
+
+/// Connection to a remote target (`gdbserver` or a gdb stub).
+pub struct GdbRemote {
+    conn: Mutex<Connection>,
+}
+
+impl GdbRemote {
+    /// Connect to a remote target, for example `gdbserver localhost:1234 ./program`.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: target address
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<Self, RemoteError> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        let mut conn = Connection {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            no_ack: false,
+        };
+
+        let features = conn.request("qSupported:multiprocess+;swbreak+;vContSupported+")?;
+        if features.split(';').any(|f| f == "QStartNoAckMode+") {
+            conn.request_ok("QStartNoAckMode")?;
+            conn.no_ack = true;
+        }
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn with_conn<T>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> Result<T, RemoteError>,
+    ) -> Result<T, RemoteError> {
+        f(&mut self.conn.lock().unwrap())
+    }
+
+    /// Return a reason of the last target stop.
+    pub fn stop_reason(&self) -> Result<StopReply, RemoteError> {
+        self.with_conn(|conn| StopReply::parse(&conn.request("?")?))
+    }
+
+    /// Return ids of remote threads.
+    pub fn threads(&self) -> Result<Vec<Pid>, RemoteError> {
+        self.with_conn(|conn| {
+            let mut threads = vec![];
+            let mut reply = conn.request("qfThreadInfo")?;
+            while let Some(ids) = reply.strip_prefix('m') {
+                threads.extend(ids.split(',').filter_map(parse_thread_id));
+                reply = conn.request("qsThreadInfo")?;
+            }
+            Ok(threads)
+        })
+    }
+
+    /// Read target memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: start address
+    /// * `read_n`: read byte count
+    pub fn read_memory(&self, addr: usize, read_n: usize) -> Result<Vec<u8>, RemoteError> {
+        self.with_conn(|conn| {
+            let mut data = Vec::with_capacity(read_n);
+            while data.len() < read_n {
+                let chunk_addr = addr + data.len();
+                let chunk_len = (read_n - data.len()).min(MEMORY_CHUNK_SIZE);
+                let chunk = decode_hex(&conn.request(&format!("m{chunk_addr:x},{chunk_len:x}"))?)?;
+                if chunk.is_empty() {
+                    return Err(RemoteError::ErrorReply(0));
+                }
+                data.extend(chunk);
+            }
+            data.truncate(read_n);
+            Ok(data)
+        })
+    }
+
+    /// Write target memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: start address
+    /// * `data`: bytes to write
+    pub fn write_memory(&self, addr: usize, data: &[u8]) -> Result<(), RemoteError> {
+        self.with_conn(|conn| {
+            for (i, chunk) in data.chunks(MEMORY_CHUNK_SIZE).enumerate() {
+                let chunk_addr = addr + i * MEMORY_CHUNK_SIZE;
+                conn.request_ok(&format!(
+                    "M{chunk_addr:x},{:x}:{}",
+                    chunk.len(),
+                    encode_hex(chunk)
+                ))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Return registers of a thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `tid`: thread id
+    pub fn read_registers(&self, tid: Pid) -> Result<user_regs_struct, RemoteError> {
+        self.with_conn(|conn| {
+            conn.request_ok(&format!("Hg{:x}", tid.as_raw()))?;
+            parse_registers(&decode_hex(&conn.request("g")?)?)
+        })
+    }
+
+    /// Replace registers of a thread, registers unknown for the target are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `tid`: thread id
+    /// * `regs`: new register values
+    pub fn write_registers(&self, tid: Pid, regs: user_regs_struct) -> Result<(), RemoteError> {
+        self.with_conn(|conn| {
+            conn.request_ok(&format!("Hg{:x}", tid.as_raw()))?;
+            // registers that are not in user_regs_struct (x87, sse) are kept as is
+            let mut data = decode_hex(&conn.request("g")?)?;
+            write_registers(&mut data, regs);
+            conn.request_ok(&format!("G{}", encode_hex(&data)))
+        })
+    }
+
+    /// Set a software breakpoint, the remote target manages breakpoint instructions itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: breakpoint address
+    pub fn insert_breakpoint(&self, addr: usize) -> Result<(), RemoteError> {
+        self.with_conn(|conn| conn.request_ok(&format!("Z0,{addr:x},1")))
+    }
+
+    /// Remove a software breakpoint set by [`GdbRemote::insert_breakpoint`].
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: breakpoint address
+    pub fn remove_breakpoint(&self, addr: usize) -> Result<(), RemoteError> {
+        self.with_conn(|conn| conn.request_ok(&format!("z0,{addr:x},1")))
+    }
+
+    fn resume(&self, action: char, tid: Pid, signal: Option<u8>) -> Result<StopReply, RemoteError> {
+        self.with_conn(|conn| {
+            let action = match signal {
+                None => action.to_string(),
+                Some(signal) => format!("{}{signal:02x}", action.to_ascii_uppercase()),
+            };
+            let reply = match conn.request(&format!("vCont;{action}:{:x}", tid.as_raw())) {
+                Err(RemoteError::Unsupported(_)) => {
+                    conn.request_ok(&format!("Hc{:x}", tid.as_raw()))?;
+                    conn.request(&action)?
+                }
+                reply => reply?,
+            };
+            StopReply::parse(&reply)
+        })
+    }
+
+    /// Continue a thread and wait until target stops.
+    ///
+    /// # Arguments
+    ///
+    /// * `tid`: thread id
+    /// * `signal`: signal number delivered to a thread
+    pub fn cont(&self, tid: Pid, signal: Option<u8>) -> Result<StopReply, RemoteError> {
+        self.resume('c', tid, signal)
+    }
+
+    /// Execute a single instruction in a thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `tid`: thread id
+    /// * `signal`: signal number delivered to a thread
+    pub fn step(&self, tid: Pid, signal: Option<u8>) -> Result<StopReply, RemoteError> {
+        self.resume('s', tid, signal)
+    }
+
+    /// Detach from a remote target, target process continues execution.
+    pub fn disconnect(&self) -> Result<(), RemoteError> {
+        self.with_conn(|conn| conn.request_ok("D"))
+    }
+
+    /// Use remote target as a memory and registers backend for all its threads. After this,
+    /// debugger functions that take a thread id access a remote target instead of using ptrace.
+    pub fn attach(self) -> Result<Arc<Self>, RemoteError> {
+        let remote = Arc::new(self);
+        for tid in remote.threads()? {
+            memory::attach_backend(tid, remote.clone());
+        }
+        Ok(remote)
+    }
+
+    /// Stop using remote target as a memory and registers backend.
+    pub fn detach(&self) -> Result<(), RemoteError> {
+        for tid in self.threads()? {
+            memory::detach_backend(tid);
+        }
+        Ok(())
+    }
+}
+
+fn to_errno(e: RemoteError) -> nix::Error {
+    debug!(target: "debugger", "remote target: {e}");
+    match e {
+        RemoteError::ErrorReply(_) => nix::Error::EFAULT,
+        RemoteError::Unsupported(_) => nix::Error::ENOSYS,
+        RemoteError::Io(_) | RemoteError::Protocol(_) => nix::Error::EIO,
+    }
+}
+
+impl TargetBackend for GdbRemote {
+    fn read_memory(&self, addr: usize, read_n: usize) -> Result<Vec<u8>, nix::Error> {
+        GdbRemote::read_memory(self, addr, read_n).map_err(to_errno)
+    }
+
+    fn registers(&self, tid: Pid) -> Option<user_regs_struct> {
+        self.read_registers(tid).map_err(to_errno).ok()
+    }
+
+    fn write_memory(&self, addr: usize, data: &[u8]) -> Result<(), nix::Error> {
+        GdbRemote::write_memory(self, addr, data).map_err(to_errno)
+    }
+
+    fn set_registers(&self, tid: Pid, regs: user_regs_struct) -> Result<(), nix::Error> {
+        self.write_registers(tid, regs).map_err(to_errno)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_packet_encoding() {
+        assert_eq!(encode_packet(b"g"), b"$g#67");
+        assert_eq!(encode_packet(b"a#b"), b"$a}\x03b#43");
+        assert_eq!(decode_payload(b"a}\x03b").unwrap(), b"a#b");
+        // `0* ` is `0` repeated 3 more times
+        assert_eq!(decode_payload(b"0* ").unwrap(), b"0000");
+        assert!(decode_payload(b"*!").is_err());
+    }
+
+    #[test]
+    fn test_stop_reply() {
+        assert_eq!(
+            StopReply::parse("T05thread:p1f.20;core:1;").unwrap(),
+            StopReply::Signal {
+                signal: 5,
+                thread: Some(Pid::from_raw(0x20)),
+            }
+        );
+        assert_eq!(
+            StopReply::parse("S0b").unwrap(),
+            StopReply::Signal {
+                signal: 11,
+                thread: None,
+            }
+        );
+        assert_eq!(
+            StopReply::parse("W00").unwrap(),
+            StopReply::Exited { code: 0 }
+        );
+        assert!(StopReply::parse("OK").is_err());
+    }
+
+    #[test]
+    fn test_registers_round_trip() {
+        let mut data = vec![0; LINUX_REGISTERS_OFFSET + 24];
+        // SAFETY: user_regs_struct contains only integers
+        let mut regs: user_regs_struct = unsafe { mem::zeroed() };
+        regs.rax = 1;
+        regs.rip = 0x5555_5555_1000;
+        regs.eflags = 0x246;
+        regs.gs = 7;
+        regs.fs_base = 0x7fff_0000;
+
+        write_registers(&mut data, regs);
+        assert_eq!(&data[..8], &1_u64.to_le_bytes());
+        let parsed = parse_registers(&data).unwrap();
+        assert_eq!(parsed.rax, 1);
+        assert_eq!(parsed.rip, 0x5555_5555_1000);
+        assert_eq!(parsed.eflags, 0x246);
+        assert_eq!(parsed.gs, 7);
+        assert_eq!(parsed.fs_base, 0x7fff_0000);
+
+        assert!(parse_registers(&data[..100]).is_err());
+    }
+
+    /// Serve a single connection, reply to known packets, memory is `[0xAA; 16]` at 0x1000.
+    fn serve_stub(listener: TcpListener) {
+        let (stream, _) = listener.accept().unwrap();
+        let mut conn = Connection {
+            reader: BufReader::new(stream.try_clone().unwrap()),
+            writer: stream,
+            no_ack: false,
+        };
+        let mut memory = [0xAA_u8; 16];
+        loop {
+            let Ok(request) = conn.receive() else {
+                return;
+            };
+            let reply = match request.as_str() {
+                r if r.starts_with("qSupported") => "PacketSize=1000;QStartNoAckMode+".to_string(),
+                "QStartNoAckMode" => {
+                    conn.no_ack = true;
+                    "OK".to_string()
+                }
+                "qfThreadInfo" => "m1f,20".to_string(),
+                "qsThreadInfo" => "l".to_string(),
+                "m1000,4" => encode_hex(&memory[..4]),
+                r if r.starts_with("M1000,2:") => {
+                    memory[..2].copy_from_slice(&decode_hex(&r[8..]).unwrap());
+                    "OK".to_string()
+                }
+                r if r.starts_with('m') => "E14".to_string(),
+                "D" => "OK".to_string(),
+                _ => String::new(),
+            };
+            conn.writer
+                .write_all(&encode_packet(reply.as_bytes()))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_remote_memory() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let stub = thread::spawn(move || serve_stub(listener));
+
+        let remote = GdbRemote::connect(addr).unwrap();
+        assert_eq!(
+            remote.threads().unwrap(),
+            vec![Pid::from_raw(0x1f), Pid::from_raw(0x20)]
+        );
+        assert_eq!(remote.read_memory(0x1000, 4).unwrap(), vec![0xAA; 4]);
+        remote.write_memory(0x1000, &[1, 2]).unwrap();
+        assert_eq!(
+            remote.read_memory(0x1000, 4).unwrap(),
+            vec![1, 2, 0xAA, 0xAA]
+        );
+        assert!(matches!(
+            remote.read_memory(0x2000, 4),
+            Err(RemoteError::ErrorReply(0x14))
+        ));
+        assert!(matches!(
+            remote.stop_reason(),
+            Err(RemoteError::Unsupported(_))
+        ));
+
+        remote.disconnect().unwrap();
+        drop(remote);
+        stub.join().unwrap();
+    }
+}
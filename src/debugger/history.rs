@@ -0,0 +1,197 @@
+//! Session history, a log of debugee stops.
+//!
+//! Every stop that is reported to the user (breakpoint or watchpoint hit, step, signal, caught
+//! syscall or debugee exit) is saved with a time, thread, place and, optionally, a few top frames
+//! of the thread stack. History survives debugee restarts and may be duplicated into a text file,
+//! so a long automated run can be reviewed afterward.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::debugee::dwarf::unit::PlaceDescriptorOwned;
+use crate::debugger::error::Error;
+use crate::debugger::Debugger;
+use crate::weak_error;
+use chrono::{DateTime, Local};
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Max count of saved records, the oldest records are dropped first.
+const HISTORY_LIMIT: usize = 10_000;
+
+/// Reason of a debugee stop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopEvent {
+    /// User defined breakpoint hit, contains a breakpoint number.
+    Breakpoint(u32),
+    /// Watchpoint hit, contains a watchpoint number.
+    Watchpoint(u32),
+    /// Stop after a step.
+    Step,
+    /// Stop by a signal.
+    Signal(Signal),
+    /// Stop at a caught syscall.
+    Syscall,
+    /// Debugee exit with a code.
+    Exit(i32),
+}
+
+impl Display for StopEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StopEvent::Breakpoint(num) => write!(f, "breakpoint #{num}"),
+            StopEvent::Watchpoint(num) => write!(f, "watchpoint #{num}"),
+            StopEvent::Step => f.write_str("step"),
+            StopEvent::Signal(signal) => write!(f, "signal {signal}"),
+            StopEvent::Syscall => f.write_str("syscall"),
+            StopEvent::Exit(code) => write!(f, "exit with code {code}"),
+        }
+    }
+}
+
+/// Saved debugee stop.
+#[derive(Debug, Clone)]
+pub struct StopRecord {
+    pub time: DateTime<Local>,
+    pub event: StopEvent,
+    /// Stopped thread (thread in focus for a debugee exit).
+    pub pid: Pid,
+    /// Program counter of a stopped thread, `None` after debugee exit.
+    pub pc: Option<RelocatedAddress>,
+    pub place: Option<PlaceDescriptorOwned>,
+    pub function: Option<String>,
+    /// Function names of top stack frames, `None` if stack capturing is disabled.
+    pub stack: Option<Vec<String>>,
+}
+
+impl Display for StopRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} thread {}",
+            self.time.format("%H:%M:%S%.3f"),
+            self.event,
+            self.pid
+        )?;
+        if let Some(pc) = self.pc {
+            write!(f, " at {pc}")?;
+        }
+        if let Some(ref function) = self.function {
+            write!(f, " in {function}")?;
+        }
+        if let Some(ref place) = self.place {
+            write!(f, " ({}:{})", place.file.display(), place.line_number)?;
+        }
+        if let Some(ref stack) = self.stack {
+            write!(f, " [{}]", stack.join(" <- "))?;
+        }
+        Ok(())
+    }
+}
+
+/// History state.
+#[derive(Default)]
+pub(super) struct History {
+    records: VecDeque<StopRecord>,
+    /// Count of top stack frames saved with each record, 0 if stack isn't saved.
+    stack_depth: usize,
+    /// File where records are duplicated.
+    log: Option<(PathBuf, File)>,
+}
+
+impl Debugger {
+    /// Return saved debugee stops, the oldest first.
+    pub fn history(&self) -> impl ExactSizeIterator<Item = &StopRecord> {
+        self.history.records.iter()
+    }
+
+    /// Remove all saved debugee stops.
+    pub fn clear_history(&mut self) {
+        self.history.records.clear();
+    }
+
+    /// Set count of top stack frames saved with each stop.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth`: count of frames, 0 disables stack saving
+    pub fn set_history_stack_depth(&mut self, depth: usize) {
+        self.history.stack_depth = depth;
+    }
+
+    /// Duplicate new history records into a text file (one record per line).
+    /// A file is opened in append mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: path to a log file, `None` disables logging
+    pub fn set_history_log(&mut self, path: Option<&Path>) -> Result<(), Error> {
+        self.history.log = match path {
+            None => None,
+            Some(path) => {
+                let file = OpenOptions::new().create(true).append(true).open(path)?;
+                Some((path.to_path_buf(), file))
+            }
+        };
+        Ok(())
+    }
+
+    /// Return path to a history log file if history logging is enabled.
+    pub fn history_log(&self) -> Option<&Path> {
+        self.history.log.as_ref().map(|(path, _)| path.as_path())
+    }
+
+    /// Save a debugee stop in the history, the stop place is taken from the exploration context.
+    ///
+    /// # Arguments
+    ///
+    /// * `event`: stop reason
+    pub(super) fn log_stop(&mut self, event: StopEvent) {
+        let pid = self.exploration_ctx().pid_on_focus();
+        let mut record = StopRecord {
+            time: Local::now(),
+            event,
+            pid,
+            pc: None,
+            place: None,
+            function: None,
+            stack: None,
+        };
+
+        if self.debugee.is_in_progress() {
+            let location = self.exploration_ctx().location();
+            record.pc = Some(location.pc);
+            // there is no debug information in stripped library frames
+            if let Ok(dwarf) = self.debugee.debug_info(location.pc) {
+                record.place = weak_error!(dwarf.find_place_from_pc(location.global_pc))
+                    .flatten()
+                    .map(|place| place.to_owned());
+                record.function = weak_error!(dwarf.find_function_by_pc(location.global_pc))
+                    .flatten()
+                    .and_then(|func| func.full_name());
+            }
+            if self.history.stack_depth > 0 {
+                record.stack = weak_error!(self
+                    .debugee
+                    .unwind(pid, Some(self.history.stack_depth)))
+                .map(|bt| {
+                    bt.into_iter()
+                        .map(|frame| frame.func_name.unwrap_or_else(|| "???".to_string()))
+                        .collect()
+                });
+            }
+        }
+
+        if let Some((_, ref mut file)) = self.history.log {
+            weak_error!(writeln!(file, "{record}"));
+        }
+
+        if self.history.records.len() == HISTORY_LIMIT {
+            self.history.records.pop_front();
+        }
+        self.history.records.push_back(record);
+    }
+}
@@ -0,0 +1,272 @@
+//! Machine-readable (JSON) representation of a debugger state: variables, breakpoints,
+//! watchpoints, backtraces, threads, functions, symbols and async tasks. Available with `json` feature (enabled by `ui` feature).
+//!
+//! Values are built from the same data as a rendered text, so addresses are hex strings
+//! and scalar values are strings, like in the console (`{"name": "a", "type": "i32", "value": "1"}`).
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::r#async::{AsyncFrame, AsyncTask};
+use crate::debugger::unwind::FrameSpan;
+use crate::debugger::variable::render::{ref_counts, RenderRepr, ValueLayout};
+use crate::debugger::variable::VariableIR;
+use crate::debugger::watchpoint::Watchpoint;
+use crate::debugger::{
+    BreakpointView, BreakpointViewOwned, FrameInfo, FunctionInfo, SymbolInfo, ThreadSnapshot,
+};
+use serde_json::{json, Value};
+use std::borrow::Cow;
+
+/// Conversion into a JSON value.
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+impl ToJson for VariableIR {
+    fn to_json(&self) -> Value {
+        let mut result = json!({ "name": self.name(), "type": self.r#type() });
+        match self.value() {
+            Some(ValueLayout::PreRendered(value)) => result["value"] = value.into(),
+            Some(ValueLayout::Referential { addr }) => {
+                result["address"] = RelocatedAddress::from(addr as usize).to_string().into();
+                if let Some(counts) = ref_counts(self) {
+                    result["strong"] = counts.strong.into();
+                    result["weak"] = counts.weak.into();
+                }
+            }
+            Some(ValueLayout::Wrapped(inner)) => result["value"] = inner.to_json(),
+            Some(ValueLayout::Structure { members }) => {
+                result["members"] = members.iter().map(ToJson::to_json).collect()
+            }
+            Some(ValueLayout::List { members, .. }) => {
+                result["items"] = members.iter().map(ToJson::to_json).collect()
+            }
+            Some(ValueLayout::Map(kvs)) => {
+                result["entries"] = kvs
+                    .iter()
+                    .map(|(key, value)| json!({ "key": key.to_json(), "value": value.to_json() }))
+                    .collect()
+            }
+            None => result["value"] = Value::Null,
+        }
+        result
+    }
+}
+
+impl ToJson for BreakpointView<'_> {
+    fn to_json(&self) -> Value {
+        json!({
+            "number": self.number,
+            "address": self.addr.to_string(),
+            "file": self.place.as_ref().map(|place| place.file.to_string_lossy()),
+            "line": self.place.as_ref().map(|place| place.line_number),
+            "disabled": self.options.disabled,
+            "condition": self.options.condition.as_ref().map(|cond| &cond.source),
+            "hits": self.options.hit_count,
+            "ignore": self.options.ignore_count,
+            "hardware": self.options.hardware.is_some(),
+        })
+    }
+}
+
+impl ToJson for BreakpointViewOwned {
+    fn to_json(&self) -> Value {
+        BreakpointView {
+            addr: self.addr,
+            number: self.number,
+            place: self.place.as_ref().map(Cow::Borrowed),
+            options: Cow::Borrowed(&self.options),
+        }
+        .to_json()
+    }
+}
+
+impl ToJson for Watchpoint {
+    fn to_json(&self) -> Value {
+        json!({
+            "number": self.number,
+            "source": self.source,
+            "address": self.addr.to_string(),
+            "size": self.size,
+            "condition": self.condition.to_string(),
+            "value": self.value.to_string(),
+        })
+    }
+}
+
+/// Backtrace, frames are numbered from the innermost one.
+impl ToJson for [FrameSpan] {
+    fn to_json(&self) -> Value {
+        self.iter()
+            .enumerate()
+            .map(|(num, frame)| {
+                json!({
+                    "num": num,
+                    "ip": frame.ip.to_string(),
+                    "function": frame.func_name,
+                    "function_start": frame.fn_start_ip.map(|addr| addr.to_string()),
+                    "file": frame.place.as_ref().map(|place| place.file.to_string_lossy()),
+                    "line": frame.place.as_ref().map(|place| place.line_number),
+                    "column": frame.place.as_ref().map(|place| place.column_number),
+                    "args": frame
+                        .args
+                        .as_ref()
+                        .map(|args| args.iter().map(ToJson::to_json).collect::<Vec<_>>()),
+                })
+            })
+            .collect()
+    }
+}
+
+impl ToJson for ThreadSnapshot {
+    fn to_json(&self) -> Value {
+        json!({
+            "number": self.thread.number,
+            "pid": self.thread.pid.as_raw(),
+            "name": self.name,
+            "in_focus": self.in_focus,
+            "running": !self.thread.is_stopped(),
+            "focus_frame": self.focus_frame,
+            "state": self.stat.as_ref().map(|stat| stat.state.to_string()),
+            "cpu": self.stat.as_ref().map(|stat| stat.cpu),
+            "user_time": self.stat.as_ref().map(|stat| stat.user_time.as_secs_f64()),
+            "system_time": self.stat.as_ref().map(|stat| stat.system_time.as_secs_f64()),
+            "file": self.place.as_ref().map(|place| place.file.to_string_lossy()),
+            "line": self.place.as_ref().map(|place| place.line_number),
+            "backtrace": self.bt.as_deref().map(ToJson::to_json),
+            "backtrace_truncated": self.bt_truncated,
+        })
+    }
+}
+
+impl ToJson for FrameInfo {
+    fn to_json(&self) -> Value {
+        json!({
+            "num": self.num,
+            "function": self.frame.func_name,
+            "cfa": self.cfa.to_string(),
+            "return_address": self.return_addr.map(|addr| addr.to_string()),
+        })
+    }
+}
+
+impl ToJson for FunctionInfo {
+    fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "linkage_name": self.linkage_name,
+            "file": self.decl.as_ref().map(|(file, _)| file.to_string_lossy()),
+            "line": self.decl.as_ref().map(|(_, line)| line),
+            "ranges": self
+                .ranges
+                .iter()
+                .map(|r| json!({ "start": r.start.to_string(), "end": r.end.to_string() }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl ToJson for AsyncTask {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "header": self.header.to_string(),
+            "state": self.state.to_string(),
+            "future": self.future_type,
+            "frames": self.frames.to_json(),
+        })
+    }
+}
+
+/// Logical async backtrace, frames are numbered from the innermost future.
+impl ToJson for [AsyncFrame] {
+    fn to_json(&self) -> Value {
+        self.iter()
+            .enumerate()
+            .map(|(num, frame)| {
+                json!({
+                    "num": num,
+                    "future": frame.future_type,
+                    "state": frame.state,
+                    "function": frame.function,
+                    "file": frame.place.as_ref().map(|(file, _)| file.to_string_lossy()),
+                    "line": frame.place.as_ref().map(|(_, line)| line),
+                    "locals": frame.locals.iter().map(ToJson::to_json).collect::<Vec<_>>(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl ToJson for SymbolInfo {
+    fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "kind": format!("{:?}", self.kind),
+            "start": self.range.start.to_string(),
+            "end": self.range.end.to_string(),
+            "file": self.file.as_ref().map(|file| file.to_string_lossy()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debugger::variable::{
+        ScalarVariable, StructVariable, SupportedScalar, VariableIdentity,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_variable_to_json() {
+        let var = VariableIR::Struct(StructVariable {
+            identity: VariableIdentity::new(Default::default(), Some("s".to_string())),
+            type_name: Some("S".to_string()),
+            members: vec![VariableIR::Scalar(ScalarVariable {
+                identity: VariableIdentity::new(Default::default(), Some("a".to_string())),
+                type_name: Some("i32".to_string()),
+                value: Some(SupportedScalar::I32(1)),
+            })],
+            type_params: HashMap::new(),
+        });
+
+        assert_eq!(
+            var.to_json(),
+            json!({
+                "name": "s",
+                "type": "S",
+                "members": [{ "name": "a", "type": "i32", "value": "1" }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_backtrace_to_json() {
+        let bt = vec![
+            FrameSpan {
+                func_name: Some("inner".to_string()),
+                fn_start_ip: Some(RelocatedAddress::from(0x1000_usize)),
+                ip: RelocatedAddress::from(0x1010_usize),
+                ..Default::default()
+            },
+            FrameSpan {
+                func_name: None,
+                fn_start_ip: None,
+                ip: RelocatedAddress::from(0x2000_usize),
+                ..Default::default()
+            },
+        ];
+
+        let value = bt.to_json();
+        assert_eq!(value[0]["num"], 0);
+        assert_eq!(value[0]["function"], "inner");
+        assert_eq!(value[1]["num"], 1);
+        assert_eq!(value[1]["function"], Value::Null);
+        assert_eq!(value[1]["line"], Value::Null);
+        assert_eq!(value[1]["args"], Value::Null);
+        assert_eq!(
+            value[1]["ip"],
+            RelocatedAddress::from(0x2000_usize).to_string()
+        );
+    }
+}
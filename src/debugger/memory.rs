@@ -0,0 +1,88 @@
+//! Alternative backends of debugee memory and registers.
+//!
+//! By default, memory and registers of a thread are read and written with ptrace.
+//! A [`TargetBackend`] attached to a thread id replaces ptrace for this thread, so
+//! [`read_memory_by_pid`], [`write_memory_by_pid`], [`RegisterMap::current`] and
+//! [`RegisterMap::persist`] callers (data evaluation, unwinding, disassembler, breakpoints)
+//! work without a local live process, for example on a core dump image or a remote target.
+//!
+//! [`read_memory_by_pid`]: crate::debugger::read_memory_by_pid
+//! [`write_memory_by_pid`]: crate::debugger::write_memory_by_pid
+//! [`RegisterMap::current`]: crate::debugger::register::RegisterMap::current
+//! [`RegisterMap::persist`]: crate::debugger::register::RegisterMap::persist
+
+use nix::libc::user_regs_struct;
+use nix::unistd::Pid;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Backend of memory and registers of a debugee.
+pub trait TargetBackend: Send + Sync {
+    /// Read N bytes starting from address.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: start address
+    /// * `read_n`: read byte count
+    fn read_memory(&self, addr: usize, read_n: usize) -> Result<Vec<u8>, nix::Error>;
+
+    /// Return registers of a thread, `None` if thread is unknown for this backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `tid`: thread id
+    fn registers(&self, tid: Pid) -> Option<user_regs_struct>;
+
+    /// Write bytes starting from address. Read-only backends (like core dumps) return `EPERM`.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: start address
+    /// * `data`: bytes to write
+    fn write_memory(&self, addr: usize, data: &[u8]) -> Result<(), nix::Error> {
+        _ = (addr, data);
+        Err(nix::Error::EPERM)
+    }
+
+    /// Replace registers of a thread. Read-only backends (like core dumps) return `EPERM`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tid`: thread id
+    /// * `regs`: new register values
+    fn set_registers(&self, tid: Pid, regs: user_regs_struct) -> Result<(), nix::Error> {
+        _ = (tid, regs);
+        Err(nix::Error::EPERM)
+    }
+}
+
+static BACKENDS: Lazy<RwLock<HashMap<Pid, Arc<dyn TargetBackend>>>> = Lazy::new(RwLock::default);
+
+/// Route memory and register access of a thread to a backend.
+///
+/// # Arguments
+///
+/// * `tid`: thread id
+/// * `backend`: target backend
+pub fn attach_backend(tid: Pid, backend: Arc<dyn TargetBackend>) {
+    BACKENDS.write().unwrap().insert(tid, backend);
+}
+
+/// Remove a backend of a thread, memory and registers are accessed with ptrace again.
+///
+/// # Arguments
+///
+/// * `tid`: thread id
+pub fn detach_backend(tid: Pid) {
+    BACKENDS.write().unwrap().remove(&tid);
+}
+
+/// Return a backend attached to a thread.
+pub(super) fn backend(tid: Pid) -> Option<Arc<dyn TargetBackend>> {
+    let backends = BACKENDS.read().unwrap();
+    if backends.is_empty() {
+        return None;
+    }
+    backends.get(&tid).cloned()
+}
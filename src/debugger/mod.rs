@@ -1,47 +1,101 @@
 pub mod address;
+pub mod r#async;
 mod breakpoint;
+mod call;
+pub mod capture;
+pub mod catchpoint;
+pub mod checkpoint;
 mod code;
+pub mod completion;
+pub mod coredump;
+pub mod coverage;
 mod debugee;
+pub mod debuginfod;
 mod error;
+pub mod event;
+pub mod examine;
+pub mod fd;
+pub mod gdb_remote;
+pub mod history;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod memory;
 pub mod process;
+pub mod progress;
 pub mod register;
 pub mod rust;
+pub mod skip;
+pub mod snapshot;
+pub mod source;
 mod step;
 mod utils;
 pub mod variable;
+pub mod watchpoint;
 
+pub use breakpoint::BreakpointCondition;
+pub use breakpoint::BreakpointOptions;
 pub use breakpoint::BreakpointView;
 pub use breakpoint::BreakpointViewOwned;
 pub use breakpoint::CreateTransparentBreakpointRequest;
 pub use debugee::dwarf::r#type::TypeDeclaration;
+pub use debugee::dwarf::symbol;
 pub use debugee::dwarf::unit::FunctionDie;
 pub use debugee::dwarf::unit::PlaceDescriptor;
 pub use debugee::dwarf::unit::PlaceDescriptorOwned;
 pub use debugee::dwarf::unwind;
 pub use debugee::dwarf::Symbol;
+pub use debugee::tracee::StopType;
+pub use debugee::tracee::ThreadStat;
 pub use debugee::tracee::Tracee;
+pub use debugee::tracee::TraceeStatus;
+pub use debugee::tracer::SignalDisposition;
+pub use debugee::tracer::SignalRecord;
+pub use debugee::tracer::SyscallStop;
+pub use debugee::AnnotatedInstruction;
+pub use debugee::DisasmTarget;
+pub use debugee::Disassembly;
 pub use debugee::FrameInfo;
 pub use debugee::FunctionAssembly;
+pub use debugee::FunctionInfo;
 pub use debugee::FunctionRange;
 pub use debugee::RegionInfo;
+pub use debugee::SymbolInfo;
 pub use debugee::ThreadSnapshot;
 pub use error::Error;
+/// Error of the public debugger API, kind of error may be matched by variant
+/// or by [`Error::category`].
+pub use error::Error as DebuggerError;
+pub use error::ErrorCategory;
+pub use step::{CallTarget, TargetPlace};
 
 use crate::debugger::address::{Address, GlobalAddress, RelocatedAddress};
 use crate::debugger::breakpoint::{Breakpoint, BreakpointRegistry, BrkptType, UninitBreakpoint};
+use crate::debugger::capture::{IoCapture, OutputStream};
+use crate::debugger::catchpoint::{SyscallCatchpoint, SyscallEvent};
+use crate::debugger::checkpoint::Checkpoints;
+use crate::debugger::coverage::Coverage;
 use crate::debugger::debugee::dwarf::r#type::TypeCache;
 use crate::debugger::debugee::dwarf::unwind::Backtrace;
 use crate::debugger::debugee::dwarf::DwarfUnwinder;
 use crate::debugger::debugee::tracer::{StopReason, TraceContext};
-use crate::debugger::debugee::{Debugee, ExecutionStatus, Location};
+use crate::debugger::debugee::{Debugee, DebugeeEvent, ExecutionStatus, Location};
 use crate::debugger::error::Error::{
-    FrameNotFound, Hook, ProcessNotStarted, Ptrace, RegisterNameNotFound, UnwindNoContext,
+    FrameNotFound, ProcessNotStarted, Ptrace, RegisterNameNotFound, ThreadNotFound, ThreadRunning,
+    UnwindNoContext, VariableWrite,
 };
+use crate::debugger::history::{History, StopEvent};
 use crate::debugger::process::{Child, Installed};
+use crate::debugger::progress::Task;
 use crate::debugger::register::{DwarfRegisterMap, Register, RegisterMap};
+use crate::debugger::skip::SkipRule;
+use crate::debugger::snapshot::{Recorder, SnapshotKind};
+use crate::debugger::source::{SourceCache, SourceLine};
 use crate::debugger::step::StepResult;
-use crate::debugger::variable::select::{VariableSelector, DQE};
-use crate::debugger::variable::VariableIR;
+use crate::debugger::variable::expr::Expr;
+use crate::debugger::variable::pretty::{PrettyPrinter, PrettyPrinterRegistry};
+use crate::debugger::variable::select::{Literal, VariableSelector, DQE};
+use crate::debugger::variable::{ParseLimits, VariableIR};
+use crate::debugger::watchpoint::{WatchValue, WatchpointRegistry};
 use crate::debugger::Error::Syscall;
 use crate::oracle::Oracle;
 use crate::{print_warns, weak_error};
@@ -51,18 +105,26 @@ use nix::libc::{c_void, uintptr_t};
 use nix::sys;
 use nix::sys::signal;
 use nix::sys::signal::{Signal, SIGKILL};
+use nix::sys::uio;
+use nix::sys::uio::RemoteIoVec;
 use nix::sys::wait::{waitpid, WaitStatus};
 use nix::unistd::Pid;
-use object::Object;
+use object::{Object, SymbolKind};
 use regex::Regex;
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::ffi::c_long;
+use std::io::IoSliceMut;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::{fs, mem, u64};
 
 /// Trait for the reverse interaction between the debugger and the user interface.
+/// All methods except `on_breakpoint` and `on_step` have a default (no-op) implementation,
+/// so an implementation handles only events it needs and new events don't break it.
+/// Hook's own errors (like output errors) are returned as [`Error::Hook`],
+/// an error interrupts a debugger command that causes an event.
 pub trait EventHook {
     /// Called when user defined breakpoint is reached.
     ///
@@ -78,7 +140,7 @@ pub trait EventHook {
         num: u32,
         place: Option<PlaceDescriptor>,
         function: Option<&FunctionDie>,
-    ) -> anyhow::Result<()>;
+    ) -> Result<(), Error>;
 
     /// Called when one of step commands is done.
     ///
@@ -92,28 +154,116 @@ pub trait EventHook {
         pc: RelocatedAddress,
         place: Option<PlaceDescriptor>,
         function: Option<&FunctionDie>,
-    ) -> anyhow::Result<()>;
+    ) -> Result<(), Error>;
+
+    /// Called when a watchpoint is triggered.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc`: address of instruction next to the one that accesses a watched memory
+    /// * `num`: watchpoint number
+    /// * `place`: stop place information
+    /// * `function`: function debug information entry
+    /// * `old`: value of a watched memory before access
+    /// * `new`: value of a watched memory after access
+    fn on_watchpoint(
+        &self,
+        _pc: RelocatedAddress,
+        _num: u32,
+        _place: Option<PlaceDescriptor>,
+        _function: Option<&FunctionDie>,
+        _old: &WatchValue,
+        _new: &WatchValue,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
 
     /// Called when debugee receive an OS signal. Debugee is in signal-stop at this moment.
     ///
     /// # Arguments
     ///
     /// * `signal`: received OS signal
-    fn on_signal(&self, signal: Signal);
+    fn on_signal(&self, _signal: Signal) {}
+
+    /// Called when debugee thread stops at entry or exit of a syscall caught by
+    /// a syscall catchpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `pc`: address of instruction next to a syscall instruction
+    /// * `event`: syscall number, name and entry arguments or exit result
+    fn on_syscall_catch(&self, _pc: RelocatedAddress, _event: &SyscallEvent) {}
+
+    /// Called in non-stop mode when a single thread is stopped (at a breakpoint, watchpoint
+    /// or by a signal) while other threads keep running.
+    /// Called right before a stop report.
+    ///
+    /// # Arguments
+    ///
+    /// * `thread`: stopped thread
+    fn on_thread_stop(&self, _thread: &Tracee) {}
 
     /// Called right after debugee exit.
     ///
     /// # Arguments
     ///
     /// * `code`: exit code
-    fn on_exit(&self, code: i32);
+    fn on_exit(&self, _code: i32) {}
 
     /// Called single time for each debugee process (on start or after reinstall).
     ///
     /// # Arguments
     ///
     /// * `pid`: debugee process pid
-    fn on_process_install(&self, pid: Pid, object: Option<&object::File>);
+    /// * `object`: debugee executable object
+    fn on_process_install(&self, _pid: Pid, _object: Option<&object::File>) {}
+
+    /// Called right before a stop report (breakpoint, step, signal or exit).
+    /// Debugee output written before the stop (but not yet shown) should be displayed here,
+    /// so it appears before the stop report.
+    fn on_output_flush(&self) {}
+
+    /// Called with a chunk of debugee output right before a stop report,
+    /// only if debugee streams are captured by the debugger (see [`IoCapture`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `stream`: stdout or stderr
+    /// * `data`: output data
+    fn on_debugee_output(&self, _stream: OutputStream, _data: &[u8]) {}
+
+    /// Called when debugee creates a new thread. Thread, process and library events are
+    /// observed while debugee is running and reported at the next debugee stop (including
+    /// internal stops, like a stop at a dynamic linker breakpoint).
+    ///
+    /// # Arguments
+    ///
+    /// * `pid`: new thread id
+    fn on_thread_created(&self, _pid: Pid) {}
+
+    /// Called when a debugee thread exits, an exit of the main thread is reported
+    /// by [`EventHook::on_exit`].
+    ///
+    /// # Arguments
+    ///
+    /// * `pid`: exited thread id
+    fn on_thread_exited(&self, _pid: Pid) {}
+
+    /// Called when a shared library is loaded into debugee.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: library path from the dynamic linker link map
+    fn on_shared_lib_loaded(&self, _path: &Path) {}
+
+    /// Called when a debugee thread creates a child process (with `fork` or `vfork`),
+    /// a child process is detached and runs without a debugger.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent`: id of a thread that called `fork`
+    /// * `child`: child process pid
+    fn on_process_forked(&self, _parent: Pid, _child: Pid) {}
 }
 
 pub struct NopHook {}
@@ -125,7 +275,7 @@ impl EventHook for NopHook {
         _: u32,
         _: Option<PlaceDescriptor>,
         _: Option<&FunctionDie>,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), Error> {
         Ok(())
     }
 
@@ -134,15 +284,9 @@ impl EventHook for NopHook {
         _: RelocatedAddress,
         _: Option<PlaceDescriptor>,
         _: Option<&FunctionDie>,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), Error> {
         Ok(())
     }
-
-    fn on_signal(&self, _: Signal) {}
-
-    fn on_exit(&self, _: i32) {}
-
-    fn on_process_install(&self, _: Pid, _: Option<&object::File>) {}
 }
 
 macro_rules! disable_when_not_stared {
@@ -153,6 +297,23 @@ macro_rules! disable_when_not_stared {
     };
 }
 
+macro_rules! disable_when_post_mortem {
+    ($this: expr) => {
+        if $this.process.core().is_some() {
+            return Err(Error::PostMortem);
+        }
+    };
+}
+
+/// Thread selector, a thread may be selected by its number or by its id.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThreadSelector {
+    /// Thread number (as shown by a `thread info` command).
+    Number(u32),
+    /// Thread id (OS thread identifier).
+    Tid(Pid),
+}
+
 /// Exploration context. Contains current explored thread and program counter.
 /// May be changed by user (by `thread` or `frame` command)
 /// or by debugger (at breakpoints, after steps, etc.).
@@ -208,7 +369,9 @@ impl ExplorationContext {
 #[derive(Default)]
 pub struct DebuggerBuilder<H: EventHook + 'static = NopHook> {
     oracles: Vec<Arc<dyn Oracle>>,
+    pretty_printers: Vec<Arc<dyn PrettyPrinter>>,
     hooks: Option<H>,
+    io_capture: Option<IoCapture>,
 }
 
 impl<H: EventHook + 'static> DebuggerBuilder<H> {
@@ -216,7 +379,9 @@ impl<H: EventHook + 'static> DebuggerBuilder<H> {
     pub fn new() -> Self {
         Self {
             oracles: vec![],
+            pretty_printers: vec![],
             hooks: None,
+            io_capture: None,
         }
     }
 
@@ -229,6 +394,18 @@ impl<H: EventHook + 'static> DebuggerBuilder<H> {
         Self { oracles, ..self }
     }
 
+    /// Add pretty-printers for user-defined types.
+    ///
+    /// # Arguments
+    ///
+    /// * `pretty_printers`: list of pretty-printers
+    pub fn with_pretty_printers(self, pretty_printers: Vec<Arc<dyn PrettyPrinter>>) -> Self {
+        Self {
+            pretty_printers,
+            ..self
+        }
+    }
+
     /// Add event hooks implementation
     ///
     /// # Arguments
@@ -241,6 +418,19 @@ impl<H: EventHook + 'static> DebuggerBuilder<H> {
         }
     }
 
+    /// Deliver debugee output into hooks and oracles. A debugee process must be created with
+    /// [`IoCapture::process`].
+    ///
+    /// # Arguments
+    ///
+    /// * `capture`: captured debugee streams
+    pub fn with_io_capture(self, capture: IoCapture) -> Self {
+        Self {
+            io_capture: Some(capture),
+            ..self
+        }
+    }
+
     /// Return all oracles.
     pub fn oracles(&self) -> impl Iterator<Item = &dyn Oracle> {
         self.oracles.iter().map(|oracle| oracle.as_ref())
@@ -252,11 +442,16 @@ impl<H: EventHook + 'static> DebuggerBuilder<H> {
     ///
     /// * `process`: debugee process
     pub fn build(self, process: Child<Installed>) -> Result<Debugger, Error> {
-        if let Some(hooks) = self.hooks {
+        let mut debugger = if let Some(hooks) = self.hooks {
             Debugger::new(process, hooks, self.oracles)
         } else {
             Debugger::new(process, NopHook {}, self.oracles)
+        }?;
+        for printer in self.pretty_printers {
+            debugger.register_pretty_printer(printer);
         }
+        debugger.io_capture = self.io_capture;
+        Ok(debugger)
     }
 }
 
@@ -268,14 +463,43 @@ pub struct Debugger {
     debugee: Debugee,
     /// Active and non-active breakpoints lists.
     breakpoints: BreakpointRegistry,
+    /// Hardware watchpoints.
+    watchpoints: WatchpointRegistry,
     /// Type declaration cache.
     type_cache: RefCell<TypeCache>,
+    /// Pretty-printers for user-defined types.
+    pretty_printers: PrettyPrinterRegistry,
     /// Debugger interrupt with UI by EventHook trait.
     hooks: Box<dyn EventHook>,
     /// Current exploration context.
     expl_context: ExplorationContext,
     /// Map of name -> (oracle, installed flag) pairs.
     oracles: IndexMap<&'static str, (Arc<dyn Oracle>, bool)>,
+    /// Line coverage tracking state.
+    coverage: Coverage,
+    /// Saved debugee states for reverse execution.
+    recorder: Recorder,
+    /// If true, only a thread that stops at a breakpoint, watchpoint or by a signal
+    /// is suspended, other threads keep running.
+    non_stop: bool,
+    /// Threads that lost focus while stopped at a breakpoint,
+    /// they must step over it before continue.
+    pending_step_over: HashSet<Pid>,
+    /// Syscall catchpoint, `None` if syscalls are not caught.
+    syscall_catchpoint: Option<SyscallCatchpoint>,
+    /// Functions that `step` command steps out of transparently.
+    skip_rules: Vec<SkipRule>,
+    /// Log of debugee stops.
+    history: History,
+    /// Suspended debugee copies.
+    checkpoints: Checkpoints,
+    /// Loaded source files.
+    sources: SourceCache,
+    /// Captured debugee standard streams, `None` if a user interface reads them itself.
+    io_capture: Option<IoCapture>,
+    /// If true, a not started debugee process must be recreated
+    /// to apply new program arguments or environment.
+    respawn_required: bool,
 }
 
 impl Debugger {
@@ -301,26 +525,75 @@ impl Debugger {
         let process_id = process.pid();
         hooks.on_process_install(process_id, Some(&object));
 
-        let debugee = if process.is_external() {
+        let debugee = if let Some(core) = process.core() {
+            Debugee::new_from_core(program_path, &process, &object, core)?
+        } else if process.is_external() {
             Debugee::new_from_external_process(program_path, &process, &object)?
         } else {
             Debugee::new_non_running(program_path, &process, &object)?
         };
 
+        // dumped process is stopped forever at a place where it receives a signal
+        let mut expl_context = ExplorationContext::new_non_running(process_id);
+        if let Some(core) = process.core() {
+            let location = debugee.get_tracee_ensure(process_id).location(&debugee)?;
+            expl_context = ExplorationContext::new(location, 0);
+            if let Ok(signal) = Signal::try_from(core.threads()[0].signal) {
+                hooks.on_signal(signal);
+            }
+        }
+
         Ok(Self {
             debugee,
             process,
             breakpoints,
+            watchpoints: WatchpointRegistry::default(),
             hooks: Box::new(hooks),
             type_cache: RefCell::default(),
-            expl_context: ExplorationContext::new_non_running(process_id),
+            pretty_printers: PrettyPrinterRegistry::default(),
+            expl_context,
             oracles: oracles
                 .into_iter()
                 .map(|oracle| (oracle.name(), (oracle, false)))
                 .collect(),
+            coverage: Coverage::default(),
+            recorder: Recorder::default(),
+            non_stop: false,
+            pending_step_over: HashSet::new(),
+            syscall_catchpoint: None,
+            skip_rules: vec![],
+            history: History::default(),
+            checkpoints: Checkpoints::default(),
+            sources: SourceCache::default(),
+            io_capture: None,
+            respawn_required: false,
         })
     }
 
+    /// Add a pretty-printer for user-defined types, a printer with the same name is replaced.
+    /// Pretty-printers are used before builtin type specializations.
+    ///
+    /// # Arguments
+    ///
+    /// * `printer`: pretty-printer
+    pub fn register_pretty_printer(&mut self, printer: Arc<dyn PrettyPrinter>) {
+        self.pretty_printers.register(printer);
+    }
+
+    /// Remove a pretty-printer, return `false` if there is no printer with this name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: pretty-printer name
+    pub fn unregister_pretty_printer(&mut self, name: &str) -> bool {
+        self.pretty_printers.unregister(name)
+    }
+
+    /// Return registered pretty-printers.
+    pub fn pretty_printers(&self) -> &PrettyPrinterRegistry {
+        &self.pretty_printers
+    }
+
     /// Return installed oracle, or `None` if oracle not found or not installed.
     ///
     /// # Arguments
@@ -395,25 +668,58 @@ impl Debugger {
         Ok(&self.expl_context)
     }
 
+    /// Return ids of all debugee threads (tracees).
+    fn thread_pids(&self) -> Vec<Pid> {
+        self.debugee.tracee_ctl().tracee_pids()
+    }
+
     /// Continue debugee execution. Step over breakpoint if called at it.
     /// Return if breakpoint is reached or signal occurred or debugee exit.
     ///
     /// **! change exploration context**
     fn continue_execution(&mut self) -> Result<StopReason, Error> {
         if let Some(StopReason::SignalStop(pid, sign)) = self.step_over_breakpoint()? {
+            self.flush_output();
+            self.hooks.on_signal(sign);
+            self.log_stop(StopEvent::Signal(sign));
+            return Ok(StopReason::SignalStop(pid, sign));
+        }
+        if let Some(StopReason::SignalStop(pid, sign)) = self.step_over_pending_breakpoints()? {
+            self.flush_output();
             self.hooks.on_signal(sign);
+            self.log_stop(StopEvent::Signal(sign));
             return Ok(StopReason::SignalStop(pid, sign));
         }
 
+        // syscall tracing may be switched on or off by oracles between debugee stops
+        let trace_syscalls = self.syscall_catchpoint.is_some()
+            || self
+                .oracles
+                .values()
+                .any(|(oracle, ready)| *ready && oracle.trace_syscalls());
+        self.debugee.tracer_mut().set_trace_syscalls(trace_syscalls);
+
+        self.debugee.tracer_mut().set_non_stop(self.non_stop);
+        if self.non_stop {
+            // in non-stop mode only a thread in focus is continued
+            let focus_pid = self.exploration_ctx().pid_on_focus();
+            self.debugee.tracee_ctl_mut().unpark(focus_pid);
+        }
+
         let stop_reason = loop {
             let event = self
                 .debugee
                 .trace_until_stop(TraceContext::new(&self.breakpoints.active_breakpoints()))?;
+            self.report_debugee_events();
             match event {
                 StopReason::DebugeeExit(code) => {
                     // ignore all possible errors on breakpoints disabling
                     _ = self.breakpoints.disable_all_breakpoints(&self.debugee);
+                    // watched memory is gone with a process
+                    self.watchpoints.clear();
+                    self.flush_output();
                     self.hooks.on_exit(code);
+                    self.log_stop(StopEvent::Exit(code));
                     break event;
                 }
                 StopReason::DebugeeStart => {
@@ -435,10 +741,11 @@ impl Debugger {
 
                                 // rendezvous already available at this point
                                 let brk = self.debugee.rendezvous().r_brk();
-                                self.breakpoints.add_and_enable(Breakpoint::new_linker_map(
-                                    brk,
-                                    self.process.pid(),
-                                ))?;
+                                let threads = self.thread_pids();
+                                self.breakpoints.add_and_enable(
+                                    Breakpoint::new_linker_map(brk, self.process.pid()),
+                                    &threads,
+                                )?;
 
                                 // check oracles is ready
                                 let oracles = self.oracles.clone();
@@ -454,12 +761,15 @@ impl Debugger {
                                 let oracles = self.oracles.clone();
                                 let ready_oracles = oracles.into_values().filter(|(_, a)| *a);
                                 for (oracle, _) in ready_oracles {
+                                    oracle.on_install(self);
                                     let watch_points = oracle.watch_points();
                                     for request in watch_points {
                                         weak_error!(self.set_transparent_breakpoint(request));
                                     }
                                 }
 
+                                self.plant_coverage_breakpoints();
+
                                 // ignore possible signals
                                 while self.step_over_breakpoint()?.is_some() {}
                                 continue;
@@ -467,10 +777,27 @@ impl Debugger {
                             BrkptType::LinkerMapFn => {
                                 // ignore possible signals
                                 while self.step_over_breakpoint()?.is_some() {}
+                                // breakpoints in just loaded libraries (kept after restart)
+                                print_warns!(self
+                                    .breakpoints
+                                    .enable_all_breakpoints(&self.debugee));
                                 print_warns!(self.refresh_deferred());
                                 continue;
                             }
                             BrkptType::UserDefined => {
+                                let number = bp.number();
+                                if !self.check_breakpoint_hit(current_pc) {
+                                    if let Some(StopReason::SignalStop(pid, sign)) =
+                                        self.step_over_breakpoint()?
+                                    {
+                                        self.flush_output();
+                                        self.hooks.on_signal(sign);
+                                        self.log_stop(StopEvent::Signal(sign));
+                                        return Ok(StopReason::SignalStop(pid, sign));
+                                    }
+                                    continue;
+                                }
+
                                 let pc = current_pc.into_global(&self.debugee)?;
                                 let dwarf = self
                                     .debugee
@@ -479,9 +806,16 @@ impl Debugger {
                                 let func = weak_error!(dwarf.find_function_by_pc(pc))
                                     .flatten()
                                     .map(|f| f.die);
-                                self.hooks
-                                    .on_breakpoint(current_pc, bp.number(), place, func)
-                                    .map_err(Hook)?;
+                                self.flush_output();
+                                self.notify_thread_stop(pid);
+                                self.hooks.on_breakpoint(current_pc, number, place, func)?;
+                                self.log_stop(StopEvent::Breakpoint(number));
+
+                                let oracles = self.oracles.clone();
+                                for (oracle, _) in oracles.into_values().filter(|(_, ready)| *ready)
+                                {
+                                    oracle.on_breakpoint(self, number);
+                                }
                                 break event;
                             }
                             BrkptType::Temporary => {
@@ -493,7 +827,9 @@ impl Debugger {
                                 if let Some(StopReason::SignalStop(pid, sign)) =
                                     self.step_over_breakpoint()?
                                 {
+                                    self.flush_output();
                                     self.hooks.on_signal(sign);
+                                    self.log_stop(StopEvent::Signal(sign));
                                     return Ok(StopReason::SignalStop(pid, sign));
                                 }
 
@@ -502,26 +838,136 @@ impl Debugger {
                         }
                     }
                 }
+                StopReason::Watchpoint(pid, current_pc) => {
+                    let hits = self.take_watchpoint_hits(pid)?;
+                    if hits.is_empty() {
+                        continue;
+                    }
+
+                    self.expl_ctx_switch_thread(pid)?;
+                    self.flush_output();
+                    self.notify_thread_stop(pid);
+                    let pc = current_pc.into_global(&self.debugee)?;
+                    for hit in hits {
+                        // watched memory may be accessed from code without debug information
+                        let dwarf = self.debugee.debug_info(current_pc).ok();
+                        let place =
+                            dwarf.and_then(|dwarf| weak_error!(dwarf.find_place_from_pc(pc))?);
+                        let func = dwarf
+                            .and_then(|dwarf| weak_error!(dwarf.find_function_by_pc(pc))?)
+                            .map(|f| f.die);
+                        self.hooks.on_watchpoint(
+                            current_pc, hit.number, place, func, &hit.old, &hit.new,
+                        )?;
+                        self.log_stop(StopEvent::Watchpoint(hit.number));
+                    }
+                    break event;
+                }
+                StopReason::SyscallStop(pid, stop) => {
+                    let oracles = self.oracles.clone();
+                    for (oracle, _) in oracles.into_values().filter(|(_, ready)| *ready) {
+                        oracle.on_syscall(self, pid, &stop);
+                    }
+
+                    let Some(catch_event) = self
+                        .syscall_catchpoint
+                        .as_mut()
+                        .and_then(|catch| catch.on_syscall(pid, stop))
+                    else {
+                        continue;
+                    };
+                    // tracer doesn't stop other threads at syscall stops
+                    let breakpoints = self.breakpoints.active_breakpoints();
+                    self.debugee
+                        .tracer_mut()
+                        .stop_others(TraceContext::new(&breakpoints), pid)?;
+                    self.expl_ctx_switch_thread(pid)?;
+                    self.flush_output();
+                    self.notify_thread_stop(pid);
+                    let pc = self.exploration_ctx().location().pc;
+                    self.hooks.on_syscall_catch(pc, &catch_event);
+                    self.log_stop(StopEvent::Syscall);
+                    break event;
+                }
                 StopReason::SignalStop(pid, sign) => {
                     if !self.debugee.is_in_progress() {
                         continue;
                     }
 
                     self.expl_ctx_switch_thread(pid)?;
+                    self.flush_output();
+                    self.notify_thread_stop(pid);
                     self.hooks.on_signal(sign);
+                    self.log_stop(StopEvent::Signal(sign));
                     break event;
                 }
             }
         };
 
+        if self.non_stop && self.debugee.is_in_progress() {
+            if let StopReason::Breakpoint(pid, _)
+            | StopReason::Watchpoint(pid, _)
+            | StopReason::SignalStop(pid, _)
+            | StopReason::SyscallStop(pid, _) = stop_reason
+            {
+                // stopped thread stays stopped until it is continued explicitly
+                self.debugee.tracee_ctl_mut().park(pid);
+            }
+        }
+
+        let oracles = self.oracles.clone();
+        for (oracle, _) in oracles.into_values().filter(|(_, ready)| *ready) {
+            oracle.on_stop(self);
+        }
+
         Ok(stop_reason)
     }
 
-    /// Restart debugee by recreating debugee process, save all user-defined breakpoints.
+    /// Deliver thread, process and shared library events observed since the last stop into hooks.
+    fn report_debugee_events(&mut self) {
+        for event in self.debugee.take_events() {
+            match event {
+                DebugeeEvent::ThreadCreated(pid) => self.hooks.on_thread_created(pid),
+                DebugeeEvent::ThreadExited(pid) => self.hooks.on_thread_exited(pid),
+                DebugeeEvent::SharedLibLoaded(path) => self.hooks.on_shared_lib_loaded(&path),
+                DebugeeEvent::ProcessForked { parent, child } => {
+                    self.hooks.on_process_forked(parent, child)
+                }
+            }
+        }
+    }
+
+    /// Step over breakpoints threads that lost focus while stopped at them,
+    /// otherwise these breakpoints are hit again right after continue.
+    /// Parked threads (in non-stop mode) are not continued, so they keep waiting.
+    fn step_over_pending_breakpoints(&mut self) -> Result<Option<StopReason>, Error> {
+        let focus_pid = self.exploration_ctx().pid_on_focus();
+        let pending = mem::take(&mut self.pending_step_over);
+        for pid in pending {
+            let tracee_ctl = self.debugee.tracee_ctl();
+            if pid == focus_pid || !tracee_ctl.tracee(pid).is_some_and(|t| t.is_stopped()) {
+                continue;
+            }
+            if tracee_ctl.is_parked(pid) {
+                self.pending_step_over.insert(pid);
+                continue;
+            }
+
+            if let Some(Some(stop)) = self.step_thread_over_breakpoint(pid)? {
+                return Ok(Some(stop));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Restart debugee by recreating debugee process (with the same arguments and environment),
+    /// save all user-defined breakpoints. Breakpoints in shared libraries are enabled again
+    /// when a library is loaded.
     /// Return when new debugee stopped or ends.
     ///
     /// **! change exploration context**
     pub fn restart_debugee(&mut self) -> Result<Pid, Error> {
+        disable_when_post_mortem!(self);
         match self.debugee.execution_status() {
             ExecutionStatus::Unload => {
                 // all breakpoints already disabled by default
@@ -537,10 +983,18 @@ impl Debugger {
         if !self.debugee.is_exited() {
             let proc_pid = self.process.pid();
             signal::kill(proc_pid, SIGKILL).map_err(|e| Syscall("kill", e))?;
+            self.debugee.tracer_mut().set_trace_syscalls(false);
             _ = self.debugee.tracer_mut().resume(TraceContext::new(&[]));
         }
 
         self.process = self.process.install()?;
+        self.respawn_required = false;
+        self.watchpoints.clear();
+        self.stop_recording();
+        self.pending_step_over.clear();
+        if let Some(catch) = self.syscall_catchpoint.as_mut() {
+            catch.reset();
+        }
 
         let new_debugee = self.debugee.extend(self.process.pid());
         _ = mem::replace(&mut self.debugee, new_debugee);
@@ -554,7 +1008,39 @@ impl Debugger {
         Ok(self.process.pid())
     }
 
+    /// Return debugee program arguments.
+    pub fn program_args(&self) -> &[String] {
+        self.process.args()
+    }
+
+    /// Replace debugee program arguments, new arguments are used at next start or restart.
+    ///
+    /// # Arguments
+    ///
+    /// * `args`: program arguments
+    pub fn set_program_args(&mut self, args: Vec<String>) {
+        self.process.set_args(args);
+        self.respawn_required = true;
+    }
+
+    /// Return debugee environment changes, variables with `None` value are removed.
+    pub fn program_env(&self) -> &[(String, Option<String>)] {
+        self.process.env()
+    }
+
+    /// Set or remove debugee environment variable, a change is applied at next start or restart.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: variable name
+    /// * `value`: variable value, `None` removes a variable from inherited environment
+    pub fn set_program_env(&mut self, name: &str, value: Option<&str>) {
+        self.process.set_env(name, value);
+        self.respawn_required = true;
+    }
+
     fn start_debugee_inner(&mut self, force: bool, dry_start: bool) -> Result<(), Error> {
+        disable_when_post_mortem!(self);
         if dry_start {
             if (self.debugee.is_in_progress() || self.debugee.is_exited()) && !force {
                 return Err(Error::AlreadyRun);
@@ -563,6 +1049,9 @@ impl Debugger {
         }
 
         match self.debugee.execution_status() {
+            ExecutionStatus::Unload if self.respawn_required => {
+                self.restart_debugee()?;
+            }
             ExecutionStatus::Unload => {
                 self.continue_execution()?;
             }
@@ -603,7 +1092,9 @@ impl Debugger {
     /// Continue debugee execution.
     pub fn continue_debugee(&mut self) -> Result<(), Error> {
         disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
         self.continue_execution()?;
+        self.record_stop(SnapshotKind::Continue);
         Ok(())
     }
 
@@ -623,6 +1114,88 @@ impl Debugger {
             .collect())
     }
 
+    /// Return functions from debug information which full name (with namespace) matches
+    /// a regular expression, sorted by name. Addresses are global (not relocated).
+    ///
+    /// # Arguments
+    ///
+    /// * `regex`: regular expression
+    pub fn find_functions_info(&self, regex: &str) -> Result<Vec<FunctionInfo>, Error> {
+        let regex = Regex::new(regex)?;
+
+        let mut functions: Vec<_> = self
+            .debugee
+            .debug_info_all()
+            .iter()
+            // objects without debug information are skipped
+            .filter_map(|dwarf| dwarf.search_functions_by_regex(&regex).ok())
+            .flatten()
+            .map(|func| {
+                let name = func.die.base_attributes.name.clone().unwrap_or_default();
+                let name = if func.die.namespace.is_empty() {
+                    name
+                } else {
+                    format!("{}::{name}", func.die.namespace.join("::"))
+                };
+                FunctionInfo {
+                    name,
+                    linkage_name: func.die.linkage_name.clone(),
+                    decl: func.die.decl_file_line.and_then(|(file, line)| {
+                        let file = func.unit().files().get(file as usize)?;
+                        Some((file.clone(), line))
+                    }),
+                    ranges: func
+                        .die
+                        .base_attributes
+                        .ranges
+                        .iter()
+                        .map(|r| GlobalAddress::from(r.begin)..GlobalAddress::from(r.end))
+                        .collect(),
+                }
+            })
+            .collect();
+        functions.sort_by(|f1, f2| f1.name.cmp(&f2.name));
+        Ok(functions)
+    }
+
+    /// Return symbols from symbol tables which name matches a regular expression,
+    /// sorted by name. Addresses are global (not relocated).
+    ///
+    /// # Arguments
+    ///
+    /// * `regex`: regular expression
+    pub fn search_symbols(&self, regex: &str) -> Result<Vec<SymbolInfo>, Error> {
+        let regex = Regex::new(regex)?;
+
+        let mut symbols: Vec<_> = self
+            .debugee
+            .debug_info_all()
+            .iter()
+            .flat_map(|dwarf| {
+                dwarf.find_symbols(&regex).into_iter().map(|symbol| {
+                    let file = if symbol.kind == SymbolKind::Text {
+                        dwarf
+                            .find_place_from_pc(symbol.addr)
+                            .ok()
+                            .flatten()
+                            .map(|place| place.file.to_path_buf())
+                    } else {
+                        None
+                    };
+                    SymbolInfo {
+                        name: symbol.name.clone(),
+                        kind: symbol.kind,
+                        range: symbol.addr
+                            ..GlobalAddress::from(u64::from(symbol.addr) + symbol.size),
+                        file,
+                    }
+                })
+            })
+            .collect();
+        symbols.sort_by(|s1, s2| s1.name.cmp(&s2.name));
+        Ok(symbols)
+    }
+
     /// Return in focus frame information.
     pub fn frame_info(&self) -> Result<FrameInfo, Error> {
         disable_when_not_stared!(self);
@@ -637,7 +1210,9 @@ impl Debugger {
     pub fn set_frame_into_focus(&mut self, num: u32) -> Result<u32, Error> {
         disable_when_not_stared!(self);
         let ctx = self.exploration_ctx();
-        let backtrace = self.debugee.unwind(ctx.pid_on_focus())?;
+        let backtrace = self
+            .debugee
+            .unwind(ctx.pid_on_focus(), Some(num as usize + 1))?;
         let frame = backtrace.get(num as usize).ok_or(FrameNotFound(num))?;
         self.expl_context = ExplorationContext::new(
             Location {
@@ -650,17 +1225,42 @@ impl Debugger {
         Ok(num)
     }
 
+    /// Return arguments of a frame of a thread in focus, nested values are not parsed
+    /// (only top-level values), so this is cheap enough for a whole backtrace.
+    /// Exploration context is restored after reading.
+    ///
+    /// # Arguments
+    ///
+    /// * `num`: frame number
+    pub fn frame_arguments(&mut self, num: u32) -> Result<Vec<VariableIR>, Error> {
+        disable_when_not_stared!(self);
+        let ctx = self.exploration_ctx().clone();
+        self.set_frame_into_focus(num)?;
+        let limits = ParseLimits {
+            max_depth: Some(0),
+            max_children: Some(0),
+        };
+        let args = self.read_argument_with_limits(DQE::Variable(VariableSelector::Any), limits);
+        self.expl_context = ctx;
+        args
+    }
+
     /// Execute `on_step` callback with current exploration context
-    fn execute_on_step_hook(&self) -> Result<(), Error> {
+    fn execute_on_step_hook(&mut self) -> Result<(), Error> {
         let ctx = self.exploration_ctx();
         let pc = ctx.location().pc;
         let global_pc = ctx.location().global_pc;
-        let dwarf = self.debugee.debug_info(pc)?;
-        let place = weak_error!(dwarf.find_place_from_pc(global_pc)).flatten();
-        let func = weak_error!(dwarf.find_function_by_pc(global_pc))
-            .flatten()
+        // there is no debug information in stripped library frames (ex. after `stepi`)
+        let dwarf = self.debugee.debug_info(pc).ok();
+        let place =
+            dwarf.and_then(|dwarf| weak_error!(dwarf.find_place_from_pc(global_pc)).flatten());
+        let func = dwarf
+            .and_then(|dwarf| weak_error!(dwarf.find_function_by_pc(global_pc)).flatten())
             .map(|f| f.die);
-        self.hooks.on_step(pc, place, func).map_err(Hook)
+        self.flush_output();
+        self.hooks.on_step(pc, place, func)?;
+        self.log_stop(StopEvent::Step);
+        Ok(())
     }
 
     /// Do a single step (until debugee reaches a different source line).
@@ -668,13 +1268,18 @@ impl Debugger {
     /// **! change exploration context**
     pub fn step_into(&mut self) -> Result<(), Error> {
         disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
         self.expl_ctx_restore_frame()?;
 
-        match self.step_in()? {
+        let result = self.step_in()?;
+        self.record_stop(SnapshotKind::Step);
+        match result {
             StepResult::Done => self.execute_on_step_hook(),
             StepResult::SignalInterrupt { signal, quiet } => {
                 if !quiet {
+                    self.flush_output();
                     self.hooks.on_signal(signal);
+                    self.log_stop(StopEvent::Signal(signal));
                 }
                 Ok(())
             }
@@ -686,14 +1291,51 @@ impl Debugger {
     /// **! change exploration context**
     pub fn stepi(&mut self) -> Result<(), Error> {
         disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
+        self.step_instruction().map(|_| ())
+    }
+
+    /// Move in focus thread to the next instruction and return an instruction at a new
+    /// program counter with up to `context` instructions before and after it, so an assembly
+    /// may be shown when there is no source code for a new location.
+    /// Return `None` if step is interrupted by a signal or instructions can't be decoded.
+    ///
+    /// **! change exploration context**
+    ///
+    /// # Arguments
+    ///
+    /// * `context`: max count of instructions before and after the program counter
+    pub fn stepi_with_asm(&mut self, context: usize) -> Result<Option<Disassembly>, Error> {
+        disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
+        if !self.step_instruction()? {
+            return Ok(None);
+        }
+
+        // step is done anyway, so a disassembly error is not returned
+        Ok(weak_error!(self.debugee.disassemble_around_pc(
+            self.exploration_ctx(),
+            context,
+            &self.breakpoints.active_breakpoints(),
+        )))
+    }
+
+    /// Do a single instruction step and execute step hook,
+    /// return `false` if step is interrupted by a signal.
+    fn step_instruction(&mut self) -> Result<bool, Error> {
         self.expl_ctx_restore_frame()?;
 
-        if let Some(StopReason::SignalStop(_, sign)) = self.single_step_instruction()? {
+        let stop = self.single_step_instruction()?;
+        self.record_stop(SnapshotKind::Step);
+        if let Some(StopReason::SignalStop(_, sign)) = stop {
+            self.flush_output();
             self.hooks.on_signal(sign);
-            return Ok(());
+            self.log_stop(StopEvent::Signal(sign));
+            return Ok(false);
         }
 
-        self.execute_on_step_hook()
+        self.execute_on_step_hook()?;
+        Ok(true)
     }
 
     /// Return list of currently running debugee threads.
@@ -702,18 +1344,123 @@ impl Debugger {
         self.debugee.thread_state(self.exploration_ctx())
     }
 
-    /// Sets the thread into focus.
+    /// Return last signals delivered to debugee (the oldest first), history is available
+    /// after debugee exit too.
+    pub fn signal_history(&self) -> Vec<SignalRecord> {
+        self.debugee.tracer().signal_history().cloned().collect()
+    }
+
+    /// Return number of a debugee thread, `None` if thread not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid`: thread id
+    pub fn thread_number(&self, pid: Pid) -> Option<u32> {
+        self.debugee
+            .tracee_ctl()
+            .tracee(pid)
+            .map(|tracee| tracee.number)
+    }
+
+    /// Sets the thread into focus. In non-stop mode only a stopped thread may be in focus.
     ///
     /// # Arguments
     ///
     /// * `num`: thread number
     pub fn set_thread_into_focus(&mut self, num: u32) -> Result<Tracee, Error> {
+        self.set_thread_focus(ThreadSelector::Number(num))
+    }
+
+    /// Sets the thread into focus, backtrace, variables, registers and steps
+    /// are related to a thread in focus. In non-stop mode only a stopped thread may be in focus.
+    ///
+    /// **! change exploration context**
+    ///
+    /// # Arguments
+    ///
+    /// * `thread`: thread number or thread id
+    pub fn set_thread_focus(&mut self, thread: ThreadSelector) -> Result<Tracee, Error> {
         disable_when_not_stared!(self);
-        let tracee = self.debugee.get_tracee_by_num(num)?;
+        let tracee = match thread {
+            ThreadSelector::Number(num) => self.debugee.get_tracee_by_num(num)?,
+            ThreadSelector::Tid(tid) => self
+                .debugee
+                .tracee_ctl()
+                .tracee(tid)
+                .cloned()
+                .ok_or(ThreadNotFound(tid))?,
+        };
+        if !tracee.is_stopped() {
+            return Err(ThreadRunning(tracee.number));
+        }
+
+        let prev_pid = self.exploration_ctx().pid_on_focus();
+        if prev_pid != tracee.pid {
+            let prev_pc = self
+                .debugee
+                .tracee_ctl()
+                .tracee(prev_pid)
+                .and_then(|prev| prev.pc().ok());
+            if prev_pc.is_some_and(|pc| self.breakpoints.get_enabled(pc).is_some()) {
+                self.pending_step_over.insert(prev_pid);
+            }
+        }
+
         self.expl_ctx_switch_thread(tracee.pid)?;
         Ok(tracee)
     }
 
+    /// Enable or disable non-stop mode. In non-stop mode only a thread that reaches
+    /// a breakpoint, watchpoint or receives a signal is stopped, other threads keep running.
+    /// Continue resumes only a thread in focus.
+    /// When non-stop mode is disabled all running threads are stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable`: enable or disable non-stop mode
+    pub fn set_non_stop(&mut self, enable: bool) -> Result<(), Error> {
+        self.non_stop = enable;
+        self.debugee.tracer_mut().set_non_stop(enable);
+        if !self.debugee.is_in_progress() {
+            return Ok(());
+        }
+
+        if enable {
+            let stopped: Vec<_> = self
+                .debugee
+                .tracee_ctl()
+                .snapshot()
+                .into_iter()
+                .filter(|tracee| tracee.is_stopped())
+                .map(|tracee| tracee.pid)
+                .collect();
+            let tracee_ctl = self.debugee.tracee_ctl_mut();
+            stopped.into_iter().for_each(|pid| tracee_ctl.park(pid));
+        } else {
+            self.debugee.tracee_ctl_mut().unpark_all();
+            let breakpoints = self.breakpoints.active_breakpoints();
+            self.debugee
+                .tracer_mut()
+                .stop_all(TraceContext::new(&breakpoints))?;
+        }
+        Ok(())
+    }
+
+    /// Return true if non-stop mode is enabled.
+    pub fn is_non_stop(&self) -> bool {
+        self.non_stop
+    }
+
+    /// Execute `on_thread_stop` callback if non-stop mode is enabled.
+    fn notify_thread_stop(&self, pid: Pid) {
+        if !self.non_stop {
+            return;
+        }
+        if let Some(tracee) = self.debugee.tracee_ctl().tracee(pid) {
+            self.hooks.on_thread_stop(tracee);
+        }
+    }
+
     /// Return stack trace.
     ///
     /// # Arguments
@@ -721,7 +1468,57 @@ impl Debugger {
     /// * `pid`: thread id
     pub fn backtrace(&self, pid: Pid) -> Result<Backtrace, Error> {
         disable_when_not_stared!(self);
-        self.debugee.unwind(pid)
+        self.debugee.unwind(pid, None)
+    }
+
+    /// Return top frames of a thread backtrace, unwinding stops after `max_frames` frames,
+    /// so it is fast even for a deep recursion.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid`: thread id
+    /// * `max_frames`: max count of frames from the top of the stack
+    pub fn backtrace_limited(&self, pid: Pid, max_frames: usize) -> Result<Backtrace, Error> {
+        disable_when_not_stared!(self);
+        self.debugee.unwind(pid, Some(max_frames))
+    }
+
+    /// Return a place in source code for an instruction address (if exists).
+    ///
+    /// # Arguments
+    ///
+    /// * `pc`: instruction address
+    pub fn find_place(&self, pc: RelocatedAddress) -> Result<Option<PlaceDescriptorOwned>, Error> {
+        disable_when_not_stared!(self);
+        let dwarf = self.debugee.debug_info(pc)?;
+        let place = dwarf.find_place_from_pc(pc.into_global(&self.debugee)?)?;
+        Ok(place.map(|p| p.to_owned()))
+    }
+
+    /// Return source lines around a place, a line of the place is marked as current.
+    /// Source paths are remapped with [`source::add_remap`] table, file contents are cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `place`: place in source code (like a stop place)
+    /// * `n`: count of lines before and after the place line
+    pub fn source_context(
+        &self,
+        place: &PlaceDescriptor,
+        n: u64,
+    ) -> Result<Vec<SourceLine>, Error> {
+        Ok(self.sources.context(place.file, place.line_number, n)?)
+    }
+
+    /// Return full name of a function that contains an instruction (if exists).
+    ///
+    /// # Arguments
+    ///
+    /// * `pc`: instruction address
+    pub fn find_function_name(&self, pc: RelocatedAddress) -> Result<Option<String>, Error> {
+        let dwarf = self.debugee.debug_info(pc)?;
+        let func = dwarf.find_function_by_pc(pc.into_global(&self.debugee)?)?;
+        Ok(func.and_then(|f| f.full_name()))
     }
 
     /// Read N bytes from a debugee process.
@@ -743,6 +1540,7 @@ impl Debugger {
     /// * `value`: value to write
     pub fn write_memory(&self, addr: uintptr_t, value: uintptr_t) -> Result<(), Error> {
         disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
         unsafe {
             sys::ptrace::write(
                 self.debugee.tracee_ctl().proc_pid(),
@@ -756,20 +1554,118 @@ impl Debugger {
     /// Move to higher stack frame.
     pub fn step_out(&mut self) -> Result<(), Error> {
         disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
         self.expl_ctx_restore_frame()?;
         self.step_out_frame()?;
+        self.record_stop(SnapshotKind::Step);
         self.execute_on_step_hook()
     }
 
     /// Do debugee step (over subroutine calls to).
     pub fn step_over(&mut self) -> Result<(), Error> {
         disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
+        self.expl_ctx_restore_frame()?;
+        let result = self.step_over_any()?;
+        self.record_stop(SnapshotKind::Step);
+        match result {
+            StepResult::Done => self.execute_on_step_hook(),
+            StepResult::SignalInterrupt { signal, quiet } => {
+                if !quiet {
+                    self.flush_output();
+                    self.hooks.on_signal(signal);
+                    self.log_stop(StopEvent::Signal(signal));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Run debugee until a place is reached (like gdb `advance`) or current frame returns.
+    /// No permanent breakpoints are left, temporary ones are removed at any stop reason.
+    ///
+    /// # Arguments
+    ///
+    /// * `place`: address, line or function where debugee must stop
+    pub fn run_until(&mut self, place: TargetPlace) -> Result<(), Error> {
+        disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
+        self.expl_ctx_restore_frame()?;
+        let addresses = self.target_place_addresses(&place)?;
+        let result = self.run_until_addresses(addresses)?;
+        self.record_stop(SnapshotKind::Step);
+        match result {
+            StepResult::Done => self.execute_on_step_hook(),
+            StepResult::SignalInterrupt { .. } => Ok(()),
+        }
+    }
+
+    /// Return subroutine calls at the current line of a frame in focus,
+    /// candidates for [`Debugger::step_into_target`].
+    pub fn step_targets(&self) -> Result<Vec<CallTarget>, Error> {
+        disable_when_not_stared!(self);
+        self.call_targets()
+    }
+
+    /// Continue debugee execution until a subroutine called at the current line is entered,
+    /// useful when a line contains multiple calls (like `foo(bar(), baz())`).
+    /// Execution also stops if the current frame returns before the callee is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: callee name or a part of it
+    pub fn step_into_target(&mut self, name: &str) -> Result<(), Error> {
+        disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
+        self.expl_ctx_restore_frame()?;
+        let entry = self.call_target_entry(name)?;
+        let result = self.run_until_addresses(vec![entry])?;
+        self.record_stop(SnapshotKind::Step);
+        match result {
+            StepResult::Done => self.execute_on_step_hook(),
+            StepResult::SignalInterrupt { .. } => Ok(()),
+        }
+    }
+
+    /// Set program counter of a thread in focus to an address of a place, so statements
+    /// may be re-executed or skipped. Debugee isn't resumed. Return a new program counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `place`: target address, line or function
+    /// * `force`: allow a jump outside of the current function (stack frame is not changed,
+    ///   so it is unsafe)
+    pub fn jump(&mut self, place: TargetPlace, force: bool) -> Result<RelocatedAddress, Error> {
+        disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
         self.expl_ctx_restore_frame()?;
-        match self.step_over_any()? {
+        let addr = self.jump_target(&place, force)?;
+
+        let pid = self.exploration_ctx().pid_on_focus();
+        let mut map = RegisterMap::current(pid)?;
+        map.update(Register::Rip, addr.as_u64());
+        map.persist(pid)?;
+
+        self.expl_ctx_update_location()?;
+        self.execute_on_step_hook()?;
+        Ok(addr)
+    }
+
+    /// Do steps over subroutine calls until debugee reaches a line greater than current one
+    /// or current frame returns (like gdb `until`), useful for escaping loops.
+    pub fn run_until_line_greater(&mut self) -> Result<(), Error> {
+        disable_when_not_stared!(self);
+        disable_when_post_mortem!(self);
+        self.expl_ctx_restore_frame()?;
+        let result = self.step_over_until_greater_line()?;
+        self.record_stop(SnapshotKind::Step);
+        match result {
             StepResult::Done => self.execute_on_step_hook(),
             StepResult::SignalInterrupt { signal, quiet } => {
                 if !quiet {
+                    self.flush_output();
                     self.hooks.on_signal(signal);
+                    self.log_stop(StopEvent::Signal(signal));
                 }
                 Ok(())
             }
@@ -799,6 +1695,85 @@ impl Debugger {
         evaluator.evaluate()
     }
 
+    /// Same as [`Debugger::read_variable`] but values that are out of parsing limits
+    /// aren't parsed, they are represented by [`VariableIR::Lazy`].
+    /// Lazy variable is expanded by reading a select expression that points to it
+    /// (like `var.field[1]`).
+    ///
+    /// # Arguments
+    ///
+    /// * `select_expr`: data query expression
+    /// * `limits`: parsing limits
+    pub fn read_variable_with_limits(
+        &self,
+        select_expr: DQE,
+        limits: ParseLimits,
+    ) -> Result<Vec<VariableIR>, Error> {
+        disable_when_not_stared!(self);
+        let evaluator =
+            variable::select::SelectExpressionEvaluator::new(self, select_expr).with_limits(limits);
+        evaluator.evaluate()
+    }
+
+    /// Evaluate an expression over variables from the current thread (arithmetic, comparisons,
+    /// casts, address-of and dereference operators).
+    ///
+    /// # Arguments
+    ///
+    /// * `expr`: expression
+    pub fn evaluate_expression(&self, expr: &Expr) -> Result<VariableIR, Error> {
+        disable_when_not_stared!(self);
+        variable::expr::ExpressionEvaluator::new(self).evaluate(expr)
+    }
+
+    /// Write a new value into a variable from the current thread, variable is searched
+    /// like in a `read_variable` (locals in priority). Return an updated variable.
+    /// Scalars, C-style enums and pointers located in memory can be modified.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name`: variable name
+    /// * `value`: new value
+    pub fn write_variable(&self, var_name: &str, value: Literal) -> Result<VariableIR, Error> {
+        disable_when_not_stared!(self);
+        let dqe = DQE::Variable(VariableSelector::Name {
+            var_name: var_name.to_string(),
+            only_local: false,
+        });
+        let evaluator = variable::select::SelectExpressionEvaluator::new(self, dqe.clone());
+        let (location, data) = evaluator
+            .evaluate_write_data(&value)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                VariableWrite(format!("`{var_name}` not found or not located in memory"))
+            })?;
+        debug_assert!(data.len() <= location.size);
+
+        let pid = self.debugee.tracee_ctl().proc_pid();
+        write_memory_by_pid(pid, location.address, &data).map_err(Ptrace)?;
+
+        self.read_variable(dqe)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| VariableWrite(format!("`{var_name}` not found")))
+    }
+
+    /// Return memory locations of variables from the current thread,
+    /// uses a select expression to filter variables.
+    ///
+    /// # Arguments
+    ///
+    /// * `select_expr`: data query expression
+    fn variable_memory_location(
+        &self,
+        select_expr: DQE,
+    ) -> Result<Vec<variable::select::MemoryLocation>, Error> {
+        disable_when_not_stared!(self);
+        let evaluator = variable::select::SelectExpressionEvaluator::new(self, select_expr);
+        evaluator.evaluate_memory_locations()
+    }
+
     ///  Reads any variable from the current thread, uses a select expression to filter variables
     /// and return their names.
     ///
@@ -823,6 +1798,24 @@ impl Debugger {
         evaluator.evaluate_on_arguments()
     }
 
+    /// Same as [`Debugger::read_argument`] but values that are out of parsing limits
+    /// aren't parsed (see [`Debugger::read_variable_with_limits`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `select_expr`: data query expression
+    /// * `limits`: parsing limits
+    pub fn read_argument_with_limits(
+        &self,
+        select_expr: DQE,
+        limits: ParseLimits,
+    ) -> Result<Vec<VariableIR>, Error> {
+        disable_when_not_stared!(self);
+        let evaluator =
+            variable::select::SelectExpressionEvaluator::new(self, select_expr).with_limits(limits);
+        evaluator.evaluate_on_arguments()
+    }
+
     /// Reads any argument from the current function, uses a select expression to filter arguments
     /// and return their names.
     ///
@@ -901,6 +1894,28 @@ impl Debugger {
             .flatten()
     }
 
+    /// Return line table rows (line and column numbers, addresses and `is_stmt` flags)
+    /// of files matched by a template. Lines with `is_stmt` rows can take a breakpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_tpl`: file name template (full path or part of a file path)
+    pub fn known_lines(&self, file_tpl: &str) -> Result<Vec<PlaceDescriptorOwned>, Error> {
+        let mut lines = vec![];
+        for dwarf in self.debugee.debug_info_all() {
+            if !dwarf.has_debug_info() {
+                continue;
+            }
+            lines.extend(
+                dwarf
+                    .known_lines(file_tpl)?
+                    .into_iter()
+                    .map(|p| p.to_owned()),
+            );
+        }
+        Ok(lines)
+    }
+
     /// Return a list of shared libraries.
     pub fn shared_libs(&self) -> Vec<RegionInfo> {
         self.debugee.dump_mapped_regions()
@@ -915,6 +1930,22 @@ impl Debugger {
         )
     }
 
+    /// Return a list of disassembled instructions of a function in focus or of a memory range.
+    /// Instructions are annotated with source code places, a current program counter
+    /// and user defined breakpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `target`: code for disassemble
+    pub fn disassemble(&self, target: DisasmTarget) -> Result<Disassembly, Error> {
+        disable_when_not_stared!(self);
+        self.debugee.disassemble(
+            self.exploration_ctx(),
+            target,
+            &self.breakpoints.active_breakpoints(),
+        )
+    }
+
     /// Return two place descriptors, at the start and at the end of the current function.
     pub fn current_function_range(&self) -> Result<FunctionRange, Error> {
         disable_when_not_stared!(self);
@@ -924,6 +1955,13 @@ impl Debugger {
 
 impl Drop for Debugger {
     fn drop(&mut self) {
+        if let Some(core) = self.process.core() {
+            core.detach();
+            return;
+        }
+
+        self.checkpoints.kill_all();
+
         if self.process.is_external() {
             _ = self.breakpoints.disable_all_breakpoints(&self.debugee);
 
@@ -997,16 +2035,75 @@ impl Drop for Debugger {
     }
 }
 
-/// Read N bytes from `PID` process.
+/// Write bytes into `PID` process memory (or into a [`memory::TargetBackend`] attached to `PID`),
+/// partially overwritten words are read before writing.
+pub fn write_memory_by_pid(pid: Pid, addr: usize, data: &[u8]) -> Result<(), nix::Error> {
+    if let Some(backend) = memory::backend(pid) {
+        return backend.write_memory(addr, data);
+    }
+
+    let word_size = mem::size_of::<c_long>();
+    let start = addr - addr % word_size;
+    let end = (addr + data.len()).div_ceil(word_size) * word_size;
+
+    for word_addr in (start..end).step_by(word_size) {
+        let mut word = sys::ptrace::read(pid, word_addr as *mut c_void)?.to_ne_bytes();
+        for (i, byte) in word.iter_mut().enumerate() {
+            let offset = word_addr + i;
+            if (addr..addr + data.len()).contains(&offset) {
+                *byte = data[offset - addr];
+            }
+        }
+        unsafe {
+            sys::ptrace::write(
+                pid,
+                word_addr as *mut c_void,
+                c_long::from_ne_bytes(word) as *mut c_void,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads larger than this size are reported as a progress and may be cancelled.
+const LARGE_READ_SIZE: usize = 1024 * 1024;
+
+/// Read N bytes from `PID` process (or from a [`memory::TargetBackend`] attached to `PID`).
+/// Large read returns `ECANCELED` error if cancelled by user.
 pub fn read_memory_by_pid(pid: Pid, addr: usize, read_n: usize) -> Result<Vec<u8>, nix::Error> {
+    if let Some(backend) = memory::backend(pid) {
+        return backend.read_memory(addr, read_n);
+    }
+
     let mut read_reminder = read_n as isize;
     let mut result = Vec::with_capacity(read_n);
 
     let single_read_size = mem::size_of::<c_long>();
 
+    let task = (read_n >= LARGE_READ_SIZE).then(|| Task::start("read memory", read_n as u64));
+
     let mut addr = addr as *mut c_long;
     while read_reminder > 0 {
-        let value = sys::ptrace::read(pid, addr as *mut c_void)?;
+        if let Some(ref task) = task {
+            if result.len() % LARGE_READ_SIZE == 0 {
+                let done = if result.is_empty() {
+                    0
+                } else {
+                    LARGE_READ_SIZE
+                };
+                task.advance(done as u64)
+                    .map_err(|_| nix::Error::ECANCELED)?;
+            }
+        }
+
+        let value = match sys::ptrace::read(pid, addr as *mut c_void) {
+            Ok(value) => value,
+            // thread is running (in non-stop mode), so memory is read without ptrace
+            Err(nix::Error::ESRCH) if result.is_empty() => {
+                return read_memory_by_vm(pid, addr as usize, read_n)
+            }
+            Err(e) => return Err(e),
+        };
         result.extend(value.to_ne_bytes().into_iter().take(read_reminder as usize));
 
         read_reminder -= single_read_size as isize;
@@ -1017,3 +2114,21 @@ pub fn read_memory_by_pid(pid: Pid, addr: usize, read_n: usize) -> Result<Vec<u8
 
     Ok(result)
 }
+
+/// Read N bytes from `PID` process with `process_vm_readv`, unlike ptrace
+/// a thread may be running.
+fn read_memory_by_vm(pid: Pid, addr: usize, read_n: usize) -> Result<Vec<u8>, nix::Error> {
+    let mut result = vec![0; read_n];
+    let read = uio::process_vm_readv(
+        pid,
+        &mut [IoSliceMut::new(&mut result)],
+        &[RemoteIoVec {
+            base: addr,
+            len: read_n,
+        }],
+    )?;
+    if read != read_n {
+        return Err(nix::Error::EFAULT);
+    }
+    Ok(result)
+}
@@ -1,3 +1,4 @@
+use crate::debugger::coredump::CoreDump;
 use crate::debugger::error::Error;
 use crate::debugger::error::Error::{Ptrace, Waitpid};
 use nix::sys;
@@ -7,14 +8,24 @@ use nix::sys::signal::{SIGSTOP, SIGTRAP};
 use nix::sys::wait::WaitStatus::PtraceEvent;
 use nix::sys::wait::{waitpid, WaitPidFlag};
 use nix::unistd::{fork, ForkResult, Pid};
-use os_pipe::PipeWriter;
+use os_pipe::{PipeReader, PipeWriter};
 use std::collections::HashSet;
 use std::iter;
 use std::marker::PhantomData;
 use std::os::unix::process::CommandExt;
+use std::path::Path;
 use std::process::Command;
+use std::sync::Arc;
 use sysinfo::{RefreshKind, System};
 
+/// Options of `ptrace` for debugee processes.
+pub(super) const TRACE_OPTIONS: Options = Options::PTRACE_O_TRACECLONE
+    .union(Options::PTRACE_O_TRACEEXEC)
+    .union(Options::PTRACE_O_TRACEEXIT)
+    .union(Options::PTRACE_O_TRACEFORK)
+    .union(Options::PTRACE_O_TRACEVFORK)
+    .union(Options::PTRACE_O_TRACESYSGOOD);
+
 /// Process state.
 pub trait State {}
 
@@ -39,9 +50,15 @@ pub struct Child<S: State> {
     program: String,
     stdout: PipeWriter,
     stderr: PipeWriter,
+    /// Stdin pipe, `None` if stdin is inherited from the debugger.
+    stdin: Option<PipeReader>,
     args: Vec<String>,
+    /// Environment changes (variables with `None` value are removed from inherited environment).
+    env: Vec<(String, Option<String>)>,
     pid: Option<Pid>,
     external_info: Option<ExternalInfo>,
+    /// Core file of a dumped process, `None` for a live process.
+    core: Option<Arc<CoreDump>>,
     _p: PhantomData<S>,
 }
 
@@ -63,13 +80,28 @@ impl Child<Template> {
         Self {
             stdout,
             stderr,
+            stdin: None,
             program: program.into(),
             args: args.into_iter().map(Into::into).collect(),
+            env: vec![],
             pid: None,
             external_info: None,
+            core: None,
             _p: PhantomData,
         }
     }
+
+    /// Redirect process stdin from a pipe.
+    ///
+    /// # Arguments
+    ///
+    /// * `stdin`: stdin pipe
+    pub fn with_stdin(self, stdin: PipeReader) -> Self {
+        Self {
+            stdin: Some(stdin),
+            ..self
+        }
+    }
 }
 
 impl Child<Installed> {
@@ -78,6 +110,27 @@ impl Child<Installed> {
         self.pid.unwrap()
     }
 
+    /// Create [`Child`] for a copy of this process created by a `fork` call (a checkpoint).
+    /// Program, arguments and output pipes are the same as in this process.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid`: pid of a process copy
+    pub fn forked(&self, pid: Pid) -> Result<Self, Error> {
+        Ok(Self {
+            stdout: self.stdout.try_clone()?,
+            stderr: self.stderr.try_clone()?,
+            stdin: self.stdin.as_ref().map(PipeReader::try_clone).transpose()?,
+            program: self.program.clone(),
+            args: self.args.clone(),
+            env: self.env.clone(),
+            pid: Some(pid),
+            external_info: None,
+            core: None,
+            _p: PhantomData,
+        })
+    }
+
     ///  Create [`Child`] from already running external process.
     ///
     /// # Arguments
@@ -117,13 +170,7 @@ impl Child<Installed> {
                 .collect();
 
             for tid in &threads {
-                sys::ptrace::seize(
-                    *tid,
-                    Options::PTRACE_O_TRACECLONE
-                        .union(Options::PTRACE_O_TRACEEXEC)
-                        .union(Options::PTRACE_O_TRACEEXIT),
-                )
-                .map_err(Error::Attach)?;
+                sys::ptrace::seize(*tid, TRACE_OPTIONS).map_err(Error::Attach)?;
             }
 
             for tid in &threads {
@@ -142,12 +189,48 @@ impl Child<Installed> {
         Ok(Self {
             stdout,
             stderr,
+            stdin: None,
             program: program_name,
             args: external_process.cmd()[1..].to_vec(),
+            env: vec![],
             pid: Some(pid),
             external_info: Some(ExternalInfo {
                 threads: interrupted_threads.into_iter().collect(),
             }),
+            core: None,
+            _p: PhantomData,
+        })
+    }
+
+    /// Create [`Child`] from a core file of a dumped process. There is no running process,
+    /// memory and registers of threads are read from a core file (see [`CoreDump::attach`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `program`: executable file of a dumped process
+    /// * `core_path`: path to a core file
+    /// * `stdout`: stdout pipe, not used for a dumped process
+    /// * `stderr`: stderr pipe, not used for a dumped process
+    pub fn from_core(
+        program: impl Into<String>,
+        core_path: impl AsRef<Path>,
+        stdout: PipeWriter,
+        stderr: PipeWriter,
+    ) -> Result<Self, Error> {
+        let core = CoreDump::open(core_path)?.attach();
+        // a thread that receives a signal goes first, its id is used as a debugee pid
+        let pid = core.threads()[0].tid;
+
+        Ok(Self {
+            stdout,
+            stderr,
+            stdin: None,
+            program: program.into(),
+            args: vec![],
+            env: vec![],
+            pid: Some(pid),
+            external_info: None,
+            core: Some(core),
             _p: PhantomData,
         })
     }
@@ -169,6 +252,44 @@ impl<S: State> Child<S> {
         self.external_info.as_ref()
     }
 
+    /// Return a core file if debugee is a dumped process (post-mortem debugging).
+    pub fn core(&self) -> Option<&CoreDump> {
+        self.core.as_deref()
+    }
+
+    /// Return program arguments.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Return environment changes, variables with `None` value are removed.
+    pub fn env(&self) -> &[(String, Option<String>)] {
+        &self.env
+    }
+
+    /// Replace program arguments, new arguments are used at next [`Child::install`].
+    ///
+    /// # Arguments
+    ///
+    /// * `args`: program arguments
+    pub fn set_args(&mut self, args: Vec<String>) {
+        self.args = args;
+    }
+
+    /// Set or remove an environment variable, a change is applied at next [`Child::install`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: variable name
+    /// * `value`: variable value, `None` removes a variable from inherited environment
+    pub fn set_env(&mut self, name: &str, value: Option<&str>) {
+        let value = value.map(ToString::to_string);
+        match self.env.iter_mut().find(|(var, _)| var == name) {
+            Some((_, old_value)) => *old_value = value,
+            None => self.env.push((name.to_string(), value)),
+        }
+    }
+
     /// Instantiate process by `fork()` system call with caller as a parent process.
     /// After installation child process stopped by `SIGSTOP` signal.
     pub fn install(&self) -> Result<Child<Installed>, Error> {
@@ -177,6 +298,15 @@ impl<S: State> Child<S> {
             .args(&self.args)
             .stdout(self.stdout.try_clone()?)
             .stderr(self.stderr.try_clone()?);
+        if let Some(ref stdin) = self.stdin {
+            debugee_cmd.stdin(stdin.try_clone()?);
+        }
+        for (name, value) in &self.env {
+            match value {
+                Some(value) => debugee_cmd.env(name, value),
+                None => debugee_cmd.env_remove(name),
+            };
+        }
 
         unsafe {
             debugee_cmd.pre_exec(move || {
@@ -188,21 +318,18 @@ impl<S: State> Child<S> {
         match unsafe { fork().expect("fork() error") } {
             ForkResult::Parent { child: pid } => {
                 waitpid(Pid::from_raw(-1), Some(WaitPidFlag::WSTOPPED)).map_err(Waitpid)?;
-                sys::ptrace::seize(
-                    pid,
-                    Options::PTRACE_O_TRACECLONE
-                        .union(Options::PTRACE_O_TRACEEXEC)
-                        .union(Options::PTRACE_O_TRACEEXIT),
-                )
-                .map_err(Ptrace)?;
+                sys::ptrace::seize(pid, TRACE_OPTIONS).map_err(Ptrace)?;
 
                 Ok(Child {
                     stdout: self.stdout.try_clone()?,
                     stderr: self.stderr.try_clone()?,
+                    stdin: self.stdin.as_ref().map(PipeReader::try_clone).transpose()?,
                     program: self.program.clone(),
                     args: self.args.clone(),
+                    env: self.env.clone(),
                     pid: Some(pid),
                     external_info: None,
+                    core: None,
                     _p: PhantomData,
                 })
             }
@@ -0,0 +1,145 @@
+//! Progress reporting and cancellation of long-running operations.
+//!
+//! Long operations (debug information parsing, large memory reads, huge containers parsing)
+//! are wrapped into a [`Task`]. Task reports its progress to a progress observer (if one set)
+//! and may be cancelled from another thread (a Ctrl-C handler, for example) using [`cancel`].
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Progress of short tasks is not reported.
+const REPORT_DELAY: Duration = Duration::from_millis(300);
+
+/// Receiver of progress events.
+pub trait ProgressObserver: Send + Sync {
+    /// Called when task progress is changed (at most once per percent).
+    ///
+    /// # Arguments
+    ///
+    /// * `task`: task name
+    /// * `done`: completed units of work
+    /// * `total`: total units of work
+    fn on_progress(&self, task: &str, done: u64, total: u64);
+
+    /// Called when task is finished or cancelled, only if the progress of the task was reported.
+    fn on_finish(&self, task: &str);
+}
+
+static OBSERVER: RwLock<Option<Box<dyn ProgressObserver>>> = RwLock::new(None);
+
+static CANCEL_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+static ACTIVE_TASKS: AtomicUsize = AtomicUsize::new(0);
+
+/// Set (or reset if `None`) a global progress observer.
+pub fn set_observer(observer: Option<Box<dyn ProgressObserver>>) {
+    *OBSERVER.write().unwrap() = observer;
+}
+
+/// Request cancellation of all running tasks.
+/// Return `false` if there are no running tasks, in this case nothing is cancelled.
+pub fn cancel() -> bool {
+    if ACTIVE_TASKS.load(Ordering::SeqCst) == 0 {
+        return false;
+    }
+    CANCEL_REQUESTED.store(true, Ordering::SeqCst);
+    true
+}
+
+/// Task was cancelled by user.
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+#[error("operation cancelled")]
+pub struct Cancelled;
+
+/// Long-running operation. Task may be shared between threads.
+pub struct Task {
+    name: &'static str,
+    total: u64,
+    done: AtomicU64,
+    /// Last reported percent, `u64::MAX` if progress is not reported yet.
+    reported_percent: AtomicU64,
+    started_at: Instant,
+}
+
+impl Task {
+    /// Start a new task.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: task name, shown to user
+    /// * `total`: total units of work
+    pub fn start(name: &'static str, total: u64) -> Self {
+        if ACTIVE_TASKS.fetch_add(1, Ordering::SeqCst) == 0 {
+            // forget about cancellation requested before any task started
+            CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+        }
+
+        Self {
+            name,
+            total,
+            done: AtomicU64::new(0),
+            reported_percent: AtomicU64::new(u64::MAX),
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Return `true` if cancellation is requested.
+    pub fn is_cancelled(&self) -> bool {
+        CANCEL_REQUESTED.load(Ordering::SeqCst)
+    }
+
+    /// Mark `n` units of work as completed, report progress if needed.
+    /// Return an error if cancellation is requested.
+    pub fn advance(&self, n: u64) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            return Err(Cancelled);
+        }
+
+        let done = self.done.fetch_add(n, Ordering::Relaxed) + n;
+        if self.total == 0 || self.started_at.elapsed() < REPORT_DELAY {
+            return Ok(());
+        }
+
+        let percent = done.min(self.total) * 100 / self.total;
+        if self.reported_percent.swap(percent, Ordering::Relaxed) != percent {
+            if let Some(observer) = OBSERVER.read().unwrap().as_ref() {
+                observer.on_progress(self.name, done.min(self.total), self.total);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        if ACTIVE_TASKS.fetch_sub(1, Ordering::SeqCst) == 1 {
+            CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+        }
+
+        if *self.reported_percent.get_mut() != u64::MAX {
+            if let Some(observer) = OBSERVER.read().unwrap().as_ref() {
+                observer.on_finish(self.name);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_task_cancellation() {
+        assert!(!cancel());
+
+        let task = Task::start("test", 10);
+        assert!(task.advance(1).is_ok());
+        assert!(cancel());
+        assert_eq!(task.advance(1), Err(Cancelled));
+        drop(task);
+
+        let task = Task::start("test", 10);
+        assert!(task.advance(1).is_ok());
+    }
+}
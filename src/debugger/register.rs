@@ -1,14 +1,18 @@
 use crate::debugger::error::Error;
 use crate::debugger::error::Error::{Ptrace, RegisterNotFound};
-use nix::libc::user_regs_struct;
+use crate::debugger::memory;
+use nix::errno::Errno;
+use nix::libc;
+use nix::libc::{user_fpregs_struct, user_regs_struct};
 use nix::sys;
 use nix::unistd::Pid;
 use smallvec::{smallvec, SmallVec};
+use std::mem;
 use strum_macros::Display;
-use strum_macros::EnumString;
+use strum_macros::{EnumIter, EnumString};
 
 /// x86_64 registers.
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, EnumString, Display)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, EnumString, Display, EnumIter)]
 #[strum(serialize_all = "snake_case")]
 pub enum Register {
     Rax,
@@ -184,6 +188,9 @@ impl RegisterMap {
     ///
     /// * `pid`: thread id.
     pub fn current(pid: Pid) -> Result<Self, Error> {
+        if let Some(regs) = memory::backend(pid).and_then(|backend| backend.registers(pid)) {
+            return Ok(regs.into());
+        }
         let regs = sys::ptrace::getregs(pid).map_err(Ptrace)?;
         Ok(regs.into())
     }
@@ -270,6 +277,9 @@ impl RegisterMap {
     ///
     /// * `pid`: target thread.
     pub fn persist(self, pid: Pid) -> Result<(), Error> {
+        if let Some(backend) = memory::backend(pid) {
+            return backend.set_registers(pid, self.into()).map_err(Ptrace);
+        }
         sys::ptrace::setregs(pid, self.into()).map_err(Ptrace)
     }
 }
@@ -337,3 +347,47 @@ impl From<RegisterMap> for DwarfRegisterMap {
         DwarfRegisterMap(dwarf_map)
     }
 }
+
+/// Return floating point (x87 and SSE) registers of a thread.
+///
+/// # Arguments
+///
+/// * `pid`: thread id.
+pub(super) fn fp_registers(pid: Pid) -> Result<user_fpregs_struct, Error> {
+    let mut fpregs = mem::MaybeUninit::<user_fpregs_struct>::uninit();
+    // SAFETY: kernel fills a whole `user_fpregs_struct`
+    let res = unsafe {
+        libc::ptrace(
+            libc::PTRACE_GETFPREGS,
+            pid.as_raw(),
+            std::ptr::null_mut::<libc::c_void>(),
+            fpregs.as_mut_ptr(),
+        )
+    };
+    if res < 0 {
+        return Err(Ptrace(Errno::last()));
+    }
+    Ok(unsafe { fpregs.assume_init() })
+}
+
+/// Replace floating point (x87 and SSE) registers of a thread.
+///
+/// # Arguments
+///
+/// * `pid`: target thread.
+/// * `fpregs`: new register values.
+pub(super) fn set_fp_registers(pid: Pid, fpregs: &user_fpregs_struct) -> Result<(), Error> {
+    // SAFETY: kernel reads a whole `user_fpregs_struct`
+    let res = unsafe {
+        libc::ptrace(
+            libc::PTRACE_SETFPREGS,
+            pid.as_raw(),
+            std::ptr::null_mut::<libc::c_void>(),
+            fpregs as *const user_fpregs_struct,
+        )
+    };
+    if res < 0 {
+        return Err(Ptrace(Errno::last()));
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! Skip rules for stepping.
+//!
+//! Functions that match any rule (like std or alloc internals) are never stopped at
+//! by a `step` command, debugger steps out of them transparently.
+
+use crate::debugger::debugee::Location;
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::{InvalidSkipRule, SkipRuleNotFound};
+use crate::debugger::Debugger;
+use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// Skip rule, source file or function name pattern.
+#[derive(Debug, Clone)]
+pub enum SkipRule {
+    /// Source file glob pattern (`*` matches any sequence of characters, `?` - any character).
+    File { glob: String, regex: Regex },
+    /// Function name (with namespace) regular expression.
+    Function(Regex),
+}
+
+impl SkipRule {
+    /// Create a rule that skips functions placed in matched source files.
+    ///
+    /// # Arguments
+    ///
+    /// * `glob`: file path glob pattern, like `*/libcore/*`
+    pub fn file(glob: &str) -> Result<Self, Error> {
+        let mut pattern = String::from("^");
+        for c in glob.chars() {
+            match c {
+                '*' => pattern.push_str(".*"),
+                '?' => pattern.push('.'),
+                _ => pattern.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        pattern.push('$');
+
+        Ok(SkipRule::File {
+            glob: glob.to_string(),
+            regex: Regex::new(&pattern).map_err(|e| InvalidSkipRule(e.to_string()))?,
+        })
+    }
+
+    /// Create a rule that skips functions with matched names.
+    ///
+    /// # Arguments
+    ///
+    /// * `regex`: function name regular expression, like `^core::`
+    pub fn function(regex: &str) -> Result<Self, Error> {
+        Ok(SkipRule::Function(
+            Regex::new(regex).map_err(|e| InvalidSkipRule(e.to_string()))?,
+        ))
+    }
+
+    fn matches(&self, file: Option<&Path>, function: Option<&str>) -> bool {
+        match self {
+            SkipRule::File { regex, .. } => {
+                file.is_some_and(|file| regex.is_match(&file.to_string_lossy()))
+            }
+            SkipRule::Function(regex) => function.is_some_and(|name| regex.is_match(name)),
+        }
+    }
+}
+
+impl Display for SkipRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipRule::File { glob, .. } => write!(f, "file {glob}"),
+            SkipRule::Function(regex) => write!(f, "function {regex}"),
+        }
+    }
+}
+
+impl Debugger {
+    /// Add a skip rule, return a rule number.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule`: file or function rule
+    pub fn add_skip_rule(&mut self, rule: SkipRule) -> usize {
+        self.skip_rules.push(rule);
+        self.skip_rules.len()
+    }
+
+    /// Remove a skip rule by its number (numbers of the next rules are shifted).
+    ///
+    /// # Arguments
+    ///
+    /// * `num`: rule number, starting from 1
+    pub fn remove_skip_rule(&mut self, num: usize) -> Result<SkipRule, Error> {
+        if num == 0 || num > self.skip_rules.len() {
+            return Err(SkipRuleNotFound(num));
+        }
+        Ok(self.skip_rules.remove(num - 1))
+    }
+
+    /// Return all skip rules.
+    pub fn skip_rules(&self) -> &[SkipRule] {
+        &self.skip_rules
+    }
+
+    /// Return true if a function at location matches any skip rule.
+    pub(super) fn is_skipped(&self, location: Location) -> Result<bool, Error> {
+        if self.skip_rules.is_empty() {
+            return Ok(false);
+        }
+
+        let dwarf = self.debugee.debug_info(location.pc)?;
+        let function = dwarf
+            .find_function_by_pc(location.global_pc)?
+            .and_then(|func| func.full_name());
+        let place = dwarf.find_place_from_pc(location.global_pc)?;
+        let file = place.as_ref().map(|place| place.file);
+
+        Ok(self
+            .skip_rules
+            .iter()
+            .any(|rule| rule.matches(file, function.as_deref())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_skip_rule_matching() {
+        let rule = SkipRule::file("*/libcore/*").unwrap();
+        assert!(rule.matches(Some(Path::new("/rustc/abc/src/libcore/fmt/mod.rs")), None));
+        assert!(!rule.matches(Some(Path::new("/home/user/libcore.rs")), None));
+        assert!(!rule.matches(None, Some("core::fmt::write")));
+
+        let rule = SkipRule::file("/src/main.r?").unwrap();
+        assert!(rule.matches(Some(Path::new("/src/main.rs")), None));
+        assert!(!rule.matches(Some(Path::new("/src/main.rs.bak")), None));
+
+        let rule = SkipRule::function("^(core|alloc)::").unwrap();
+        assert!(rule.matches(None, Some("core::fmt::write")));
+        assert!(!rule.matches(None, Some("calc::sum2")));
+        assert!(!rule.matches(Some(Path::new("/src/core/mod.rs")), None));
+
+        assert!(SkipRule::function("(").is_err());
+    }
+}
@@ -0,0 +1,387 @@
+//! Coarse record and replay of debugee execution.
+//!
+//! When recording is started, a debugee state is saved at every stop: registers of all threads
+//! and content of writable private memory regions. Memory pages that are not changed since
+//! the previous snapshot are shared, so a snapshot costs about the size of changed memory.
+//! Reverse execution restores one of the saved states. Effects outside of debugee memory
+//! (files, sockets, other processes) are not reverted.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::{
+    NoRecordedState, ProcessNotStarted, Ptrace, RecordingNotStarted,
+};
+use crate::debugger::{register, Debugger};
+use crate::weak_error;
+use nix::libc::{user_fpregs_struct, user_regs_struct};
+use nix::sys::ptrace;
+use nix::sys::uio;
+use nix::sys::uio::RemoteIoVec;
+use nix::unistd::Pid;
+use proc_maps::MapRange;
+use std::collections::{HashSet, VecDeque};
+use std::io::{IoSlice, IoSliceMut};
+use std::sync::Arc;
+use strum_macros::Display;
+
+/// Max count of saved snapshots, the oldest snapshots are dropped first.
+const SNAPSHOT_LIMIT: usize = 256;
+const PAGE_SIZE: usize = 4096;
+
+/// Debugee stop after which a snapshot is taken.
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum SnapshotKind {
+    /// State at the moment when recording is started.
+    Start,
+    /// Stop after a step (into, over, out or instruction step).
+    Step,
+    /// Stop after a continue (at a breakpoint, watchpoint or by a signal).
+    Continue,
+}
+
+/// Information about a saved snapshot.
+#[derive(Debug, Clone)]
+pub struct SnapshotInfo {
+    pub kind: SnapshotKind,
+    /// Thread in focus.
+    pub pid: Pid,
+    /// Program counter of a thread in focus.
+    pub pc: RelocatedAddress,
+}
+
+/// State of a recording.
+#[derive(Debug, Clone)]
+pub struct RecordInfo {
+    /// Saved snapshots, the oldest first, the last snapshot is a current state.
+    pub snapshots: Vec<SnapshotInfo>,
+    /// Size of saved memory pages in bytes.
+    pub memory_size: usize,
+}
+
+type Page = Arc<[u8]>;
+
+/// Content of a writable memory region, pages that can't be read are `None`.
+struct RegionSnapshot {
+    start: usize,
+    pages: Vec<Option<Page>>,
+}
+
+struct ThreadState {
+    pid: Pid,
+    regs: user_regs_struct,
+    fpregs: user_fpregs_struct,
+}
+
+struct Snapshot {
+    info: SnapshotInfo,
+    threads: Vec<ThreadState>,
+    regions: Vec<RegionSnapshot>,
+}
+
+/// Recording state, snapshots are saved only if recording is enabled.
+#[derive(Default)]
+pub(super) struct Recorder {
+    enabled: bool,
+    snapshots: VecDeque<Snapshot>,
+}
+
+/// Return `true` if the memory region must be saved in snapshots.
+fn is_recorded(map: &MapRange) -> bool {
+    map.is_read() && map.is_write() && map.flags.ends_with('p')
+}
+
+/// Read memory region page by page, pages that can't be read are `None`.
+///
+/// # Arguments
+///
+/// * `pid`: debugee process id
+/// * `start`: region start address
+/// * `len`: region length in bytes
+fn read_pages(pid: Pid, start: usize, len: usize) -> Vec<Option<Vec<u8>>> {
+    let mut buf = vec![0; len];
+    let read = uio::process_vm_readv(
+        pid,
+        &mut [IoSliceMut::new(&mut buf)],
+        &[RemoteIoVec { base: start, len }],
+    )
+    .unwrap_or(0);
+
+    buf.chunks(PAGE_SIZE)
+        .enumerate()
+        .map(|(i, page)| {
+            if (i + 1) * PAGE_SIZE <= read {
+                return Some(page.to_vec());
+            }
+            // read stops at the first unreadable page, pages after it are read one by one
+            let mut page = vec![0; PAGE_SIZE];
+            let base = start + i * PAGE_SIZE;
+            let read = uio::process_vm_readv(
+                pid,
+                &mut [IoSliceMut::new(&mut page)],
+                &[RemoteIoVec {
+                    base,
+                    len: PAGE_SIZE,
+                }],
+            );
+            (read == Ok(PAGE_SIZE)).then_some(page)
+        })
+        .collect()
+}
+
+fn write_page(pid: Pid, addr: usize, page: &[u8]) -> Result<(), Error> {
+    uio::process_vm_writev(
+        pid,
+        &[IoSlice::new(page)],
+        &[RemoteIoVec {
+            base: addr,
+            len: page.len(),
+        }],
+    )
+    .map_err(Ptrace)?;
+    Ok(())
+}
+
+impl Debugger {
+    /// Start recording of debugee states, the current state is saved immediately.
+    /// While recording is on, the state is saved at every stop after continue or step,
+    /// so [`Debugger::reverse_step`] and [`Debugger::reverse_continue`] can return to it.
+    pub fn start_recording(&mut self) -> Result<(), Error> {
+        if !self.debugee.is_in_progress() {
+            return Err(ProcessNotStarted);
+        }
+        if self.process.core().is_some() {
+            return Err(Error::PostMortem);
+        }
+        self.recorder = Recorder {
+            enabled: true,
+            snapshots: VecDeque::new(),
+        };
+        self.record_snapshot(SnapshotKind::Start)
+    }
+
+    /// Stop recording and forget all saved states.
+    pub fn stop_recording(&mut self) {
+        self.recorder = Recorder::default();
+    }
+
+    /// Return recording state, `None` if recording is not started.
+    pub fn record_info(&self) -> Option<RecordInfo> {
+        if !self.recorder.enabled {
+            return None;
+        }
+
+        let mut pages = HashSet::new();
+        let mut memory_size = 0;
+        for snapshot in &self.recorder.snapshots {
+            for page in snapshot
+                .regions
+                .iter()
+                .flat_map(|r| r.pages.iter().flatten())
+            {
+                if pages.insert(Arc::as_ptr(page)) {
+                    memory_size += page.len();
+                }
+            }
+        }
+
+        Some(RecordInfo {
+            snapshots: self
+                .recorder
+                .snapshots
+                .iter()
+                .map(|snapshot| snapshot.info.clone())
+                .collect(),
+            memory_size,
+        })
+    }
+
+    /// Return debugee to the state saved at the previous stop.
+    ///
+    /// **! change exploration context**
+    pub fn reverse_step(&mut self) -> Result<(), Error> {
+        self.reverse(|_| true)
+    }
+
+    /// Return debugee to the state saved at the previous stop after continue
+    /// (or to the state at recording start if there is no such stop).
+    ///
+    /// **! change exploration context**
+    pub fn reverse_continue(&mut self) -> Result<(), Error> {
+        self.reverse(|info| info.kind != SnapshotKind::Step)
+    }
+
+    fn reverse(&mut self, is_target: impl Fn(&SnapshotInfo) -> bool) -> Result<(), Error> {
+        if !self.debugee.is_in_progress() {
+            return Err(ProcessNotStarted);
+        }
+        if !self.recorder.enabled {
+            return Err(RecordingNotStarted);
+        }
+        let snapshots = &mut self.recorder.snapshots;
+        if snapshots.len() < 2 {
+            return Err(NoRecordedState);
+        }
+
+        // the last snapshot is a current state
+        snapshots.pop_back();
+        while snapshots.len() > 1 && !snapshots.back().is_some_and(|s| is_target(&s.info)) {
+            snapshots.pop_back();
+        }
+
+        let snapshot = self.recorder.snapshots.back().expect("infallible");
+        self.restore_snapshot(snapshot)?;
+
+        let pid = snapshot.info.pid;
+        if self.debugee.tracee_ctl().tracee(pid).is_some() {
+            self.expl_ctx_switch_thread(pid)?;
+        } else {
+            self.expl_ctx_update_location()?;
+        }
+        self.execute_on_step_hook()
+    }
+
+    /// Save a debugee state if recording is started, must be called after a debugee stop.
+    /// Recording is stopped if debugee is exited.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind`: stop kind
+    pub(super) fn record_stop(&mut self, kind: SnapshotKind) {
+        if !self.recorder.enabled {
+            return;
+        }
+        if !self.debugee.is_in_progress() {
+            self.stop_recording();
+            return;
+        }
+        weak_error!(self.record_snapshot(kind), "save debugee state");
+    }
+
+    fn record_snapshot(&mut self, kind: SnapshotKind) -> Result<(), Error> {
+        let snapshot = self.take_snapshot(kind, self.recorder.snapshots.back())?;
+        self.recorder.snapshots.push_back(snapshot);
+        if self.recorder.snapshots.len() > SNAPSHOT_LIMIT {
+            self.recorder.snapshots.pop_front();
+        }
+        Ok(())
+    }
+
+    /// Save current debugee state, pages equal to pages of previous snapshot are shared.
+    fn take_snapshot(
+        &self,
+        kind: SnapshotKind,
+        prev: Option<&Snapshot>,
+    ) -> Result<Snapshot, Error> {
+        let proc_pid = self.process.pid();
+        let maps = proc_maps::get_process_maps(proc_pid.as_raw())?;
+
+        let regions = maps
+            .iter()
+            .filter(|map| is_recorded(map))
+            .map(|map| {
+                let prev_pages = prev
+                    .and_then(|prev| prev.regions.iter().find(|r| r.start == map.start()))
+                    .map(|r| r.pages.as_slice())
+                    .unwrap_or_default();
+                let pages = read_pages(proc_pid, map.start(), map.size())
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, page)| {
+                        let page = page?;
+                        match prev_pages.get(i) {
+                            Some(Some(prev)) if prev.as_ref() == page.as_slice() => {
+                                Some(prev.clone())
+                            }
+                            _ => Some(Page::from(page)),
+                        }
+                    })
+                    .collect();
+                RegionSnapshot {
+                    start: map.start(),
+                    pages,
+                }
+            })
+            .collect();
+
+        let threads = self
+            .debugee
+            .tracee_ctl()
+            .snapshot()
+            .into_iter()
+            // running threads (in non-stop mode) are not saved
+            .filter(|tracee| tracee.is_stopped())
+            .map(|tracee| {
+                Ok(ThreadState {
+                    pid: tracee.pid,
+                    regs: ptrace::getregs(tracee.pid).map_err(Ptrace)?,
+                    fpregs: register::fp_registers(tracee.pid)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let location = self.exploration_ctx().location();
+        Ok(Snapshot {
+            info: SnapshotInfo {
+                kind,
+                pid: location.pid,
+                pc: location.pc,
+            },
+            threads,
+            regions,
+        })
+    }
+
+    /// Write saved memory pages and registers back into debugee,
+    /// only pages that differ from current memory are written.
+    /// Threads created after the snapshot are left as is.
+    fn restore_snapshot(&self, snapshot: &Snapshot) -> Result<(), Error> {
+        let proc_pid = self.process.pid();
+        for region in &snapshot.regions {
+            let current = read_pages(proc_pid, region.start, region.pages.len() * PAGE_SIZE);
+            for (i, (saved, current)) in region.pages.iter().zip(current).enumerate() {
+                let Some(saved) = saved else {
+                    continue;
+                };
+                if current.as_deref() == Some(saved.as_ref()) {
+                    continue;
+                }
+                // region may be unmapped after the snapshot
+                weak_error!(write_page(proc_pid, region.start + i * PAGE_SIZE, saved));
+            }
+        }
+
+        for thread in &snapshot.threads {
+            if self.debugee.tracee_ctl().tracee(thread.pid).is_none() {
+                continue;
+            }
+            ptrace::setregs(thread.pid, thread.regs).map_err(Ptrace)?;
+            register::set_fp_registers(thread.pid, &thread.fpregs)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::hint::black_box;
+
+    #[test]
+    fn test_read_pages() {
+        let pid = Pid::this();
+        let data = black_box(vec![7u8; PAGE_SIZE * 3]);
+        // align start to a page to read exactly two pages of the buffer
+        let start = (data.as_ptr() as usize).next_multiple_of(PAGE_SIZE);
+
+        let pages = read_pages(pid, start, PAGE_SIZE * 2);
+        assert_eq!(pages.len(), 2);
+        assert!(pages
+            .iter()
+            .all(|page| page.as_deref() == Some(&[7u8; PAGE_SIZE][..])));
+
+        // null page is never mapped
+        let pages = read_pages(pid, 0, PAGE_SIZE);
+        assert_eq!(pages, vec![None]);
+    }
+}
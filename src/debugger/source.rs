@@ -0,0 +1,165 @@
+//! Source code listing.
+//!
+//! Source files are loaded by paths from debug information. If a program is built on another
+//! machine or sources are moved after a build, a directory remap table (like gdb
+//! `set substitute-path`) replaces a path prefix before a file is loaded.
+//! Remap table is shared by all listing users (the debugger and user interfaces).
+
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::{fs, io};
+
+static REMAP: Lazy<RwLock<Vec<(PathBuf, PathBuf)>>> = Lazy::new(RwLock::default);
+
+/// Replace a directory prefix of source paths, a remap with the same `from` directory is replaced.
+///
+/// # Arguments
+///
+/// * `from`: directory from debug information (like a build machine directory)
+/// * `to`: local directory
+pub fn add_remap(from: impl Into<PathBuf>, to: impl Into<PathBuf>) {
+    let (from, to) = (from.into(), to.into());
+    let mut remap = REMAP.write().unwrap();
+    match remap.iter_mut().find(|(f, _)| *f == from) {
+        Some(entry) => entry.1 = to,
+        None => remap.push((from, to)),
+    }
+}
+
+/// Remove all directory remaps.
+pub fn clear_remap() {
+    REMAP.write().unwrap().clear();
+}
+
+/// Return directory remap table.
+pub fn remaps() -> Vec<(PathBuf, PathBuf)> {
+    REMAP.read().unwrap().clone()
+}
+
+/// Return a local path of a source file, the longest matched remap prefix is used.
+///
+/// # Arguments
+///
+/// * `path`: path from debug information
+pub fn resolve(path: &Path) -> PathBuf {
+    REMAP
+        .read()
+        .unwrap()
+        .iter()
+        .filter_map(|(from, to)| {
+            path.strip_prefix(from)
+                .ok()
+                .map(|rest| (from, to.join(rest)))
+        })
+        .max_by_key(|(from, _)| from.components().count())
+        .map(|(_, path)| path)
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// A line of a source file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLine {
+    /// Line number, starts from 1.
+    pub number: u64,
+    pub text: String,
+    /// True for a line of the stop place.
+    pub current: bool,
+}
+
+/// Cache of source file contents, files are cached by their local (remapped) paths.
+#[derive(Default)]
+pub struct SourceCache {
+    files: RefCell<HashMap<PathBuf, Arc<[String]>>>,
+}
+
+impl SourceCache {
+    /// Return all lines of a source file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file`: path from debug information
+    pub fn lines(&self, file: &Path) -> io::Result<Arc<[String]>> {
+        let path = resolve(file);
+        if let Some(lines) = self.files.borrow().get(&path) {
+            return Ok(lines.clone());
+        }
+
+        let lines: Arc<[String]> = io::BufReader::new(fs::File::open(&path)?)
+            .lines()
+            .map_while(Result::ok)
+            .collect();
+        self.files.borrow_mut().insert(path, lines.clone());
+        Ok(lines)
+    }
+
+    /// Return lines around a current line, `n` lines before and after it.
+    ///
+    /// # Arguments
+    ///
+    /// * `file`: path from debug information
+    /// * `line`: current line number
+    /// * `n`: count of context lines at each side
+    pub fn context(&self, file: &Path, line: u64, n: u64) -> io::Result<Vec<SourceLine>> {
+        let lines = self.lines(file)?;
+        let line = line.max(1);
+        let start = line.saturating_sub(n).max(1);
+
+        Ok(lines
+            .iter()
+            .enumerate()
+            .skip(start as usize - 1)
+            .take((line - start + n + 1) as usize)
+            .map(|(idx, text)| SourceLine {
+                number: idx as u64 + 1,
+                text: text.clone(),
+                current: idx as u64 + 1 == line,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_source_context() {
+        let dir = std::env::temp_dir().join(format!("bs_source_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let text = (1..=10).map(|i| format!("line {i}\n")).collect::<String>();
+        fs::write(dir.join("main.rs"), text).unwrap();
+
+        let build_dir = Path::new("/build/project/src");
+        add_remap("/build", "/nowhere");
+        add_remap(build_dir, &dir);
+        assert_eq!(resolve(&build_dir.join("main.rs")), dir.join("main.rs"));
+        assert_eq!(
+            resolve(Path::new("/build/other.rs")),
+            Path::new("/nowhere/other.rs")
+        );
+        assert_eq!(resolve(Path::new("/src/lib.rs")), Path::new("/src/lib.rs"));
+
+        let cache = SourceCache::default();
+        let context = cache.context(&build_dir.join("main.rs"), 2, 2).unwrap();
+        assert_eq!(
+            context.iter().map(|l| l.number).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert!(context[1].current);
+        assert_eq!(context[1].text, "line 2");
+
+        let context = cache.context(&build_dir.join("main.rs"), 10, 1).unwrap();
+        assert_eq!(
+            context.iter().map(|l| l.number).collect::<Vec<_>>(),
+            vec![9, 10]
+        );
+
+        clear_remap();
+        assert!(cache.context(&build_dir.join("main.rs"), 1, 1).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
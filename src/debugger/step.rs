@@ -1,11 +1,47 @@
-use crate::debugger::address::{Address, GlobalAddress};
+use crate::debugger::address::{Address, GlobalAddress, RelocatedAddress};
 use crate::debugger::breakpoint::Breakpoint;
 use crate::debugger::debugee::dwarf::unit::PlaceDescriptorOwned;
 use crate::debugger::debugee::tracer::{StopReason, TraceContext};
+use crate::debugger::debugee::DisasmTarget;
 use crate::debugger::error::Error;
-use crate::debugger::error::Error::{NoFunctionRanges, PlaceNotFound, ProcessExit};
+use crate::debugger::error::Error::{
+    JumpOutsideFunction, NoFunctionRanges, NoSuitablePlace, PlaceNotFound, ProcessExit,
+    StepTargetNotFound,
+};
 use crate::debugger::{Debugger, ExplorationContext};
 use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+
+/// A place in debugee code, target of `until`, `advance` and `jump` commands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TargetPlace {
+    /// Instruction address.
+    Address(RelocatedAddress),
+    /// File name (or part of a file path) and line number.
+    Line(String, u64),
+    /// Function name template.
+    Function(String),
+}
+
+/// A subroutine call at the current line, candidate for a `step into` target.
+#[derive(Debug, Clone)]
+pub struct CallTarget {
+    /// Address of a call instruction.
+    pub call_addr: RelocatedAddress,
+    /// Callee address, `None` for indirect calls.
+    pub callee_addr: Option<RelocatedAddress>,
+    /// Callee name (from debug information or symbol table), `None` if unknown.
+    pub name: Option<String>,
+}
+
+impl CallTarget {
+    /// Return true if a callee name is equal to a template (with or without namespace).
+    fn name_is(&self, tpl: &str) -> bool {
+        self.name.as_deref().is_some_and(|name| {
+            name == tpl || name.strip_suffix(tpl).is_some_and(|ns| ns.ends_with("::"))
+        })
+    }
+}
 
 /// Result of a step, if [`SignalInterrupt`] then step process interrupted by a signal and user must know it.
 /// If `quiet` set to `true` than no hooks must occurred.
@@ -131,6 +167,25 @@ impl Debugger {
                 .debug_info(location.pc)?
                 .get_cfa(&self.debugee, &ExplorationContext::new(location, 0))?;
 
+            // functions that match skip rules are stepped out transparently
+            if start_cfa != next_cfa && self.is_skipped(location)? {
+                let ret_addr = self.debugee.return_addr(location.pid)?;
+                self.step_out_frame()?;
+                let location = self.exploration_ctx().location();
+                if ret_addr != Some(location.pc) {
+                    // stopped at a breakpoint inside a skipped function
+                    break;
+                }
+                // a return address may be a start of the next statement
+                let dwarf = self.debugee.debug_info(location.pc)?;
+                if let Some(place) = dwarf.find_exact_place_from_pc(location.global_pc)? {
+                    if place.is_stmt && (place.file != sp_file || place.line_number != sp_line) {
+                        break;
+                    }
+                }
+                continue;
+            }
+
             // step is done if:
             // 1) we may step at same place in code but in another stack frame
             // 2) we step at another place in code (file + line)
@@ -167,22 +222,38 @@ impl Debugger {
     ///
     /// **! change exploration context**
     pub(super) fn step_over_breakpoint(&mut self) -> Result<Option<StopReason>, Error> {
+        let pid = self.exploration_ctx().pid_on_focus();
+        let Some(mb_signal) = self.step_thread_over_breakpoint(pid)? else {
+            return Ok(None);
+        };
+        self.expl_ctx_update_location()?;
+        Ok(mb_signal)
+    }
+
+    /// If a thread is stopped at a breakpoint, then it takes a step through this point.
+    /// Return `None` if there is no enabled breakpoint at a thread program counter, otherwise
+    /// return a step result, a [`StopReason::SignalStop`] if the step didn't happen cause signal.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid`: thread id
+    pub(super) fn step_thread_over_breakpoint(
+        &mut self,
+        pid: Pid,
+    ) -> Result<Option<Option<StopReason>>, Error> {
         // cannot use debugee::Location mapping offset may be not init yet
-        let tracee = self
-            .debugee
-            .get_tracee_ensure(self.exploration_ctx().pid_on_focus());
+        let tracee = self.debugee.get_tracee_ensure(pid);
         let mb_brkpt = self.breakpoints.get_enabled(tracee.pc()?);
-        let tracee_pid = tracee.pid;
         if let Some(brkpt) = mb_brkpt {
             if brkpt.is_enabled() {
-                brkpt.disable()?;
+                // hardware breakpoint is removed only from a stepped thread
+                brkpt.disable(&[pid])?;
                 let mb_signal = self.debugee.tracer_mut().single_step(
                     TraceContext::new(&self.breakpoints.active_breakpoints()),
-                    tracee_pid,
+                    pid,
                 )?;
-                brkpt.enable()?;
-                self.expl_ctx_update_location()?;
-                return Ok(mb_signal);
+                brkpt.enable(&[pid])?;
+                return Ok(Some(mb_signal));
             }
         }
         Ok(None)
@@ -203,7 +274,8 @@ impl Debugger {
             } else {
                 let brkpt =
                     Breakpoint::new_temporary(debug_info.pathname(), ret_addr, location.pid);
-                self.breakpoints.add_and_enable(brkpt)?;
+                let threads = self.thread_pids();
+                self.breakpoints.add_and_enable(brkpt, &threads)?;
                 self.continue_execution()?;
                 self.remove_breakpoint(Address::Relocated(ret_addr))?;
             }
@@ -218,6 +290,255 @@ impl Debugger {
         Ok(())
     }
 
+    /// Return addresses of a target place.
+    ///
+    /// # Arguments
+    ///
+    /// * `place`: target place
+    pub(super) fn target_place_addresses(
+        &self,
+        place: &TargetPlace,
+    ) -> Result<Vec<RelocatedAddress>, Error> {
+        let places = match place {
+            TargetPlace::Address(addr) => return Ok(vec![*addr]),
+            TargetPlace::Line(file, line) => self.search_lines(file, *line)?,
+            TargetPlace::Function(tpl) => self.search_functions(tpl)?,
+        };
+
+        let mut addresses = vec![];
+        for (dwarf, places) in places {
+            for place in places {
+                addresses.push(place.address.relocate_to_segment(&self.debugee, dwarf)?);
+            }
+        }
+        if addresses.is_empty() {
+            return Err(NoSuitablePlace);
+        }
+        Ok(addresses)
+    }
+
+    /// Return subroutine calls located at the current line of a frame in focus,
+    /// after the program counter, in order of appearance.
+    /// Calls are found by disassembling of the current function.
+    pub(super) fn call_targets(&self) -> Result<Vec<CallTarget>, Error> {
+        let ctx = self.exploration_ctx();
+        let location = ctx.location();
+        let dwarf = self.debugee.debug_info(location.pc)?;
+        let place = dwarf
+            .find_place_from_pc(location.global_pc)?
+            .ok_or(PlaceNotFound(location.global_pc))?;
+        let disasm = self.debugee.disassemble(
+            ctx,
+            DisasmTarget::CurrentFunction,
+            &self.breakpoints.active_breakpoints(),
+        )?;
+
+        disasm
+            .instructions
+            .into_iter()
+            .skip_while(|ins| ins.address < location.pc)
+            // instructions without a line (line 0) are generated by a compiler
+            // and may be placed between instructions of the current line
+            .take_while(|ins| {
+                ins.place.as_ref().is_some_and(|p| {
+                    p.line_number == 0
+                        || (p.line_number == place.line_number && p.file == place.file)
+                })
+            })
+            .filter(|ins| {
+                ins.mnemonic
+                    .as_deref()
+                    .is_some_and(|mnemonic| mnemonic.starts_with("call"))
+            })
+            .map(|ins| -> Result<CallTarget, Error> {
+                // direct call operand is an absolute address, like `0x55ab12`
+                let callee = ins
+                    .operands
+                    .as_deref()
+                    .and_then(|op| op.strip_prefix("0x"))
+                    .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+                    .map(GlobalAddress::from);
+                let name = callee.and_then(|callee| match dwarf.find_function_by_pc(callee) {
+                    Ok(Some(func)) => func.full_name(),
+                    _ => dwarf
+                        .find_symbol_by_pc(callee)
+                        .map(|symbol| symbol.display_name()),
+                });
+                Ok(CallTarget {
+                    call_addr: ins.address,
+                    callee_addr: callee
+                        .map(|callee| callee.relocate_to_segment(&self.debugee, dwarf))
+                        .transpose()?,
+                    name,
+                })
+            })
+            .collect()
+    }
+
+    /// Return an entry address (after a prolog) of a subroutine called at the current line.
+    /// If there are multiple callee candidates, exact name match is preferred,
+    /// then the first call that contains a template in its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: callee name or a part of it
+    pub(super) fn call_target_entry(&self, name: &str) -> Result<RelocatedAddress, Error> {
+        let targets = self.call_targets()?;
+        let (callee, dwarf) = targets
+            .iter()
+            .find(|target| target.name_is(name))
+            .or_else(|| {
+                targets.iter().find(|target| {
+                    target
+                        .name
+                        .as_deref()
+                        .is_some_and(|callee| callee.contains(name))
+                })
+            })
+            .and_then(|target| target.callee_addr)
+            .map(|callee| Ok::<_, Error>((callee, self.debugee.debug_info(callee)?)))
+            .transpose()?
+            .ok_or_else(|| StepTargetNotFound(name.to_string()))?;
+
+        let global = callee.into_global(&self.debugee)?;
+        match dwarf.find_function_by_pc(global)? {
+            // for functions with debug information stop after a prolog, like a regular step
+            Some(func) => Ok(func
+                .prolog_end_place()?
+                .address
+                .relocate_to_segment(&self.debugee, dwarf)?),
+            None => Ok(callee),
+        }
+    }
+
+    /// Return a jump target address for a thread in focus. Address must belong to the current
+    /// function unless jump is forced, if a place has multiple addresses (like a line with
+    /// inlined code) the lowest one is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `place`: jump target place
+    /// * `force`: allow a jump outside of the current function
+    pub(super) fn jump_target(
+        &self,
+        place: &TargetPlace,
+        force: bool,
+    ) -> Result<RelocatedAddress, Error> {
+        let mut addresses = self.target_place_addresses(place)?;
+        addresses.sort_unstable();
+
+        let location = self.exploration_ctx().location();
+        let dwarf = self.debugee.debug_info(location.pc)?;
+        let func = dwarf.find_function_by_pc(location.global_pc)?;
+        let in_current_fn = addresses.iter().copied().find(|addr| {
+            func.as_ref().is_some_and(|func| {
+                // an address must be in the same object file as the current function
+                let same_object = self
+                    .debugee
+                    .debug_info(*addr)
+                    .is_ok_and(|d| d.pathname() == dwarf.pathname());
+                same_object
+                    && addr
+                        .into_global(&self.debugee)
+                        .is_ok_and(|global| global.in_ranges(func.ranges()))
+            })
+        });
+
+        match in_current_fn {
+            Some(addr) => Ok(addr),
+            None if force => Ok(addresses[0]),
+            None => Err(JumpOutsideFunction),
+        }
+    }
+
+    /// Continue debugee execution until one of the addresses is reached or current frame returns.
+    /// Temporary breakpoints are set at the addresses and at the return address,
+    /// they are removed at any stop reason (breakpoint, signal or debugee exit).
+    ///
+    /// **! change exploration context**
+    ///
+    /// # Arguments
+    ///
+    /// * `addresses`: stop addresses
+    pub(super) fn run_until_addresses(
+        &mut self,
+        mut addresses: Vec<RelocatedAddress>,
+    ) -> Result<StepResult, Error> {
+        let location = self.exploration_ctx().location();
+        if let Some(ret_addr) = self.debugee.return_addr(location.pid)? {
+            addresses.push(ret_addr);
+        }
+        addresses.sort_unstable();
+        addresses.dedup();
+
+        let dwarf = self.debugee.debug_info(location.pc)?;
+        let to_delete: Vec<_> = addresses
+            .into_iter()
+            .filter(|addr| self.breakpoints.get_enabled(*addr).is_none())
+            .collect();
+        let threads = self.thread_pids();
+        self.breakpoints.add_and_enable_all(
+            to_delete
+                .iter()
+                .map(|addr| Breakpoint::new_temporary(dwarf.pathname(), *addr, location.pid))
+                .collect(),
+            &threads,
+        )?;
+
+        let stop_reason = self.continue_execution();
+        let threads = self.thread_pids();
+        self.breakpoints.remove_all_by_addr(&to_delete, &threads)?;
+
+        if let StopReason::SignalStop(_, signal) = stop_reason? {
+            // on signal hook already called at [`Self::continue_execution`]
+            return Ok(StepResult::signal_interrupt_quiet(signal));
+        }
+
+        if self.debugee.is_exited() {
+            // todo add exit code here
+            return Err(ProcessExit(0));
+        }
+
+        self.expl_ctx_update_location()?;
+        Ok(StepResult::Done)
+    }
+
+    /// Do steps over subroutine calls until debugee reaches a line greater than current one
+    /// or current frame returns (like gdb `until` without argument), so a backward jump
+    /// at the end of a loop doesn't stop a debugee.
+    ///
+    /// **! change exploration context**
+    pub(super) fn step_over_until_greater_line(&mut self) -> Result<StepResult, Error> {
+        let location = self.exploration_ctx().location();
+        let dwarf = self.debugee.debug_info(location.pc)?;
+        let start_place = dwarf
+            .find_place_from_pc(location.global_pc)?
+            .ok_or(PlaceNotFound(location.global_pc))?;
+        let start_file = start_place.file.to_path_buf();
+        let start_line = start_place.line_number;
+        let start_cfa = dwarf.get_cfa(&self.debugee, &ExplorationContext::new(location, 0))?;
+
+        loop {
+            if let StepResult::SignalInterrupt { signal, quiet } = self.step_over_any()? {
+                return Ok(StepResult::SignalInterrupt { signal, quiet });
+            }
+
+            let location = self.exploration_ctx().location();
+            let dwarf = self.debugee.debug_info(location.pc)?;
+            let cfa = dwarf.get_cfa(&self.debugee, &ExplorationContext::new(location, 0))?;
+            let Some(place) = dwarf.find_place_from_pc(location.global_pc)? else {
+                return Ok(StepResult::Done);
+            };
+
+            // step is done if:
+            // 1) current frame is changed (returned from a function)
+            // 2) debugee reaches another file or a line greater than start one
+            if cfa != start_cfa || place.file != start_file || place.line_number > start_line {
+                return Ok(StepResult::Done);
+            }
+        }
+    }
+
     /// Do debugee step (over subroutine calls too).
     /// Returns [`StepResult::SignalInterrupt`] if the step is interrupted by a signal
     /// or [`StepResult::Done`] if step done.
@@ -247,6 +568,8 @@ impl Debugger {
         let current_place = dwarf
             .find_place_from_pc(current_location.global_pc)?
             .ok_or(PlaceNotFound(current_location.global_pc))?;
+        let start_cfa =
+            dwarf.get_cfa(&self.debugee, &ExplorationContext::new(current_location, 0))?;
 
         let mut step_over_breakpoints = vec![];
         let mut to_delete = vec![];
@@ -292,35 +615,50 @@ impl Debugger {
             }
         }
 
-        step_over_breakpoints
-            .into_iter()
-            .try_for_each(|load_addr| {
-                self.breakpoints
-                    .add_and_enable(Breakpoint::new_temporary(
-                        dwarf.pathname(),
-                        load_addr,
-                        current_location.pid,
-                    ))
-                    .map(|_| ())
-            })?;
+        let threads = self.thread_pids();
+        self.breakpoints.add_and_enable_all(
+            step_over_breakpoints
+                .into_iter()
+                .map(|load_addr| {
+                    Breakpoint::new_temporary(dwarf.pathname(), load_addr, current_location.pid)
+                })
+                .collect(),
+            &threads,
+        )?;
 
         let return_addr = self.debugee.return_addr(current_location.pid)?;
         if let Some(ret_addr) = return_addr {
             if self.breakpoints.get_enabled(ret_addr).is_none() {
-                self.breakpoints.add_and_enable(Breakpoint::new_temporary(
-                    dwarf.pathname(),
-                    ret_addr,
-                    current_location.pid,
-                ))?;
+                self.breakpoints.add_and_enable(
+                    Breakpoint::new_temporary(dwarf.pathname(), ret_addr, current_location.pid),
+                    &threads,
+                )?;
                 to_delete.push(ret_addr);
             }
         }
 
-        let stop_reason = self.continue_execution()?;
+        let stop_reason = loop {
+            let stop_reason = self.continue_execution()?;
+
+            // if a function is called recursively, step breakpoints are hit in deeper frames too,
+            // these hits are ignored, step is done in the start frame or in a caller frame
+            if let StopReason::Breakpoint(_, pc) = stop_reason {
+                if to_delete.contains(&pc) && self.debugee.is_in_progress() {
+                    let location = self.exploration_ctx().location();
+                    let cfa = self
+                        .debugee
+                        .debug_info(location.pc)?
+                        .get_cfa(&self.debugee, &ExplorationContext::new(location, 0))?;
+                    if cfa < start_cfa {
+                        continue;
+                    }
+                }
+            }
+            break stop_reason;
+        };
 
-        to_delete
-            .into_iter()
-            .try_for_each(|addr| self.remove_breakpoint(Address::Relocated(addr)).map(|_| ()))?;
+        let threads = self.thread_pids();
+        self.breakpoints.remove_all_by_addr(&to_delete, &threads)?;
 
         if let StopReason::SignalStop(_, signal) = stop_reason {
             // on signal hook already called at [`Self::continue_execution`]
@@ -0,0 +1,579 @@
+//! Expressions over program data: arithmetic (`a + b * 2`), comparisons, logical operators,
+//! casts (`(u64)x`), address-of and dereference of arbitrary expressions.
+//!
+//! Operands of an expression are data query expressions ([`DQE`]) or literals,
+//! the result of an evaluation is a [`VariableIR`]. Numbers are evaluated as 128-bit integers
+//! or 64-bit floats, pointers are treated as addresses in arithmetic.
+
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::ExprEvaluation;
+use crate::debugger::variable::select::{Literal, DQE};
+use crate::debugger::variable::{
+    PointerVariable, ScalarVariable, SpecializedVariableIR, SupportedScalar, VariableIR,
+    VariableIdentity,
+};
+use crate::debugger::Debugger;
+use std::fmt::{Display, Formatter};
+use strum_macros::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+pub enum UnaryOp {
+    #[strum(serialize = "-")]
+    Neg,
+    #[strum(serialize = "!")]
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+pub enum BinaryOp {
+    #[strum(serialize = "+")]
+    Add,
+    #[strum(serialize = "-")]
+    Sub,
+    #[strum(serialize = "*")]
+    Mul,
+    #[strum(serialize = "/")]
+    Div,
+    #[strum(serialize = "%")]
+    Rem,
+    #[strum(serialize = "==")]
+    Eq,
+    #[strum(serialize = "!=")]
+    Ne,
+    #[strum(serialize = "<")]
+    Lt,
+    #[strum(serialize = "<=")]
+    Le,
+    #[strum(serialize = ">")]
+    Gt,
+    #[strum(serialize = ">=")]
+    Ge,
+    #[strum(serialize = "&&")]
+    And,
+    #[strum(serialize = "||")]
+    Or,
+}
+
+impl BinaryOp {
+    /// Operator precedence, operators with greater precedence bind tighter.
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Or => 0,
+            BinaryOp::And => 1,
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Lt
+            | BinaryOp::Le
+            | BinaryOp::Gt
+            | BinaryOp::Ge => 2,
+            BinaryOp::Add | BinaryOp::Sub => 3,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 4,
+        }
+    }
+}
+
+/// Primitive types available for a cast.
+pub const CAST_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32",
+    "f64", "bool",
+];
+
+/// Expression over program data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Data query expression, the first selected variable is used as a value.
+    Select(DQE),
+    Literal(Literal),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// Cast to a primitive type (like `(u64)x`) or to a pointer type (like `(*const u8)addr`).
+    Cast(String, Box<Expr>),
+    AddressOf(Box<Expr>),
+    Deref(Box<Expr>),
+}
+
+impl Expr {
+    /// Return boxed expression.
+    pub fn boxed(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let operand = |expr: &Expr, min_precedence: u8| match expr {
+            Expr::Binary(op, ..) if op.precedence() < min_precedence => format!("({expr})"),
+            _ => expr.to_string(),
+        };
+        // operands of prefix operators are wrapped if they are binary expressions
+        let prefix_operand = |expr: &Expr| operand(expr, u8::MAX);
+
+        match self {
+            Expr::Select(dqe) => dqe.fmt(f),
+            Expr::Literal(literal) => literal.fmt(f),
+            Expr::Unary(op, expr) => write!(f, "{op}{}", prefix_operand(expr)),
+            Expr::Binary(op, lhs, rhs) => write!(
+                f,
+                "{} {op} {}",
+                operand(lhs, op.precedence()),
+                operand(rhs, op.precedence() + 1)
+            ),
+            Expr::Cast(type_name, expr) => write!(f, "({type_name}){}", prefix_operand(expr)),
+            Expr::AddressOf(expr) => write!(f, "&{}", prefix_operand(expr)),
+            Expr::Deref(expr) => write!(f, "*{}", prefix_operand(expr)),
+        }
+    }
+}
+
+/// Numeric value of an operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Int(i128),
+    Uint(u128),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Number {
+    fn from_scalar(scalar: &SupportedScalar) -> Option<Self> {
+        Some(match *scalar {
+            SupportedScalar::I8(v) => Number::Int(v as i128),
+            SupportedScalar::I16(v) => Number::Int(v as i128),
+            SupportedScalar::I32(v) => Number::Int(v as i128),
+            SupportedScalar::I64(v) => Number::Int(v as i128),
+            SupportedScalar::I128(v) => Number::Int(v),
+            SupportedScalar::Isize(v) => Number::Int(v as i128),
+            SupportedScalar::U8(v) => Number::Uint(v as u128),
+            SupportedScalar::U16(v) => Number::Uint(v as u128),
+            SupportedScalar::U32(v) => Number::Uint(v as u128),
+            SupportedScalar::U64(v) => Number::Uint(v as u128),
+            SupportedScalar::U128(v) => Number::Uint(v),
+            SupportedScalar::Usize(v) => Number::Uint(v as u128),
+            SupportedScalar::F32(v) => Number::Float(v as f64),
+            SupportedScalar::F64(v) => Number::Float(v),
+            SupportedScalar::Bool(v) => Number::Bool(v),
+            SupportedScalar::Char(v) => Number::Uint(v as u128),
+            SupportedScalar::Empty() => return None,
+        })
+    }
+
+    fn from_literal(literal: &Literal) -> Result<Self, Error> {
+        Ok(match literal {
+            Literal::Int(v) => Number::Int(*v as i128),
+            Literal::Float(v) => Number::Float(*v),
+            Literal::Address(v) => Number::Uint(*v as u128),
+            Literal::Bool(v) => Number::Bool(*v),
+            _ => return Err(ExprEvaluation(format!("`{literal}` is not a number"))),
+        })
+    }
+
+    fn from_variable(var: &VariableIR) -> Result<Self, Error> {
+        let number = match var {
+            VariableIR::Scalar(ScalarVariable {
+                value: Some(scalar),
+                ..
+            }) => Number::from_scalar(scalar),
+            VariableIR::Pointer(PointerVariable {
+                value: Some(ptr), ..
+            })
+            | VariableIR::Specialized(SpecializedVariableIR::CStrPointer {
+                original:
+                    PointerVariable {
+                        value: Some(ptr), ..
+                    },
+                ..
+            }) => Some(Number::Uint(*ptr as usize as u128)),
+            _ => None,
+        };
+        number.ok_or_else(|| ExprEvaluation(format!("`{}` is not a number", var.identity())))
+    }
+
+    fn is_truthy(self) -> bool {
+        match self {
+            Number::Int(v) => v != 0,
+            Number::Uint(v) => v != 0,
+            Number::Float(v) => v != 0.0,
+            Number::Bool(v) => v,
+        }
+    }
+
+    fn as_i128(self) -> Option<i128> {
+        match self {
+            Number::Int(v) => Some(v),
+            Number::Uint(v) => i128::try_from(v).ok(),
+            Number::Float(_) | Number::Bool(_) => None,
+        }
+    }
+
+    fn as_f64(self) -> Option<f64> {
+        match self {
+            Number::Int(v) => Some(v as f64),
+            Number::Uint(v) => Some(v as f64),
+            Number::Float(v) => Some(v),
+            Number::Bool(_) => None,
+        }
+    }
+
+    fn unary(self, op: UnaryOp) -> Result<Self, Error> {
+        match (op, self) {
+            (UnaryOp::Not, number) => Ok(Number::Bool(!number.is_truthy())),
+            (UnaryOp::Neg, Number::Float(v)) => Ok(Number::Float(-v)),
+            (UnaryOp::Neg, Number::Bool(_)) => Err(ExprEvaluation(
+                "unary `-` can't be applied to bool".to_string(),
+            )),
+            (UnaryOp::Neg, number) => number
+                .as_i128()
+                .and_then(i128::checked_neg)
+                .map(Number::Int)
+                .ok_or_else(|| ExprEvaluation("integer overflow".to_string())),
+        }
+    }
+
+    fn binary(self, op: BinaryOp, rhs: Self) -> Result<Self, Error> {
+        use std::cmp::Ordering;
+
+        let ordering = || -> Result<Ordering, Error> {
+            let ordering = match (self, rhs) {
+                (Number::Bool(l), Number::Bool(r)) => Some(l.cmp(&r)),
+                (Number::Float(_), _) | (_, Number::Float(_)) => self
+                    .as_f64()
+                    .zip(rhs.as_f64())
+                    .and_then(|(l, r)| l.partial_cmp(&r)),
+                (Number::Uint(l), Number::Uint(r)) => Some(l.cmp(&r)),
+                _ => self.as_i128().zip(rhs.as_i128()).map(|(l, r)| l.cmp(&r)),
+            };
+            ordering.ok_or_else(|| ExprEvaluation(format!("operands of `{op}` are not comparable")))
+        };
+
+        let result = match op {
+            BinaryOp::And => Number::Bool(self.is_truthy() && rhs.is_truthy()),
+            BinaryOp::Or => Number::Bool(self.is_truthy() || rhs.is_truthy()),
+            BinaryOp::Eq => Number::Bool(ordering()? == Ordering::Equal),
+            BinaryOp::Ne => Number::Bool(ordering()? != Ordering::Equal),
+            BinaryOp::Lt => Number::Bool(ordering()? == Ordering::Less),
+            BinaryOp::Le => Number::Bool(ordering()? != Ordering::Greater),
+            BinaryOp::Gt => Number::Bool(ordering()? == Ordering::Greater),
+            BinaryOp::Ge => Number::Bool(ordering()? != Ordering::Less),
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
+                self.arithmetic(op, rhs)?
+            }
+        };
+        Ok(result)
+    }
+
+    fn arithmetic(self, op: BinaryOp, rhs: Self) -> Result<Self, Error> {
+        if matches!(self, Number::Bool(_)) || matches!(rhs, Number::Bool(_)) {
+            return Err(ExprEvaluation(format!("`{op}` can't be applied to bool")));
+        }
+        if matches!(op, BinaryOp::Div | BinaryOp::Rem) && !rhs.is_truthy() {
+            return Err(ExprEvaluation("division by zero".to_string()));
+        }
+
+        if let (Number::Float(_), _) | (_, Number::Float(_)) = (self, rhs) {
+            let (l, r) = self.as_f64().zip(rhs.as_f64()).expect("not a bool");
+            return Ok(Number::Float(match op {
+                BinaryOp::Add => l + r,
+                BinaryOp::Sub => l - r,
+                BinaryOp::Mul => l * r,
+                BinaryOp::Div => l / r,
+                _ => l % r,
+            }));
+        }
+
+        if let (Number::Uint(l), Number::Uint(r)) = (self, rhs) {
+            let result = match op {
+                BinaryOp::Add => l.checked_add(r),
+                BinaryOp::Sub => l.checked_sub(r),
+                BinaryOp::Mul => l.checked_mul(r),
+                BinaryOp::Div => l.checked_div(r),
+                _ => l.checked_rem(r),
+            };
+            // unsigned subtraction may produce a negative number, calculate it as signed
+            if let Some(result) = result {
+                return Ok(Number::Uint(result));
+            }
+        }
+
+        let overflow = || ExprEvaluation("integer overflow".to_string());
+        let l = self.as_i128().ok_or_else(overflow)?;
+        let r = rhs.as_i128().ok_or_else(overflow)?;
+        let result = match op {
+            BinaryOp::Add => l.checked_add(r),
+            BinaryOp::Sub => l.checked_sub(r),
+            BinaryOp::Mul => l.checked_mul(r),
+            BinaryOp::Div => l.checked_div(r),
+            _ => l.checked_rem(r),
+        };
+        result.map(Number::Int).ok_or_else(overflow)
+    }
+
+    /// Convert number into a scalar of a primitive type, like `as` operator does.
+    fn cast(self, type_name: &str) -> Result<SupportedScalar, Error> {
+        let (int, float) = match self {
+            Number::Int(v) => (v, v as f64),
+            Number::Uint(v) => (v as i128, v as f64),
+            Number::Float(v) => (v as i128, v),
+            Number::Bool(v) => (v as i128, v as i128 as f64),
+        };
+        Ok(match type_name {
+            "i8" => SupportedScalar::I8(int as i8),
+            "i16" => SupportedScalar::I16(int as i16),
+            "i32" => SupportedScalar::I32(int as i32),
+            "i64" => SupportedScalar::I64(int as i64),
+            "i128" => SupportedScalar::I128(int),
+            "isize" => SupportedScalar::Isize(int as isize),
+            "u8" => SupportedScalar::U8(int as u8),
+            "u16" => SupportedScalar::U16(int as u16),
+            "u32" => SupportedScalar::U32(int as u32),
+            "u64" => SupportedScalar::U64(int as u64),
+            "u128" => SupportedScalar::U128(match self {
+                Number::Uint(v) => v,
+                _ => int as u128,
+            }),
+            "usize" => SupportedScalar::Usize(int as usize),
+            "f32" => SupportedScalar::F32(float as f32),
+            "f64" => SupportedScalar::F64(float),
+            "bool" => SupportedScalar::Bool(self.is_truthy()),
+            _ => {
+                return Err(ExprEvaluation(format!(
+                    "unsupported cast type `{type_name}`"
+                )))
+            }
+        })
+    }
+
+    /// Convert number into a scalar of a default type: `i64`, `u64` (or 128-bit integers
+    /// if value doesn't fit), `f64` or `bool`.
+    fn into_scalar(self) -> SupportedScalar {
+        match self {
+            Number::Int(v) => i64::try_from(v)
+                .map(SupportedScalar::I64)
+                .unwrap_or(SupportedScalar::I128(v)),
+            Number::Uint(v) => u64::try_from(v)
+                .map(SupportedScalar::U64)
+                .unwrap_or(SupportedScalar::U128(v)),
+            Number::Float(v) => SupportedScalar::F64(v),
+            Number::Bool(v) => SupportedScalar::Bool(v),
+        }
+    }
+}
+
+fn scalar_variable(scalar: SupportedScalar, type_name: &str) -> VariableIR {
+    VariableIR::Scalar(ScalarVariable {
+        identity: VariableIdentity::no_namespace(None),
+        type_name: Some(type_name.to_string()),
+        value: Some(scalar),
+    })
+}
+
+fn default_type_name(scalar: &SupportedScalar) -> &'static str {
+    match scalar {
+        SupportedScalar::I64(_) => "i64",
+        SupportedScalar::I128(_) => "i128",
+        SupportedScalar::U64(_) => "u64",
+        SupportedScalar::U128(_) => "u128",
+        SupportedScalar::F64(_) => "f64",
+        _ => "bool",
+    }
+}
+
+/// Evaluate [`Expr`] at current debugee location.
+pub struct ExpressionEvaluator<'a> {
+    debugger: &'a Debugger,
+}
+
+impl<'a> ExpressionEvaluator<'a> {
+    pub fn new(debugger: &'a Debugger) -> Self {
+        Self { debugger }
+    }
+
+    /// Evaluate an expression. Result of a data query expression keeps a variable name,
+    /// other results are named by an expression itself.
+    pub fn evaluate(&self, expr: &Expr) -> Result<VariableIR, Error> {
+        let mut result = self.evaluate_inner(expr)?;
+        if !matches!(expr, Expr::Select(_)) {
+            *result.identity_mut() = VariableIdentity::no_namespace(Some(expr.to_string()));
+        }
+        Ok(result)
+    }
+
+    fn evaluate_inner(&self, expr: &Expr) -> Result<VariableIR, Error> {
+        match expr {
+            Expr::Select(dqe) => self.select(dqe),
+            Expr::Literal(literal) => Ok(Self::number_variable(Number::from_literal(literal)?)),
+            Expr::Unary(op, expr) => Ok(Self::number_variable(self.number(expr)?.unary(*op)?)),
+            Expr::Binary(op @ (BinaryOp::And | BinaryOp::Or), lhs, rhs) => {
+                // right operand evaluated only if needed
+                let lhs = self.number(lhs)?.is_truthy();
+                let result = match op {
+                    BinaryOp::And => lhs && self.number(rhs)?.is_truthy(),
+                    _ => lhs || self.number(rhs)?.is_truthy(),
+                };
+                Ok(Self::number_variable(Number::Bool(result)))
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                let result = self.number(lhs)?.binary(*op, self.number(rhs)?)?;
+                Ok(Self::number_variable(result))
+            }
+            Expr::Cast(type_name, expr) if type_name.starts_with('*') => {
+                let addr = match self.number(expr)? {
+                    Number::Uint(v) => v as usize,
+                    Number::Int(v) => v as usize,
+                    _ => {
+                        return Err(ExprEvaluation(format!(
+                            "`{expr}` is not an address, can't cast it to `{type_name}`"
+                        )))
+                    }
+                };
+                self.select(&DQE::PtrCast(addr, type_name.clone()))
+            }
+            Expr::Cast(type_name, expr) => {
+                let scalar = self.number(expr)?.cast(type_name)?;
+                Ok(scalar_variable(scalar, type_name))
+            }
+            Expr::AddressOf(expr) => self.address_of(expr),
+            Expr::Deref(expr) => match expr.as_ref() {
+                // select expressions are dereferenced using a type information
+                Expr::Select(dqe) => self.select(&DQE::Deref(dqe.clone().boxed())),
+                _ => match self.evaluate_inner(expr)? {
+                    VariableIR::Pointer(PointerVariable {
+                        value: Some(ptr),
+                        type_name: Some(type_name),
+                        ..
+                    })
+                    | VariableIR::Specialized(SpecializedVariableIR::CStrPointer {
+                        original:
+                            PointerVariable {
+                                value: Some(ptr),
+                                type_name: Some(type_name),
+                                ..
+                            },
+                        ..
+                    }) => self.select(&DQE::Deref(DQE::PtrCast(ptr as usize, type_name).boxed())),
+                    _ => Err(ExprEvaluation(format!("`{expr}` is not a pointer"))),
+                },
+            },
+        }
+    }
+
+    fn select(&self, dqe: &DQE) -> Result<VariableIR, Error> {
+        self.debugger
+            .read_variable(dqe.clone())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ExprEvaluation(format!("`{dqe}` not found")))
+    }
+
+    fn number(&self, expr: &Expr) -> Result<Number, Error> {
+        match expr {
+            Expr::Literal(literal) => Number::from_literal(literal),
+            _ => Number::from_variable(&self.evaluate_inner(expr)?),
+        }
+    }
+
+    fn number_variable(number: Number) -> VariableIR {
+        let scalar = number.into_scalar();
+        let type_name = default_type_name(&scalar);
+        scalar_variable(scalar, type_name)
+    }
+
+    fn address_of(&self, expr: &Expr) -> Result<VariableIR, Error> {
+        let Expr::Select(dqe @ DQE::Variable(_)) = expr else {
+            return Err(ExprEvaluation(format!(
+                "address of `{expr}` is unknown, only variables are supported"
+            )));
+        };
+        let location = self
+            .debugger
+            .variable_memory_location(dqe.clone())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ExprEvaluation(format!("`{dqe}` not found or not located in memory")))?;
+
+        Ok(VariableIR::Pointer(PointerVariable {
+            identity: VariableIdentity::no_namespace(None),
+            type_name: location.type_name.map(|t| format!("*const {t}")),
+            value: Some(location.address as *const ()),
+            target_type: None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debugger::variable::select::VariableSelector;
+
+    fn var(name: &str) -> Expr {
+        Expr::Select(DQE::Variable(VariableSelector::Name {
+            var_name: name.to_string(),
+            only_local: false,
+        }))
+    }
+
+    #[test]
+    fn test_expr_display() {
+        let expr = Expr::Binary(
+            BinaryOp::Mul,
+            Expr::Binary(BinaryOp::Add, var("a").boxed(), var("b").boxed()).boxed(),
+            Expr::Literal(Literal::Int(2)).boxed(),
+        );
+        assert_eq!(expr.to_string(), "(a + b) * 2");
+
+        let expr = Expr::Binary(
+            BinaryOp::Add,
+            var("a").boxed(),
+            Expr::Binary(BinaryOp::Mul, var("b").boxed(), var("c").boxed()).boxed(),
+        );
+        assert_eq!(expr.to_string(), "a + b * c");
+
+        let expr = Expr::Cast(
+            "u64".to_string(),
+            Expr::Deref(Expr::AddressOf(var("x").boxed()).boxed()).boxed(),
+        );
+        assert_eq!(expr.to_string(), "(u64)*&x");
+    }
+
+    #[test]
+    fn test_number_arithmetic() {
+        let int = Number::Int;
+        let uint = Number::Uint;
+
+        assert_eq!(int(2).binary(BinaryOp::Add, uint(3)).unwrap(), int(5));
+        assert_eq!(uint(2).binary(BinaryOp::Mul, uint(3)).unwrap(), uint(6));
+        assert_eq!(uint(2).binary(BinaryOp::Sub, uint(3)).unwrap(), int(-1));
+        assert_eq!(
+            int(3).binary(BinaryOp::Div, Number::Float(2.0)).unwrap(),
+            Number::Float(1.5)
+        );
+        assert!(int(1).binary(BinaryOp::Div, int(0)).is_err());
+        assert!(Number::Bool(true).binary(BinaryOp::Add, int(1)).is_err());
+        assert_eq!(
+            int(-1).binary(BinaryOp::Lt, uint(1)).unwrap(),
+            Number::Bool(true)
+        );
+        assert_eq!(
+            uint(0).binary(BinaryOp::Or, Number::Bool(true)).unwrap(),
+            Number::Bool(true)
+        );
+        assert_eq!(uint(1).unary(UnaryOp::Neg).unwrap(), int(-1));
+        assert_eq!(int(0).unary(UnaryOp::Not).unwrap(), Number::Bool(true));
+    }
+
+    #[test]
+    fn test_number_cast() {
+        assert_eq!(
+            Number::Int(-1).cast("u8").unwrap(),
+            SupportedScalar::U8(255)
+        );
+        assert_eq!(
+            Number::Float(2.7).cast("i32").unwrap(),
+            SupportedScalar::I32(2)
+        );
+        assert_eq!(
+            Number::Uint(1).cast("f64").unwrap(),
+            SupportedScalar::F64(1.0)
+        );
+        assert!(Number::Int(1).cast("String").is_err());
+    }
+}
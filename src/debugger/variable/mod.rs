@@ -3,6 +3,8 @@ use crate::debugger::debugee::dwarf::r#type::{
 };
 use crate::debugger::debugee::dwarf::r#type::{ComplexType, TypeDeclaration};
 use crate::debugger::debugee::dwarf::{AsAllocatedData, ContextualDieRef, NamespaceHierarchy};
+use crate::debugger::progress::Cancelled;
+use crate::debugger::variable::pretty::{PrettyPrinterContext, PrettyPrinterRegistry, PrettyValue};
 use crate::debugger::variable::render::RenderRepr;
 use crate::debugger::variable::specialization::{
     HashSetVariable, StrVariable, StringVariable, VariableParserExtension,
@@ -14,17 +16,22 @@ use gimli::{
     DW_ATE_unsigned, DW_ATE_unsigned_char, DW_ATE_ASCII, DW_ATE_UTF,
 };
 use log::warn;
+use std::cell::Cell;
 use std::collections::{HashMap, VecDeque};
 use std::fmt::{Debug, Display, Formatter};
+use std::path::PathBuf;
 use std::string::FromUtf8Error;
 use uuid::Uuid;
 
+pub mod expr;
+pub mod pretty;
 pub mod render;
 pub mod select;
 mod specialization;
+pub(super) mod write;
 
 use crate::debugger::variable::select::{Literal, LiteralOrWildcard};
-pub use specialization::SpecializedVariableIR;
+pub use specialization::{RefCounts, SpecializedVariableIR};
 
 #[derive(Debug, thiserror::Error, PartialEq)]
 pub enum AssumeError {
@@ -60,6 +67,8 @@ pub enum ParsingError {
     UnsupportedVersion,
     #[error("error while reading from debugee memory: {0}")]
     ReadDebugeeMemory(#[from] nix::Error),
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
 }
 
 /// Identifier of debugee variables.
@@ -189,7 +198,7 @@ pub struct ScalarVariable {
 }
 
 impl ScalarVariable {
-    fn try_as_number(&self) -> Option<i64> {
+    pub(crate) fn try_as_number(&self) -> Option<i64> {
         match self.value {
             Some(SupportedScalar::I8(num)) => Some(num as i64),
             Some(SupportedScalar::I16(num)) => Some(num as i64),
@@ -204,6 +213,14 @@ impl ScalarVariable {
             _ => None,
         }
     }
+
+    /// Return true if scalar is a `true` boolean or a non-zero integer.
+    pub(crate) fn is_truthy(&self) -> bool {
+        match self.value {
+            Some(SupportedScalar::Bool(b)) => b,
+            _ => self.try_as_number().map(|num| num != 0).unwrap_or_default(),
+        }
+    }
 }
 
 /// Represents structures.
@@ -256,6 +273,8 @@ pub struct RustEnumVariable {
     pub type_name: Option<String>,
     /// Variable IR representation of selected variant.
     pub value: Option<Box<VariableIR>>,
+    /// Declaration place of selected variant, a suspend point for async fn state machines.
+    pub variant_decl: Option<(PathBuf, u64)>,
 }
 
 /// Raw pointers, references, Box.
@@ -329,8 +348,9 @@ impl PointerVariable {
                 .chunks(deref_size)
                 .enumerate()
                 .map(|(i, chunk)| {
-                    parser.parse_inner(
+                    parser.parse_item(
                         eval_ctx,
+                        i,
                         VariableIdentity::no_namespace(Some(format!("{}", i as i64))),
                         Some(val.slice_ref(chunk)),
                         target_type,
@@ -366,6 +386,24 @@ pub struct CModifiedVariable {
     pub value: Option<Box<VariableIR>>,
 }
 
+/// Value that is not parsed yet, it is deeper than a parsing depth limit or out of
+/// a children limit (see [`ParseLimits`]).
+#[derive(Clone)]
+pub struct LazyVariable {
+    pub identity: VariableIdentity,
+    pub type_name: Option<String>,
+    value: Option<Bytes>,
+    type_id: TypeIdentity,
+}
+
+impl LazyVariable {
+    /// Parse underline value, nested values are parsed according to parser limits.
+    /// Parser must be created for the same type as a parser that produce this variable.
+    pub fn expand(self, eval_ctx: &EvaluationContext, parser: &VariableParser) -> VariableIR {
+        parser.parse_inner(eval_ctx, self.identity, self.value, self.type_id)
+    }
+}
+
 /// Variable intermediate representation.
 #[derive(Clone)]
 pub enum VariableIR {
@@ -378,6 +416,7 @@ pub enum VariableIR {
     Subroutine(SubroutineVariable),
     Specialized(SpecializedVariableIR),
     CModifiedVariable(CModifiedVariable),
+    Lazy(LazyVariable),
 }
 
 // SAFETY: this enum may contain a raw pointers on memory in a debugee process,
@@ -416,7 +455,12 @@ impl VariableIR {
     fn assume_field_as_pointer(&self, field_name: &'static str) -> Result<*const (), AssumeError> {
         self.bfs_iterator()
             .find_map(|child| {
-                if let VariableIR::Pointer(pointer) = child {
+                if let VariableIR::Pointer(pointer)
+                | VariableIR::Specialized(SpecializedVariableIR::CStrPointer {
+                    original: pointer,
+                    ..
+                }) = child
+                {
                     if pointer.identity.name.as_deref()? == field_name {
                         return pointer.value;
                     }
@@ -433,10 +477,20 @@ impl VariableIR {
     ) -> Result<RustEnumVariable, AssumeError> {
         self.bfs_iterator()
             .find_map(|child| {
-                if let VariableIR::RustEnum(r_enum) = child {
-                    if r_enum.identity.name.as_deref()? == field_name {
-                        return Some(r_enum.clone());
-                    }
+                let r_enum = match child {
+                    VariableIR::RustEnum(r_enum)
+                    | VariableIR::Specialized(
+                        SpecializedVariableIR::Option {
+                            original: r_enum, ..
+                        }
+                        | SpecializedVariableIR::Result {
+                            original: r_enum, ..
+                        },
+                    ) => r_enum,
+                    _ => return None,
+                };
+                if r_enum.identity.name.as_deref()? == field_name {
+                    return Some(r_enum.clone());
                 }
                 None
             })
@@ -484,9 +538,21 @@ impl VariableIR {
                 SpecializedVariableIR::Rc { original, .. } => &original.identity,
                 SpecializedVariableIR::Arc { original, .. } => &original.identity,
                 SpecializedVariableIR::Uuid { original, .. } => &original.identity,
+                SpecializedVariableIR::Mutex { original, .. } => &original.identity,
+                SpecializedVariableIR::RwLock { original, .. } => &original.identity,
+                SpecializedVariableIR::Atomic { original, .. } => &original.identity,
+                SpecializedVariableIR::Option { original, .. } => &original.identity,
+                SpecializedVariableIR::Result { original, .. } => &original.identity,
+                SpecializedVariableIR::Duration { original, .. } => &original.identity,
+                SpecializedVariableIR::SystemTime { original, .. } => &original.identity,
+                SpecializedVariableIR::CString { original, .. } => &original.identity,
+                SpecializedVariableIR::OsString { original, .. } => &original.identity,
+                SpecializedVariableIR::CStrPointer { original, .. } => &original.identity,
+                SpecializedVariableIR::Custom { original, .. } => &original.identity,
             },
             VariableIR::Subroutine(s) => &s.identity,
             VariableIR::CModifiedVariable(v) => &v.identity,
+            VariableIR::Lazy(l) => &l.identity,
         }
     }
 
@@ -513,9 +579,29 @@ impl VariableIR {
                 SpecializedVariableIR::Rc { original, .. } => &mut original.identity,
                 SpecializedVariableIR::Arc { original, .. } => &mut original.identity,
                 SpecializedVariableIR::Uuid { original, .. } => &mut original.identity,
+                SpecializedVariableIR::Mutex { original, .. } => &mut original.identity,
+                SpecializedVariableIR::RwLock { original, .. } => &mut original.identity,
+                SpecializedVariableIR::Atomic { original, .. } => &mut original.identity,
+                SpecializedVariableIR::Option { original, .. } => &mut original.identity,
+                SpecializedVariableIR::Result { original, .. } => &mut original.identity,
+                SpecializedVariableIR::Duration { original, .. } => &mut original.identity,
+                SpecializedVariableIR::SystemTime { original, .. } => &mut original.identity,
+                SpecializedVariableIR::CString { original, .. } => &mut original.identity,
+                SpecializedVariableIR::OsString { original, .. } => &mut original.identity,
+                SpecializedVariableIR::CStrPointer { original, .. } => &mut original.identity,
+                SpecializedVariableIR::Custom { original, .. } => &mut original.identity,
             },
             VariableIR::Subroutine(s) => &mut s.identity,
             VariableIR::CModifiedVariable(v) => &mut v.identity,
+            VariableIR::Lazy(l) => &mut l.identity,
+        }
+    }
+
+    /// Parse a lazy variable, other variables are returned as is.
+    fn expand(self, eval_ctx: &EvaluationContext, variable_parser: &VariableParser) -> Self {
+        match self {
+            VariableIR::Lazy(lazy) => lazy.expand(eval_ctx, variable_parser),
+            _ => self,
         }
     }
 
@@ -531,6 +617,16 @@ impl VariableIR {
             | VariableIR::Specialized(SpecializedVariableIR::Arc { value, .. }) => {
                 value.and_then(|var| var.deref(eval_ctx, variable_parser))
             }
+            VariableIR::Specialized(SpecializedVariableIR::Option { original, .. })
+            | VariableIR::Specialized(SpecializedVariableIR::Result { original, .. }) => {
+                VariableIR::RustEnum(original).deref(eval_ctx, variable_parser)
+            }
+            VariableIR::Specialized(SpecializedVariableIR::Atomic { value, .. }) => {
+                value.and_then(|var| var.deref(eval_ctx, variable_parser))
+            }
+            VariableIR::Specialized(SpecializedVariableIR::CStrPointer { original, .. }) => {
+                original.deref(eval_ctx, variable_parser)
+            }
             VariableIR::Specialized(SpecializedVariableIR::Tls { tls_var, .. }) => tls_var
                 .and_then(|var| {
                     var.inner_value
@@ -541,7 +637,8 @@ impl VariableIR {
     }
 
     /// Return variable field, `None` if get field is not allowed for variable type.
-    /// Supported: structures, rust-style enums, hashmaps, btree-maps.
+    /// Supported: structures, rust-style enums, options, results, locks, atomics, hashmaps,
+    /// btree-maps.
     fn field(self, field_name: &str) -> Option<Self> {
         match self {
             VariableIR::Struct(structure) => structure
@@ -573,9 +670,29 @@ impl VariableIR {
                 SpecializedVariableIR::Tls { tls_var, .. } => tls_var
                     .and_then(|var| var.inner_value.and_then(|inner| inner.field(field_name))),
                 SpecializedVariableIR::Cell { value, .. }
-                | SpecializedVariableIR::RefCell { value, .. } => {
+                | SpecializedVariableIR::RefCell { value, .. }
+                | SpecializedVariableIR::Mutex { value, .. }
+                | SpecializedVariableIR::RwLock { value, .. } => {
                     value.and_then(|var| var.field(field_name))
                 }
+                SpecializedVariableIR::Atomic { original, .. } => {
+                    VariableIR::Struct(original).field(field_name)
+                }
+                // fields of a payload are available directly (`opt.field` like `opt.0.field`)
+                SpecializedVariableIR::Option { value, original } => {
+                    let payload = value.flatten();
+                    VariableIR::RustEnum(original)
+                        .field(field_name)
+                        .or_else(|| payload.and_then(|var| var.field(field_name)))
+                }
+                SpecializedVariableIR::Result { value, original } => {
+                    let payload = value.map(|res| match res {
+                        Ok(var) | Err(var) => var,
+                    });
+                    VariableIR::RustEnum(original)
+                        .field(field_name)
+                        .or_else(|| payload.and_then(|var| var.field(field_name)))
+                }
                 _ => None,
             },
             _ => None,
@@ -609,6 +726,10 @@ impl VariableIR {
                 | SpecializedVariableIR::RefCell { value, .. } => {
                     value.and_then(|var| var.index(idx))
                 }
+                SpecializedVariableIR::Option { original, .. }
+                | SpecializedVariableIR::Result { original, .. } => {
+                    VariableIR::RustEnum(original).index(idx)
+                }
                 SpecializedVariableIR::BTreeMap { map: Some(map), .. }
                 | SpecializedVariableIR::HashMap { map: Some(map), .. } => {
                     for (k, mut v) in map.kv_items {
@@ -662,6 +783,11 @@ impl VariableIR {
                     let right = right?;
                     ptr.slice(eval_ctx, variable_parser, left, right)
                 }
+                SpecializedVariableIR::CStrPointer { original, .. } => {
+                    // for pointer the right bound must always be specified
+                    let right = right?;
+                    original.slice(eval_ctx, variable_parser, left, right)
+                }
                 SpecializedVariableIR::Vector { vec, .. }
                 | SpecializedVariableIR::VecDeque { vec, .. } => {
                     let vec = vec.as_mut()?;
@@ -784,19 +910,46 @@ impl VariableIR {
                     string: Some(StrVariable { value, .. }),
                     ..
                 } => literal.equal_with_string(&value),
+                SpecializedVariableIR::CString {
+                    string: Some(StringVariable { value, .. }),
+                    ..
+                }
+                | SpecializedVariableIR::OsString {
+                    string: Some(StringVariable { value, .. }),
+                    ..
+                } => literal.equal_with_string(&value),
+                SpecializedVariableIR::CStrPointer { string, original } => match string {
+                    Some(StringVariable { value, .. })
+                        if !matches!(literal, Literal::Address(_)) =>
+                    {
+                        literal.equal_with_string(&value)
+                    }
+                    _ => VariableIR::Pointer(original).match_literal(literal),
+                },
                 SpecializedVariableIR::Uuid {
                     value: Some(bytes), ..
                 } => {
                     let uuid = Uuid::from_bytes(bytes);
                     literal.equal_with_string(&uuid.to_string())
                 }
+                SpecializedVariableIR::Custom {
+                    value: Some(value), ..
+                } => match value {
+                    PrettyValue::Text(text) => literal.equal_with_string(&text),
+                    PrettyValue::Variable(var) => var.match_literal(literal),
+                },
                 SpecializedVariableIR::Cell { mut value, .. }
-                | SpecializedVariableIR::RefCell { mut value, .. } => {
+                | SpecializedVariableIR::RefCell { mut value, .. }
+                | SpecializedVariableIR::Atomic { mut value, .. } => {
                     let Some(inner) = value.take() else {
                         return false;
                     };
                     inner.match_literal(literal)
                 }
+                SpecializedVariableIR::Option { original, .. }
+                | SpecializedVariableIR::Result { original, .. } => {
+                    VariableIR::RustEnum(original).match_literal(literal)
+                }
                 SpecializedVariableIR::Rc {
                     value:
                         Some(PointerVariable {
@@ -903,13 +1056,122 @@ impl VariableIR {
     }
 }
 
+/// Limits of a variable parsing. Values that are deeper than a depth limit
+/// or out of a children limit are not parsed, [`VariableIR::Lazy`] is used instead of them.
+/// There are no limits by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ParseLimits {
+    /// Max nesting level of structure members, array items and collection elements.
+    pub max_depth: Option<usize>,
+    /// Max count of parsed array and vector items.
+    pub max_children: Option<usize>,
+}
+
 pub struct VariableParser<'a> {
     r#type: &'a ComplexType,
+    printers: Option<&'a PrettyPrinterRegistry>,
+    limits: ParseLimits,
+    /// Nesting level of a currently parsed value.
+    depth: Cell<usize>,
+    /// True when internals of specialized types are parsed, limits aren't applied in this case.
+    unlimited: Cell<bool>,
 }
 
 impl<'a> VariableParser<'a> {
     pub fn new(r#type: &'a ComplexType) -> Self {
-        Self { r#type }
+        Self {
+            r#type,
+            printers: None,
+            limits: ParseLimits::default(),
+            depth: Cell::new(0),
+            unlimited: Cell::new(false),
+        }
+    }
+
+    /// Use pretty-printers for structure types.
+    pub fn with_pretty_printers(self, printers: &'a PrettyPrinterRegistry) -> Self {
+        Self {
+            printers: Some(printers),
+            ..self
+        }
+    }
+
+    /// Set parsing limits.
+    pub fn with_limits(self, limits: ParseLimits) -> Self {
+        Self { limits, ..self }
+    }
+
+    fn lazy(
+        &self,
+        identity: VariableIdentity,
+        value: Option<Bytes>,
+        type_id: TypeIdentity,
+    ) -> VariableIR {
+        VariableIR::Lazy(LazyVariable {
+            identity,
+            type_name: self.r#type.type_name(type_id),
+            value,
+            type_id,
+        })
+    }
+
+    /// Parse a nested value (structure member, array item or collection element).
+    /// Return a lazy variable if value has members and a depth limit is reached.
+    fn parse_child(
+        &self,
+        eval_ctx: &EvaluationContext,
+        identity: VariableIdentity,
+        value: Option<Bytes>,
+        type_id: TypeIdentity,
+    ) -> VariableIR {
+        if self.unlimited.get() {
+            return self.parse_inner(eval_ctx, identity, value, type_id);
+        }
+
+        let depth = self.depth.get();
+        if self.limits.max_depth.is_some_and(|max| depth >= max) {
+            let has_members = !matches!(
+                self.r#type.types.get(&type_id),
+                Some(
+                    TypeDeclaration::Scalar(_)
+                        | TypeDeclaration::CStyleEnum { .. }
+                        | TypeDeclaration::Pointer { .. }
+                        | TypeDeclaration::Subroutine { .. }
+                )
+            );
+            if has_members {
+                return self.lazy(identity, value, type_id);
+            }
+        }
+
+        self.depth.set(depth + 1);
+        let var = self.parse_inner(eval_ctx, identity, value, type_id);
+        self.depth.set(depth);
+        var
+    }
+
+    /// Same as [`VariableParser::parse_child`] but for an array or vector item,
+    /// items out of a children limit are not parsed.
+    fn parse_item(
+        &self,
+        eval_ctx: &EvaluationContext,
+        index: usize,
+        identity: VariableIdentity,
+        value: Option<Bytes>,
+        type_id: TypeIdentity,
+    ) -> VariableIR {
+        if !self.unlimited.get() && self.limits.max_children.is_some_and(|max| index >= max) {
+            return self.lazy(identity, value, type_id);
+        }
+        self.parse_child(eval_ctx, identity, value, type_id)
+    }
+
+    /// Parse a value without limits.
+    fn parse_unlimited<T>(&self, parse: impl FnOnce() -> T) -> T {
+        let unlimited = self.unlimited.replace(true);
+        let result = parse();
+        self.unlimited.set(unlimited);
+        result
     }
 
     fn parse_scalar(
@@ -1007,7 +1269,11 @@ impl<'a> VariableParser<'a> {
     ) -> StructVariable {
         let children = members
             .iter()
-            .filter_map(|member| self.parse_struct_member(eval_ctx, member, value.as_ref()))
+            .filter_map(|member| {
+                let (identity, member_val, type_ref) =
+                    self.struct_member_data(eval_ctx, member, value.as_ref())?;
+                Some(self.parse_child(eval_ctx, identity, member_val, type_ref))
+            })
             .collect();
 
         StructVariable {
@@ -1024,6 +1290,18 @@ impl<'a> VariableParser<'a> {
         member: &StructureMember,
         parent_value: Option<&Bytes>,
     ) -> Option<VariableIR> {
+        let (identity, member_val, type_ref) =
+            self.struct_member_data(eval_ctx, member, parent_value)?;
+        Some(self.parse_inner(eval_ctx, identity, member_val, type_ref))
+    }
+
+    /// Return identity, value and type of structure member.
+    fn struct_member_data(
+        &self,
+        eval_ctx: &EvaluationContext,
+        member: &StructureMember,
+        parent_value: Option<&Bytes>,
+    ) -> Option<(VariableIdentity, Option<Bytes>, TypeIdentity)> {
         let name = member.name.clone();
         let Some(type_ref) = member.type_ref else {
             warn!(
@@ -1035,8 +1313,7 @@ impl<'a> VariableParser<'a> {
         let member_val =
             parent_value.and_then(|val| member.value(eval_ctx, self.r#type, val.as_ptr() as usize));
 
-        Some(self.parse_inner(
-            eval_ctx,
+        Some((
             VariableIdentity::no_namespace(member.name.clone()),
             member_val,
             type_ref,
@@ -1071,8 +1348,9 @@ impl<'a> VariableParser<'a> {
             Some(
                 raw_items_iter
                     .map(|(i, chunk)| {
-                        self.parse_inner(
+                        self.parse_item(
                             eval_ctx,
+                            i,
                             VariableIdentity::no_namespace(Some(format!(
                                 "{index}",
                                 index = bounds.0 + i as i64
@@ -1145,6 +1423,7 @@ impl<'a> VariableParser<'a> {
         let enumerator =
             discr_value.and_then(|v| enumerators.get(&Some(v)).or_else(|| enumerators.get(&None)));
 
+        let variant_decl = enumerator.and_then(|member| member.decl.clone());
         let enumerator = enumerator.and_then(|member| {
             Some(Box::new(self.parse_struct_member(
                 eval_ctx,
@@ -1157,6 +1436,7 @@ impl<'a> VariableParser<'a> {
             identity,
             type_name,
             value: enumerator,
+            variant_decl,
         }
     }
 
@@ -1182,6 +1462,23 @@ impl<'a> VariableParser<'a> {
         }
     }
 
+    /// Return `true` if pointer is a raw pointer to `c_char` (`*const i8`, `*mut i8` or C `char *`).
+    fn is_c_char_pointer(&self, eval_ctx: &EvaluationContext, pointer: &PointerVariable) -> bool {
+        let Some(target_type) = pointer.target_type else {
+            return false;
+        };
+        // references (`&i8`) point to a single value
+        let is_raw = pointer
+            .type_name
+            .as_deref()
+            .is_some_and(|name| !name.starts_with('&'));
+        let is_char = self
+            .r#type
+            .type_name(target_type)
+            .is_some_and(|name| matches!(name.as_str(), "i8" | "char" | "const char"));
+        is_raw && is_char && self.r#type.type_size_in_bytes(eval_ctx, target_type) == Some(1)
+    }
+
     fn parse_inner(
         &self,
         eval_ctx: &EvaluationContext,
@@ -1202,14 +1499,34 @@ impl<'a> VariableParser<'a> {
                 name: struct_name,
                 ..
             } => {
-                let struct_var = self.parse_struct_variable(
-                    eval_ctx,
-                    identity,
-                    value,
-                    type_name,
-                    type_params.clone(),
-                    members,
-                );
+                // specialized parsers need a whole structure, so structure internals
+                // are parsed without limits
+                let parse_struct = |internals: bool| {
+                    let parse = || {
+                        self.parse_struct_variable(
+                            eval_ctx,
+                            identity,
+                            value,
+                            type_name,
+                            type_params.clone(),
+                            members,
+                        )
+                    };
+                    if internals {
+                        self.parse_unlimited(parse)
+                    } else {
+                        parse()
+                    }
+                };
+
+                let printer = self
+                    .printers
+                    .zip(struct_name.as_deref())
+                    .and_then(|(printers, name)| printers.find(name, type_ns_h));
+                if let Some(printer) = printer {
+                    let ctx = PrettyPrinterContext::new(eval_ctx, self);
+                    return VariableIR::Specialized(printer.render(&ctx, parse_struct(true)));
+                }
 
                 let parser_ext = VariableParserExtension::new(self);
                 // Reinterpret structure if underline data type is:
@@ -1224,12 +1541,21 @@ impl<'a> VariableParser<'a> {
                 // - vecdeque
                 // - cell/refcell
                 // - rc/arc
+                // - uuid
+                // - mutex/rwlock
+                // - atomics
+                // - duration/system time
+                // - c string/os string/path buf
                 if struct_name.as_deref() == Some("&str") {
-                    return VariableIR::Specialized(parser_ext.parse_str(eval_ctx, struct_var));
+                    return VariableIR::Specialized(
+                        parser_ext.parse_str(eval_ctx, parse_struct(true)),
+                    );
                 };
 
                 if struct_name.as_deref() == Some("String") {
-                    return VariableIR::Specialized(parser_ext.parse_string(eval_ctx, struct_var));
+                    return VariableIR::Specialized(
+                        parser_ext.parse_string(eval_ctx, parse_struct(true)),
+                    );
                 };
 
                 if struct_name.as_ref().map(|name| name.starts_with("Vec")) == Some(true)
@@ -1237,7 +1563,7 @@ impl<'a> VariableParser<'a> {
                 {
                     return VariableIR::Specialized(parser_ext.parse_vector(
                         eval_ctx,
-                        struct_var,
+                        parse_struct(true),
                         type_params,
                     ));
                 };
@@ -1249,19 +1575,25 @@ impl<'a> VariableParser<'a> {
                     (1, 77, 0) ..= (1, u32::MAX, u32::MAX) => type_ns_h.contains(&["std", "sys", "pal", "common", "thread_local", "fast_local"])
                 );
                 if is_tls_type == Some(true) {
-                    return VariableIR::Specialized(parser_ext.parse_tls(struct_var, type_params));
+                    return VariableIR::Specialized(
+                        parser_ext.parse_tls(parse_struct(true), type_params),
+                    );
                 }
 
                 if struct_name.as_ref().map(|name| name.starts_with("HashMap")) == Some(true)
                     && type_ns_h.contains(&["collections", "hash", "map"])
                 {
-                    return VariableIR::Specialized(parser_ext.parse_hashmap(eval_ctx, struct_var));
+                    return VariableIR::Specialized(
+                        parser_ext.parse_hashmap(eval_ctx, parse_struct(true)),
+                    );
                 };
 
                 if struct_name.as_ref().map(|name| name.starts_with("HashSet")) == Some(true)
                     && type_ns_h.contains(&["collections", "hash", "set"])
                 {
-                    return VariableIR::Specialized(parser_ext.parse_hashset(eval_ctx, struct_var));
+                    return VariableIR::Specialized(
+                        parser_ext.parse_hashset(eval_ctx, parse_struct(true)),
+                    );
                 };
 
                 if struct_name
@@ -1272,7 +1604,7 @@ impl<'a> VariableParser<'a> {
                 {
                     return VariableIR::Specialized(parser_ext.parse_btree_map(
                         eval_ctx,
-                        struct_var,
+                        parse_struct(true),
                         type_id,
                         type_params,
                     ));
@@ -1284,7 +1616,7 @@ impl<'a> VariableParser<'a> {
                     == Some(true)
                     && type_ns_h.contains(&["collections", "btree", "set"])
                 {
-                    return VariableIR::Specialized(parser_ext.parse_btree_set(struct_var));
+                    return VariableIR::Specialized(parser_ext.parse_btree_set(parse_struct(true)));
                 };
 
                 if struct_name
@@ -1295,7 +1627,7 @@ impl<'a> VariableParser<'a> {
                 {
                     return VariableIR::Specialized(parser_ext.parse_vec_dequeue(
                         eval_ctx,
-                        struct_var,
+                        parse_struct(true),
                         type_params,
                     ));
                 };
@@ -1303,13 +1635,13 @@ impl<'a> VariableParser<'a> {
                 if struct_name.as_ref().map(|name| name.starts_with("Cell")) == Some(true)
                     && type_ns_h.contains(&["cell"])
                 {
-                    return VariableIR::Specialized(parser_ext.parse_cell(struct_var));
+                    return VariableIR::Specialized(parser_ext.parse_cell(parse_struct(true)));
                 };
 
                 if struct_name.as_ref().map(|name| name.starts_with("RefCell")) == Some(true)
                     && type_ns_h.contains(&["cell"])
                 {
-                    return VariableIR::Specialized(parser_ext.parse_refcell(struct_var));
+                    return VariableIR::Specialized(parser_ext.parse_refcell(parse_struct(true)));
                 };
 
                 if struct_name
@@ -1318,7 +1650,9 @@ impl<'a> VariableParser<'a> {
                     == Some(true)
                     && type_ns_h.contains(&["rc"])
                 {
-                    return VariableIR::Specialized(parser_ext.parse_rc(struct_var));
+                    return VariableIR::Specialized(
+                        parser_ext.parse_rc(eval_ctx, parse_struct(true)),
+                    );
                 };
 
                 if struct_name
@@ -1327,16 +1661,72 @@ impl<'a> VariableParser<'a> {
                     == Some(true)
                     && type_ns_h.contains(&["sync"])
                 {
-                    return VariableIR::Specialized(parser_ext.parse_arc(struct_var));
+                    return VariableIR::Specialized(
+                        parser_ext.parse_arc(eval_ctx, parse_struct(true)),
+                    );
                 };
 
                 if struct_name.as_ref().map(|name| name == "Uuid") == Some(true)
                     && type_ns_h.contains(&["uuid"])
                 {
-                    return VariableIR::Specialized(parser_ext.parse_uuid(struct_var));
+                    return VariableIR::Specialized(parser_ext.parse_uuid(parse_struct(true)));
                 };
 
-                VariableIR::Struct(struct_var)
+                if struct_name.as_ref().map(|name| name.starts_with("Mutex<")) == Some(true)
+                    && type_ns_h.contains(&["std", "sync"])
+                {
+                    return VariableIR::Specialized(parser_ext.parse_mutex(parse_struct(true)));
+                };
+
+                if struct_name.as_ref().map(|name| name.starts_with("RwLock<")) == Some(true)
+                    && type_ns_h.contains(&["std", "sync"])
+                {
+                    return VariableIR::Specialized(parser_ext.parse_rwlock(parse_struct(true)));
+                };
+
+                if struct_name.as_ref().map(|name| name.starts_with("Atomic")) == Some(true)
+                    && type_ns_h.contains(&["sync", "atomic"])
+                {
+                    return VariableIR::Specialized(parser_ext.parse_atomic(parse_struct(true)));
+                };
+
+                if struct_name.as_deref() == Some("Duration")
+                    && type_ns_h.contains(&["core", "time"])
+                {
+                    return VariableIR::Specialized(parser_ext.parse_duration(parse_struct(true)));
+                };
+
+                if struct_name.as_deref() == Some("SystemTime")
+                    && type_ns_h.contains(&["std", "time"])
+                {
+                    return VariableIR::Specialized(
+                        parser_ext.parse_system_time(parse_struct(true)),
+                    );
+                };
+
+                let is_c_string = match struct_name.as_deref() {
+                    Some("CString") => type_ns_h.contains(&["ffi", "c_str"]),
+                    Some(name) => {
+                        name.starts_with('&') && (name == "&CStr" || name.ends_with("::CStr"))
+                    }
+                    None => false,
+                };
+                if is_c_string {
+                    return VariableIR::Specialized(
+                        parser_ext.parse_c_string(eval_ctx, parse_struct(true)),
+                    );
+                };
+
+                if (struct_name.as_deref() == Some("OsString")
+                    && type_ns_h.contains(&["ffi", "os_str"]))
+                    || (struct_name.as_deref() == Some("PathBuf") && type_ns_h.contains(&["path"]))
+                {
+                    return VariableIR::Specialized(
+                        parser_ext.parse_os_string(eval_ctx, parse_struct(true)),
+                    );
+                };
+
+                VariableIR::Struct(parse_struct(false))
             }
             TypeDeclaration::Array(decl) => {
                 VariableIR::Array(self.parse_array(eval_ctx, identity, value, type_name, decl))
@@ -1354,19 +1744,48 @@ impl<'a> VariableParser<'a> {
                 enumerators,
             )),
             TypeDeclaration::RustEnum {
+                namespaces: type_ns_h,
+                name: enum_name,
                 discr_type,
                 enumerators,
                 ..
-            } => VariableIR::RustEnum(self.parse_rust_enum(
-                eval_ctx,
-                identity,
-                value,
-                type_name,
-                discr_type.as_ref().map(|t| t.as_ref()),
-                enumerators,
-            )),
+            } => {
+                let r#enum = self.parse_rust_enum(
+                    eval_ctx,
+                    identity,
+                    value,
+                    type_name,
+                    discr_type.as_ref().map(|t| t.as_ref()),
+                    enumerators,
+                );
+
+                // Reinterpret enum if underline data type is:
+                // - option
+                // - result
+                let parser_ext = VariableParserExtension::new(self);
+                if enum_name.as_ref().map(|name| name.starts_with("Option<")) == Some(true)
+                    && type_ns_h.contains(&["option"])
+                {
+                    return VariableIR::Specialized(parser_ext.parse_option(r#enum));
+                };
+
+                if enum_name.as_ref().map(|name| name.starts_with("Result<")) == Some(true)
+                    && type_ns_h.contains(&["result"])
+                {
+                    return VariableIR::Specialized(parser_ext.parse_result(r#enum));
+                };
+
+                VariableIR::RustEnum(r#enum)
+            }
             TypeDeclaration::Pointer { target_type, .. } => {
-                VariableIR::Pointer(self.parse_pointer(identity, value, type_name, *target_type))
+                let pointer = self.parse_pointer(identity, value, type_name, *target_type);
+                if self.is_c_char_pointer(eval_ctx, &pointer) {
+                    let parser_ext = VariableParserExtension::new(self);
+                    return VariableIR::Specialized(
+                        parser_ext.parse_c_str_pointer(eval_ctx, pointer),
+                    );
+                }
+                VariableIR::Pointer(pointer)
             }
             TypeDeclaration::Union { members, .. } => {
                 let struct_var = self.parse_struct_variable(
@@ -1488,6 +1907,26 @@ impl<'a> Iterator for BfsIterator<'a> {
                 }
                 SpecializedVariableIR::Rc { .. } | SpecializedVariableIR::Arc { .. } => {}
                 SpecializedVariableIR::Uuid { .. } => {}
+                SpecializedVariableIR::Option { original, .. }
+                | SpecializedVariableIR::Result { original, .. } => {
+                    if let Some(enumerator) = original.value.as_ref() {
+                        self.queue.push_back(enumerator)
+                    }
+                }
+                SpecializedVariableIR::Mutex { original, .. }
+                | SpecializedVariableIR::RwLock { original, .. }
+                | SpecializedVariableIR::Atomic { original, .. }
+                | SpecializedVariableIR::Duration { original, .. }
+                | SpecializedVariableIR::SystemTime { original, .. }
+                | SpecializedVariableIR::CString { original, .. }
+                | SpecializedVariableIR::OsString { original, .. }
+                | SpecializedVariableIR::Custom { original, .. } => {
+                    original
+                        .members
+                        .iter()
+                        .for_each(|member| self.queue.push_back(member));
+                }
+                SpecializedVariableIR::CStrPointer { .. } => {}
             },
             _ => {}
         }
@@ -1497,7 +1936,7 @@ impl<'a> Iterator for BfsIterator<'a> {
 }
 
 #[inline(never)]
-fn scalar_from_bytes<T: Copy>(bytes: &Bytes) -> T {
+pub(crate) fn scalar_from_bytes<T: Copy>(bytes: &Bytes) -> T {
     let ptr = bytes.as_ptr();
     unsafe { std::ptr::read_unaligned::<T>(ptr as *const T) }
 }
@@ -1596,6 +2035,7 @@ mod test {
                                         type_name: None,
                                         value: None,
                                     }))),
+                                    variant_decl: None,
                                 }),
                                 VariableIR::Scalar(ScalarVariable {
                                     identity: VariableIdentity::no_namespace(Some(
@@ -1864,6 +2304,7 @@ mod test {
                         })],
                         type_params: Default::default(),
                     }))),
+                    variant_decl: None,
                 }),
                 eq_literal: Literal::EnumVariant(
                     "Variant1".to_string(),
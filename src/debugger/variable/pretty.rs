@@ -0,0 +1,197 @@
+//! User-defined pretty-printers.
+//!
+//! Builtin specializations (vectors, hashmaps, smart pointers, etc.) are known to the parser.
+//! Pretty-printers extend this list without changes in the debugger: a printer matches
+//! a structure type by its name and namespace and renders a parsed structure into
+//! a [`SpecializedVariableIR`] (commonly a [`SpecializedVariableIR::Custom`]).
+
+use crate::debugger;
+use crate::debugger::debugee::dwarf::r#type::{EvaluationContext, TypeIdentity};
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::Ptrace;
+use crate::debugger::variable::{
+    SpecializedVariableIR, StructVariable, VariableIR, VariableIdentity, VariableParser,
+};
+use bytes::Bytes;
+use std::sync::Arc;
+
+/// Pretty-printer of a structure type.
+pub trait PrettyPrinter: Send + Sync {
+    /// Return printer name.
+    fn name(&self) -> &'static str;
+
+    /// Return `true` if printer renders structures of a type.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_name`: structure type name (like `SmallVec<[u8; 4]>`)
+    /// * `namespace`: type namespace (like `["smallvec"]`)
+    fn matches(&self, type_name: &str, namespace: &[String]) -> bool;
+
+    /// Render a structure.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`: access to debugee memory and types
+    /// * `structure`: parsed structure with all members
+    fn render(
+        &self,
+        ctx: &PrettyPrinterContext,
+        structure: StructVariable,
+    ) -> SpecializedVariableIR;
+}
+
+/// Value produced by a pretty-printer.
+#[derive(Clone)]
+pub enum PrettyValue {
+    /// Text representation, like a formatted date.
+    Text(String),
+    /// Another variable, like an array of collection items.
+    Variable(Box<VariableIR>),
+}
+
+/// Access to a debugee state for pretty-printers.
+pub struct PrettyPrinterContext<'a> {
+    eval_ctx: &'a EvaluationContext<'a>,
+    parser: &'a VariableParser<'a>,
+}
+
+impl<'a> PrettyPrinterContext<'a> {
+    pub(super) fn new(eval_ctx: &'a EvaluationContext<'a>, parser: &'a VariableParser<'a>) -> Self {
+        Self { eval_ctx, parser }
+    }
+
+    /// Read debugee memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: address in debugee address space
+    /// * `len`: count of bytes to read
+    pub fn read_memory(&self, addr: usize, len: usize) -> Result<Vec<u8>, Error> {
+        debugger::read_memory_by_pid(self.eval_ctx.expl_ctx.pid_on_focus(), addr, len)
+            .map_err(Ptrace)
+    }
+
+    /// Return a type size in bytes, `None` if size is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_id`: type identity (like a type parameter of a structure)
+    pub fn type_size(&self, type_id: TypeIdentity) -> Option<u64> {
+        self.parser
+            .r#type
+            .type_size_in_bytes(self.eval_ctx, type_id)
+    }
+
+    /// Return a type name.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_id`: type identity (like a type parameter of a structure)
+    pub fn type_name(&self, type_id: TypeIdentity) -> Option<String> {
+        self.parser.r#type.type_name(type_id)
+    }
+
+    /// Parse a value of a type, for example, an element of a collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: variable name
+    /// * `value`: raw value
+    /// * `type_id`: type identity (like a type parameter of a structure)
+    pub fn parse(&self, name: &str, value: Vec<u8>, type_id: TypeIdentity) -> VariableIR {
+        self.parser.parse_child(
+            self.eval_ctx,
+            VariableIdentity::no_namespace(Some(name.to_string())),
+            Some(Bytes::from(value)),
+            type_id,
+        )
+    }
+}
+
+/// Registered pretty-printers. Printers are checked in registration order
+/// and before builtin specializations, the first matched printer is used.
+#[derive(Default, Clone)]
+pub struct PrettyPrinterRegistry {
+    printers: Vec<Arc<dyn PrettyPrinter>>,
+}
+
+impl PrettyPrinterRegistry {
+    /// Add a printer, a printer with the same name is replaced.
+    pub fn register(&mut self, printer: Arc<dyn PrettyPrinter>) {
+        match self
+            .printers
+            .iter()
+            .position(|p| p.name() == printer.name())
+        {
+            Some(idx) => self.printers[idx] = printer,
+            None => self.printers.push(printer),
+        }
+    }
+
+    /// Remove a printer, return `false` if there is no printer with this name.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let len = self.printers.len();
+        self.printers.retain(|p| p.name() != name);
+        len != self.printers.len()
+    }
+
+    /// Return names of registered printers.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.printers.iter().map(|p| p.name())
+    }
+
+    /// Return the first printer that matches a structure type.
+    pub(super) fn find(&self, type_name: &str, namespace: &[String]) -> Option<&dyn PrettyPrinter> {
+        self.printers
+            .iter()
+            .find(|p| p.matches(type_name, namespace))
+            .map(|p| p.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct NamePrinter(&'static str, &'static str);
+
+    impl PrettyPrinter for NamePrinter {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn matches(&self, type_name: &str, namespace: &[String]) -> bool {
+            type_name.starts_with(self.1) && namespace.iter().any(|ns| ns == "lib")
+        }
+
+        fn render(
+            &self,
+            _: &PrettyPrinterContext,
+            structure: StructVariable,
+        ) -> SpecializedVariableIR {
+            SpecializedVariableIR::Custom {
+                value: Some(PrettyValue::Text(self.0.to_string())),
+                original: structure,
+            }
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = PrettyPrinterRegistry::default();
+        registry.register(Arc::new(NamePrinter("date", "DateTime<")));
+        registry.register(Arc::new(NamePrinter("any", "")));
+        registry.register(Arc::new(NamePrinter("date", "Date")));
+
+        let ns = vec!["lib".to_string()];
+        assert_eq!(registry.names().collect::<Vec<_>>(), vec!["date", "any"]);
+        assert_eq!(registry.find("Date", &ns).map(|p| p.name()), Some("date"));
+        assert_eq!(registry.find("Time", &ns).map(|p| p.name()), Some("any"));
+        assert!(registry.find("Date", &[]).is_none());
+
+        assert!(registry.unregister("date"));
+        assert!(!registry.unregister("date"));
+        assert_eq!(registry.find("Date", &ns).map(|p| p.name()), Some("any"));
+    }
+}
@@ -1,5 +1,6 @@
-use crate::debugger::variable::SpecializedVariableIR;
-use crate::debugger::variable::VariableIR;
+use crate::debugger::variable::pretty::PrettyValue;
+use crate::debugger::variable::{RefCounts, SpecializedVariableIR, VariableIR};
+use chrono::{DateTime, SecondsFormat, Utc};
 use std::borrow::Cow;
 use std::fmt::{Debug, Formatter};
 
@@ -46,6 +47,16 @@ impl<'a> Debug for ValueLayout<'a> {
     }
 }
 
+/// Return reference counts of a `Rc` or `Arc` variable.
+pub fn ref_counts(var: &VariableIR) -> Option<RefCounts> {
+    match var {
+        VariableIR::Specialized(
+            SpecializedVariableIR::Rc { counts, .. } | SpecializedVariableIR::Arc { counts, .. },
+        ) => *counts,
+        _ => None,
+    }
+}
+
 pub trait RenderRepr {
     fn name(&self) -> String;
     fn r#type(&self) -> &str;
@@ -102,12 +113,24 @@ impl RenderRepr for VariableIR {
                 SpecializedVariableIR::Rc { original, .. }
                 | SpecializedVariableIR::Arc { original, .. } => &original.type_name,
                 SpecializedVariableIR::Uuid { original, .. } => &original.type_name,
+                SpecializedVariableIR::Mutex { original, .. }
+                | SpecializedVariableIR::RwLock { original, .. }
+                | SpecializedVariableIR::Atomic { original, .. } => &original.type_name,
+                SpecializedVariableIR::Option { original, .. }
+                | SpecializedVariableIR::Result { original, .. } => &original.type_name,
+                SpecializedVariableIR::Duration { original, .. }
+                | SpecializedVariableIR::SystemTime { original, .. } => &original.type_name,
+                SpecializedVariableIR::CString { original, .. }
+                | SpecializedVariableIR::OsString { original, .. } => &original.type_name,
+                SpecializedVariableIR::CStrPointer { original, .. } => &original.type_name,
+                SpecializedVariableIR::Custom { original, .. } => &original.type_name,
             },
             VariableIR::Subroutine(_) => {
                 // currently this line is unreachable cause dereference fn pointer is forbidden
                 &None
             }
             VariableIR::CModifiedVariable(v) => &v.type_name,
+            VariableIR::Lazy(l) => &l.type_name,
         };
         r#type.as_deref().unwrap_or("unknown")
     }
@@ -198,14 +221,21 @@ impl RenderRepr for VariableIR {
                     },
                 },
                 SpecializedVariableIR::Cell { value, original }
-                | SpecializedVariableIR::RefCell { value, original } => match value {
+                | SpecializedVariableIR::RefCell { value, original }
+                | SpecializedVariableIR::Mutex { value, original }
+                | SpecializedVariableIR::RwLock { value, original }
+                | SpecializedVariableIR::Atomic { value, original } => match value {
                     Some(v) => v.value()?,
                     None => ValueLayout::Structure {
                         members: original.members.as_ref(),
                     },
                 },
-                SpecializedVariableIR::Rc { value, original }
-                | SpecializedVariableIR::Arc { value, original } => match value {
+                SpecializedVariableIR::Rc {
+                    value, original, ..
+                }
+                | SpecializedVariableIR::Arc {
+                    value, original, ..
+                } => match value {
                     None => ValueLayout::Structure {
                         members: original.members.as_ref(),
                     },
@@ -223,12 +253,60 @@ impl RenderRepr for VariableIR {
                         ValueLayout::PreRendered(Cow::Owned(uuid.to_string()))
                     }
                 },
+                SpecializedVariableIR::Option { value, original } => match value {
+                    None => ValueLayout::Wrapped(original.value.as_ref()?),
+                    Some(Some(payload)) => ValueLayout::Wrapped(payload),
+                    Some(None) => ValueLayout::PreRendered(Cow::Borrowed("None")),
+                },
+                SpecializedVariableIR::Result { value, original } => match value {
+                    None => ValueLayout::Wrapped(original.value.as_ref()?),
+                    Some(Ok(payload) | Err(payload)) => ValueLayout::Wrapped(payload),
+                },
+                SpecializedVariableIR::Duration { value, original } => match value {
+                    None => ValueLayout::Structure {
+                        members: original.members.as_ref(),
+                    },
+                    Some(duration) => ValueLayout::PreRendered(Cow::Owned(format!("{duration:?}"))),
+                },
+                SpecializedVariableIR::SystemTime { value, original } => match value {
+                    None => ValueLayout::Structure {
+                        members: original.members.as_ref(),
+                    },
+                    Some((secs, nanos)) => {
+                        let time = match DateTime::<Utc>::from_timestamp(*secs, *nanos) {
+                            Some(time) => time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                            None => format!("{secs}.{nanos:09}s since epoch"),
+                        };
+                        ValueLayout::PreRendered(Cow::Owned(time))
+                    }
+                },
+                SpecializedVariableIR::CString { string, original }
+                | SpecializedVariableIR::OsString { string, original } => match string {
+                    None => ValueLayout::Structure {
+                        members: original.members.as_ref(),
+                    },
+                    Some(s) => ValueLayout::PreRendered(Cow::Borrowed(&s.value)),
+                },
+                SpecializedVariableIR::CStrPointer { string, original } => match string {
+                    None => ValueLayout::Referential {
+                        addr: original.value?,
+                    },
+                    Some(s) => ValueLayout::PreRendered(Cow::Borrowed(&s.value)),
+                },
+                SpecializedVariableIR::Custom { value, original } => match value {
+                    None => ValueLayout::Structure {
+                        members: original.members.as_ref(),
+                    },
+                    Some(PrettyValue::Text(text)) => ValueLayout::PreRendered(Cow::Borrowed(text)),
+                    Some(PrettyValue::Variable(var)) => var.value()?,
+                },
             },
             VariableIR::Subroutine(_) => {
                 // currently this line is unreachable a cause dereference fn pointer is forbidden
                 return None;
             }
             VariableIR::CModifiedVariable(v) => ValueLayout::Wrapped(v.value.as_ref()?),
+            VariableIR::Lazy(_) => ValueLayout::PreRendered(Cow::Borrowed("...")),
         };
         Some(value_repr)
     }
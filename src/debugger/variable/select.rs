@@ -6,7 +6,9 @@ use crate::debugger::debugee::dwarf::{
 };
 use crate::debugger::error::Error;
 use crate::debugger::error::Error::FunctionNotFound;
-use crate::debugger::variable::{AssumeError, ParsingError, VariableIR, VariableIdentity};
+use crate::debugger::variable::{
+    write, AssumeError, ParseLimits, ParsingError, VariableIR, VariableIdentity,
+};
 use crate::debugger::Error::TypeNotFound;
 use crate::debugger::{variable, Debugger};
 use crate::{ctx_resolve_unit_call, weak_error};
@@ -14,6 +16,7 @@ use bytes::Bytes;
 use gimli::{Attribute, DebugInfoOffset, UnitOffset};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 
 /// This die not exists in debug information.
 /// It may be used to represent variables that are
@@ -63,12 +66,44 @@ pub enum Literal {
     AssocArray(HashMap<String, LiteralOrWildcard>),
 }
 
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::String(s) => write!(f, "\"{s}\""),
+            Literal::Int(i) => write!(f, "{i}"),
+            Literal::Float(float) => write!(f, "{float:?}"),
+            Literal::Address(addr) => write!(f, "{addr:#X}"),
+            Literal::Bool(b) => write!(f, "{b}"),
+            Literal::EnumVariant(variant, None) => f.write_str(variant),
+            Literal::EnumVariant(variant, Some(value)) => write!(f, "{variant}({value})"),
+            Literal::Array(items) => {
+                let items: Vec<_> = items.iter().map(ToString::to_string).collect();
+                write!(f, "{{{}}}", items.join(", "))
+            }
+            Literal::AssocArray(items) => {
+                let mut items: Vec<_> = items.iter().map(|(k, v)| format!("{k}: {v}")).collect();
+                items.sort();
+                write!(f, "{{{}}}", items.join(", "))
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum LiteralOrWildcard {
     Literal(Literal),
     Wildcard,
 }
 
+impl Display for LiteralOrWildcard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LiteralOrWildcard::Literal(literal) => literal.fmt(f),
+            LiteralOrWildcard::Wildcard => f.write_str("*"),
+        }
+    }
+}
+
 macro_rules! impl_equal {
     ($lhs: expr, $rhs: expr, $lit: path) => {
         if let $lit(lhs) = $lhs {
@@ -129,10 +164,42 @@ impl DQE {
     }
 }
 
+impl Display for DQE {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // dereference has lower priority than postfix operators
+        let operand = |expr: &DQE| match expr {
+            DQE::Deref(_) => format!("({expr})"),
+            _ => expr.to_string(),
+        };
+
+        match self {
+            DQE::Variable(VariableSelector::Name { var_name, .. }) => f.write_str(var_name),
+            DQE::Variable(VariableSelector::Any) => f.write_str("locals"),
+            DQE::PtrCast(addr, type_name) => write!(f, "({type_name}){addr:#X}"),
+            DQE::Field(expr, field) => write!(f, "{}.{field}", operand(expr)),
+            DQE::Index(expr, idx) => write!(f, "{}[{idx}]", operand(expr)),
+            DQE::Slice(expr, from, to) => {
+                let bound = |b: &Option<usize>| b.map(|b| b.to_string()).unwrap_or_default();
+                write!(f, "{}[{}..{}]", operand(expr), bound(from), bound(to))
+            }
+            DQE::Deref(expr) => write!(f, "*{expr}"),
+        }
+    }
+}
+
+/// Location of a variable in debugee memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryLocation {
+    pub address: usize,
+    pub size: usize,
+    pub type_name: Option<String>,
+}
+
 /// Evaluate `Expression` at current breakpoint (for current debugee location).
 pub struct SelectExpressionEvaluator<'a> {
     debugger: &'a Debugger,
     expression: DQE,
+    limits: ParseLimits,
 }
 
 macro_rules! type_from_cache {
@@ -155,9 +222,16 @@ impl<'a> SelectExpressionEvaluator<'a> {
         Self {
             debugger,
             expression,
+            limits: ParseLimits::default(),
         }
     }
 
+    /// Set variable parsing limits. Lazy variables on the path of a select expression
+    /// are expanded, so an expression like `var.field` may be used to expand `field`.
+    pub fn with_limits(self, limits: ParseLimits) -> Self {
+        Self { limits, ..self }
+    }
+
     fn extract_variable_by_selector(
         &self,
         selector: &VariableSelector,
@@ -246,6 +320,79 @@ impl<'a> SelectExpressionEvaluator<'a> {
         }
     }
 
+    /// Evaluate memory locations of variables, variables that aren't located
+    /// in memory are skipped. Only filter expression supported.
+    ///
+    /// # Panics
+    /// This method will panic if select expression contain any operators excluding a variable selector.
+    pub fn evaluate_memory_locations(&self) -> Result<Vec<MemoryLocation>, Error> {
+        match &self.expression {
+            DQE::Variable(selector) => {
+                let vars = self.extract_variable_by_selector(selector)?;
+                let mut type_cache = self.debugger.type_cache.borrow_mut();
+
+                Ok(vars
+                    .iter()
+                    .filter_map(|var| {
+                        let r#type = weak_error!(type_from_cache!(var, type_cache))?;
+                        let (address, size) = var.memory_location(
+                            self.debugger.exploration_ctx(),
+                            &self.debugger.debugee,
+                            r#type,
+                        )?;
+                        Some(MemoryLocation {
+                            address,
+                            size,
+                            type_name: r#type.type_name(r#type.root),
+                        })
+                    })
+                    .collect())
+            }
+            _ => unreachable!("unexpected expression variant"),
+        }
+    }
+
+    /// Evaluate memory locations of variables and convert a literal into a binary representation
+    /// of variable types, variables that aren't located in memory are skipped.
+    /// Only filter expression supported.
+    ///
+    /// # Panics
+    /// This method will panic if select expression contain any operators excluding a variable selector.
+    pub fn evaluate_write_data(
+        &self,
+        literal: &Literal,
+    ) -> Result<Vec<(MemoryLocation, Vec<u8>)>, Error> {
+        match &self.expression {
+            DQE::Variable(selector) => {
+                let vars = self.extract_variable_by_selector(selector)?;
+                let mut type_cache = self.debugger.type_cache.borrow_mut();
+
+                let mut result = vec![];
+                for var in &vars {
+                    let Some(r#type) = weak_error!(type_from_cache!(var, type_cache)) else {
+                        continue;
+                    };
+                    let Some((address, size)) = var.memory_location(
+                        self.debugger.exploration_ctx(),
+                        &self.debugger.debugee,
+                        r#type,
+                    ) else {
+                        continue;
+                    };
+                    let data = write::literal_into_bytes(r#type, r#type.root, literal)?;
+                    let location = MemoryLocation {
+                        address,
+                        size,
+                        type_name: r#type.type_name(r#type.root),
+                    };
+                    result.push((location, data));
+                }
+                Ok(result)
+            }
+            _ => unreachable!("unexpected expression variant"),
+        }
+    }
+
     fn evaluate_inner(&self, expression: &DQE) -> Result<Vec<VariableIR>, Error> {
         // evaluate variable one by one in `evaluate_single_variable` method
         // here just filter variables
@@ -368,7 +515,9 @@ impl<'a> SelectExpressionEvaluator<'a> {
         variable_die: &ContextualDieRef<impl AsAllocatedData>,
         r#type: &ComplexType,
     ) -> Option<VariableIR> {
-        let parser = variable::VariableParser::new(r#type);
+        let parser = variable::VariableParser::new(r#type)
+            .with_pretty_printers(&self.debugger.pretty_printers)
+            .with_limits(self.limits);
 
         let evaluator = ctx_resolve_unit_call!(variable_die, evaluator, &self.debugger.debugee);
         let evaluation_context = &dwarf::r#type::EvaluationContext {
@@ -397,10 +546,12 @@ impl<'a> SelectExpressionEvaluator<'a> {
             DQE::Field(expr, field) => {
                 let var = self.evaluate_single_variable(expr, variable_die, r#type)?;
                 var.field(field)
+                    .map(|member| member.expand(evaluation_context, &parser))
             }
             DQE::Index(expr, idx) => {
                 let var = self.evaluate_single_variable(expr, variable_die, r#type)?;
                 var.index(idx)
+                    .map(|item| item.expand(evaluation_context, &parser))
             }
             DQE::Slice(expr, left, right) => {
                 let var = self.evaluate_single_variable(expr, variable_die, r#type)?;
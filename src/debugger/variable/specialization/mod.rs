@@ -2,6 +2,8 @@ mod btree;
 mod hashbrown;
 
 use crate::debugger::debugee::dwarf::r#type::{EvaluationContext, TypeIdentity};
+use crate::debugger::progress::{Cancelled, Task};
+use crate::debugger::variable::pretty::PrettyValue;
 use crate::debugger::variable::render::RenderRepr;
 use crate::debugger::variable::specialization::btree::BTreeReflection;
 use crate::debugger::variable::specialization::hashbrown::HashmapReflection;
@@ -10,15 +12,16 @@ use crate::debugger::variable::AssumeError::{
 };
 use crate::debugger::variable::ParsingError::Assume;
 use crate::debugger::variable::{
-    ArrayVariable, AssumeError, ParsingError, PointerVariable, ScalarVariable, StructVariable,
-    SupportedScalar, VariableIR, VariableIdentity, VariableParser,
+    ArrayVariable, AssumeError, ParsingError, PointerVariable, RustEnumVariable, ScalarVariable,
+    StructVariable, SupportedScalar, VariableIR, VariableIdentity, VariableParser,
 };
 use crate::{debugger, version_switch, weak_error};
-use anyhow::Context;
 use bytes::Bytes;
 use fallible_iterator::FallibleIterator;
 use itertools::Itertools;
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
 use AssumeError::{FieldNotFound, IncompleteInterp, UnknownSize};
 
 /// During program execution, the debugger may encounter uninitialized variables.
@@ -104,6 +107,20 @@ pub struct TlsVariable {
     pub inner_type: Option<String>,
 }
 
+/// Reference counts of a `Rc` or `Arc` allocation, counted like
+/// `Rc::strong_count` and `Rc::weak_count` do.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RefCounts {
+    pub strong: usize,
+    pub weak: usize,
+}
+
+impl Display for RefCounts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "strong: {}, weak: {}", self.strong, self.weak)
+    }
+}
+
 #[derive(Clone)]
 pub enum SpecializedVariableIR {
     Vector {
@@ -152,16 +169,74 @@ pub enum SpecializedVariableIR {
     },
     Rc {
         value: Option<PointerVariable>,
+        counts: Option<RefCounts>,
         original: StructVariable,
     },
     Arc {
         value: Option<PointerVariable>,
+        counts: Option<RefCounts>,
         original: StructVariable,
     },
     Uuid {
         value: Option<[u8; 16]>,
         original: StructVariable,
     },
+    /// `std::sync::Mutex<T>`, `value` is a structure with a lock state and a locked value.
+    Mutex {
+        value: Option<Box<VariableIR>>,
+        original: StructVariable,
+    },
+    /// `std::sync::RwLock<T>`, `value` is a structure with a lock state and a locked value.
+    RwLock {
+        value: Option<Box<VariableIR>>,
+        original: StructVariable,
+    },
+    /// Atomic types (`AtomicUsize`, `AtomicBool`, `AtomicPtr<T>`, etc.), `value` is a current value.
+    Atomic {
+        value: Option<Box<VariableIR>>,
+        original: StructVariable,
+    },
+    /// `Option<T>`, `value` is a payload of `Some` variant or `None` for `None` variant.
+    Option {
+        value: Option<Option<Box<VariableIR>>>,
+        original: RustEnumVariable,
+    },
+    /// `Result<T, E>`, `value` is a payload of `Ok` or `Err` variant.
+    Result {
+        value: Option<Result<Box<VariableIR>, Box<VariableIR>>>,
+        original: RustEnumVariable,
+    },
+    /// `core::time::Duration`.
+    Duration {
+        value: Option<Duration>,
+        original: StructVariable,
+    },
+    /// `std::time::SystemTime`, `value` is a count of seconds and nanoseconds since unix epoch.
+    SystemTime {
+        value: Option<(i64, u32)>,
+        original: StructVariable,
+    },
+    /// `CString` or `&CStr`, `string` is a lossy UTF-8 representation without a trailing NUL.
+    CString {
+        string: Option<StringVariable>,
+        original: StructVariable,
+    },
+    /// `OsString` or `PathBuf`, `string` is a lossy UTF-8 representation.
+    OsString {
+        string: Option<StringVariable>,
+        original: StructVariable,
+    },
+    /// `*const c_char` or `*mut c_char`, `string` is a NUL-terminated string at pointer address,
+    /// `None` for a null pointer.
+    CStrPointer {
+        string: Option<StringVariable>,
+        original: PointerVariable,
+    },
+    /// Structure rendered by a user-defined pretty-printer.
+    Custom {
+        value: Option<PrettyValue>,
+        original: StructVariable,
+    },
 }
 
 pub struct VariableParserExtension<'a> {
@@ -179,9 +254,10 @@ impl<'a> VariableParserExtension<'a> {
         structure: StructVariable,
     ) -> SpecializedVariableIR {
         SpecializedVariableIR::Str {
-            string: weak_error!(self
-                .parse_str_inner(eval_ctx, VariableIR::Struct(structure.clone()))
-                .context("&str interpretation")),
+            string: weak_error!(
+                self.parse_str_inner(eval_ctx, VariableIR::Struct(structure.clone())),
+                "&str interpretation:"
+            ),
             original: structure,
         }
     }
@@ -215,9 +291,10 @@ impl<'a> VariableParserExtension<'a> {
         structure: StructVariable,
     ) -> SpecializedVariableIR {
         SpecializedVariableIR::String {
-            string: weak_error!(self
-                .parse_string_inner(eval_ctx, VariableIR::Struct(structure.clone()))
-                .context("String interpretation")),
+            string: weak_error!(
+                self.parse_string_inner(eval_ctx, VariableIR::Struct(structure.clone())),
+                "String interpretation:"
+            ),
             original: structure,
         }
     }
@@ -244,6 +321,114 @@ impl<'a> VariableParserExtension<'a> {
         })
     }
 
+    pub fn parse_c_string(
+        &self,
+        eval_ctx: &EvaluationContext,
+        structure: StructVariable,
+    ) -> SpecializedVariableIR {
+        SpecializedVariableIR::CString {
+            string: weak_error!(
+                self.parse_c_string_inner(eval_ctx, VariableIR::Struct(structure.clone())),
+                "CString interpretation:"
+            ),
+            original: structure,
+        }
+    }
+
+    fn parse_c_string_inner(
+        &self,
+        eval_ctx: &EvaluationContext,
+        ir: VariableIR,
+    ) -> Result<StringVariable, ParsingError> {
+        // both `CString` and `&CStr` are fat pointers to bytes with a trailing NUL
+        let len = ir.assume_field_as_scalar_number("length")?;
+        let len = guard_len(len);
+
+        let data_ptr = ir.assume_field_as_pointer("data_ptr")?;
+
+        let mut data = debugger::read_memory_by_pid(
+            eval_ctx.expl_ctx.pid_on_focus(),
+            data_ptr as usize,
+            len as usize,
+        )?;
+        if data.last() == Some(&0) {
+            data.pop();
+        }
+
+        Ok(StringVariable {
+            identity: ir.identity().clone(),
+            value: String::from_utf8_lossy(&data).into_owned(),
+        })
+    }
+
+    pub fn parse_os_string(
+        &self,
+        eval_ctx: &EvaluationContext,
+        structure: StructVariable,
+    ) -> SpecializedVariableIR {
+        SpecializedVariableIR::OsString {
+            string: weak_error!(
+                self.parse_os_string_inner(eval_ctx, VariableIR::Struct(structure.clone())),
+                "OsString interpretation:"
+            ),
+            original: structure,
+        }
+    }
+
+    fn parse_os_string_inner(
+        &self,
+        eval_ctx: &EvaluationContext,
+        ir: VariableIR,
+    ) -> Result<StringVariable, ParsingError> {
+        // we assume that `OsString` (and `PathBuf`) is a wrapper over `Vec<u8>`
+        let len = ir.assume_field_as_scalar_number("len")?;
+        let len = guard_len(len);
+
+        let data_ptr = ir.assume_field_as_pointer("pointer")?;
+
+        let data = debugger::read_memory_by_pid(
+            eval_ctx.expl_ctx.pid_on_focus(),
+            data_ptr as usize,
+            len as usize,
+        )?;
+
+        Ok(StringVariable {
+            identity: ir.identity().clone(),
+            value: String::from_utf8_lossy(&data).into_owned(),
+        })
+    }
+
+    pub fn parse_c_str_pointer(
+        &self,
+        eval_ctx: &EvaluationContext,
+        pointer: PointerVariable,
+    ) -> SpecializedVariableIR {
+        SpecializedVariableIR::CStrPointer {
+            string: weak_error!(
+                self.parse_c_str_pointer_inner(eval_ctx, &pointer),
+                "C string interpretation:"
+            )
+            .flatten(),
+            original: pointer,
+        }
+    }
+
+    fn parse_c_str_pointer_inner(
+        &self,
+        eval_ctx: &EvaluationContext,
+        pointer: &PointerVariable,
+    ) -> Result<Option<StringVariable>, ParsingError> {
+        let Some(ptr) = pointer.value.filter(|ptr| !ptr.is_null()) else {
+            return Ok(None);
+        };
+        let data = read_c_str(eval_ctx, ptr as usize)?;
+
+        Ok(Some(StringVariable {
+            identity: pointer.identity.clone(),
+            value: String::from_utf8_lossy(&data).into_owned(),
+        }))
+    }
+
     pub fn parse_vector(
         &self,
         eval_ctx: &EvaluationContext,
@@ -251,9 +436,14 @@ impl<'a> VariableParserExtension<'a> {
         type_params: &HashMap<String, Option<TypeIdentity>>,
     ) -> SpecializedVariableIR {
         SpecializedVariableIR::Vector {
-            vec: weak_error!(self
-                .parse_vector_inner(eval_ctx, VariableIR::Struct(structure.clone()), type_params)
-                .context("Vec<T> interpretation")),
+            vec: weak_error!(
+                self.parse_vector_inner(
+                    eval_ctx,
+                    VariableIR::Struct(structure.clone()),
+                    type_params
+                ),
+                "Vec<T> interpretation:"
+            ),
             original: structure,
         }
     }
@@ -301,16 +491,19 @@ impl<'a> VariableParserExtension<'a> {
             &mut empty_chunks
         };
 
+        let task = Task::start("parse vector", len as u64);
         let items = raw_items_iter
             .map(|(i, chunk)| {
-                self.parser.parse_inner(
+                task.advance(1)?;
+                Ok(self.parser.parse_item(
                     eval_ctx,
+                    i,
                     VariableIdentity::no_namespace(Some(format!("{}", i as i64))),
                     Some(data.slice_ref(chunk)),
                     inner_type,
-                )
+                ))
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, Cancelled>>()?;
 
         Ok(VecVariable {
             structure: StructVariable {
@@ -343,9 +536,10 @@ impl<'a> VariableParserExtension<'a> {
         type_params: &HashMap<String, Option<TypeIdentity>>,
     ) -> SpecializedVariableIR {
         SpecializedVariableIR::Tls {
-            tls_var: weak_error!(self
-                .parse_tls_inner(VariableIR::Struct(structure.clone()), type_params)
-                .context("TLS variable interpretation")),
+            tls_var: weak_error!(
+                self.parse_tls_inner(VariableIR::Struct(structure.clone()), type_params),
+                "TLS variable interpretation:"
+            ),
             original: structure,
         }
     }
@@ -407,9 +601,10 @@ impl<'a> VariableParserExtension<'a> {
         structure: StructVariable,
     ) -> SpecializedVariableIR {
         SpecializedVariableIR::HashMap {
-            map: weak_error!(self
-                .parse_hashmap_inner(eval_ctx, VariableIR::Struct(structure.clone()))
-                .context("HashMap<K, V> interpretation")),
+            map: weak_error!(
+                self.parse_hashmap_inner(eval_ctx, VariableIR::Struct(structure.clone())),
+                "HashMap<K, V> interpretation:"
+            ),
             original: structure,
         }
     }
@@ -437,10 +632,12 @@ impl<'a> VariableParserExtension<'a> {
         let reflection =
             HashmapReflection::new(ctrl as *mut u8, bucket_mask as usize, kv_size as usize);
 
+        let task = Task::start("parse hashmap", bucket_mask as u64 + 1);
         let iterator = reflection.iter(eval_ctx.expl_ctx.pid_on_focus())?;
         let kv_items = iterator
             .map_err(ParsingError::from)
             .filter_map(|bucket| {
+                task.advance(1)?;
                 let data = bucket.read(eval_ctx.expl_ctx.pid_on_focus());
                 let tuple = self.parser.parse_inner(
                     eval_ctx,
@@ -474,9 +671,10 @@ impl<'a> VariableParserExtension<'a> {
         structure: StructVariable,
     ) -> SpecializedVariableIR {
         SpecializedVariableIR::HashSet {
-            set: weak_error!(self
-                .parse_hashset_inner(eval_ctx, VariableIR::Struct(structure.clone()))
-                .context("HashSet<T> interpretation")),
+            set: weak_error!(
+                self.parse_hashset_inner(eval_ctx, VariableIR::Struct(structure.clone())),
+                "HashSet<T> interpretation:"
+            ),
             original: structure,
         }
     }
@@ -545,14 +743,15 @@ impl<'a> VariableParserExtension<'a> {
         type_params: &HashMap<String, Option<TypeIdentity>>,
     ) -> SpecializedVariableIR {
         SpecializedVariableIR::BTreeMap {
-            map: weak_error!(self
-                .parse_btree_map_inner(
+            map: weak_error!(
+                self.parse_btree_map_inner(
                     eval_ctx,
                     VariableIR::Struct(structure.clone()),
                     identity,
                     type_params
-                )
-                .context("BTreeMap<K, V> interpretation")),
+                ),
+                "BTreeMap<K, V> interpretation:"
+            ),
             original: structure,
         }
     }
@@ -588,14 +787,14 @@ impl<'a> VariableParserExtension<'a> {
         let kv_items = iterator
             .map_err(ParsingError::from)
             .map(|(k, v)| {
-                let key = self.parser.parse_inner(
+                let key = self.parser.parse_child(
                     eval_ctx,
                     VariableIdentity::no_namespace(Some("k".to_string())),
                     Some(Bytes::from(k)),
                     k_type,
                 );
 
-                let value = self.parser.parse_inner(
+                let value = self.parser.parse_child(
                     eval_ctx,
                     VariableIdentity::no_namespace(Some("v".to_string())),
                     Some(Bytes::from(v)),
@@ -615,9 +814,10 @@ impl<'a> VariableParserExtension<'a> {
 
     pub fn parse_btree_set(&self, structure: StructVariable) -> SpecializedVariableIR {
         SpecializedVariableIR::BTreeSet {
-            set: weak_error!(self
-                .parse_btree_set_inner(VariableIR::Struct(structure.clone()))
-                .context("BTreeSet interpretation")),
+            set: weak_error!(
+                self.parse_btree_set_inner(VariableIR::Struct(structure.clone())),
+                "BTreeSet interpretation:"
+            ),
             original: structure,
         }
     }
@@ -653,13 +853,14 @@ impl<'a> VariableParserExtension<'a> {
         type_params: &HashMap<String, Option<TypeIdentity>>,
     ) -> SpecializedVariableIR {
         SpecializedVariableIR::VecDeque {
-            vec: weak_error!(self
-                .parse_vec_dequeue_inner(
+            vec: weak_error!(
+                self.parse_vec_dequeue_inner(
                     eval_ctx,
                     VariableIR::Struct(structure.clone()),
                     type_params
-                )
-                .context("VeqDequeue<T> interpretation")),
+                ),
+                "VeqDequeue<T> interpretation:"
+            ),
             original: structure,
         }
     }
@@ -714,8 +915,9 @@ impl<'a> VariableParserExtension<'a> {
             .enumerate()
             .map(|(i, real_idx)| {
                 let el_data = &data[real_idx * el_type_size..(real_idx + 1) * el_type_size];
-                self.parser.parse_inner(
+                self.parser.parse_item(
                     eval_ctx,
+                    i,
                     VariableIdentity::no_namespace(Some(format!("{}", i as i64))),
                     Some(data.slice_ref(el_data)),
                     inner_type,
@@ -754,9 +956,10 @@ impl<'a> VariableParserExtension<'a> {
 
     pub fn parse_cell(&self, structure: StructVariable) -> SpecializedVariableIR {
         SpecializedVariableIR::Cell {
-            value: weak_error!(self
-                .parse_cell_inner(VariableIR::Struct(structure.clone()))
-                .context("Cell<T> interpretation"))
+            value: weak_error!(
+                self.parse_cell_inner(VariableIR::Struct(structure.clone())),
+                "Cell<T> interpretation:"
+            )
             .map(Box::new),
             original: structure,
         }
@@ -773,9 +976,10 @@ impl<'a> VariableParserExtension<'a> {
 
     pub fn parse_refcell(&self, structure: StructVariable) -> SpecializedVariableIR {
         SpecializedVariableIR::RefCell {
-            value: weak_error!(self
-                .parse_refcell_inner(VariableIR::Struct(structure.clone()))
-                .context("RefCell<T> interpretation"))
+            value: weak_error!(
+                self.parse_refcell_inner(VariableIR::Struct(structure.clone())),
+                "RefCell<T> interpretation:"
+            )
             .map(Box::new),
             original: structure,
         }
@@ -815,11 +1019,135 @@ impl<'a> VariableParserExtension<'a> {
         }))
     }
 
-    pub fn parse_rc(&self, structure: StructVariable) -> SpecializedVariableIR {
+    pub fn parse_mutex(&self, structure: StructVariable) -> SpecializedVariableIR {
+        SpecializedVariableIR::Mutex {
+            value: weak_error!(
+                self.parse_mutex_inner(VariableIR::Struct(structure.clone())),
+                "Mutex<T> interpretation:"
+            )
+            .map(Box::new),
+            original: structure,
+        }
+    }
+
+    fn parse_mutex_inner(&self, ir: VariableIR) -> Result<VariableIR, ParsingError> {
+        // we assume that mutex is futex based, futex value is 0 if mutex is unlocked
+        let futex = ir
+            .bfs_iterator()
+            .find(|child| child.name() == "futex")
+            .ok_or(FieldNotFound("futex"))?;
+        let locked = futex.assume_field_as_scalar_number("value")? != 0;
+
+        Ok(VariableIR::Struct(StructVariable {
+            identity: ir.identity().clone(),
+            type_name: Some(ir.r#type().to_owned()),
+            members: vec![
+                bool_scalar("locked", locked),
+                bool_scalar("poisoned", is_poisoned(&ir)?),
+                lock_data(&ir)?,
+            ],
+            type_params: Default::default(),
+        }))
+    }
+
+    pub fn parse_rwlock(&self, structure: StructVariable) -> SpecializedVariableIR {
+        SpecializedVariableIR::RwLock {
+            value: weak_error!(
+                self.parse_rwlock_inner(VariableIR::Struct(structure.clone())),
+                "RwLock<T> interpretation:"
+            )
+            .map(Box::new),
+            original: structure,
+        }
+    }
+
+    fn parse_rwlock_inner(&self, ir: VariableIR) -> Result<VariableIR, ParsingError> {
+        // we assume that rwlock is futex based, lower 30 bits of a state is a count of readers,
+        // all these bits are set if rwlock is write locked
+        const MASK: i64 = (1 << 30) - 1;
+        let state = ir
+            .bfs_iterator()
+            .find(|child| child.name() == "state")
+            .ok_or(FieldNotFound("state"))?;
+        let lock_bits = state.assume_field_as_scalar_number("value")? & MASK;
+        let write_locked = lock_bits == MASK;
+        let readers = if write_locked { 0 } else { lock_bits as u32 };
+
+        Ok(VariableIR::Struct(StructVariable {
+            identity: ir.identity().clone(),
+            type_name: Some(ir.r#type().to_owned()),
+            members: vec![
+                VariableIR::Scalar(ScalarVariable {
+                    identity: VariableIdentity::no_namespace(Some("readers".to_string())),
+                    type_name: Some("u32".to_owned()),
+                    value: Some(SupportedScalar::U32(readers)),
+                }),
+                bool_scalar("write_locked", write_locked),
+                bool_scalar("poisoned", is_poisoned(&ir)?),
+                lock_data(&ir)?,
+            ],
+            type_params: Default::default(),
+        }))
+    }
+
+    pub fn parse_atomic(&self, structure: StructVariable) -> SpecializedVariableIR {
+        SpecializedVariableIR::Atomic {
+            value: weak_error!(
+                self.parse_atomic_inner(&structure),
+                "Atomic interpretation:"
+            )
+            .map(Box::new),
+            original: structure,
+        }
+    }
+
+    fn parse_atomic_inner(&self, structure: &StructVariable) -> Result<VariableIR, ParsingError> {
+        let Some(VariableIR::Struct(unsafe_cell)) = structure.members.first() else {
+            return Err(FieldNotFound("v").into());
+        };
+        let value = unsafe_cell
+            .members
+            .first()
+            .ok_or(IncompleteInterp("UnsafeCell"))?;
+
+        // `AtomicBool` is represented as an `u8` in dwarf
+        if structure.type_name.as_deref() == Some("AtomicBool") {
+            if let VariableIR::Scalar(ScalarVariable {
+                identity,
+                value: Some(SupportedScalar::U8(byte)),
+                ..
+            }) = value
+            {
+                return Ok(VariableIR::Scalar(ScalarVariable {
+                    identity: identity.clone(),
+                    type_name: Some("bool".to_owned()),
+                    value: Some(SupportedScalar::Bool(*byte != 0)),
+                }));
+            }
+        }
+
+        Ok(value.clone())
+    }
+
+    pub fn parse_rc(
+        &self,
+        eval_ctx: &EvaluationContext,
+        structure: StructVariable,
+    ) -> SpecializedVariableIR {
+        let value = weak_error!(
+            self.parse_rc_inner(VariableIR::Struct(structure.clone())),
+            "Rc<T> interpretation:"
+        );
+        let counts = value.as_ref().and_then(|pointer| {
+            weak_error!(
+                read_ref_counts(eval_ctx, pointer),
+                "Rc<T> reference counts:"
+            )
+            .flatten()
+        });
         SpecializedVariableIR::Rc {
-            value: weak_error!(self
-                .parse_rc_inner(VariableIR::Struct(structure.clone()))
-                .context("Rc<T> interpretation")),
+            value,
+            counts,
             original: structure,
         }
     }
@@ -840,11 +1168,25 @@ impl<'a> VariableParserExtension<'a> {
             .ok_or(IncompleteInterp("rc"))?)
     }
 
-    pub fn parse_arc(&self, structure: StructVariable) -> SpecializedVariableIR {
+    pub fn parse_arc(
+        &self,
+        eval_ctx: &EvaluationContext,
+        structure: StructVariable,
+    ) -> SpecializedVariableIR {
+        let value = weak_error!(
+            self.parse_arc_inner(VariableIR::Struct(structure.clone())),
+            "Arc<T> interpretation:"
+        );
+        let counts = value.as_ref().and_then(|pointer| {
+            weak_error!(
+                read_ref_counts(eval_ctx, pointer),
+                "Arc<T> reference counts:"
+            )
+            .flatten()
+        });
         SpecializedVariableIR::Arc {
-            value: weak_error!(self
-                .parse_arc_inner(VariableIR::Struct(structure.clone()))
-                .context("Arc<T> interpretation")),
+            value,
+            counts,
             original: structure,
         }
     }
@@ -867,9 +1209,7 @@ impl<'a> VariableParserExtension<'a> {
 
     pub fn parse_uuid(&self, structure: StructVariable) -> SpecializedVariableIR {
         SpecializedVariableIR::Uuid {
-            value: weak_error!(self
-                .parse_uuid_inner(&structure)
-                .context("Uuid interpretation")),
+            value: weak_error!(self.parse_uuid_inner(&structure), "Uuid interpretation:"),
             original: structure,
         }
     }
@@ -901,6 +1241,217 @@ impl<'a> VariableParserExtension<'a> {
 
         Ok(bytes_repr)
     }
+
+    pub fn parse_option(&self, r#enum: RustEnumVariable) -> SpecializedVariableIR {
+        SpecializedVariableIR::Option {
+            value: weak_error!(self.parse_option_inner(&r#enum), "Option interpretation:"),
+            original: r#enum,
+        }
+    }
+
+    fn parse_option_inner(
+        &self,
+        r#enum: &RustEnumVariable,
+    ) -> Result<Option<Box<VariableIR>>, ParsingError> {
+        match variant_payload(r#enum)? {
+            ("None", None) => Ok(None),
+            ("Some", Some(payload)) => Ok(Some(Box::new(payload))),
+            _ => Err(UnexpectedType("unknown option variant").into()),
+        }
+    }
+
+    pub fn parse_result(&self, r#enum: RustEnumVariable) -> SpecializedVariableIR {
+        SpecializedVariableIR::Result {
+            value: weak_error!(self.parse_result_inner(&r#enum), "Result interpretation:"),
+            original: r#enum,
+        }
+    }
+
+    fn parse_result_inner(
+        &self,
+        r#enum: &RustEnumVariable,
+    ) -> Result<Result<Box<VariableIR>, Box<VariableIR>>, ParsingError> {
+        match variant_payload(r#enum)? {
+            ("Ok", Some(payload)) => Ok(Ok(Box::new(payload))),
+            ("Err", Some(payload)) => Ok(Err(Box::new(payload))),
+            _ => Err(UnexpectedType("unknown result variant").into()),
+        }
+    }
+
+    pub fn parse_duration(&self, structure: StructVariable) -> SpecializedVariableIR {
+        SpecializedVariableIR::Duration {
+            value: weak_error!(
+                self.parse_duration_inner(structure.clone()),
+                "Duration interpretation:"
+            ),
+            original: structure,
+        }
+    }
+
+    fn parse_duration_inner(&self, structure: StructVariable) -> Result<Duration, ParsingError> {
+        let ir = VariableIR::Struct(structure);
+        let secs = ir.assume_field_as_scalar_number("secs")?;
+        let nanos = assume_field_as_newtype_number(&ir, "nanos")?;
+        Ok(Duration::new(secs as u64, nanos as u32))
+    }
+
+    pub fn parse_system_time(&self, structure: StructVariable) -> SpecializedVariableIR {
+        SpecializedVariableIR::SystemTime {
+            value: weak_error!(
+                self.parse_system_time_inner(structure.clone()),
+                "SystemTime interpretation:"
+            ),
+            original: structure,
+        }
+    }
+
+    fn parse_system_time_inner(
+        &self,
+        structure: StructVariable,
+    ) -> Result<(i64, u32), ParsingError> {
+        // we assume that system time is represented as a `timespec` structure
+        let ir = VariableIR::Struct(structure);
+        let secs = ir.assume_field_as_scalar_number("tv_sec")?;
+        let nanos = assume_field_as_newtype_number(&ir, "tv_nsec")?;
+        Ok((secs, nanos as u32))
+    }
+}
+
+/// Read bytes of a NUL-terminated string (without the NUL), at most [`LEN_GUARD`] bytes are read.
+/// Memory is read in small chunks that never cross a page boundary, so a string placed
+/// at the end of a mapped region is read too.
+///
+/// # Arguments
+///
+/// * `eval_ctx`: evaluation context
+/// * `addr`: string address
+fn read_c_str(eval_ctx: &EvaluationContext, mut addr: usize) -> Result<Vec<u8>, ParsingError> {
+    const CHUNK_SIZE: usize = 256;
+    const PAGE_SIZE: usize = 4096;
+
+    let limit = LEN_GUARD as usize;
+    let mut data = vec![];
+    while data.len() < limit {
+        let chunk_len = CHUNK_SIZE
+            .min(PAGE_SIZE - addr % PAGE_SIZE)
+            .min(limit - data.len());
+        let chunk =
+            debugger::read_memory_by_pid(eval_ctx.expl_ctx.pid_on_focus(), addr, chunk_len)?;
+        if let Some(nul_pos) = chunk.iter().position(|&b| b == 0) {
+            data.extend_from_slice(&chunk[..nul_pos]);
+            break;
+        }
+        data.extend_from_slice(&chunk);
+        addr += chunk_len;
+    }
+    Ok(data)
+}
+
+/// Read reference counts from a `RcBox` (or `ArcInner`) allocation, strong and weak counters
+/// are the first two fields of both structures. Return `None` for a dangling pointer
+/// (created by `Weak::new`).
+///
+/// # Arguments
+///
+/// * `eval_ctx`: evaluation context
+/// * `pointer`: pointer to a `RcBox` or `ArcInner`
+fn read_ref_counts(
+    eval_ctx: &EvaluationContext,
+    pointer: &PointerVariable,
+) -> Result<Option<RefCounts>, ParsingError> {
+    let Some(ptr) = pointer.value else {
+        return Ok(None);
+    };
+    if ptr as usize == usize::MAX {
+        return Ok(None);
+    }
+
+    const USIZE_LEN: usize = std::mem::size_of::<usize>();
+    let data = debugger::read_memory_by_pid(
+        eval_ctx.expl_ctx.pid_on_focus(),
+        ptr as usize,
+        USIZE_LEN * 2,
+    )?;
+    let strong = usize::from_ne_bytes(data[..USIZE_LEN].try_into().expect("infallible"));
+    let weak = usize::from_ne_bytes(data[USIZE_LEN..].try_into().expect("infallible"));
+
+    // all strong pointers hold an implicit weak reference
+    let weak = if strong == 0 {
+        0
+    } else {
+        weak.saturating_sub(1)
+    };
+    Ok(Some(RefCounts { strong, weak }))
+}
+
+fn bool_scalar(name: &str, value: bool) -> VariableIR {
+    VariableIR::Scalar(ScalarVariable {
+        identity: VariableIdentity::no_namespace(Some(name.to_string())),
+        type_name: Some("bool".to_owned()),
+        value: Some(SupportedScalar::Bool(value)),
+    })
+}
+
+/// Return `true` if a lock (`Mutex` or `RwLock`) is poisoned.
+fn is_poisoned(ir: &VariableIR) -> Result<bool, ParsingError> {
+    let failed = ir
+        .bfs_iterator()
+        .find(|child| child.name() == "failed")
+        .ok_or(FieldNotFound("failed"))?;
+    Ok(failed.assume_field_as_scalar_number("value")? != 0)
+}
+
+/// Return a value protected by a lock (`Mutex` or `RwLock`).
+fn lock_data(ir: &VariableIR) -> Result<VariableIR, ParsingError> {
+    let unsafe_cell = ir.assume_field_as_struct("data")?;
+    let value = unsafe_cell
+        .members
+        .first()
+        .ok_or(IncompleteInterp("UnsafeCell"))?;
+    Ok(value.clone())
+}
+
+/// Return a name of selected enum variant and a payload of a single-field variant.
+/// Payload is renamed to the variant name.
+fn variant_payload(r#enum: &RustEnumVariable) -> Result<(&str, Option<VariableIR>), ParsingError> {
+    let Some(VariableIR::Struct(variant)) = r#enum.value.as_deref() else {
+        return Err(IncompleteInterp("enum variant").into());
+    };
+    let name = variant
+        .identity
+        .name
+        .as_deref()
+        .ok_or(IncompleteInterp("enum variant"))?;
+    let payload = match variant.members.as_slice() {
+        [] => None,
+        [payload] => Some(payload.clone_and_rename(name)),
+        _ => return Err(UnexpectedType("enum variant must contain a single field").into()),
+    };
+    Ok((name, payload))
+}
+
+/// Return a field value as a number, the field may be a scalar or
+/// a wrapper over scalar (like `Nanoseconds(u32)`).
+fn assume_field_as_newtype_number(
+    ir: &VariableIR,
+    field_name: &'static str,
+) -> Result<i64, AssumeError> {
+    let mut field = ir
+        .bfs_iterator()
+        .find(|child| child.name() == field_name)
+        .ok_or(FieldNotFound(field_name))?;
+    while let VariableIR::Struct(wrapper) = field {
+        field = wrapper
+            .members
+            .first()
+            .ok_or(AssumeError::FieldNotANumber(field_name))?;
+    }
+    match field {
+        VariableIR::Scalar(scalar) => scalar
+            .try_as_number()
+            .ok_or(AssumeError::FieldNotANumber(field_name)),
+        _ => Err(AssumeError::FieldNotANumber(field_name)),
+    }
 }
 
 fn extract_capacity(eval_ctx: &EvaluationContext, ir: &VariableIR) -> Result<usize, ParsingError> {
@@ -0,0 +1,213 @@
+//! Conversion of literals into a binary representation of debugee types,
+//! used for modifying values of variables.
+
+use crate::debugger::debugee::dwarf::r#type::{
+    ComplexType, ScalarType, TypeDeclaration, TypeIdentity,
+};
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::VariableWrite;
+use crate::debugger::variable::select::Literal;
+use gimli::{
+    DW_ATE_address, DW_ATE_boolean, DW_ATE_float, DW_ATE_signed, DW_ATE_signed_char,
+    DW_ATE_unsigned, DW_ATE_unsigned_char, DW_ATE_ASCII, DW_ATE_UTF,
+};
+use std::mem;
+
+/// Convert a literal into bytes of a value of a given type.
+/// Scalars, C-style enums (discriminant is set by variant name or by integer value)
+/// and pointers are supported.
+///
+/// # Arguments
+///
+/// * `r#type`: complex type
+/// * `type_id`: identity of a value type in a complex type
+/// * `literal`: new value
+pub fn literal_into_bytes(
+    r#type: &ComplexType,
+    type_id: TypeIdentity,
+    literal: &Literal,
+) -> Result<Vec<u8>, Error> {
+    let type_name = || r#type.type_name(type_id).unwrap_or("unknown".to_string());
+    let unsupported = || {
+        VariableWrite(format!(
+            "`{literal}` can't be written into `{}`",
+            type_name()
+        ))
+    };
+
+    let Some(decl) = r#type.types.get(&type_id) else {
+        return Err(VariableWrite("unknown variable type".to_string()));
+    };
+
+    match decl {
+        TypeDeclaration::Scalar(scalar) => {
+            scalar_into_bytes(scalar, literal).ok_or_else(unsupported)
+        }
+        TypeDeclaration::CStyleEnum {
+            byte_size,
+            discr_type,
+            enumerators,
+            ..
+        } => {
+            let discr = match literal {
+                Literal::Int(discr) => *discr,
+                Literal::EnumVariant(variant, None) => {
+                    // variant may be set with an enum name (like `Enum::Variant`)
+                    let variant = variant.rsplit("::").next().unwrap_or_default();
+                    let (discr, _) = enumerators
+                        .iter()
+                        .find(|(_, name)| name.as_str() == variant)
+                        .ok_or_else(unsupported)?;
+                    *discr
+                }
+                _ => return Err(unsupported()),
+            };
+
+            let size = byte_size.or_else(|| match discr_type.and_then(|t| r#type.types.get(&t)) {
+                Some(TypeDeclaration::Scalar(scalar)) => scalar.byte_size,
+                _ => None,
+            });
+            int_into_bytes(discr as i128, size.unwrap_or(0) as usize, true).ok_or_else(unsupported)
+        }
+        TypeDeclaration::Pointer { .. } => {
+            let addr = match literal {
+                Literal::Address(addr) => *addr as i128,
+                Literal::Int(addr) => *addr as i128,
+                _ => return Err(unsupported()),
+            };
+            int_into_bytes(addr, mem::size_of::<usize>(), false).ok_or_else(unsupported)
+        }
+        TypeDeclaration::ModifiedType {
+            inner: Some(inner), ..
+        } => literal_into_bytes(r#type, *inner, literal),
+        _ => Err(unsupported()),
+    }
+}
+
+#[allow(non_upper_case_globals)]
+fn scalar_into_bytes(scalar: &ScalarType, literal: &Literal) -> Option<Vec<u8>> {
+    let size = scalar.byte_size? as usize;
+    match (scalar.encoding?, literal) {
+        (DW_ATE_signed | DW_ATE_signed_char, Literal::Int(i)) => {
+            int_into_bytes(*i as i128, size, true)
+        }
+        (DW_ATE_unsigned | DW_ATE_unsigned_char | DW_ATE_address, Literal::Int(i)) => {
+            int_into_bytes(*i as i128, size, false)
+        }
+        (DW_ATE_unsigned | DW_ATE_address, Literal::Address(addr)) => {
+            int_into_bytes(*addr as i128, size, false)
+        }
+        (DW_ATE_float, Literal::Float(f)) => float_into_bytes(*f, size),
+        (DW_ATE_float, Literal::Int(i)) => float_into_bytes(*i as f64, size),
+        (DW_ATE_boolean, Literal::Bool(b)) => Some(vec![*b as u8]),
+        (DW_ATE_UTF | DW_ATE_ASCII, Literal::String(s)) => {
+            let mut chars = s.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return None;
+            };
+            int_into_bytes(c as i128, size, false)
+        }
+        _ => None,
+    }
+}
+
+/// Return first `size` bytes of a value if value fits into `size` bytes integer.
+fn int_into_bytes(value: i128, size: usize, signed: bool) -> Option<Vec<u8>> {
+    if size == 0 || size > 16 {
+        return None;
+    }
+    let bits = size as u32 * 8;
+    let fits = match (signed, bits) {
+        (_, 128) => signed || value >= 0,
+        (true, _) => (-(1 << (bits - 1))..(1 << (bits - 1))).contains(&value),
+        (false, _) => (0..(1 << bits)).contains(&value),
+    };
+    fits.then(|| value.to_le_bytes()[..size].to_vec())
+}
+
+fn float_into_bytes(value: f64, size: usize) -> Option<Vec<u8>> {
+    match size {
+        4 => Some((value as f32).to_le_bytes().to_vec()),
+        8 => Some(value.to_le_bytes().to_vec()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debugger::debugee::dwarf::unit::DieRef;
+    use crate::debugger::debugee::dwarf::NamespaceHierarchy;
+    use gimli::{DwAte, UnitOffset};
+    use std::collections::HashMap;
+
+    fn scalar_type(name: &str, size: u64, encoding: DwAte) -> ComplexType {
+        let root = DieRef::Unit(UnitOffset(1));
+        let decl = TypeDeclaration::Scalar(ScalarType {
+            namespaces: NamespaceHierarchy::default(),
+            name: Some(name.to_string()),
+            byte_size: Some(size),
+            encoding: Some(encoding),
+        });
+        ComplexType {
+            types: HashMap::from([(root, decl)]),
+            root,
+        }
+    }
+
+    #[test]
+    fn test_scalar_into_bytes() {
+        struct TestCase {
+            r#type: ComplexType,
+            literal: Literal,
+            expected: Option<Vec<u8>>,
+        }
+        let test_cases = vec![
+            TestCase {
+                r#type: scalar_type("i8", 1, DW_ATE_signed),
+                literal: Literal::Int(-1),
+                expected: Some(vec![0xFF]),
+            },
+            TestCase {
+                r#type: scalar_type("i8", 1, DW_ATE_signed),
+                literal: Literal::Int(128),
+                expected: None,
+            },
+            TestCase {
+                r#type: scalar_type("u32", 4, DW_ATE_unsigned),
+                literal: Literal::Int(0x01020304),
+                expected: Some(vec![4, 3, 2, 1]),
+            },
+            TestCase {
+                r#type: scalar_type("u32", 4, DW_ATE_unsigned),
+                literal: Literal::Int(-1),
+                expected: None,
+            },
+            TestCase {
+                r#type: scalar_type("f64", 8, DW_ATE_float),
+                literal: Literal::Float(1.5),
+                expected: Some(1.5f64.to_le_bytes().to_vec()),
+            },
+            TestCase {
+                r#type: scalar_type("bool", 1, DW_ATE_boolean),
+                literal: Literal::Bool(true),
+                expected: Some(vec![1]),
+            },
+            TestCase {
+                r#type: scalar_type("char", 4, DW_ATE_UTF),
+                literal: Literal::String("b".to_string()),
+                expected: Some(vec![b'b', 0, 0, 0]),
+            },
+            TestCase {
+                r#type: scalar_type("bool", 1, DW_ATE_boolean),
+                literal: Literal::Int(1),
+                expected: None,
+            },
+        ];
+
+        for tc in test_cases {
+            let bytes = literal_into_bytes(&tc.r#type, tc.r#type.root, &tc.literal).ok();
+            assert_eq!(bytes, tc.expected, "{}", tc.literal);
+        }
+    }
+}
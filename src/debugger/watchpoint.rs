@@ -0,0 +1,648 @@
+//! Watchpoints, stop debugee when a watched memory is written (or accessed).
+//!
+//! Watchpoints are implemented with x86-64 debug registers: DR0-DR3 contain watched addresses,
+//! DR7 controls conditions and lengths of watched ranges and DR6 contains a status
+//! of the last debug exception. Debug registers are per-thread, so they are set for each
+//! debugee thread (and copied into new threads by a tracer).
+//!
+//! Debug registers are shared with hardware breakpoints (see [`set_exec_breakpoint`]),
+//! registers reserved by breakpoints are never used by watchpoints.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::breakpoint;
+use crate::debugger::error::Error;
+use crate::debugger::error::Error::{Ptrace, WatchpointLimit, WatchpointNoMemory, WatchpointSize};
+use crate::debugger::variable::select::{MemoryLocation, VariableSelector, DQE};
+use crate::debugger::{read_memory_by_pid, Debugger};
+use nix::libc;
+use nix::libc::c_void;
+use nix::sys::ptrace;
+use nix::sys::ptrace::AddressType;
+use nix::unistd::Pid;
+use std::fmt::{Display, Formatter};
+use std::mem;
+use strum_macros::Display;
+
+/// Offset of `u_debugreg` field in `struct user` (`sys/user.h`).
+const DEBUG_REG_OFFSET: usize = mem::offset_of!(libc::user, u_debugreg);
+/// Number of address debug registers (DR0-DR3).
+pub(crate) const ADDRESS_REG_COUNT: usize = 4;
+/// Debug status register.
+const DR_STATUS: usize = 6;
+/// Debug control register.
+const DR_CONTROL: usize = 7;
+
+fn read_debug_reg(pid: Pid, idx: usize) -> Result<u64, Error> {
+    let offset = DEBUG_REG_OFFSET + idx * mem::size_of::<u64>();
+    ptrace::read_user(pid, offset as AddressType)
+        .map(|value| value as u64)
+        .map_err(Ptrace)
+}
+
+fn write_debug_reg(pid: Pid, idx: usize, value: u64) -> Result<(), Error> {
+    let offset = DEBUG_REG_OFFSET + idx * mem::size_of::<u64>();
+    // SAFETY: kernel validates debug register values written into a user area
+    unsafe { ptrace::write_user(pid, offset as AddressType, value as *mut c_void) }.map_err(Ptrace)
+}
+
+/// Copy watchpoints of one thread into another. Debug registers aren't inherited
+/// by a new thread, so tracer copies them from a thread that calls `clone`.
+///
+/// # Arguments
+///
+/// * `from`: parent thread id
+/// * `to`: new thread id
+pub(crate) fn copy_debug_regs(from: Pid, to: Pid) -> Result<(), Error> {
+    let control = read_debug_reg(from, DR_CONTROL)?;
+    if control == 0 {
+        return Ok(());
+    }
+    for idx in 0..ADDRESS_REG_COUNT {
+        write_debug_reg(to, idx, read_debug_reg(from, idx)?)?;
+    }
+    write_debug_reg(to, DR_CONTROL, control)
+}
+
+/// Return DR7 bits (enable, condition and length) of an address debug register.
+fn control_mask(reg: usize) -> u64 {
+    0b11 << (reg * 2) | 0b1111 << (16 + reg * 4)
+}
+
+/// Set or remove a hardware execution breakpoint in threads.
+/// Execution breakpoint has a zero condition and length bits, so only an enable bit
+/// is set in DR7, bits of other address registers remain unchanged.
+///
+/// # Arguments
+///
+/// * `threads`: debugee threads (tracees)
+/// * `reg`: address debug register index (0-3)
+/// * `addr`: breakpoint address
+/// * `enable`: set breakpoint if true, remove otherwise
+pub(crate) fn set_exec_breakpoint(
+    threads: &[Pid],
+    reg: usize,
+    addr: RelocatedAddress,
+    enable: bool,
+) -> Result<(), Error> {
+    debug_assert!(reg < ADDRESS_REG_COUNT);
+    for &pid in threads {
+        let control = read_debug_reg(pid, DR_CONTROL)? & !control_mask(reg);
+        if enable {
+            write_debug_reg(pid, reg, addr.as_u64())?;
+            write_debug_reg(pid, DR_CONTROL, control | 1 << (reg * 2))?;
+        } else {
+            write_debug_reg(pid, DR_CONTROL, control)?;
+        }
+    }
+    Ok(())
+}
+
+/// Check that an address debug register is triggered in a thread,
+/// if so, the register is removed from a thread debug status.
+///
+/// # Arguments
+///
+/// * `pid`: thread id
+/// * `reg`: address debug register index (0-3)
+pub(crate) fn take_debug_status(pid: Pid, reg: usize) -> Result<bool, Error> {
+    let status = read_debug_reg(pid, DR_STATUS)?;
+    if status & (1 << reg) == 0 {
+        return Ok(false);
+    }
+    write_debug_reg(pid, DR_STATUS, status & !(1 << reg))?;
+    Ok(true)
+}
+
+/// Check that a watchpoint is triggered in a thread (any address debug register
+/// except registers of hardware breakpoints), thread debug status remains unchanged.
+///
+/// # Arguments
+///
+/// * `pid`: thread id
+/// * `reserved`: debug registers used by hardware breakpoints
+pub(crate) fn has_watch_status(pid: Pid, reserved: &[usize]) -> Result<bool, Error> {
+    let status = read_debug_reg(pid, DR_STATUS)?;
+    Ok((0..ADDRESS_REG_COUNT).any(|reg| !reserved.contains(&reg) && status & (1 << reg) != 0))
+}
+
+/// Split memory range into ranges suitable for debug registers
+/// (1, 2, 4 or 8 bytes length, aligned by length).
+fn aligned_ranges(addr: usize, size: usize) -> Vec<(usize, u8)> {
+    let mut ranges = vec![];
+    let end = addr + size;
+    let mut addr = addr;
+    while addr < end {
+        let len = [8, 4, 2, 1]
+            .into_iter()
+            .find(|&len| addr % len == 0 && addr + len <= end)
+            .expect("1-byte length always suitable");
+        ranges.push((addr, len as u8));
+        addr += len;
+    }
+    ranges
+}
+
+/// Condition when a watchpoint stops debugee.
+#[derive(Debug, Display, Clone, Copy, PartialEq)]
+pub enum WatchCondition {
+    /// Stop when watched memory is written.
+    #[strum(serialize = "write")]
+    Write,
+    /// Stop when watched memory is read or written.
+    #[strum(serialize = "read/write")]
+    ReadWrite,
+}
+
+impl WatchCondition {
+    /// Return R/W bits of DR7 register.
+    fn control_bits(self) -> u64 {
+        match self {
+            WatchCondition::Write => 0b01,
+            WatchCondition::ReadWrite => 0b11,
+        }
+    }
+}
+
+/// Value of a watched memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchValue {
+    pub bytes: Vec<u8>,
+    /// Type name, known if a watchpoint is set on a variable.
+    pub type_name: Option<String>,
+}
+
+impl Display for WatchValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        macro_rules! render_num {
+            ($t: ty) => {
+                if let Ok(bytes) = self.bytes.as_slice().try_into() {
+                    return write!(f, "{}", <$t>::from_le_bytes(bytes));
+                }
+            };
+        }
+
+        match self.type_name.as_deref() {
+            Some("i8") => render_num!(i8),
+            Some("i16") => render_num!(i16),
+            Some("i32") => render_num!(i32),
+            Some("i64") | Some("isize") => render_num!(i64),
+            Some("u8") => render_num!(u8),
+            Some("u16") => render_num!(u16),
+            Some("u32") => render_num!(u32),
+            Some("u64") | Some("usize") => render_num!(u64),
+            Some("f32") => render_num!(f32),
+            Some("f64") => render_num!(f64),
+            Some("bool") if self.bytes.len() == 1 => return write!(f, "{}", self.bytes[0] != 0),
+            Some("char") => {
+                if let Ok(bytes) = self.bytes.as_slice().try_into() {
+                    if let Some(ch) = char::from_u32(u32::from_le_bytes(bytes)) {
+                        return write!(f, "{ch}");
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // unknown type, render as a little-endian hex number
+        f.write_str("0x")?;
+        self.bytes
+            .iter()
+            .rev()
+            .try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+/// Hardware watchpoint.
+#[derive(Debug, Clone)]
+pub struct Watchpoint {
+    pub number: u32,
+    /// Watched variable name or address.
+    pub source: String,
+    pub addr: RelocatedAddress,
+    pub size: usize,
+    pub condition: WatchCondition,
+    /// Last known value of a watched memory.
+    pub value: WatchValue,
+    /// Debug register index, address and length for each watched range.
+    ranges: Vec<(usize, usize, u8)>,
+}
+
+/// Watchpoint hit, contains watched memory value before and after a hit.
+#[derive(Debug, Clone)]
+pub struct WatchpointHit {
+    pub number: u32,
+    pub source: String,
+    pub old: WatchValue,
+    pub new: WatchValue,
+}
+
+#[derive(Default)]
+pub(super) struct WatchpointRegistry {
+    watchpoints: Vec<Watchpoint>,
+}
+
+impl WatchpointRegistry {
+    /// Return all watchpoints.
+    pub fn all(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// Return value of DR7 register for current watchpoints.
+    fn control(&self) -> u64 {
+        self.watchpoints
+            .iter()
+            .flat_map(|wp| wp.ranges.iter().map(move |range| (wp.condition, range)))
+            .fold(0, |control, (condition, &(reg, _, len))| {
+                let len_bits = match len {
+                    1 => 0b00,
+                    2 => 0b01,
+                    8 => 0b10,
+                    _ => 0b11,
+                };
+                control
+                    | 1 << (reg * 2)
+                    | condition.control_bits() << (16 + reg * 4)
+                    | len_bits << (18 + reg * 4)
+            })
+    }
+
+    /// Return indexes of address debug registers used by watchpoints.
+    pub fn used_regs(&self) -> Vec<usize> {
+        self.watchpoints
+            .iter()
+            .flat_map(|wp| wp.ranges.iter().map(|range| range.0))
+            .collect()
+    }
+
+    /// Write watchpoints into debug registers of all threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `threads`: debugee threads
+    /// * `reserved`: debug registers used by hardware breakpoints, their control bits are preserved
+    fn apply(&self, threads: &[Pid], reserved: &[usize]) -> Result<(), Error> {
+        let control = self.control();
+        let reserved_mask = reserved
+            .iter()
+            .fold(0, |mask, &reg| mask | control_mask(reg));
+        for &pid in threads {
+            let reserved_control = if reserved_mask == 0 {
+                0
+            } else {
+                read_debug_reg(pid, DR_CONTROL)? & reserved_mask
+            };
+            // disable all watchpoints before addresses change
+            write_debug_reg(pid, DR_CONTROL, reserved_control)?;
+            for wp in &self.watchpoints {
+                for &(reg, addr, _) in &wp.ranges {
+                    write_debug_reg(pid, reg, addr as u64)?;
+                }
+            }
+            write_debug_reg(pid, DR_CONTROL, control | reserved_control)?;
+        }
+        Ok(())
+    }
+
+    /// Add a new watchpoint and set it in all threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `threads`: debugee threads
+    /// * `reserved`: debug registers used by hardware breakpoints
+    /// * `source`: watched variable name or address
+    /// * `addr`: watched memory address
+    /// * `condition`: watchpoint condition
+    /// * `value`: current value of watched memory
+    fn add(
+        &mut self,
+        threads: &[Pid],
+        reserved: &[usize],
+        source: String,
+        addr: RelocatedAddress,
+        condition: WatchCondition,
+        value: WatchValue,
+    ) -> Result<Watchpoint, Error> {
+        let size = value.bytes.len();
+        let ranges = aligned_ranges(addr.as_usize(), size);
+        let free_regs: Vec<_> = (0..ADDRESS_REG_COUNT)
+            .filter(|reg| {
+                !reserved.contains(reg)
+                    && !self
+                        .watchpoints
+                        .iter()
+                        .any(|wp| wp.ranges.iter().any(|range| range.0 == *reg))
+            })
+            .collect();
+        if ranges.len() > free_regs.len() {
+            return Err(WatchpointLimit(size));
+        }
+
+        let wp = Watchpoint {
+            number: breakpoint::next_number(),
+            source,
+            addr,
+            size,
+            condition,
+            value,
+            ranges: free_regs
+                .into_iter()
+                .zip(ranges)
+                .map(|(reg, (addr, len))| (reg, addr, len))
+                .collect(),
+        };
+        self.watchpoints.push(wp.clone());
+
+        if let Err(e) = self.apply(threads, reserved) {
+            self.watchpoints.pop();
+            _ = self.apply(threads, reserved);
+            return Err(e);
+        }
+        Ok(wp)
+    }
+
+    /// Remove watchpoint by its number, return removed watchpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `threads`: debugee threads
+    /// * `reserved`: debug registers used by hardware breakpoints
+    /// * `number`: watchpoint number
+    fn remove(
+        &mut self,
+        threads: &[Pid],
+        reserved: &[usize],
+        number: u32,
+    ) -> Result<Option<Watchpoint>, Error> {
+        let Some(idx) = self.watchpoints.iter().position(|wp| wp.number == number) else {
+            return Ok(None);
+        };
+        let wp = self.watchpoints.remove(idx);
+        self.apply(threads, reserved)?;
+        Ok(Some(wp))
+    }
+
+    /// Remove all watchpoints without debug registers update, used when debugee process is gone.
+    pub fn clear(&mut self) {
+        self.watchpoints.clear();
+    }
+
+    /// Return indexes of watchpoints triggered in a thread, then remove registers
+    /// of watchpoints from a thread debug status (other status bits remain unchanged).
+    ///
+    /// # Arguments
+    ///
+    /// * `pid`: thread id
+    fn take_triggered(&self, pid: Pid) -> Result<Vec<usize>, Error> {
+        let status = read_debug_reg(pid, DR_STATUS)?;
+        let watched = self
+            .used_regs()
+            .into_iter()
+            .fold(0, |mask, reg| mask | 1 << reg);
+        if status & watched != 0 {
+            write_debug_reg(pid, DR_STATUS, status & !watched)?;
+        }
+
+        Ok(self
+            .watchpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, wp)| wp.ranges.iter().any(|range| status & (1 << range.0) != 0))
+            .map(|(idx, _)| idx)
+            .collect())
+    }
+}
+
+impl Debugger {
+    fn read_watch_value(&self, addr: usize, size: usize) -> Result<Vec<u8>, Error> {
+        read_memory_by_pid(self.debugee.tracee_ctl().proc_pid(), addr, size).map_err(Ptrace)
+    }
+
+    /// Set a watchpoint on a variable, variable is searched in the current thread
+    /// (locals in priority), like in a `var` command.
+    /// Note that watchpoint remains at the same address even if the variable goes out of scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name`: variable name
+    /// * `condition`: watchpoint condition
+    pub fn set_watchpoint_on_var(
+        &mut self,
+        var_name: &str,
+        condition: WatchCondition,
+    ) -> Result<Watchpoint, Error> {
+        let locations = self.variable_memory_location(DQE::Variable(VariableSelector::Name {
+            var_name: var_name.to_string(),
+            only_local: false,
+        }))?;
+        let MemoryLocation {
+            address,
+            size,
+            type_name,
+        } = locations
+            .into_iter()
+            .next()
+            .ok_or_else(|| WatchpointNoMemory(var_name.to_string()))?;
+
+        if size == 0 {
+            return Err(WatchpointSize(size));
+        }
+        let value = WatchValue {
+            bytes: self.read_watch_value(address, size)?,
+            type_name,
+        };
+        let threads = self.thread_pids();
+        self.watchpoints.add(
+            &threads,
+            &self.breakpoints.hardware_regs(),
+            var_name.to_string(),
+            address.into(),
+            condition,
+            value,
+        )
+    }
+
+    /// Set a watchpoint on a memory range.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr`: start address
+    /// * `size`: size of a watched memory in bytes
+    /// * `condition`: watchpoint condition
+    pub fn set_watchpoint_at_addr(
+        &mut self,
+        addr: RelocatedAddress,
+        size: usize,
+        condition: WatchCondition,
+    ) -> Result<Watchpoint, Error> {
+        if !self.debugee.is_in_progress() {
+            return Err(Error::ProcessNotStarted);
+        }
+        if size == 0 {
+            return Err(WatchpointSize(size));
+        }
+        let value = WatchValue {
+            bytes: self.read_watch_value(addr.as_usize(), size)?,
+            type_name: None,
+        };
+        let threads = self.thread_pids();
+        self.watchpoints.add(
+            &threads,
+            &self.breakpoints.hardware_regs(),
+            format!("*{addr}"),
+            addr,
+            condition,
+            value,
+        )
+    }
+
+    /// Remove watchpoint by its number, return removed watchpoint if exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `number`: watchpoint number
+    pub fn remove_watchpoint(&mut self, number: u32) -> Result<Option<Watchpoint>, Error> {
+        let threads = self.thread_pids();
+        self.watchpoints
+            .remove(&threads, &self.breakpoints.hardware_regs(), number)
+    }
+
+    /// Return all watchpoints.
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        self.watchpoints.all()
+    }
+
+    /// Return watchpoints triggered in a thread, update last known values of watched memory.
+    /// Write-only watchpoints are not triggered if the value of memory remains the same.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid`: thread id
+    pub(super) fn take_watchpoint_hits(&mut self, pid: Pid) -> Result<Vec<WatchpointHit>, Error> {
+        let triggered = self.watchpoints.take_triggered(pid)?;
+
+        let mut hits = vec![];
+        for idx in triggered {
+            let wp = &self.watchpoints.all()[idx];
+            let new = WatchValue {
+                bytes: self.read_watch_value(wp.addr.as_usize(), wp.size)?,
+                type_name: wp.value.type_name.clone(),
+            };
+            if wp.condition == WatchCondition::Write && new == wp.value {
+                continue;
+            }
+
+            let wp = &mut self.watchpoints.watchpoints[idx];
+            let old = mem::replace(&mut wp.value, new.clone());
+            hits.push(WatchpointHit {
+                number: wp.number,
+                source: wp.source.clone(),
+                old,
+                new,
+            });
+        }
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_aligned_ranges() {
+        assert_eq!(aligned_ranges(0x1000, 4), vec![(0x1000, 4)]);
+        assert_eq!(aligned_ranges(0x1000, 16), vec![(0x1000, 8), (0x1008, 8)]);
+        assert_eq!(
+            aligned_ranges(0x1003, 6),
+            vec![(0x1003, 1), (0x1004, 4), (0x1008, 1)]
+        );
+    }
+
+    #[test]
+    fn test_control() {
+        let mut registry = WatchpointRegistry::default();
+        let value = |size| WatchValue {
+            bytes: vec![0; size],
+            type_name: None,
+        };
+        registry
+            .add(
+                &[],
+                &[],
+                String::new(),
+                0x1000_usize.into(),
+                WatchCondition::Write,
+                value(4),
+            )
+            .unwrap();
+        registry
+            .add(
+                &[],
+                &[],
+                String::new(),
+                0x2000_usize.into(),
+                WatchCondition::ReadWrite,
+                value(8),
+            )
+            .unwrap();
+        // DR0: enabled, write, 4 bytes; DR1: enabled, read/write, 8 bytes
+        assert_eq!(registry.control(), 0b1011_1101 << 16 | 0b0101);
+
+        let err = registry.add(
+            &[],
+            &[],
+            String::new(),
+            0x3000_usize.into(),
+            WatchCondition::Write,
+            value(32),
+        );
+        assert!(matches!(err, Err(WatchpointLimit(32))));
+    }
+
+    #[test]
+    fn test_reserved_regs() {
+        let mut registry = WatchpointRegistry::default();
+        let value = WatchValue {
+            bytes: vec![0; 8],
+            type_name: None,
+        };
+        let wp = registry
+            .add(
+                &[],
+                &[0, 2],
+                String::new(),
+                0x1000_usize.into(),
+                WatchCondition::Write,
+                value.clone(),
+            )
+            .unwrap();
+        assert_eq!(wp.ranges[0].0, 1);
+        assert_eq!(registry.used_regs(), vec![1]);
+
+        let err = registry.add(
+            &[],
+            &[0, 2, 3],
+            String::new(),
+            0x2000_usize.into(),
+            WatchCondition::Write,
+            value,
+        );
+        assert!(matches!(err, Err(WatchpointLimit(8))));
+
+        assert_eq!(control_mask(1), 0b1111 << 20 | 0b1100);
+    }
+
+    #[test]
+    fn test_watch_value_render() {
+        let value = WatchValue {
+            bytes: (-5_i32).to_le_bytes().to_vec(),
+            type_name: Some("i32".to_string()),
+        };
+        assert_eq!(value.to_string(), "-5");
+        let value = WatchValue {
+            bytes: vec![0xef, 0xbe, 0xad, 0xde],
+            type_name: None,
+        };
+        assert_eq!(value.to_string(), "0xdeadbeef");
+    }
+}
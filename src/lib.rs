@@ -1,5 +1,23 @@
+//! BugStalker is a modern debugger for rust applications.
+//!
+//! Besides the `bs` binary, the crate may be embedded into other tools. Public API of the engine:
+//! - [`debugger::process::Child`] - a debugee process, created from an executable or attached
+//!   to a running process
+//! - [`debugger::DebuggerBuilder`] and [`debugger::Debugger`] - execution control, breakpoints,
+//!   threads, stack and program data
+//! - [`debugger::event`] - channel-based stream of debugger events (an alternative to
+//!   a custom [`debugger::EventHook`] implementation)
+//! - [`debugger::progress`] - progress reporting and cancellation of long-running operations
+//! - [`oracle::Oracle`] - debugger plugins
+//! - `debugger::json` - JSON representation of variables, breakpoints, backtraces and threads
+//!   (`json` feature)
+//!
+//! User interfaces (console, tui and DAP server) are available with `ui` feature (enabled
+//! by default). Use `default-features = false, features = ["libunwind"]` to depend only on the engine.
+
 pub mod debugger;
 pub mod log;
 pub mod oracle;
+#[cfg(feature = "ui")]
 pub mod ui;
 pub mod version;
@@ -3,17 +3,24 @@
 use bugstalker::debugger::rust;
 use bugstalker::log::LOGGER_SWITCHER;
 use bugstalker::ui;
-use bugstalker::ui::config::{Theme, UIConfig};
+use bugstalker::ui::command::parser;
+use bugstalker::ui::config::{Theme, UIConfig, Verbosity};
+use bugstalker::ui::console::BatchCommandError;
+use bugstalker::ui::dap::Transport;
+use bugstalker::ui::init::InitScript;
+use bugstalker::ui::style;
 use bugstalker::ui::supervisor::{DebugeeSource, Interface};
 use clap::error::ErrorKind;
-use clap::{arg, CommandFactory, Parser};
+use clap::{arg, CommandFactory, Parser, Subcommand};
 use std::fmt::Display;
 use std::path::PathBuf;
 use std::process::exit;
 use std::str::FromStr;
+use std::{fs, io};
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Args {
     /// Start with terminal ui
     #[clap(long)]
@@ -27,12 +34,17 @@ pub struct Args {
     /// Executable file (debugee)
     debugee: Option<String>,
 
+    /// Open a core file of a crashed debugee (post-mortem debugging),
+    /// debugee executable file must be provided too
+    #[clap(long, short, requires = "debugee", conflicts_with = "pid")]
+    core: Option<PathBuf>,
+
     /// Path to rust stdlib
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     std_lib_path: Option<String>,
 
     /// Discover a specific oracle (maybe more than one)
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     oracle: Vec<String>,
 
     /// Arguments are passed to debugee
@@ -45,6 +57,75 @@ pub struct Args {
     #[clap(short, long)]
     #[arg(default_value = "solarized_dark")]
     theme: String,
+
+    /// Disable colors in debugger output, logs and source code.
+    /// Colors also disabled if NO_COLOR environment variable is set
+    #[clap(long)]
+    #[arg(default_value_t = false)]
+    no_color: bool,
+
+    /// Run in non-interactive mode: execute commands from a file (see `-x`) or stdin and exit.
+    /// Exit code is non-zero if any of commands failed
+    #[clap(long)]
+    #[arg(default_value_t = false)]
+    batch: bool,
+
+    /// File with debugger commands (one command per line) for batch mode
+    #[clap(short = 'x', long)]
+    command_file: Option<PathBuf>,
+
+    /// Print only command outputs and errors
+    #[clap(short, long, conflicts_with = "verbose", global = true)]
+    #[arg(default_value_t = false)]
+    quiet: bool,
+
+    /// Print error categories and debugger warnings
+    #[clap(short, long, global = true)]
+    #[arg(default_value_t = false)]
+    verbose: bool,
+
+    /// Print command results, debugger events and errors as JSON objects (one object per line).
+    /// Colors are disabled in this mode
+    #[clap(long)]
+    #[arg(default_value_t = false)]
+    json: bool,
+
+    /// Listen on a unix socket for JSON-RPC requests from external tools
+    /// (add breakpoints, read variables, etc.) during an interactive console session
+    #[clap(long, value_name = "SOCKET")]
+    remote: Option<PathBuf>,
+
+    /// Show source locations (file:line) as terminal hyperlinks (OSC-8).
+    /// Template is an url with {path} and {line} placeholders,
+    /// for example: vscode://file/{path}:{line}
+    #[clap(long, value_name = "TEMPLATE", num_args = 0..=1, default_missing_value = "file://{path}")]
+    hyperlinks: Option<String>,
+
+    /// Do not execute commands from init files
+    /// (~/.config/bugstalker/init and ./.bugstalker)
+    #[clap(long)]
+    #[arg(default_value_t = false)]
+    no_init: bool,
+
+    /// Load project-local init file (./.bugstalker) even if the current directory
+    /// isn't listed by `safe-path` directive of the global init file
+    #[clap(long, conflicts_with = "no_init")]
+    #[arg(default_value_t = false)]
+    local_init: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Run a Debug Adapter Protocol server (used by editors, VSCode for example).
+    /// Debugee is launched (or attached) by a client request
+    Dap {
+        /// Listen on a tcp port instead of using stdin and stdout
+        #[clap(long)]
+        port: Option<u16>,
+    },
 }
 
 fn print_fatal_and_exit(kind: ErrorKind, message: impl Display) -> ! {
@@ -66,26 +147,86 @@ impl<T, E: Display> FatalResult<T> for Result<T, E> {
     }
 }
 
+impl Args {
+    fn verbosity(&self) -> Verbosity {
+        if self.quiet {
+            Verbosity::Quiet
+        } else if self.verbose {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+}
+
 impl From<&Args> for UIConfig {
     fn from(args: &Args) -> Self {
+        let theme = Theme::from_str(&args.theme)
+            .unwrap_or_exit(ErrorKind::InvalidValue, "Not an available theme");
+        let init = if args.no_init {
+            InitScript::default()
+        } else {
+            InitScript::load(args.local_init)
+        };
         Self {
-            theme: Theme::from_str(&args.theme)
-                .unwrap_or_exit(ErrorKind::InvalidValue, "Not an available theme"),
+            theme: if style::is_no_color() {
+                Theme::None
+            } else {
+                theme
+            },
+            verbosity: args.verbosity(),
+            json: args.json,
+            remote: args.remote.clone(),
+            hyperlinks: args
+                .hyperlinks
+                .clone()
+                .or_else(|| init.hyperlinks.clone())
+                .filter(|_| !style::is_no_color()),
+            init,
         }
     }
 }
 
 fn main() {
-    let logger = env_logger::Logger::from_default_env();
+    let mut args = Args::parse();
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    style::set_no_color(args.no_color || no_color_env || args.json);
+
+    let logger = env_logger::Builder::new()
+        .filter_level(args.verbosity().log_level())
+        .parse_default_env()
+        .write_style(style::log_write_style())
+        .build();
     let filter = logger.filter();
     LOGGER_SWITCHER.switch(logger, filter);
 
-    let args = Args::parse();
     ui::config::set(UIConfig::from(&args));
 
+    if args.args.is_empty() {
+        if let Some(ref init_args) = ui::config::current().init.args {
+            args.args = init_args.clone();
+        }
+    }
+
     rust::Environment::init(args.std_lib_path.map(PathBuf::from));
 
-    let debugee_src = if let Some(ref debugee) = args.debugee {
+    if let Some(Command::Dap { port }) = args.command {
+        let transport = match port {
+            Some(port) => Transport::Tcp { port },
+            None => Transport::Stdio,
+        };
+        if let Err(e) = ui::dap::run(transport, &args.oracle) {
+            print_fatal_and_exit(ErrorKind::Io, format!("Application error: {e:#}"));
+        }
+        return;
+    }
+
+    let debugee_src = if let (Some(debugee), Some(core)) = (&args.debugee, &args.core) {
+        DebugeeSource::Core {
+            path: debugee,
+            core,
+        }
+    } else if let Some(ref debugee) = args.debugee {
         DebugeeSource::File {
             path: debugee,
             args: &args.args,
@@ -96,12 +237,40 @@ fn main() {
         print_fatal_and_exit(ErrorKind::ArgumentConflict, "Please provide a debugee name or use a \"-p\" option for attach to already running process");
     };
 
-    let interface = if args.tui {
+    let interface = if args.batch {
+        let script = match args.command_file {
+            Some(ref path) => {
+                fs::read_to_string(path).unwrap_or_exit(ErrorKind::Io, "Read command file")
+            }
+            None => io::read_to_string(io::stdin())
+                .unwrap_or_exit(ErrorKind::Io, "Read commands from stdin"),
+        };
+        let commands = parser::join_continued_lines(
+            script
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#')),
+        );
+        Interface::Batch(commands)
+    } else if args.tui {
         Interface::TUI
     } else {
         Interface::Default
     };
 
-    ui::supervisor::Supervisor::run(debugee_src, interface, &args.oracle)
-        .unwrap_or_exit(ErrorKind::InvalidSubcommand, "Application error")
+    if let Err(e) = ui::supervisor::Supervisor::run(debugee_src, interface, &args.oracle) {
+        // in batch mode exit code depends on a category of failed command error
+        let code = e
+            .downcast_ref::<BatchCommandError>()
+            .map(|e| e.category.exit_code())
+            .unwrap_or(1);
+        let mut cmd = Args::command();
+        _ = cmd
+            .error(
+                ErrorKind::InvalidSubcommand,
+                format!("Application error: {e}"),
+            )
+            .print();
+        exit(code);
+    }
 }
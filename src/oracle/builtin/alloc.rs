@@ -0,0 +1,487 @@
+//! Allocation oracle: count allocations and live bytes per call site.
+//!
+//! Rust allocator entry points (`__rust_alloc`, `__rust_alloc_zeroed`, `__rust_realloc`
+//! and `__rust_dealloc`) are watched by transparent breakpoints. Result of an allocation
+//! (a pointer) is read at a return address of allocator function. Call site is the first
+//! frame outside of allocator and standard library code.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::{CreateTransparentBreakpointRequest, Debugger, Error};
+use crate::oracle::builtin::return_address;
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::style::{FunctionNameView, KeywordView};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
+use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
+use crate::ui::tui::Msg;
+use log::warn;
+use nix::unistd::Pid;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "ui")]
+use tuirealm::Component;
+
+/// Count of call sites in reports.
+#[cfg(feature = "ui")]
+const TOP_SITES: usize = 15;
+
+/// Frames of functions with these prefixes are not a call site.
+const SKIP_PREFIXES: &[&str] = &[
+    "__rust",
+    "__rdl",
+    "alloc::",
+    "core::",
+    "std::",
+    "<alloc::",
+    "<core::",
+    "<std::",
+    "hashbrown::",
+];
+
+/// Allocation statistic of a single call site.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SiteStat {
+    /// Function and source place of a call site.
+    pub site: String,
+    /// Number of allocations (including reallocations).
+    pub allocs: u64,
+    /// Number of deallocations.
+    pub frees: u64,
+    /// Total allocated bytes.
+    pub allocated_bytes: u64,
+    /// Bytes allocated at this site and not freed yet.
+    pub live_bytes: u64,
+}
+
+/// Allocation in progress (allocator function is called but not returned yet).
+struct Pending {
+    size: u64,
+    site: Arc<str>,
+    /// Pointer to memory that is reallocated.
+    realloc_from: Option<u64>,
+}
+
+#[derive(Default)]
+struct State {
+    pending: HashMap<Pid, Vec<Pending>>,
+    /// Live allocations: pointer -> (size, call site).
+    live: HashMap<u64, (u64, Arc<str>)>,
+    sites: HashMap<Arc<str>, SiteStat>,
+    /// Return addresses where breakpoints are already installed.
+    return_points: HashSet<RelocatedAddress>,
+}
+
+impl State {
+    fn free(&mut self, ptr: u64) {
+        if let Some((size, site)) = self.live.remove(&ptr) {
+            if let Some(stat) = self.sites.get_mut(&site) {
+                stat.frees += 1;
+                stat.live_bytes = stat.live_bytes.saturating_sub(size);
+            }
+        }
+    }
+
+    fn alloc(&mut self, ptr: u64, size: u64, site: Arc<str>) {
+        let stat = self.sites.entry(site.clone()).or_insert_with(|| SiteStat {
+            site: site.to_string(),
+            ..SiteStat::default()
+        });
+        stat.allocs += 1;
+        stat.allocated_bytes += size;
+        stat.live_bytes += size;
+        self.live.insert(ptr, (size, site));
+    }
+}
+
+/// [`AllocOracle`] count allocations and live bytes per call site.
+#[derive(Default)]
+pub struct AllocOracle {
+    state: Mutex<State>,
+}
+
+impl AllocOracle {
+    /// Create a new oracle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return call sites sorted by a key (descending).
+    ///
+    /// # Arguments
+    ///
+    /// * `limit`: max call site count
+    /// * `key`: sort key
+    pub fn top_sites(&self, limit: usize, key: impl Fn(&SiteStat) -> u64) -> Vec<SiteStat> {
+        let state = self.state.lock().unwrap();
+        let mut sites: Vec<_> = state.sites.values().cloned().collect();
+        sites.sort_by_key(|stat| std::cmp::Reverse(key(stat)));
+        sites.truncate(limit);
+        sites
+    }
+
+    /// Return total number of live allocations and live bytes.
+    pub fn live(&self) -> (usize, u64) {
+        let state = self.state.lock().unwrap();
+        let bytes = state.live.values().map(|(size, _)| size).sum();
+        (state.live.len(), bytes)
+    }
+
+    /// Forget collected statistic, live allocations are tracked further.
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        state.sites.clear();
+        for (size, site) in state.live.values() {
+            let stat = state.sites.entry(site.clone()).or_insert_with(|| SiteStat {
+                site: site.to_string(),
+                ..SiteStat::default()
+            });
+            stat.live_bytes += size;
+        }
+    }
+
+    /// Return call site of current allocation.
+    fn call_site(dbg: &Debugger, pid: Pid) -> Arc<str> {
+        let bt = dbg.backtrace(pid).unwrap_or_default();
+        let frame = bt.iter().skip(1).find(|frame| {
+            frame
+                .func_name
+                .as_deref()
+                .is_some_and(|name| !SKIP_PREFIXES.iter().any(|p| name.starts_with(p)))
+        });
+        let Some(frame) = frame else {
+            return Arc::from("unknown");
+        };
+
+        let func = frame.func_name.as_deref().unwrap_or_default();
+        // ip is a return address, use call instruction for a place
+        match dbg.find_place(frame.ip.offset(-1)).ok().flatten() {
+            Some(place) => Arc::from(format!(
+                "{func} ({}:{})",
+                place.file.display(),
+                place.line_number
+            )),
+            None => Arc::from(func),
+        }
+    }
+
+    /// Called at allocator function entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `size_reg`: register with allocation size
+    /// * `realloc`: true if called function is `__rust_realloc`
+    fn on_alloc(
+        self: &Arc<Self>,
+        dbg: &mut Debugger,
+        size_reg: &str,
+        realloc: bool,
+    ) -> Result<(), Error> {
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        let size = dbg.get_register_value(size_reg)?;
+        let realloc_from = realloc.then(|| dbg.get_register_value("rdi")).transpose()?;
+        let ret_addr = return_address(dbg)?;
+
+        let site = Self::call_site(dbg, pid);
+        let need_return_point = {
+            let mut state = self.state.lock().unwrap();
+            state.pending.entry(pid).or_default().push(Pending {
+                size,
+                site,
+                realloc_from,
+            });
+            state.return_points.insert(ret_addr)
+        };
+
+        if need_return_point {
+            let oracle = self.clone();
+            dbg.set_transparent_breakpoint(CreateTransparentBreakpointRequest::address(
+                ret_addr,
+                move |dbg: &mut Debugger| {
+                    if let Err(e) = oracle.on_alloc_return(dbg) {
+                        warn!(target: "alloc oracle", "allocation result: {e}")
+                    }
+                },
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Called when allocator function returns.
+    fn on_alloc_return(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        let ptr = dbg.get_register_value("rax")?;
+
+        let mut state = self.state.lock().unwrap();
+        let Some(pending) = state.pending.get_mut(&pid).and_then(|stack| stack.pop()) else {
+            return Ok(());
+        };
+        if ptr == 0 {
+            return Ok(());
+        }
+        if let Some(old_ptr) = pending.realloc_from {
+            state.free(old_ptr);
+        }
+        state.alloc(ptr, pending.size, pending.site);
+        Ok(())
+    }
+
+    fn on_dealloc(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let ptr = dbg.get_register_value("rdi")?;
+        self.state.lock().unwrap().free(ptr);
+        Ok(())
+    }
+}
+
+impl Oracle for AllocOracle {
+    fn name(&self) -> &'static str {
+        "alloc"
+    }
+
+    fn ready_for_install(&self, dbg: &Debugger) -> bool {
+        let has_symbols = !dbg
+            .get_symbols("^__rust_alloc$")
+            .unwrap_or_default()
+            .is_empty();
+        if has_symbols {
+            // breakpoints of a previous debugee process are gone
+            *self.state.lock().unwrap() = State::default();
+        }
+        has_symbols
+    }
+
+    fn watch_points(self: Arc<Self>) -> Vec<CreateTransparentBreakpointRequest> {
+        // (symbol regex, register with allocation size, is realloc)
+        let alloc_fns = [
+            ("^__rust_alloc$", "rdi", false),
+            ("^__rust_alloc_zeroed$", "rdi", false),
+            ("^__rust_realloc$", "rcx", true),
+        ];
+        let mut watch_points: Vec<_> = alloc_fns
+            .into_iter()
+            .map(|(symbol, size_reg, realloc)| {
+                let oracle = self.clone();
+                CreateTransparentBreakpointRequest::symbol(symbol, move |dbg: &mut Debugger| {
+                    if let Err(e) = oracle.on_alloc(dbg, size_reg, realloc) {
+                        warn!(target: "alloc oracle", "allocation: {e}")
+                    }
+                })
+            })
+            .collect();
+
+        let oracle = self.clone();
+        let dealloc_handler = move |dbg: &mut Debugger| {
+            if let Err(e) = oracle.on_dealloc(dbg) {
+                warn!(target: "alloc oracle", "deallocation: {e}")
+            }
+        };
+        watch_points.push(CreateTransparentBreakpointRequest::symbol(
+            "^__rust_dealloc$",
+            dealloc_handler,
+        ));
+
+        watch_points
+    }
+}
+
+#[cfg(feature = "ui")]
+impl ConsolePlugin for AllocOracle {
+    fn print(&self, printer: &ExternalPrinter, subcommand: Option<&str>) {
+        match subcommand {
+            Some("reset") => {
+                self.reset();
+                printer.println("Allocation statistic is reset");
+                return;
+            }
+            Some("report") => {
+                let (count, bytes) = self.live();
+                printer.println(format!(
+                    "{} bytes live in {} allocations\n",
+                    KeywordView::from(bytes),
+                    KeywordView::from(count)
+                ));
+                printer.println(format!(
+                    "{:<12} {:<8} {:<8} call site",
+                    "live bytes", "allocs", "frees"
+                ));
+                for stat in self
+                    .top_sites(TOP_SITES, |stat| stat.live_bytes)
+                    .into_iter()
+                    .filter(|stat| stat.live_bytes > 0)
+                {
+                    printer.println(format!(
+                        "{:<12} {:<8} {:<8} {}",
+                        stat.live_bytes,
+                        stat.allocs,
+                        stat.frees,
+                        FunctionNameView::from(stat.site)
+                    ));
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        let (count, bytes) = self.live();
+        printer.println(format!(
+            "{} bytes live in {} allocations\n",
+            KeywordView::from(bytes),
+            KeywordView::from(count)
+        ));
+        printer.println(format!(
+            "{:<12} {:<8} {:<12} call site",
+            "allocated", "allocs", "live bytes"
+        ));
+        for stat in self.top_sites(TOP_SITES, |stat| stat.allocated_bytes) {
+            printer.println(format!(
+                "{:<12} {:<8} {:<12} {}",
+                stat.allocated_bytes,
+                stat.allocs,
+                stat.live_bytes,
+                FunctionNameView::from(stat.site)
+            ));
+        }
+    }
+
+    fn help(&self) -> &str {
+        "alloc - allocations per call site, `oracle alloc report` shows call sites with the most \
+        live bytes (possible leaks), `oracle alloc reset` forgets collected statistic"
+    }
+}
+
+#[cfg(feature = "ui")]
+impl TuiPlugin for AllocOracle {
+    fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
+        Box::new(tui::AllocComponent::new(self))
+    }
+}
+
+#[cfg(feature = "ui")]
+pub mod tui {
+    use crate::oracle::builtin::alloc::{AllocOracle, TOP_SITES};
+    use crate::ui::tui::app::port::UserEvent;
+    use crate::ui::tui::Msg;
+    use std::sync::Arc;
+    use tui_realm_stdlib::Table;
+    use tuirealm::command::{Cmd, Direction};
+    use tuirealm::event::{Key, KeyEvent};
+    use tuirealm::props::{Alignment, BorderType, Borders, Color, Style, TableBuilder, TextSpan};
+    use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent};
+
+    #[derive(MockComponent)]
+    pub struct AllocComponent {
+        component: Table,
+        oracle: Arc<AllocOracle>,
+    }
+
+    impl AllocComponent {
+        pub fn new(oracle: Arc<AllocOracle>) -> Self {
+            let component = Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(Color::LightYellow),
+                )
+                .title("Live bytes by call site", Alignment::Center)
+                .inactive(Style::default().fg(Color::Gray))
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("▶")
+                .rewind(true)
+                .step(4)
+                .widths(&[12, 10, 10, 68])
+                .headers(&["Live bytes", "Allocs", "Frees", "Call site"]);
+
+            let mut this = Self { component, oracle };
+            this.refresh_table();
+            this
+        }
+
+        fn refresh_table(&mut self) {
+            let mut table = TableBuilder::default();
+            let sites = self.oracle.top_sites(TOP_SITES, |stat| stat.live_bytes);
+            if sites.is_empty() {
+                table
+                    .add_col(TextSpan::from(""))
+                    .add_col(TextSpan::from(""))
+                    .add_col(TextSpan::from(""))
+                    .add_col(TextSpan::from(""))
+                    .add_row();
+            }
+            for stat in sites {
+                table
+                    .add_col(TextSpan::from(stat.live_bytes.to_string()).fg(Color::LightBlue))
+                    .add_col(TextSpan::from(stat.allocs.to_string()))
+                    .add_col(TextSpan::from(stat.frees.to_string()))
+                    .add_col(TextSpan::from(stat.site))
+                    .add_row();
+            }
+            self.component
+                .attr(Attribute::Content, AttrValue::Table(table.build()));
+        }
+    }
+
+    impl Component<Msg, UserEvent> for AllocComponent {
+        fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+            match ev {
+                Event::Keyboard(KeyEvent {
+                    code: Key::Down, ..
+                }) => {
+                    self.perform(Cmd::Move(Direction::Down));
+                }
+                Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                    self.perform(Cmd::Move(Direction::Up));
+                }
+                Event::Tick => {
+                    self.refresh_table();
+                }
+                _ => {}
+            }
+
+            Some(Msg::None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_live_bytes() {
+        let oracle = AllocOracle::new();
+        {
+            let mut state = oracle.state.lock().unwrap();
+            let site: Arc<str> = Arc::from("main (main.rs:5)");
+            state.alloc(0x10, 100, site.clone());
+            state.alloc(0x20, 50, site.clone());
+            state.alloc(0x30, 8, Arc::from("other"));
+            state.free(0x10);
+            // unknown pointer is ignored
+            state.free(0x40);
+        }
+
+        assert_eq!(oracle.live(), (2, 58));
+        let top = oracle.top_sites(1, |stat| stat.live_bytes);
+        assert_eq!(
+            top,
+            vec![SiteStat {
+                site: "main (main.rs:5)".to_string(),
+                allocs: 2,
+                frees: 1,
+                allocated_bytes: 150,
+                live_bytes: 50,
+            }]
+        );
+
+        oracle.reset();
+        let top = oracle.top_sites(1, |stat| stat.live_bytes);
+        assert_eq!(top[0].allocs, 0);
+        assert_eq!(top[0].live_bytes, 50);
+    }
+}
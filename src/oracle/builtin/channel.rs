@@ -0,0 +1,435 @@
+//! Channel traffic oracle: count messages per channel and find channels with growing buffers.
+//!
+//! Std (`mpsc`/`mpmc`) and crossbeam channels are watched at internal `write` and `read`
+//! functions of channel flavors, these functions are called only when a message is actually
+//! sent or received, a channel is identified by a flavor structure address.
+//! Tokio channels are watched at message list `push` and `pop` functions, sent and received
+//! counts are read from list positions (a channel is identified by a list address).
+
+use crate::debugger::variable::select::{VariableSelector, DQE};
+use crate::debugger::variable::VariableIR;
+use crate::debugger::{CreateTransparentBreakpointRequest, Debugger, Error};
+use crate::oracle::builtin::usize_value;
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::style::{AddressView, KeywordView};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
+use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
+use crate::ui::tui::Msg;
+use indexmap::IndexMap;
+use log::warn;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use strum_macros::Display;
+#[cfg(feature = "ui")]
+use tuirealm::Component;
+
+/// How often a channel backlog is sampled.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// A channel is growing if its backlog grows over this number of samples.
+const GROWTH_SAMPLES: usize = 5;
+
+#[derive(Debug, Display, Clone, Copy, PartialEq)]
+pub enum ChannelKind {
+    #[strum(serialize = "std")]
+    Std,
+    #[strum(serialize = "crossbeam")]
+    Crossbeam,
+    #[strum(serialize = "tokio")]
+    Tokio,
+}
+
+/// Message counters of a single channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelStat {
+    /// Channel address.
+    pub addr: usize,
+    pub kind: ChannelKind,
+    pub sent: u64,
+    pub received: u64,
+    /// Backlog samples (oldest first).
+    samples: VecDeque<u64>,
+    last_sample_at: Option<Instant>,
+}
+
+impl ChannelStat {
+    fn new(addr: usize, kind: ChannelKind) -> Self {
+        Self {
+            addr,
+            kind,
+            sent: 0,
+            received: 0,
+            samples: VecDeque::new(),
+            last_sample_at: None,
+        }
+    }
+
+    /// Return number of messages sent but not received yet.
+    pub fn backlog(&self) -> u64 {
+        self.sent.saturating_sub(self.received)
+    }
+
+    /// Return true if a channel backlog does not decrease over last samples
+    /// and increases in total.
+    pub fn is_growing(&self) -> bool {
+        if self.samples.len() < GROWTH_SAMPLES {
+            return false;
+        }
+        let non_decreasing = self
+            .samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .all(|(prev, next)| next >= prev);
+        non_decreasing && self.samples.back() > self.samples.front()
+    }
+
+    fn sample(&mut self, now: Instant) {
+        if self
+            .last_sample_at
+            .is_some_and(|at| now.duration_since(at) < SAMPLE_INTERVAL)
+        {
+            return;
+        }
+        self.last_sample_at = Some(now);
+        if self.samples.len() == GROWTH_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(self.backlog());
+    }
+}
+
+/// [`ChannelOracle`] count messages of std, crossbeam and tokio channels.
+#[derive(Default)]
+pub struct ChannelOracle {
+    channels: Mutex<IndexMap<usize, ChannelStat>>,
+}
+
+impl ChannelOracle {
+    /// Create a new oracle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return all observed channels.
+    pub fn channels(&self) -> Vec<ChannelStat> {
+        self.channels.lock().unwrap().values().cloned().collect()
+    }
+
+    fn update(&self, addr: usize, kind: ChannelKind, f: impl FnOnce(&mut ChannelStat)) {
+        let mut channels = self.channels.lock().unwrap();
+        let stat = channels
+            .entry(addr)
+            .or_insert_with(|| ChannelStat::new(addr, kind));
+        f(stat);
+        stat.sample(Instant::now());
+    }
+
+    /// Called at flavor `write` or `read` function entry, channel address is a first argument.
+    fn on_flavor_call(dbg: &Debugger) -> Result<usize, Error> {
+        Ok(dbg.get_register_value("rdi")? as usize)
+    }
+
+    /// Read an address and usize field of a function argument.
+    fn read_list_position(
+        dbg: &Debugger,
+        arg: &str,
+        field: &str,
+    ) -> Result<Option<(usize, u64)>, Error> {
+        let arg_expr = DQE::Variable(VariableSelector::Name {
+            var_name: arg.to_string(),
+            only_local: true,
+        });
+        let Some(VariableIR::Pointer(ptr)) = dbg.read_argument(arg_expr.clone())?.pop() else {
+            return Ok(None);
+        };
+        let Some(addr) = ptr.value else {
+            return Ok(None);
+        };
+        let field_value = dbg
+            .read_argument(DQE::Field(
+                DQE::Deref(arg_expr.boxed()).boxed(),
+                field.to_string(),
+            ))?
+            .pop();
+        Ok(field_value
+            .as_ref()
+            .and_then(usize_value)
+            .map(|position| (addr as usize, position as u64)))
+    }
+
+    fn on_tokio_push(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        if let Some((addr, tail)) = Self::read_list_position(dbg, "self", "tail_position")? {
+            // message is not pushed yet
+            self.update(addr, ChannelKind::Tokio, |stat| stat.sent = tail + 1);
+        }
+        Ok(())
+    }
+
+    fn on_tokio_pop(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let Some((tx_addr, tail)) = Self::read_list_position(dbg, "tx", "tail_position")? else {
+            return Ok(());
+        };
+        let index = dbg
+            .read_argument(DQE::Field(
+                DQE::Deref(
+                    DQE::Variable(VariableSelector::Name {
+                        var_name: "self".to_string(),
+                        only_local: true,
+                    })
+                    .boxed(),
+                )
+                .boxed(),
+                "index".to_string(),
+            ))?
+            .pop();
+        if let Some(index) = index.as_ref().and_then(usize_value) {
+            self.update(tx_addr, ChannelKind::Tokio, |stat| {
+                stat.sent = tail;
+                stat.received = index as u64;
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Oracle for ChannelOracle {
+    fn name(&self) -> &'static str {
+        "channel"
+    }
+
+    fn ready_for_install(&self, dbg: &Debugger) -> bool {
+        let has_symbols = !dbg
+            .get_symbols(r"^(std::sync::mpmc|crossbeam_channel|tokio::sync::mpsc)::")
+            .unwrap_or_default()
+            .is_empty();
+        if has_symbols {
+            self.channels.lock().unwrap().clear();
+        }
+        has_symbols
+    }
+
+    fn watch_points(self: Arc<Self>) -> Vec<CreateTransparentBreakpointRequest> {
+        let flavors = [
+            (
+                r"^std::sync::mpmc::(array|list|zero)::Channel<T>::",
+                ChannelKind::Std,
+            ),
+            (
+                r"^crossbeam_channel::flavors::(array|list|zero)::Channel<T>::",
+                ChannelKind::Crossbeam,
+            ),
+        ];
+
+        let mut watch_points = vec![];
+        for (prefix, kind) in flavors {
+            for (func, is_send) in [("write", true), ("read", false)] {
+                let oracle = self.clone();
+                let handler = move |dbg: &mut Debugger| match Self::on_flavor_call(dbg) {
+                    Ok(addr) => oracle.update(addr, kind, |stat| {
+                        if is_send {
+                            stat.sent += 1;
+                        } else {
+                            stat.received += 1;
+                        }
+                    }),
+                    Err(e) => warn!(target: "channel oracle", "{kind} channel {func}: {e}"),
+                };
+                watch_points.push(CreateTransparentBreakpointRequest::symbol(
+                    format!("{prefix}{func}::"),
+                    handler,
+                ));
+            }
+        }
+
+        let oracle = self.clone();
+        let push_handler = move |dbg: &mut Debugger| {
+            if let Err(e) = oracle.on_tokio_push(dbg) {
+                warn!(target: "channel oracle", "tokio channel send: {e}")
+            }
+        };
+        watch_points.push(CreateTransparentBreakpointRequest::function(
+            "tokio::sync::mpsc::list::Tx::push",
+            push_handler,
+        ));
+
+        let oracle = self.clone();
+        let pop_handler = move |dbg: &mut Debugger| {
+            if let Err(e) = oracle.on_tokio_pop(dbg) {
+                warn!(target: "channel oracle", "tokio channel receive: {e}")
+            }
+        };
+        watch_points.push(CreateTransparentBreakpointRequest::function(
+            "tokio::sync::mpsc::list::Rx::pop",
+            pop_handler,
+        ));
+
+        watch_points
+    }
+}
+
+#[cfg(feature = "ui")]
+impl ConsolePlugin for ChannelOracle {
+    fn print(&self, printer: &ExternalPrinter, subcommand: Option<&str>) {
+        let mut channels = self.channels();
+        if subcommand == Some("growing") {
+            channels.retain(|stat| stat.is_growing());
+        }
+        channels.sort_by_key(|stat| std::cmp::Reverse(stat.backlog()));
+
+        printer.println(format!("{} channels\n", KeywordView::from(channels.len())));
+        if channels.is_empty() {
+            return;
+        }
+
+        printer.println(format!(
+            "{:<18} {:<10} {:<10} {:<10} {:<10}",
+            "channel", "kind", "sent", "received", "backlog"
+        ));
+        for stat in channels {
+            let growing = if stat.is_growing() { "growing" } else { "" };
+            printer.println(format!(
+                "{:<18} {:<10} {:<10} {:<10} {:<10} {growing}",
+                AddressView::from(format!("{:#X}", stat.addr)),
+                stat.kind.to_string(),
+                stat.sent,
+                stat.received,
+                stat.backlog(),
+            ));
+        }
+    }
+
+    fn help(&self) -> &str {
+        "channel - messages of std, crossbeam and tokio channels, \
+        `oracle channel growing` shows only channels with a growing backlog"
+    }
+}
+
+#[cfg(feature = "ui")]
+impl TuiPlugin for ChannelOracle {
+    fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
+        Box::new(tui::ChannelComponent::new(self))
+    }
+}
+
+#[cfg(feature = "ui")]
+pub mod tui {
+    use crate::oracle::builtin::channel::ChannelOracle;
+    use crate::ui::tui::app::port::UserEvent;
+    use crate::ui::tui::Msg;
+    use std::sync::Arc;
+    use tui_realm_stdlib::Table;
+    use tuirealm::command::{Cmd, Direction};
+    use tuirealm::event::{Key, KeyEvent};
+    use tuirealm::props::{Alignment, BorderType, Borders, Color, Style, TableBuilder, TextSpan};
+    use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent};
+
+    #[derive(MockComponent)]
+    pub struct ChannelComponent {
+        component: Table,
+        oracle: Arc<ChannelOracle>,
+    }
+
+    impl ChannelComponent {
+        pub fn new(oracle: Arc<ChannelOracle>) -> Self {
+            let component = Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(Color::LightYellow),
+                )
+                .title("Channels", Alignment::Center)
+                .inactive(Style::default().fg(Color::Gray))
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("▶")
+                .rewind(true)
+                .step(4)
+                .widths(&[20, 12, 12, 12, 12])
+                .headers(&["Channel", "Kind", "Sent", "Received", "Backlog"]);
+
+            let mut this = Self { component, oracle };
+            this.refresh_table();
+            this
+        }
+
+        fn refresh_table(&mut self) {
+            let mut table = TableBuilder::default();
+            let mut channels = self.oracle.channels();
+            channels.sort_by_key(|stat| std::cmp::Reverse(stat.backlog()));
+            if channels.is_empty() {
+                for _ in 0..5 {
+                    table.add_col(TextSpan::from(""));
+                }
+                table.add_row();
+            }
+            for stat in channels {
+                let backlog_fg = if stat.is_growing() {
+                    Color::Red
+                } else {
+                    Color::Reset
+                };
+                table
+                    .add_col(TextSpan::from(format!("{:#X}", stat.addr)).fg(Color::LightBlue))
+                    .add_col(TextSpan::from(stat.kind.to_string()))
+                    .add_col(TextSpan::from(stat.sent.to_string()))
+                    .add_col(TextSpan::from(stat.received.to_string()))
+                    .add_col(TextSpan::from(stat.backlog().to_string()).fg(backlog_fg))
+                    .add_row();
+            }
+            self.component
+                .attr(Attribute::Content, AttrValue::Table(table.build()));
+        }
+    }
+
+    impl Component<Msg, UserEvent> for ChannelComponent {
+        fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+            match ev {
+                Event::Keyboard(KeyEvent {
+                    code: Key::Down, ..
+                }) => {
+                    self.perform(Cmd::Move(Direction::Down));
+                }
+                Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                    self.perform(Cmd::Move(Direction::Up));
+                }
+                Event::Tick => {
+                    self.refresh_table();
+                }
+                _ => {}
+            }
+
+            Some(Msg::None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_growing_backlog() {
+        let mut stat = ChannelStat::new(0x10, ChannelKind::Std);
+        let mut now = Instant::now();
+        for i in 0..GROWTH_SAMPLES as u64 {
+            stat.sent += 2;
+            stat.received += 1;
+            stat.sample(now);
+            // sample is ignored, too early
+            stat.sample(now);
+            now += SAMPLE_INTERVAL;
+            assert_eq!(stat.backlog(), i + 1);
+        }
+        assert!(stat.is_growing());
+
+        stat.received = stat.sent;
+        stat.sample(now);
+        assert!(!stat.is_growing());
+    }
+}
@@ -0,0 +1,1051 @@
+//! Database oracle: log of executed SQL statements with parameters and durations.
+//!
+//! Supported drivers:
+//! - sqlx (any backend), statements are read from `sqlx_core::logger::QueryLogger` when it
+//!   finishes, so SQL, duration and row count are known;
+//! - SQLite C API (rusqlite, diesel and sqlx SQLite backends with bundled SQLite), statements
+//!   are read at `sqlite3_prepare_v2/v3`, parameters at `sqlite3_bind_*` and duration is a sum
+//!   of `sqlite3_step` calls until statement is reset;
+//! - postgres and tokio-postgres, statements are read from `postgres_protocol` frontend
+//!   messages (`Parse`, `Bind` and `Query`), duration is unknown because execution
+//!   is asynchronous.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::variable::select::{VariableSelector, DQE};
+use crate::debugger::variable::{SpecializedVariableIR, SupportedScalar, VariableIR};
+use crate::debugger::{CreateTransparentBreakpointRequest, Debugger, Error};
+use crate::oracle::builtin::{find_member, return_address};
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::style::KeywordView;
+#[cfg(feature = "ui")]
+use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
+use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
+use crate::ui::tui::Msg;
+use log::warn;
+use nix::libc;
+use nix::unistd::Pid;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use strum_macros::Display;
+#[cfg(feature = "ui")]
+use tuirealm::Component;
+
+/// Max length of SQL statement or parameter that oracle reads from debugee memory.
+const MAX_SQL_LEN: usize = 8 * 1024;
+
+/// Queries longer than this are considered slow by default.
+#[cfg(feature = "ui")]
+const DEFAULT_SLOW_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// Result code of `sqlite3_step` when a new row is ready.
+const SQLITE_ROW: u64 = 100;
+
+/// Symbols of supported drivers, oracle is installed if any of them present in a debugee.
+const DRIVER_SYMBOLS: &str = r"^(sqlx_core::logger::QueryLogger.*::finish|sqlite3_step$|postgres_protocol::message::frontend::)";
+
+#[derive(Debug, Display, Clone, Copy, PartialEq)]
+pub enum Driver {
+    #[strum(serialize = "sqlx")]
+    Sqlx,
+    #[strum(serialize = "sqlite")]
+    Sqlite,
+    #[strum(serialize = "postgres")]
+    Postgres,
+}
+
+/// Executed SQL statement.
+#[derive(Debug, Clone)]
+pub struct Query {
+    pub driver: Driver,
+    pub sql: String,
+    /// Rendered parameters (in order of placeholders), empty if unknown.
+    pub params: Vec<String>,
+    /// Execution time, `None` if unknown.
+    pub duration: Option<Duration>,
+    /// Returned (or affected) rows, `None` if unknown.
+    pub rows: Option<u64>,
+    pub thread_pid: Pid,
+}
+
+impl Query {
+    fn new(driver: Driver, sql: String, thread_pid: Pid) -> Self {
+        Self {
+            driver,
+            sql,
+            params: vec![],
+            duration: None,
+            rows: None,
+            thread_pid,
+        }
+    }
+
+    /// Return true if query takes more time than threshold.
+    pub fn is_slow(&self, threshold: Duration) -> bool {
+        self.duration.is_some_and(|duration| duration > threshold)
+    }
+}
+
+/// Prepared SQLite statement.
+#[derive(Default)]
+struct SqliteStmt {
+    sql: String,
+    params: BTreeMap<i32, String>,
+    /// Index of a current execution in a log, `None` if statement is not executing.
+    execution: Option<usize>,
+}
+
+#[derive(Default)]
+struct State {
+    log: Vec<Query>,
+    /// Log length at previous and last debugee stops.
+    stops: (usize, usize),
+    /// Postgres prepared statements: name -> SQL.
+    pg_statements: HashMap<String, String>,
+    /// SQLite statements: statement pointer -> statement.
+    sqlite_stmts: HashMap<u64, SqliteStmt>,
+    /// `sqlite3_prepare_*` calls in progress: SQL and an address of output statement pointer.
+    prepares: HashMap<Pid, Vec<(String, u64)>>,
+    /// `sqlite3_step` calls in progress: statement pointer and start time.
+    steps: HashMap<Pid, Vec<(u64, Instant)>>,
+    /// Return addresses where breakpoints are already installed.
+    return_points: HashSet<RelocatedAddress>,
+}
+
+impl State {
+    /// Postgres statement is prepared with a name.
+    fn pg_parse(&mut self, name: String, sql: String) {
+        self.pg_statements.insert(name, sql);
+    }
+
+    /// Postgres statement prepared earlier is executed.
+    fn pg_bind(&mut self, name: &str, pid: Pid) {
+        let sql = self
+            .pg_statements
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| format!("<statement {name}>"));
+        self.log.push(Query::new(Driver::Postgres, sql, pid));
+    }
+
+    /// `sqlite3_prepare_*` is called in a thread.
+    fn sqlite_prepare_begin(&mut self, pid: Pid, sql: String, stmt_ptr_addr: u64) {
+        self.prepares
+            .entry(pid)
+            .or_default()
+            .push((sql, stmt_ptr_addr));
+    }
+
+    /// `sqlite3_prepare_*` returns in a thread, return SQL and an address of output
+    /// statement pointer of the innermost call.
+    fn sqlite_prepare_end(&mut self, pid: Pid) -> Option<(String, u64)> {
+        self.prepares.get_mut(&pid).and_then(|s| s.pop())
+    }
+
+    /// Remember a prepared statement, null statement means that prepare failed.
+    fn sqlite_add_stmt(&mut self, stmt_ptr: u64, sql: &str) {
+        if stmt_ptr != 0 {
+            self.sqlite_stmts.insert(
+                stmt_ptr,
+                SqliteStmt {
+                    sql: sql.trim().to_string(),
+                    ..SqliteStmt::default()
+                },
+            );
+        }
+    }
+
+    fn sqlite_bind(&mut self, stmt_ptr: u64, idx: i32, value: String) {
+        if let Some(stmt) = self.sqlite_stmts.get_mut(&stmt_ptr) {
+            stmt.params.insert(idx, value);
+        }
+    }
+
+    /// `sqlite3_step` is called in a thread, first step after statement reset starts a new
+    /// query. Return false if statement is unknown.
+    fn sqlite_step_begin(&mut self, pid: Pid, stmt_ptr: u64, now: Instant) -> bool {
+        let Some(stmt) = self.sqlite_stmts.get_mut(&stmt_ptr) else {
+            return false;
+        };
+        if stmt.execution.is_none() {
+            let mut query = Query::new(Driver::Sqlite, stmt.sql.clone(), pid);
+            query.params = stmt.params.values().cloned().collect();
+            query.duration = Some(Duration::ZERO);
+            query.rows = Some(0);
+            self.log.push(query);
+            stmt.execution = Some(self.log.len() - 1);
+        }
+        self.steps.entry(pid).or_default().push((stmt_ptr, now));
+        true
+    }
+
+    /// `sqlite3_step` returns in a thread with a result code `rc`.
+    fn sqlite_step_end(&mut self, pid: Pid, rc: u64, now: Instant) {
+        let Some((stmt_ptr, started_at)) = self.steps.get_mut(&pid).and_then(|s| s.pop()) else {
+            return;
+        };
+        let Some(idx) = self
+            .sqlite_stmts
+            .get(&stmt_ptr)
+            .and_then(|stmt| stmt.execution)
+        else {
+            return;
+        };
+        if let Some(query) = self.log.get_mut(idx) {
+            let elapsed = now.saturating_duration_since(started_at);
+            query.duration = Some(query.duration.unwrap_or_default() + elapsed);
+            if rc == SQLITE_ROW {
+                query.rows = Some(query.rows.unwrap_or_default() + 1);
+            }
+        }
+    }
+
+    /// Statement is reset (or finalized if `finalize` is true).
+    fn sqlite_stmt_done(&mut self, stmt_ptr: u64, finalize: bool) {
+        if finalize {
+            self.sqlite_stmts.remove(&stmt_ptr);
+        } else if let Some(stmt) = self.sqlite_stmts.get_mut(&stmt_ptr) {
+            stmt.execution = None;
+        }
+    }
+
+    fn sqlite_clear_bindings(&mut self, stmt_ptr: u64) {
+        if let Some(stmt) = self.sqlite_stmts.get_mut(&stmt_ptr) {
+            stmt.params.clear();
+        }
+    }
+}
+
+fn arg(name: &str) -> DQE {
+    DQE::Variable(VariableSelector::Name {
+        var_name: name.to_string(),
+        only_local: true,
+    })
+}
+
+fn field(expr: DQE, name: &str) -> DQE {
+    DQE::Field(expr.boxed(), name.to_string())
+}
+
+/// Return value of a `&str` or `String` variable.
+fn str_value(var: &VariableIR) -> Option<String> {
+    match var {
+        VariableIR::Specialized(SpecializedVariableIR::Str {
+            string: Some(s), ..
+        }) => Some(s.value.clone()),
+        VariableIR::Specialized(SpecializedVariableIR::String {
+            string: Some(s), ..
+        }) => Some(s.value.clone()),
+        _ => None,
+    }
+}
+
+/// Return value of an integer scalar, wrappers are unfolded by first member.
+fn int_value(var: &VariableIR) -> Option<i64> {
+    match var {
+        VariableIR::Scalar(scalar) => match scalar.value {
+            Some(SupportedScalar::I64(value)) => Some(value),
+            Some(SupportedScalar::U64(value)) => Some(value as i64),
+            Some(SupportedScalar::U32(value)) => Some(value as i64),
+            Some(SupportedScalar::Usize(value)) => Some(value as i64),
+            _ => None,
+        },
+        VariableIR::Struct(structure) => int_value(structure.members.first()?),
+        _ => None,
+    }
+}
+
+/// Return time elapsed since a debugee `std::time::Instant`. Instant is a monotonic clock
+/// value, so it's comparable with a monotonic clock of the debugger.
+fn elapsed_since(instant: &VariableIR) -> Option<Duration> {
+    let sec = int_value(find_member(instant, "tv_sec")?)?;
+    let nsec = int_value(find_member(instant, "tv_nsec")?)?;
+    let start = Duration::new(sec as u64, nsec as u32);
+
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `now` is a valid timespec
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) } != 0 {
+        return None;
+    }
+    let now = Duration::new(now.tv_sec as u64, now.tv_nsec as u32);
+    now.checked_sub(start)
+}
+
+/// Read a C string, `len` is a string length or negative value for NUL-terminated string.
+///
+/// # Arguments
+///
+/// * `read_memory`: read a number of bytes from debugee memory at address
+fn read_c_string(
+    read_memory: impl Fn(usize, usize) -> Option<Vec<u8>>,
+    ptr: u64,
+    len: i32,
+) -> Option<String> {
+    if ptr == 0 {
+        return None;
+    }
+    let bytes = if len >= 0 {
+        read_memory(ptr as usize, (len as usize).min(MAX_SQL_LEN))?
+    } else {
+        // read by chunks, a string may be at the end of a mapped region
+        const CHUNK: usize = 256;
+        let mut bytes = vec![];
+        while bytes.len() < MAX_SQL_LEN {
+            let chunk = read_memory(ptr as usize + bytes.len(), CHUNK)?;
+            match chunk.iter().position(|&b| b == 0) {
+                Some(nul) => {
+                    bytes.extend_from_slice(&chunk[..nul]);
+                    break;
+                }
+                None => bytes.extend_from_slice(&chunk),
+            }
+        }
+        bytes
+    };
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Kind of SQLite parameter binding function (`sqlite3_bind_*`).
+#[derive(Clone, Copy)]
+enum SqliteBind {
+    Int,
+    Int64,
+    Double,
+    Text,
+    Blob,
+    Null,
+}
+
+/// [`DbOracle`] log executed SQL statements.
+#[derive(Default)]
+pub struct DbOracle {
+    state: Mutex<State>,
+}
+
+impl DbOracle {
+    /// Create a new oracle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return all executed queries.
+    pub fn log(&self) -> Vec<Query> {
+        self.state.lock().unwrap().log.clone()
+    }
+
+    /// Return queries executed between two last debugee stops.
+    pub fn since_last_stop(&self) -> Vec<Query> {
+        let state = self.state.lock().unwrap();
+        let (prev, last) = state.stops;
+        state.log[prev..last].to_vec()
+    }
+
+    /// Called at `QueryLogger::finish`, a query is done.
+    fn on_sqlx_finish(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let logger = DQE::Deref(arg("self").boxed());
+        let Some(sql) = dbg
+            .read_argument(field(logger.clone(), "sql"))?
+            .pop()
+            .as_ref()
+            .and_then(str_value)
+        else {
+            return Ok(());
+        };
+
+        let mut query = Query::new(Driver::Sqlx, sql, dbg.exploration_ctx().pid_on_focus());
+        query.duration = dbg
+            .read_argument(field(logger.clone(), "start"))?
+            .pop()
+            .as_ref()
+            .and_then(elapsed_since);
+        let rows = |name: &str| -> Result<Option<i64>, Error> {
+            Ok(dbg
+                .read_argument(field(logger.clone(), name))?
+                .pop()
+                .as_ref()
+                .and_then(int_value))
+        };
+        query.rows = match (rows("rows_returned")?, rows("rows_affected")?) {
+            (Some(returned), Some(affected)) => Some(returned.max(affected) as u64),
+            (returned, affected) => returned.or(affected).map(|rows| rows as u64),
+        };
+
+        self.state.lock().unwrap().log.push(query);
+        Ok(())
+    }
+
+    /// Called at `postgres_protocol::message::frontend::parse`, a statement is prepared.
+    fn on_pg_parse(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let name = dbg.read_argument(arg("name"))?.pop();
+        let sql = dbg.read_argument(arg("query"))?.pop();
+        if let (Some(name), Some(sql)) = (
+            name.as_ref().and_then(str_value),
+            sql.as_ref().and_then(str_value),
+        ) {
+            self.state.lock().unwrap().pg_parse(name, sql);
+        }
+        Ok(())
+    }
+
+    /// Called at `postgres_protocol::message::frontend::bind`, a prepared statement
+    /// is executed.
+    fn on_pg_bind(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let Some(name) = dbg
+            .read_argument(arg("statement"))?
+            .pop()
+            .as_ref()
+            .and_then(str_value)
+        else {
+            return Ok(());
+        };
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        self.state.lock().unwrap().pg_bind(&name, pid);
+        Ok(())
+    }
+
+    /// Called at `postgres_protocol::message::frontend::query`, a simple query is executed.
+    fn on_pg_query(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        if let Some(sql) = dbg
+            .read_argument(arg("query"))?
+            .pop()
+            .as_ref()
+            .and_then(str_value)
+        {
+            let pid = dbg.exploration_ctx().pid_on_focus();
+            let query = Query::new(Driver::Postgres, sql, pid);
+            self.state.lock().unwrap().log.push(query);
+        }
+        Ok(())
+    }
+
+    /// Set a breakpoint at current function return address (if not set yet).
+    fn ensure_return_point(
+        self: &Arc<Self>,
+        dbg: &mut Debugger,
+        ret_addr: RelocatedAddress,
+        handler: fn(&Self, &mut Debugger) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        if !self.state.lock().unwrap().return_points.insert(ret_addr) {
+            return Ok(());
+        }
+        let oracle = self.clone();
+        dbg.set_transparent_breakpoint(CreateTransparentBreakpointRequest::address(
+            ret_addr,
+            move |dbg: &mut Debugger| {
+                if let Err(e) = handler(&oracle, dbg) {
+                    warn!(target: "db oracle", "sqlite function result: {e}")
+                }
+            },
+        ))
+    }
+
+    /// Called at `sqlite3_prepare_v2` or `sqlite3_prepare_v3` entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `stmt_reg`: register with an address of output statement pointer
+    fn on_sqlite_prepare(
+        self: &Arc<Self>,
+        dbg: &mut Debugger,
+        stmt_reg: &str,
+    ) -> Result<(), Error> {
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        let sql_ptr = dbg.get_register_value("rsi")?;
+        let sql_len = dbg.get_register_value("rdx")? as u32 as i32;
+        let stmt_ptr_addr = dbg.get_register_value(stmt_reg)?;
+        let ret_addr = return_address(dbg)?;
+
+        let sql = read_c_string(
+            |addr, len| dbg.read_memory(addr, len).ok(),
+            sql_ptr,
+            sql_len,
+        )
+        .unwrap_or_default();
+        self.state
+            .lock()
+            .unwrap()
+            .sqlite_prepare_begin(pid, sql, stmt_ptr_addr);
+
+        self.ensure_return_point(dbg, ret_addr, Self::on_sqlite_prepare_return)
+    }
+
+    fn on_sqlite_prepare_return(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        let mut state = self.state.lock().unwrap();
+        let Some((sql, stmt_ptr_addr)) = state.sqlite_prepare_end(pid) else {
+            return Ok(());
+        };
+        let stmt = dbg.read_memory(stmt_ptr_addr as usize, 8)?;
+        let stmt = u64::from_ne_bytes(stmt.try_into().expect("8 bytes are read"));
+        state.sqlite_add_stmt(stmt, &sql);
+        Ok(())
+    }
+
+    fn on_sqlite_bind(&self, dbg: &mut Debugger, bind: SqliteBind) -> Result<(), Error> {
+        let stmt = dbg.get_register_value("rdi")?;
+        let idx = dbg.get_register_value("rsi")? as u32 as i32;
+        let value = match bind {
+            SqliteBind::Int => (dbg.get_register_value("rdx")? as u32 as i32).to_string(),
+            SqliteBind::Int64 => (dbg.get_register_value("rdx")? as i64).to_string(),
+            // passed through xmm0
+            SqliteBind::Double => "<double>".to_string(),
+            SqliteBind::Text => {
+                let ptr = dbg.get_register_value("rdx")?;
+                let len = dbg.get_register_value("rcx")? as u32 as i32;
+                match read_c_string(|addr, len| dbg.read_memory(addr, len).ok(), ptr, len) {
+                    Some(text) => format!("{text:?}"),
+                    None => "NULL".to_string(),
+                }
+            }
+            SqliteBind::Blob => {
+                let len = dbg.get_register_value("rcx")? as u32 as i32;
+                format!("<blob {len} bytes>")
+            }
+            SqliteBind::Null => "NULL".to_string(),
+        };
+
+        self.state.lock().unwrap().sqlite_bind(stmt, idx, value);
+        Ok(())
+    }
+
+    fn on_sqlite_step(self: &Arc<Self>, dbg: &mut Debugger) -> Result<(), Error> {
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        let stmt_ptr = dbg.get_register_value("rdi")?;
+        let ret_addr = return_address(dbg)?;
+
+        let known_stmt =
+            self.state
+                .lock()
+                .unwrap()
+                .sqlite_step_begin(pid, stmt_ptr, Instant::now());
+        if !known_stmt {
+            return Ok(());
+        }
+
+        self.ensure_return_point(dbg, ret_addr, Self::on_sqlite_step_return)
+    }
+
+    fn on_sqlite_step_return(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        let rc = dbg.get_register_value("rax")? as u32 as u64;
+
+        self.state
+            .lock()
+            .unwrap()
+            .sqlite_step_end(pid, rc, Instant::now());
+        Ok(())
+    }
+
+    /// Called at `sqlite3_reset`, `sqlite3_clear_bindings` and `sqlite3_finalize`.
+    fn on_sqlite_stmt_done(&self, dbg: &mut Debugger, finalize: bool) -> Result<(), Error> {
+        let stmt_ptr = dbg.get_register_value("rdi")?;
+        self.state
+            .lock()
+            .unwrap()
+            .sqlite_stmt_done(stmt_ptr, finalize);
+        Ok(())
+    }
+
+    fn on_sqlite_clear_bindings(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let stmt_ptr = dbg.get_register_value("rdi")?;
+        self.state.lock().unwrap().sqlite_clear_bindings(stmt_ptr);
+        Ok(())
+    }
+}
+
+impl Oracle for DbOracle {
+    fn name(&self) -> &'static str {
+        "db"
+    }
+
+    fn ready_for_install(&self, dbg: &Debugger) -> bool {
+        let has_symbols = !dbg
+            .get_symbols(DRIVER_SYMBOLS)
+            .unwrap_or_default()
+            .is_empty();
+        if has_symbols {
+            // breakpoints of a previous debugee process are gone
+            *self.state.lock().unwrap() = State::default();
+        }
+        has_symbols
+    }
+
+    fn watch_points(self: Arc<Self>) -> Vec<CreateTransparentBreakpointRequest> {
+        let mut watch_points = vec![];
+
+        type Handler = fn(&DbOracle, &mut Debugger) -> Result<(), Error>;
+        let functions: [(&str, Handler); 4] = [
+            (
+                "sqlx_core::logger::QueryLogger::finish",
+                Self::on_sqlx_finish,
+            ),
+            (
+                "postgres_protocol::message::frontend::parse",
+                Self::on_pg_parse,
+            ),
+            (
+                "postgres_protocol::message::frontend::bind",
+                Self::on_pg_bind,
+            ),
+            (
+                "postgres_protocol::message::frontend::query",
+                Self::on_pg_query,
+            ),
+        ];
+        for (function, handler) in functions {
+            let oracle = self.clone();
+            watch_points.push(CreateTransparentBreakpointRequest::function(
+                function,
+                move |dbg: &mut Debugger| {
+                    if let Err(e) = handler(&oracle, dbg) {
+                        warn!(target: "db oracle", "{function}: {e}")
+                    }
+                },
+            ));
+        }
+
+        for (symbol, stmt_reg) in [
+            ("^sqlite3_prepare_v2$", "rcx"),
+            ("^sqlite3_prepare_v3$", "r8"),
+        ] {
+            let oracle = self.clone();
+            watch_points.push(CreateTransparentBreakpointRequest::symbol(
+                symbol,
+                move |dbg: &mut Debugger| {
+                    if let Err(e) = oracle.on_sqlite_prepare(dbg, stmt_reg) {
+                        warn!(target: "db oracle", "sqlite prepare: {e}")
+                    }
+                },
+            ));
+        }
+
+        let binds = [
+            ("^sqlite3_bind_int$", SqliteBind::Int),
+            ("^sqlite3_bind_int64$", SqliteBind::Int64),
+            ("^sqlite3_bind_double$", SqliteBind::Double),
+            ("^sqlite3_bind_text$", SqliteBind::Text),
+            ("^sqlite3_bind_blob$", SqliteBind::Blob),
+            ("^sqlite3_bind_null$", SqliteBind::Null),
+        ];
+        for (symbol, bind) in binds {
+            let oracle = self.clone();
+            watch_points.push(CreateTransparentBreakpointRequest::symbol(
+                symbol,
+                move |dbg: &mut Debugger| {
+                    if let Err(e) = oracle.on_sqlite_bind(dbg, bind) {
+                        warn!(target: "db oracle", "sqlite bind: {e}")
+                    }
+                },
+            ));
+        }
+
+        let oracle = self.clone();
+        watch_points.push(CreateTransparentBreakpointRequest::symbol(
+            "^sqlite3_step$",
+            move |dbg: &mut Debugger| {
+                if let Err(e) = oracle.on_sqlite_step(dbg) {
+                    warn!(target: "db oracle", "sqlite step: {e}")
+                }
+            },
+        ));
+
+        for (symbol, finalize) in [("^sqlite3_reset$", false), ("^sqlite3_finalize$", true)] {
+            let oracle = self.clone();
+            watch_points.push(CreateTransparentBreakpointRequest::symbol(
+                symbol,
+                move |dbg: &mut Debugger| {
+                    if let Err(e) = oracle.on_sqlite_stmt_done(dbg, finalize) {
+                        warn!(target: "db oracle", "sqlite statement: {e}")
+                    }
+                },
+            ));
+        }
+
+        let oracle = self.clone();
+        watch_points.push(CreateTransparentBreakpointRequest::symbol(
+            "^sqlite3_clear_bindings$",
+            move |dbg: &mut Debugger| {
+                if let Err(e) = oracle.on_sqlite_clear_bindings(dbg) {
+                    warn!(target: "db oracle", "sqlite clear bindings: {e}")
+                }
+            },
+        ));
+
+        watch_points
+    }
+
+    fn on_stop(&self, _dbg: &Debugger) {
+        let mut state = self.state.lock().unwrap();
+        state.stops = (state.stops.1, state.log.len());
+    }
+}
+
+#[cfg(feature = "ui")]
+fn format_duration(query: &Query) -> String {
+    query
+        .duration
+        .map(|duration| format!("{:.3}ms", duration.as_secs_f64() * 1000.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(feature = "ui")]
+impl ConsolePlugin for DbOracle {
+    fn print(&self, printer: &ExternalPrinter, subcommand: Option<&str>) {
+        let (title, queries) = match subcommand {
+            Some("all") => ("all queries".to_string(), self.log()),
+            Some(sub) if sub.starts_with("slow") => {
+                let threshold = sub
+                    .strip_prefix("slow")
+                    .and_then(|ms| ms.parse().ok())
+                    .map(Duration::from_millis)
+                    .unwrap_or(DEFAULT_SLOW_THRESHOLD);
+                let slow = self
+                    .log()
+                    .into_iter()
+                    .filter(|query| query.is_slow(threshold))
+                    .collect();
+                (
+                    format!("queries slower than {}ms", threshold.as_millis()),
+                    slow,
+                )
+            }
+            _ => (
+                "queries since previous stop".to_string(),
+                self.since_last_stop(),
+            ),
+        };
+
+        printer.println(format!("{}: {title}", KeywordView::from(queries.len())));
+        for query in queries {
+            let params = if query.params.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", query.params.join(", "))
+            };
+            let rows = query
+                .rows
+                .map(|rows| format!(", {rows} rows"))
+                .unwrap_or_default();
+            let slow = if query.is_slow(DEFAULT_SLOW_THRESHOLD) {
+                " (slow)"
+            } else {
+                ""
+            };
+            printer.println(format!(
+                "{:<9} {:>12}{slow} {}{params}{rows}",
+                query.driver,
+                format_duration(&query),
+                KeywordView::from(&query.sql),
+            ));
+        }
+    }
+
+    fn help(&self) -> &str {
+        "db - SQL statements executed since previous stop, `oracle db all` shows all statements, \
+        `oracle db slow` (or `oracle db slow{ms}`, e.g. `slow50`) shows statements slower \
+        than 100ms (or a given threshold)"
+    }
+}
+
+#[cfg(feature = "ui")]
+impl TuiPlugin for DbOracle {
+    fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
+        Box::new(tui::DbComponent::new(self))
+    }
+}
+
+#[cfg(feature = "ui")]
+pub mod tui {
+    use crate::oracle::builtin::db::{format_duration, DbOracle, DEFAULT_SLOW_THRESHOLD};
+    use crate::ui::tui::app::port::UserEvent;
+    use crate::ui::tui::Msg;
+    use std::sync::Arc;
+    use tui_realm_stdlib::Table;
+    use tuirealm::command::{Cmd, Direction};
+    use tuirealm::event::{Key, KeyEvent};
+    use tuirealm::props::{Alignment, BorderType, Borders, Color, Style, TableBuilder, TextSpan};
+    use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent};
+
+    #[derive(MockComponent)]
+    pub struct DbComponent {
+        component: Table,
+        oracle: Arc<DbOracle>,
+    }
+
+    impl DbComponent {
+        pub fn new(oracle: Arc<DbOracle>) -> Self {
+            let component = Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(Color::LightYellow),
+                )
+                .title("SQL queries", Alignment::Center)
+                .inactive(Style::default().fg(Color::Gray))
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("▶")
+                .rewind(true)
+                .step(4)
+                .widths(&[10, 12, 8, 70])
+                .headers(&["Driver", "Duration", "Rows", "SQL"]);
+
+            let mut this = Self { component, oracle };
+            this.refresh_table();
+            this
+        }
+
+        fn refresh_table(&mut self) {
+            let mut table = TableBuilder::default();
+            let log = self.oracle.log();
+            if log.is_empty() {
+                for _ in 0..4 {
+                    table.add_col(TextSpan::from(""));
+                }
+                table.add_row();
+            }
+            // latest queries first
+            for query in log.iter().rev() {
+                let duration_fg = if query.is_slow(DEFAULT_SLOW_THRESHOLD) {
+                    Color::Red
+                } else {
+                    Color::Reset
+                };
+                let mut sql = query.sql.clone();
+                if !query.params.is_empty() {
+                    sql = format!("{sql} [{}]", query.params.join(", "));
+                }
+                table
+                    .add_col(TextSpan::from(query.driver.to_string()))
+                    .add_col(TextSpan::from(format_duration(query)).fg(duration_fg))
+                    .add_col(TextSpan::from(
+                        query
+                            .rows
+                            .map(|rows| rows.to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                    ))
+                    .add_col(TextSpan::from(sql).fg(Color::LightBlue))
+                    .add_row();
+            }
+            self.component
+                .attr(Attribute::Content, AttrValue::Table(table.build()));
+        }
+    }
+
+    impl Component<Msg, UserEvent> for DbComponent {
+        fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+            match ev {
+                Event::Keyboard(KeyEvent {
+                    code: Key::Down, ..
+                }) => {
+                    self.perform(Cmd::Move(Direction::Down));
+                }
+                Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                    self.perform(Cmd::Move(Direction::Up));
+                }
+                Event::Tick => {
+                    self.refresh_table();
+                }
+                _ => {}
+            }
+
+            Some(Msg::None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_slow_query() {
+        let mut query = Query::new(Driver::Sqlite, "SELECT 1".to_string(), Pid::from_raw(1));
+        assert!(!query.is_slow(Duration::ZERO));
+        query.duration = Some(Duration::from_millis(150));
+        assert!(query.is_slow(Duration::from_millis(100)));
+        assert!(!query.is_slow(Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn test_driver_symbols() {
+        let re = regex::Regex::new(DRIVER_SYMBOLS).unwrap();
+
+        struct TestCase {
+            symbol: &'static str,
+            is_driver: bool,
+        }
+        let test_cases = [
+            TestCase {
+                symbol: "sqlx_core::logger::QueryLogger<DB>::finish",
+                is_driver: true,
+            },
+            TestCase {
+                symbol: "sqlite3_step",
+                is_driver: true,
+            },
+            TestCase {
+                symbol: "postgres_protocol::message::frontend::bind",
+                is_driver: true,
+            },
+            TestCase {
+                symbol: "sqlite3_stepper",
+                is_driver: false,
+            },
+            TestCase {
+                symbol: "my_app::sqlite3_step",
+                is_driver: false,
+            },
+            TestCase {
+                symbol: "sqlx_core::logger::QueryLogger<DB>::new",
+                is_driver: false,
+            },
+        ];
+
+        for tc in test_cases {
+            assert_eq!(re.is_match(tc.symbol), tc.is_driver, "{}", tc.symbol);
+        }
+    }
+
+    #[test]
+    fn test_read_c_string() {
+        const BASE: usize = 0x1000;
+        let mut memory = b"SELECT 1\0garbage".to_vec();
+        memory.extend(vec![b'x'; 300]);
+        memory.push(0);
+        let read_memory = |addr: usize, len: usize| {
+            let start = addr.checked_sub(BASE)?;
+            let end = (start + len).min(memory.len());
+            memory.get(start..end).map(|bytes| bytes.to_vec())
+        };
+
+        assert_eq!(read_c_string(read_memory, 0, -1), None);
+        assert_eq!(
+            read_c_string(read_memory, BASE as u64, 6).as_deref(),
+            Some("SELECT")
+        );
+        assert_eq!(
+            read_c_string(read_memory, BASE as u64, -1).as_deref(),
+            Some("SELECT 1")
+        );
+        // a string is longer than a read chunk
+        let long = read_c_string(read_memory, BASE as u64 + 16, -1).unwrap();
+        assert_eq!(long, "x".repeat(300));
+        // unreadable memory
+        assert_eq!(read_c_string(read_memory, 0x10, -1), None);
+    }
+
+    #[test]
+    fn test_sqlite_statement_lifecycle() {
+        let pid = Pid::from_raw(1);
+        let start = Instant::now();
+        let mut state = State::default();
+
+        state.sqlite_prepare_begin(pid, "  SELECT * FROM t WHERE id = ?  ".to_string(), 0x10);
+        assert_eq!(
+            state.sqlite_prepare_end(pid),
+            Some(("  SELECT * FROM t WHERE id = ?  ".to_string(), 0x10))
+        );
+        assert_eq!(state.sqlite_prepare_end(pid), None);
+        state.sqlite_add_stmt(0xA0, "  SELECT * FROM t WHERE id = ?  ");
+        // failed prepare
+        state.sqlite_add_stmt(0, "SELEC");
+        assert_eq!(state.sqlite_stmts.len(), 1);
+
+        state.sqlite_bind(0xA0, 1, "42".to_string());
+        // first execution returns two rows
+        assert!(state.sqlite_step_begin(pid, 0xA0, start));
+        state.sqlite_step_end(pid, SQLITE_ROW, start + Duration::from_millis(10));
+        assert!(state.sqlite_step_begin(pid, 0xA0, start));
+        state.sqlite_step_end(pid, SQLITE_ROW, start + Duration::from_millis(5));
+        assert!(state.sqlite_step_begin(pid, 0xA0, start));
+        state.sqlite_step_end(pid, 101, start + Duration::from_millis(1));
+        // second execution with other parameter
+        state.sqlite_stmt_done(0xA0, false);
+        state.sqlite_clear_bindings(0xA0);
+        state.sqlite_bind(0xA0, 1, "43".to_string());
+        assert!(state.sqlite_step_begin(pid, 0xA0, start));
+        state.sqlite_step_end(pid, 101, start + Duration::from_millis(2));
+        // unknown statement is not logged
+        state.sqlite_stmt_done(0xA0, true);
+        assert!(!state.sqlite_step_begin(pid, 0xA0, start));
+
+        assert_eq!(state.log.len(), 2);
+        assert_eq!(state.log[0].driver, Driver::Sqlite);
+        assert_eq!(state.log[0].sql, "SELECT * FROM t WHERE id = ?");
+        assert_eq!(state.log[0].params, vec!["42".to_string()]);
+        assert_eq!(state.log[0].duration, Some(Duration::from_millis(16)));
+        assert_eq!(state.log[0].rows, Some(2));
+        assert_eq!(state.log[1].params, vec!["43".to_string()]);
+        assert_eq!(state.log[1].duration, Some(Duration::from_millis(2)));
+        assert_eq!(state.log[1].rows, Some(0));
+    }
+
+    #[test]
+    fn test_sqlite_pairing_per_thread() {
+        let (pid1, pid2) = (Pid::from_raw(1), Pid::from_raw(2));
+        let start = Instant::now();
+        let mut state = State::default();
+
+        // prepares in two threads are interleaved
+        state.sqlite_prepare_begin(pid1, "SELECT 1".to_string(), 0x10);
+        state.sqlite_prepare_begin(pid2, "SELECT 2".to_string(), 0x20);
+        state.sqlite_prepare_begin(pid1, "SELECT 3".to_string(), 0x30);
+        assert_eq!(
+            state.sqlite_prepare_end(pid1),
+            Some(("SELECT 3".to_string(), 0x30))
+        );
+        assert_eq!(
+            state.sqlite_prepare_end(pid2),
+            Some(("SELECT 2".to_string(), 0x20))
+        );
+        assert_eq!(
+            state.sqlite_prepare_end(pid1),
+            Some(("SELECT 1".to_string(), 0x10))
+        );
+        state.sqlite_add_stmt(0xA1, "SELECT 1");
+        state.sqlite_add_stmt(0xA2, "SELECT 2");
+
+        // steps in two threads are interleaved, each return matches a step of the same thread
+        assert!(state.sqlite_step_begin(pid1, 0xA1, start));
+        assert!(state.sqlite_step_begin(pid2, 0xA2, start));
+        state.sqlite_step_end(pid1, SQLITE_ROW, start + Duration::from_millis(3));
+        state.sqlite_step_end(pid2, 101, start + Duration::from_millis(7));
+        // return without a step is ignored
+        state.sqlite_step_end(pid2, SQLITE_ROW, start + Duration::from_millis(9));
+
+        assert_eq!(state.log.len(), 2);
+        assert_eq!(state.log[0].sql, "SELECT 1");
+        assert_eq!(state.log[0].thread_pid, pid1);
+        assert_eq!(state.log[0].duration, Some(Duration::from_millis(3)));
+        assert_eq!(state.log[0].rows, Some(1));
+        assert_eq!(state.log[1].sql, "SELECT 2");
+        assert_eq!(state.log[1].thread_pid, pid2);
+        assert_eq!(state.log[1].duration, Some(Duration::from_millis(7)));
+        assert_eq!(state.log[1].rows, Some(0));
+    }
+
+    #[test]
+    fn test_pg_statements() {
+        let pid = Pid::from_raw(1);
+        let mut state = State::default();
+
+        state.pg_parse("s0".to_string(), "SELECT $1".to_string());
+        state.pg_bind("s0", pid);
+        state.pg_bind("s1", pid);
+
+        assert_eq!(state.log.len(), 2);
+        assert_eq!(state.log[0].driver, Driver::Postgres);
+        assert_eq!(state.log[0].sql, "SELECT $1");
+        assert_eq!(state.log[0].duration, None);
+        assert_eq!(state.log[1].sql, "<statement s1>");
+    }
+}
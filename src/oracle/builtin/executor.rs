@@ -0,0 +1,483 @@
+//! Oracle for async-std and smol runtimes.
+//!
+//! Both runtimes run their tasks with `async-task` crate and use `async-io` reactor,
+//! so tasks and reactor are watched in the same way. Runtime is recognized by
+//! symbols present in a debugee binary.
+
+use crate::debugger::variable::render::RenderRepr;
+use crate::debugger::variable::select::{VariableSelector, DQE};
+use crate::debugger::variable::VariableIR;
+use crate::debugger::CreateTransparentBreakpointRequest;
+use crate::debugger::{Debugger, Error};
+use crate::oracle::builtin::usize_value;
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::style::KeywordView;
+#[cfg(feature = "ui")]
+use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
+use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
+use crate::ui::tui::Msg;
+use chrono::Duration;
+use indexmap::IndexMap;
+use log::warn;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use strum_macros::Display;
+#[cfg(feature = "ui")]
+use tuirealm::Component;
+
+/// Runtime built on top of `async-task` and `async-io`.
+#[derive(Debug, Display, Clone, Copy, PartialEq)]
+pub enum Runtime {
+    #[strum(serialize = "async-std")]
+    AsyncStd,
+    #[strum(serialize = "smol")]
+    Smol,
+}
+
+#[derive(Debug, Display, Clone, Copy, PartialEq)]
+enum State {
+    Scheduled,
+    Running,
+    Idle,
+    Completed,
+    Closed,
+}
+
+impl State {
+    fn from_raw(state: usize) -> Self {
+        // list of async-task states
+        const SCHEDULED: usize = 1 << 0;
+        const RUNNING: usize = 1 << 1;
+        const COMPLETED: usize = 1 << 2;
+        const CLOSED: usize = 1 << 3;
+
+        if state & CLOSED == CLOSED {
+            State::Closed
+        } else if state & RUNNING == RUNNING {
+            State::Running
+        } else if state & COMPLETED == COMPLETED {
+            State::Completed
+        } else if state & SCHEDULED == SCHEDULED {
+            State::Scheduled
+        } else {
+            State::Idle
+        }
+    }
+}
+
+// task state is shown only by user interfaces
+#[cfg_attr(not(feature = "ui"), allow(dead_code))]
+#[derive(Clone)]
+struct Task {
+    id: u64,
+    polls: u64,
+    created_at: Instant,
+    state: State,
+    dropped_at: Option<Instant>,
+}
+
+impl Task {
+    #[cfg_attr(not(feature = "ui"), allow(dead_code))]
+    fn task_time(&self) -> Duration {
+        let end = self.dropped_at.unwrap_or_else(Instant::now);
+        Duration::from_std(end.duration_since(self.created_at)).expect("infallible")
+    }
+}
+
+/// State of `async-io` reactor.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReactorState {
+    /// Number of registered I/O sources.
+    pub sources: u64,
+    /// Number of registered timers.
+    pub timers: u64,
+    /// Number of reactor ticks (waits for I/O events).
+    pub ticks: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    runtime: Option<Runtime>,
+    /// Tasks by their addresses, a task is recognized at first poll.
+    tasks: IndexMap<usize, Task>,
+    next_id: u64,
+    reactor: ReactorState,
+}
+
+/// [`ExecutorOracle`] collect and represent tasks and reactor state of async-std or smol runtime.
+#[derive(Default)]
+pub struct ExecutorOracle {
+    inner: Mutex<Inner>,
+}
+
+impl ExecutorOracle {
+    /// Create a new oracle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return runtime recognized in a debugee (if exists).
+    pub fn runtime(&self) -> Option<Runtime> {
+        self.inner.lock().unwrap().runtime
+    }
+
+    /// Return current reactor state.
+    pub fn reactor(&self) -> ReactorState {
+        self.inner.lock().unwrap().reactor
+    }
+
+    #[cfg_attr(not(feature = "ui"), allow(dead_code))]
+    fn active_tasks(&self) -> Vec<Task> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .tasks
+            .values()
+            .filter(|task| task.dropped_at.is_none())
+            .cloned()
+            .collect()
+    }
+
+    fn update_reactor(&self, f: impl FnOnce(&mut ReactorState)) {
+        f(&mut self.inner.lock().unwrap().reactor)
+    }
+}
+
+impl Oracle for ExecutorOracle {
+    fn name(&self) -> &'static str {
+        "async"
+    }
+
+    fn ready_for_install(&self, dbg: &Debugger) -> bool {
+        let has_symbols = |regex: &str| !dbg.get_symbols(regex).unwrap_or_default().is_empty();
+
+        if !has_symbols("async_task::raw::RawTask.*::run") {
+            return false;
+        }
+
+        let runtime = if has_symbols("^async_std::") {
+            Runtime::AsyncStd
+        } else if has_symbols("^async_executor::") {
+            Runtime::Smol
+        } else {
+            return false;
+        };
+
+        *self.inner.lock().unwrap() = Inner {
+            runtime: Some(runtime),
+            ..Inner::default()
+        };
+        true
+    }
+
+    fn watch_points(self: Arc<Self>) -> Vec<CreateTransparentBreakpointRequest> {
+        let oracle = self.clone();
+        let run_handler = move |dbg: &mut Debugger| {
+            if let Err(e) = oracle.on_run(dbg) {
+                warn!(target: "async oracle", "run task: {e}")
+            }
+        };
+        let run_brkpt = CreateTransparentBreakpointRequest::function(
+            "async_task::raw::RawTask::run",
+            run_handler,
+        );
+
+        let oracle = self.clone();
+        let destroy_handler = move |dbg: &mut Debugger| {
+            if let Err(e) = oracle.on_destroy(dbg) {
+                warn!(target: "async oracle", "destroy task: {e}")
+            }
+        };
+        let destroy_brkpt = CreateTransparentBreakpointRequest::function(
+            "async_task::raw::RawTask::destroy",
+            destroy_handler,
+        );
+
+        let mut watch_points = vec![run_brkpt, destroy_brkpt];
+
+        let reactor_handlers: [(&str, fn(&mut ReactorState)); 5] = [
+            ("insert_io", |r| r.sources += 1),
+            ("remove_io", |r| r.sources = r.sources.saturating_sub(1)),
+            ("insert_timer", |r| r.timers += 1),
+            ("remove_timer", |r| r.timers = r.timers.saturating_sub(1)),
+            ("react", |r| r.ticks += 1),
+        ];
+        for (func, handler) in reactor_handlers {
+            let oracle = self.clone();
+            let namespace = if func == "react" {
+                "ReactorLock"
+            } else {
+                "Reactor"
+            };
+            watch_points.push(CreateTransparentBreakpointRequest::function(
+                format!("async_io::reactor::{namespace}::{func}"),
+                move |_: &mut Debugger| oracle.update_reactor(handler),
+            ));
+        }
+
+        watch_points
+    }
+}
+
+impl ExecutorOracle {
+    /// Read `ptr` function argument, interpret it as a raw task pointer.
+    fn task_ptr_from_args(dbg: &Debugger) -> Result<Option<usize>, Error> {
+        let args = dbg.read_argument(DQE::Variable(VariableSelector::Name {
+            var_name: "ptr".to_string(),
+            only_local: true,
+        }))?;
+        let Some(VariableIR::Pointer(ptr)) = args.first() else {
+            return Ok(None);
+        };
+        Ok(ptr.value.map(|ptr| ptr as usize))
+    }
+
+    /// Read a task state from the task header.
+    fn read_state(dbg: &Debugger, ptr: usize) -> Option<State> {
+        let header = dbg
+            .read_variable(DQE::Deref(
+                DQE::PtrCast(ptr, "*const async_task::header::Header<()>".to_string()).boxed(),
+            ))
+            .ok()?;
+        let Some(VariableIR::Struct(header)) = header.first() else {
+            return None;
+        };
+        let state = header
+            .members
+            .iter()
+            .find(|member| member.name() == "state")?;
+        usize_value(state).map(State::from_raw)
+    }
+
+    fn on_run(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let Some(ptr) = Self::task_ptr_from_args(dbg)? else {
+            return Ok(());
+        };
+        let state = Self::read_state(dbg, ptr);
+
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+        // task memory may be reused by a new task after previous one is destroyed
+        let is_new = inner
+            .tasks
+            .get(&ptr)
+            .map_or(true, |task| task.dropped_at.is_some());
+        if is_new {
+            inner.next_id += 1;
+            inner.tasks.shift_remove(&ptr);
+            inner.tasks.insert(
+                ptr,
+                Task {
+                    id: inner.next_id,
+                    polls: 0,
+                    created_at: Instant::now(),
+                    state: State::Scheduled,
+                    dropped_at: None,
+                },
+            );
+        }
+
+        let task = inner.tasks.get_mut(&ptr).expect("inserted above");
+        task.polls += 1;
+        task.state = state.unwrap_or(State::Running);
+        Ok(())
+    }
+
+    fn on_destroy(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let Some(ptr) = Self::task_ptr_from_args(dbg)? else {
+            return Ok(());
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(task) = inner.tasks.get_mut(&ptr) {
+            task.state = State::Closed;
+            task.dropped_at = Some(Instant::now());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ui")]
+impl ConsolePlugin for ExecutorOracle {
+    fn print(&self, printer: &ExternalPrinter, _: Option<&str>) {
+        let Some(runtime) = self.runtime() else {
+            printer.println("async-std or smol runtime not found");
+            return;
+        };
+
+        let reactor = self.reactor();
+        printer.println(format!(
+            "{} runtime, reactor: {} I/O sources, {} timers, {} ticks",
+            KeywordView::from(runtime),
+            reactor.sources,
+            reactor.timers,
+            reactor.ticks,
+        ));
+
+        let tasks = self.active_tasks();
+        printer.println(format!(
+            "{} tasks running\n",
+            KeywordView::from(tasks.len())
+        ));
+
+        if !tasks.is_empty() {
+            printer.println(format!(
+                "{task:<5} {state:<10} {time:<5} {polls}",
+                task = "task",
+                state = "state",
+                time = "time",
+                polls = "polls",
+            ));
+
+            for task in tasks {
+                let elapsed = task.task_time();
+                let time = format!("{}m{}s", elapsed.num_minutes(), elapsed.num_seconds() % 60);
+                printer.println(format!(
+                    "{id:<5} {state:<10} {time:<5} {polls}",
+                    id = task.id,
+                    state = task.state.to_string(),
+                    polls = task.polls,
+                ));
+            }
+        }
+    }
+
+    fn help(&self) -> &str {
+        "async - async-std and smol runtime tasks and reactor state"
+    }
+}
+
+#[cfg(feature = "ui")]
+impl TuiPlugin for ExecutorOracle {
+    fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
+        Box::new(tui::ExecutorComponent::new(self))
+    }
+}
+
+#[cfg(feature = "ui")]
+pub mod tui {
+    use crate::oracle::builtin::executor::{ExecutorOracle, State};
+    use crate::ui::tui::app::port::UserEvent;
+    use crate::ui::tui::Msg;
+    use std::sync::Arc;
+    use tui_realm_stdlib::Table;
+    use tuirealm::command::{Cmd, Direction};
+    use tuirealm::event::{Key, KeyEvent};
+    use tuirealm::props::{Alignment, BorderType, Borders, Color, Style, TableBuilder, TextSpan};
+    use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent};
+
+    impl State {
+        fn fg(self) -> Color {
+            match self {
+                State::Scheduled => Color::LightBlue,
+                State::Running => Color::Green,
+                State::Idle => Color::White,
+                State::Completed => Color::White,
+                State::Closed => Color::Gray,
+            }
+        }
+    }
+
+    #[derive(MockComponent)]
+    pub struct ExecutorComponent {
+        component: Table,
+        oracle: Arc<ExecutorOracle>,
+    }
+
+    impl ExecutorComponent {
+        pub fn new(oracle: Arc<ExecutorOracle>) -> Self {
+            let component = Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(Color::LightYellow),
+                )
+                .title("Active tasks", Alignment::Center)
+                .inactive(Style::default().fg(Color::Gray))
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("▶")
+                .rewind(true)
+                .step(4)
+                .widths(&[10, 15, 10, 10])
+                .headers(&["Task ID", "State", "Time", "Polls"]);
+
+            let mut this = Self { component, oracle };
+            this.refresh_table();
+            this
+        }
+
+        fn refresh_table(&mut self) {
+            let mut table = TableBuilder::default();
+
+            let reactor = self.oracle.reactor();
+            let runtime = self
+                .oracle
+                .runtime()
+                .map(|rt| rt.to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            table
+                .add_col(TextSpan::from(runtime).fg(Color::LightBlue))
+                .add_col(TextSpan::from(format!("{} sources", reactor.sources)))
+                .add_col(TextSpan::from(format!("{} timers", reactor.timers)))
+                .add_col(TextSpan::from(format!("{} ticks", reactor.ticks)))
+                .add_row();
+
+            for task in self.oracle.active_tasks() {
+                let elapsed = task.task_time();
+                table
+                    .add_col(TextSpan::from(task.id.to_string()))
+                    .add_col(TextSpan::from(task.state.to_string()).fg(task.state.fg()))
+                    .add_col(TextSpan::from(format!(
+                        "{}m{}s",
+                        elapsed.num_minutes(),
+                        elapsed.num_seconds() % 60
+                    )))
+                    .add_col(TextSpan::from(task.polls.to_string()))
+                    .add_row();
+            }
+
+            self.component
+                .attr(Attribute::Content, AttrValue::Table(table.build()));
+        }
+    }
+
+    impl Component<Msg, UserEvent> for ExecutorComponent {
+        fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+            match ev {
+                Event::Keyboard(KeyEvent {
+                    code: Key::Down, ..
+                }) => {
+                    self.perform(Cmd::Move(Direction::Down));
+                }
+                Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                    self.perform(Cmd::Move(Direction::Up));
+                }
+                Event::Tick => {
+                    self.refresh_table();
+                }
+                _ => {}
+            }
+
+            Some(Msg::None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_task_state() {
+        assert_eq!(State::from_raw(0), State::Idle);
+        assert_eq!(State::from_raw(1 | 1 << 4), State::Scheduled);
+        assert_eq!(State::from_raw(1 | 2), State::Running);
+        assert_eq!(State::from_raw(4 | 1 << 8), State::Completed);
+        assert_eq!(State::from_raw(2 | 8), State::Closed);
+    }
+}
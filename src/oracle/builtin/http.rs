@@ -0,0 +1,573 @@
+//! HTTP oracle: log of HTTP/1 requests made or served by hyper (and clients built on top of it,
+//! like reqwest).
+//!
+//! Requests and responses are observed in two hyper functions shared by client and server:
+//! `hyper::proto::h1::role::parse_headers` (incoming message, raw head is read from a buffer)
+//! and `hyper::proto::h1::role::encode_headers` (outgoing message, head is read from
+//! `MessageHead` structure). Request and response are matched by a connection, connection
+//! is identified by an address of its `req_method` state.
+
+use crate::debugger::variable::render::RenderRepr;
+use crate::debugger::variable::select::{VariableSelector, DQE};
+use crate::debugger::variable::{SpecializedVariableIR, SupportedScalar, VariableIR};
+use crate::debugger::{CreateTransparentBreakpointRequest, Debugger, Error};
+use crate::oracle::builtin::{find_member, pointer_value, usize_value};
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::style::{FunctionNameView, KeywordView};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
+use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
+use crate::ui::tui::Msg;
+use log::warn;
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use strum_macros::Display;
+#[cfg(feature = "ui")]
+use tuirealm::Component;
+
+/// Max size of a message head that oracle reads from debugee memory.
+const MAX_HEAD_LEN: usize = 8 * 1024;
+
+/// Count of log records printed by console.
+#[cfg(feature = "ui")]
+const LOG_TAIL: usize = 30;
+
+/// Frames of functions with these prefixes are not a task.
+const SKIP_PREFIXES: &[&str] = &[
+    "hyper::", "<hyper::", "tokio::", "<tokio::", "core::", "<core::", "std::", "<std::",
+];
+
+/// Side of an HTTP exchange.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    #[strum(serialize = "client")]
+    Client,
+    #[strum(serialize = "server")]
+    Server,
+}
+
+/// HTTP request and its response.
+#[derive(Debug, Clone)]
+pub struct Exchange {
+    pub side: Side,
+    pub method: String,
+    pub url: String,
+    /// Response status, `None` until a response is sent or received.
+    pub status: Option<u16>,
+    pub started_at: Instant,
+    pub finished_at: Option<Instant>,
+    pub thread_num: Option<u32>,
+    pub thread_pid: Pid,
+    /// Async function (a task) that issues or handles a request.
+    pub task: Option<String>,
+}
+
+impl Exchange {
+    /// Return request latency, `None` if response is not received yet.
+    pub fn latency(&self) -> Option<Duration> {
+        Some(self.finished_at?.duration_since(self.started_at))
+    }
+}
+
+/// Parsed message head.
+#[derive(Debug, PartialEq)]
+enum Head {
+    Request {
+        method: String,
+        target: String,
+        host: Option<String>,
+    },
+    Response {
+        status: u16,
+    },
+}
+
+impl Head {
+    /// Parse HTTP/1 message head, return `None` if head is incomplete or malformed.
+    fn parse(raw: &[u8]) -> Option<Self> {
+        let end = raw.windows(4).position(|w| w == b"\r\n\r\n")?;
+        let head = std::str::from_utf8(&raw[..end]).ok()?;
+        let mut lines = head.split("\r\n");
+        let start_line = lines.next()?;
+
+        if let Some(status_line) = start_line.strip_prefix("HTTP/") {
+            let status = status_line.split(' ').nth(1)?.parse().ok()?;
+            return Some(Head::Response { status });
+        }
+
+        let mut parts = start_line.split(' ');
+        let method = parts.next()?.to_string();
+        let target = parts.next()?.to_string();
+        parts.next()?.strip_prefix("HTTP/")?;
+        let host = lines.find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("host")
+                .then(|| value.trim().to_string())
+        });
+
+        Some(Head::Request {
+            method,
+            target,
+            host,
+        })
+    }
+}
+
+/// Make an URL from a request target and a host.
+fn make_url(target: &str, host: Option<&str>) -> String {
+    match host {
+        Some(host) if target.starts_with('/') => format!("http://{host}{target}"),
+        _ => target.to_string(),
+    }
+}
+
+fn arg(name: &str) -> DQE {
+    DQE::Variable(VariableSelector::Name {
+        var_name: name.to_string(),
+        only_local: true,
+    })
+}
+
+fn field(expr: DQE, name: &str) -> DQE {
+    DQE::Field(expr.boxed(), name.to_string())
+}
+
+/// Return name of a selected variant of a first enum, search in depth.
+fn variant_name(var: &VariableIR) -> Option<String> {
+    match var {
+        VariableIR::RustEnum(r#enum)
+        | VariableIR::Specialized(
+            SpecializedVariableIR::Option {
+                original: r#enum, ..
+            }
+            | SpecializedVariableIR::Result {
+                original: r#enum, ..
+            },
+        ) => match r#enum.value.as_deref()? {
+            VariableIR::Struct(variant) => variant.identity.name.clone(),
+            _ => None,
+        },
+        VariableIR::Struct(structure) => structure.members.iter().find_map(variant_name),
+        _ => None,
+    }
+}
+
+/// Return value of a first u16 scalar, search in depth.
+fn u16_value(var: &VariableIR) -> Option<u16> {
+    match var {
+        VariableIR::Scalar(scalar) => match scalar.value {
+            Some(SupportedScalar::U16(value)) => Some(value),
+            _ => None,
+        },
+        VariableIR::Struct(structure) => structure.members.iter().find_map(u16_value),
+        _ => None,
+    }
+}
+
+/// [`HttpOracle`] log HTTP requests with response status and latency.
+#[derive(Default)]
+pub struct HttpOracle {
+    log: Mutex<Vec<Exchange>>,
+    /// Requests without response: (connection, side) -> index in log.
+    pending: Mutex<HashMap<(usize, Side), usize>>,
+}
+
+impl HttpOracle {
+    /// Create a new oracle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return all logged requests in order of issue.
+    pub fn log(&self) -> Vec<Exchange> {
+        self.log.lock().unwrap().clone()
+    }
+
+    /// Read bytes of `bytes::Bytes`, `bytes::BytesMut` or any structure with `ptr` and `len`
+    /// members.
+    fn read_bytes(dbg: &Debugger, var: &VariableIR) -> Option<Vec<u8>> {
+        let ptr = pointer_value(find_member(var, "ptr")?)?;
+        let len = usize_value(find_member(var, "len")?)?;
+        if ptr == 0 || len == 0 {
+            return Some(vec![]);
+        }
+        dbg.read_memory(ptr, len.min(MAX_HEAD_LEN)).ok()
+    }
+
+    /// Return connection identity, an address of connection `req_method` state.
+    fn connection(dbg: &Debugger, ctx: &str) -> Result<Option<usize>, Error> {
+        Ok(dbg
+            .read_argument(field(arg(ctx), "req_method"))?
+            .pop()
+            .as_ref()
+            .and_then(pointer_value))
+    }
+
+    /// Return focused thread and a task that runs on it.
+    fn issuer(dbg: &Debugger) -> (Pid, Option<u32>, Option<String>) {
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        let thread_num = dbg
+            .thread_state()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|snapshot| snapshot.thread.pid == pid)
+            .map(|snapshot| snapshot.thread.number);
+        let task = dbg.backtrace(pid).ok().and_then(|bt| {
+            bt.into_iter().find_map(|frame| {
+                let name = frame.func_name?;
+                let is_async = name.contains("{async_fn#") || name.contains("{async_block#");
+                (is_async && !SKIP_PREFIXES.iter().any(|p| name.starts_with(p))).then_some(name)
+            })
+        });
+        (pid, thread_num, task)
+    }
+
+    fn start(&self, dbg: &Debugger, conn: usize, side: Side, method: String, url: String) {
+        let (thread_pid, thread_num, task) = Self::issuer(dbg);
+        let mut log = self.log.lock().unwrap();
+        log.push(Exchange {
+            side,
+            method,
+            url,
+            status: None,
+            started_at: Instant::now(),
+            finished_at: None,
+            thread_num,
+            thread_pid,
+            task,
+        });
+        self.pending
+            .lock()
+            .unwrap()
+            .insert((conn, side), log.len() - 1);
+    }
+
+    fn finish(&self, conn: usize, side: Side, status: u16) {
+        let Some(idx) = self.pending.lock().unwrap().remove(&(conn, side)) else {
+            return;
+        };
+        if let Some(exchange) = self.log.lock().unwrap().get_mut(idx) {
+            exchange.status = Some(status);
+            exchange.finished_at = Some(Instant::now());
+        }
+    }
+
+    /// Called at `parse_headers` function, incoming message is a request for a server
+    /// and a response for a client.
+    fn on_parse(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let Some(conn) = Self::connection(dbg, "ctx")? else {
+            return Ok(());
+        };
+        let Some(buf) = dbg.read_argument(DQE::Deref(arg("bytes").boxed()))?.pop() else {
+            return Ok(());
+        };
+        let Some(raw) = Self::read_bytes(dbg, &buf) else {
+            return Ok(());
+        };
+
+        // incomplete head will be parsed again after next read
+        match Head::parse(&raw) {
+            Some(Head::Request {
+                method,
+                target,
+                host,
+            }) => {
+                let url = make_url(&target, host.as_deref());
+                self.start(dbg, conn, Side::Server, method, url);
+            }
+            Some(Head::Response { status }) => self.finish(conn, Side::Client, status),
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Called at `encode_headers` function, outgoing message is a request for a client
+    /// (subject is a `RequestLine`) and a response for a server (subject is a `StatusCode`).
+    fn on_encode(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let Some(conn) = Self::connection(dbg, "enc")? else {
+            return Ok(());
+        };
+        let subject_expr = field(DQE::Deref(field(arg("enc"), "head").boxed()), "subject");
+        let Some(subject) = dbg.read_argument(subject_expr)?.pop() else {
+            return Ok(());
+        };
+
+        if subject.r#type().contains("StatusCode") {
+            if let Some(status) = u16_value(&subject) {
+                self.finish(conn, Side::Server, status);
+            }
+            return Ok(());
+        }
+
+        let VariableIR::Struct(ref line) = subject else {
+            return Ok(());
+        };
+        let (Some(method), Some(uri)) = (line.members.first(), line.members.get(1)) else {
+            return Ok(());
+        };
+        let method = variant_name(method)
+            .map(|variant| variant.to_uppercase())
+            .unwrap_or_else(|| "?".to_string());
+
+        let uri_part = |name: &str| {
+            find_member(uri, name)
+                .and_then(|part| Self::read_bytes(dbg, part))
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .filter(|part| !part.is_empty())
+        };
+        let path = uri_part("path_and_query").unwrap_or_else(|| "/".to_string());
+        let url = make_url(&path, uri_part("authority").as_deref());
+
+        self.start(dbg, conn, Side::Client, method, url);
+        Ok(())
+    }
+}
+
+impl Oracle for HttpOracle {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn ready_for_install(&self, dbg: &Debugger) -> bool {
+        let has_symbols = !dbg
+            .get_symbols(r"^hyper::proto::h1::role::(parse|encode)_headers")
+            .unwrap_or_default()
+            .is_empty();
+        if has_symbols {
+            self.log.lock().unwrap().clear();
+            self.pending.lock().unwrap().clear();
+        }
+        has_symbols
+    }
+
+    fn watch_points(self: Arc<Self>) -> Vec<CreateTransparentBreakpointRequest> {
+        let oracle = self.clone();
+        let parse = CreateTransparentBreakpointRequest::function(
+            "hyper::proto::h1::role::parse_headers",
+            move |dbg: &mut Debugger| {
+                if let Err(e) = oracle.on_parse(dbg) {
+                    warn!(target: "http oracle", "incoming message: {e}")
+                }
+            },
+        );
+
+        let oracle = self.clone();
+        let encode = CreateTransparentBreakpointRequest::function(
+            "hyper::proto::h1::role::encode_headers",
+            move |dbg: &mut Debugger| {
+                if let Err(e) = oracle.on_encode(dbg) {
+                    warn!(target: "http oracle", "outgoing message: {e}")
+                }
+            },
+        );
+
+        vec![parse, encode]
+    }
+}
+
+#[cfg(feature = "ui")]
+fn format_latency(exchange: &Exchange) -> String {
+    exchange
+        .latency()
+        .map(|latency| format!("{}ms", latency.as_millis()))
+        .unwrap_or_else(|| "pending".to_string())
+}
+
+#[cfg(feature = "ui")]
+impl ConsolePlugin for HttpOracle {
+    fn print(&self, printer: &ExternalPrinter, subcommand: Option<&str>) {
+        let side = match subcommand {
+            Some("client") => Some(Side::Client),
+            Some("server") => Some(Side::Server),
+            _ => None,
+        };
+        let log: Vec<_> = self
+            .log()
+            .into_iter()
+            .filter(|exchange| side.is_none() || Some(exchange.side) == side)
+            .collect();
+
+        printer.println(format!("{} requests\n", KeywordView::from(log.len())));
+        printer.println(format!(
+            "{:<8} {:<8} {:<6} {:<10} {:<10} url",
+            "side", "method", "status", "latency", "thread"
+        ));
+        for exchange in log.iter().skip(log.len().saturating_sub(LOG_TAIL)) {
+            let status = exchange
+                .status
+                .map(|status| status.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let thread = exchange
+                .thread_num
+                .map(|num| format!("#{num}"))
+                .unwrap_or_else(|| exchange.thread_pid.to_string());
+            let task = exchange
+                .task
+                .as_ref()
+                .map(|task| format!(" in {}", FunctionNameView::from(task)))
+                .unwrap_or_default();
+            printer.println(format!(
+                "{:<8} {:<8} {:<6} {:<10} {:<10} {}{task}",
+                exchange.side,
+                KeywordView::from(&exchange.method),
+                status,
+                format_latency(exchange),
+                thread,
+                exchange.url,
+            ));
+        }
+    }
+
+    fn help(&self) -> &str {
+        "http - log of HTTP requests (method, url, status, latency and an issuer thread/task), \
+        `oracle http client` and `oracle http server` show only one side"
+    }
+}
+
+#[cfg(feature = "ui")]
+impl TuiPlugin for HttpOracle {
+    fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
+        Box::new(tui::HttpComponent::new(self))
+    }
+}
+
+#[cfg(feature = "ui")]
+pub mod tui {
+    use crate::oracle::builtin::http::{format_latency, HttpOracle};
+    use crate::ui::tui::app::port::UserEvent;
+    use crate::ui::tui::Msg;
+    use std::sync::Arc;
+    use tui_realm_stdlib::Table;
+    use tuirealm::command::{Cmd, Direction};
+    use tuirealm::event::{Key, KeyEvent};
+    use tuirealm::props::{Alignment, BorderType, Borders, Color, Style, TableBuilder, TextSpan};
+    use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent};
+
+    #[derive(MockComponent)]
+    pub struct HttpComponent {
+        component: Table,
+        oracle: Arc<HttpOracle>,
+    }
+
+    impl HttpComponent {
+        pub fn new(oracle: Arc<HttpOracle>) -> Self {
+            let component = Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(Color::LightYellow),
+                )
+                .title("HTTP requests", Alignment::Center)
+                .inactive(Style::default().fg(Color::Gray))
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("▶")
+                .rewind(true)
+                .step(4)
+                .widths(&[8, 8, 8, 10, 10, 56])
+                .headers(&["Side", "Method", "Status", "Latency", "Thread", "URL"]);
+
+            let mut this = Self { component, oracle };
+            this.refresh_table();
+            this
+        }
+
+        fn refresh_table(&mut self) {
+            let mut table = TableBuilder::default();
+            let log = self.oracle.log();
+            if log.is_empty() {
+                for _ in 0..6 {
+                    table.add_col(TextSpan::from(""));
+                }
+                table.add_row();
+            }
+            // latest requests first
+            for exchange in log.iter().rev() {
+                let status_fg = match exchange.status {
+                    Some(status) if status >= 400 => Color::Red,
+                    Some(_) => Color::Green,
+                    None => Color::Gray,
+                };
+                table
+                    .add_col(TextSpan::from(exchange.side.to_string()))
+                    .add_col(TextSpan::from(&exchange.method).fg(Color::LightBlue))
+                    .add_col(
+                        TextSpan::from(
+                            exchange
+                                .status
+                                .map(|status| status.to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                        )
+                        .fg(status_fg),
+                    )
+                    .add_col(TextSpan::from(format_latency(exchange)))
+                    .add_col(TextSpan::from(exchange.thread_pid.to_string()))
+                    .add_col(TextSpan::from(&exchange.url))
+                    .add_row();
+            }
+            self.component
+                .attr(Attribute::Content, AttrValue::Table(table.build()));
+        }
+    }
+
+    impl Component<Msg, UserEvent> for HttpComponent {
+        fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+            match ev {
+                Event::Keyboard(KeyEvent {
+                    code: Key::Down, ..
+                }) => {
+                    self.perform(Cmd::Move(Direction::Down));
+                }
+                Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                    self.perform(Cmd::Move(Direction::Up));
+                }
+                Event::Tick => {
+                    self.refresh_table();
+                }
+                _ => {}
+            }
+
+            Some(Msg::None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_head() {
+        let request =
+            b"GET /api/users?id=1 HTTP/1.1\r\nhost: localhost:3000\r\naccept: */*\r\n\r\n";
+        let head = Head::parse(request).unwrap();
+        assert_eq!(
+            head,
+            Head::Request {
+                method: "GET".to_string(),
+                target: "/api/users?id=1".to_string(),
+                host: Some("localhost:3000".to_string()),
+            }
+        );
+        let Head::Request { target, host, .. } = head else {
+            unreachable!()
+        };
+        assert_eq!(
+            make_url(&target, host.as_deref()),
+            "http://localhost:3000/api/users?id=1"
+        );
+
+        let response = b"HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\n\r\n";
+        assert_eq!(Head::parse(response), Some(Head::Response { status: 404 }));
+
+        // incomplete head
+        assert_eq!(Head::parse(b"POST /api HTTP/1.1\r\nhost: local"), None);
+    }
+}
@@ -1,9 +1,32 @@
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::variable::render::RenderRepr;
+use crate::debugger::variable::{SpecializedVariableIR, SupportedScalar, VariableIR};
+use crate::debugger::{Debugger, Error};
+use crate::oracle::builtin::alloc::AllocOracle;
+use crate::oracle::builtin::channel::ChannelOracle;
+use crate::oracle::builtin::db::DbOracle;
+use crate::oracle::builtin::executor::ExecutorOracle;
+use crate::oracle::builtin::http::HttpOracle;
+use crate::oracle::builtin::mutex::MutexOracle;
 use crate::oracle::builtin::nop::NopOracle;
+use crate::oracle::builtin::panic::PanicOracle;
+use crate::oracle::builtin::perf::PerfOracle;
+use crate::oracle::builtin::syscall::SyscallOracle;
 use crate::oracle::builtin::tokio::TokioOracle;
-use crate::oracle::Oracle;
+use crate::oracle::{plugin, Oracle};
+use log::{info, warn};
 use std::sync::Arc;
 
+pub mod alloc;
+pub mod channel;
+pub mod db;
+pub mod executor;
+pub mod http;
+pub mod mutex;
 pub mod nop;
+pub mod panic;
+pub mod perf;
+pub mod syscall;
 pub mod tokio;
 
 /// Create an oracle specified by name.
@@ -14,7 +37,117 @@ pub mod tokio;
 pub fn make_builtin(name: &str) -> Option<Arc<dyn Oracle>> {
     match name {
         "tokio" => Some(Arc::new(TokioOracle::new())),
+        "async" | "async-std" | "smol" => Some(Arc::new(ExecutorOracle::new())),
+        "alloc" => Some(Arc::new(AllocOracle::new())),
+        "mutex" => Some(Arc::new(MutexOracle::new())),
+        "channel" => Some(Arc::new(ChannelOracle::new())),
+        "http" => Some(Arc::new(HttpOracle::new())),
+        "db" => Some(Arc::new(DbOracle::new())),
+        "perf" => Some(Arc::new(PerfOracle::new())),
+        "panic" => Some(Arc::new(PanicOracle::new())),
+        "syscall" => Some(Arc::new(SyscallOracle::new())),
         "nop" => Some(Arc::new(NopOracle::default())),
         _ => None,
     }
 }
+
+/// Create oracles specified by names, unknown names are skipped with a warning.
+/// A name that looks like a path (see [`plugin::is_plugin_path`]) is loaded as a plugin library.
+///
+/// # Arguments
+///
+/// * `names`: list of oracle names or paths to plugin libraries
+pub fn discover(names: &[String]) -> Vec<Arc<dyn Oracle>> {
+    names
+        .iter()
+        .filter_map(|ora_name| {
+            if plugin::is_plugin_path(ora_name) {
+                return match plugin::load(ora_name) {
+                    Ok(oracle) => {
+                        info!(target: "debugger", "oracle `{}` loaded from `{ora_name}`", oracle.name());
+                        Some(oracle)
+                    }
+                    Err(e) => {
+                        warn!(target: "debugger", "oracle plugin `{ora_name}`: {e}");
+                        None
+                    }
+                };
+            }
+
+            if let Some(oracle) = make_builtin(ora_name) {
+                info!(target: "debugger", "oracle `{ora_name}` discovered");
+                Some(oracle)
+            } else {
+                warn!(target: "debugger", "oracle `{ora_name}` not found");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Return an address where current function returns, must be called at function entry
+/// (before function prologue), when a return address is on top of a stack.
+///
+/// # Arguments
+///
+/// * `dbg`: debugger instance
+pub(crate) fn return_address(dbg: &Debugger) -> Result<RelocatedAddress, Error> {
+    let rsp = dbg.get_register_value("rsp")?;
+    let ret_addr = dbg.read_memory(rsp as usize, 8)?;
+    Ok(RelocatedAddress::from(u64::from_ne_bytes(
+        ret_addr.try_into().expect("8 bytes are read"),
+    )))
+}
+
+/// Return value of an atomic (or a plain) usize, wrappers are unfolded by first member.
+pub(crate) fn usize_value(var: &VariableIR) -> Option<usize> {
+    match var {
+        VariableIR::Scalar(scalar) => match scalar.value {
+            Some(SupportedScalar::Usize(value)) => Some(value),
+            _ => None,
+        },
+        VariableIR::Struct(structure) => usize_value(structure.members.first()?),
+        VariableIR::Specialized(SpecializedVariableIR::Atomic {
+            value: Some(value), ..
+        }) => usize_value(value),
+        _ => None,
+    }
+}
+
+/// Return value of a raw pointer, wrappers (like `NonNull` or `Unique`) are unfolded
+/// by first member.
+pub(crate) fn pointer_value(var: &VariableIR) -> Option<usize> {
+    match var {
+        VariableIR::Pointer(pointer)
+        | VariableIR::Specialized(SpecializedVariableIR::CStrPointer {
+            original: pointer, ..
+        }) => pointer.value.map(|ptr| ptr as usize),
+        VariableIR::Struct(structure) => pointer_value(structure.members.first()?),
+        VariableIR::Specialized(SpecializedVariableIR::Atomic {
+            value: Some(value), ..
+        }) => pointer_value(value),
+        _ => None,
+    }
+}
+
+/// Find a member by name, search in depth.
+pub(crate) fn find_member<'a>(var: &'a VariableIR, name: &str) -> Option<&'a VariableIR> {
+    match var {
+        VariableIR::Struct(structure) => structure.members.iter().find_map(|member| {
+            if member.name() == name {
+                return Some(member);
+            }
+            find_member(member, name)
+        }),
+        VariableIR::RustEnum(r#enum)
+        | VariableIR::Specialized(
+            SpecializedVariableIR::Option {
+                original: r#enum, ..
+            }
+            | SpecializedVariableIR::Result {
+                original: r#enum, ..
+            },
+        ) => find_member(r#enum.value.as_ref()?, name),
+        _ => None,
+    }
+}
@@ -0,0 +1,491 @@
+//! Mutex contention oracle.
+//!
+//! Std `Mutex` and `RwLock` (futex based implementations) call a slow path function
+//! only when a lock is contended (a thread is going to wait on a futex), and a wake function
+//! when a lock is released and there are waiters. Slow paths are watched to find
+//! waiters and wait times, wake functions - to find lock owners (threads that block waiters).
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::unwind::Backtrace;
+use crate::debugger::{CreateTransparentBreakpointRequest, Debugger, Error};
+use crate::oracle::builtin::return_address;
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::style::{AddressView, FunctionNameView, KeywordView};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
+use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
+use crate::ui::tui::Msg;
+use log::warn;
+use nix::unistd::Pid;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use strum_macros::Display;
+#[cfg(feature = "ui")]
+use tuirealm::Component;
+
+/// Default summary time window.
+#[cfg(any(feature = "ui", test))]
+const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+/// Max number of stored wait events.
+const MAX_EVENTS: usize = 10_000;
+/// Number of frames in representative backtraces.
+const BACKTRACE_DEPTH: usize = 5;
+/// Count of locks in reports.
+#[cfg(feature = "ui")]
+const TOP_LOCKS: usize = 10;
+
+/// Frames of functions with these prefixes are not shown in representative backtraces.
+const SKIP_PREFIXES: &[&str] = &["std::sys::", "std::sync::", "core::", "<std::sync::"];
+
+#[derive(Debug, Display, Clone, Copy, PartialEq)]
+pub enum LockKind {
+    #[strum(serialize = "mutex")]
+    Mutex,
+    #[strum(serialize = "rwlock (read)")]
+    Read,
+    #[strum(serialize = "rwlock (write)")]
+    Write,
+}
+
+/// Single wait for a lock.
+struct WaitEvent {
+    lock: usize,
+    kind: LockKind,
+    waiter: Pid,
+    started_at: Instant,
+    waited: Option<Duration>,
+}
+
+/// Contention summary of a single lock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockContention {
+    /// Lock address.
+    pub lock: usize,
+    pub kind: LockKind,
+    /// Number of waits in a time window.
+    pub waits: usize,
+    /// Total wait time of finished waits in a time window.
+    pub wait_time: Duration,
+    /// Threads that waited for a lock.
+    pub waiters: Vec<Pid>,
+    /// Threads that held a lock while other threads were waiting.
+    pub owners: Vec<Pid>,
+    /// Backtrace of one of the waiters (function names).
+    pub waiter_backtrace: Vec<String>,
+}
+
+#[derive(Default)]
+struct State {
+    events: VecDeque<WaitEvent>,
+    /// Index of waits in progress: thread -> event number (counting from `first_event`).
+    in_progress: HashMap<Pid, usize>,
+    /// Number of events removed from the front of `events`.
+    first_event: usize,
+    owners: HashMap<usize, HashSet<Pid>>,
+    waiter_backtraces: HashMap<usize, Vec<String>>,
+    return_points: HashSet<RelocatedAddress>,
+}
+
+impl State {
+    fn push(&mut self, event: WaitEvent) {
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_front();
+            self.first_event += 1;
+        }
+        let idx = self.first_event + self.events.len();
+        self.in_progress.insert(event.waiter, idx);
+        self.events.push_back(event);
+    }
+
+    fn finish(&mut self, waiter: Pid) {
+        let Some(idx) = self.in_progress.remove(&waiter) else {
+            return;
+        };
+        if let Some(event) = idx
+            .checked_sub(self.first_event)
+            .and_then(|i| self.events.get_mut(i))
+        {
+            event.waited = Some(event.started_at.elapsed());
+        }
+    }
+
+    fn summary(&self, window: Duration) -> Vec<LockContention> {
+        let now = Instant::now();
+        let mut locks: HashMap<usize, LockContention> = HashMap::new();
+        for event in self
+            .events
+            .iter()
+            .filter(|e| now.duration_since(e.started_at) <= window)
+        {
+            let lock = locks.entry(event.lock).or_insert_with(|| LockContention {
+                lock: event.lock,
+                kind: event.kind,
+                waits: 0,
+                wait_time: Duration::ZERO,
+                waiters: vec![],
+                owners: self
+                    .owners
+                    .get(&event.lock)
+                    .map(|owners| owners.iter().copied().collect())
+                    .unwrap_or_default(),
+                waiter_backtrace: self
+                    .waiter_backtraces
+                    .get(&event.lock)
+                    .cloned()
+                    .unwrap_or_default(),
+            });
+            lock.waits += 1;
+            lock.wait_time += event.waited.unwrap_or_default();
+            if !lock.waiters.contains(&event.waiter) {
+                lock.waiters.push(event.waiter);
+            }
+        }
+
+        let mut locks: Vec<_> = locks.into_values().collect();
+        locks.sort_by(|l1, l2| (l2.wait_time, l2.waits).cmp(&(l1.wait_time, l1.waits)));
+        locks
+    }
+}
+
+/// [`MutexOracle`] collect waits for contended std `Mutex` and `RwLock`.
+#[derive(Default)]
+pub struct MutexOracle {
+    state: Mutex<State>,
+}
+
+impl MutexOracle {
+    /// Create a new oracle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the most contended locks (by wait time) over a time window.
+    ///
+    /// # Arguments
+    ///
+    /// * `window`: summary time window, waits started earlier are ignored
+    pub fn contention(&self, window: Duration) -> Vec<LockContention> {
+        self.state.lock().unwrap().summary(window)
+    }
+
+    fn representative_backtrace(bt: &Backtrace) -> Vec<String> {
+        bt.iter()
+            .filter_map(|frame| frame.func_name.clone())
+            .filter(|name| !SKIP_PREFIXES.iter().any(|p| name.starts_with(p)))
+            .take(BACKTRACE_DEPTH)
+            .collect()
+    }
+
+    /// Called at slow path entry, lock address is a first argument.
+    fn on_wait(self: &Arc<Self>, dbg: &mut Debugger, kind: LockKind) -> Result<(), Error> {
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        let lock = dbg.get_register_value("rdi")? as usize;
+        let ret_addr = return_address(dbg)?;
+
+        let need_backtrace = !self
+            .state
+            .lock()
+            .unwrap()
+            .waiter_backtraces
+            .contains_key(&lock);
+        let backtrace = need_backtrace
+            .then(|| dbg.backtrace(pid).ok())
+            .flatten()
+            .map(|bt| Self::representative_backtrace(&bt));
+
+        let need_return_point = {
+            let mut state = self.state.lock().unwrap();
+            if let Some(bt) = backtrace {
+                state.waiter_backtraces.insert(lock, bt);
+            }
+            state.push(WaitEvent {
+                lock,
+                kind,
+                waiter: pid,
+                started_at: Instant::now(),
+                waited: None,
+            });
+            state.return_points.insert(ret_addr)
+        };
+
+        if need_return_point {
+            let oracle = self.clone();
+            dbg.set_transparent_breakpoint(CreateTransparentBreakpointRequest::address(
+                ret_addr,
+                move |dbg: &mut Debugger| {
+                    let pid = dbg.exploration_ctx().pid_on_focus();
+                    oracle.state.lock().unwrap().finish(pid);
+                },
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Called when a lock is released by owner and there are waiters.
+    fn on_wake(&self, dbg: &mut Debugger) -> Result<(), Error> {
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        let lock = dbg.get_register_value("rdi")? as usize;
+        self.state
+            .lock()
+            .unwrap()
+            .owners
+            .entry(lock)
+            .or_default()
+            .insert(pid);
+        Ok(())
+    }
+}
+
+impl Oracle for MutexOracle {
+    fn name(&self) -> &'static str {
+        "mutex"
+    }
+
+    fn ready_for_install(&self, dbg: &Debugger) -> bool {
+        let has_symbols = !dbg
+            .get_symbols(r"^std::sys::.*futex::(Mutex|RwLock)::")
+            .unwrap_or_default()
+            .is_empty();
+        if has_symbols {
+            // breakpoints of a previous debugee process are gone
+            *self.state.lock().unwrap() = State::default();
+        }
+        has_symbols
+    }
+
+    fn watch_points(self: Arc<Self>) -> Vec<CreateTransparentBreakpointRequest> {
+        let slow_paths = [
+            (
+                r"^std::sys::.*futex::Mutex::lock_contended::",
+                LockKind::Mutex,
+            ),
+            (
+                r"^std::sys::.*futex::RwLock::read_contended::",
+                LockKind::Read,
+            ),
+            (
+                r"^std::sys::.*futex::RwLock::write_contended::",
+                LockKind::Write,
+            ),
+        ];
+        let mut watch_points: Vec<_> = slow_paths
+            .into_iter()
+            .map(|(regex, kind)| {
+                let oracle = self.clone();
+                CreateTransparentBreakpointRequest::symbol(regex, move |dbg: &mut Debugger| {
+                    if let Err(e) = oracle.on_wait(dbg, kind) {
+                        warn!(target: "mutex oracle", "lock wait: {e}")
+                    }
+                })
+            })
+            .collect();
+
+        let wake_fns = [
+            r"^std::sys::.*futex::Mutex::wake::",
+            r"^std::sys::.*futex::RwLock::wake_writer_or_readers::",
+        ];
+        for regex in wake_fns {
+            let oracle = self.clone();
+            watch_points.push(CreateTransparentBreakpointRequest::symbol(
+                regex,
+                move |dbg: &mut Debugger| {
+                    if let Err(e) = oracle.on_wake(dbg) {
+                        warn!(target: "mutex oracle", "lock wake: {e}")
+                    }
+                },
+            ));
+        }
+
+        watch_points
+    }
+}
+
+#[cfg(feature = "ui")]
+fn format_pids(pids: &[Pid]) -> String {
+    if pids.is_empty() {
+        return "unknown".to_string();
+    }
+    pids.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(feature = "ui")]
+impl ConsolePlugin for MutexOracle {
+    fn print(&self, printer: &ExternalPrinter, subcommand: Option<&str>) {
+        let window = match subcommand {
+            None => DEFAULT_WINDOW,
+            Some(secs) => match secs.parse() {
+                Ok(secs) => Duration::from_secs(secs),
+                Err(_) => {
+                    printer.println("time window must be a number of seconds");
+                    return;
+                }
+            },
+        };
+
+        let locks = self.contention(window);
+        printer.println(format!(
+            "{} contended locks in the last {}s",
+            KeywordView::from(locks.len()),
+            window.as_secs()
+        ));
+
+        for lock in locks.into_iter().take(TOP_LOCKS) {
+            printer.println(format!(
+                "\n{} {}: {} waits, {:?} total wait time",
+                lock.kind,
+                AddressView::from(format!("{:#X}", lock.lock)),
+                lock.waits,
+                lock.wait_time,
+            ));
+            printer.println(format!("  waiters: {}", format_pids(&lock.waiters)));
+            printer.println(format!("  owners: {}", format_pids(&lock.owners)));
+            for func in lock.waiter_backtrace {
+                printer.println(format!("    {}", FunctionNameView::from(func)));
+            }
+        }
+    }
+
+    fn help(&self) -> &str {
+        "mutex - the most contended std Mutex and RwLock over the last 60 seconds, \
+        `oracle mutex <seconds>` use another time window"
+    }
+}
+
+#[cfg(feature = "ui")]
+impl TuiPlugin for MutexOracle {
+    fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
+        Box::new(tui::MutexComponent::new(self))
+    }
+}
+
+#[cfg(feature = "ui")]
+pub mod tui {
+    use crate::oracle::builtin::mutex::{format_pids, MutexOracle, DEFAULT_WINDOW};
+    use crate::ui::tui::app::port::UserEvent;
+    use crate::ui::tui::Msg;
+    use std::sync::Arc;
+    use tui_realm_stdlib::Table;
+    use tuirealm::command::{Cmd, Direction};
+    use tuirealm::event::{Key, KeyEvent};
+    use tuirealm::props::{Alignment, BorderType, Borders, Color, Style, TableBuilder, TextSpan};
+    use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent};
+
+    #[derive(MockComponent)]
+    pub struct MutexComponent {
+        component: Table,
+        oracle: Arc<MutexOracle>,
+    }
+
+    impl MutexComponent {
+        pub fn new(oracle: Arc<MutexOracle>) -> Self {
+            let component = Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(Color::LightYellow),
+                )
+                .title("Contended locks (last 60s)", Alignment::Center)
+                .inactive(Style::default().fg(Color::Gray))
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("▶")
+                .rewind(true)
+                .step(4)
+                .widths(&[18, 14, 8, 12, 16, 32])
+                .headers(&["Lock", "Kind", "Waits", "Wait time", "Owners", "Waiter"]);
+
+            let mut this = Self { component, oracle };
+            this.refresh_table();
+            this
+        }
+
+        fn refresh_table(&mut self) {
+            let mut table = TableBuilder::default();
+            let locks = self.oracle.contention(DEFAULT_WINDOW);
+            if locks.is_empty() {
+                for _ in 0..6 {
+                    table.add_col(TextSpan::from(""));
+                }
+                table.add_row();
+            }
+            for lock in locks {
+                table
+                    .add_col(TextSpan::from(format!("{:#X}", lock.lock)).fg(Color::LightBlue))
+                    .add_col(TextSpan::from(lock.kind.to_string()))
+                    .add_col(TextSpan::from(lock.waits.to_string()))
+                    .add_col(TextSpan::from(format!("{:?}", lock.wait_time)))
+                    .add_col(TextSpan::from(format_pids(&lock.owners)))
+                    .add_col(TextSpan::from(
+                        lock.waiter_backtrace.first().cloned().unwrap_or_default(),
+                    ))
+                    .add_row();
+            }
+            self.component
+                .attr(Attribute::Content, AttrValue::Table(table.build()));
+        }
+    }
+
+    impl Component<Msg, UserEvent> for MutexComponent {
+        fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+            match ev {
+                Event::Keyboard(KeyEvent {
+                    code: Key::Down, ..
+                }) => {
+                    self.perform(Cmd::Move(Direction::Down));
+                }
+                Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                    self.perform(Cmd::Move(Direction::Up));
+                }
+                Event::Tick => {
+                    self.refresh_table();
+                }
+                _ => {}
+            }
+
+            Some(Msg::None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_contention_summary() {
+        let mut state = State::default();
+        let (t1, t2, t3) = (Pid::from_raw(1), Pid::from_raw(2), Pid::from_raw(3));
+        let event = |lock, waiter, started_at| WaitEvent {
+            lock,
+            kind: LockKind::Mutex,
+            waiter,
+            started_at,
+            waited: None,
+        };
+
+        state.push(event(0x10, t1, Instant::now() - Duration::from_secs(120)));
+        state.finish(t1);
+        state.push(event(0x10, t1, Instant::now()));
+        state.finish(t1);
+        state.push(event(0x10, t2, Instant::now()));
+        state.push(event(0x20, t3, Instant::now()));
+        state.owners.entry(0x10).or_default().insert(t3);
+
+        let summary = state.summary(DEFAULT_WINDOW);
+        assert_eq!(summary.len(), 2);
+        let lock = summary.iter().find(|l| l.lock == 0x10).unwrap();
+        assert_eq!(lock.waits, 2);
+        assert_eq!(lock.waiters, vec![t1, t2]);
+        assert_eq!(lock.owners, vec![t3]);
+        assert!(state.in_progress.contains_key(&t2));
+    }
+}
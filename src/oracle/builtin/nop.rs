@@ -1,16 +1,24 @@
 use crate::debugger::{CreateTransparentBreakpointRequest, Debugger};
+#[cfg(feature = "ui")]
 use crate::oracle::builtin::nop::tui::NopComponent;
-use crate::oracle::{ConsolePlugin, Oracle, TuiPlugin};
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
 use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
 use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
 use crate::ui::tui::Msg;
 use std::sync::Arc;
+#[cfg(feature = "ui")]
 use tuirealm::Component;
 
 /// Nop-oracle, just for test purposes.
 #[derive(Default)]
 pub struct NopOracle {}
 
+#[cfg(feature = "ui")]
 impl ConsolePlugin for NopOracle {
     fn print(&self, printer: &ExternalPrinter, _: Option<&str>) {
         printer.println("nop");
@@ -21,6 +29,7 @@ impl ConsolePlugin for NopOracle {
     }
 }
 
+#[cfg(feature = "ui")]
 impl TuiPlugin for NopOracle {
     fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
         Box::<NopComponent>::default()
@@ -41,6 +50,7 @@ impl Oracle for NopOracle {
     }
 }
 
+#[cfg(feature = "ui")]
 pub mod tui {
     use crate::ui::tui::app::port::UserEvent;
     use crate::ui::tui::Msg;
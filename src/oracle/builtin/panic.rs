@@ -0,0 +1,529 @@
+//! Panic oracle: capture a context of every panic (message, location, thread, backtrace and
+//! locals of a panicking frame) into a report.
+//!
+//! Panics are caught at `core::panicking::panic_fmt` (panics with a formatted message) and
+//! `std::panicking::begin_panic` (panics with an arbitrary payload). Standard library is built
+//! without variables debug information, so a message is read from `fmt::Arguments` memory:
+//! string pieces are used as is and `&str` arguments are substituted,
+//! other arguments are shown as `{}`.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::unwind::Backtrace;
+#[cfg(feature = "ui")]
+use crate::debugger::variable::render::RenderRepr;
+use crate::debugger::variable::VariableIR;
+use crate::debugger::{CreateTransparentBreakpointRequest, Debugger, Error};
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
+use crate::ui::command::export;
+#[cfg(feature = "ui")]
+use crate::ui::console::print::style::{AddressView, FunctionNameView, KeywordView, PlaceView};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
+use crate::ui::console::variable::render_variable;
+#[cfg(feature = "ui")]
+use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
+use crate::ui::tui::Msg;
+use log::warn;
+use nix::unistd::Pid;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "ui")]
+use tuirealm::Component;
+
+/// Max length of a message piece, longer slices are not considered as strings.
+const MAX_PIECE_LEN: usize = 4096;
+
+/// Frames of functions with these prefixes are a part of panic machinery.
+const PANIC_PREFIXES: &[&str] = &[
+    "core::panicking::",
+    "std::panicking::",
+    "std::sys",
+    "std::rt::",
+    "core::option::",
+    "core::result::",
+    "<core::",
+    "<std::",
+    "rust_begin_unwind",
+    "rust_panic",
+];
+
+/// Frame of a panic backtrace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PanicFrame {
+    pub func_name: Option<String>,
+    pub ip: RelocatedAddress,
+    pub place: Option<(PathBuf, u64)>,
+}
+
+/// Context of a panic.
+#[derive(Clone)]
+pub struct PanicReport {
+    /// Panic message (if known).
+    pub message: Option<String>,
+    /// Source place of a panicking frame.
+    pub location: Option<(PathBuf, u64)>,
+    pub thread_num: Option<u32>,
+    pub thread_pid: Pid,
+    pub thread_name: Option<String>,
+    pub backtrace: Vec<PanicFrame>,
+    /// Number of a panicking frame (first frame outside of panic machinery) in a backtrace.
+    pub frame_num: Option<usize>,
+    /// Local variables of a panicking frame.
+    pub locals: Vec<VariableIR>,
+}
+
+/// Return number of a first frame outside of panic machinery that has a source place.
+fn panicking_frame(backtrace: &[PanicFrame]) -> Option<usize> {
+    backtrace.iter().position(|frame| {
+        frame.place.is_some()
+            && frame
+                .func_name
+                .as_deref()
+                .is_some_and(|name| !PANIC_PREFIXES.iter().any(|p| name.starts_with(p)))
+    })
+}
+
+/// [`PanicOracle`] capture a context of debugee panics.
+#[derive(Default)]
+pub struct PanicOracle {
+    reports: Mutex<Vec<PanicReport>>,
+}
+
+// SAFETY: this is safe to use panic oracle from any thread until someone try to
+// dereference pointers in captured variables (this lead reading of tracee threads memory),
+// dereference may be done only from tracer (ptrace) thread
+unsafe impl Send for PanicOracle {}
+unsafe impl Sync for PanicOracle {}
+
+impl PanicOracle {
+    /// Create a new oracle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return reports of all panics since debugee start.
+    pub fn reports(&self) -> Vec<PanicReport> {
+        self.reports.lock().unwrap().clone()
+    }
+
+    fn read_usize(dbg: &Debugger, addr: usize) -> Option<usize> {
+        let bytes = dbg.read_memory(addr, 8).ok()?;
+        Some(usize::from_ne_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Read a `&str` (pointer and length pair) at address.
+    fn read_str(dbg: &Debugger, addr: usize) -> Option<String> {
+        let ptr = Self::read_usize(dbg, addr)?;
+        let len = Self::read_usize(dbg, addr + 8)?;
+        if len > MAX_PIECE_LEN || (ptr == 0 && len != 0) {
+            return None;
+        }
+        if len == 0 {
+            return Some(String::new());
+        }
+        String::from_utf8(dbg.read_memory(ptr, len).ok()?).ok()
+    }
+
+    /// Read a slice of `&str` at address.
+    fn read_str_slice(dbg: &Debugger, addr: usize) -> Option<Vec<String>> {
+        let ptr = Self::read_usize(dbg, addr)?;
+        let len = Self::read_usize(dbg, addr + 8)?;
+        if ptr == 0 || len == 0 || len > MAX_PIECE_LEN {
+            return None;
+        }
+        (0..len)
+            .map(|i| Self::read_str(dbg, ptr + i * 16))
+            .collect()
+    }
+
+    /// Try to render an argument of `fmt::Arguments` (value and formatter pointers pair),
+    /// only `&str` arguments are rendered.
+    fn read_argument(dbg: &Debugger, addr: usize) -> Option<String> {
+        let words = [
+            Self::read_usize(dbg, addr)?,
+            Self::read_usize(dbg, addr + 8)?,
+        ];
+        // formatter is a pointer to a function, value is a pointer to data
+        let (value, formatter) = words.iter().find_map(|&formatter| {
+            let name = dbg
+                .find_function_name(RelocatedAddress::from(formatter))
+                .ok()
+                .flatten()?;
+            let value = words.into_iter().find(|&w| w != formatter)?;
+            Some((value, name))
+        })?;
+        if formatter.ends_with("as core::fmt::Display>::fmt") {
+            return Self::read_str(dbg, value);
+        }
+        None
+    }
+
+    /// Read panic message from `fmt::Arguments` structure, structure fields order is unknown,
+    /// so fields are recognized by their content.
+    fn read_message(dbg: &Debugger, args_addr: usize) -> Option<String> {
+        // three fat pointers: pieces, placeholders (may be null) and arguments
+        let fields = [args_addr, args_addr + 16, args_addr + 32];
+        let (pieces_idx, pieces) = fields
+            .iter()
+            .enumerate()
+            .find_map(|(i, &field)| Some((i, Self::read_str_slice(dbg, field)?)))?;
+
+        // arguments slice is the last non-empty field, except pieces
+        let args = fields
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != pieces_idx)
+            .filter_map(|(_, &field)| {
+                let ptr = Self::read_usize(dbg, field)?;
+                let len = Self::read_usize(dbg, field + 8)?;
+                (ptr != 0 && len != 0 && len <= MAX_PIECE_LEN).then_some((ptr, len))
+            })
+            .last();
+
+        let mut message = String::new();
+        for (i, piece) in pieces.iter().enumerate() {
+            message.push_str(piece);
+            let Some((args_ptr, args_len)) = args else {
+                continue;
+            };
+            if i < args_len && (i + 1 < pieces.len() || args_len >= pieces.len()) {
+                let arg = Self::read_argument(dbg, args_ptr + i * 16);
+                message.push_str(arg.as_deref().unwrap_or("{}"));
+            }
+        }
+        Some(message)
+    }
+
+    fn capture(dbg: &mut Debugger, message: Option<String>) -> Result<PanicReport, Error> {
+        let pid = dbg.exploration_ctx().pid_on_focus();
+        let bt: Backtrace = dbg.backtrace(pid)?;
+
+        let backtrace: Vec<_> = bt
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                // ip of a non-top frame is a return address, use call instruction for a place
+                let ip = if i == 0 {
+                    frame.ip
+                } else {
+                    frame.ip.offset(-1)
+                };
+                PanicFrame {
+                    func_name: frame.func_name.clone(),
+                    ip: frame.ip,
+                    place: dbg
+                        .find_place(ip)
+                        .ok()
+                        .flatten()
+                        .map(|place| (place.file, place.line_number)),
+                }
+            })
+            .collect();
+
+        let frame_num = panicking_frame(&backtrace);
+
+        let mut locals = vec![];
+        if let Some(num) = frame_num {
+            dbg.set_frame_into_focus(num as u32)?;
+            locals = dbg.read_local_variables().unwrap_or_default();
+            dbg.set_frame_into_focus(0)?;
+        }
+
+        let thread = dbg
+            .thread_state()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|snapshot| snapshot.thread.pid == pid);
+
+        Ok(PanicReport {
+            message,
+            location: frame_num.and_then(|num| backtrace[num].place.clone()),
+            thread_num: thread.as_ref().map(|t| t.thread.number),
+            thread_pid: pid,
+            thread_name: thread.and_then(|t| t.name),
+            backtrace,
+            frame_num,
+            locals,
+        })
+    }
+
+    fn on_panic(&self, dbg: &mut Debugger, with_message: bool) -> Result<(), Error> {
+        let message = if with_message {
+            let args_addr = dbg.get_register_value("rdi")? as usize;
+            Self::read_message(dbg, args_addr)
+        } else {
+            None
+        };
+        let report = Self::capture(dbg, message)?;
+        self.reports.lock().unwrap().push(report);
+        Ok(())
+    }
+}
+
+impl Oracle for PanicOracle {
+    fn name(&self) -> &'static str {
+        "panic"
+    }
+
+    fn ready_for_install(&self, dbg: &Debugger) -> bool {
+        let has_symbols = !dbg
+            .get_symbols(r"^core::panicking::panic_fmt::")
+            .unwrap_or_default()
+            .is_empty();
+        if has_symbols {
+            self.reports.lock().unwrap().clear();
+        }
+        has_symbols
+    }
+
+    fn watch_points(self: Arc<Self>) -> Vec<CreateTransparentBreakpointRequest> {
+        [
+            (r"^core::panicking::panic_fmt::", true),
+            (r"^std::panicking::begin_panic::", false),
+        ]
+        .into_iter()
+        .map(|(regex, with_message)| {
+            let oracle = self.clone();
+            CreateTransparentBreakpointRequest::symbol(regex, move |dbg: &mut Debugger| {
+                if let Err(e) = oracle.on_panic(dbg, with_message) {
+                    warn!(target: "panic oracle", "capture panic: {e}")
+                }
+            })
+        })
+        .collect()
+    }
+}
+
+#[cfg(feature = "ui")]
+impl PanicReport {
+    /// Return report as a JSON object.
+    pub fn to_json(&self) -> serde_json::Value {
+        let place = |place: &Option<(PathBuf, u64)>| {
+            place.as_ref().map(
+                |(file, line)| serde_json::json!({"file": file.to_string_lossy(), "line": line}),
+            )
+        };
+        serde_json::json!({
+            "message": self.message,
+            "location": place(&self.location),
+            "thread": {
+                "num": self.thread_num,
+                "pid": self.thread_pid.as_raw(),
+                "name": self.thread_name,
+            },
+            "backtrace": self.backtrace.iter().map(|frame| serde_json::json!({
+                "function": frame.func_name,
+                "ip": format!("{}", frame.ip),
+                "place": place(&frame.place),
+            })).collect::<Vec<_>>(),
+            "frame_num": self.frame_num,
+            "locals": self.locals.iter().map(|var| {
+                (var.name(), export::to_value(var))
+            }).collect::<serde_json::Map<_, _>>(),
+        })
+    }
+}
+
+#[cfg(feature = "ui")]
+impl ConsolePlugin for PanicOracle {
+    fn print(&self, printer: &ExternalPrinter, subcommand: Option<&str>) {
+        let reports = self.reports();
+        if subcommand == Some("json") {
+            let reports: Vec<_> = reports.iter().map(PanicReport::to_json).collect();
+            printer.println(serde_json::to_string_pretty(&reports).expect("infallible"));
+            return;
+        }
+
+        let Some(report) = reports.last() else {
+            printer.println("no panics");
+            return;
+        };
+        if reports.len() > 1 {
+            printer.println(format!(
+                "{} panics, the last one is shown",
+                KeywordView::from(reports.len())
+            ));
+        }
+
+        let thread = format!(
+            "thread #{} ({}{})",
+            report
+                .thread_num
+                .map(|num| num.to_string())
+                .unwrap_or_else(|| "?".to_string()),
+            report.thread_pid,
+            report
+                .thread_name
+                .as_ref()
+                .map(|name| format!(", {name}"))
+                .unwrap_or_default()
+        );
+        let location = match report.location {
+            Some((ref file, line)) => format!(" at {}", PlaceView::new(file, line)),
+            None => String::new(),
+        };
+        printer.println(format!(
+            "{thread} panicked{location}: {}",
+            KeywordView::from(report.message.as_deref().unwrap_or("<unknown payload>"))
+        ));
+
+        printer.println("\nbacktrace:");
+        for (i, frame) in report.backtrace.iter().enumerate() {
+            let marker = if Some(i) == report.frame_num {
+                "▶"
+            } else {
+                " "
+            };
+            let place = match frame.place {
+                Some((ref file, line)) => format!(" at {}", PlaceView::new(file, line)),
+                None => String::new(),
+            };
+            printer.println(format!(
+                "{marker} #{i} {} - {}{place}",
+                AddressView::from(frame.ip),
+                FunctionNameView::from(frame.func_name.as_deref()),
+            ));
+        }
+
+        if !report.locals.is_empty() {
+            printer.println("\nlocals:");
+            for var in &report.locals {
+                match render_variable(var) {
+                    Ok(rendered) => printer.println(rendered),
+                    Err(e) => printer.println(format!("{}: {e}", var.name())),
+                }
+            }
+        }
+    }
+
+    fn help(&self) -> &str {
+        "panic - context of the last panic (message, location, thread, backtrace and locals), \
+        `oracle panic json` prints all panics as JSON"
+    }
+}
+
+#[cfg(feature = "ui")]
+impl TuiPlugin for PanicOracle {
+    fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
+        Box::new(tui::PanicComponent::new(self))
+    }
+}
+
+#[cfg(feature = "ui")]
+pub mod tui {
+    use crate::oracle::builtin::panic::PanicOracle;
+    use crate::ui::tui::app::port::UserEvent;
+    use crate::ui::tui::Msg;
+    use std::sync::Arc;
+    use tui_realm_stdlib::Table;
+    use tuirealm::command::{Cmd, Direction};
+    use tuirealm::event::{Key, KeyEvent};
+    use tuirealm::props::{Alignment, BorderType, Borders, Color, Style, TableBuilder, TextSpan};
+    use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent};
+
+    #[derive(MockComponent)]
+    pub struct PanicComponent {
+        component: Table,
+        oracle: Arc<PanicOracle>,
+    }
+
+    impl PanicComponent {
+        pub fn new(oracle: Arc<PanicOracle>) -> Self {
+            let component = Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(Color::LightYellow),
+                )
+                .title("Panics", Alignment::Center)
+                .inactive(Style::default().fg(Color::Gray))
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("▶")
+                .rewind(true)
+                .step(4)
+                .widths(&[12, 48, 40])
+                .headers(&["Thread", "Location", "Message"]);
+
+            let mut this = Self { component, oracle };
+            this.refresh_table();
+            this
+        }
+
+        fn refresh_table(&mut self) {
+            let mut table = TableBuilder::default();
+            let reports = self.oracle.reports();
+            if reports.is_empty() {
+                for _ in 0..3 {
+                    table.add_col(TextSpan::from(""));
+                }
+                table.add_row();
+            }
+            for report in reports.into_iter().rev() {
+                let location = report
+                    .location
+                    .map(|(file, line)| format!("{}:{line}", file.display()))
+                    .unwrap_or_else(|| "???".to_string());
+                table
+                    .add_col(TextSpan::from(report.thread_pid.to_string()).fg(Color::LightBlue))
+                    .add_col(TextSpan::from(location).fg(Color::Green))
+                    .add_col(
+                        TextSpan::from(report.message.as_deref().unwrap_or("<unknown payload>"))
+                            .fg(Color::Red),
+                    )
+                    .add_row();
+            }
+            self.component
+                .attr(Attribute::Content, AttrValue::Table(table.build()));
+        }
+    }
+
+    impl Component<Msg, UserEvent> for PanicComponent {
+        fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+            match ev {
+                Event::Keyboard(KeyEvent {
+                    code: Key::Down, ..
+                }) => {
+                    self.perform(Cmd::Move(Direction::Down));
+                }
+                Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                    self.perform(Cmd::Move(Direction::Up));
+                }
+                Event::Tick => {
+                    self.refresh_table();
+                }
+                _ => {}
+            }
+
+            Some(Msg::None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_panicking_frame() {
+        let frame = |name: &str, place: bool| PanicFrame {
+            func_name: Some(name.to_string()),
+            ip: RelocatedAddress::from(0x10_usize),
+            place: place.then(|| (PathBuf::from("main.rs"), 1)),
+        };
+        let backtrace = vec![
+            frame("core::panicking::panic_fmt", true),
+            frame("core::result::unwrap_failed", true),
+            frame("<core::result::Result<T,E>>::unwrap", true),
+            frame("calc::divide", false),
+            frame("calc::main", true),
+        ];
+        assert_eq!(panicking_frame(&backtrace), Some(4));
+        assert_eq!(panicking_frame(&backtrace[..4]), None);
+    }
+}
@@ -0,0 +1,673 @@
+//! Perf oracle: hardware counters and sampled stacks of a debugee (linux perf events).
+//!
+//! Counters (instructions, cycles, cache misses) are counted only while debugee is running,
+//! stops at breakpoints or steps are not included. Stacks are sampled on every cpu and
+//! collected by a background thread, addresses are resolved into function names
+//! when debugee stops.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::{CreateTransparentBreakpointRequest, Debugger};
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::style::{FunctionNameView, KeywordView};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
+use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
+use crate::ui::tui::Msg;
+use log::{debug, warn};
+use nix::libc;
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::fd::{FromRawFd, OwnedFd};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use std::{mem, ptr, thread};
+#[cfg(feature = "ui")]
+use tuirealm::Component;
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+
+const PERF_SAMPLE_IP: u64 = 1 << 0;
+const PERF_SAMPLE_TID: u64 = 1 << 1;
+const PERF_SAMPLE_CALLCHAIN: u64 = 1 << 5;
+
+const ATTR_FLAG_INHERIT: u64 = 1 << 1;
+const ATTR_FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+const ATTR_FLAG_EXCLUDE_HV: u64 = 1 << 6;
+const ATTR_FLAG_FREQ: u64 = 1 << 10;
+const ATTR_FLAG_EXCLUDE_CALLCHAIN_KERNEL: u64 = 1 << 21;
+
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+const PERF_RECORD_LOST: u32 = 2;
+const PERF_RECORD_SAMPLE: u32 = 9;
+/// Callchain entries above this value are context markers (like `PERF_CONTEXT_USER`).
+const PERF_CONTEXT_MAX: u64 = -4095_i64 as u64;
+
+/// Sampling frequency (samples per second).
+const SAMPLE_FREQ: u64 = 99;
+/// Size of a ring buffer data area in pages, must be a power of two.
+const RING_BUFFER_PAGES: usize = 128;
+/// How often background thread reads samples from ring buffers.
+const DRAIN_INTERVAL: Duration = Duration::from_millis(100);
+/// Count of functions in hot-function summary.
+#[cfg(feature = "ui")]
+const HOT_FUNCTIONS: usize = 15;
+
+/// `struct perf_event_attr` (`PERF_ATTR_SIZE_VER5`).
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    r#type: u32,
+    size: u32,
+    config: u64,
+    sample_freq: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+    config2: u64,
+    branch_sample_type: u64,
+    sample_regs_user: u64,
+    sample_stack_user: u32,
+    clockid: i32,
+    sample_regs_intr: u64,
+    aux_watermark: u32,
+    sample_max_stack: u16,
+    reserved: u16,
+}
+
+fn perf_event_open(attr: &PerfEventAttr, pid: Pid, cpu: i32) -> io::Result<OwnedFd> {
+    // SAFETY: attr is a valid `perf_event_attr` structure with a correct size field
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_perf_event_open,
+            attr as *const PerfEventAttr,
+            pid.as_raw(),
+            cpu,
+            -1,
+            PERF_FLAG_FD_CLOEXEC,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: fd is a new opened file descriptor
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as i32) })
+}
+
+/// Hardware counter of all debugee threads.
+struct Counter {
+    name: &'static str,
+    file: File,
+}
+
+impl Counter {
+    fn open(name: &'static str, config: u64, pid: Pid) -> io::Result<Self> {
+        let attr = PerfEventAttr {
+            r#type: PERF_TYPE_HARDWARE,
+            size: mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            flags: ATTR_FLAG_INHERIT | ATTR_FLAG_EXCLUDE_KERNEL | ATTR_FLAG_EXCLUDE_HV,
+            ..PerfEventAttr::default()
+        };
+        Ok(Self {
+            name,
+            file: File::from(perf_event_open(&attr, pid, -1)?),
+        })
+    }
+
+    fn value(&self) -> io::Result<u64> {
+        let mut buf = [0; 8];
+        (&self.file).read_exact(&mut buf)?;
+        Ok(u64::from_ne_bytes(buf))
+    }
+}
+
+/// Sampling event of a single cpu with a memory mapped ring buffer.
+struct Sampler {
+    _fd: OwnedFd,
+    base: *mut u8,
+    page_size: usize,
+}
+
+// SAFETY: ring buffer memory is used only under the oracle state mutex
+unsafe impl Send for Sampler {}
+
+impl Sampler {
+    fn open(pid: Pid, cpu: i32) -> io::Result<Self> {
+        let attr = PerfEventAttr {
+            r#type: PERF_TYPE_HARDWARE,
+            size: mem::size_of::<PerfEventAttr>() as u32,
+            config: PERF_COUNT_HW_CPU_CYCLES,
+            sample_freq: SAMPLE_FREQ,
+            sample_type: PERF_SAMPLE_IP | PERF_SAMPLE_TID | PERF_SAMPLE_CALLCHAIN,
+            flags: ATTR_FLAG_INHERIT
+                | ATTR_FLAG_EXCLUDE_KERNEL
+                | ATTR_FLAG_EXCLUDE_HV
+                | ATTR_FLAG_FREQ
+                | ATTR_FLAG_EXCLUDE_CALLCHAIN_KERNEL,
+            ..PerfEventAttr::default()
+        };
+        let fd = perf_event_open(&attr, pid, cpu)?;
+
+        // SAFETY: sysconf has no preconditions
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        // SAFETY: map a ring buffer (metadata page and data pages) of opened perf event
+        let base = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                (RING_BUFFER_PAGES + 1) * page_size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                std::os::fd::AsRawFd::as_raw_fd(&fd),
+                0,
+            )
+        };
+        if base == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            _fd: fd,
+            base: base as *mut u8,
+            page_size,
+        })
+    }
+
+    fn data_size(&self) -> usize {
+        RING_BUFFER_PAGES * self.page_size
+    }
+
+    /// Return `data_head` and `data_tail` fields of `struct perf_event_mmap_page`.
+    fn head_and_tail(&self) -> (&AtomicU64, &AtomicU64) {
+        // SAFETY: fields are placed at offsets 1024 and 1032 of a metadata page
+        unsafe {
+            (
+                &*(self.base.add(1024) as *const AtomicU64),
+                &*(self.base.add(1032) as *const AtomicU64),
+            )
+        }
+    }
+
+    fn read_u64(&self, offset: u64) -> u64 {
+        let mut bytes = [0; 8];
+        self.copy(offset, &mut bytes);
+        u64::from_ne_bytes(bytes)
+    }
+
+    /// Copy bytes from a data area, data area is a cyclic buffer.
+    fn copy(&self, offset: u64, dst: &mut [u8]) {
+        let size = self.data_size();
+        for (i, byte) in dst.iter_mut().enumerate() {
+            let pos = (offset as usize + i) % size;
+            // SAFETY: pos is inside data area
+            *byte = unsafe { *self.base.add(self.page_size + pos) };
+        }
+    }
+
+    /// Read all available records, pass stacks (instruction pointer first) into a callback.
+    /// Return number of lost samples.
+    fn drain(&self, mut on_stack: impl FnMut(Vec<u64>)) -> u64 {
+        let (head, tail) = self.head_and_tail();
+        let head_pos = head.load(Ordering::Acquire);
+        let mut pos = tail.load(Ordering::Relaxed);
+        let mut lost = 0;
+
+        while pos < head_pos {
+            let mut header = [0; 8];
+            self.copy(pos, &mut header);
+            let r#type = u32::from_ne_bytes(header[0..4].try_into().expect("infallible"));
+            let size = u16::from_ne_bytes(header[6..8].try_into().expect("infallible")) as u64;
+            if size == 0 {
+                break;
+            }
+
+            match r#type {
+                PERF_RECORD_SAMPLE => {
+                    // ip, pid + tid, callchain length, callchain
+                    let ip = self.read_u64(pos + 8);
+                    let nr = self.read_u64(pos + 24);
+                    let mut stack = vec![ip];
+                    for i in 0..nr {
+                        let addr = self.read_u64(pos + 32 + i * 8);
+                        if addr < PERF_CONTEXT_MAX && addr != ip {
+                            stack.push(addr);
+                        }
+                    }
+                    on_stack(stack);
+                }
+                PERF_RECORD_LOST => lost += self.read_u64(pos + 16),
+                _ => {}
+            }
+            pos += size;
+        }
+
+        tail.store(head_pos, Ordering::Release);
+        lost
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        // SAFETY: memory was mapped in `Sampler::open` with the same size
+        unsafe {
+            libc::munmap(
+                self.base as *mut libc::c_void,
+                (RING_BUFFER_PAGES + 1) * self.page_size,
+            );
+        }
+    }
+}
+
+/// Function statistic in a hot-function summary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotFunction {
+    pub name: String,
+    /// Number of samples where function is on top of a stack.
+    pub self_samples: u64,
+    /// Number of samples where function is anywhere in a stack.
+    pub total_samples: u64,
+}
+
+#[derive(Default)]
+struct State {
+    counters: Vec<Counter>,
+    samplers: Vec<Sampler>,
+    /// Sampled stacks, not resolved yet, and their counts.
+    pending: HashMap<Vec<u64>, u64>,
+    /// Resolved stacks (function names) and their counts.
+    stacks: HashMap<Vec<String>, u64>,
+    samples: u64,
+    lost: u64,
+    /// Cache of resolved addresses.
+    names: HashMap<u64, String>,
+    /// Incremented at each debugee start, used to stop an outdated background thread.
+    generation: u64,
+}
+
+impl State {
+    fn drain(&mut self) {
+        let pending = &mut self.pending;
+        for sampler in &self.samplers {
+            self.lost += sampler.drain(|stack| *pending.entry(stack).or_default() += 1);
+        }
+    }
+}
+
+/// [`PerfOracle`] collect hardware counters and sampled stacks while debugee is running.
+#[derive(Default)]
+pub struct PerfOracle {
+    state: Arc<Mutex<State>>,
+}
+
+impl PerfOracle {
+    /// Create a new oracle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return counter names and values.
+    pub fn counters(&self) -> Vec<(&'static str, u64)> {
+        let state = self.state.lock().unwrap();
+        state
+            .counters
+            .iter()
+            .filter_map(|counter| Some((counter.name, counter.value().ok()?)))
+            .collect()
+    }
+
+    /// Return number of collected and lost samples.
+    pub fn sample_count(&self) -> (u64, u64) {
+        let state = self.state.lock().unwrap();
+        (state.samples, state.lost)
+    }
+
+    /// Return functions with the most number of samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit`: max functions count
+    pub fn hot_functions(&self, limit: usize) -> Vec<HotFunction> {
+        let state = self.state.lock().unwrap();
+        let mut functions: HashMap<&str, HotFunction> = HashMap::new();
+        for (stack, &count) in &state.stacks {
+            for (depth, name) in stack.iter().enumerate() {
+                // recursive calls are counted once
+                if stack[..depth].contains(name) {
+                    continue;
+                }
+                let func = functions.entry(name).or_insert_with(|| HotFunction {
+                    name: name.clone(),
+                    self_samples: 0,
+                    total_samples: 0,
+                });
+                func.total_samples += count;
+                if depth == 0 {
+                    func.self_samples += count;
+                }
+            }
+        }
+
+        let mut functions: Vec<_> = functions.into_values().collect();
+        functions.sort_by(|f1, f2| {
+            (f2.self_samples, f2.total_samples).cmp(&(f1.self_samples, f1.total_samples))
+        });
+        functions.truncate(limit);
+        functions
+    }
+
+    /// Forget collected samples, counters continue counting.
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.drain();
+        state.pending.clear();
+        state.stacks.clear();
+        state.samples = 0;
+        state.lost = 0;
+    }
+
+    fn open(&self, pid: Pid) -> io::Result<()> {
+        let counters = vec![
+            Counter::open("instructions", PERF_COUNT_HW_INSTRUCTIONS, pid)?,
+            Counter::open("cycles", PERF_COUNT_HW_CPU_CYCLES, pid)?,
+            Counter::open("cache misses", PERF_COUNT_HW_CACHE_MISSES, pid)?,
+        ];
+
+        // SAFETY: sysconf has no preconditions
+        let cpus = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_ONLN) }.max(1) as i32;
+        let samplers: Vec<_> = (0..cpus)
+            .filter_map(|cpu| match Sampler::open(pid, cpu) {
+                Ok(sampler) => Some(sampler),
+                Err(e) => {
+                    warn!(target: "perf oracle", "sampling on cpu {cpu}: {e}");
+                    None
+                }
+            })
+            .collect();
+
+        let mut state = self.state.lock().unwrap();
+        let generation = state.generation + 1;
+        *state = State {
+            counters,
+            samplers,
+            generation,
+            ..State::default()
+        };
+        drop(state);
+
+        let state = Arc::downgrade(&self.state);
+        thread::spawn(move || drain_loop(state, generation));
+        Ok(())
+    }
+}
+
+/// Read ring buffers periodically, so they are not overflowed while debugee is running.
+/// Exits when oracle is dropped or a new debugee process is started.
+fn drain_loop(state: Weak<Mutex<State>>, generation: u64) {
+    loop {
+        thread::sleep(DRAIN_INTERVAL);
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        let mut state = state.lock().unwrap();
+        if state.generation != generation {
+            return;
+        }
+        state.drain();
+    }
+}
+
+impl Oracle for PerfOracle {
+    fn name(&self) -> &'static str {
+        "perf"
+    }
+
+    fn ready_for_install(&self, dbg: &Debugger) -> bool {
+        let pid = dbg.process().pid();
+        // probe counter is closed right away, events are opened at install
+        match Counter::open("probe", PERF_COUNT_HW_INSTRUCTIONS, pid) {
+            Ok(_) => true,
+            Err(e) => {
+                debug!(target: "perf oracle", "perf events are not available: {e}");
+                false
+            }
+        }
+    }
+
+    fn on_install(&self, dbg: &Debugger) {
+        if let Err(e) = self.open(dbg.process().pid()) {
+            warn!(target: "perf oracle", "open perf events: {e}");
+        }
+    }
+
+    fn watch_points(self: Arc<Self>) -> Vec<CreateTransparentBreakpointRequest> {
+        vec![]
+    }
+
+    fn on_stop(&self, dbg: &Debugger) {
+        let mut state = self.state.lock().unwrap();
+        state.drain();
+
+        let state = &mut *state;
+        for (stack, count) in state.pending.drain() {
+            let names: Vec<String> = stack
+                .into_iter()
+                .map(|addr| {
+                    state
+                        .names
+                        .entry(addr)
+                        .or_insert_with(|| {
+                            let pc = RelocatedAddress::from(addr as usize);
+                            dbg.find_function_name(pc)
+                                .ok()
+                                .flatten()
+                                .map(|name| name.trim_start_matches("::").to_string())
+                                .unwrap_or_else(|| format!("{addr:#x}"))
+                        })
+                        .clone()
+                })
+                .collect();
+            *state.stacks.entry(names).or_default() += count;
+            state.samples += count;
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+impl ConsolePlugin for PerfOracle {
+    fn print(&self, printer: &ExternalPrinter, subcommand: Option<&str>) {
+        if subcommand == Some("reset") {
+            self.reset();
+            printer.println("Samples are reset");
+            return;
+        }
+
+        for (name, value) in self.counters() {
+            printer.println(format!("{name:<15} {}", KeywordView::from(value)));
+        }
+
+        let (samples, lost) = self.sample_count();
+        printer.println(format!(
+            "\n{} samples ({lost} lost)\n",
+            KeywordView::from(samples)
+        ));
+        if samples == 0 {
+            return;
+        }
+
+        printer.println(format!("{:<8} {:<8} function", "self", "total"));
+        for func in self.hot_functions(HOT_FUNCTIONS) {
+            printer.println(format!(
+                "{:<8} {:<8} {}",
+                format!("{:.1}%", func.self_samples as f64 * 100.0 / samples as f64),
+                format!("{:.1}%", func.total_samples as f64 * 100.0 / samples as f64),
+                FunctionNameView::from(func.name),
+            ));
+        }
+    }
+
+    fn help(&self) -> &str {
+        "perf - hardware counters and hot functions (sampled while debugee is running), \
+        `oracle perf reset` forgets collected samples"
+    }
+}
+
+#[cfg(feature = "ui")]
+impl TuiPlugin for PerfOracle {
+    fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
+        Box::new(tui::PerfComponent::new(self))
+    }
+}
+
+#[cfg(feature = "ui")]
+pub mod tui {
+    use crate::oracle::builtin::perf::{PerfOracle, HOT_FUNCTIONS};
+    use crate::ui::style::{self, StyleElement};
+    use crate::ui::tui::app::port::UserEvent;
+    use crate::ui::tui::Msg;
+    use std::sync::Arc;
+    use tui_realm_stdlib::Table;
+    use tuirealm::command::{Cmd, Direction};
+    use tuirealm::event::{Key, KeyEvent};
+    use tuirealm::props::{Alignment, BorderType, Borders, Color, Style, TableBuilder, TextSpan};
+    use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent};
+
+    #[derive(MockComponent)]
+    pub struct PerfComponent {
+        component: Table,
+        oracle: Arc<PerfOracle>,
+    }
+
+    impl PerfComponent {
+        pub fn new(oracle: Arc<PerfOracle>) -> Self {
+            let component = Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(style::tui_color(StyleElement::Panel)),
+                )
+                .title("Hot functions", Alignment::Center)
+                .inactive(Style::default().fg(Color::Gray))
+                .scroll(true)
+                .highlighted_color(style::tui_color(StyleElement::Panel))
+                .highlighted_str("▶")
+                .rewind(true)
+                .step(4)
+                .widths(&[10, 10, 80])
+                .headers(&["Self", "Total", "Function"]);
+
+            let mut this = Self { component, oracle };
+            this.refresh_table();
+            this
+        }
+
+        fn refresh_table(&mut self) {
+            let mut table = TableBuilder::default();
+            for (name, value) in self.oracle.counters() {
+                table
+                    .add_col(TextSpan::from(""))
+                    .add_col(
+                        TextSpan::from(value.to_string())
+                            .fg(style::tui_color(StyleElement::Keyword)),
+                    )
+                    .add_col(TextSpan::from(name))
+                    .add_row();
+            }
+
+            let (samples, _) = self.oracle.sample_count();
+            for func in self.oracle.hot_functions(HOT_FUNCTIONS) {
+                let percent = |count: u64| format!("{:.1}%", count as f64 * 100.0 / samples as f64);
+                table
+                    .add_col(
+                        TextSpan::from(percent(func.self_samples))
+                            .fg(style::tui_color(StyleElement::Keyword)),
+                    )
+                    .add_col(TextSpan::from(percent(func.total_samples)))
+                    .add_col(
+                        TextSpan::from(func.name).fg(style::tui_color(StyleElement::FunctionName)),
+                    )
+                    .add_row();
+            }
+
+            if self.oracle.counters().is_empty() && samples == 0 {
+                table
+                    .add_col(TextSpan::from(""))
+                    .add_col(TextSpan::from(""))
+                    .add_col(TextSpan::from(""))
+                    .add_row();
+            }
+
+            self.component
+                .attr(Attribute::Content, AttrValue::Table(table.build()));
+        }
+    }
+
+    impl Component<Msg, UserEvent> for PerfComponent {
+        fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+            match ev {
+                Event::Keyboard(KeyEvent {
+                    code: Key::Down, ..
+                }) => {
+                    self.perform(Cmd::Move(Direction::Down));
+                }
+                Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                    self.perform(Cmd::Move(Direction::Up));
+                }
+                Event::Tick => {
+                    self.refresh_table();
+                }
+                _ => {}
+            }
+
+            Some(Msg::None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hot_functions() {
+        let oracle = PerfOracle::new();
+        {
+            let mut state = oracle.state.lock().unwrap();
+            let stack = |names: &[&str]| names.iter().map(ToString::to_string).collect();
+            state.stacks.insert(stack(&["leaf", "work", "main"]), 3);
+            state.stacks.insert(stack(&["work", "main"]), 1);
+            state.stacks.insert(stack(&["fib", "fib", "main"]), 2);
+            state.samples = 6;
+        }
+
+        let hot = oracle.hot_functions(2);
+        assert_eq!(
+            hot,
+            vec![
+                HotFunction {
+                    name: "leaf".to_string(),
+                    self_samples: 3,
+                    total_samples: 3,
+                },
+                HotFunction {
+                    name: "fib".to_string(),
+                    self_samples: 2,
+                    total_samples: 2,
+                },
+            ]
+        );
+    }
+}
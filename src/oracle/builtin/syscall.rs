@@ -0,0 +1,1060 @@
+//! Syscall tracing oracle.
+//!
+//! Like `strace`, debugee threads are resumed with `PTRACE_SYSCALL`, so each of them stops
+//! at every syscall entry and exit. Arguments are decoded at syscall entry (output buffers -
+//! at syscall exit), a record is logged when syscall returns. Tracing can be switched off
+//! and on between debugee stops, it is filtered by syscall names and thread numbers.
+
+use crate::debugger::{CreateTransparentBreakpointRequest, Debugger, SyscallStop};
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::style::{ErrorView, FunctionNameView, KeywordView};
+#[cfg(feature = "ui")]
+use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
+use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
+use crate::ui::tui::Msg;
+use nix::errno::Errno;
+use nix::unistd::Pid;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+#[cfg(feature = "ui")]
+use tuirealm::Component;
+
+/// Max number of stored records.
+const MAX_RECORDS: usize = 10_000;
+/// Number of records printed by `oracle syscall` command.
+#[cfg(feature = "ui")]
+const LAST_RECORDS: usize = 50;
+/// Max number of bytes shown for string and buffer arguments.
+const MAX_STR_LEN: usize = 64;
+const PAGE_SIZE: usize = 4096;
+const AT_FDCWD: i32 = -100;
+
+/// Names of x86_64 syscalls from 0 to 334.
+const SYSCALL_NAMES: &[&str] = &[
+    "read",
+    "write",
+    "open",
+    "close",
+    "stat",
+    "fstat",
+    "lstat",
+    "poll",
+    "lseek",
+    "mmap",
+    "mprotect",
+    "munmap",
+    "brk",
+    "rt_sigaction",
+    "rt_sigprocmask",
+    "rt_sigreturn",
+    "ioctl",
+    "pread64",
+    "pwrite64",
+    "readv",
+    "writev",
+    "access",
+    "pipe",
+    "select",
+    "sched_yield",
+    "mremap",
+    "msync",
+    "mincore",
+    "madvise",
+    "shmget",
+    "shmat",
+    "shmctl",
+    "dup",
+    "dup2",
+    "pause",
+    "nanosleep",
+    "getitimer",
+    "alarm",
+    "setitimer",
+    "getpid",
+    "sendfile",
+    "socket",
+    "connect",
+    "accept",
+    "sendto",
+    "recvfrom",
+    "sendmsg",
+    "recvmsg",
+    "shutdown",
+    "bind",
+    "listen",
+    "getsockname",
+    "getpeername",
+    "socketpair",
+    "setsockopt",
+    "getsockopt",
+    "clone",
+    "fork",
+    "vfork",
+    "execve",
+    "exit",
+    "wait4",
+    "kill",
+    "uname",
+    "semget",
+    "semop",
+    "semctl",
+    "shmdt",
+    "msgget",
+    "msgsnd",
+    "msgrcv",
+    "msgctl",
+    "fcntl",
+    "flock",
+    "fsync",
+    "fdatasync",
+    "truncate",
+    "ftruncate",
+    "getdents",
+    "getcwd",
+    "chdir",
+    "fchdir",
+    "rename",
+    "mkdir",
+    "rmdir",
+    "creat",
+    "link",
+    "unlink",
+    "symlink",
+    "readlink",
+    "chmod",
+    "fchmod",
+    "chown",
+    "fchown",
+    "lchown",
+    "umask",
+    "gettimeofday",
+    "getrlimit",
+    "getrusage",
+    "sysinfo",
+    "times",
+    "ptrace",
+    "getuid",
+    "syslog",
+    "getgid",
+    "setuid",
+    "setgid",
+    "geteuid",
+    "getegid",
+    "setpgid",
+    "getppid",
+    "getpgrp",
+    "setsid",
+    "setreuid",
+    "setregid",
+    "getgroups",
+    "setgroups",
+    "setresuid",
+    "getresuid",
+    "setresgid",
+    "getresgid",
+    "getpgid",
+    "setfsuid",
+    "setfsgid",
+    "getsid",
+    "capget",
+    "capset",
+    "rt_sigpending",
+    "rt_sigtimedwait",
+    "rt_sigqueueinfo",
+    "rt_sigsuspend",
+    "sigaltstack",
+    "utime",
+    "mknod",
+    "uselib",
+    "personality",
+    "ustat",
+    "statfs",
+    "fstatfs",
+    "sysfs",
+    "getpriority",
+    "setpriority",
+    "sched_setparam",
+    "sched_getparam",
+    "sched_setscheduler",
+    "sched_getscheduler",
+    "sched_get_priority_max",
+    "sched_get_priority_min",
+    "sched_rr_get_interval",
+    "mlock",
+    "munlock",
+    "mlockall",
+    "munlockall",
+    "vhangup",
+    "modify_ldt",
+    "pivot_root",
+    "_sysctl",
+    "prctl",
+    "arch_prctl",
+    "adjtimex",
+    "setrlimit",
+    "chroot",
+    "sync",
+    "acct",
+    "settimeofday",
+    "mount",
+    "umount2",
+    "swapon",
+    "swapoff",
+    "reboot",
+    "sethostname",
+    "setdomainname",
+    "iopl",
+    "ioperm",
+    "create_module",
+    "init_module",
+    "delete_module",
+    "get_kernel_syms",
+    "query_module",
+    "quotactl",
+    "nfsservctl",
+    "getpmsg",
+    "putpmsg",
+    "afs_syscall",
+    "tuxcall",
+    "security",
+    "gettid",
+    "readahead",
+    "setxattr",
+    "lsetxattr",
+    "fsetxattr",
+    "getxattr",
+    "lgetxattr",
+    "fgetxattr",
+    "listxattr",
+    "llistxattr",
+    "flistxattr",
+    "removexattr",
+    "lremovexattr",
+    "fremovexattr",
+    "tkill",
+    "time",
+    "futex",
+    "sched_setaffinity",
+    "sched_getaffinity",
+    "set_thread_area",
+    "io_setup",
+    "io_destroy",
+    "io_getevents",
+    "io_submit",
+    "io_cancel",
+    "get_thread_area",
+    "lookup_dcookie",
+    "epoll_create",
+    "epoll_ctl_old",
+    "epoll_wait_old",
+    "remap_file_pages",
+    "getdents64",
+    "set_tid_address",
+    "restart_syscall",
+    "semtimedop",
+    "fadvise64",
+    "timer_create",
+    "timer_settime",
+    "timer_gettime",
+    "timer_getoverrun",
+    "timer_delete",
+    "clock_settime",
+    "clock_gettime",
+    "clock_getres",
+    "clock_nanosleep",
+    "exit_group",
+    "epoll_wait",
+    "epoll_ctl",
+    "tgkill",
+    "utimes",
+    "vserver",
+    "mbind",
+    "set_mempolicy",
+    "get_mempolicy",
+    "mq_open",
+    "mq_unlink",
+    "mq_timedsend",
+    "mq_timedreceive",
+    "mq_notify",
+    "mq_getsetattr",
+    "kexec_load",
+    "waitid",
+    "add_key",
+    "request_key",
+    "keyctl",
+    "ioprio_set",
+    "ioprio_get",
+    "inotify_init",
+    "inotify_add_watch",
+    "inotify_rm_watch",
+    "migrate_pages",
+    "openat",
+    "mkdirat",
+    "mknodat",
+    "fchownat",
+    "futimesat",
+    "newfstatat",
+    "unlinkat",
+    "renameat",
+    "linkat",
+    "symlinkat",
+    "readlinkat",
+    "fchmodat",
+    "faccessat",
+    "pselect6",
+    "ppoll",
+    "unshare",
+    "set_robust_list",
+    "get_robust_list",
+    "splice",
+    "tee",
+    "sync_file_range",
+    "vmsplice",
+    "move_pages",
+    "utimensat",
+    "epoll_pwait",
+    "signalfd",
+    "timerfd_create",
+    "eventfd",
+    "fallocate",
+    "timerfd_settime",
+    "timerfd_gettime",
+    "accept4",
+    "signalfd4",
+    "eventfd2",
+    "epoll_create1",
+    "dup3",
+    "pipe2",
+    "inotify_init1",
+    "preadv",
+    "pwritev",
+    "rt_tgsigqueueinfo",
+    "perf_event_open",
+    "recvmmsg",
+    "fanotify_init",
+    "fanotify_mark",
+    "prlimit64",
+    "name_to_handle_at",
+    "open_by_handle_at",
+    "clock_adjtime",
+    "syncfs",
+    "sendmmsg",
+    "setns",
+    "getcpu",
+    "process_vm_readv",
+    "process_vm_writev",
+    "kcmp",
+    "finit_module",
+    "sched_setattr",
+    "sched_getattr",
+    "renameat2",
+    "seccomp",
+    "getrandom",
+    "memfd_create",
+    "kexec_file_load",
+    "bpf",
+    "execveat",
+    "userfaultfd",
+    "membarrier",
+    "mlock2",
+    "copy_file_range",
+    "preadv2",
+    "pwritev2",
+    "pkey_mprotect",
+    "pkey_alloc",
+    "pkey_free",
+    "statx",
+    "io_pgetevents",
+    "rseq",
+];
+
+/// Names of x86_64 syscalls starting from [`NEW_SYSCALLS_START`].
+const NEW_SYSCALL_NAMES: &[&str] = &[
+    "pidfd_send_signal",
+    "io_uring_setup",
+    "io_uring_enter",
+    "io_uring_register",
+    "open_tree",
+    "move_mount",
+    "fsopen",
+    "fsconfig",
+    "fsmount",
+    "fspick",
+    "pidfd_open",
+    "clone3",
+    "close_range",
+    "openat2",
+    "pidfd_getfd",
+    "faccessat2",
+    "process_madvise",
+    "epoll_pwait2",
+    "mount_setattr",
+    "quotactl_fd",
+    "landlock_create_ruleset",
+    "landlock_add_rule",
+    "landlock_restrict_self",
+    "memfd_secret",
+    "process_mrelease",
+    "futex_waitv",
+    "set_mempolicy_home_node",
+];
+const NEW_SYSCALLS_START: u64 = 424;
+
+/// Return name of a syscall by its number, `None` if number is unknown.
+///
+/// # Arguments
+///
+/// * `nr`: syscall number
+pub fn syscall_name(nr: u64) -> Option<&'static str> {
+    if let Some(name) = SYSCALL_NAMES.get(nr as usize) {
+        return Some(name);
+    }
+    nr.checked_sub(NEW_SYSCALLS_START)
+        .and_then(|idx| NEW_SYSCALL_NAMES.get(idx as usize))
+        .copied()
+}
+
+/// Return number of a syscall by its name, `None` if name is unknown.
+///
+/// # Arguments
+///
+/// * `name`: syscall name
+pub fn syscall_number(name: &str) -> Option<u64> {
+    if let Some(nr) = SYSCALL_NAMES.iter().position(|n| *n == name) {
+        return Some(nr as u64);
+    }
+    NEW_SYSCALL_NAMES
+        .iter()
+        .position(|n| *n == name)
+        .map(|idx| NEW_SYSCALLS_START + idx as u64)
+}
+
+/// How to render a syscall argument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArgKind {
+    Int,
+    Hex,
+    Oct,
+    Fd,
+    /// Fd or `AT_FDCWD`.
+    DirFd,
+    /// Pointer to a null-terminated string.
+    Str,
+    /// Pointer to a buffer filled by debugee, length is an argument with specified index.
+    InBuf(usize),
+    /// Pointer to a buffer filled by kernel, length is a syscall return value.
+    OutBuf,
+}
+
+/// Return kinds of syscall arguments, `None` for syscalls without a known signature.
+fn signature(name: &str) -> Option<&'static [ArgKind]> {
+    use ArgKind::*;
+
+    let args: &'static [ArgKind] = match name {
+        "read" | "recvfrom" => &[Fd, OutBuf, Int],
+        "write" | "sendto" => &[Fd, InBuf(2), Int],
+        "pread64" => &[Fd, OutBuf, Int, Int],
+        "pwrite64" => &[Fd, InBuf(2), Int, Int],
+        "open" => &[Str, Hex, Oct],
+        "openat" => &[DirFd, Str, Hex, Oct],
+        "creat" => &[Str, Oct],
+        "close" | "dup" | "fsync" | "fdatasync" | "fchdir" | "syncfs" => &[Fd],
+        "dup2" | "dup3" => &[Fd, Fd, Hex],
+        "stat" | "lstat" => &[Str, Hex],
+        "fstat" => &[Fd, Hex],
+        "newfstatat" => &[DirFd, Str, Hex, Hex],
+        "statx" => &[DirFd, Str, Hex, Hex, Hex],
+        "access" => &[Str, Oct],
+        "faccessat" | "faccessat2" => &[DirFd, Str, Oct, Hex],
+        "lseek" => &[Fd, Int, Int],
+        "mmap" => &[Hex, Int, Hex, Hex, Fd, Hex],
+        "mprotect" => &[Hex, Int, Hex],
+        "munmap" => &[Hex, Int],
+        "brk" => &[Hex],
+        "ioctl" | "fcntl" => &[Fd, Hex, Hex],
+        "execve" => &[Str, Hex, Hex],
+        "chdir" | "mkdir" | "rmdir" | "unlink" | "chroot" => &[Str, Oct],
+        "unlinkat" => &[DirFd, Str, Hex],
+        "mkdirat" => &[DirFd, Str, Oct],
+        "rename" | "link" | "symlink" => &[Str, Str],
+        "readlink" => &[Str, OutBuf, Int],
+        "readlinkat" => &[DirFd, Str, OutBuf, Int],
+        "getcwd" => &[OutBuf, Int],
+        "getdents64" => &[Fd, Hex, Int],
+        "socket" => &[Int, Hex, Int],
+        "connect" | "bind" => &[Fd, Hex, Int],
+        "accept" | "accept4" => &[Fd, Hex, Hex, Hex],
+        "listen" | "shutdown" => &[Fd, Int],
+        "pipe" | "pipe2" => &[Hex, Hex],
+        "futex" => &[Hex, Int, Int, Hex, Hex, Int],
+        "nanosleep" => &[Hex, Hex],
+        "clock_nanosleep" => &[Int, Hex, Hex, Hex],
+        "kill" | "tkill" => &[Int, Int],
+        "tgkill" => &[Int, Int, Int],
+        "exit" | "exit_group" => &[Int],
+        "epoll_wait" | "epoll_pwait" => &[Fd, Hex, Int, Int],
+        "epoll_ctl" => &[Fd, Int, Fd, Hex],
+        "poll" => &[Hex, Int, Int],
+        "getrandom" => &[Hex, Int, Hex],
+        "madvise" => &[Hex, Int, Int],
+        "sched_yield" | "getpid" | "gettid" | "getppid" | "getuid" | "getgid" | "fork"
+        | "vfork" => &[],
+        _ => return None,
+    };
+    Some(args)
+}
+
+/// Render a bytes as a quoted string, non-printable bytes are escaped.
+fn quote(bytes: &[u8], truncated: bool) -> String {
+    let mut s = String::with_capacity(bytes.len() + 2);
+    s.push('"');
+    s.extend(bytes.iter().flat_map(|b| b.escape_ascii().map(char::from)));
+    s.push('"');
+    if truncated {
+        s.push_str("...");
+    }
+    s
+}
+
+/// Read no more than `len` bytes of debugee memory, starting from `addr`. If page after `addr`
+/// isn't readable then only bytes up to page boundary are read.
+fn read_bytes(dbg: &Debugger, addr: usize, len: usize) -> Option<Vec<u8>> {
+    if addr == 0 {
+        return None;
+    }
+    dbg.read_memory(addr, len).ok().or_else(|| {
+        let to_page_end = PAGE_SIZE - addr % PAGE_SIZE;
+        dbg.read_memory(addr, len.min(to_page_end)).ok()
+    })
+}
+
+/// Render a single syscall argument.
+///
+/// # Arguments
+///
+/// * `dbg`: debugger instance
+/// * `kind`: argument kind
+/// * `args`: raw syscall arguments
+/// * `idx`: argument index
+/// * `rval`: syscall return value, `None` at syscall entry
+fn render_arg(
+    dbg: &Debugger,
+    kind: ArgKind,
+    args: &[u64; 6],
+    idx: usize,
+    rval: Option<i64>,
+) -> String {
+    let value = args[idx];
+    let as_ptr = || format!("{value:#x}");
+    match kind {
+        ArgKind::Int => (value as i64).to_string(),
+        ArgKind::Hex => as_ptr(),
+        ArgKind::Oct => format!("{value:#o}"),
+        ArgKind::Fd => (value as i32).to_string(),
+        ArgKind::DirFd if value as i32 == AT_FDCWD => "AT_FDCWD".to_string(),
+        ArgKind::DirFd => (value as i32).to_string(),
+        ArgKind::Str => match read_bytes(dbg, value as usize, MAX_STR_LEN) {
+            None => as_ptr(),
+            Some(bytes) => match bytes.iter().position(|&b| b == 0) {
+                Some(end) => quote(&bytes[..end], false),
+                None => quote(&bytes, true),
+            },
+        },
+        ArgKind::InBuf(len_idx) => {
+            let len = args[len_idx] as usize;
+            match read_bytes(dbg, value as usize, len.min(MAX_STR_LEN)) {
+                None => as_ptr(),
+                Some(bytes) => quote(&bytes, len > bytes.len()),
+            }
+        }
+        ArgKind::OutBuf => match rval {
+            Some(len) if len > 0 => {
+                let len = len as usize;
+                match read_bytes(dbg, value as usize, len.min(MAX_STR_LEN)) {
+                    None => as_ptr(),
+                    Some(bytes) => quote(&bytes, len > bytes.len()),
+                }
+            }
+            _ => as_ptr(),
+        },
+    }
+}
+
+/// Syscall result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyscallResult {
+    Ok(i64),
+    Err(Errno),
+    /// Syscall never returns (like `exit_group`) or debugee exits while in syscall.
+    Unfinished,
+}
+
+impl Display for SyscallResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyscallResult::Ok(rval) if *rval > u32::MAX as i64 => write!(f, "{rval:#x}"),
+            SyscallResult::Ok(rval) => write!(f, "{rval}"),
+            SyscallResult::Err(errno) => write!(f, "-1 {errno:?} ({})", errno.desc()),
+            SyscallResult::Unfinished => f.write_str("?"),
+        }
+    }
+}
+
+/// Single traced syscall.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyscallRecord {
+    pub thread_pid: Pid,
+    pub thread_num: Option<u32>,
+    pub nr: u64,
+    /// Syscall name, `syscall_<nr>` for unknown syscalls.
+    pub name: String,
+    /// Rendered arguments.
+    pub args: Vec<String>,
+    pub result: SyscallResult,
+    /// Time between syscall entry and exit.
+    pub duration: Option<Duration>,
+}
+
+impl Display for SyscallRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}({}) = {}",
+            self.name,
+            self.args.join(", "),
+            self.result
+        )
+    }
+}
+
+/// Syscall between entry and exit.
+struct Pending {
+    record: SyscallRecord,
+    raw_args: [u64; 6],
+    started_at: Instant,
+}
+
+struct State {
+    enabled: bool,
+    /// Traced syscall names, empty if all syscalls are traced.
+    names: HashSet<String>,
+    /// Traced thread numbers, empty if all threads are traced.
+    threads: HashSet<u32>,
+    pending: HashMap<Pid, Pending>,
+    log: VecDeque<SyscallRecord>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            names: HashSet::default(),
+            threads: HashSet::default(),
+            pending: HashMap::default(),
+            log: VecDeque::default(),
+        }
+    }
+}
+
+impl State {
+    fn push(&mut self, record: SyscallRecord) {
+        if self.log.len() == MAX_RECORDS {
+            self.log.pop_front();
+        }
+        self.log.push_back(record);
+    }
+
+    fn is_traced(&self, name: &str, thread_num: Option<u32>) -> bool {
+        let name_match = self.names.is_empty() || self.names.contains(name);
+        let thread_match = self.threads.is_empty()
+            || thread_num
+                .map(|num| self.threads.contains(&num))
+                .unwrap_or_default();
+        name_match && thread_match
+    }
+}
+
+/// [`SyscallOracle`] logs syscalls of debugee threads with decoded arguments and results.
+#[derive(Default)]
+pub struct SyscallOracle {
+    state: Mutex<State>,
+}
+
+impl SyscallOracle {
+    /// Create a new oracle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Switch syscall tracing on or off, takes effect when debugee continues.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable`: true to switch tracing on
+    pub fn set_enabled(&self, enable: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.enabled = enable;
+        if !enable {
+            state.pending.clear();
+        }
+    }
+
+    /// Trace only syscalls with specified names, trace all syscalls if list is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `names`: syscall names
+    pub fn set_name_filter(&self, names: impl IntoIterator<Item = String>) {
+        self.state.lock().unwrap().names = names.into_iter().collect();
+    }
+
+    /// Trace only threads with specified numbers, trace all threads if list is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `threads`: thread numbers
+    pub fn set_thread_filter(&self, threads: impl IntoIterator<Item = u32>) {
+        self.state.lock().unwrap().threads = threads.into_iter().collect();
+    }
+
+    /// Return last traced syscalls (no more than `n`), the latest is last.
+    ///
+    /// # Arguments
+    ///
+    /// * `n`: max number of records
+    pub fn last_records(&self, n: usize) -> Vec<SyscallRecord> {
+        let state = self.state.lock().unwrap();
+        state
+            .log
+            .iter()
+            .skip(state.log.len().saturating_sub(n))
+            .cloned()
+            .collect()
+    }
+
+    fn on_entry(&self, dbg: &Debugger, pid: Pid, nr: u64, args: [u64; 6]) {
+        let thread_num = dbg.thread_number(pid);
+        let name = syscall_name(nr)
+            .map(ToString::to_string)
+            .unwrap_or_else(|| format!("syscall_{nr}"));
+        if !self.state.lock().unwrap().is_traced(&name, thread_num) {
+            return;
+        }
+
+        let rendered = match signature(&name) {
+            Some(kinds) => kinds
+                .iter()
+                .enumerate()
+                .map(|(idx, kind)| render_arg(dbg, *kind, &args, idx, None))
+                .collect(),
+            None => args.iter().map(|arg| format!("{arg:#x}")).collect(),
+        };
+        let record = SyscallRecord {
+            thread_pid: pid,
+            thread_num,
+            nr,
+            name,
+            args: rendered,
+            result: SyscallResult::Unfinished,
+            duration: None,
+        };
+
+        let mut state = self.state.lock().unwrap();
+        if matches!(record.name.as_str(), "exit" | "exit_group") {
+            // there is no exit stop for this syscalls
+            state.push(record);
+            return;
+        }
+        state.pending.insert(
+            pid,
+            Pending {
+                record,
+                raw_args: args,
+                started_at: Instant::now(),
+            },
+        );
+    }
+
+    fn on_exit(&self, dbg: &Debugger, pid: Pid, rval: i64, is_error: bool) {
+        let Some(pending) = self.state.lock().unwrap().pending.remove(&pid) else {
+            return;
+        };
+        let mut record = pending.record;
+        record.duration = Some(pending.started_at.elapsed());
+        record.result = if is_error {
+            SyscallResult::Err(Errno::from_i32(-rval as i32))
+        } else {
+            SyscallResult::Ok(rval)
+        };
+
+        // output buffers are filled at syscall exit
+        if let Some(kinds) = signature(&record.name) {
+            for (idx, kind) in kinds.iter().enumerate() {
+                if *kind == ArgKind::OutBuf {
+                    record.args[idx] = render_arg(
+                        dbg,
+                        *kind,
+                        &pending.raw_args,
+                        idx,
+                        Some(rval).filter(|_| !is_error),
+                    );
+                }
+            }
+        }
+
+        self.state.lock().unwrap().push(record);
+    }
+}
+
+impl Oracle for SyscallOracle {
+    fn name(&self) -> &'static str {
+        "syscall"
+    }
+
+    fn ready_for_install(&self, _: &Debugger) -> bool {
+        // filters are user settings, so they survive debugee restart
+        let mut state = self.state.lock().unwrap();
+        state.pending.clear();
+        state.log.clear();
+        true
+    }
+
+    fn watch_points(self: Arc<Self>) -> Vec<CreateTransparentBreakpointRequest> {
+        vec![]
+    }
+
+    fn trace_syscalls(&self) -> bool {
+        self.state.lock().unwrap().enabled
+    }
+
+    fn on_syscall(&self, dbg: &Debugger, pid: Pid, stop: &SyscallStop) {
+        match *stop {
+            SyscallStop::Entry { nr, args } => self.on_entry(dbg, pid, nr, args),
+            SyscallStop::Exit { rval, is_error } => self.on_exit(dbg, pid, rval, is_error),
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+impl ConsolePlugin for SyscallOracle {
+    fn print(&self, printer: &ExternalPrinter, subcommand: Option<&str>) {
+        let mut words = subcommand.unwrap_or_default().split_whitespace();
+        if let Some(cmd) = words.next() {
+            match cmd {
+                "on" => {
+                    self.set_enabled(true);
+                    printer.println("syscall tracing enabled");
+                }
+                "off" => {
+                    self.set_enabled(false);
+                    printer.println("syscall tracing disabled");
+                }
+                "all" => {
+                    self.set_name_filter(vec![]);
+                    self.set_thread_filter(vec![]);
+                    printer.println("trace all syscalls of all threads");
+                }
+                "filter" => {
+                    let names: Vec<_> = words.map(ToString::to_string).collect();
+                    match names.iter().find(|name| {
+                        !SYSCALL_NAMES.contains(&name.as_str())
+                            && !NEW_SYSCALL_NAMES.contains(&name.as_str())
+                    }) {
+                        Some(unknown) => {
+                            printer.println(ErrorView::from(format!("unknown syscall: {unknown}")))
+                        }
+                        None => self.set_name_filter(names),
+                    }
+                }
+                "thread" => match words.map(str::parse).collect::<Result<Vec<u32>, _>>() {
+                    Ok(threads) => self.set_thread_filter(threads),
+                    Err(_) => printer.println(ErrorView::from("thread number must be a number")),
+                },
+                _ => printer.println(ErrorView::from(format!("unknown subcommand: {cmd}"))),
+            }
+            return;
+        }
+
+        let records = self.last_records(LAST_RECORDS);
+        printer.println(format!(
+            "{} last syscalls",
+            KeywordView::from(records.len())
+        ));
+        for record in records {
+            let thread = record
+                .thread_num
+                .map(|num| format!("#{num}"))
+                .unwrap_or_else(|| record.thread_pid.to_string());
+            printer.println(format!(
+                "[{thread}] {}({}) = {} <{:?}>",
+                FunctionNameView::from(&record.name),
+                record.args.join(", "),
+                record.result,
+                record.duration.unwrap_or_default(),
+            ));
+        }
+    }
+
+    fn help(&self) -> &str {
+        "syscall - strace-like log of debugee syscalls, \
+        `oracle syscall on|off` switch tracing on or off, \
+        `oracle syscall filter <name> ...` trace only specified syscalls, \
+        `oracle syscall thread <number> ...` trace only specified threads, \
+        `oracle syscall all` reset filters"
+    }
+}
+
+#[cfg(feature = "ui")]
+impl TuiPlugin for SyscallOracle {
+    fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
+        Box::new(tui::SyscallComponent::new(self))
+    }
+}
+
+#[cfg(feature = "ui")]
+pub mod tui {
+    use crate::oracle::builtin::syscall::{SyscallOracle, SyscallResult, LAST_RECORDS};
+    use crate::ui::tui::app::port::UserEvent;
+    use crate::ui::tui::Msg;
+    use std::sync::Arc;
+    use tui_realm_stdlib::Table;
+    use tuirealm::command::{Cmd, Direction};
+    use tuirealm::event::{Key, KeyEvent};
+    use tuirealm::props::{Alignment, BorderType, Borders, Color, Style, TableBuilder, TextSpan};
+    use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent};
+
+    #[derive(MockComponent)]
+    pub struct SyscallComponent {
+        component: Table,
+        oracle: Arc<SyscallOracle>,
+    }
+
+    impl SyscallComponent {
+        pub fn new(oracle: Arc<SyscallOracle>) -> Self {
+            let component = Table::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(Color::LightYellow),
+                )
+                .title("Syscalls", Alignment::Center)
+                .inactive(Style::default().fg(Color::Gray))
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("▶")
+                .rewind(true)
+                .step(4)
+                .widths(&[8, 16, 46, 20, 10])
+                .headers(&["Thread", "Syscall", "Arguments", "Result", "Duration"]);
+
+            let mut this = Self { component, oracle };
+            this.refresh_table();
+            this
+        }
+
+        fn refresh_table(&mut self) {
+            let mut table = TableBuilder::default();
+            let records = self.oracle.last_records(LAST_RECORDS);
+            if records.is_empty() {
+                for _ in 0..5 {
+                    table.add_col(TextSpan::from(""));
+                }
+                table.add_row();
+            }
+            for record in records.into_iter().rev() {
+                let thread = record
+                    .thread_num
+                    .map(|num| num.to_string())
+                    .unwrap_or_else(|| record.thread_pid.to_string());
+                let result_color = match record.result {
+                    SyscallResult::Err(_) => Color::Red,
+                    _ => Color::Reset,
+                };
+                table
+                    .add_col(TextSpan::from(thread))
+                    .add_col(TextSpan::from(&record.name).fg(Color::LightBlue))
+                    .add_col(TextSpan::from(record.args.join(", ")))
+                    .add_col(TextSpan::from(record.result.to_string()).fg(result_color))
+                    .add_col(TextSpan::from(
+                        record
+                            .duration
+                            .map(|d| format!("{d:?}"))
+                            .unwrap_or_default(),
+                    ))
+                    .add_row();
+            }
+            self.component
+                .attr(Attribute::Content, AttrValue::Table(table.build()));
+        }
+    }
+
+    impl Component<Msg, UserEvent> for SyscallComponent {
+        fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+            match ev {
+                Event::Keyboard(KeyEvent {
+                    code: Key::Down, ..
+                }) => {
+                    self.perform(Cmd::Move(Direction::Down));
+                }
+                Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                    self.perform(Cmd::Move(Direction::Up));
+                }
+                Event::Tick => {
+                    self.refresh_table();
+                }
+                _ => {}
+            }
+
+            Some(Msg::None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_syscall_name() {
+        assert_eq!(syscall_name(0), Some("read"));
+        assert_eq!(syscall_name(257), Some("openat"));
+        assert_eq!(syscall_name(334), Some("rseq"));
+        assert_eq!(syscall_name(335), None);
+        assert_eq!(syscall_number("openat"), Some(257));
+        assert_eq!(syscall_number("futex_waitv"), Some(449));
+        assert_eq!(syscall_number("unknown"), None);
+        assert_eq!(syscall_name(424), Some("pidfd_send_signal"));
+        assert_eq!(syscall_name(435), Some("clone3"));
+        assert_eq!(syscall_name(450), Some("set_mempolicy_home_node"));
+        assert_eq!(syscall_name(451), None);
+    }
+
+    #[test]
+    fn test_render_result() {
+        assert_eq!(SyscallResult::Ok(3).to_string(), "3");
+        assert_eq!(
+            SyscallResult::Err(Errno::ENOENT).to_string(),
+            "-1 ENOENT (No such file or directory)"
+        );
+        assert_eq!(quote(b"ab\n\x01", true), r#""ab\n\x01"..."#);
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut state = State::default();
+        assert!(state.is_traced("openat", Some(1)));
+        state.names.insert("openat".to_string());
+        state.threads.insert(2);
+        assert!(!state.is_traced("openat", Some(1)));
+        assert!(!state.is_traced("read", Some(2)));
+        assert!(state.is_traced("openat", Some(2)));
+        assert!(!state.is_traced("openat", None));
+    }
+}
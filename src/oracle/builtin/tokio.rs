@@ -3,11 +3,19 @@ use crate::debugger::variable::select::{VariableSelector, DQE};
 use crate::debugger::variable::{ScalarVariable, StructVariable, SupportedScalar, VariableIR};
 use crate::debugger::CreateTransparentBreakpointRequest;
 use crate::debugger::{Debugger, Error};
-use crate::oracle::{ConsolePlugin, Oracle, TuiPlugin};
+use crate::oracle::builtin::usize_value;
+use crate::oracle::Oracle;
+#[cfg(feature = "ui")]
+use crate::oracle::{ConsolePlugin, TuiPlugin};
+#[cfg(feature = "ui")]
 use crate::ui::console::print::style::KeywordView;
+#[cfg(feature = "ui")]
 use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
 use crate::ui::short::Abbreviator;
+#[cfg(feature = "ui")]
 use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
 use crate::ui::tui::Msg;
 use chrono::Duration;
 use indexmap::IndexMap;
@@ -16,6 +24,7 @@ use std::mem::size_of;
 use std::sync::Arc;
 use std::time::Instant;
 use strum_macros::{Display, EnumString};
+#[cfg(feature = "ui")]
 use tuirealm::Component;
 
 #[derive(Debug, Display, EnumString, Clone, Copy)]
@@ -61,6 +70,8 @@ enum State {
     Complete,
 }
 
+// task state is shown only by user interfaces
+#[cfg_attr(not(feature = "ui"), allow(dead_code))]
 #[derive(Clone)]
 struct Task {
     _id: u64,
@@ -124,6 +135,7 @@ impl Task {
         self.dropped_at = Some(Instant::now());
     }
 
+    #[cfg_attr(not(feature = "ui"), allow(dead_code))]
     fn task_time(&self) -> Duration {
         if let Some(dropped_at) = self.dropped_at {
             Duration::from_std(dropped_at.duration_since(self.created_at)).expect("infallible")
@@ -227,6 +239,7 @@ impl Oracle for TokioOracle {
     }
 }
 
+#[cfg(feature = "ui")]
 impl ConsolePlugin for TokioOracle {
     fn print(&self, printer: &ExternalPrinter, _: Option<&str>) {
         let tasks = self.tasks.lock().unwrap().clone();
@@ -282,19 +295,7 @@ impl ConsolePlugin for TokioOracle {
 impl TokioOracle {
     /// Return underline value of loom `AtomicUsize` structure.
     fn extract_value_from_atomic_usize(&self, val: &StructVariable) -> Option<usize> {
-        if let VariableIR::Struct(inner) = val.members.first()? {
-            if let VariableIR::Struct(value) = inner.members.first()? {
-                if let VariableIR::Struct(v) = value.members.first()? {
-                    if let VariableIR::Scalar(value) = v.members.first()? {
-                        if let Some(SupportedScalar::Usize(usize)) = value.value {
-                            return Some(usize);
-                        }
-                    }
-                }
-            }
-        }
-
-        None
+        usize_value(val.members.first()?)
     }
 
     /// Refresh all non-dropped tasks by reading tracee memory and reflect tasks.
@@ -448,12 +449,14 @@ impl TokioOracle {
     }
 }
 
+#[cfg(feature = "ui")]
 impl TuiPlugin for TokioOracle {
     fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>> {
         Box::new(tui::TokioComponent::new(self))
     }
 }
 
+#[cfg(feature = "ui")]
 pub mod tui {
     use crate::oracle::builtin::tokio::{State, TokioOracle};
     use crate::ui::short::Abbreviator;
@@ -1,18 +1,28 @@
 //! Oracles system.
 //! Oracle is an optional plugin for debugger. Oracles use `watch points` for analyse
 //! debug information and visualize it. As example - tokio oracle can can keep track of active
-//! tasks. There is builtin and external (created by user) oracles.
+//! tasks. There is builtin and external (created by user) oracles, external oracles
+//! are loaded from dynamic libraries (see [`plugin`]).
 
 pub mod builtin;
+pub mod plugin;
 
+use crate::debugger::capture::OutputStream;
 use crate::debugger::CreateTransparentBreakpointRequest;
 use crate::debugger::Debugger;
+use crate::debugger::SyscallStop;
+#[cfg(feature = "ui")]
 use crate::ui::console::print::ExternalPrinter;
+#[cfg(feature = "ui")]
 use crate::ui::tui::app::port::UserEvent;
+#[cfg(feature = "ui")]
 use crate::ui::tui::Msg;
+use nix::unistd::Pid;
 use std::sync::Arc;
+#[cfg(feature = "ui")]
 use tuirealm::Component;
 
+#[cfg(feature = "ui")]
 pub trait ConsolePlugin {
     /// Print information into console.
     ///
@@ -26,12 +36,27 @@ pub trait ConsolePlugin {
     fn help(&self) -> &str;
 }
 
+#[cfg(feature = "ui")]
 pub trait TuiPlugin: Send + Sync {
     /// Return tui component for visualize oracle information.
     fn make_tui_component(self: Arc<Self>) -> Box<dyn Component<Msg, UserEvent>>;
 }
 
-pub trait Oracle: ConsolePlugin + TuiPlugin {
+/// Oracle visualization in user interfaces, empty if `ui` feature is disabled.
+#[cfg(feature = "ui")]
+pub trait UiPlugin: ConsolePlugin + TuiPlugin {}
+
+#[cfg(feature = "ui")]
+impl<T: ConsolePlugin + TuiPlugin> UiPlugin for T {}
+
+/// Oracle visualization in user interfaces, empty if `ui` feature is disabled.
+#[cfg(not(feature = "ui"))]
+pub trait UiPlugin: Send + Sync {}
+
+#[cfg(not(feature = "ui"))]
+impl<T: Send + Sync> UiPlugin for T {}
+
+pub trait Oracle: UiPlugin {
     /// Return oracle name.
     fn name(&self) -> &'static str;
 
@@ -44,7 +69,57 @@ pub trait Oracle: ConsolePlugin + TuiPlugin {
     /// * `dbg`: debugger instance
     fn ready_for_install(&self, dbg: &Debugger) -> bool;
 
+    /// Called once when oracle is installed (after [`Oracle::ready_for_install`] returns true),
+    /// right before watch points are set. Oracle may acquire debugee related resources here.
+    ///
+    /// # Arguments
+    ///
+    /// * `dbg`: debugger instance
+    fn on_install(&self, _dbg: &Debugger) {}
+
     /// A list of watch_point using by oracle. In debugger watch point implement by transparent
     /// breakpoints.
     fn watch_points(self: Arc<Self>) -> Vec<CreateTransparentBreakpointRequest>;
+
+    /// Called every time debugee stops (or exits). Oracle may process data collected
+    /// while debugee was running, for example, resolve addresses into function names.
+    ///
+    /// # Arguments
+    ///
+    /// * `dbg`: debugger instance
+    fn on_stop(&self, _dbg: &Debugger) {}
+
+    /// Called when debugee stops at a user defined breakpoint, before [`Oracle::on_stop`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dbg`: debugger instance
+    /// * `number`: breakpoint number
+    fn on_breakpoint(&self, _dbg: &Debugger, _number: u32) {}
+
+    /// True if oracle wants to observe debugee syscalls. Checked every time before debugee
+    /// continues, so syscall tracing may be switched on or off without debugee restart.
+    fn trace_syscalls(&self) -> bool {
+        false
+    }
+
+    /// Called at syscall entry and exit of a debugee thread, if syscall tracing is enabled
+    /// (see [`Oracle::trace_syscalls`]). Other threads aren't stopped at this point.
+    ///
+    /// # Arguments
+    ///
+    /// * `dbg`: debugger instance
+    /// * `pid`: thread id
+    /// * `stop`: syscall entry or exit information
+    fn on_syscall(&self, _dbg: &Debugger, _pid: Pid, _stop: &SyscallStop) {}
+
+    /// Called with a chunk of debugee output right before a stop report, only if debugee
+    /// streams are captured by the debugger (see [`crate::debugger::capture::IoCapture`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `dbg`: debugger instance
+    /// * `stream`: stdout or stderr
+    /// * `data`: output data
+    fn on_debugee_output(&self, _dbg: &Debugger, _stream: OutputStream, _data: &[u8]) {}
 }
@@ -0,0 +1,116 @@
+//! External oracles, loaded from dynamic libraries.
+//!
+//! Oracle plugin is a `cdylib` crate that depends on `bugstalker` and declares its oracle
+//! with a [`declare_oracle`](crate::declare_oracle) macro:
+//!
+//! ```ignore
+//! use bugstalker::oracle::Oracle;
+//!
+//! #[derive(Default)]
+//! struct MyOracle;
+//!
+//! impl Oracle for MyOracle {
+//!     // ...
+//! }
+//!
+//! bugstalker::declare_oracle!(MyOracle::default());
+//! ```
+//!
+//! Then the plugin is discovered like a builtin oracle: `bs --oracle ./libmy_oracle.so ...`.
+//! Oracle trait objects are passed between a debugger and a plugin as is, so a plugin must be
+//! built with the same compiler and the same `bugstalker` version (and features) as a debugger.
+
+use crate::oracle::Oracle;
+use libloading::{Library, Symbol};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Version of a plugin API, plugins with another version are rejected.
+pub const API_VERSION: u32 = 1;
+
+/// Symbol of a function that returns plugin API version.
+pub const API_VERSION_SYMBOL: &[u8] = b"_bugstalker_oracle_api_version";
+
+/// Symbol of a function that creates an oracle.
+pub const CREATE_SYMBOL: &[u8] = b"_bugstalker_oracle_create";
+
+/// Signature of a function that creates an oracle, returned pointer is a result of `Box::into_raw`.
+pub type CreateFn = unsafe extern "C" fn() -> *mut Arc<dyn Oracle>;
+
+/// Declare an oracle of a plugin library.
+///
+/// # Arguments
+///
+/// * an expression that creates an oracle
+#[macro_export]
+macro_rules! declare_oracle {
+    ($constructor:expr) => {
+        #[no_mangle]
+        pub extern "C" fn _bugstalker_oracle_api_version() -> u32 {
+            $crate::oracle::plugin::API_VERSION
+        }
+
+        #[no_mangle]
+        #[allow(improper_ctypes_definitions)]
+        pub extern "C" fn _bugstalker_oracle_create(
+        ) -> *mut ::std::sync::Arc<dyn $crate::oracle::Oracle> {
+            let oracle: ::std::sync::Arc<dyn $crate::oracle::Oracle> =
+                ::std::sync::Arc::new($constructor);
+            Box::into_raw(Box::new(oracle))
+        }
+    };
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PluginError {
+    #[error("load library: {0}")]
+    Load(#[from] libloading::Error),
+    #[error("plugin API version mismatch, expected {expected}, found {found}")]
+    ApiVersion { expected: u32, found: u32 },
+    #[error("plugin returns a null oracle")]
+    NullOracle,
+}
+
+/// Return true if oracle name is a path to a plugin library.
+///
+/// # Arguments
+///
+/// * `name`: oracle name
+pub fn is_plugin_path(name: &str) -> bool {
+    name.contains('/') || name.ends_with(".so")
+}
+
+/// Load an oracle from a plugin library.
+///
+/// # Arguments
+///
+/// * `path`: path to a dynamic library
+pub fn load(path: impl AsRef<Path>) -> Result<Arc<dyn Oracle>, PluginError> {
+    // SAFETY: running initialization code of a plugin is a user decision
+    let lib = unsafe { Library::new(path.as_ref()) }?;
+
+    let oracle = {
+        // SAFETY: symbol signatures are defined by `declare_oracle` macro
+        let version: Symbol<unsafe extern "C" fn() -> u32> =
+            unsafe { lib.get(API_VERSION_SYMBOL) }?;
+        let found = unsafe { version() };
+        if found != API_VERSION {
+            return Err(PluginError::ApiVersion {
+                expected: API_VERSION,
+                found,
+            });
+        }
+
+        let create: Symbol<CreateFn> = unsafe { lib.get(CREATE_SYMBOL) }?;
+        let oracle = unsafe { create() };
+        if oracle.is_null() {
+            return Err(PluginError::NullOracle);
+        }
+        // SAFETY: pointer is created by `Box::into_raw` in plugin
+        *unsafe { Box::from_raw(oracle) }
+    };
+
+    // oracle code lives in a library, so library never unloads
+    std::mem::forget(lib);
+    Ok(oracle)
+}
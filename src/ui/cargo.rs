@@ -0,0 +1,120 @@
+//! Cargo integration: build a target with debug-friendly settings and locate its executable
+//! (used by `cargo bugstalker` subcommand).
+
+use anyhow::bail;
+use itertools::Itertools;
+use serde::Deserialize;
+use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Kind of built target.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Target {
+    /// Binary or example.
+    Run,
+    /// Test harness (unit tests, integration tests, doc tests are not supported).
+    Test,
+}
+
+/// Profile overrides: full debug information, no optimizations in dev profile and
+/// not stripped binaries. Release builds keep optimizations but contain debug information.
+const PROFILE_OVERRIDES: &[(&str, &str)] = &[
+    ("CARGO_PROFILE_DEV_DEBUG", "true"),
+    ("CARGO_PROFILE_DEV_OPT_LEVEL", "0"),
+    ("CARGO_PROFILE_DEV_STRIP", "none"),
+    ("CARGO_PROFILE_TEST_DEBUG", "true"),
+    ("CARGO_PROFILE_RELEASE_DEBUG", "true"),
+    ("CARGO_PROFILE_RELEASE_STRIP", "none"),
+];
+
+#[derive(Deserialize)]
+struct Profile {
+    test: bool,
+}
+
+/// `compiler-artifact` message of cargo (`--message-format=json`).
+#[derive(Deserialize)]
+struct Artifact {
+    reason: String,
+    profile: Profile,
+    executable: Option<PathBuf>,
+}
+
+/// Return executables from cargo json messages (one message per line).
+fn executables(messages: &str, target: Target) -> Vec<PathBuf> {
+    messages
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Artifact>(line).ok())
+        .filter(|artifact| artifact.reason == "compiler-artifact")
+        .filter(|artifact| artifact.profile.test == (target == Target::Test))
+        .filter_map(|artifact| artifact.executable)
+        .unique()
+        .collect()
+}
+
+/// Build a target and return path to its executable.
+///
+/// # Arguments
+///
+/// * `target`: kind of target
+/// * `cargo_args`: cargo arguments (package and target selection, features, profile, etc.)
+///
+/// # Errors
+///
+/// Return error if build failed or arguments select zero or several executables.
+pub fn build(target: Target, cargo_args: &[String]) -> anyhow::Result<PathBuf> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let mut cmd = Command::new(cargo);
+    match target {
+        Target::Run => cmd.arg("build"),
+        Target::Test => cmd.args(["test", "--no-run"]),
+    };
+    let output = cmd
+        .arg("--message-format=json-render-diagnostics")
+        .args(cargo_args)
+        .envs(PROFILE_OVERRIDES.iter().copied())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        bail!("cargo build failed ({})", output.status);
+    }
+
+    let mut executables = executables(&String::from_utf8_lossy(&output.stdout), target);
+    match executables.len() {
+        0 => bail!("no executables found, check target selection arguments"),
+        1 => Ok(executables.remove(0)),
+        _ => bail!(
+            "several executables found, select one of them (`--bin`, `--example`, `--test` or `--lib` arguments): {}",
+            executables.iter().map(|path| path.display()).join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_executables_from_messages() {
+        let messages = r#"{"reason":"compiler-artifact","package_id":"app 0.1.0","target":{"kind":["lib"],"name":"app"},"profile":{"test":false},"executable":null}
+{"reason":"compiler-artifact","package_id":"app 0.1.0","target":{"kind":["bin"],"name":"app"},"profile":{"test":false},"executable":"/app/target/debug/app"}
+{"reason":"compiler-artifact","package_id":"app 0.1.0","target":{"kind":["lib"],"name":"app"},"profile":{"test":true},"executable":"/app/target/debug/deps/app-1a2b"}
+{"reason":"compiler-artifact","package_id":"app 0.1.0","target":{"kind":["test"],"name":"it"},"profile":{"test":true},"executable":"/app/target/debug/deps/it-3c4d"}
+{"reason":"build-finished","success":true}"#;
+
+        assert_eq!(
+            executables(messages, Target::Run),
+            vec![PathBuf::from("/app/target/debug/app")]
+        );
+        assert_eq!(
+            executables(messages, Target::Test),
+            vec![
+                PathBuf::from("/app/target/debug/deps/app-1a2b"),
+                PathBuf::from("/app/target/debug/deps/it-3c4d"),
+            ]
+        );
+    }
+}
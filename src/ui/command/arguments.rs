@@ -1,5 +1,5 @@
 use crate::debugger::variable::select::DQE;
-use crate::debugger::variable::VariableIR;
+use crate::debugger::variable::{ParseLimits, VariableIR};
 use crate::debugger::Debugger;
 use crate::ui::command;
 
@@ -15,4 +15,14 @@ impl<'a> Handler<'a> {
     pub fn handle(&self, select_expression: DQE) -> command::CommandResult<Vec<VariableIR>> {
         Ok(self.dbg.read_argument(select_expression)?)
     }
+
+    pub fn handle_with_limits(
+        &self,
+        select_expression: DQE,
+        limits: ParseLimits,
+    ) -> command::CommandResult<Vec<VariableIR>> {
+        Ok(self
+            .dbg
+            .read_argument_with_limits(select_expression, limits)?)
+    }
 }
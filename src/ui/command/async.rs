@@ -0,0 +1,35 @@
+use crate::debugger::r#async::{AsyncFrame, AsyncTask};
+use crate::debugger::variable::select::DQE;
+use crate::debugger::Debugger;
+use crate::ui::command;
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Tasks of async runtimes.
+    Backtrace,
+    /// Logical async backtrace of a future variable.
+    Future(DQE),
+}
+
+pub enum ExecutionResult {
+    Tasks(Vec<AsyncTask>),
+    /// Async backtrace for each selected future.
+    Futures(Vec<Vec<AsyncFrame>>),
+}
+
+pub struct Handler<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<ExecutionResult> {
+        match cmd {
+            Command::Backtrace => Ok(ExecutionResult::Tasks(self.dbg.async_tasks()?)),
+            Command::Future(expr) => Ok(ExecutionResult::Futures(self.dbg.read_future(expr)?)),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use crate::debugger::{Debugger, ThreadSnapshot};
 use crate::ui::command;
 
+/// Max count of frames for which arguments are read, each frame requires a stack unwinding.
+const MAX_FRAMES_WITH_ARGS: usize = 32;
+
 #[derive(Debug, Clone)]
 pub enum Command {
     CurrentThread,
@@ -8,20 +11,40 @@ pub enum Command {
 }
 
 pub struct Handler<'a> {
-    dbg: &'a Debugger,
+    dbg: &'a mut Debugger,
 }
 
 impl<'a> Handler<'a> {
-    pub fn new(debugger: &'a Debugger) -> Self {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
         Self { dbg: debugger }
     }
 
-    pub fn handle(&self, cmd: Command) -> command::CommandResult<Vec<ThreadSnapshot>> {
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<Vec<ThreadSnapshot>> {
         let mut snap = self.dbg.thread_state()?;
+        // thread snapshot contains only top frames, the rest are unwound for printed threads only
+        if matches!(cmd, Command::CurrentThread) {
+            snap.retain(|thread| thread.in_focus);
+        }
+        for thread in snap.iter_mut() {
+            thread.fetch_backtrace(self.dbg, None)?;
+        }
 
         match cmd {
             Command::CurrentThread => {
-                Ok(snap.into_iter().filter(|thread| thread.in_focus).collect())
+                let mut threads = snap;
+                // arguments are read for a thread in focus only, for all threads it is too slow
+                for thread in threads.iter_mut() {
+                    let Some(bt) = thread.bt.as_mut() else {
+                        continue;
+                    };
+                    for (num, frame) in bt.iter_mut().enumerate().take(MAX_FRAMES_WITH_ARGS) {
+                        // frames without debug information have no arguments
+                        if frame.place.is_some() {
+                            frame.args = self.dbg.frame_arguments(num as u32).ok();
+                        }
+                    }
+                }
+                Ok(threads)
             }
             Command::All => {
                 snap.sort_unstable_by(|t1, t2| t1.thread.pid.cmp(&t2.thread.pid));
@@ -1,32 +1,58 @@
 use crate::debugger::address::Address;
-use crate::debugger::BreakpointView;
 use crate::debugger::Debugger;
 use crate::debugger::Error;
+use crate::debugger::{BreakpointCondition, BreakpointView};
+use crate::ui::command::parser;
+use chumsky::Parser;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub enum BreakpointIdentity {
     Address(usize),
     Line(String, u64),
     Function(String),
+    /// Function symbol and offset from its start in bytes.
+    Symbol(String, u64),
     Number(u32),
 }
 
 #[derive(Debug, Clone)]
 pub enum Command {
     Add(BreakpointIdentity),
+    /// Add breakpoints that use debug registers instead of INT3 instruction.
+    AddHardware(BreakpointIdentity),
     Remove(BreakpointIdentity),
     Info,
     AddDeferred(BreakpointIdentity),
+    Enable(u32),
+    Disable(u32),
+    /// Set (or remove if `None`) breakpoint condition.
+    Condition(u32, Option<BreakpointCondition>),
+    /// Set a number of next breakpoint hits that don't stop a debugee.
+    Ignore(u32, u32),
+    /// Save breakpoints into a file as a gdb script.
+    Save(PathBuf),
+    /// Set breakpoints from a gdb script.
+    Load(PathBuf),
+    /// Set (or remove if `None`) a script executed when a breakpoint is hit.
+    Commands(u32, Option<String>),
 }
 
 impl Command {
     /// Return underline breakpoint identity (if command not an `info`).
     pub fn identity(&self) -> Option<BreakpointIdentity> {
         match self {
-            Command::Add(b) => Some(b.clone()),
+            Command::Add(b) | Command::AddHardware(b) => Some(b.clone()),
             Command::Remove(b) => Some(b.clone()),
-            Command::Info => None,
+            Command::Info | Command::Save(_) | Command::Load(_) => None,
             Command::AddDeferred(b) => Some(b.clone()),
+            Command::Enable(num)
+            | Command::Disable(num)
+            | Command::Condition(num, _)
+            | Command::Ignore(num, _)
+            | Command::Commands(num, _) => Some(BreakpointIdentity::Number(*num)),
         }
     }
 }
@@ -39,7 +65,150 @@ pub enum ExecutionResult<'a> {
     New(Vec<BreakpointView<'a>>),
     Removed(Vec<BreakpointView<'a>>),
     Dump(Vec<BreakpointView<'a>>),
+    Updated(Vec<BreakpointView<'a>>),
     AddDeferred,
+    /// Number of breakpoints saved into a file.
+    Saved(usize),
+    /// Breakpoints set from a gdb script, and script lines that can't be imported.
+    Loaded(Vec<BreakpointView<'a>>, Vec<String>),
+}
+
+/// Reference to a breakpoint in a gdb script.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GdbBreakpointRef {
+    /// `$bpnum` - last set breakpoint.
+    Last,
+    /// Number of breakpoint, breakpoints are numbered in order of `break` commands.
+    Number(u32),
+}
+
+/// Update of an already set breakpoint.
+#[derive(Debug, Clone)]
+enum GdbUpdate {
+    Condition(Option<String>),
+    Ignore(u32),
+    Enable,
+    Disable,
+}
+
+/// Subset of gdb commands supported by breakpoints import.
+#[derive(Debug, Clone)]
+enum GdbCommand {
+    /// `break <location> [if <condition>]` (or `hbreak` for a hardware breakpoint)
+    Break(BreakpointIdentity, Option<String>, bool),
+    /// `condition`, `ignore`, `enable` or `disable` commands
+    Update(GdbBreakpointRef, GdbUpdate),
+}
+
+fn parse_gdb_location(location: &str) -> Option<BreakpointIdentity> {
+    if location.is_empty() {
+        return None;
+    }
+    if let Some(addr) = location.strip_prefix('*') {
+        let addr = addr.trim();
+        if let Some((symbol, offset)) = addr.split_once('+') {
+            let offset = offset.trim();
+            let offset = match offset.strip_prefix("0x").or(offset.strip_prefix("0X")) {
+                Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+                None => offset.parse().ok()?,
+            };
+            return Some(BreakpointIdentity::Symbol(
+                symbol.trim().to_string(),
+                offset,
+            ));
+        }
+        let hex = addr.strip_prefix("0x").or(addr.strip_prefix("0X"))?;
+        return usize::from_str_radix(hex, 16)
+            .ok()
+            .map(BreakpointIdentity::Address);
+    }
+    if let Some((file, line)) = location.rsplit_once(':') {
+        if let Ok(line) = line.parse() {
+            return Some(BreakpointIdentity::Line(file.to_string(), line));
+        }
+    }
+    Some(BreakpointIdentity::Function(location.to_string()))
+}
+
+fn parse_gdb_ref(r: &str) -> Option<GdbBreakpointRef> {
+    match r {
+        "$bpnum" => Some(GdbBreakpointRef::Last),
+        _ => r.parse().ok().map(GdbBreakpointRef::Number),
+    }
+}
+
+/// Parse a single line of gdb script, return `None` if command is not supported.
+fn parse_gdb_command(line: &str) -> Option<GdbCommand> {
+    let (cmd, args) = line
+        .split_once(char::is_whitespace)
+        .map(|(cmd, args)| (cmd, args.trim()))
+        .unwrap_or((line, ""));
+    match cmd {
+        "break" | "br" | "b" | "hbreak" => {
+            let (location, condition) = match args.split_once(" if ") {
+                Some((location, condition)) => (location.trim(), Some(condition.trim())),
+                None => (args, None),
+            };
+            Some(GdbCommand::Break(
+                parse_gdb_location(location)?,
+                condition.map(ToString::to_string),
+                cmd == "hbreak",
+            ))
+        }
+        "condition" => {
+            let (r, condition) = args
+                .split_once(char::is_whitespace)
+                .map(|(r, condition)| (r, condition.trim()))
+                .unwrap_or((args, ""));
+            Some(GdbCommand::Update(
+                parse_gdb_ref(r)?,
+                GdbUpdate::Condition((!condition.is_empty()).then(|| condition.to_string())),
+            ))
+        }
+        "ignore" => {
+            let (r, count) = args.split_once(char::is_whitespace)?;
+            Some(GdbCommand::Update(
+                parse_gdb_ref(r)?,
+                GdbUpdate::Ignore(count.trim().parse().ok()?),
+            ))
+        }
+        "enable" => Some(GdbCommand::Update(parse_gdb_ref(args)?, GdbUpdate::Enable)),
+        "disable" => Some(GdbCommand::Update(parse_gdb_ref(args)?, GdbUpdate::Disable)),
+        _ => None,
+    }
+}
+
+/// Render breakpoints as a gdb script. Breakpoints are saved as `break file:line`
+/// (or `break *address` if place is unknown) followed by condition, ignore count and state.
+/// Hardware breakpoints are saved with `hbreak` command.
+pub fn to_gdb_script(brkpts: &[BreakpointView]) -> String {
+    let mut script = String::from("# breakpoints saved by BugStalker\n");
+    for brkpt in brkpts {
+        let cmd = if brkpt.options.hardware.is_some() {
+            "hbreak"
+        } else {
+            "break"
+        };
+        _ = match brkpt.place {
+            Some(ref place) => writeln!(
+                script,
+                "{cmd} {}:{}",
+                place.file.display(),
+                place.line_number
+            ),
+            None => writeln!(script, "{cmd} *{}", brkpt.addr),
+        };
+        if let Some(ref condition) = brkpt.options.condition {
+            _ = writeln!(script, "condition $bpnum {}", condition.source);
+        }
+        if brkpt.options.ignore_count > 0 {
+            _ = writeln!(script, "ignore $bpnum {}", brkpt.options.ignore_count);
+        }
+        if brkpt.options.disabled {
+            _ = writeln!(script, "disable $bpnum");
+        }
+    }
+    script
 }
 
 impl<'a> Handler<'a> {
@@ -47,24 +216,49 @@ impl<'a> Handler<'a> {
         Self { dbg: debugger }
     }
 
+    fn add(&mut self, brkpt: &BreakpointIdentity) -> Result<Vec<BreakpointView>, Error> {
+        Ok(match brkpt {
+            BreakpointIdentity::Address(addr) => {
+                vec![self.dbg.set_breakpoint_at_addr((*addr).into())?]
+            }
+            BreakpointIdentity::Line(file, line) => self.dbg.set_breakpoint_at_line(file, *line)?,
+            BreakpointIdentity::Function(func_name) => self.dbg.set_breakpoint_at_fn(func_name)?,
+            BreakpointIdentity::Symbol(symbol, offset) => {
+                self.dbg.set_breakpoint_at_symbol(symbol, *offset)?
+            }
+            BreakpointIdentity::Number(_) => {
+                unreachable!()
+            }
+        })
+    }
+
     pub fn handle(&mut self, cmd: &Command) -> Result<ExecutionResult, Error> {
         let result = match cmd {
-            Command::Add(brkpt) => {
-                let res = match brkpt {
-                    BreakpointIdentity::Address(addr) => {
-                        vec![self.dbg.set_breakpoint_at_addr((*addr).into())?]
-                    }
-                    BreakpointIdentity::Line(file, line) => {
-                        self.dbg.set_breakpoint_at_line(file, *line)?
-                    }
-                    BreakpointIdentity::Function(func_name) => {
-                        self.dbg.set_breakpoint_at_fn(func_name)?
+            Command::Add(brkpt) => ExecutionResult::New(self.add(brkpt)?),
+            Command::AddHardware(brkpt) => {
+                let mut numbers: Vec<_> = self
+                    .add(brkpt)?
+                    .into_iter()
+                    .map(|brkpt| brkpt.number)
+                    .collect();
+                // locations of one breakpoint share a number
+                numbers.dedup();
+                for &number in &numbers {
+                    if let Err(e) = self.dbg.set_breakpoint_hardware(number, true) {
+                        // don't leave software breakpoints instead of requested ones
+                        for &number in &numbers {
+                            _ = self.dbg.remove_breakpoint_by_number(number);
+                        }
+                        return Err(e);
                     }
-                    BreakpointIdentity::Number(_) => {
-                        unreachable!()
-                    }
-                };
-                ExecutionResult::New(res)
+                }
+                ExecutionResult::New(
+                    self.dbg
+                        .breakpoints_snapshot()
+                        .into_iter()
+                        .filter(|brkpt| numbers.contains(&brkpt.number))
+                        .collect(),
+                )
             }
             Command::Remove(brkpt) => {
                 let res = match brkpt {
@@ -79,11 +273,12 @@ impl<'a> Handler<'a> {
                     BreakpointIdentity::Function(func_name) => {
                         self.dbg.remove_breakpoint_at_fn(func_name)?
                     }
-                    BreakpointIdentity::Number(number) => self
-                        .dbg
-                        .remove_breakpoint_by_number(*number)?
-                        .map(|brkpt| vec![brkpt])
-                        .unwrap_or_default(),
+                    BreakpointIdentity::Symbol(symbol, offset) => {
+                        self.dbg.remove_breakpoint_at_symbol(symbol, *offset)?
+                    }
+                    BreakpointIdentity::Number(number) => {
+                        self.dbg.remove_breakpoint_by_number(*number)?
+                    }
                 };
                 ExecutionResult::Removed(res)
             }
@@ -99,13 +294,218 @@ impl<'a> Handler<'a> {
                     BreakpointIdentity::Function(function) => {
                         self.dbg.add_deferred_at_function(function)
                     }
+                    BreakpointIdentity::Symbol(symbol, offset) => {
+                        self.dbg.add_deferred_at_symbol(symbol, *offset)
+                    }
                     BreakpointIdentity::Number(_) => {
                         unreachable!()
                     }
                 };
                 ExecutionResult::AddDeferred
             }
+            Command::Enable(number) => {
+                ExecutionResult::Updated(self.dbg.enable_breakpoint(*number).into_iter().collect())
+            }
+            Command::Disable(number) => {
+                ExecutionResult::Updated(self.dbg.disable_breakpoint(*number).into_iter().collect())
+            }
+            Command::Condition(number, condition) => ExecutionResult::Updated(
+                self.dbg
+                    .set_breakpoint_condition(*number, condition.clone())
+                    .into_iter()
+                    .collect(),
+            ),
+            Command::Ignore(number, count) => ExecutionResult::Updated(
+                self.dbg
+                    .set_breakpoint_ignore_count(*number, *count)
+                    .into_iter()
+                    .collect(),
+            ),
+            Command::Commands(number, _) => ExecutionResult::Updated(
+                self.dbg
+                    .breakpoints_snapshot()
+                    .into_iter()
+                    .filter(|brkpt| brkpt.number == *number)
+                    .collect(),
+            ),
+            Command::Save(path) => {
+                let brkpts = self.dbg.breakpoints_snapshot();
+                fs::write(path, to_gdb_script(&brkpts))?;
+                ExecutionResult::Saved(brkpts.len())
+            }
+            Command::Load(path) => {
+                let script = fs::read_to_string(path)?;
+                let (numbers, skipped) = self.load_gdb_script(&script);
+                let brkpts = self
+                    .dbg
+                    .breakpoints_snapshot()
+                    .into_iter()
+                    .filter(|brkpt| numbers.contains(&brkpt.number))
+                    .collect();
+                ExecutionResult::Loaded(brkpts, skipped)
+            }
         };
         Ok(result)
     }
+
+    /// Execute gdb script commands, return numbers of new breakpoints and skipped lines.
+    /// Unsupported commands and failed breakpoints don't interrupt an import.
+    fn load_gdb_script(&mut self, script: &str) -> (Vec<u32>, Vec<String>) {
+        // breakpoint numbers for each `break` command of a script
+        let mut script_brkpts: Vec<Vec<u32>> = vec![];
+        let mut skipped = vec![];
+
+        for (line_num, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut skip =
+                |reason: &str| skipped.push(format!("{}: {line} ({reason})", line_num + 1));
+
+            let Some(cmd) = parse_gdb_command(line) else {
+                skip("unsupported command");
+                continue;
+            };
+
+            let (r, update) = match cmd {
+                GdbCommand::Break(identity, condition, hardware) => {
+                    let cmd = if hardware {
+                        Command::AddHardware(identity)
+                    } else {
+                        Command::Add(identity)
+                    };
+                    match self.handle(&cmd) {
+                        Ok(ExecutionResult::New(brkpts)) => {
+                            script_brkpts.push(brkpts.iter().map(|brkpt| brkpt.number).collect())
+                        }
+                        Ok(_) => unreachable!("add command always returns new breakpoints"),
+                        Err(e) => {
+                            script_brkpts.push(vec![]);
+                            skip(&e.to_string());
+                        }
+                    }
+                    let Some(condition) = condition else {
+                        continue;
+                    };
+                    (
+                        GdbBreakpointRef::Last,
+                        GdbUpdate::Condition(Some(condition)),
+                    )
+                }
+                GdbCommand::Update(r, update) => (r, update),
+            };
+
+            let numbers = match r {
+                GdbBreakpointRef::Last => script_brkpts.last(),
+                GdbBreakpointRef::Number(num) => num
+                    .checked_sub(1)
+                    .and_then(|idx| script_brkpts.get(idx as usize)),
+            };
+            let Some(numbers) = numbers else {
+                skip("unknown breakpoint");
+                continue;
+            };
+
+            match update {
+                GdbUpdate::Condition(source) => {
+                    let condition = match source {
+                        None => None,
+                        Some(source) => {
+                            match parser::brkpt_condition().parse(&source).into_result() {
+                                Ok(condition) => condition,
+                                Err(_) => {
+                                    skip("invalid condition");
+                                    continue;
+                                }
+                            }
+                        }
+                    };
+                    for &number in numbers {
+                        self.dbg.set_breakpoint_condition(number, condition.clone());
+                    }
+                }
+                GdbUpdate::Ignore(count) => {
+                    for &number in numbers {
+                        self.dbg.set_breakpoint_ignore_count(number, count);
+                    }
+                }
+                GdbUpdate::Enable => {
+                    for &number in numbers {
+                        self.dbg.enable_breakpoint(number);
+                    }
+                }
+                GdbUpdate::Disable => {
+                    for &number in numbers {
+                        self.dbg.disable_breakpoint(number);
+                    }
+                }
+            }
+        }
+
+        (script_brkpts.into_iter().flatten().collect(), skipped)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_gdb_command() {
+        assert!(matches!(
+            parse_gdb_command("break main.rs:15"),
+            Some(GdbCommand::Break(BreakpointIdentity::Line(file, 15), None, false)) if file == "main.rs"
+        ));
+        assert!(matches!(
+            parse_gdb_command("b  app::module::func if a > 1"),
+            Some(GdbCommand::Break(BreakpointIdentity::Function(func), Some(cond), false))
+                if func == "app::module::func" && cond == "a > 1"
+        ));
+        assert!(matches!(
+            parse_gdb_command("break *0x55555555BD30"),
+            Some(GdbCommand::Break(
+                BreakpointIdentity::Address(0x55555555BD30),
+                None,
+                false
+            ))
+        ));
+        assert!(matches!(
+            parse_gdb_command("hbreak main.rs:15"),
+            Some(GdbCommand::Break(BreakpointIdentity::Line(file, 15), None, true)) if file == "main.rs"
+        ));
+        assert!(matches!(
+            parse_gdb_command("break *main+0x10"),
+            Some(GdbCommand::Break(BreakpointIdentity::Symbol(symbol, 16), None, false)) if symbol == "main"
+        ));
+        assert!(matches!(
+            parse_gdb_command("condition $bpnum a.b"),
+            Some(GdbCommand::Update(GdbBreakpointRef::Last, GdbUpdate::Condition(Some(cond)))) if cond == "a.b"
+        ));
+        assert!(matches!(
+            parse_gdb_command("condition 2"),
+            Some(GdbCommand::Update(
+                GdbBreakpointRef::Number(2),
+                GdbUpdate::Condition(None)
+            ))
+        ));
+        assert!(matches!(
+            parse_gdb_command("disable $bpnum"),
+            Some(GdbCommand::Update(
+                GdbBreakpointRef::Last,
+                GdbUpdate::Disable
+            ))
+        ));
+        assert!(matches!(
+            parse_gdb_command("ignore 1 5"),
+            Some(GdbCommand::Update(
+                GdbBreakpointRef::Number(1),
+                GdbUpdate::Ignore(5)
+            ))
+        ));
+        assert!(parse_gdb_command("ignore $bpnum").is_none());
+        assert!(parse_gdb_command("set pagination off").is_none());
+        assert!(parse_gdb_command("break").is_none());
+        assert!(parse_gdb_command("break *main").is_none());
+    }
 }
@@ -0,0 +1,46 @@
+use crate::debugger::catchpoint::SyscallFilter;
+use crate::debugger::Debugger;
+use crate::ui::command;
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Catch syscalls with specified names or numbers, any syscall if list is empty.
+    Syscall(Vec<String>),
+    Off,
+    Info,
+}
+
+pub enum ExecutionResult {
+    Enabled(SyscallFilter),
+    Disabled,
+    /// Caught syscalls, `None` if catchpoint is not set.
+    Info(Option<SyscallFilter>),
+}
+
+/// Set or remove a syscall catchpoint.
+pub struct Handler<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<ExecutionResult> {
+        match cmd {
+            Command::Syscall(syscalls) => {
+                let filter = SyscallFilter::from_names(syscalls)?;
+                self.dbg.enable_syscall_catchpoint(filter.clone());
+                Ok(ExecutionResult::Enabled(filter))
+            }
+            Command::Off => {
+                self.dbg.disable_syscall_catchpoint();
+                Ok(ExecutionResult::Disabled)
+            }
+            Command::Info => Ok(ExecutionResult::Info(
+                self.dbg.syscall_catchpoint().cloned(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::debugger::checkpoint::CheckpointInfo;
+use crate::debugger::Debugger;
+use crate::ui::command;
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Create,
+    Info,
+    /// Restart debugee from a checkpoint with specified id.
+    Restart(u32),
+    /// Delete a checkpoint with specified id.
+    Delete(u32),
+}
+
+pub enum ExecutionResult {
+    Created(CheckpointInfo),
+    Info(Vec<CheckpointInfo>),
+    Restarted,
+    Deleted,
+}
+
+/// Create debugee checkpoints and restart debugee from them.
+pub struct Handler<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<ExecutionResult> {
+        match cmd {
+            Command::Create => Ok(ExecutionResult::Created(self.dbg.checkpoint()?)),
+            Command::Info => Ok(ExecutionResult::Info(self.dbg.checkpoints().to_vec())),
+            Command::Restart(id) => {
+                self.dbg.restart(id)?;
+                Ok(ExecutionResult::Restarted)
+            }
+            Command::Delete(id) => {
+                self.dbg.delete_checkpoint(id)?;
+                Ok(ExecutionResult::Deleted)
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::debugger::coverage::{CoverageTarget, FileCoverage};
+use crate::debugger::Debugger;
+use crate::ui::command;
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Track(CoverageTarget),
+    Info,
+    Clear,
+}
+
+pub enum ExecutionResult {
+    /// Number of new tracked lines.
+    Tracked(usize),
+    Report(Vec<FileCoverage>),
+    Cleared,
+}
+
+pub struct Handler<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<ExecutionResult> {
+        match cmd {
+            Command::Track(target) => {
+                Ok(ExecutionResult::Tracked(self.dbg.track_coverage(target)?))
+            }
+            Command::Info => Ok(ExecutionResult::Report(self.dbg.coverage())),
+            Command::Clear => {
+                self.dbg.clear_coverage()?;
+                Ok(ExecutionResult::Cleared)
+            }
+        }
+    }
+}
+
+/// Collapse sorted line numbers into ranges, ex. `1, 3-5, 9`.
+pub fn line_ranges(lines: impl IntoIterator<Item = u64>) -> String {
+    let mut ranges: Vec<(u64, u64)> = vec![];
+    for line in lines {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == line => *end = line,
+            _ => ranges.push((line, line)),
+        }
+    }
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_line_ranges() {
+        assert_eq!(line_ranges([1, 3, 4, 5, 9]), "1, 3-5, 9");
+        assert_eq!(line_ranges([7]), "7");
+        assert_eq!(line_ranges([]), "");
+    }
+}
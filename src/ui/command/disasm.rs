@@ -0,0 +1,16 @@
+use crate::debugger::{Debugger, DisasmTarget, Disassembly};
+use crate::ui::command;
+
+pub struct Handler<'a> {
+    dbg: &'a Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&self, target: DisasmTarget) -> command::CommandResult<Disassembly> {
+        Ok(self.dbg.disassemble(target)?)
+    }
+}
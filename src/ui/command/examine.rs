@@ -0,0 +1,28 @@
+use crate::debugger::examine::{ExamineFormat, MemoryRow, UnitSize};
+use crate::debugger::Debugger;
+use crate::ui::command;
+
+/// Examine `count` memory units starting from an address (like `x/{count}{format}{size} {addr}`).
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub addr: usize,
+    pub count: usize,
+    pub format: ExamineFormat,
+    pub size: UnitSize,
+}
+
+pub struct Handler<'a> {
+    dbg: &'a Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&self, cmd: Command) -> command::CommandResult<Vec<MemoryRow>> {
+        Ok(self
+            .dbg
+            .examine_memory(cmd.addr, cmd.count, cmd.format, cmd.size)?)
+    }
+}
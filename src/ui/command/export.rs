@@ -0,0 +1,248 @@
+//! Export of variables into CSV or JSON files, for analysis in external tools.
+//!
+//! Arrays, vectors, maps and structures are converted into rows:
+//! - array of scalars: `index,value`
+//! - array of structures: `index,<field1>,<field2>,...`
+//! - map or structure: `key,value` (or `key,<field1>,...` if values are structures)
+//!
+//! Nested non-scalar values are written as JSON text into a single cell.
+
+use crate::debugger::variable::render::{RenderRepr, ValueLayout};
+use crate::debugger::variable::select::DQE;
+use crate::debugger::variable::{SupportedScalar, VariableIR};
+use crate::debugger::Debugger;
+use crate::ui::command;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use strum_macros::{Display, EnumString};
+
+/// Output file format.
+#[derive(Debug, Clone, Copy, PartialEq, EnumString, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    /// Choose a format by file extension, CSV is used by default.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Format::Json,
+            _ => Format::Csv,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub dqe: DQE,
+    pub path: PathBuf,
+    /// Output format, if `None` format is chosen by file extension.
+    pub format: Option<Format>,
+}
+
+pub struct Handler<'a> {
+    dbg: &'a Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    /// Write selected variables into a file, return number of exported rows
+    /// (or top level items for JSON format).
+    pub fn handle(&self, cmd: Command) -> command::CommandResult<usize> {
+        let vars = self.dbg.read_variable(cmd.dqe)?;
+        // single variable exported as is, multiple variables as a structure
+        let value = match vars.as_slice() {
+            [var] => to_value(var),
+            _ => Value::Object(vars.iter().map(|var| (var.name(), to_value(var))).collect()),
+        };
+
+        let format = cmd.format.unwrap_or_else(|| Format::from_path(&cmd.path));
+        let (content, count) = match format {
+            Format::Csv => {
+                let rows = to_rows(&value);
+                let count = rows.len().saturating_sub(1);
+                (rows.join("\n") + "\n", count)
+            }
+            Format::Json => {
+                let count = match value {
+                    Value::Array(ref items) => items.len(),
+                    Value::Object(ref items) => items.len(),
+                    _ => 1,
+                };
+                let json = serde_json::to_string_pretty(&value).expect("infallible");
+                (json + "\n", count)
+            }
+        };
+
+        fs::write(&cmd.path, content).map_err(crate::debugger::Error::from)?;
+        Ok(count)
+    }
+}
+
+fn scalar_to_value(scalar: &SupportedScalar) -> Value {
+    match *scalar {
+        SupportedScalar::I8(v) => v.into(),
+        SupportedScalar::I16(v) => v.into(),
+        SupportedScalar::I32(v) => v.into(),
+        SupportedScalar::I64(v) => v.into(),
+        SupportedScalar::Isize(v) => v.into(),
+        SupportedScalar::U8(v) => v.into(),
+        SupportedScalar::U16(v) => v.into(),
+        SupportedScalar::U32(v) => v.into(),
+        SupportedScalar::U64(v) => v.into(),
+        SupportedScalar::Usize(v) => v.into(),
+        SupportedScalar::I128(v) => {
+            i64::try_from(v).map_or_else(|_| v.to_string().into(), Value::from)
+        }
+        SupportedScalar::U128(v) => {
+            u64::try_from(v).map_or_else(|_| v.to_string().into(), Value::from)
+        }
+        SupportedScalar::F32(v) => v.into(),
+        SupportedScalar::F64(v) => v.into(),
+        SupportedScalar::Bool(v) => v.into(),
+        SupportedScalar::Char(v) => v.to_string().into(),
+        SupportedScalar::Empty() => Value::Null,
+    }
+}
+
+/// Convert variable into a JSON value, scalars keep their types.
+pub(crate) fn to_value(var: &VariableIR) -> Value {
+    if let VariableIR::Scalar(scalar) = var {
+        return scalar
+            .value
+            .as_ref()
+            .map(scalar_to_value)
+            .unwrap_or_default();
+    }
+
+    match var.value() {
+        None => Value::Null,
+        Some(ValueLayout::PreRendered(value)) => value.into(),
+        Some(ValueLayout::Referential { addr }) => format!("{addr:p}").into(),
+        Some(ValueLayout::Wrapped(inner)) => to_value(inner),
+        Some(ValueLayout::Structure { members }) => Value::Object(
+            members
+                .iter()
+                .map(|member| (member.name(), to_value(member)))
+                .collect(),
+        ),
+        Some(ValueLayout::List { members, .. }) => members.iter().map(to_value).collect(),
+        Some(ValueLayout::Map(kvs)) => {
+            let keys = kvs.iter().map(|(k, _)| to_value(k)).collect::<Vec<_>>();
+            if keys.iter().all(|key| !key.is_array() && !key.is_object()) {
+                let mut map = Map::new();
+                for (key, (_, value)) in keys.into_iter().zip(kvs) {
+                    map.insert(cell(&key), to_value(value));
+                }
+                Value::Object(map)
+            } else {
+                keys.into_iter()
+                    .zip(kvs)
+                    .map(|(key, (_, value))| {
+                        Value::Object(Map::from_iter([
+                            ("key".to_string(), key),
+                            ("value".to_string(), to_value(value)),
+                        ]))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Render a value as a CSV cell (without escaping).
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    }
+}
+
+fn escape(cell: String) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell
+    }
+}
+
+/// Convert a value into CSV rows, the first row is a header.
+fn to_rows(value: &Value) -> Vec<String> {
+    let (key_column, entries): (&str, Vec<(String, &Value)>) = match value {
+        Value::Array(items) => (
+            "index",
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| (i.to_string(), item))
+                .collect(),
+        ),
+        Value::Object(items) => ("key", items.iter().map(|(k, v)| (k.clone(), v)).collect()),
+        scalar => return vec!["value".to_string(), escape(cell(scalar))],
+    };
+
+    // if all entries are objects - their fields are columns
+    let mut columns: Vec<&str> = vec![];
+    let is_table = !entries.is_empty() && entries.iter().all(|(_, v)| v.is_object());
+    if is_table {
+        for (_, entry) in &entries {
+            for field in entry.as_object().expect("checked").keys() {
+                if !columns.contains(&field.as_str()) {
+                    columns.push(field);
+                }
+            }
+        }
+    } else {
+        columns.push("value");
+    }
+
+    let header = std::iter::once(key_column)
+        .chain(columns.iter().copied())
+        .map(|column| escape(column.to_string()))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let mut rows = vec![header];
+    for (key, entry) in entries {
+        let mut row = vec![escape(key)];
+        if is_table {
+            for column in &columns {
+                row.push(escape(entry.get(column).map(cell).unwrap_or_default()));
+            }
+        } else {
+            row.push(escape(cell(entry)));
+        }
+        rows.push(row.join(","));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_csv_rows() {
+        assert_eq!(
+            to_rows(&json!([1, 2.5, "a,b"])),
+            vec!["index,value", "0,1", "1,2.5", "2,\"a,b\""]
+        );
+        assert_eq!(
+            to_rows(&json!([{"x": 1, "y": 2}, {"x": 3, "z": [1]}])),
+            vec!["index,x,y,z", "0,1,2,", "1,3,,[1]"]
+        );
+        assert_eq!(
+            to_rows(&json!({"a": 1, "b": null})),
+            vec!["key,value", "a,1", "b,"]
+        );
+        assert_eq!(to_rows(&json!(true)), vec!["value", "true"]);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::debugger::history::StopRecord;
+use crate::debugger::Debugger;
+use crate::ui::command;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Info,
+    Clear,
+    /// Save a number of top stack frames with each stop, 0 disables stack saving.
+    Stack(usize),
+    /// Duplicate history into a file, `None` disables logging.
+    Log(Option<String>),
+}
+
+pub enum ExecutionResult {
+    /// All saved stops, the oldest first.
+    Info(Vec<StopRecord>),
+    Cleared,
+    StackDepth(usize),
+    Log(Option<PathBuf>),
+}
+
+/// Show and configure a history of debugee stops.
+pub struct Handler<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<ExecutionResult> {
+        match cmd {
+            Command::Info => Ok(ExecutionResult::Info(self.dbg.history().cloned().collect())),
+            Command::Clear => {
+                self.dbg.clear_history();
+                Ok(ExecutionResult::Cleared)
+            }
+            Command::Stack(depth) => {
+                self.dbg.set_history_stack_depth(depth);
+                Ok(ExecutionResult::StackDepth(depth))
+            }
+            Command::Log(path) => {
+                self.dbg.set_history_log(path.as_deref().map(Path::new))?;
+                Ok(ExecutionResult::Log(
+                    self.dbg.history_log().map(Path::to_path_buf),
+                ))
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::debugger::fd::FileDescriptor;
+use crate::debugger::{Debugger, FunctionInfo, SignalDisposition, SignalRecord};
+use crate::ui::command;
+use nix::sys::signal::Signal;
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Open file descriptors of a debugee.
+    Fds,
+    /// How debugger handles signals, or history of delivered signals.
+    Signals { history: bool },
+    /// Functions which names match a regular expression.
+    Functions(String),
+}
+
+pub enum ExecutionResult {
+    Fds(Vec<FileDescriptor>),
+    SignalDispositions(Vec<(Signal, SignalDisposition)>),
+    SignalHistory(Vec<SignalRecord>),
+    Functions(Vec<FunctionInfo>),
+}
+
+pub struct Handler<'a> {
+    dbg: &'a Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&self, cmd: Command) -> command::CommandResult<ExecutionResult> {
+        match cmd {
+            Command::Fds => Ok(ExecutionResult::Fds(self.dbg.open_fds()?)),
+            Command::Signals { history: false } => Ok(ExecutionResult::SignalDispositions(
+                Signal::iterator()
+                    .map(|signal| (signal, SignalDisposition::of(signal)))
+                    .collect(),
+            )),
+            Command::Signals { history: true } => {
+                Ok(ExecutionResult::SignalHistory(self.dbg.signal_history()))
+            }
+            Command::Functions(regex) => Ok(ExecutionResult::Functions(
+                self.dbg.find_functions_info(&regex)?,
+            )),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::{Debugger, TargetPlace};
+use crate::ui::command;
+
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub place: TargetPlace,
+    /// Allow a jump outside of the current function.
+    pub force: bool,
+}
+
+// Set program counter of a thread in focus
+pub struct Handler<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<RelocatedAddress> {
+        Ok(self.dbg.jump(cmd.place, cmd.force)?)
+    }
+}
@@ -6,15 +6,28 @@
 //! define what exactly debugger must to do and return result of it.
 
 pub mod arguments;
+pub mod r#async;
 pub mod backtrace;
 pub mod r#break;
+pub mod catch;
+pub mod checkpoint;
 pub mod r#continue;
+pub mod coverage;
+pub mod disasm;
+pub mod examine;
+pub mod export;
 pub mod frame;
+pub mod history;
+pub mod info;
+pub mod jump;
 pub mod memory;
 pub mod parser;
+pub mod record;
 pub mod register;
 pub mod run;
+pub mod set;
 pub mod sharedlib;
+pub mod skip;
 pub mod source_code;
 pub mod step_instruction;
 pub mod step_into;
@@ -22,27 +35,44 @@ pub mod step_out;
 pub mod step_over;
 pub mod symbol;
 pub mod thread;
+pub mod until;
 pub mod variables;
+pub mod watch;
 
-use crate::debugger::variable::select::DQE;
-use crate::debugger::Error;
+use crate::debugger::variable::expr::Expr;
+use crate::debugger::variable::select::{Literal, DQE};
+use crate::debugger::{DisasmTarget, Error, ErrorCategory};
+pub use crate::ui::syntax::RenderError;
 
 #[derive(thiserror::Error, Debug)]
 pub enum CommandError {
     #[error("malformed command")]
     Parsing(String),
     #[error("render error: \n{0}")]
-    FileRender(anyhow::Error),
+    FileRender(RenderError),
     #[error(transparent)]
     Handle(#[from] Error),
 }
 
+impl CommandError {
+    /// Return error category.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            CommandError::Parsing(_) => ErrorCategory::Usage,
+            CommandError::FileRender(_) => ErrorCategory::Internal,
+            CommandError::Handle(err) => err.category(),
+        }
+    }
+}
+
 pub type CommandResult<T> = Result<T, CommandError>;
 
 /// External commands that can be processed by the debugger.
 #[derive(Debug, Clone)]
 pub enum Command {
     PrintVariables(DQE),
+    PrintExpression(Expr),
+    ExportVariables(export::Command),
     PrintArguments(DQE),
     PrintBacktrace(backtrace::Command),
     Continue,
@@ -50,19 +80,61 @@ pub enum Command {
     Run,
     StepInstruction,
     StepInto,
+    StepIntoTarget(step_into::Command),
     StepOut,
     StepOver,
+    Until(until::Command),
+    Jump(jump::Command),
     PrintSymbol(String),
     Breakpoint(r#break::Command),
+    Watchpoint(watch::Command),
     Memory(memory::Command),
+    Examine(examine::Command),
     Register(register::Command),
     Thread(thread::Command),
     SharedLib,
+    Info(info::Command),
+    Coverage(coverage::Command),
     SourceCode(source_code::Command),
     SkipInput,
     Oracle(String, Option<String>),
+    Script(String),
+    Set(set::Command),
+    Call(String, Vec<Literal>),
+    /// Force a function in selected frame to return, optionally with a value.
+    Return(Option<Literal>),
+    Record(record::Command),
+    Disassemble(DisasmTarget),
+    Catch(catch::Command),
+    Skip(skip::Command),
+    History(history::Command),
+    Checkpoint(checkpoint::Command),
+    Async(r#async::Command),
     Help {
         command: Option<String>,
         reason: Option<String>,
     },
 }
+
+impl Command {
+    /// Return true if command may be repeated by an empty input (like in gdb),
+    /// these are execution control and view commands.
+    pub fn is_repeatable(&self) -> bool {
+        matches!(
+            self,
+            Command::Continue
+                | Command::StepInstruction
+                | Command::StepInto
+                | Command::StepOut
+                | Command::StepOver
+                | Command::Until(until::Command::NextLine)
+                | Command::PrintBacktrace(_)
+                | Command::PrintVariables(_)
+                | Command::PrintExpression(_)
+                | Command::PrintArguments(_)
+                | Command::SourceCode(_)
+                | Command::Record(record::Command::ReverseStep)
+                | Command::Record(record::Command::ReverseContinue)
+        )
+    }
+}
@@ -0,0 +1,79 @@
+//! Compatibility with GDB commands.
+//!
+//! Common GDB commands that have no native equivalent with the same name (like `p`, `n`
+//! or `info locals`) are translated into native commands before parsing. GDB commands whose
+//! syntax is native already (`b file:line`, `bt`, `c`, `finish`, `x/16x`) are not listed here.
+
+use std::borrow::Cow;
+
+/// GDB command (space separated words) and a native command that replaces it,
+/// longer commands go first.
+pub const GDB_ALIASES: &[(&str, &str)] = &[
+    ("info locals", "var locals"),
+    ("info args", "arg all"),
+    ("info breakpoints", "break info"),
+    ("info break", "break info"),
+    ("info threads", "thread info"),
+    ("info frame", "frame info"),
+    ("info registers", "register info"),
+    ("info sharedlibrary", "sharedlib info"),
+    ("print", "var"),
+    ("p", "var"),
+    ("n", "next"),
+    ("s", "step"),
+    ("si", "stepi"),
+    ("where", "backtrace"),
+    ("delete", "break remove"),
+];
+
+/// Match input with a command, return the rest of input if matched.
+fn strip_command<'a>(input: &'a str, command: &str) -> Option<&'a str> {
+    let mut rest = input.trim_start();
+    for word in command.split(' ') {
+        let tail = rest.strip_prefix(word)?;
+        if !tail.is_empty() && !tail.starts_with(char::is_whitespace) {
+            return None;
+        }
+        rest = tail.trim_start();
+    }
+    Some(rest)
+}
+
+/// Replace GDB command in the input with a native one, input without GDB command
+/// is returned as is.
+///
+/// # Arguments
+///
+/// * `input`: user input
+pub fn translate(input: &str) -> Cow<'_, str> {
+    GDB_ALIASES
+        .iter()
+        .find_map(|(gdb, native)| {
+            let rest = strip_command(input, gdb)?;
+            if rest.is_empty() {
+                Some(Cow::Borrowed(*native))
+            } else {
+                Some(Cow::Owned(format!("{native} {rest}")))
+            }
+        })
+        .unwrap_or(Cow::Borrowed(input))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(translate("p vec[1]"), "var vec[1]");
+        assert_eq!(translate(" info  locals "), "var locals");
+        assert_eq!(translate("n"), "next");
+        assert_eq!(translate("delete 2"), "break remove 2");
+        // native commands and commands with a common prefix are not translated
+        assert_eq!(translate("next"), "next");
+        assert_eq!(translate("step"), "step");
+        assert_eq!(translate("info signals"), "info signals");
+        assert_eq!(translate("info localsx"), "info localsx");
+        assert_eq!(translate("print_me"), "print_me");
+    }
+}
@@ -1,4 +1,5 @@
-//! data query expressions parser.
+//! data query expressions and expressions parser.
+use crate::debugger::variable::expr::{BinaryOp, Expr, UnaryOp, CAST_TYPES};
 use crate::debugger::variable::select::{Literal, LiteralOrWildcard, VariableSelector, DQE};
 use crate::ui::command::parser::{hex, rust_identifier};
 use chumsky::prelude::*;
@@ -23,7 +24,8 @@ fn ptr_cast<'a>() -> impl Parser<'a, &'a str, DQE, Err<'a>> + Clone {
         .labelled("pointer cast")
 }
 
-fn literal<'a>() -> impl Parser<'a, &'a str, Literal, Err<'a>> + Clone {
+/// Literal object (number, string, bool, address, enum variant, array or associative array).
+pub fn literal<'a>() -> impl Parser<'a, &'a str, Literal, Err<'a>> + Clone {
     let op = |c| just(c).padded();
 
     let literal = recursive(|literal| {
@@ -109,7 +111,13 @@ fn literal<'a>() -> impl Parser<'a, &'a str, Literal, Err<'a>> + Clone {
     literal
 }
 
+/// Parse a whole input as a data query expression.
 pub fn parser<'a>() -> impl Parser<'a, &'a str, DQE, Err<'a>> {
+    expression().then_ignore(end())
+}
+
+/// Data query expression that may be followed by other tokens.
+pub fn expression<'a>() -> impl Parser<'a, &'a str, DQE, Err<'a>> + Clone {
     let selector = rust_identifier().padded().map(|name: &str| {
         DQE::Variable(VariableSelector::Name {
             var_name: name.to_string(),
@@ -117,7 +125,7 @@ pub fn parser<'a>() -> impl Parser<'a, &'a str, DQE, Err<'a>> {
         })
     });
 
-    let expr = recursive(|expr| {
+    recursive(|expr| {
         let op = |c| just(c).padded();
 
         let atom = selector.or(expr.delimited_by(op('('), op(')'))).padded();
@@ -167,9 +175,138 @@ pub fn parser<'a>() -> impl Parser<'a, &'a str, DQE, Err<'a>> {
         op('*')
             .repeated()
             .foldr(expr, |_op, rhs| DQE::Deref(Box::new(rhs)))
+    })
+}
+
+/// Prefix operator of an expression.
+#[derive(Clone)]
+enum Prefix {
+    Unary(UnaryOp),
+    AddressOf,
+    Deref,
+    Cast(String),
+}
+
+impl Prefix {
+    fn apply(self, expr: Expr) -> Expr {
+        match self {
+            Prefix::Unary(op) => Expr::Unary(op, expr.boxed()),
+            Prefix::AddressOf => Expr::AddressOf(expr.boxed()),
+            Prefix::Deref => Expr::Deref(expr.boxed()),
+            Prefix::Cast(type_name) => Expr::Cast(type_name, expr.boxed()),
+        }
+    }
+}
+
+fn binary<'a>(
+    operand: impl Parser<'a, &'a str, Expr, Err<'a>> + Clone + 'a,
+    op: impl Parser<'a, &'a str, BinaryOp, Err<'a>> + Clone + 'a,
+) -> Boxed<'a, 'a, &'a str, Expr, Err<'a>> {
+    operand
+        .clone()
+        .foldl(op.then(operand).repeated(), |lhs, (op, rhs)| {
+            Expr::Binary(op, lhs.boxed(), rhs.boxed())
+        })
+        .boxed()
+}
+
+fn cast_type<'a>() -> impl Parser<'a, &'a str, String, Err<'a>> + Clone {
+    let op = |c| just(c).padded();
+
+    let primitive = text::ascii::ident().try_map(|type_name: &str, span| {
+        if CAST_TYPES.contains(&type_name) {
+            Ok(type_name.to_string())
+        } else {
+            Err(Rich::custom(
+                span,
+                format!("unsupported cast type `{type_name}`"),
+            ))
+        }
     });
+    let pointer = just("*const")
+        .or(just("*mut"))
+        .then(none_of(")").repeated())
+        .to_slice()
+        .map(|type_name: &str| type_name.trim().to_string());
+
+    primitive
+        .or(pointer)
+        .padded()
+        .delimited_by(op("("), op(")"))
+        .labelled("cast type")
+}
+
+/// Parse a whole input as an expression.
+pub fn expr_parser<'a>() -> impl Parser<'a, &'a str, Expr, Err<'a>> {
+    expr().then_ignore(end())
+}
 
-    expr.then_ignore(end())
+/// Expression over data query expressions and literals (arithmetic, comparisons, logical
+/// operators, casts, address-of and dereference) that may be followed by other tokens.
+pub fn expr<'a>() -> impl Parser<'a, &'a str, Expr, Err<'a>> + Clone {
+    recursive(|expr| {
+        let op = |c| just(c).padded();
+
+        let bool = text::ascii::keyword("true")
+            .to(Literal::Bool(true))
+            .or(text::ascii::keyword("false").to(Literal::Bool(false)));
+        let float = text::int(10)
+            .then_ignore(just("."))
+            .then(text::int(10))
+            .to_slice()
+            .map(|f: &str| Literal::Float(f.parse::<f64>().expect("infallible")));
+        let int = text::int(10).try_map(|i: &str, span| {
+            i.parse::<i64>()
+                .map(Literal::Int)
+                .map_err(|e| Rich::custom(span, e.to_string()))
+        });
+        let literal = choice((bool, hex().map(Literal::Address), float, int))
+            .padded()
+            .map(Expr::Literal);
+
+        let atom = literal
+            .or(expression().map(Expr::Select))
+            .or(expr.delimited_by(op("("), op(")")))
+            .padded();
+
+        let prefix = choice((
+            op("-").to(Prefix::Unary(UnaryOp::Neg)),
+            op("!").to(Prefix::Unary(UnaryOp::Not)),
+            op("&").to(Prefix::AddressOf),
+            op("*").to(Prefix::Deref),
+            cast_type().map(Prefix::Cast),
+        ));
+        let unary = prefix
+            .repeated()
+            .foldr(atom, |prefix, expr| prefix.apply(expr))
+            .boxed();
+
+        let product = binary(
+            unary,
+            choice((
+                op("*").to(BinaryOp::Mul),
+                op("/").to(BinaryOp::Div),
+                op("%").to(BinaryOp::Rem),
+            )),
+        );
+        let sum = binary(
+            product,
+            op("+").to(BinaryOp::Add).or(op("-").to(BinaryOp::Sub)),
+        );
+        let comparison = binary(
+            sum,
+            choice((
+                op("==").to(BinaryOp::Eq),
+                op("!=").to(BinaryOp::Ne),
+                op("<=").to(BinaryOp::Le),
+                op(">=").to(BinaryOp::Ge),
+                op("<").to(BinaryOp::Lt),
+                op(">").to(BinaryOp::Gt),
+            )),
+        );
+        let and = binary(comparison, op("&&").to(BinaryOp::And));
+        binary(and, op("||").to(BinaryOp::Or))
+    })
 }
 
 #[cfg(test)]
@@ -635,6 +772,119 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_expr_parser() {
+        let var = |name: &str| {
+            Expr::Select(DQE::Variable(VariableSelector::Name {
+                var_name: name.to_string(),
+                only_local: false,
+            }))
+        };
+        let int = |i| Expr::Literal(Literal::Int(i));
+
+        struct TestCase {
+            string: &'static str,
+            expr: Expr,
+        }
+        let test_cases = vec![
+            TestCase {
+                string: "var1",
+                expr: var("var1"),
+            },
+            TestCase {
+                string: "a + b * 2",
+                expr: Expr::Binary(
+                    BinaryOp::Add,
+                    var("a").boxed(),
+                    Expr::Binary(BinaryOp::Mul, var("b").boxed(), int(2).boxed()).boxed(),
+                ),
+            },
+            TestCase {
+                string: "(a + b) * 2",
+                expr: Expr::Binary(
+                    BinaryOp::Mul,
+                    Expr::Binary(BinaryOp::Add, var("a").boxed(), var("b").boxed()).boxed(),
+                    int(2).boxed(),
+                ),
+            },
+            TestCase {
+                string: "a - b - 1",
+                expr: Expr::Binary(
+                    BinaryOp::Sub,
+                    Expr::Binary(BinaryOp::Sub, var("a").boxed(), var("b").boxed()).boxed(),
+                    int(1).boxed(),
+                ),
+            },
+            TestCase {
+                string: "x.len >= 10 && !flag || false",
+                expr: Expr::Binary(
+                    BinaryOp::Or,
+                    Expr::Binary(
+                        BinaryOp::And,
+                        Expr::Binary(
+                            BinaryOp::Ge,
+                            Expr::Select(DQE::Field(
+                                DQE::Variable(VariableSelector::Name {
+                                    var_name: "x".to_string(),
+                                    only_local: false,
+                                })
+                                .boxed(),
+                                "len".to_string(),
+                            ))
+                            .boxed(),
+                            int(10).boxed(),
+                        )
+                        .boxed(),
+                        Expr::Unary(UnaryOp::Not, var("flag").boxed()).boxed(),
+                    )
+                    .boxed(),
+                    Expr::Literal(Literal::Bool(false)).boxed(),
+                ),
+            },
+            TestCase {
+                string: "(u64)-x",
+                expr: Expr::Cast(
+                    "u64".to_string(),
+                    Expr::Unary(UnaryOp::Neg, var("x").boxed()).boxed(),
+                ),
+            },
+            TestCase {
+                string: "(a) % 2.5",
+                expr: Expr::Binary(
+                    BinaryOp::Rem,
+                    var("a").boxed(),
+                    Expr::Literal(Literal::Float(2.5)).boxed(),
+                ),
+            },
+            TestCase {
+                string: "*(&x) != 0x10",
+                expr: Expr::Binary(
+                    BinaryOp::Ne,
+                    Expr::Deref(Expr::AddressOf(var("x").boxed()).boxed()).boxed(),
+                    Expr::Literal(Literal::Address(0x10)).boxed(),
+                ),
+            },
+            TestCase {
+                string: "*(*const u8)(addr + 1)",
+                expr: Expr::Deref(
+                    Expr::Cast(
+                        "*const u8".to_string(),
+                        Expr::Binary(BinaryOp::Add, var("addr").boxed(), int(1).boxed()).boxed(),
+                    )
+                    .boxed(),
+                ),
+            },
+        ];
+
+        for tc in test_cases {
+            let expr = expr_parser().parse(tc.string).into_result().unwrap();
+            assert_eq!(expr, tc.expr, "{}", tc.string);
+        }
+
+        assert!(expr_parser().parse("a +").into_result().is_err());
+        assert!(expr_parser().parse("(String)a").into_result().is_err());
+    }
+
     #[test]
     fn test_expr_parsing_error() {
         struct TestCase {
@@ -1,18 +1,34 @@
+mod compat;
 pub mod expression;
 
 use super::r#break::BreakpointIdentity;
-use super::{frame, memory, register, source_code, thread, Command, CommandError};
-use super::{r#break, CommandResult};
+use super::{
+    catch, checkpoint, coverage, examine, export, frame, history, info, jump, memory, record,
+    register, set, skip, source_code, step_into, thread, until, watch, Command, CommandError,
+};
+use super::{r#async, r#break, CommandResult};
+use crate::debugger::coverage::CoverageTarget;
+use crate::debugger::examine::{ExamineFormat, UnitSize};
+use crate::debugger::symbol::SymbolNameStyle;
 use crate::debugger::variable::select::{VariableSelector, DQE};
+use crate::debugger::watchpoint::WatchCondition;
+use crate::debugger::{BreakpointCondition, DisasmTarget, TargetPlace, ThreadSelector};
+use crate::ui::style;
+use crate::ui::style::StyleElement;
 use ariadne::{Color, Fmt, Label, Report, ReportKind, Source};
 use chumsky::error::{Rich, RichPattern, RichReason};
 use chumsky::prelude::{any, choice, end, just};
 use chumsky::text::{whitespace, Char};
 use chumsky::{extra, text, Boxed, Parser};
 use itertools::Itertools;
+use nix::unistd::Pid;
+use std::path::PathBuf;
+use std::str::FromStr;
 
 pub const VAR_COMMAND: &str = "var";
 pub const VAR_LOCAL_KEY: &str = "locals";
+pub const VAR_EXPORT_OPTION: &str = "--export";
+pub const VAR_FORMAT_OPTION: &str = "--format";
 pub const ARG_COMMAND: &str = "arg";
 pub const ARG_ALL_KEY: &str = "all";
 pub const BACKTRACE_COMMAND: &str = "backtrace";
@@ -29,19 +45,40 @@ pub const RUN_COMMAND_SHORT: &str = "r";
 pub const STEP_INSTRUCTION_COMMAND: &str = "stepi";
 pub const STEP_INTO_COMMAND: &str = "stepinto";
 pub const STEP_INTO_COMMAND_SHORT: &str = "step";
+pub const STEP_INTO_TARGET_SUBCOMMAND: &str = "into";
+pub const STEP_INTO_TARGETS_SUBCOMMAND: &str = "targets";
 pub const STEP_OUT_COMMAND: &str = "stepout";
 pub const STEP_OUT_COMMAND_SHORT: &str = "finish";
 pub const STEP_OVER_COMMAND: &str = "stepover";
 pub const STEP_OVER_COMMAND_SHORT: &str = "next";
+pub const UNTIL_COMMAND: &str = "until";
+pub const ADVANCE_COMMAND: &str = "advance";
+pub const JUMP_COMMAND: &str = "jump";
+pub const JUMP_FORCE_OPTION: &str = "--force";
 pub const SYMBOL_COMMAND: &str = "symbol";
 pub const BREAK_COMMAND: &str = "break";
 pub const BREAK_COMMAND_SHORT: &str = "b";
 pub const BREAK_REMOVE_SUBCOMMAND: &str = "remove";
 pub const BREAK_REMOVE_SUBCOMMAND_SHORT: &str = "r";
+pub const BREAK_INFO_SUBCOMMAND: &str = "info";
+pub const BREAK_ENABLE_SUBCOMMAND: &str = "enable";
+pub const BREAK_DISABLE_SUBCOMMAND: &str = "disable";
+pub const BREAK_CONDITION_SUBCOMMAND: &str = "condition";
+pub const BREAK_CONDITION_NONE: &str = "none";
+pub const BREAK_IGNORE_SUBCOMMAND: &str = "ignore";
+pub const BREAK_SAVE_SUBCOMMAND: &str = "save";
+pub const BREAK_LOAD_SUBCOMMAND: &str = "load";
+pub const BREAK_COMMANDS_SUBCOMMAND: &str = "commands";
+pub const BREAK_HW_OPTION: &str = "--hw";
+pub const WATCH_COMMAND: &str = "watch";
+pub const WATCH_REMOVE_SUBCOMMAND: &str = "remove";
+pub const WATCH_INFO_SUBCOMMAND: &str = "info";
+pub const WATCH_RW_OPTION: &str = "--rw";
 pub const MEMORY_COMMAND: &str = "memory";
 pub const MEMORY_COMMAND_SHORT: &str = "mem";
 pub const MEMORY_COMMAND_READ_SUBCOMMAND: &str = "read";
 pub const MEMORY_COMMAND_WRITE_SUBCOMMAND: &str = "write";
+pub const EXAMINE_COMMAND: &str = "x";
 pub const REGISTER_COMMAND: &str = "register";
 pub const REGISTER_COMMAND_SHORT: &str = "reg";
 pub const REGISTER_COMMAND_READ_SUBCOMMAND: &str = "read";
@@ -51,12 +88,72 @@ pub const THREAD_COMMAND: &str = "thread";
 pub const THREAD_COMMAND_INFO_SUBCOMMAND: &str = "info";
 pub const THREAD_COMMAND_SWITCH_SUBCOMMAND: &str = "switch";
 pub const THREAD_COMMAND_CURRENT_SUBCOMMAND: &str = "current";
+pub const THREAD_COMMAND_TID_KEY: &str = "tid";
 pub const SHARED_LIB_COMMAND: &str = "sharedlib";
 pub const SHARED_LIB_COMMAND_INFO_SUBCOMMAND: &str = "info";
+pub const INFO_COMMAND: &str = "info";
+pub const INFO_FDS_SUBCOMMAND: &str = "fds";
+pub const INFO_SIGNALS_SUBCOMMAND: &str = "signals";
+pub const INFO_SIGNALS_HISTORY_OPTION: &str = "--history";
+pub const INFO_FUNCTIONS_SUBCOMMAND: &str = "functions";
 pub const SOURCE_COMMAND: &str = "source";
 pub const SOURCE_COMMAND_DISASM_SUBCOMMAND: &str = "asm";
 pub const SOURCE_COMMAND_FUNCTION_SUBCOMMAND: &str = "fn";
+pub const COVERAGE_COMMAND: &str = "coverage";
+pub const COVERAGE_FILE_SUBCOMMAND: &str = "file";
+pub const COVERAGE_FN_SUBCOMMAND: &str = "fn";
+pub const COVERAGE_INFO_SUBCOMMAND: &str = "info";
+pub const COVERAGE_CLEAR_SUBCOMMAND: &str = "clear";
 pub const ORACLE_COMMAND: &str = "oracle";
+pub const SCRIPT_COMMAND: &str = "script";
+pub const SET_COMMAND: &str = "set";
+pub const SET_STYLE_SUBCOMMAND: &str = "style";
+pub const SET_LOGGING_SUBCOMMAND: &str = "logging";
+pub const SET_LOGGING_ON: &str = "on";
+pub const SET_LOGGING_OFF: &str = "off";
+pub const SET_VARIABLE_SUBCOMMAND: &str = "variable";
+pub const SET_VARIABLE_SUBCOMMAND_SHORT: &str = "var";
+pub const SET_NON_STOP_SUBCOMMAND: &str = "non-stop";
+pub const SET_NON_STOP_ON: &str = "on";
+pub const SET_NON_STOP_OFF: &str = "off";
+pub const SET_SUBSTITUTE_PATH_SUBCOMMAND: &str = "substitute-path";
+pub const SET_DEMANGLE_SUBCOMMAND: &str = "demangle";
+pub const SET_ARGS_SUBCOMMAND: &str = "args";
+pub const SET_ENV_SUBCOMMAND: &str = "env";
+pub const SET_ENV_UNSET_OPTION: &str = "--unset";
+pub const CALL_COMMAND: &str = "call";
+pub const RETURN_COMMAND: &str = "return";
+pub const RECORD_COMMAND: &str = "record";
+pub const RECORD_START_SUBCOMMAND: &str = "start";
+pub const RECORD_STOP_SUBCOMMAND: &str = "stop";
+pub const RECORD_INFO_SUBCOMMAND: &str = "info";
+pub const REVERSE_STEP_COMMAND: &str = "reverse-step";
+pub const REVERSE_STEP_COMMAND_SHORT: &str = "rs";
+pub const REVERSE_CONTINUE_COMMAND: &str = "reverse-continue";
+pub const REVERSE_CONTINUE_COMMAND_SHORT: &str = "rc";
+pub const DISASM_COMMAND: &str = "disasm";
+pub const CATCH_COMMAND: &str = "catch";
+pub const CATCH_SYSCALL_SUBCOMMAND: &str = "syscall";
+pub const CATCH_OFF_SUBCOMMAND: &str = "off";
+pub const CATCH_INFO_SUBCOMMAND: &str = "info";
+pub const SKIP_COMMAND: &str = "skip";
+pub const SKIP_FILE_SUBCOMMAND: &str = "file";
+pub const SKIP_FUNCTION_SUBCOMMAND: &str = "function";
+pub const SKIP_REMOVE_SUBCOMMAND: &str = "remove";
+pub const SKIP_INFO_SUBCOMMAND: &str = "info";
+pub const HISTORY_COMMAND: &str = "history";
+pub const HISTORY_CLEAR_SUBCOMMAND: &str = "clear";
+pub const HISTORY_STACK_SUBCOMMAND: &str = "stack";
+pub const HISTORY_LOG_SUBCOMMAND: &str = "log";
+pub const HISTORY_LOG_OFF_SUBCOMMAND: &str = "off";
+pub const CHECKPOINT_COMMAND: &str = "checkpoint";
+pub const CHECKPOINT_INFO_SUBCOMMAND: &str = "info";
+pub const CHECKPOINT_RESTART_SUBCOMMAND: &str = "restart";
+pub const CHECKPOINT_DELETE_SUBCOMMAND: &str = "delete";
+pub const ASYNC_COMMAND: &str = "async";
+pub const ASYNC_BACKTRACE_SUBCOMMAND: &str = "backtrace";
+pub const ASYNC_BACKTRACE_SUBCOMMAND_SHORT: &str = "bt";
+pub const ASYNC_FUTURE_SUBCOMMAND: &str = "future";
 pub const HELP_COMMAND: &str = "help";
 pub const HELP_COMMAND_SHORT: &str = "h";
 
@@ -102,6 +199,23 @@ pub fn brkpt_at_line_parser<'a>() -> impl chumsky::Parser<'a, &'a str, Breakpoin
         .padded()
 }
 
+/// Parse a function symbol with an offset from its start, like `main+16` or `main+0x10`.
+pub fn brkpt_at_symbol<'a>() -> impl chumsky::Parser<'a, &'a str, BreakpointIdentity, Err<'a>> {
+    let offset = hex()
+        .map(|offset| offset as u64)
+        .or(text::int(10).from_str().unwrapped());
+    any()
+        .filter(|c: &char| !c.is_whitespace() && *c != '+')
+        .repeated()
+        .at_least(1)
+        .to_slice()
+        .then_ignore(just('+'))
+        .then(offset)
+        .map(|(symbol, offset): (&str, u64)| BreakpointIdentity::Symbol(symbol.to_string(), offset))
+        .padded()
+        .labelled("symbol+offset")
+}
+
 pub fn brkpt_number<'a>() -> impl chumsky::Parser<'a, &'a str, BreakpointIdentity, Err<'a>> {
     text::int(10)
         .from_str()
@@ -117,6 +231,95 @@ pub fn brkpt_at_fn<'a>() -> impl chumsky::Parser<'a, &'a str, BreakpointIdentity
         .map(|fn_name: &str| BreakpointIdentity::Function(fn_name.trim().to_string()))
 }
 
+/// Parse a target place of `until` and `advance` commands: address, `file:line` or function name.
+pub fn target_place<'a>() -> impl chumsky::Parser<'a, &'a str, TargetPlace, Err<'a>> {
+    let function = any()
+        .repeated()
+        .at_least(1)
+        .to_slice()
+        .map(|fn_name: &str| TargetPlace::Function(fn_name.trim().to_string()));
+    choice((brkpt_at_addr_parser(), brkpt_at_line_parser()))
+        .map(|brkpt| match brkpt {
+            BreakpointIdentity::Address(addr) => TargetPlace::Address(addr.into()),
+            BreakpointIdentity::Line(file, line) => TargetPlace::Line(file, line),
+            _ => unreachable!(),
+        })
+        .or(function)
+}
+
+/// Parse breakpoint condition (expression) or `none` keyword.
+pub fn brkpt_condition<'a>(
+) -> impl chumsky::Parser<'a, &'a str, Option<BreakpointCondition>, Err<'a>> {
+    any()
+        .repeated()
+        .at_least(1)
+        .to_slice()
+        .try_map(|source: &str, span| {
+            let source = source.trim();
+            if source == BREAK_CONDITION_NONE {
+                return Ok(None);
+            }
+            let expr = expression::expr_parser()
+                .parse(source)
+                .into_result()
+                .map_err(|_| Rich::custom(span, format!("invalid condition: {source}")))?;
+            Ok(Some(BreakpointCondition {
+                source: source.to_string(),
+                expr,
+            }))
+        })
+        .labelled("breakpoint condition")
+}
+
+/// Return true if input has unclosed brackets (`(`, `[`, `{`) or an unterminated string literal,
+/// in this case input must be continued on the next line.
+pub fn is_incomplete(input: &str) -> bool {
+    let mut depth: isize = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for ch in input.chars() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match ch {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    in_string || depth > 0
+}
+
+/// Join lines of a multi-line commands, a line is joined with the next one if
+/// it has unclosed brackets or an unterminated string literal.
+pub fn join_continued_lines<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut commands = vec![];
+    let mut current: Option<String> = None;
+    for line in lines {
+        let command = match current.take() {
+            None => line.to_string(),
+            Some(prev) => format!("{prev}\n{line}"),
+        };
+        if is_incomplete(&command) {
+            current = Some(command);
+        } else {
+            commands.push(command);
+        }
+    }
+    commands.extend(current);
+    commands
+}
+
 fn command<'a, I>(ctx: &'static str, inner: I) -> Boxed<'a, 'a, &'a str, Command, Err<'a>>
 where
     I: chumsky::Parser<'a, &'a str, Command, Err<'a>> + 'a,
@@ -216,7 +419,47 @@ impl Command {
             .ignore_then(expression::parser())
             .map(Command::PrintVariables);
 
-        let print_variables = choice((print_local_vars, print_var)).boxed();
+        let export_format = choice((
+            just("csv").to(export::Format::Csv),
+            just("json").to(export::Format::Json),
+        ))
+        .labelled("export format (csv or json)");
+        let export_var = op_w_arg(VAR_COMMAND)
+            .ignore_then(
+                just(VAR_LOCAL_KEY)
+                    .then(ws_req)
+                    .to(DQE::Variable(VariableSelector::Any))
+                    .or(expression::expression()),
+            )
+            .then_ignore(just(VAR_EXPORT_OPTION).padded())
+            .then(
+                any()
+                    .filter(|c: &char| !c.is_whitespace())
+                    .repeated()
+                    .at_least(1)
+                    .to_slice(),
+            )
+            .then(
+                ws_req
+                    .ignore_then(just(VAR_FORMAT_OPTION))
+                    .ignore_then(ws_req)
+                    .ignore_then(export_format)
+                    .or_not(),
+            )
+            .then_ignore(whitespace())
+            .map(|((dqe, path), format): ((DQE, &str), _)| {
+                Command::ExportVariables(export::Command {
+                    dqe,
+                    path: PathBuf::from(path),
+                    format,
+                })
+            });
+
+        let print_expr = op_w_arg(VAR_COMMAND)
+            .ignore_then(expression::expr_parser())
+            .map(Command::PrintExpression);
+
+        let print_variables = choice((print_local_vars, export_var, print_var, print_expr)).boxed();
 
         let print_all_args = op_w_arg(ARG_COMMAND)
             .then(sub_op(ARG_ALL_KEY))
@@ -234,9 +477,37 @@ impl Command {
         let r#continue = op2(CONTINUE_COMMAND, CONTINUE_COMMAND_SHORT).to(Command::Continue);
         let run = op2(RUN_COMMAND, RUN_COMMAND_SHORT).to(Command::Run);
         let stepi = op(STEP_INSTRUCTION_COMMAND).to(Command::StepInstruction);
-        let step_into = op2(STEP_INTO_COMMAND, STEP_INTO_COMMAND_SHORT).to(Command::StepInto);
+        let step_into = op2_w_arg(STEP_INTO_COMMAND, STEP_INTO_COMMAND_SHORT)
+            .ignore_then(choice((
+                sub_op(STEP_INTO_TARGETS_SUBCOMMAND).to(step_into::Command::Targets),
+                sub_op_w_arg(STEP_INTO_TARGET_SUBCOMMAND)
+                    .ignore_then(any().repeated().at_least(1).to_slice())
+                    .map(|name: &str| step_into::Command::Target(name.trim().to_string())),
+            )))
+            .map(Command::StepIntoTarget)
+            .or(op2(STEP_INTO_COMMAND, STEP_INTO_COMMAND_SHORT).to(Command::StepInto))
+            .boxed();
         let step_out = op2(STEP_OUT_COMMAND, STEP_OUT_COMMAND_SHORT).to(Command::StepOut);
         let step_over = op2(STEP_OVER_COMMAND, STEP_OVER_COMMAND_SHORT).to(Command::StepOver);
+        let until = op_w_arg(UNTIL_COMMAND)
+            .ignore_then(target_place())
+            .map(|place| Command::Until(until::Command::Place(place)))
+            .or(op(UNTIL_COMMAND).to(Command::Until(until::Command::NextLine)))
+            .boxed();
+        let advance = op_w_arg(ADVANCE_COMMAND)
+            .ignore_then(target_place())
+            .map(|place| Command::Until(until::Command::Place(place)))
+            .boxed();
+        let jump = op_w_arg(JUMP_COMMAND)
+            .ignore_then(sub_op_w_arg(JUMP_FORCE_OPTION).or_not())
+            .then(target_place())
+            .map(|(force, place)| {
+                Command::Jump(jump::Command {
+                    place,
+                    force: force.is_some(),
+                })
+            })
+            .boxed();
 
         let source_code = op_w_arg(SOURCE_COMMAND)
             .ignore_then(choice((
@@ -277,6 +548,7 @@ impl Command {
             .map(|s| Command::PrintSymbol(s.trim().to_string()))
             .boxed();
 
+        let brkpt_num = text::int(10).from_str().unwrapped().padded();
         let r#break = op2_w_arg(BREAK_COMMAND, BREAK_COMMAND_SHORT)
             .ignore_then(choice((
                 sub_op2_w_arg(BREAK_REMOVE_SUBCOMMAND, BREAK_REMOVE_SUBCOMMAND_SHORT)
@@ -284,17 +556,85 @@ impl Command {
                         brkpt_at_addr_parser(),
                         brkpt_at_line_parser(),
                         brkpt_number(),
+                        brkpt_at_symbol(),
                         brkpt_at_fn(),
                     )))
                     .map(|brkpt| Command::Breakpoint(r#break::Command::Remove(brkpt))),
-                sub_op("info").to(Command::Breakpoint(r#break::Command::Info)),
-                choice((
-                    brkpt_at_addr_parser(),
-                    brkpt_at_line_parser(),
-                    brkpt_at_fn(),
-                ))
-                .map(|brkpt| Command::Breakpoint(r#break::Command::Add(brkpt))),
+                sub_op(BREAK_INFO_SUBCOMMAND).to(Command::Breakpoint(r#break::Command::Info)),
+                sub_op_w_arg(BREAK_ENABLE_SUBCOMMAND)
+                    .ignore_then(brkpt_num.clone())
+                    .map(|num| Command::Breakpoint(r#break::Command::Enable(num))),
+                sub_op_w_arg(BREAK_DISABLE_SUBCOMMAND)
+                    .ignore_then(brkpt_num.clone())
+                    .map(|num| Command::Breakpoint(r#break::Command::Disable(num))),
+                sub_op_w_arg(BREAK_CONDITION_SUBCOMMAND)
+                    .ignore_then(brkpt_num.clone().then(brkpt_condition()))
+                    .map(|(num, cond)| Command::Breakpoint(r#break::Command::Condition(num, cond))),
+                sub_op_w_arg(BREAK_IGNORE_SUBCOMMAND)
+                    .ignore_then(brkpt_num.clone().then(brkpt_num))
+                    .map(|(num, count)| Command::Breakpoint(r#break::Command::Ignore(num, count))),
+                sub_op_w_arg(BREAK_COMMANDS_SUBCOMMAND)
+                    .ignore_then(brkpt_num.clone().then(any().repeated().padded().to_slice()))
+                    .map(|(num, script): (u32, &str)| {
+                        let script = Some(script.trim().to_string()).filter(|s| !s.is_empty());
+                        Command::Breakpoint(r#break::Command::Commands(num, script))
+                    }),
+                sub_op_w_arg(BREAK_SAVE_SUBCOMMAND)
+                    .ignore_then(any().repeated().at_least(1).padded().to_slice())
+                    .map(|path: &str| {
+                        Command::Breakpoint(r#break::Command::Save(PathBuf::from(path.trim())))
+                    }),
+                sub_op_w_arg(BREAK_LOAD_SUBCOMMAND)
+                    .ignore_then(any().repeated().at_least(1).padded().to_slice())
+                    .map(|path: &str| {
+                        Command::Breakpoint(r#break::Command::Load(PathBuf::from(path.trim())))
+                    }),
+                just(BREAK_HW_OPTION)
+                    .padded()
+                    .or_not()
+                    .then(choice((
+                        brkpt_at_addr_parser(),
+                        brkpt_at_line_parser(),
+                        brkpt_at_symbol(),
+                        brkpt_at_fn(),
+                    )))
+                    .map(|(hw, brkpt)| {
+                        if hw.is_some() {
+                            Command::Breakpoint(r#break::Command::AddHardware(brkpt))
+                        } else {
+                            Command::Breakpoint(r#break::Command::Add(brkpt))
+                        }
+                    }),
+            )))
+            .boxed();
+
+        let watch_target = choice((
+            just('*')
+                .ignore_then(hex())
+                .map(watch::WatchpointIdentity::Address),
+            rust_identifier()
+                .map(|name: &str| watch::WatchpointIdentity::Variable(name.trim().to_string())),
+        ));
+        let watch = op_w_arg(WATCH_COMMAND)
+            .ignore_then(choice((
+                sub_op_w_arg(WATCH_REMOVE_SUBCOMMAND)
+                    .ignore_then(text::int(10).from_str().unwrapped().padded())
+                    .map(watch::Command::Remove),
+                sub_op(WATCH_INFO_SUBCOMMAND).to(watch::Command::Info),
+                just(WATCH_RW_OPTION)
+                    .padded()
+                    .or_not()
+                    .then(watch_target)
+                    .map(|(rw, target)| {
+                        let condition = if rw.is_some() {
+                            WatchCondition::ReadWrite
+                        } else {
+                            WatchCondition::Write
+                        };
+                        watch::Command::Add(target, condition)
+                    }),
             )))
+            .map(Command::Watchpoint)
             .boxed();
 
         let memory = op2_w_arg(MEMORY_COMMAND, MEMORY_COMMAND_SHORT)
@@ -308,6 +648,57 @@ impl Command {
             )))
             .boxed();
 
+        let examine_spec = just('/')
+            .ignore_then(text::int(10).from_str::<usize>().unwrapped().or_not())
+            .then(
+                any()
+                    .filter(|c: &char| c.is_ascii_alphabetic())
+                    .repeated()
+                    .to_slice()
+                    .try_map(|letters: &str, span| {
+                        let mut format = None;
+                        let mut size = None;
+                        for letter in letters.chars() {
+                            match (
+                                ExamineFormat::from_letter(letter),
+                                UnitSize::from_letter(letter),
+                            ) {
+                                (Some(f), _) if format.is_none() => format = Some(f),
+                                (_, Some(s)) if size.is_none() => size = Some(s),
+                                _ => {
+                                    return Err(Rich::custom(
+                                        span,
+                                        format!("invalid examine format: {letters}"),
+                                    ))
+                                }
+                            }
+                        }
+                        Ok((format, size))
+                    }),
+            )
+            .labelled("examine format");
+        let examine = whitespace()
+            .then(just(EXAMINE_COMMAND))
+            .ignore_then(examine_spec.or_not())
+            .then_ignore(ws_req)
+            .then(hex())
+            .map(|(spec, addr)| {
+                let (count, (format, size)) = spec.unwrap_or_default();
+                // char and float formats have their own default unit size
+                let size = size.unwrap_or(match format {
+                    Some(ExamineFormat::Char) => UnitSize::Byte,
+                    Some(ExamineFormat::Float) => UnitSize::Giant,
+                    _ => UnitSize::Word,
+                });
+                Command::Examine(examine::Command {
+                    addr,
+                    count: count.unwrap_or(1),
+                    format: format.unwrap_or_default(),
+                    size,
+                })
+            })
+            .boxed();
+
         let register = op2_w_arg(REGISTER_COMMAND, REGISTER_COMMAND_SHORT)
             .ignore_then(choice((
                 sub_op(REGISTER_COMMAND_INFO_SUBCOMMAND)
@@ -336,10 +727,16 @@ impl Command {
                 sub_op(THREAD_COMMAND_CURRENT_SUBCOMMAND)
                     .to(Command::Thread(thread::Command::Current)),
                 sub_op_w_arg(THREAD_COMMAND_SWITCH_SUBCOMMAND)
-                    .ignore_then(text::int(10))
-                    .from_str()
-                    .unwrapped()
-                    .map(|num| Command::Thread(thread::Command::Switch(num)))
+                    .ignore_then(choice((
+                        sub_op_w_arg(THREAD_COMMAND_TID_KEY)
+                            .ignore_then(text::int(10).from_str().unwrapped())
+                            .map(|tid| ThreadSelector::Tid(Pid::from_raw(tid))),
+                        text::int(10)
+                            .from_str()
+                            .unwrapped()
+                            .map(ThreadSelector::Number),
+                    )))
+                    .map(|thread| Command::Thread(thread::Command::Switch(thread)))
                     .padded(),
             )))
             .boxed();
@@ -359,15 +756,337 @@ impl Command {
             .to(Command::SharedLib)
             .boxed();
 
+        let info = op_w_arg(INFO_COMMAND)
+            .ignore_then(choice((
+                sub_op(INFO_FDS_SUBCOMMAND).to(info::Command::Fds),
+                sub_op(INFO_SIGNALS_SUBCOMMAND)
+                    .ignore_then(just(INFO_SIGNALS_HISTORY_OPTION).padded().or_not())
+                    .map(|history| info::Command::Signals {
+                        history: history.is_some(),
+                    }),
+                sub_op_w_arg(INFO_FUNCTIONS_SUBCOMMAND)
+                    .ignore_then(any().repeated().at_least(1).padded().to_slice())
+                    .map(|regex: &str| info::Command::Functions(regex.trim().to_string())),
+            )))
+            .map(Command::Info)
+            .boxed();
+
+        let coverage_target = || any().repeated().at_least(1).padded().to_slice();
+        let coverage = op(COVERAGE_COMMAND)
+            .ignore_then(
+                choice((
+                    sub_op_w_arg(COVERAGE_FILE_SUBCOMMAND)
+                        .ignore_then(coverage_target())
+                        .map(|tpl: &str| {
+                            coverage::Command::Track(CoverageTarget::File(tpl.trim().to_string()))
+                        }),
+                    sub_op_w_arg(COVERAGE_FN_SUBCOMMAND)
+                        .ignore_then(coverage_target())
+                        .map(|tpl: &str| {
+                            coverage::Command::Track(CoverageTarget::Function(
+                                tpl.trim().to_string(),
+                            ))
+                        }),
+                    sub_op(COVERAGE_INFO_SUBCOMMAND).to(coverage::Command::Info),
+                    sub_op(COVERAGE_CLEAR_SUBCOMMAND).to(coverage::Command::Clear),
+                ))
+                .or_not(),
+            )
+            .map(|cmd| Command::Coverage(cmd.unwrap_or(coverage::Command::Info)))
+            .boxed();
+
         let oracle = op_w_arg(ORACLE_COMMAND)
-            .ignore_then(text::ident().padded().then(text::ident().or_not()))
-            .map(|(name, subcmd)| {
-                Command::Oracle(name.trim().to_string(), subcmd.map(ToString::to_string))
+            .ignore_then(
+                text::ident()
+                    .padded()
+                    .then(any().repeated().at_least(1).to_slice().or_not()),
+            )
+            .map(|(name, subcmd): (&str, Option<&str>)| {
+                Command::Oracle(
+                    name.trim().to_string(),
+                    subcmd.map(|subcmd| subcmd.trim().to_string()),
+                )
             })
             .padded()
             .boxed();
 
-        choice((
+        let script = op_w_arg(SCRIPT_COMMAND)
+            .ignore_then(any().repeated().at_least(1).padded().to_slice())
+            .map(|path: &str| Command::Script(path.trim().to_string()))
+            .boxed();
+
+        let style_element = text::ident()
+            .try_map(|name: &str, span| {
+                StyleElement::from_str(name)
+                    .map_err(|_| Rich::custom(span, format!("unknown style element: {name}")))
+            })
+            .labelled("style element")
+            .padded();
+        let color = any()
+            .filter(|c: &char| !c.is_whitespace())
+            .repeated()
+            .at_least(1)
+            .to_slice()
+            .try_map(|name: &str, span| {
+                style::parse_color(name)
+                    .ok_or_else(|| Rich::custom(span, format!("unknown color: {name}")))
+            })
+            .labelled("color")
+            .padded();
+        let set_style = sub_op_w_arg(SET_STYLE_SUBCOMMAND)
+            .ignore_then(style_element.then(color))
+            .map(|(element, color)| set::Command::Style(element, color));
+        let set_logging = sub_op_w_arg(SET_LOGGING_SUBCOMMAND).ignore_then(choice((
+            sub_op_w_arg(SET_LOGGING_ON)
+                .ignore_then(any().repeated().at_least(1).padded().to_slice())
+                .map(|path: &str| set::Command::Logging(Some(PathBuf::from(path.trim())))),
+            sub_op(SET_LOGGING_OFF).to(set::Command::Logging(None)),
+        )));
+        let set_variable = sub_op_w_arg(SET_VARIABLE_SUBCOMMAND)
+            .or(sub_op_w_arg(SET_VARIABLE_SUBCOMMAND_SHORT))
+            .ignore_then(rust_identifier())
+            .then_ignore(just("=").padded())
+            .then(expression::literal().padded())
+            .map(|(name, value)| set::Command::Variable(name.to_string(), value));
+        let set_non_stop = sub_op_w_arg(SET_NON_STOP_SUBCOMMAND).ignore_then(choice((
+            sub_op(SET_NON_STOP_ON).to(set::Command::NonStop(true)),
+            sub_op(SET_NON_STOP_OFF).to(set::Command::NonStop(false)),
+        )));
+        let path = any()
+            .filter(|c: &char| !c.is_whitespace())
+            .repeated()
+            .at_least(1)
+            .to_slice()
+            .map(PathBuf::from)
+            .padded();
+        let set_substitute_path = sub_op_w_arg(SET_SUBSTITUTE_PATH_SUBCOMMAND)
+            .ignore_then(path.clone().then(path))
+            .map(|(from, to)| set::Command::SubstitutePath(from, to));
+        let set_demangle = sub_op_w_arg(SET_DEMANGLE_SUBCOMMAND)
+            .ignore_then(
+                text::ascii::ident()
+                    .try_map(|style: &str, span| {
+                        SymbolNameStyle::from_str(style).map_err(|e| Rich::custom(span, e))
+                    })
+                    .labelled("demangled, raw or both")
+                    .padded(),
+            )
+            .map(set::Command::Demangle);
+        // arguments are separated by whitespaces, quoted arguments may contain whitespaces
+        let program_arg = choice((
+            any()
+                .filter(|c: &char| *c != '"')
+                .repeated()
+                .to_slice()
+                .delimited_by(just('"'), just('"')),
+            any()
+                .filter(|c: &char| !c.is_whitespace())
+                .repeated()
+                .at_least(1)
+                .to_slice(),
+        ))
+        .map(ToString::to_string);
+        let set_args = sub_op(SET_ARGS_SUBCOMMAND)
+            .ignore_then(program_arg.padded().repeated().collect::<Vec<_>>())
+            .map(set::Command::Args);
+        let env_name = any()
+            .filter(|c: &char| !c.is_whitespace() && *c != '=')
+            .repeated()
+            .at_least(1)
+            .to_slice()
+            .map(ToString::to_string)
+            .labelled("variable name");
+        let set_env = sub_op_w_arg(SET_ENV_SUBCOMMAND).ignore_then(choice((
+            just(SET_ENV_UNSET_OPTION)
+                .padded()
+                .ignore_then(env_name.clone().padded())
+                .map(|name| set::Command::Env(name, None)),
+            env_name
+                .then_ignore(just('='))
+                .then(any().repeated().to_slice())
+                .map(|(name, value): (String, &str)| {
+                    set::Command::Env(name, Some(value.trim().to_string()))
+                }),
+        )));
+        let set = op_w_arg(SET_COMMAND)
+            .ignore_then(choice((
+                set_style,
+                set_logging,
+                set_variable,
+                set_non_stop,
+                set_substitute_path,
+                set_demangle,
+                set_args,
+                set_env,
+            )))
+            .map(Command::Set)
+            .boxed();
+
+        let call = op_w_arg(CALL_COMMAND)
+            .ignore_then(rust_identifier())
+            .then(
+                expression::literal()
+                    .padded()
+                    .separated_by(just(","))
+                    .collect::<Vec<_>>()
+                    .delimited_by(just("("), just(")"))
+                    .padded(),
+            )
+            .map(|(name, args)| Command::Call(name.to_string(), args))
+            .boxed();
+
+        let r#return = op_w_arg(RETURN_COMMAND)
+            .ignore_then(expression::literal().padded())
+            .map(|value| Command::Return(Some(value)))
+            .or(op(RETURN_COMMAND).to(Command::Return(None)))
+            .boxed();
+
+        let record = choice((
+            op(RECORD_COMMAND)
+                .ignore_then(
+                    choice((
+                        sub_op(RECORD_START_SUBCOMMAND).to(record::Command::Start),
+                        sub_op(RECORD_STOP_SUBCOMMAND).to(record::Command::Stop),
+                        sub_op(RECORD_INFO_SUBCOMMAND).to(record::Command::Info),
+                    ))
+                    .or_not(),
+                )
+                .map(|cmd| cmd.unwrap_or(record::Command::Start)),
+            op2(REVERSE_STEP_COMMAND, REVERSE_STEP_COMMAND_SHORT).to(record::Command::ReverseStep),
+            op2(REVERSE_CONTINUE_COMMAND, REVERSE_CONTINUE_COMMAND_SHORT)
+                .to(record::Command::ReverseContinue),
+        ))
+        .map(Command::Record)
+        .boxed();
+
+        let disasm_range = hex()
+            .then(choice((
+                hex().map(|end| (end, false)),
+                just('+')
+                    .ignore_then(text::int(10).from_str::<usize>().unwrapped())
+                    .padded()
+                    .map(|len| (len, true)),
+            )))
+            .map(|(start, (end_or_len, is_len))| {
+                let end = if is_len {
+                    start.saturating_add(end_or_len)
+                } else {
+                    end_or_len
+                };
+                DisasmTarget::Range(start.into(), end.into())
+            });
+        let disasm = op(DISASM_COMMAND)
+            .ignore_then(disasm_range.or_not())
+            .map(|range| Command::Disassemble(range.unwrap_or(DisasmTarget::CurrentFunction)))
+            .boxed();
+
+        let syscall = any()
+            .filter(|c: &char| c.is_ascii_alphanumeric() || *c == '_')
+            .repeated()
+            .at_least(1)
+            .to_slice()
+            .map(ToString::to_string)
+            .labelled("syscall name or number");
+        let catch = op_w_arg(CATCH_COMMAND)
+            .ignore_then(choice((
+                sub_op(CATCH_SYSCALL_SUBCOMMAND)
+                    .ignore_then(
+                        syscall
+                            .separated_by(whitespace().at_least(1))
+                            .allow_trailing()
+                            .collect::<Vec<_>>(),
+                    )
+                    .map(catch::Command::Syscall),
+                sub_op(CATCH_OFF_SUBCOMMAND).to(catch::Command::Off),
+                sub_op(CATCH_INFO_SUBCOMMAND).to(catch::Command::Info),
+            )))
+            .map(Command::Catch)
+            .boxed();
+
+        // pattern may be quoted, like `skip file "*/libcore/*"`
+        let skip_pattern = || {
+            any()
+                .repeated()
+                .at_least(1)
+                .to_slice()
+                .map(|pattern: &str| pattern.trim().trim_matches('"').to_string())
+        };
+        let skip = op_w_arg(SKIP_COMMAND)
+            .ignore_then(choice((
+                sub_op_w_arg(SKIP_FILE_SUBCOMMAND)
+                    .ignore_then(skip_pattern())
+                    .map(skip::Command::File),
+                sub_op_w_arg(SKIP_FUNCTION_SUBCOMMAND)
+                    .ignore_then(skip_pattern())
+                    .map(skip::Command::Function),
+                sub_op_w_arg(SKIP_REMOVE_SUBCOMMAND)
+                    .ignore_then(text::int(10).from_str().unwrapped().padded())
+                    .map(skip::Command::Remove),
+                sub_op(SKIP_INFO_SUBCOMMAND).to(skip::Command::Info),
+            )))
+            .map(Command::Skip)
+            .boxed();
+
+        let history = op(HISTORY_COMMAND)
+            .ignore_then(
+                choice((
+                    sub_op(HISTORY_CLEAR_SUBCOMMAND).to(history::Command::Clear),
+                    sub_op_w_arg(HISTORY_STACK_SUBCOMMAND)
+                        .ignore_then(text::int(10).from_str().unwrapped().padded())
+                        .map(history::Command::Stack),
+                    sub_op_w_arg(HISTORY_LOG_SUBCOMMAND)
+                        .ignore_then(choice((
+                            sub_op(HISTORY_LOG_OFF_SUBCOMMAND).to(None),
+                            any()
+                                .repeated()
+                                .at_least(1)
+                                .to_slice()
+                                .map(|path: &str| Some(path.trim().to_string())),
+                        )))
+                        .map(history::Command::Log),
+                ))
+                .or_not(),
+            )
+            .map(|cmd| Command::History(cmd.unwrap_or(history::Command::Info)))
+            .boxed();
+
+        let checkpoint_id = || {
+            text::int(10)
+                .from_str()
+                .unwrapped()
+                .padded()
+                .labelled("checkpoint id")
+        };
+        let checkpoint = op(CHECKPOINT_COMMAND)
+            .ignore_then(
+                choice((
+                    sub_op(CHECKPOINT_INFO_SUBCOMMAND).to(checkpoint::Command::Info),
+                    sub_op_w_arg(CHECKPOINT_RESTART_SUBCOMMAND)
+                        .ignore_then(checkpoint_id())
+                        .map(checkpoint::Command::Restart),
+                    sub_op_w_arg(CHECKPOINT_DELETE_SUBCOMMAND)
+                        .ignore_then(checkpoint_id())
+                        .map(checkpoint::Command::Delete),
+                ))
+                .or_not(),
+            )
+            .map(|cmd| Command::Checkpoint(cmd.unwrap_or(checkpoint::Command::Create)))
+            .boxed();
+
+        let r#async = op_w_arg(ASYNC_COMMAND)
+            .ignore_then(choice((
+                sub_op(ASYNC_BACKTRACE_SUBCOMMAND)
+                    .or(sub_op(ASYNC_BACKTRACE_SUBCOMMAND_SHORT))
+                    .to(r#async::Command::Backtrace),
+                sub_op_w_arg(ASYNC_FUTURE_SUBCOMMAND)
+                    .ignore_then(expression::parser())
+                    .map(r#async::Command::Future),
+            )))
+            .map(Command::Async)
+            .boxed();
+
+        // commands are split into groups because of the limit of alternatives in a single choice
+        let execution_and_data = choice((
             command(VAR_COMMAND, print_variables),
             command(ARG_COMMAND, print_arguments),
             command(CONTINUE_COMMAND, r#continue),
@@ -376,27 +1095,91 @@ impl Command {
             command(STEP_INTO_COMMAND, step_into),
             command(STEP_OUT_COMMAND, step_out),
             command(STEP_OVER_COMMAND, step_over),
+            command(UNTIL_COMMAND, until),
+            command(ADVANCE_COMMAND, advance),
+            command(JUMP_COMMAND, jump),
             command(SOURCE_COMMAND, source_code),
+            command(DISASM_COMMAND, disasm),
+            command(CHECKPOINT_COMMAND, checkpoint),
+        ));
+        let other = choice((
             command(HELP_COMMAND, help),
             command(BACKTRACE_COMMAND, backtrace),
             command(SYMBOL_COMMAND, symbol),
             command(BREAK_COMMAND, r#break),
+            command(WATCH_COMMAND, watch),
             command(MEMORY_COMMAND, memory),
+            command(EXAMINE_COMMAND, examine),
             command(REGISTER_COMMAND, register),
             command(THREAD_COMMAND, thread),
             command(FRAME_COMMAND, frame),
             command(SHARED_LIB_COMMAND, shared_lib),
+            command(INFO_COMMAND, info),
+            command(COVERAGE_COMMAND, coverage),
             command(ORACLE_COMMAND, oracle),
-        ))
+            command(SCRIPT_COMMAND, script),
+            command(SET_COMMAND, set),
+            command(CALL_COMMAND, call),
+            command(RETURN_COMMAND, r#return),
+            command(RECORD_COMMAND, record),
+            command(CATCH_COMMAND, catch),
+            command(SKIP_COMMAND, skip),
+            command(HISTORY_COMMAND, history),
+            command(ASYNC_COMMAND, r#async),
+        ));
+
+        choice((execution_and_data, other))
     }
 
     /// Parse input string into command.
     pub fn parse(input: &str) -> CommandResult<Command> {
+        let input = compat::translate(input);
         Self::parser()
-            .parse(input)
+            .parse(input.as_ref())
             .into_result()
-            .map_err(|e| CommandError::Parsing(Self::render_errors(input, e)))
+            .map_err(|e| CommandError::Parsing(Self::render_errors(input.as_ref(), e)))
+    }
+}
+
+#[test]
+fn test_incomplete_input() {
+    struct TestCase {
+        input: &'static str,
+        incomplete: bool,
+    }
+    let cases = vec![
+        TestCase {
+            input: "var vec",
+            incomplete: false,
+        },
+        TestCase {
+            input: "break condition 1 map[{field_1: 1,",
+            incomplete: true,
+        },
+        TestCase {
+            input: "break condition 1 map[{field_1: 1, field_2: 2}]",
+            incomplete: false,
+        },
+        TestCase {
+            input: "var map[\"key (",
+            incomplete: true,
+        },
+        TestCase {
+            input: "var map[\"key \\\" (\"]",
+            incomplete: false,
+        },
+        TestCase {
+            input: "var arr)",
+            incomplete: false,
+        },
+    ];
+
+    for case in cases {
+        assert_eq!(is_incomplete(case.input), case.incomplete, "{}", case.input);
     }
+
+    let commands = join_continued_lines(["var map[{", "a: 1", "}]", "continue", "var (a"]);
+    assert_eq!(commands, vec!["var map[{\na: 1\n}]", "continue", "var (a"]);
 }
 
 #[test]
@@ -471,6 +1254,9 @@ fn test_rust_identifier_parser() {
 
 #[test]
 fn test_parser() {
+    use crate::debugger::variable::expr::{BinaryOp, Expr};
+    use crate::debugger::variable::select::Literal;
+
     struct TestCase {
         inputs: Vec<&'static str>,
         command_matcher: fn(result: Result<Command, CommandError>),
@@ -503,6 +1289,24 @@ fn test_parser() {
                 ));
             },
         },
+        TestCase {
+            inputs: vec!["var a + b * 2", "var  (u8)x == 1"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::PrintExpression(Expr::Binary(..))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["var &x"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::PrintExpression(Expr::AddressOf(_))
+                ));
+            },
+        },
         TestCase {
             inputs: vec!["var ("],
             command_matcher: |result| assert!(result.is_err()),
@@ -618,6 +1422,28 @@ fn test_parser() {
                 assert!(matches!(result.unwrap(), Command::StepInto));
             },
         },
+        TestCase {
+            inputs: vec![
+                "step into sum2",
+                "stepinto into  sum2 ",
+                " step into   sum2",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::StepIntoTarget(step_into::Command::Target(name)) if name == "sum2"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["step targets", "stepinto targets", " step  targets "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::StepIntoTarget(step_into::Command::Targets)
+                ));
+            },
+        },
         TestCase {
             inputs: vec!["finish", "stepout"],
             command_matcher: |result| {
@@ -631,188 +1457,484 @@ fn test_parser() {
             },
         },
         TestCase {
-            inputs: vec!["b some_func", "break some_func", "   break some_func   "],
+            inputs: vec!["until", "  until  "],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Breakpoint(r#break::Command::Add(BreakpointIdentity::Function(f))) if f == "some_func"
+                    Command::Until(until::Command::NextLine)
                 ));
             },
         },
         TestCase {
-            inputs: vec!["b rust_fn", "b info_rust"],
+            inputs: vec![
+                "until main.rs:15",
+                "advance main.rs:15",
+                " advance  main.rs:15 ",
+            ],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Breakpoint(r#break::Command::Add(BreakpointIdentity::Function(f))) if f == "rust_fn" || f == "info_rust"
+                    Command::Until(until::Command::Place(TargetPlace::Line(file, 15))) if file == "main.rs"
                 ));
             },
         },
         TestCase {
-            inputs: vec!["b file:123", "break file:123", "   break file:123   "],
+            inputs: vec!["until 0x100", "advance 0x100"],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Breakpoint(r#break::Command::Add(BreakpointIdentity::Line(f, n))) if f == "file" && n == 123
+                    Command::Until(until::Command::Place(TargetPlace::Address(addr))) if addr.as_usize() == 0x100
                 ));
             },
         },
         TestCase {
-            inputs: vec!["b 0x123", "break 0x123", "   break 0x0123   "],
+            inputs: vec!["advance calc::sum", "until calc::sum "],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Breakpoint(r#break::Command::Add(BreakpointIdentity::Address(a))) if a == 0x123
+                    Command::Until(until::Command::Place(TargetPlace::Function(f))) if f == "calc::sum"
                 ));
             },
         },
         TestCase {
-            inputs: vec![
-                "b r some_func",
-                "break r some_func",
-                "   break r  some_func   ",
-            ],
+            inputs: vec!["jump main.rs:15", " jump  main.rs:15 "],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Breakpoint(r#break::Command::Remove(BreakpointIdentity::Function(f))) if f == "some_func"
+                    Command::Jump(jump::Command { place: TargetPlace::Line(file, 15), force: false }) if file == "main.rs"
                 ));
             },
         },
         TestCase {
-            inputs: vec![
-                "b r ns1::some_func",
-                "break r ns1::some_func",
-                "   break r  ns1::some_func   ",
-            ],
+            inputs: vec!["jump --force 0x100"],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Breakpoint(r#break::Command::Remove(BreakpointIdentity::Function(f))) if f == "ns1::some_func"
+                    Command::Jump(jump::Command { place: TargetPlace::Address(addr), force: true }) if addr.as_usize() == 0x100
                 ));
             },
         },
         TestCase {
-            inputs: vec![
-                "b remove file:123",
-                "break r file:123",
-                "   break  remove file:123   ",
-            ],
+            inputs: vec!["return", " return "],
+            command_matcher: |result| {
+                assert!(matches!(result.unwrap(), Command::Return(None)));
+            },
+        },
+        TestCase {
+            inputs: vec!["return 5", " return  5 "],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Breakpoint(r#break::Command::Remove(BreakpointIdentity::Line(f, n))) if f == "file" && n == 123
+                    Command::Return(Some(Literal::Int(5)))
                 ));
             },
         },
         TestCase {
-            inputs: vec!["b remove 0x123", "break r 0x123", "   break r 0x123   "],
+            inputs: vec!["b some_func", "break some_func", "   break some_func   "],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Breakpoint(r#break::Command::Remove(BreakpointIdentity::Address(a))) if a == 0x123
+                    Command::Breakpoint(r#break::Command::Add(BreakpointIdentity::Function(f))) if f == "some_func"
                 ));
             },
         },
         TestCase {
-            inputs: vec!["b info", "break info ", "   break   info   "],
+            inputs: vec!["b rust_fn", "b info_rust"],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Breakpoint(r#break::Command::Info)
+                    Command::Breakpoint(r#break::Command::Add(BreakpointIdentity::Function(f))) if f == "rust_fn" || f == "info_rust"
                 ));
             },
         },
         TestCase {
-            inputs: vec![
-                "mem read 0x123",
-                "memory read 0x123",
-                "   mem read   0x123   ",
-            ],
+            inputs: vec!["b file:123", "break file:123", "   break file:123   "],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Memory(memory::Command::Read(a)) if a == 0x123
+                    Command::Breakpoint(r#break::Command::Add(BreakpointIdentity::Line(f, n))) if f == "file" && n == 123
                 ));
             },
         },
         TestCase {
-            inputs: vec![
-                "mem write 0x123 0x321",
-                "memory write 0x123 0x321",
-                "   mem write   0x123  0x321 ",
-            ],
+            inputs: vec!["b 0x123", "break 0x123", "   break 0x0123   "],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Memory(memory::Command::Write(a, v)) if a == 0x123 && v == 0x321
+                    Command::Breakpoint(r#break::Command::Add(BreakpointIdentity::Address(a))) if a == 0x123
                 ));
             },
         },
         TestCase {
-            inputs: vec!["reg info", "register info", "   reg  info "],
+            inputs: vec!["b main+16", "break main+0x10", "   break  main+16  "],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Register(register::Command::Info)
+                    Command::Breakpoint(r#break::Command::Add(BreakpointIdentity::Symbol(s, 16))) if s == "main"
                 ));
             },
         },
         TestCase {
-            inputs: vec!["reg read rip", "register read rip", "   reg  read   rip "],
+            inputs: vec!["b --hw file:123", "break  --hw  file:123 "],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Register(register::Command::Read(r)) if r == "rip"
+                    Command::Breakpoint(r#break::Command::AddHardware(BreakpointIdentity::Line(f, n))) if f == "file" && n == 123
                 ));
             },
         },
         TestCase {
-            inputs: vec![
-                "reg write rip 0x123",
-                "register write rip 0x123",
-                "   reg  write  rip  0x123 ",
-            ],
+            inputs: vec!["b --hw 0x123", "break --hw some_func"],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Register(register::Command::Write(r, v)) if r == "rip" && v == 0x123
+                    Command::Breakpoint(r#break::Command::AddHardware(
+                        BreakpointIdentity::Address(0x123) | BreakpointIdentity::Function(_)
+                    ))
                 ));
             },
         },
         TestCase {
-            inputs: vec!["thread info", "thread    info  "],
+            inputs: vec!["b r calc::sum2+4", "break remove calc::sum2+0x4"],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Thread(thread::Command::Info)
+                    Command::Breakpoint(r#break::Command::Remove(BreakpointIdentity::Symbol(s, 4))) if s == "calc::sum2"
                 ));
             },
         },
         TestCase {
-            inputs: vec!["thread current", "thread    current  "],
+            inputs: vec![
+                "b r some_func",
+                "break r some_func",
+                "   break r  some_func   ",
+            ],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Thread(thread::Command::Current)
+                    Command::Breakpoint(r#break::Command::Remove(BreakpointIdentity::Function(f))) if f == "some_func"
                 ));
             },
         },
         TestCase {
-            inputs: vec!["thread switch 1", " thread  switch 1  "],
+            inputs: vec![
+                "b r ns1::some_func",
+                "break r ns1::some_func",
+                "   break r  ns1::some_func   ",
+            ],
             command_matcher: |result| {
                 assert!(matches!(
                     result.unwrap(),
-                    Command::Thread(thread::Command::Switch(1))
+                    Command::Breakpoint(r#break::Command::Remove(BreakpointIdentity::Function(f))) if f == "ns1::some_func"
                 ));
             },
         },
+        TestCase {
+            inputs: vec![
+                "b remove file:123",
+                "break r file:123",
+                "   break  remove file:123   ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Remove(BreakpointIdentity::Line(f, n))) if f == "file" && n == 123
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["b remove 0x123", "break r 0x123", "   break r 0x123   "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Remove(BreakpointIdentity::Address(a))) if a == 0x123
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["b info", "break info ", "   break   info   "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Info)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec![
+                "mem read 0x123",
+                "memory read 0x123",
+                "   mem read   0x123   ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Memory(memory::Command::Read(a)) if a == 0x123
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec![
+                "mem write 0x123 0x321",
+                "memory write 0x123 0x321",
+                "   mem write   0x123  0x321 ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Memory(memory::Command::Write(a, v)) if a == 0x123 && v == 0x321
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["x 0x100", "  x   0x100 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Examine(examine::Command {
+                        addr: 0x100,
+                        count: 1,
+                        format: ExamineFormat::Hex,
+                        size: UnitSize::Word,
+                    })
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["x/16xb 0x100", "x/16bx 0x100", " x/16xb  0x100 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Examine(examine::Command {
+                        addr: 0x100,
+                        count: 16,
+                        format: ExamineFormat::Hex,
+                        size: UnitSize::Byte,
+                    })
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["x/4dg 0x100"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Examine(examine::Command {
+                        count: 4,
+                        format: ExamineFormat::Decimal,
+                        size: UnitSize::Giant,
+                        ..
+                    })
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["x/c 0x100"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Examine(examine::Command {
+                        count: 1,
+                        format: ExamineFormat::Char,
+                        size: UnitSize::Byte,
+                        ..
+                    })
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["x/4xx 0x100", "x/4z 0x100", "x/4xb", "x/4xb 100"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["reg info", "register info", "   reg  info "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Register(register::Command::Info)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["reg read rip", "register read rip", "   reg  read   rip "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Register(register::Command::Read(r)) if r == "rip"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec![
+                "reg write rip 0x123",
+                "register write rip 0x123",
+                "   reg  write  rip  0x123 ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Register(register::Command::Write(r, v)) if r == "rip" && v == 0x123
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["thread info", "thread    info  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Thread(thread::Command::Info)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["thread current", "thread    current  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Thread(thread::Command::Current)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["thread switch 1", " thread  switch 1  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Thread(thread::Command::Switch(ThreadSelector::Number(1)))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["thread switch tid 4242", " thread  switch  tid  4242 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Thread(thread::Command::Switch(ThreadSelector::Tid(tid))) if tid == Pid::from_raw(4242)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["thread switch tid", "thread switch tid abc"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
         TestCase {
             inputs: vec!["sharedlib info", " sharedlib     info  "],
             command_matcher: |result| {
                 assert!(matches!(result.unwrap(), Command::SharedLib));
             },
         },
+        TestCase {
+            inputs: vec!["watch my_var", " watch   my_var  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Watchpoint(watch::Command::Add(watch::WatchpointIdentity::Variable(var), WatchCondition::Write)) if var == "my_var"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["watch --rw *0xDEADBEEF", " watch  --rw  *0xdeadbeef "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Watchpoint(watch::Command::Add(
+                        watch::WatchpointIdentity::Address(0xDEADBEEF),
+                        WatchCondition::ReadWrite
+                    ))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["watch remove 2", " watch  remove  2 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Watchpoint(watch::Command::Remove(2))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["watch info", " watch  info "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Watchpoint(watch::Command::Info)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["info fds", " info     fds  "],
+            command_matcher: |result| {
+                assert!(matches!(result.unwrap(), Command::Info(info::Command::Fds)));
+            },
+        },
+        TestCase {
+            inputs: vec!["info signals", " info  signals "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Info(info::Command::Signals { history: false })
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["info signals --history", " info  signals   --history "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Info(info::Command::Signals { history: true })
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["info functions ^main$", " info  functions   ^main$ "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Info(info::Command::Functions(regex)) if regex == "^main$"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["info functions", "info functions  "],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["coverage", " coverage  info "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Coverage(coverage::Command::Info)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["coverage file main.rs", " coverage  file   main.rs "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Coverage(coverage::Command::Track(CoverageTarget::File(file))) if file == "main.rs"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["coverage fn ns::func", " coverage  fn   ns::func  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Coverage(coverage::Command::Track(CoverageTarget::Function(f))) if f == "ns::func"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["coverage clear"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Coverage(coverage::Command::Clear)
+                ));
+            },
+        },
         TestCase {
             inputs: vec!["source asm", " source   asm  "],
             command_matcher: |result| {
@@ -858,6 +1980,697 @@ fn test_parser() {
                 ));
             },
         },
+        TestCase {
+            inputs: vec![
+                "oracle syscall filter openat read",
+                " oracle  syscall filter openat read  ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Oracle(name, Some(subcmd)) if name == "syscall" && subcmd == "filter openat read"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["break enable 1", " b  enable  1 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Enable(1))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["break disable 2", " b  disable  2 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Disable(2))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["break condition 1 a.b", " b  condition  1  a.b "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Condition(1, Some(cond))) if cond.source == "a.b"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["break condition 1 i > 10"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Condition(1, Some(cond)))
+                        if matches!(cond.expr, Expr::Binary(BinaryOp::Gt, ..))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["break condition 1 none"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Condition(1, None))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["break ignore 3 5", " b  ignore  3  5 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Ignore(3, 5))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec![
+                "break commands 2 print(var(\"x\")); cont()",
+                " b  commands  2   print(var(\"x\")); cont()  ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Commands(2, Some(script)))
+                        if script == "print(var(\"x\")); cont()"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["break commands 2", "b commands 2 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Commands(2, None))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec![
+                "var buf --export ./buf.csv",
+                " var  buf   --export  ./buf.csv  ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::ExportVariables(export::Command { dqe: DQE::Variable(VariableSelector::Name { var_name, .. }), path, format: None })
+                        if var_name == "buf" && path == PathBuf::from("./buf.csv")
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["var a.b[1..3] --export data --format json"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::ExportVariables(export::Command { dqe: DQE::Slice(..), path, format: Some(export::Format::Json) })
+                        if path == PathBuf::from("data")
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["var locals --export locals.json"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::ExportVariables(export::Command {
+                        dqe: DQE::Variable(VariableSelector::Any),
+                        format: None,
+                        ..
+                    })
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["var a --export", "var a --export f --format xml"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["break save ./brkpts.gdb", " b  save   ./brkpts.gdb "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Save(path)) if path == PathBuf::from("./brkpts.gdb")
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["break load ./.gdbinit", " b  load   ./.gdbinit "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Load(path)) if path == PathBuf::from("./.gdbinit")
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["break enable", "break condition 1"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["script ./inspect.rhai", " script   ./inspect.rhai  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Script(path) if path == "./inspect.rhai"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["script", "script  "],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec![
+                "set style address dark_red",
+                " set  style  address   dark_red ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Style(
+                        StyleElement::Address,
+                        Some(crossterm::style::Color::DarkRed)
+                    ))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set style panel none"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Style(StyleElement::Panel, None))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set style panel #00ff00"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Style(
+                        StyleElement::Panel,
+                        Some(crossterm::style::Color::Rgb { r: 0, g: 255, b: 0 })
+                    ))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec![
+                "set style unknown red",
+                "set style address unknown",
+                "set style address",
+            ],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec![
+                "set logging on ./session.log",
+                " set  logging  on   ./session.log ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Logging(Some(path))) if path == PathBuf::from("./session.log")
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set logging off", " set  logging  off "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Logging(None))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set var x = 5", " set  variable  x=5 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Variable(name, Literal::Int(5))) if name == "x"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set var e = MyEnum::B"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Variable(name, Literal::EnumVariant(variant, None)))
+                        if name == "e" && variant == "MyEnum::B"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set var x", "set var x = "],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["set logging", "set logging on", "set logging off file"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["set non-stop on", " set  non-stop  on "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::NonStop(true))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set non-stop off"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::NonStop(false))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec![
+                "set substitute-path /build/src ./src",
+                " set  substitute-path  /build/src   ./src ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::SubstitutePath(from, to))
+                        if from == PathBuf::from("/build/src") && to == PathBuf::from("./src")
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set substitute-path", "set substitute-path /build/src"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["set demangle raw", "  set  demangle   raw  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Demangle(SymbolNameStyle::Raw))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set demangle both"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Demangle(SymbolNameStyle::Both))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set args 1 2 \"a b\"", " set  args  1  2  \"a b\" "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Args(args)) if args == ["1", "2", "a b"]
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set args", "set args "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Args(args)) if args.is_empty()
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set env FOO=bar baz", " set  env  FOO=bar baz "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Env(name, Some(value))) if name == "FOO" && value == "bar baz"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set env FOO="],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Env(name, Some(value))) if name == "FOO" && value.is_empty()
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set env --unset FOO", "set env  --unset  FOO "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Set(set::Command::Env(name, None)) if name == "FOO"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["set env", "set env FOO", "set env =bar"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["set demangle", "set demangle mangled"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["set non-stop", "set non-stop yes"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["call sum(1, 2)", " call  sum ( 1,2 ) "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Call(name, args) if name == "sum" && matches!(args[..], [Literal::Int(1), Literal::Int(2)])
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["call ns::reset()"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Call(name, args) if name == "ns::reset" && args.is_empty()
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["call", "call sum", "call sum(1, 2"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["record", " record start "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Record(record::Command::Start)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["record info"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Record(record::Command::Info)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["reverse-step", "rs"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Record(record::Command::ReverseStep)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["reverse-continue", " rc "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Record(record::Command::ReverseContinue)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["record foo", "rs 1"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["catch syscall", " catch  syscall "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Catch(catch::Command::Syscall(syscalls)) if syscalls.is_empty()
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["catch syscall write 0", " catch syscall  write   0 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Catch(catch::Command::Syscall(syscalls)) if syscalls == ["write", "0"]
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["catch off"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Catch(catch::Command::Off)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["catch info"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Catch(catch::Command::Info)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["catch", "catch signal", "catch syscall write,read"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec![
+                "skip file */libcore/*",
+                "skip file \"*/libcore/*\"",
+                " skip  file  */libcore/* ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Skip(skip::Command::File(glob)) if glob == "*/libcore/*"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["skip function ^core::", "skip function  ^core:: "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Skip(skip::Command::Function(regex)) if regex == "^core::"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["skip remove 2", " skip remove  2 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Skip(skip::Command::Remove(2))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["skip info"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Skip(skip::Command::Info)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["skip", "skip file", "skip remove x"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["history", " history  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::History(history::Command::Info)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["history clear"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::History(history::Command::Clear)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["history stack 3", " history  stack  3 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::History(history::Command::Stack(3))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["history log /tmp/stops.log", "history log  /tmp/stops.log "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::History(history::Command::Log(Some(path))) if path == "/tmp/stops.log"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["history log off"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::History(history::Command::Log(None))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["history stack", "history log", "history foo"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["checkpoint", " checkpoint  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Checkpoint(checkpoint::Command::Create)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["checkpoint info"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Checkpoint(checkpoint::Command::Info)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["checkpoint restart 2", " checkpoint  restart  2 "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Checkpoint(checkpoint::Command::Restart(2))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["checkpoint delete 1"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Checkpoint(checkpoint::Command::Delete(1))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["checkpoint restart", "checkpoint delete x", "checkpoint 1"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["async backtrace", "async bt", " async  bt  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Async(r#async::Command::Backtrace)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["async future fut", " async  future  fut "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Async(r#async::Command::Future(DQE::Variable(VariableSelector::Name { var_name, .. }))) if var_name == "fut"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["async", "async tasks", "async future"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["disasm", " disasm  "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Disassemble(DisasmTarget::CurrentFunction)
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec![
+                "disasm 0x100 0x120",
+                "disasm 0x100 +32",
+                " disasm 0x100  + 32 ",
+            ],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Disassemble(DisasmTarget::Range(start, end))
+                    if usize::from(start) == 0x100 && usize::from(end) == 0x120
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["disasm 0x100", "disasm foo", "disasm 0x100 32"],
+            command_matcher: |result| assert!(result.is_err()),
+        },
+        TestCase {
+            inputs: vec!["p vec", "print vec", " p  vec "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::PrintVariables(DQE::Variable(VariableSelector::Name { var_name, .. })) if var_name == "vec"
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["info locals", " info  locals "],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::PrintVariables(DQE::Variable(VariableSelector::Any))
+                ));
+            },
+        },
+        TestCase {
+            inputs: vec!["n", " n "],
+            command_matcher: |result| assert!(matches!(result.unwrap(), Command::StepOver)),
+        },
+        TestCase {
+            inputs: vec!["s"],
+            command_matcher: |result| assert!(matches!(result.unwrap(), Command::StepInto)),
+        },
+        TestCase {
+            inputs: vec!["where", "bt"],
+            command_matcher: |result| {
+                assert!(matches!(result.unwrap(), Command::PrintBacktrace(_)));
+            },
+        },
+        TestCase {
+            inputs: vec!["delete 1"],
+            command_matcher: |result| {
+                assert!(matches!(
+                    result.unwrap(),
+                    Command::Breakpoint(r#break::Command::Remove(BreakpointIdentity::Number(1)))
+                ));
+            },
+        },
     ];
 
     for case in cases {
@@ -0,0 +1,53 @@
+use crate::debugger::snapshot::RecordInfo;
+use crate::debugger::Debugger;
+use crate::ui::command;
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Start,
+    Stop,
+    Info,
+    ReverseStep,
+    ReverseContinue,
+}
+
+pub enum ExecutionResult {
+    Started,
+    Stopped,
+    /// Recording state, `None` if recording is not started.
+    Info(Option<RecordInfo>),
+    Reversed,
+}
+
+/// Record debugee states and return to them.
+pub struct Handler<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<ExecutionResult> {
+        match cmd {
+            Command::Start => {
+                self.dbg.start_recording()?;
+                Ok(ExecutionResult::Started)
+            }
+            Command::Stop => {
+                self.dbg.stop_recording();
+                Ok(ExecutionResult::Stopped)
+            }
+            Command::Info => Ok(ExecutionResult::Info(self.dbg.record_info())),
+            Command::ReverseStep => {
+                self.dbg.reverse_step()?;
+                Ok(ExecutionResult::Reversed)
+            }
+            Command::ReverseContinue => {
+                self.dbg.reverse_continue()?;
+                Ok(ExecutionResult::Reversed)
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::debugger::symbol::SymbolNameStyle;
+use crate::debugger::variable::select::Literal;
+use crate::ui::style::StyleElement;
+use crossterm::style::Color;
+use std::path::PathBuf;
+
+/// Change debugger settings or debugee data.
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Set color of ui element, `None` means that element has no color.
+    Style(StyleElement, Option<Color>),
+    /// Write session transcript into a file, `None` means that logging is disabled.
+    Logging(Option<PathBuf>),
+    /// Write a new value into a debugee variable.
+    Variable(String, Literal),
+    /// Enable or disable non-stop mode.
+    NonStop(bool),
+    /// Replace a directory prefix of source file paths (from, to).
+    SubstitutePath(PathBuf, PathBuf),
+    /// Change representation of symbol names in backtraces and symbol lookups.
+    Demangle(SymbolNameStyle),
+    /// Replace debugee program arguments.
+    Args(Vec<String>),
+    /// Set debugee environment variable, `None` value means that variable is removed.
+    Env(String, Option<String>),
+}
@@ -0,0 +1,52 @@
+use crate::debugger::skip::SkipRule;
+use crate::debugger::Debugger;
+use crate::ui::command;
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Skip functions from files that match a glob pattern.
+    File(String),
+    /// Skip functions with names that match a regular expression.
+    Function(String),
+    /// Remove a rule by its number.
+    Remove(usize),
+    Info,
+}
+
+pub enum ExecutionResult {
+    Added(usize, SkipRule),
+    Removed(usize, SkipRule),
+    /// All skip rules.
+    Info(Vec<SkipRule>),
+}
+
+/// Manage skip rules, functions that `step` command steps out of.
+pub struct Handler<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<ExecutionResult> {
+        match cmd {
+            Command::File(glob) => {
+                let rule = SkipRule::file(&glob)?;
+                let num = self.dbg.add_skip_rule(rule.clone());
+                Ok(ExecutionResult::Added(num, rule))
+            }
+            Command::Function(regex) => {
+                let rule = SkipRule::function(&regex)?;
+                let num = self.dbg.add_skip_rule(rule.clone());
+                Ok(ExecutionResult::Added(num, rule))
+            }
+            Command::Remove(num) => {
+                let rule = self.dbg.remove_skip_rule(num)?;
+                Ok(ExecutionResult::Removed(num, rule))
+            }
+            Command::Info => Ok(ExecutionResult::Info(self.dbg.skip_rules().to_vec())),
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use crate::debugger::Debugger;
+use crate::debugger::{Debugger, Disassembly};
 use crate::ui::command;
 
+/// Count of instructions shown before and after the program counter.
+const ASM_CONTEXT_SIZE: usize = 3;
+
 /// Step on next instruction
 pub struct Handler<'a> {
     dbg: &'a mut Debugger,
@@ -11,7 +14,8 @@ impl<'a> Handler<'a> {
         Self { dbg: debugger }
     }
 
-    pub fn handle(&mut self) -> command::CommandResult<()> {
-        Ok(self.dbg.stepi()?)
+    /// Do a step and return assembly instructions around a new program counter.
+    pub fn handle(&mut self) -> command::CommandResult<Option<Disassembly>> {
+        Ok(self.dbg.stepi_with_asm(ASM_CONTEXT_SIZE)?)
     }
 }
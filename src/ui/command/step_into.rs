@@ -1,6 +1,19 @@
-use crate::debugger::Debugger;
+use crate::debugger::{CallTarget, Debugger};
 use crate::ui::command;
 
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// List subroutine calls at the current line.
+    Targets,
+    /// Step into a subroutine called at the current line.
+    Target(String),
+}
+
+pub enum ExecutionResult {
+    Targets(Vec<CallTarget>),
+    Done,
+}
+
 /// Step program until it reaches a different source line.
 pub struct Handler<'a> {
     dbg: &'a mut Debugger,
@@ -14,4 +27,15 @@ impl<'a> Handler<'a> {
     pub fn handle(&mut self) -> command::CommandResult<()> {
         Ok(self.dbg.step_into()?)
     }
+
+    /// Handle a step into a specific callee (or list of callee candidates).
+    pub fn handle_target(&mut self, cmd: Command) -> command::CommandResult<ExecutionResult> {
+        match cmd {
+            Command::Targets => Ok(ExecutionResult::Targets(self.dbg.step_targets()?)),
+            Command::Target(name) => {
+                self.dbg.step_into_target(&name)?;
+                Ok(ExecutionResult::Done)
+            }
+        }
+    }
 }
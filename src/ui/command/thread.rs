@@ -1,12 +1,12 @@
 use crate::debugger::Tracee;
-use crate::debugger::{Debugger, ThreadSnapshot};
+use crate::debugger::{Debugger, ThreadSelector, ThreadSnapshot};
 use crate::ui::command;
 
 #[derive(Debug, Clone)]
 pub enum Command {
     Info,
     Current,
-    Switch(u32),
+    Switch(ThreadSelector),
 }
 
 pub struct Handler<'a> {
@@ -35,8 +35,8 @@ impl<'a> Handler<'a> {
                     state.into_iter().filter(|t| t.in_focus).collect(),
                 ))
             }
-            Command::Switch(num) => {
-                let in_focus_tracee = self.dbg.set_thread_into_focus(num)?;
+            Command::Switch(thread) => {
+                let in_focus_tracee = self.dbg.set_thread_focus(thread)?;
                 Ok(ExecutionResult::BroughtIntoFocus(in_focus_tracee))
             }
         }
@@ -0,0 +1,29 @@
+use crate::debugger::{Debugger, TargetPlace};
+use crate::ui::command;
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Run until a line greater than current one is reached (escape a loop).
+    NextLine,
+    /// Run until a place is reached.
+    Place(TargetPlace),
+}
+
+// Execute until a place is reached or selected stack frame returns
+pub struct Handler<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<()> {
+        match cmd {
+            Command::NextLine => self.dbg.run_until_line_greater()?,
+            Command::Place(place) => self.dbg.run_until(place)?,
+        }
+        Ok(())
+    }
+}
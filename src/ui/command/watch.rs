@@ -0,0 +1,52 @@
+use crate::debugger::watchpoint::{WatchCondition, Watchpoint};
+use crate::debugger::Debugger;
+use crate::ui::command;
+
+/// Size of a memory watched by address.
+const ADDRESS_WATCH_SIZE: usize = 8;
+
+#[derive(Debug, Clone)]
+pub enum WatchpointIdentity {
+    Variable(String),
+    Address(usize),
+}
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Add(WatchpointIdentity, WatchCondition),
+    Remove(u32),
+    Info,
+}
+
+pub enum ExecutionResult<'a> {
+    New(Watchpoint),
+    Removed(Option<Watchpoint>),
+    Dump(&'a [Watchpoint]),
+}
+
+pub struct Handler<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn handle(&mut self, cmd: Command) -> command::CommandResult<ExecutionResult> {
+        let result = match cmd {
+            Command::Add(WatchpointIdentity::Variable(var_name), condition) => {
+                ExecutionResult::New(self.dbg.set_watchpoint_on_var(&var_name, condition)?)
+            }
+            Command::Add(WatchpointIdentity::Address(addr), condition) => ExecutionResult::New(
+                self.dbg
+                    .set_watchpoint_at_addr(addr.into(), ADDRESS_WATCH_SIZE, condition)?,
+            ),
+            Command::Remove(number) => {
+                ExecutionResult::Removed(self.dbg.remove_watchpoint(number)?)
+            }
+            Command::Info => ExecutionResult::Dump(self.dbg.watchpoints()),
+        };
+        Ok(result)
+    }
+}
@@ -1,3 +1,6 @@
+use crate::ui::init::InitScript;
+use log::LevelFilter;
+use std::path::PathBuf;
 use std::sync::OnceLock;
 use strum_macros::{Display, EnumString, IntoStaticStr};
 
@@ -36,11 +39,44 @@ impl Theme {
     }
 }
 
+/// Amount of diagnostic information shown to user.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum Verbosity {
+    /// Only command outputs and errors.
+    Quiet,
+    #[default]
+    Normal,
+    /// Additionally error categories and debugger warnings.
+    Verbose,
+}
+
+impl Verbosity {
+    /// Return default log level (used if `RUST_LOG` is not set).
+    pub fn log_level(self) -> LevelFilter {
+        match self {
+            Verbosity::Quiet => LevelFilter::Off,
+            Verbosity::Normal => LevelFilter::Error,
+            Verbosity::Verbose => LevelFilter::Warn,
+        }
+    }
+}
+
 /// Application user interface config.
 #[derive(Debug)]
 pub struct UIConfig {
     /// Theme for visualizing program data and source codes.
     pub theme: Theme,
+    /// Diagnostic verbosity level.
+    pub verbosity: Verbosity,
+    /// Print command results and debugger events as JSON objects (one object per line).
+    pub json: bool,
+    /// Unix socket path for remote control requests (interactive console only).
+    pub remote: Option<PathBuf>,
+    /// Url template (with `{path}` and `{line}` placeholders) for source location hyperlinks,
+    /// `None` if hyperlinks are disabled.
+    pub hyperlinks: Option<String>,
+    /// Commands and aliases loaded from init files.
+    pub init: InitScript,
 }
 
 /// Read-only ui configuration (set only once, at debugger start).
@@ -1,17 +1,29 @@
 use crate::ui::command::parser::{
-    ARG_ALL_KEY, ARG_COMMAND, BACKTRACE_ALL_SUBCOMMAND, BACKTRACE_COMMAND, BACKTRACE_COMMAND_SHORT,
-    BREAK_COMMAND, BREAK_COMMAND_SHORT, CONTINUE_COMMAND, CONTINUE_COMMAND_SHORT, FRAME_COMMAND,
-    FRAME_COMMAND_INFO_SUBCOMMAND, FRAME_COMMAND_SWITCH_SUBCOMMAND, HELP_COMMAND,
-    HELP_COMMAND_SHORT, MEMORY_COMMAND, MEMORY_COMMAND_READ_SUBCOMMAND, MEMORY_COMMAND_SHORT,
-    MEMORY_COMMAND_WRITE_SUBCOMMAND, ORACLE_COMMAND, REGISTER_COMMAND,
+    ADVANCE_COMMAND, ARG_ALL_KEY, ARG_COMMAND, ASYNC_BACKTRACE_SUBCOMMAND,
+    ASYNC_BACKTRACE_SUBCOMMAND_SHORT, ASYNC_COMMAND, ASYNC_FUTURE_SUBCOMMAND,
+    BACKTRACE_ALL_SUBCOMMAND, BACKTRACE_COMMAND, BACKTRACE_COMMAND_SHORT, BREAK_COMMAND,
+    BREAK_COMMAND_SHORT, CONTINUE_COMMAND, CONTINUE_COMMAND_SHORT, COVERAGE_CLEAR_SUBCOMMAND,
+    COVERAGE_COMMAND, COVERAGE_FILE_SUBCOMMAND, COVERAGE_FN_SUBCOMMAND, COVERAGE_INFO_SUBCOMMAND,
+    FRAME_COMMAND, FRAME_COMMAND_INFO_SUBCOMMAND, FRAME_COMMAND_SWITCH_SUBCOMMAND, HELP_COMMAND,
+    HELP_COMMAND_SHORT, HISTORY_CLEAR_SUBCOMMAND, HISTORY_COMMAND, HISTORY_LOG_SUBCOMMAND,
+    HISTORY_STACK_SUBCOMMAND, INFO_COMMAND, INFO_FDS_SUBCOMMAND, INFO_FUNCTIONS_SUBCOMMAND,
+    INFO_SIGNALS_SUBCOMMAND, JUMP_COMMAND, MEMORY_COMMAND, MEMORY_COMMAND_READ_SUBCOMMAND,
+    MEMORY_COMMAND_SHORT, MEMORY_COMMAND_WRITE_SUBCOMMAND, ORACLE_COMMAND, REGISTER_COMMAND,
     REGISTER_COMMAND_INFO_SUBCOMMAND, REGISTER_COMMAND_READ_SUBCOMMAND, REGISTER_COMMAND_SHORT,
-    REGISTER_COMMAND_WRITE_SUBCOMMAND, RUN_COMMAND, RUN_COMMAND_SHORT, SHARED_LIB_COMMAND,
-    SHARED_LIB_COMMAND_INFO_SUBCOMMAND, SOURCE_COMMAND, SOURCE_COMMAND_DISASM_SUBCOMMAND,
-    SOURCE_COMMAND_FUNCTION_SUBCOMMAND, STEP_INSTRUCTION_COMMAND, STEP_INTO_COMMAND,
-    STEP_INTO_COMMAND_SHORT, STEP_OUT_COMMAND, STEP_OUT_COMMAND_SHORT, STEP_OVER_COMMAND,
-    STEP_OVER_COMMAND_SHORT, SYMBOL_COMMAND, THREAD_COMMAND, THREAD_COMMAND_CURRENT_SUBCOMMAND,
-    THREAD_COMMAND_INFO_SUBCOMMAND, THREAD_COMMAND_SWITCH_SUBCOMMAND, VAR_COMMAND, VAR_LOCAL_KEY,
+    REGISTER_COMMAND_WRITE_SUBCOMMAND, RUN_COMMAND, RUN_COMMAND_SHORT, SCRIPT_COMMAND,
+    SET_ARGS_SUBCOMMAND, SET_COMMAND, SET_ENV_SUBCOMMAND, SET_LOGGING_SUBCOMMAND,
+    SET_STYLE_SUBCOMMAND, SHARED_LIB_COMMAND, SHARED_LIB_COMMAND_INFO_SUBCOMMAND, SKIP_COMMAND,
+    SKIP_FILE_SUBCOMMAND, SKIP_FUNCTION_SUBCOMMAND, SKIP_INFO_SUBCOMMAND, SKIP_REMOVE_SUBCOMMAND,
+    SOURCE_COMMAND, SOURCE_COMMAND_DISASM_SUBCOMMAND, SOURCE_COMMAND_FUNCTION_SUBCOMMAND,
+    STEP_INSTRUCTION_COMMAND, STEP_INTO_COMMAND, STEP_INTO_COMMAND_SHORT,
+    STEP_INTO_TARGETS_SUBCOMMAND, STEP_INTO_TARGET_SUBCOMMAND, STEP_OUT_COMMAND,
+    STEP_OUT_COMMAND_SHORT, STEP_OVER_COMMAND, STEP_OVER_COMMAND_SHORT, SYMBOL_COMMAND,
+    THREAD_COMMAND, THREAD_COMMAND_CURRENT_SUBCOMMAND, THREAD_COMMAND_INFO_SUBCOMMAND,
+    THREAD_COMMAND_SWITCH_SUBCOMMAND, UNTIL_COMMAND, VAR_COMMAND, VAR_LOCAL_KEY, WATCH_COMMAND,
+    WATCH_INFO_SUBCOMMAND, WATCH_REMOVE_SUBCOMMAND,
 };
+use crate::ui::keymap::KeyMode;
+use crate::ui::{config, style};
 use chumsky::prelude::{any, choice, just};
 use chumsky::text::whitespace;
 use chumsky::{extra, text, Parser};
@@ -21,7 +33,7 @@ use rustyline::highlight::Highlighter;
 use rustyline::hint::HistoryHinter;
 use rustyline::history::MemHistory;
 use rustyline::line_buffer::LineBuffer;
-use rustyline::{Changeset, CompletionType, Config, Context, Editor};
+use rustyline::{Changeset, CompletionType, Config, Context, EditMode, Editor};
 use rustyline_derive::{Helper, Hinter, Validator};
 use std::borrow::Cow;
 use std::borrow::Cow::{Borrowed, Owned};
@@ -30,6 +42,17 @@ use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use trie_rs::{Trie, TrieBuilder};
 
+/// Max count of function names suggested for a breakpoint.
+const MAX_FUNCTION_HINTS: usize = 50;
+
+/// Return `true` if all template characters are found in a name in the same order.
+fn fuzzy_match(template: &str, name: &str) -> bool {
+    let mut name_chars = name.chars();
+    template
+        .chars()
+        .all(|tc| name_chars.by_ref().any(|nc| nc == tc))
+}
+
 struct CommandHint {
     short: Option<String>,
     long: String,
@@ -86,6 +109,8 @@ pub struct CommandCompleter {
     vars: Vec<String>,
     arg_hints: Trie<u8>,
     args: Vec<String>,
+    function_hints: Vec<String>,
+    register_hints: Vec<String>,
 }
 
 impl CommandCompleter {
@@ -110,6 +135,8 @@ impl CommandCompleter {
             arg_hints: TrieBuilder::new().build(),
             args: vec![],
             vars: vec![],
+            function_hints: vec![],
+            register_hints: vec![],
         }
     }
 
@@ -126,6 +153,16 @@ impl CommandCompleter {
         self.file_hints = builder.build();
     }
 
+    pub fn replace_function_hints(&mut self, functions: impl IntoIterator<Item = String>) {
+        self.function_hints = functions.into_iter().collect();
+        self.function_hints.sort_unstable();
+        self.function_hints.dedup();
+    }
+
+    pub fn replace_register_hints(&mut self, registers: impl IntoIterator<Item = String>) {
+        self.register_hints = registers.into_iter().collect();
+    }
+
     pub fn replace_local_var_hints(&mut self, variables: impl IntoIterator<Item = String>) {
         let mut builder = TrieBuilder::new();
         self.vars = variables.into_iter().collect();
@@ -154,6 +191,7 @@ enum CompletableCommand<'a> {
     Breakpoint(&'a str),
     PrintVariables(&'a str),
     PrintArguments(&'a str),
+    Register(&'a str),
     Unrecognized(&'a str, Option<&'a str>),
 }
 
@@ -177,13 +215,21 @@ impl<'a> CompletableCommand<'a> {
             .ignore_then(any().repeated().to_slice())
             .map(CompletableCommand::PrintArguments);
 
+        let reg = op(REGISTER_COMMAND)
+            .or(op(REGISTER_COMMAND_SHORT))
+            .then(whitespace().at_least(1))
+            .then(op(REGISTER_COMMAND_READ_SUBCOMMAND).or(op(REGISTER_COMMAND_WRITE_SUBCOMMAND)))
+            .then(whitespace().at_least(1))
+            .ignore_then(any().repeated().to_slice())
+            .map(CompletableCommand::Register);
+
         let other = text::ident()
             .then_ignore(whitespace().at_least(1))
             .then(text::ident().or_not())
             .map(|(s1, s2): (&str, Option<&str>)| CompletableCommand::Unrecognized(s1.trim(), s2))
             .padded();
 
-        let r = choice((bp, var, arg, other)).parse(line);
+        let r = choice((bp, var, arg, reg, other)).parse(line);
         r.into_result().ok()
     }
 }
@@ -224,6 +270,23 @@ impl Completer for CommandCompleter {
                     });
                     return Ok(pairs_from_variants(variants_iter, line, maybe_file, ":"));
                 }
+
+                // function names are matched fuzzy, shorter names are suggested first
+                let mut functions: Vec<_> = self
+                    .function_hints
+                    .iter()
+                    .filter(|name| fuzzy_match(maybe_file, name))
+                    .collect();
+                if !functions.is_empty() {
+                    functions.sort_by_key(|name| name.len());
+                    functions.truncate(MAX_FUNCTION_HINTS);
+                    return Ok(pairs_from_variants(
+                        functions.into_iter(),
+                        line,
+                        maybe_file,
+                        "",
+                    ));
+                }
             }
             Some(CompletableCommand::PrintVariables(maybe_var)) => {
                 if maybe_var.trim().is_empty() {
@@ -251,6 +314,13 @@ impl Completer for CommandCompleter {
                     return Ok(pairs_from_variants(variants_iter, line, maybe_arg, ""));
                 }
             }
+            Some(CompletableCommand::Register(maybe_reg)) => {
+                let registers = self
+                    .register_hints
+                    .iter()
+                    .filter(|reg| reg.starts_with(maybe_reg.trim_start()));
+                return Ok(pairs_from_variants(registers, line, maybe_reg, ""));
+            }
             Some(CompletableCommand::Unrecognized(cmd, mb_subcmd_part)) => {
                 if let Some(subcommands) = self.subcommand_hints.get(cmd) {
                     let pos = cmd.len() + 1;
@@ -317,7 +387,7 @@ impl Highlighter for RLHelper {
         prompt: &'p str,
         default: bool,
     ) -> Cow<'b, str> {
-        if default {
+        if default && !style::is_no_color() {
             Borrowed(&self.colored_prompt)
         } else {
             Borrowed(prompt)
@@ -325,6 +395,9 @@ impl Highlighter for RLHelper {
     }
 
     fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        if style::is_no_color() {
+            return Borrowed(hint);
+        }
         Owned(format!("{}", hint.with(Color::Grey)))
     }
 }
@@ -333,9 +406,14 @@ pub fn create_editor(
     promt: &str,
     oracles: &[&str],
 ) -> anyhow::Result<Editor<RLHelper, MemHistory>> {
+    let edit_mode = match config::current().init.keymap.mode {
+        KeyMode::Emacs => EditMode::Emacs,
+        KeyMode::Vi => EditMode::Vi,
+    };
     let config = Config::builder()
         .history_ignore_space(true)
         .completion_type(CompletionType::List)
+        .edit_mode(edit_mode)
         .build();
 
     let commands = [
@@ -352,11 +430,29 @@ pub fn create_editor(
         },
         (RUN_COMMAND_SHORT, RUN_COMMAND).into(),
         STEP_INSTRUCTION_COMMAND.into(),
-        (STEP_INTO_COMMAND_SHORT, STEP_INTO_COMMAND).into(),
+        CommandHint {
+            short: Some(STEP_INTO_COMMAND_SHORT.to_string()),
+            long: STEP_INTO_COMMAND.to_string(),
+            subcommands: vec![
+                STEP_INTO_TARGET_SUBCOMMAND.to_string(),
+                STEP_INTO_TARGETS_SUBCOMMAND.to_string(),
+            ],
+        },
         (STEP_OUT_COMMAND_SHORT, STEP_OUT_COMMAND).into(),
         (STEP_OVER_COMMAND_SHORT, STEP_OVER_COMMAND).into(),
+        UNTIL_COMMAND.into(),
+        ADVANCE_COMMAND.into(),
+        JUMP_COMMAND.into(),
         SYMBOL_COMMAND.into(),
         (BREAK_COMMAND_SHORT, BREAK_COMMAND).into(),
+        CommandHint {
+            short: None,
+            long: WATCH_COMMAND.to_string(),
+            subcommands: vec![
+                WATCH_REMOVE_SUBCOMMAND.to_string(),
+                WATCH_INFO_SUBCOMMAND.to_string(),
+            ],
+        },
         CommandHint {
             short: Some(BACKTRACE_COMMAND_SHORT.to_string()),
             long: BACKTRACE_COMMAND.to_string(),
@@ -389,11 +485,58 @@ pub fn create_editor(
                 THREAD_COMMAND_CURRENT_SUBCOMMAND.to_string(),
             ],
         },
+        CommandHint {
+            short: None,
+            long: ASYNC_COMMAND.to_string(),
+            subcommands: vec![
+                ASYNC_BACKTRACE_SUBCOMMAND.to_string(),
+                ASYNC_BACKTRACE_SUBCOMMAND_SHORT.to_string(),
+                ASYNC_FUTURE_SUBCOMMAND.to_string(),
+            ],
+        },
+        CommandHint {
+            short: None,
+            long: SKIP_COMMAND.to_string(),
+            subcommands: vec![
+                SKIP_FILE_SUBCOMMAND.to_string(),
+                SKIP_FUNCTION_SUBCOMMAND.to_string(),
+                SKIP_REMOVE_SUBCOMMAND.to_string(),
+                SKIP_INFO_SUBCOMMAND.to_string(),
+            ],
+        },
+        CommandHint {
+            short: None,
+            long: HISTORY_COMMAND.to_string(),
+            subcommands: vec![
+                HISTORY_CLEAR_SUBCOMMAND.to_string(),
+                HISTORY_STACK_SUBCOMMAND.to_string(),
+                HISTORY_LOG_SUBCOMMAND.to_string(),
+            ],
+        },
         CommandHint {
             short: None,
             long: SHARED_LIB_COMMAND.to_string(),
             subcommands: vec![SHARED_LIB_COMMAND_INFO_SUBCOMMAND.to_string()],
         },
+        CommandHint {
+            short: None,
+            long: INFO_COMMAND.to_string(),
+            subcommands: vec![
+                INFO_FDS_SUBCOMMAND.to_string(),
+                INFO_SIGNALS_SUBCOMMAND.to_string(),
+                INFO_FUNCTIONS_SUBCOMMAND.to_string(),
+            ],
+        },
+        CommandHint {
+            short: None,
+            long: COVERAGE_COMMAND.to_string(),
+            subcommands: vec![
+                COVERAGE_FILE_SUBCOMMAND.to_string(),
+                COVERAGE_FN_SUBCOMMAND.to_string(),
+                COVERAGE_INFO_SUBCOMMAND.to_string(),
+                COVERAGE_CLEAR_SUBCOMMAND.to_string(),
+            ],
+        },
         CommandHint {
             short: None,
             long: SOURCE_COMMAND.to_string(),
@@ -407,6 +550,17 @@ pub fn create_editor(
             long: ORACLE_COMMAND.to_string(),
             subcommands: oracles.iter().map(ToString::to_string).collect(),
         },
+        SCRIPT_COMMAND.into(),
+        CommandHint {
+            short: None,
+            long: SET_COMMAND.to_string(),
+            subcommands: vec![
+                SET_STYLE_SUBCOMMAND.to_string(),
+                SET_LOGGING_SUBCOMMAND.to_string(),
+                SET_ARGS_SUBCOMMAND.to_string(),
+                SET_ENV_SUBCOMMAND.to_string(),
+            ],
+        },
         ("q", "quit").into(),
     ];
 
@@ -1,6 +1,7 @@
+use crate::debugger::source::{self, SourceLine};
 use crate::debugger::PlaceDescriptor;
 use crate::ui::syntax;
-use crate::ui::syntax::StylizedLine;
+use crate::ui::syntax::{RenderError, StylizedLine};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::BufRead;
@@ -19,11 +20,11 @@ impl FileView {
         Self::default()
     }
 
-    fn render(&self, file_path: &Path, start: u64, length: u64) -> anyhow::Result<String> {
+    fn render(&self, file_path: &Path, start: u64, length: u64) -> Result<String, RenderError> {
         let mut cache = self.cached_lines.borrow_mut();
         let file_lines = match cache.get(file_path) {
             None => {
-                let file = fs::File::open(file_path)?;
+                let file = fs::File::open(source::resolve(file_path))?;
                 let lines = io::BufReader::new(file)
                     .lines()
                     .map_while(Result::ok)
@@ -42,23 +43,55 @@ impl FileView {
             .iter()
             .skip(start as usize)
             .take(length as usize)
-            .try_fold(String::default(), |acc, line| -> anyhow::Result<String> {
-                let line_number = start + 1 + i;
-                i += 1;
+            .try_fold(
+                String::default(),
+                |acc, line| -> Result<String, RenderError> {
+                    let line_number = start + 1 + i;
+                    i += 1;
 
-                match line_renderer.render_line(line)? {
-                    StylizedLine::NoneStyle(line) => Ok(format!("{acc}{line_number:>4} {line}\n")),
+                    match line_renderer.render_line(line)? {
+                        StylizedLine::NoneStyle(line) => {
+                            Ok(format!("{acc}{line_number:>4} {line}\n"))
+                        }
+                        StylizedLine::Stylized(segments) => {
+                            let escaped = as_24_bit_terminal_escaped(&segments, false);
+                            Ok(format!("{acc}{line_number:>4} {escaped}\x1b[0m\n"))
+                        }
+                    }
+                },
+            )?;
+
+        Ok(result)
+    }
+
+    /// Render source lines with syntax highlighting, a current line is marked with `>`.
+    pub fn render_lines(&self, lines: &[SourceLine]) -> Result<String, RenderError> {
+        let syntax_renderer = syntax::rust_syntax_renderer();
+        let mut line_renderer = syntax_renderer.line_renderer();
+
+        lines.iter().try_fold(
+            String::default(),
+            |acc, line| -> Result<String, RenderError> {
+                let marker = if line.current { '>' } else { ' ' };
+                let number = line.number;
+                match line_renderer.render_line(&line.text)? {
+                    StylizedLine::NoneStyle(text) => {
+                        Ok(format!("{acc}{marker}{number:>4} {text}\n"))
+                    }
                     StylizedLine::Stylized(segments) => {
                         let escaped = as_24_bit_terminal_escaped(&segments, false);
-                        Ok(format!("{acc}{line_number:>4} {escaped}\x1b[0m\n"))
+                        Ok(format!("{acc}{marker}{number:>4} {escaped}\x1b[0m\n"))
                     }
                 }
-            })?;
-
-        Ok(result)
+            },
+        )
     }
 
-    pub fn render_source(&self, place: &PlaceDescriptor, bounds: u64) -> anyhow::Result<String> {
+    pub fn render_source(
+        &self,
+        place: &PlaceDescriptor,
+        bounds: u64,
+    ) -> Result<String, RenderError> {
         let line_number = if place.line_number == 0 {
             1
         } else {
@@ -79,7 +112,7 @@ impl FileView {
         file: &Path,
         from_line: u64,
         to_line: u64,
-    ) -> anyhow::Result<String> {
+    ) -> Result<String, RenderError> {
         let start = if from_line == 0 { 0 } else { from_line - 1 };
         let bound = to_line - from_line + 1;
 
@@ -12,19 +12,44 @@ f, frame info|switch <number>               -- print current stack frame informa
 c, continue                                 -- continue program being debugged, after signal or breakpoint
 r, run                                      -- start or restart debugged programm 
 stepi                                       -- step one instruction
-step, stepinto                              -- step program until it reaches a different source line
+step, stepinto <>|into <function>|targets   -- step program until it reaches a different source line or a callee
 finish, stepout                             -- execute program until selected stack frame returns
 next, stepover                              -- step program, stepping over subroutine calls
+until <>|<location>, advance <location>     -- run until a greater line (escape a loop) or a location is reached
+jump <location>, jump --force <location>    -- set program counter to a location (re-execute or skip statements)
 b, break <addr>|<file:line>|<function>      -- manage breakpoints
+watch <variable>|*<addr>                    -- manage watchpoints
 symbol <name>                               -- print symbol kind and address
 mem, memory read|write <addr>               -- read or write into debugged program memory
+x/<count><format><size> <addr>              -- examine memory with hex, decimal, float or char units
 reg, register read|write|info <addr>        -- read, write, or view debugged program registers
 thread info|current|switch <number>         -- show list of threads or current (in focus) thread or set thread in focus
 sharedlib info                              -- show list of shared libraries
+info fds|signals|functions <regex>          -- show open file descriptors, signals or functions of debugged program
+coverage file|fn|info|clear                 -- track line coverage of files or functions
 source asm|fn|<bounds>                      -- show source code or assembly instructions for current (in focus) function
+disasm <>|<start> <end>|<start> +<length>   -- show annotated assembly of current function or memory range
 oracle <oracle> <>|<subcommand>             -- execute a specific oracle
+script <path>                               -- execute a rhai script
+set style <element> <color>                 -- change color of ui element
+set logging on <file>|off                   -- write session transcript into a file
+set non-stop on|off                         -- stop only a thread that hits a breakpoint
+set substitute-path <from> <to>             -- load source files from another directory
+set demangle demangled|raw|both             -- show symbol names demangled, raw or in both forms
+set args <args>, set env <name>=<value>     -- change program arguments and environment
+call <function>(<arguments>)                -- call a function of debugged program
+return <>|<value>                           -- return from a function of selected frame immediately
+record start|stop|info                      -- save program state at every stop for reverse execution
+rs, reverse-step                            -- return to the previous recorded stop
+rc, reverse-continue                        -- return to the previous recorded stop after continue
+catch syscall <>|<names>, catch off|info    -- stop at entry and exit of syscalls
+skip file|function|remove|info <arg>        -- manage functions that step command steps out of
+history <>|clear|stack <n>|log <path>|off  -- show a log of program stops (breakpoints, steps, signals)
+checkpoint <>|info|restart <id>|delete <id> -- save a copy of a program and restart from it
+async backtrace|bt, async future <expr>     -- show tasks of async (tokio) runtimes or decode a future
 h, help <>|<command>                        -- show help
 tui                                         -- change ui mode to tui
+<empty input>                               -- repeat the last step, continue or view command
 q, quit                                     -- exit the BugStalker 
 "#;
 
@@ -90,6 +115,12 @@ Show local and global variables, supports data queries expressions over variable
 Available subcomands:
 var locals - print current stack frame local variables
 var <name or expression> - print local and global variables with selected name
+var <arithmetic expression> - evaluate an expression over variables and literals, supports 
+`+ - * / %`, comparisons (`== != < <= > >=`), `&& || !`, casts to primitive types (`(u64)x`), 
+address-of (`&x`) and dereference (`*expr`)
+var <name or expression>|locals --export <file> [--format csv|json] - write variables into 
+a file, arrays and maps of scalars are written as rows, format is chosen by file extension 
+if `--format` is not set (csv by default)
 
 Examples of usage:
 var locals - print current stack frame local variables
@@ -99,6 +130,10 @@ var some_array[0] - print first element if `some_array` is a vector, array, vecd
 var some_array[2..5] - print 3 elements, starts from index 2
 var *some_array[0] - print dereferenced value of some_array[0]
 var (*some_array)[0] - print first element of *some_array
+var some_vec --export ./vec.csv - write elements of `some_vec` into a csv file
+var a + b * 2 - print result of an arithmetic expression
+var (u8)some_int > 10 && flag - print result of a logical expression
+var *(*const u32)(&some_struct + 8) - print u32 value located at offset 8 of `some_struct`
 ";
 
 pub const HELP_ARG: &str = "\
@@ -121,13 +156,15 @@ Show backtrace of all stack frames in current thread or from all threads.
 
 Available subcomands:
 backtrace all - show backtrace for all running threads
-backtrace - show backtrace of current thread
+backtrace - show backtrace of current thread, with frame arguments
 
 Output format:
 thread {id} - {current ip value}
-{current ip value} - {function name} ({function address} + {offset})
-{the address of the instruction in the overlay stack frame} - {function name} ({function address} + {offset})
+{current ip value} - {function name}({arguments}) ({function address} + {offset}) at {file}:{line}:{column}
+{the address of the instruction in the overlay stack frame} - {function name}({arguments}) ({function address} + {offset}) at {file}:{line}:{column}
 ...
+
+Only scalar and pointer arguments are shown, other values are replaced by `...`.
 ";
 
 pub const HELP_FRAME: &str = "\
@@ -156,11 +193,24 @@ Start or restart debugged programm.
 pub const HELP_STEPI: &str = "\
 \x1b[32;1mstepi\x1b[0m
 step one instruction.
+If there is no source code for a new location (ex. in a library without debug information),
+the instruction at a new program counter and a few instructions around it are shown.
 ";
 
 pub const HELP_STEPINTO: &str = "\
 \x1b[32;1mstep, stepinto\x1b[0m
 Step program until it reaches a different source line.
+When a line contains multiple calls (like `foo(bar(), baz())`) a specific callee may be selected,
+program also stops when current stack frame returns before the callee is called.
+
+Available subcommands:
+step - step program until it reaches a different source line
+step into <function> - run program until a function called at the current line is entered
+step targets - show calls at the current line
+
+Examples:
+\x1b[32;1mstep targets\x1b[0m - list functions called at the current line
+\x1b[32;1mstep into sum2\x1b[0m - step into calc::sum2 function, skipping other calls
 ";
 
 pub const HELP_STEPOUT: &str = "\
@@ -173,17 +223,67 @@ pub const HELP_STEPOVER: &str = "\
 Step program, stepping over subroutine calls.
 ";
 
+pub const HELP_UNTIL: &str = "\
+\x1b[32;1muntil, advance\x1b[0m
+Run program until a location is reached, no permanent breakpoints are left.
+Program also stops when current stack frame returns.
+
+Available subcommands:
+until - step program, stepping over subroutine calls, until a line greater than current one is reached (escape a loop)
+until <addr>|<file:line>|<function> - run program until a location is reached
+advance <addr>|<file:line>|<function> - same as until with a location
+
+Examples:
+\x1b[32;1muntil\x1b[0m - escape a loop: stop at the first line after the loop end
+\x1b[32;1madvance main.rs:15\x1b[0m - run until line 15 of main.rs
+\x1b[32;1muntil calc::sum\x1b[0m - run until calc::sum function is called
+";
+
+pub const HELP_JUMP: &str = "\
+\x1b[32;1mjump\x1b[0m
+Set program counter of a thread in focus to an address of a location, so statements may be
+re-executed or skipped. Program is not resumed. Location must be in the current function,
+use --force option to jump into another function (stack frame is not changed, it is unsafe).
+
+Available subcommands:
+jump <addr>|<file:line>|<function> - jump to a location in the current function
+jump --force <addr>|<file:line>|<function> - jump to any location
+
+Examples:
+\x1b[32;1mjump main.rs:10\x1b[0m - execute line 10 of main.rs at next step
+";
+
 pub const HELP_BREAK: &str = "\
 \x1b[32;1mb, break\x1b[0m
 Manage breakpoints.
 
 Available subcomands:
 break <location> - set breakpoint to location
+break --hw <location> - set hardware breakpoint to location, hardware breakpoint uses a debug 
+register instead of program code patching, so it works in read-only or shared memory and 
+doesn't change code that checksums itself. Debug registers are shared with watchpoints, 
+no more than 4 hardware breakpoints and watched ranges can be set at the same time
 break remove <location>|<number> - deactivate and delete selected breakpoint
 break info - show all breakpoints
+break enable <number> - enable breakpoint
+break disable <number> - disable breakpoint, disabled breakpoint does not stop a program
+break condition <number> <expression>|none - stop at breakpoint only if expression 
+value is true (or not zero), `none` removes condition. Example: break condition 1 a.b > 10
+break ignore <number> <count> - don't stop at next <count> breakpoint hits, 
+`break info` shows hit counts. Example: break ignore 3 5
+break save <file> - save breakpoints into a file as a gdb script (`break file:line` commands)
+break load <file> - set breakpoints from a gdb script (`break`, `hbreak`, `condition`, `ignore`, 
+`enable` and `disable` commands are supported, other lines are skipped)
+break commands <number> [script] - execute a rhai script (see `help script`) when breakpoint 
+is hit, without a script removes commands. Example: break commands 1 print(var(\"x\")); cont()
 
 Posible location format:
-- at instruction. Example: break 0x55555555BD30
+- at instruction. Address must be inside an executable memory region, code without debug
+information is allowed. Example: break 0x55555555BD30
+- at function symbol (from ELF symbol table) plus offset in bytes, debug information is
+not required. Examples:
+    * break main+16
+    * break memcpy+0x10
 - at function start. A function can be defined by its full name (with namespace) 
 or by function name (in case of possible collisions, breakpoints will be set in 
 all matching functions). Examples: 
@@ -193,6 +293,26 @@ all matching functions). Examples:
 - a breakpoint number (only for `remove` subcommand)
 ";
 
+pub const HELP_WATCH: &str = "\
+\x1b[32;1mwatch\x1b[0m
+Manage watchpoints, watchpoint stops a program when watched memory is changed (or accessed).
+Watchpoints use hardware debug registers, so no more than 4 watchpoints (up to 8 bytes of 
+aligned memory each, larger variables take several registers) can be set at the same time.
+
+Available subcomands:
+watch <location> - stop when watched memory value changes
+watch --rw <location> - stop when watched memory is read or written
+watch remove <number> - delete selected watchpoint
+watch info - show all watchpoints
+
+Posible location format:
+- variable name, local variables are in priority. Example: watch my_var
+- 8-byte memory block at address. Example: watch *0x55555555BD30
+
+Note that watchpoint stays at the same memory even if variable goes out of scope,
+all watchpoints are removed when program exits.
+";
+
 pub const HELP_SYMBOL: &str = "\
 \x1b[32;1msymbol\x1b[0m
 Print symbols matched by regular expression.
@@ -210,6 +330,24 @@ memory read <address> - print 8-byte block at address in debugee memory
 memory write <address> <value> - writes 8-byte value to address in debugee memory
 ";
 
+pub const HELP_EXAMINE: &str = "\
+\x1b[32;1mx\x1b[0m
+Examine debugged program memory. Memory is shown by rows of 16 bytes, each row contains
+interpreted units and ASCII representation of row bytes.
+
+x/<count><format><size> <address>
+
+count - count of units (1 by default)
+format - x (hex, default), d (signed decimal), u (unsigned decimal), o (octal), t (binary),
+f (float), c (char)
+size - b (1 byte), h (2 bytes), w (4 bytes, default), g (8 bytes)
+
+Examples of usage:
+x/16xb 0x7fffffffe000 - 16 bytes in hex
+x/4dw 0x7fffffffe000 - 4 signed 32-bit integers
+x/2f 0x7fffffffe000 - 2 double precision floats
+";
+
 pub const HELP_REGISTER: &str = "\
 \x1b[32;1mreg, register\x1b[0m
 Read, write, or view debugged program registers (x86_64 registers support).
@@ -225,9 +363,14 @@ pub const HELP_THREAD: &str = "\
 Show threads information or set thread to focus.
 
 Available subcomands:
-thread info - print list of thread information
+thread info - print list of thread information: number, OS thread id, name, stop place,
+kernel state (R - running, S - sleeping, D - uninterruptible wait, t - stopped by debugger),
+last CPU and user/system time
 thread current - prints thread that has focus
 thread switch <number> - set thread <number> to focus
+thread switch tid <tid> - set thread with OS thread id <tid> to focus
+
+Backtrace, variables, registers and steps are related to a thread in focus.
 ";
 
 pub const HELP_SHARED_LIB: &str = "\
@@ -238,6 +381,33 @@ Available subcomands:
 sharedlib info - print list of loaded shared libraries and their mapping addresses
 ";
 
+pub const HELP_INFO: &str = "\
+\x1b[32;1minfo\x1b[0m
+Show debugged program state information.
+
+Available subcomands:
+info fds - print open file descriptors: descriptor target (file path, socket addresses, pipe), offset and flags
+info signals - print how debugger handles signals (pass signal to a program, stop a program or stop and suppress signal)
+info signals --history - print last signals delivered to a program: time, receiver thread, sender process, signal code and handling decision
+info functions <regex> - print functions which full name matches a regular expression: declaration place and address ranges
+";
+
+pub const HELP_COVERAGE: &str = "\
+\x1b[32;1mcoverage\x1b[0m
+Track line coverage of selected files or functions. Temporary breakpoints are set on every statement
+and removed after the first hit, so covered code is executed at full speed.
+
+Available subcomands:
+coverage file <file> - track all statements of a file (full path or part of a file path)
+coverage fn <function> - track all statements of a function (full name or part of a path)
+coverage, coverage info - print executed lines count and untouched lines for each tracked file
+coverage clear - stop tracking and forget collected data
+
+Examples of usage:
+coverage file main.rs
+coverage fn parser::parse
+";
+
 pub const HELP_SOURCE: &str = "\
 \x1b[32;1msource\x1b[0m
 Show source code or assembly instructions for current (in focus) function.
@@ -248,6 +418,23 @@ source asm - show assembly of function in focus
 source <bounds> - show line in focus with <bounds> lines up and down of this line
 ";
 
+pub const HELP_DISASM: &str = "\
+\x1b[32;1mdisasm\x1b[0m
+Show assembly instructions of current (in focus) function or of a memory range. Instructions are
+grouped by source lines, current program counter is marked with `=>`, instructions with
+breakpoints are marked with a breakpoint number.
+
+Available forms:
+disasm - show assembly of function in focus
+disasm <start> <end> - show assembly of memory range, <end> address is not included
+disasm <start> +<length> - show assembly of <length> bytes starting from <start>
+
+Examples of usage:
+disasm
+disasm 0x55555555B6D0 0x55555555B700
+disasm 0x55555555B6D0 +64
+";
+
 pub const HELP_TUI: &str = "\
 \x1b[32;1mtui\x1b[0m
 Change ui mode to terminal ui.
@@ -260,6 +447,202 @@ Execute a specific oracle.
 List of available oracles:
 ";
 
+pub const HELP_SCRIPT: &str = "\
+\x1b[32;1mscript\x1b[0m
+Execute a rhai script (see https://rhai.rs) with access to the debugger.
+
+Available functions:
+run(), cont(), step_into(), step_over(), step_out(), stepi() - control program execution
+break_at(<location>) - set a breakpoint, location format is the same as in `break` command
+break_remove(<number>) - remove breakpoint by its number
+var(<expression>), arg(<expression>) - list of variables or arguments selected by data query expression
+locals() - map of local variables
+bt() - list of function names in backtrace of current thread
+reg(<reg_name>) - register value
+mem(<address>, <n>) - read n bytes from debugged program memory
+location() - map with current program counter, file and line
+pretty_printer(<name>, <type_prefix>, <function>) - render structures which type name starts 
+with <type_prefix> by a script function, function takes a structure as a map and returns a text
+print(<value>) - print a value
+
+Examples of usage:
+script ./inspect.rhai
+";
+
+pub const HELP_SET: &str = "\
+\x1b[32;1mset\x1b[0m
+Change debugger settings or program data.
+
+Available subcommands:
+set style <element> <color> - change color of ui element
+set logging on <file> - write every command, its output, program output and stop events into a file
+set logging off - stop writing session transcript
+set var|variable <name> = <literal> - write a new value into a variable, integers, floats, 
+bools, chars ('c'), C-style enum variants and pointers (0x... addresses) are supported
+set non-stop on|off - in non-stop mode only a thread that hits a breakpoint, watchpoint or receives
+a signal is stopped, other threads keep running; `continue` and steps resume a thread in focus only,
+use `thread switch` to move focus to another stopped thread
+set substitute-path <from> <to> - load source files which paths start with <from> directory 
+from <to> directory, for programs built on another machine or moved sources
+set demangle demangled|raw|both - representation of symbol names in backtraces and symbol lookups:
+demangled (default, ex. `std::rt::lang_start`), raw (ex. `_ZN3std2rt10lang_start17h...E`)
+or both forms
+set args <args> - replace program arguments (quote an argument with whitespaces), applied at next
+run or restart
+set env <name>=<value> - set program environment variable, applied at next run or restart
+set env --unset <name> - remove a variable from program environment
+
+Available elements: address, file_path, function_name, keyword, asm_instruction, asm_operands,
+error, breakpoint, panel (borders and selected items of tui panels).
+Color is a name (black, red, dark_red, green, dark_green, yellow, dark_yellow, blue, dark_blue,
+magenta, dark_magenta, cyan, dark_cyan, white, grey, dark_grey), a hex rgb value (#rrggbb) or none.
+
+Colors can be disabled at all with `--no-color` argument or NO_COLOR environment variable.
+
+Examples of usage:
+set style address dark_cyan
+set style panel #87afff
+set style error none
+set logging on ./session.log
+set var counter = 10
+set var ptr = 0x7FFFFFFFDC94
+set non-stop on
+set substitute-path /build/project/src ./src
+set demangle both
+set args 1 2 \"a b\"
+set env RUST_LOG=debug
+";
+
+pub const HELP_CALL: &str = "\
+\x1b[32;1mcall\x1b[0m
+Call a function of debugged program in current (in focus) thread and print the returned value.
+Arguments are literals: integers, floats, bools, chars ('c'), C-style enum variants and pointers
+(0x... addresses). Registers of the thread are restored after the call, other threads are running
+while the function executes. If the program stops inside the called function (at a breakpoint
+or by a signal) the call is abandoned.
+
+Examples of usage:
+call sum(1, 2)
+call my_mod::reset()
+";
+
+pub const HELP_RETURN: &str = "\
+\x1b[32;1mreturn\x1b[0m
+Force a function of selected stack frame to return immediately, selected frame and frames above it
+are popped. Optional value (a literal, like in `call` command) is placed into a return register.
+The rest of function code is not executed, so destructors of local variables are not called.
+
+Examples of usage:
+return - return from a function that returns nothing
+return 42 - return 42 from a function
+";
+
+pub const HELP_RECORD: &str = "\
+\x1b[32;1mrecord\x1b[0m
+Save program state (registers of all threads and writable memory) at every stop after
+continue or step, and return to one of the saved states. Memory pages are shared between states
+if they are not changed. Effects outside of program memory (files, sockets, other processes)
+are not reverted.
+
+Available subcommands:
+record, record start - start recording, current state is saved immediately
+record stop - stop recording and forget saved states
+record info - show saved states
+rs, reverse-step - return to the previous saved state
+rc, reverse-continue - return to the previous saved state after continue (a breakpoint,
+watchpoint or signal stop), or to the state at recording start
+
+Examples of usage:
+record
+c
+rc
+";
+
+pub const HELP_CATCH: &str = "\
+\x1b[32;1mcatch\x1b[0m
+Stop program at entry and exit of selected syscalls. Argument registers are shown at a syscall
+entry, a return value - at a syscall exit.
+
+Available subcommands:
+catch syscall - stop at any syscall
+catch syscall <name or number> <name or number> ... - stop at syscalls with specified names or numbers
+catch off - remove a syscall catchpoint
+catch info - show caught syscalls
+
+Examples of usage:
+catch syscall write openat
+catch syscall 1
+";
+
+pub const HELP_SKIP: &str = "\
+\x1b[32;1mskip\x1b[0m
+Manage skip rules. Step command doesn't stop in functions that match any rule (like standard
+library internals), it steps out of them transparently. Breakpoints in skipped functions still work.
+
+Available subcommands:
+skip file <glob> - skip functions from source files that match a glob pattern (`*` - any characters, `?` - any character)
+skip function <regex> - skip functions with names (with namespace) that match a regular expression
+skip remove <number> - remove a rule
+skip info - show all rules
+
+Examples of usage:
+skip file \"*/library/core/*\"
+skip function ^(core|alloc|std)::
+";
+
+pub const HELP_HISTORY: &str = "\
+\x1b[32;1mhistory\x1b[0m
+Show a log of program stops in this session: breakpoint and watchpoint hits, steps, signals,
+caught syscalls and program exits, with time, thread and place of each stop.
+History is kept across program restarts.
+
+Available subcommands:
+history - show all stops, the oldest first
+history clear - remove all saved stops
+history stack <n> - save <n> top stack frames with each next stop (0 - don't save stack)
+history log <path> - append each next stop to a text file
+history log off - stop logging into a file
+
+Examples of usage:
+history stack 3
+history log /tmp/stops.log
+";
+
+pub const HELP_CHECKPOINT: &str = "\
+\x1b[32;1mcheckpoint\x1b[0m
+Save a suspended copy of a stopped program (a checkpoint) and restart program from it later,
+so a code region may be replayed many times without restarting a program from scratch.
+Only the thread in focus is saved. Watchpoints and recorded states are removed at restart.
+
+Available subcommands:
+checkpoint - create a checkpoint
+checkpoint info - show checkpoints
+checkpoint restart <id> - kill current program and restart it from a checkpoint
+checkpoint delete <id> - remove a checkpoint
+
+Examples of usage:
+checkpoint
+checkpoint restart 1
+";
+
+pub const HELP_ASYNC: &str = "\
+\x1b[32;1masync\x1b[0m
+Inspect async runtimes of a program. Tokio runtimes are found by threads of runtime schedulers
+(worker threads or a thread that blocks on a runtime).
+
+Available subcommands:
+async backtrace, async bt - show tasks of async runtimes: id, header address, state (idle, scheduled,
+running, complete, cancelled), a type of a future wrapped by a task and a logical async backtrace
+of a task
+async future <expr> - show a logical async backtrace of a future selected by an expression (like a
+local variable)
+
+A logical async backtrace is a chain of futures, from the innermost awaited future to the outermost
+one. Each async fn (or async block) is shown with its state (SuspendN for an await point, Unresumed,
+Returned or Panicked), a function and a source line of an await point, and locals that are alive
+at this await point.
+";
+
 pub const HELP_QUIT: &str = "\
 \x1b[32;1mq, quit\x1b[0m
 Exit the BugStalker, kill debugee before it.
@@ -292,12 +675,18 @@ impl Helper {
             Some(parser::STEP_OVER_COMMAND) | Some(parser::STEP_OVER_COMMAND_SHORT) => {
                 HELP_STEPOVER
             }
+            Some(parser::UNTIL_COMMAND) | Some(parser::ADVANCE_COMMAND) => HELP_UNTIL,
+            Some(parser::JUMP_COMMAND) => HELP_JUMP,
             Some(parser::BREAK_COMMAND) | Some(parser::BREAK_COMMAND_SHORT) => HELP_BREAK,
+            Some(parser::WATCH_COMMAND) => HELP_WATCH,
             Some(parser::SYMBOL_COMMAND) => HELP_SYMBOL,
             Some(parser::MEMORY_COMMAND) | Some(parser::MEMORY_COMMAND_SHORT) => HELP_MEMORY,
+            Some(parser::EXAMINE_COMMAND) => HELP_EXAMINE,
             Some(parser::REGISTER_COMMAND) | Some(parser::REGISTER_COMMAND_SHORT) => HELP_REGISTER,
             Some(parser::THREAD_COMMAND) => HELP_THREAD,
             Some(parser::SHARED_LIB_COMMAND) => HELP_SHARED_LIB,
+            Some(parser::INFO_COMMAND) => HELP_INFO,
+            Some(parser::COVERAGE_COMMAND) => HELP_COVERAGE,
             Some(parser::SOURCE_COMMAND) => HELP_SOURCE,
             Some(parser::ORACLE_COMMAND) => self.oracle_help.get_or_insert_with(|| {
                 let mut help = HELP_ORACLE.to_string();
@@ -305,6 +694,21 @@ impl Helper {
                 oracles.for_each(|oracle| help = format!("{help}{}\n", oracle.help()));
                 help
             }),
+            Some(parser::SCRIPT_COMMAND) => HELP_SCRIPT,
+            Some(parser::SET_COMMAND) => HELP_SET,
+            Some(parser::CALL_COMMAND) => HELP_CALL,
+            Some(parser::RETURN_COMMAND) => HELP_RETURN,
+            Some(parser::DISASM_COMMAND) => HELP_DISASM,
+            Some(parser::CATCH_COMMAND) => HELP_CATCH,
+            Some(parser::SKIP_COMMAND) => HELP_SKIP,
+            Some(parser::HISTORY_COMMAND) => HELP_HISTORY,
+            Some(parser::CHECKPOINT_COMMAND) => HELP_CHECKPOINT,
+            Some(parser::ASYNC_COMMAND) => HELP_ASYNC,
+            Some(parser::RECORD_COMMAND)
+            | Some(parser::REVERSE_STEP_COMMAND)
+            | Some(parser::REVERSE_STEP_COMMAND_SHORT)
+            | Some(parser::REVERSE_CONTINUE_COMMAND)
+            | Some(parser::REVERSE_CONTINUE_COMMAND_SHORT) => HELP_RECORD,
             Some("tui") => HELP_TUI,
             Some("q") | Some("quit") => HELP_QUIT,
             _ => "unknown command",
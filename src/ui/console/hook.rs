@@ -1,33 +1,60 @@
 use crate::debugger::address::RelocatedAddress;
+use crate::debugger::catchpoint::SyscallEvent;
+use crate::debugger::watchpoint::WatchValue;
 use crate::debugger::PlaceDescriptor;
-use crate::debugger::{EventHook, FunctionDie};
+use crate::debugger::{Error, EventHook, FunctionDie, SyscallStop, Tracee};
+use crate::oracle::builtin::syscall::SyscallResult;
+use crate::ui;
 use crate::ui::console::file::FileView;
-use crate::ui::console::print::style::{AddressView, FilePathView, FunctionNameView, KeywordView};
+use crate::ui::console::json;
+use crate::ui::console::print::style::{
+    AddressView, BreakpointNumberView, FunctionNameView, KeywordView, PlaceView,
+};
 use crate::ui::console::print::ExternalPrinter;
+use crate::ui::DebugeeOutReader;
 use crate::version;
+use itertools::Itertools;
 use log::warn;
+use nix::errno::Errno;
 use nix::sys::signal::Signal;
 use nix::unistd::Pid;
+use serde_json::json;
 use std::cell::RefCell;
 use std::ops::Add;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
 
 #[derive(Default)]
 struct Context {
     prev_func: Option<FunctionDie>,
 }
 
+/// Maximum time to wait until debugee output written before a stop is shown.
+const OUTPUT_FLUSH_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Number of the last hit breakpoint, `0` if there is no unprocessed hit.
+static LAST_BREAKPOINT_HIT: AtomicU32 = AtomicU32::new(0);
+
+/// Return a number of a breakpoint hit since the last call (if exists).
+pub fn take_breakpoint_hit() -> Option<u32> {
+    Some(LAST_BREAKPOINT_HIT.swap(0, Ordering::AcqRel)).filter(|&num| num != 0)
+}
+
 pub struct TerminalHook {
     file_view: Rc<FileView>,
     on_install_proc: Box<dyn Fn(Pid)>,
     printer: ExternalPrinter,
     context: RefCell<Context>,
+    /// Debugee stdout and stderr
+    debugee_streams: [DebugeeOutReader; 2],
 }
 
 impl TerminalHook {
     pub fn new(
         printer: ExternalPrinter,
         fv: Rc<FileView>,
+        debugee_streams: [DebugeeOutReader; 2],
         on_install_proc: impl Fn(Pid) + 'static,
     ) -> Self {
         Self {
@@ -35,10 +62,18 @@ impl TerminalHook {
             on_install_proc: Box::new(on_install_proc),
             printer,
             context: RefCell::new(Context::default()),
+            debugee_streams,
         }
     }
 }
 
+fn func_name(func: &FunctionDie) -> String {
+    func.namespace
+        .join("::")
+        .add("::")
+        .add(func.base_attributes.name.as_deref().unwrap_or_default())
+}
+
 impl EventHook for TerminalHook {
     fn on_breakpoint(
         &self,
@@ -46,15 +81,32 @@ impl EventHook for TerminalHook {
         num: u32,
         mb_place: Option<PlaceDescriptor>,
         mb_func: Option<&FunctionDie>,
-    ) -> anyhow::Result<()> {
-        let msg = format!("Hit breakpoint {num} at {}:", AddressView::from(pc));
+    ) -> Result<(), Error> {
+        LAST_BREAKPOINT_HIT.store(num, Ordering::Release);
+
+        if json::enabled() {
+            let mut event = json::place("breakpoint", pc, mb_place.as_ref());
+            event["number"] = num.into();
+            event["function"] = mb_func.map(func_name).into();
+            self.printer.println(event.to_string());
+            return Ok(());
+        }
+
+        let msg = format!(
+            "Hit breakpoint {} at {}:",
+            BreakpointNumberView::from(num),
+            AddressView::from(pc)
+        );
         if let Some(place) = mb_place {
             self.printer.println(format!(
-                "{msg} {}:{}",
-                FilePathView::from(place.file.to_string_lossy()),
-                place.line_number
+                "{msg} {}",
+                PlaceView::new(place.file, place.line_number)
             ));
-            self.printer.print(self.file_view.render_source(&place, 0)?);
+            self.printer.print(
+                self.file_view
+                    .render_source(&place, 0)
+                    .map_err(|e| Error::Hook(e.into()))?,
+            );
         } else {
             self.printer.println(format!("{msg} undefined place"));
         }
@@ -66,29 +118,34 @@ impl EventHook for TerminalHook {
 
     fn on_step(
         &self,
-        _: RelocatedAddress,
+        pc: RelocatedAddress,
         mb_place: Option<PlaceDescriptor>,
         mb_func: Option<&FunctionDie>,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), Error> {
+        if json::enabled() {
+            let mut event = json::place("step", pc, mb_place.as_ref());
+            event["function"] = mb_func.map(func_name).into();
+            self.printer.println(event.to_string());
+            return Ok(());
+        }
+
         if let Some(place) = mb_place {
             if self.context.borrow().prev_func.as_ref() != mb_func {
                 self.context.borrow_mut().prev_func = mb_func.cloned();
 
-                let func_name = mb_func.map(|f| {
-                    f.namespace
-                        .join("::")
-                        .add("::")
-                        .add(f.base_attributes.name.as_deref().unwrap_or_default())
-                });
+                let func_name = mb_func.map(func_name);
 
                 self.printer.println(format!(
-                    "{} at {}:{}",
+                    "{} at {}",
                     FunctionNameView::from(func_name),
-                    FilePathView::from(place.file.to_string_lossy()),
-                    place.line_number,
+                    PlaceView::new(place.file, place.line_number),
                 ));
             }
-            self.printer.print(self.file_view.render_source(&place, 0)?);
+            self.printer.print(
+                self.file_view
+                    .render_source(&place, 0)
+                    .map_err(|e| Error::Hook(e.into()))?,
+            );
         } else {
             self.printer.println("undefined place, go to next");
         }
@@ -96,14 +153,137 @@ impl EventHook for TerminalHook {
         Ok(())
     }
 
+    fn on_watchpoint(
+        &self,
+        pc: RelocatedAddress,
+        num: u32,
+        mb_place: Option<PlaceDescriptor>,
+        mb_func: Option<&FunctionDie>,
+        old: &WatchValue,
+        new: &WatchValue,
+    ) -> Result<(), Error> {
+        if json::enabled() {
+            let mut event = json::place("watchpoint", pc, mb_place.as_ref());
+            event["number"] = num.into();
+            event["function"] = mb_func.map(func_name).into();
+            event["old"] = old.to_string().into();
+            event["new"] = new.to_string().into();
+            self.printer.println(event.to_string());
+            return Ok(());
+        }
+
+        let msg = format!(
+            "Hit watchpoint {} at {}:",
+            BreakpointNumberView::from(num),
+            AddressView::from(pc)
+        );
+        match mb_place {
+            Some(ref place) => self.printer.println(format!(
+                "{msg} {}",
+                PlaceView::new(place.file, place.line_number)
+            )),
+            None => self.printer.println(format!("{msg} undefined place")),
+        }
+        self.printer.println(format!("Old value: {old}"));
+        self.printer.println(format!("New value: {new}"));
+        if let Some(place) = mb_place {
+            self.printer.print(
+                self.file_view
+                    .render_source(&place, 0)
+                    .map_err(|e| Error::Hook(e.into()))?,
+            );
+        }
+
+        self.context.borrow_mut().prev_func = mb_func.cloned();
+
+        Ok(())
+    }
+
     fn on_signal(&self, signal: Signal) {
+        if json::enabled() {
+            let event = json!({ "event": "signal", "signal": signal.as_str() });
+            self.printer.println(event.to_string());
+            return;
+        }
         self.printer.println(format!(
             "Signal {} received, debugee stopped",
             KeywordView::from(signal)
         ));
     }
 
+    fn on_syscall_catch(&self, pc: RelocatedAddress, event: &SyscallEvent) {
+        let name = event
+            .name
+            .map_or(format!("syscall_{}", event.nr), ToString::to_string);
+
+        if json::enabled() {
+            let mut json_event = json!({
+                "event": "syscall",
+                "pc": pc.to_string(),
+                "pid": event.pid.as_raw(),
+                "nr": event.nr,
+                "name": name,
+            });
+            match event.stop {
+                SyscallStop::Entry { args, .. } => {
+                    json_event["point"] = "entry".into();
+                    json_event["args"] = args.to_vec().into();
+                }
+                SyscallStop::Exit { rval, is_error } => {
+                    json_event["point"] = "exit".into();
+                    json_event["rval"] = rval.into();
+                    json_event["is_error"] = is_error.into();
+                }
+            }
+            self.printer.println(json_event.to_string());
+            return;
+        }
+
+        let msg = format!(
+            "Catch syscall {} ({}) at {}, thread {}",
+            FunctionNameView::from(name),
+            event.nr,
+            AddressView::from(pc),
+            event.pid
+        );
+        match event.stop {
+            SyscallStop::Entry { args, .. } => {
+                let args = args.iter().map(|arg| format!("{arg:#x}")).join(", ");
+                self.printer.println(format!("{msg}: call with ({args})"));
+            }
+            SyscallStop::Exit { rval, is_error } => {
+                let result = if is_error {
+                    SyscallResult::Err(Errno::from_i32(-rval as i32))
+                } else {
+                    SyscallResult::Ok(rval)
+                };
+                self.printer.println(format!("{msg}: return {result}"));
+            }
+        }
+    }
+
+    fn on_thread_stop(&self, thread: &Tracee) {
+        if json::enabled() {
+            let event = json!({
+                "event": "thread_stop",
+                "thread": thread.number,
+                "pid": thread.pid.as_raw(),
+            });
+            self.printer.println(event.to_string());
+            return;
+        }
+        self.printer.println(format!(
+            "Thread #{} ({}) stopped, other threads are running",
+            thread.number, thread.pid
+        ));
+    }
+
     fn on_exit(&self, code: i32) {
+        if json::enabled() {
+            let event = json!({ "event": "exit", "code": code });
+            self.printer.println(event.to_string());
+            return;
+        }
         self.printer.println(format!(
             "Program exit with code: {}",
             KeywordView::from(code)
@@ -120,4 +300,9 @@ impl EventHook for TerminalHook {
         }
         (self.on_install_proc)(pid)
     }
+
+    fn on_output_flush(&self) {
+        // debugee output is printed by a separate threads, wait until all written data is read
+        ui::wait_output_read(&self.debugee_streams, OUTPUT_FLUSH_TIMEOUT);
+    }
 }
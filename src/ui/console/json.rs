@@ -0,0 +1,162 @@
+//! Machine-readable (JSON) representation of command results and debugger events.
+//!
+//! In JSON mode (`--json` argument) each result or event is printed as a single-line JSON object.
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::fd::FileDescriptor;
+use crate::debugger::history::StopRecord;
+use crate::debugger::json::ToJson;
+use crate::debugger::r#async::{AsyncFrame, AsyncTask};
+use crate::debugger::variable::VariableIR;
+use crate::debugger::watchpoint::Watchpoint;
+use crate::debugger::{
+    BreakpointView, CallTarget, Disassembly, FrameInfo, FunctionAssembly, FunctionInfo,
+    PlaceDescriptor, RegionInfo, SignalRecord, Symbol, ThreadSnapshot,
+};
+use crate::ui::command::register::RegisterValue;
+use crate::ui::command::CommandError;
+use serde_json::{json, Value};
+
+/// Return `true` if JSON mode is enabled.
+pub fn enabled() -> bool {
+    crate::ui::config::current().json
+}
+
+pub fn variable(var: &VariableIR) -> Value {
+    var.to_json()
+}
+
+pub fn breakpoint(action: &str, brkpt: &BreakpointView) -> Value {
+    let mut result = brkpt.to_json();
+    result["action"] = action.into();
+    result
+}
+
+pub fn watchpoint(action: &str, wp: &Watchpoint) -> Value {
+    let mut result = wp.to_json();
+    result["action"] = action.into();
+    result
+}
+
+pub fn thread(thread: &ThreadSnapshot) -> Value {
+    thread.to_json()
+}
+
+pub fn frame_info(info: &FrameInfo) -> Value {
+    info.to_json()
+}
+
+pub fn register(register: &RegisterValue) -> Value {
+    json!({ "register": register.register_name, "value": register.value })
+}
+
+pub fn symbol(symbol: &Symbol) -> Value {
+    json!({
+        "name": symbol.name,
+        "raw_name": symbol.raw_name,
+        "kind": format!("{:?}", symbol.kind),
+        "address": symbol.addr.to_string(),
+    })
+}
+
+pub fn function(func: &FunctionInfo) -> Value {
+    func.to_json()
+}
+
+pub fn async_task(task: &AsyncTask) -> Value {
+    task.to_json()
+}
+
+pub fn async_frames(frames: &[AsyncFrame]) -> Value {
+    frames.to_json()
+}
+
+pub fn shared_lib(lib: &RegionInfo) -> Value {
+    json!({
+        "path": lib.path.to_string_lossy(),
+        "has_debug_info": lib.has_debug_info,
+        "from": lib.range.as_ref().map(|range| range.from.to_string()),
+        "to": lib.range.as_ref().map(|range| range.to.to_string()),
+    })
+}
+
+pub fn fd(fd: &FileDescriptor) -> Value {
+    json!({
+        "fd": fd.fd,
+        "target": fd.target.to_string(),
+        "flags": fd.flag_names(),
+        "pos": fd.pos,
+    })
+}
+
+pub fn signal(record: &SignalRecord) -> Value {
+    json!({
+        "signal": record.signal.as_str(),
+        "thread": record.thread.as_raw(),
+        "sender": record.sender.map(|pid| pid.as_raw()),
+        "code": record.code,
+        "time": record.time.to_rfc3339(),
+        "disposition": record.disposition.to_string(),
+    })
+}
+
+pub fn assembly(asm: &FunctionAssembly) -> Value {
+    json!({
+        "function": asm.name,
+        "instructions": asm.instructions.iter().map(|ins| json!({
+            "address": ins.address.to_string(),
+            "mnemonic": ins.mnemonic,
+            "operands": ins.operands,
+            "in_focus": ins.address == asm.addr_in_focus,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+pub fn disassembly(disasm: &Disassembly) -> Value {
+    json!({
+        "function": disasm.name,
+        "instructions": disasm.instructions.iter().map(|ins| json!({
+            "address": ins.address.to_string(),
+            "mnemonic": ins.mnemonic,
+            "operands": ins.operands,
+            "file": ins.place.as_ref().map(|place| place.file.to_string_lossy()),
+            "line": ins.place.as_ref().map(|place| place.line_number),
+            "is_pc": ins.is_pc,
+            "breakpoint": ins.breakpoint,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+pub fn call_target(target: &CallTarget) -> Value {
+    json!({
+        "function": target.name,
+        "call_address": target.call_addr.to_string(),
+        "callee_address": target.callee_addr.map(|addr| addr.to_string()),
+    })
+}
+
+pub fn stop_record(record: &StopRecord) -> Value {
+    json!({
+        "time": record.time.to_rfc3339(),
+        "event": record.event.to_string(),
+        "thread": record.pid.as_raw(),
+        "address": record.pc.map(|pc| pc.to_string()),
+        "function": record.function,
+        "file": record.place.as_ref().map(|place| place.file.to_string_lossy()),
+        "line": record.place.as_ref().map(|place| place.line_number),
+        "stack": record.stack,
+    })
+}
+
+pub fn place(event: &str, pc: RelocatedAddress, place: Option<&PlaceDescriptor>) -> Value {
+    json!({
+        "event": event,
+        "address": pc.to_string(),
+        "file": place.map(|place| place.file.to_string_lossy()),
+        "line": place.map(|place| place.line_number),
+    })
+}
+
+pub fn error(e: &CommandError) -> Value {
+    json!({ "error": format!("{e:#}"), "category": e.category().to_string() })
+}
@@ -1,45 +1,80 @@
 use crate::debugger;
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::catchpoint::SyscallFilter;
+use crate::debugger::checkpoint::CheckpointInfo;
 use crate::debugger::process::{Child, Installed};
+use crate::debugger::progress::ProgressObserver;
+use crate::debugger::r#async::AsyncFrame;
+use crate::debugger::source;
+use crate::debugger::symbol;
+use crate::debugger::symbol::SymbolNameStyle;
+use crate::debugger::variable::render::{RenderRepr, ValueLayout};
 use crate::debugger::variable::select::{VariableSelector, DQE};
+use crate::debugger::variable::VariableIR;
 use crate::debugger::{Debugger, DebuggerBuilder};
+use crate::oracle::builtin::syscall::syscall_name;
 use crate::ui::command::arguments::Handler as ArgumentsHandler;
 use crate::ui::command::backtrace::Handler as BacktraceHandler;
+use crate::ui::command::catch::ExecutionResult as CatchResult;
+use crate::ui::command::checkpoint::ExecutionResult as CheckpointResult;
+use crate::ui::command::coverage::ExecutionResult as CoverageResult;
+use crate::ui::command::disasm::Handler as DisasmHandler;
+use crate::ui::command::examine::Handler as ExamineHandler;
+use crate::ui::command::export::Handler as ExportHandler;
 use crate::ui::command::frame::ExecutionResult as FrameResult;
 use crate::ui::command::frame::Handler as FrameHandler;
+use crate::ui::command::history::ExecutionResult as HistoryResult;
+use crate::ui::command::info::ExecutionResult as InfoResult;
+use crate::ui::command::info::Handler as InfoHandler;
 use crate::ui::command::memory::Handler as MemoryHandler;
+use crate::ui::command::r#async::ExecutionResult as AsyncResult;
 use crate::ui::command::r#break::ExecutionResult;
 use crate::ui::command::r#break::Handler as BreakpointHandler;
 use crate::ui::command::r#continue::Handler as ContinueHandler;
+use crate::ui::command::record::ExecutionResult as RecordResult;
 use crate::ui::command::register::Handler as RegisterHandler;
 use crate::ui::command::run::Handler as RunHandler;
 use crate::ui::command::sharedlib::Handler as SharedlibHandler;
+use crate::ui::command::skip::ExecutionResult as SkipResult;
 use crate::ui::command::source_code::{DisAsmHandler, FunctionLineRangeHandler};
 use crate::ui::command::symbol::Handler as SymbolHandler;
 use crate::ui::command::thread::ExecutionResult as ThreadResult;
 use crate::ui::command::variables::Handler as VariablesHandler;
+use crate::ui::command::watch::ExecutionResult as WatchResult;
+use crate::ui::command::watch::Handler as WatchHandler;
 use crate::ui::command::{
-    r#break, source_code, step_instruction, step_into, step_out, step_over, CommandError,
+    jump, parser, r#break, set, source_code, step_instruction, step_into, step_out, step_over,
+    until, CommandError,
 };
 use crate::ui::command::{run, Command};
+use crate::ui::config::Verbosity;
 use crate::ui::console::editor::{create_editor, CommandCompleter, RLHelper};
 use crate::ui::console::file::FileView;
 use crate::ui::console::help::*;
 use crate::ui::console::hook::TerminalHook;
 use crate::ui::console::print::style::{
-    AddressView, AsmInstructionView, AsmOperandsView, ErrorView, FilePathView, FunctionNameView,
+    AddressView, AsmInstructionView, AsmOperandsView, BreakpointNumberView, ErrorView,
+    FilePathView, FunctionNameView, KeywordView, PlaceView,
 };
 use crate::ui::console::print::ExternalPrinter;
+use crate::ui::console::progress::ProgressBar;
 use crate::ui::console::variable::render_variable;
+use crate::ui::script::ScriptEngine;
+use crate::ui::style;
 use crate::ui::DebugeeOutReader;
-use crate::ui::{command, supervisor};
+use crate::ui::{command, config, supervisor};
 use crossterm::style::{Color, Stylize};
-use debugger::Error;
+use debugger::{Error, ErrorCategory};
 use nix::sys::signal::{kill, Signal};
 use nix::unistd::Pid;
+use object::SymbolKind;
+use r#break::BreakpointIdentity;
 use r#break::Command as BreakpointCommand;
 use rustyline::error::ReadlineError;
 use rustyline::history::MemHistory;
 use rustyline::Editor;
+use serde_json::json;
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
 use std::process::exit;
 use std::rc::Rc;
@@ -54,7 +89,12 @@ mod editor;
 pub mod file;
 mod help;
 pub mod hook;
+mod json;
+mod pager;
 pub mod print;
+mod progress;
+mod remote;
+mod transcript;
 mod variable;
 
 const WELCOME_TEXT: &str = r#"
@@ -62,15 +102,26 @@ BugStalker greets
 "#;
 const PROMT: &str = "(bs) ";
 const PROMT_YES_NO: &str = "(bs y/n) ";
+/// Prompt for continuation of multi-line input.
+const PROMT_CONTINUATION: &str = "... ";
 
 type BSEditor = Editor<RLHelper, MemHistory>;
 
+/// Command failed in batch mode.
+#[derive(Debug, thiserror::Error)]
+#[error("command `{command}` failed")]
+pub struct BatchCommandError {
+    pub command: String,
+    pub category: ErrorCategory,
+}
+
 /// Shared debugee process pid, installed by hook or at console ui creation
 static DEBUGEE_PID: AtomicI32 = AtomicI32::new(-1);
 
 pub struct AppBuilder {
     debugee_out: DebugeeOutReader,
     debugee_err: DebugeeOutReader,
+    batch: Option<Vec<String>>,
 }
 
 impl AppBuilder {
@@ -78,6 +129,20 @@ impl AppBuilder {
         Self {
             debugee_out,
             debugee_err,
+            batch: None,
+        }
+    }
+
+    /// Run application in batch (non-interactive) mode, application executes
+    /// commands one by one and exits after the last one.
+    ///
+    /// # Arguments
+    ///
+    /// * `commands`: list of commands
+    pub fn batch(self, commands: Vec<String>) -> Self {
+        Self {
+            batch: Some(commands),
+            ..self
         }
     }
 
@@ -89,18 +154,21 @@ impl AppBuilder {
         let (user_cmd_tx, user_cmd_rx) = mpsc::sync_channel::<UserAction>(0);
         let mut editor = create_editor(PROMT, oracles)?;
         let file_view = Rc::new(FileView::new());
-        let hook = TerminalHook::new(
-            ExternalPrinter::new(&mut editor)?,
-            file_view.clone(),
-            move |pid| DEBUGEE_PID.store(pid.as_raw(), Ordering::Release),
-        );
+        let hook_printer = match self.batch {
+            None => ExternalPrinter::new(&mut editor)?,
+            Some(_) => ExternalPrinter::stdout(),
+        };
+        let streams = [self.debugee_out.clone(), self.debugee_err.clone()];
+        let hook = TerminalHook::new(hook_printer, file_view.clone(), streams, move |pid| {
+            DEBUGEE_PID.store(pid.as_raw(), Ordering::Release)
+        });
 
         let debugger = debugger_lazy(hook)?;
         if let Some(h) = editor.helper_mut() {
-            h.completer
-                .lock()
-                .unwrap()
-                .replace_file_hints(debugger.known_files().cloned())
+            let mut completer = h.completer.lock().unwrap();
+            completer.replace_file_hints(debugger.known_files().cloned());
+            completer.replace_function_hints(function_names(&debugger));
+            completer.replace_register_hints(debugger.complete_register(""));
         }
 
         Ok(TerminalApplication {
@@ -111,6 +179,7 @@ impl AppBuilder {
             debugee_err: self.debugee_err,
             user_act_tx: user_cmd_tx,
             user_act_rx: user_cmd_rx,
+            batch: self.batch,
         })
     }
 
@@ -157,6 +226,8 @@ enum UserAction {
     ChangeMode,
     /// Do nothing
     Nop,
+    /// Request from a remote client
+    Remote(remote::Request),
 }
 
 enum EditorMode {
@@ -172,18 +243,37 @@ pub struct TerminalApplication {
     debugee_err: DebugeeOutReader,
     user_act_tx: SyncSender<UserAction>,
     user_act_rx: Receiver<UserAction>,
+    batch: Option<Vec<String>>,
 }
 
 pub static HELLO_ONCE: Once = Once::new();
 
 impl TerminalApplication {
+    fn printer(&mut self) -> rustyline::Result<ExternalPrinter> {
+        match self.batch {
+            None => ExternalPrinter::new(&mut self.editor),
+            Some(_) => Ok(ExternalPrinter::stdout()),
+        }
+    }
+
     pub fn run(mut self) -> anyhow::Result<supervisor::ControlFlow> {
-        let logger = env_logger::Logger::from_default_env();
+        let verbosity = config::current().verbosity;
+        let logger = env_logger::Builder::new()
+            .filter_level(verbosity.log_level())
+            .parse_default_env()
+            .write_style(style::log_write_style())
+            .build();
         let filter = logger.filter();
         crate::log::LOGGER_SWITCHER.switch(logger, filter);
 
+        debugger::progress::set_observer(
+            ProgressBar::new()
+                .filter(|_| verbosity != Verbosity::Quiet)
+                .map(|bar| Box::new(bar) as Box<dyn ProgressObserver>),
+        );
+
         macro_rules! print_out {
-            ($stream: expr, $format: tt, $printer: expr, $cancel: expr) => {{
+            ($stream: expr, $stream_name: literal, $format: tt, $printer: expr, $cancel: expr) => {{
                 let mut stream = BufReader::new($stream);
                 loop {
                     if $cancel.load(Ordering::SeqCst) {
@@ -204,7 +294,12 @@ impl TerminalApplication {
                     if size == 0 {
                         return;
                     }
-                    $printer.print(format!($format, line))
+                    if json::enabled() {
+                        let event = json!({ "event": "output", "stream": $stream_name, "text": line });
+                        $printer.println(event.to_string());
+                    } else {
+                        $printer.print(format!($format, line))
+                    }
                 }
             }};
         }
@@ -216,16 +311,19 @@ impl TerminalApplication {
             let cancel2 = cancel.clone();
 
             let stdout = TimeoutReader::new(self.debugee_out.clone(), Duration::from_millis(1));
-            let stdout_printer = ExternalPrinter::new(&mut self.editor)?;
-            thread::spawn(move || print_out!(stdout, "{}", stdout_printer, cancel1));
+            let stdout_printer = self.printer()?;
+            thread::spawn(move || print_out!(stdout, "stdout", "{}", stdout_printer, cancel1));
 
             let stderr = TimeoutReader::new(self.debugee_err.clone(), Duration::from_millis(1));
-            let stderr_printer = ExternalPrinter::new(&mut self.editor)?;
-            thread::spawn(move || print_out!(stderr, "\x1b[31m{}", stderr_printer, cancel2));
+            let stderr_printer = self.printer()?;
+            thread::spawn(move || {
+                print_out!(stderr, "stderr", "\x1b[31m{}", stderr_printer, cancel2)
+            });
         };
 
         let (ready_to_next_command_tx, ready_to_next_command_rx) = mpsc::channel();
 
+        let printer = Rc::new(self.printer()?);
         let app_loop = AppLoop {
             debugger: self.debugger,
             file_view: self.file_view,
@@ -237,32 +335,56 @@ impl TerminalApplication {
                     .expect("helper must exists")
                     .completer,
             ),
-            printer: ExternalPrinter::new(&mut self.editor)?,
+            printer,
             debugee_out: self.debugee_out.clone(),
             debugee_err: self.debugee_err.clone(),
             cancel_output_flag: cancel,
             ready_to_next_command_tx,
             helper: Default::default(),
+            script_engine: None,
+            breakpoint_scripts: HashMap::new(),
+            batch: self.batch.is_some(),
+            remote: false,
+            last_command: None,
         };
 
         static CTRLC_ONCE: Once = Once::new();
         CTRLC_ONCE.call_once(|| {
-            // this handler called only if debugee running, otherwise
-            // ctrl+c will handle by `readline`
+            // this handler called only if debugee running or a command in progress,
+            // otherwise ctrl+c will handle by `readline`
             ctrlc::set_handler(move || {
+                // cancel long-running debugger operation if exists,
+                // interrupt debugee otherwise
+                if debugger::progress::cancel() {
+                    return;
+                }
                 let pid = Pid::from_raw(DEBUGEE_PID.load(Ordering::Acquire));
                 _ = kill(pid, Signal::SIGINT);
             })
             .expect("error setting Ctrl-C handler")
         });
 
+        if let Some(commands) = self.batch {
+            return app_loop.run_batch(commands);
+        }
+
         let error_printer = ExternalPrinter::new(&mut self.editor)?;
+        if let Some(ref path) = config::current().remote {
+            if let Err(e) = remote::serve(path, self.user_act_tx.clone()) {
+                error_printer.println(ErrorView::from(format!(
+                    "Remote control socket {}: {e}",
+                    path.display()
+                )));
+            }
+        }
         let mut editor = self.editor;
         {
             let control_tx = self.user_act_tx.clone();
             thread::spawn(move || {
                 HELLO_ONCE.call_once(|| {
-                    println!("{WELCOME_TEXT}");
+                    if verbosity != Verbosity::Quiet && !json::enabled() {
+                        println!("{WELCOME_TEXT}");
+                    }
                 });
 
                 loop {
@@ -276,7 +398,19 @@ impl TerminalApplication {
                         editor_helper.colored_prompt = format!("{}", promt.with(Color::DarkGreen));
                     }
 
-                    let line = editor.readline(promt);
+                    let line = editor.readline(promt).and_then(|mut input| {
+                        // input with unclosed brackets continues on the next line
+                        while parser::is_incomplete(&input) {
+                            if let Some(editor_helper) = editor.helper_mut() {
+                                editor_helper.colored_prompt =
+                                    format!("{}", PROMT_CONTINUATION.with(Color::DarkGreen));
+                            }
+                            let continuation = editor.readline(PROMT_CONTINUATION)?;
+                            input.push('\n');
+                            input.push_str(&continuation);
+                        }
+                        Ok(input)
+                    });
                     match line {
                         Ok(input) => {
                             if input == "q" || input == "quit" {
@@ -322,25 +456,107 @@ impl TerminalApplication {
     }
 }
 
+/// Return function names from symbol tables (without a hash suffix of rust symbols).
+fn function_names(debugger: &Debugger) -> Vec<String> {
+    debugger
+        .search_symbols(".")
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|symbol| symbol.kind == SymbolKind::Text)
+        .map(|symbol| match symbol.name.rsplit_once("::h") {
+            Some((name, hash))
+                if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) =>
+            {
+                name.to_string()
+            }
+            _ => symbol.name,
+        })
+        .collect()
+}
+
+/// Render frame arguments in a single line like `(a=1, s=...)`, only scalar values and pointers
+/// are shown, other values are replaced by `...`.
+fn frame_args_view(args: &[VariableIR]) -> String {
+    let args = args
+        .iter()
+        .map(|arg| {
+            let value = match arg.value() {
+                Some(ValueLayout::PreRendered(value)) => value.to_string(),
+                Some(ValueLayout::Referential { addr }) => {
+                    RelocatedAddress::from(addr as usize).to_string()
+                }
+                _ => "...".to_string(),
+            };
+            format!("{}={value}", arg.name())
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("({args})")
+}
+
+/// Render a logical async backtrace, a line for each future followed by its live locals.
+///
+/// # Arguments
+///
+/// * `frames`: async backtrace, innermost future first
+/// * `indent`: prefix of each line
+fn async_frames_output(frames: Vec<AsyncFrame>, indent: &str) -> Vec<String> {
+    let mut output = vec![];
+    for (num, frame) in frames.into_iter().enumerate() {
+        let mut line = format!(
+            "{indent}#{num} {}",
+            FunctionNameView::from(frame.function.or(frame.future_type))
+        );
+        if let Some(state) = frame.state {
+            line.push_str(&format!(" [{state}]"));
+        }
+        if let Some((file, line_number)) = frame.place.as_ref() {
+            line.push_str(&format!(" at {}", PlaceView::new(file, *line_number)));
+        }
+        output.push(line);
+
+        for local in frame.locals {
+            let local =
+                render_variable(&local).unwrap_or(print::style::UNKNOWN_PLACEHOLDER.to_string());
+            output.extend(local.lines().map(|l| format!("{indent}    {l}")));
+        }
+    }
+    output
+}
+
 struct AppLoop {
     debugger: Debugger,
     file_view: Rc<FileView>,
     user_input_rx: Receiver<UserAction>,
-    printer: ExternalPrinter,
+    printer: Rc<ExternalPrinter>,
     completer: Arc<Mutex<CommandCompleter>>,
     debugee_out: DebugeeOutReader,
     debugee_err: DebugeeOutReader,
     cancel_output_flag: Arc<AtomicBool>,
     helper: Helper,
     ready_to_next_command_tx: mpsc::Sender<EditorMode>,
+    script_engine: Option<ScriptEngine>,
+    /// Scripts executed when a breakpoint is hit (`break commands`), by breakpoint number
+    breakpoint_scripts: HashMap<u32, String>,
+    batch: bool,
+    /// A command from a remote client in progress
+    remote: bool,
+    /// Last parsed command, repeated if user input is empty
+    last_command: Option<Command>,
 }
 
 impl AppLoop {
     fn yes(&self, question: &str) -> bool {
         self.printer.println(question);
+        if self.batch || self.remote {
+            // there is no user in batch mode (or for remote commands),
+            // so the answer is always negative
+            self.printer.println("n");
+            return false;
+        }
 
+        _ = self.ready_to_next_command_tx.send(EditorMode::YesNo);
         loop {
-            _ = self.ready_to_next_command_tx.send(EditorMode::YesNo);
             let act = self
                 .user_input_rx
                 .recv()
@@ -349,8 +565,15 @@ impl AppLoop {
                 UserAction::Cmd(cmd) => match cmd.to_lowercase().as_str() {
                     "y" | "yes" => true,
                     "n" | "no" => false,
-                    _ => continue,
+                    _ => {
+                        _ = self.ready_to_next_command_tx.send(EditorMode::YesNo);
+                        continue;
+                    }
                 },
+                UserAction::Remote(request) => {
+                    request.reject();
+                    continue;
+                }
                 UserAction::Terminate | UserAction::ChangeMode | UserAction::Nop => false,
             };
         }
@@ -370,39 +593,177 @@ impl AppLoop {
         Ok(())
     }
 
-    fn handle_command(&mut self, cmd: &str) -> Result<(), CommandError> {
-        if cmd.is_empty() {
-            return Ok(());
+    /// Print a single-line JSON object (JSON mode).
+    fn print_json(&self, value: serde_json::Value) {
+        self.printer.println(value.to_string());
+    }
+
+    /// Render assembly instructions grouped by source lines,
+    /// a program counter and breakpoints are marked.
+    fn render_asm(instructions: Vec<debugger::AnnotatedInstruction>) -> Vec<String> {
+        let mut output = vec![];
+        let mut last_place = None;
+        for ins in instructions {
+            if let Some(place) = ins.place {
+                let place = (place.file, place.line_number);
+                if last_place.as_ref() != Some(&place) {
+                    output.push(format!("{}", PlaceView::new(&place.0, place.1)));
+                    last_place = Some(place);
+                }
+            }
+
+            let marker = if ins.is_pc { "=>" } else { "  " };
+            let mut instruction_view = format!(
+                "{marker} {} {} {}",
+                AddressView::from(ins.address),
+                AsmInstructionView::from(ins.mnemonic),
+                AsmOperandsView::from(ins.operands),
+            );
+            if let Some(number) = ins.breakpoint {
+                instruction_view.push_str(&format!(
+                    " <- Breakpoint {}",
+                    BreakpointNumberView::from(number)
+                ));
+            }
+
+            if ins.is_pc {
+                output.push(format!("{}", instruction_view.bold()));
+            } else {
+                output.push(instruction_view);
+            }
         }
+        output
+    }
 
-        match Command::parse(cmd)? {
-            Command::PrintVariables(print_var_command) => VariablesHandler::new(&self.debugger)
-                .handle(print_var_command)?
+    /// Print command output, output that doesn't fit into the terminal shown through a pager.
+    fn print_paged(&self, output: Vec<String>) {
+        if self.batch || self.remote || !pager::page(&output) {
+            output
                 .into_iter()
-                .for_each(|var| {
-                    self.printer.println(
+                .for_each(|line| self.printer.println(line));
+        } else {
+            // output shown through a pager, but it still must be recorded
+            output
+                .iter()
+                .for_each(|line| transcript::record(&format!("{line}\n")));
+        }
+    }
+
+    /// Execute scripts of hit breakpoints (`break commands`). A script may continue a program,
+    /// so scripts are executed until a program stops at a breakpoint without a script.
+    fn run_breakpoint_scripts(&mut self) {
+        while let Some(number) = hook::take_breakpoint_hit() {
+            let Some(script) = self.breakpoint_scripts.get(&number).cloned() else {
+                return;
+            };
+            let printer = self.printer.clone();
+            let engine = self
+                .script_engine
+                .get_or_insert_with(|| ScriptEngine::new(move |s| printer.println(s)));
+            if let Err(e) = engine.run(&mut self.debugger, &script) {
+                self.printer.println(ErrorView::from(format!(
+                    "Breakpoint {number} commands failed: {e}"
+                )));
+                return;
+            }
+        }
+    }
+
+    fn handle_command(&mut self, cmd: &str) -> Result<(), CommandError> {
+        // drop hits that are not caused by this command (for example, hits in tui mode)
+        _ = hook::take_breakpoint_hit();
+        let result = self.execute_command(cmd);
+        self.run_breakpoint_scripts();
+        result
+    }
+
+    fn execute_command(&mut self, cmd: &str) -> Result<(), CommandError> {
+        let command = if cmd.is_empty() {
+            // empty input repeats the last command
+            match self.last_command.take() {
+                Some(command) if command.is_repeatable() => command,
+                _ => return Ok(()),
+            }
+        } else {
+            let cmd = config::current().init.expand_alias(cmd);
+            Command::parse(&cmd)?
+        };
+        self.last_command = Some(command.clone());
+
+        match command {
+            Command::PrintVariables(print_var_command) => {
+                let vars = VariablesHandler::new(&self.debugger).handle(print_var_command)?;
+                if json::enabled() {
+                    vars.iter()
+                        .for_each(|var| self.print_json(json::variable(var)));
+                    return Ok(());
+                }
+                let output = vars
+                    .into_iter()
+                    .map(|var| {
                         render_variable(&var)
-                            .unwrap_or(print::style::UNKNOWN_PLACEHOLDER.to_string()),
-                    );
-                }),
-            Command::PrintArguments(print_arg_command) => ArgumentsHandler::new(&self.debugger)
-                .handle(print_arg_command)?
-                .into_iter()
-                .for_each(|arg| {
-                    self.printer.println(
+                            .unwrap_or(print::style::UNKNOWN_PLACEHOLDER.to_string())
+                    })
+                    .collect();
+                self.print_paged(output);
+            }
+            Command::PrintExpression(expr) => {
+                let var = VariablesHandler::new(&self.debugger).handle_expression(&expr)?;
+                if json::enabled() {
+                    self.print_json(json::variable(&var));
+                    return Ok(());
+                }
+                self.printer.println(
+                    render_variable(&var).unwrap_or(print::style::UNKNOWN_PLACEHOLDER.to_string()),
+                );
+            }
+            Command::ExportVariables(export_command) => {
+                let path = export_command.path.clone();
+                let count = ExportHandler::new(&self.debugger).handle(export_command)?;
+                if json::enabled() {
+                    self.print_json(json!({
+                        "action": "export",
+                        "file": path.to_string_lossy(),
+                        "count": count,
+                    }));
+                } else {
+                    self.printer.println(format!(
+                        "Exported {count} items into {}",
+                        FilePathView::from(path.display())
+                    ));
+                }
+            }
+            Command::PrintArguments(print_arg_command) => {
+                let args = ArgumentsHandler::new(&self.debugger).handle(print_arg_command)?;
+                if json::enabled() {
+                    args.iter()
+                        .for_each(|arg| self.print_json(json::variable(arg)));
+                    return Ok(());
+                }
+                let output = args
+                    .into_iter()
+                    .map(|arg| {
                         render_variable(&arg)
-                            .unwrap_or(print::style::UNKNOWN_PLACEHOLDER.to_string()),
-                    );
-                }),
+                            .unwrap_or(print::style::UNKNOWN_PLACEHOLDER.to_string())
+                    })
+                    .collect();
+                self.print_paged(output);
+            }
             Command::PrintBacktrace(cmd) => {
-                let bt = BacktraceHandler::new(&self.debugger).handle(cmd)?;
+                let bt = BacktraceHandler::new(&mut self.debugger).handle(cmd)?;
+                if json::enabled() {
+                    bt.iter()
+                        .for_each(|thread| self.print_json(json::thread(thread)));
+                    return Ok(());
+                }
+                let mut output = vec![];
                 bt.into_iter().for_each(|thread| {
                     let ip = thread
                         .bt
                         .as_ref()
                         .and_then(|bt| bt.first().map(|f| f.ip.to_string()));
 
-                    self.printer.println(format!(
+                    output.push(format!(
                         "thread #{}, {} - {}",
                         thread.thread.number,
                         thread.thread.pid,
@@ -419,24 +780,37 @@ impl AppLoop {
 
                             let fn_ip_or_zero = frame.fn_start_ip.unwrap_or_default();
 
+                            let args = frame
+                                .args
+                                .as_deref()
+                                .map(frame_args_view)
+                                .unwrap_or_default();
                             let mut frame_info = format!(
-                                "#{frame_num} {} - {} ({} + {:#X})",
+                                "#{frame_num} {} - {}{args} ({} + {:#X})",
                                 AddressView::from(frame.ip),
                                 FunctionNameView::from(frame.func_name),
                                 AddressView::from(frame.fn_start_ip),
                                 frame.ip.as_u64().saturating_sub(fn_ip_or_zero.as_u64()),
                             );
+                            if let Some(place) = frame.place.as_ref() {
+                                frame_info.push_str(&format!(
+                                    " at {}:{}",
+                                    PlaceView::new(&place.file, place.line_number),
+                                    place.column_number
+                                ));
+                            }
                             if thread.focus_frame == Some(frame_num) {
                                 frame_info = frame_info.bold().to_string();
                             }
 
-                            self.printer.println(frame_info);
+                            output.push(frame_info);
                             if user_bt_end {
                                 break;
                             }
                         }
                     }
                 });
+                self.print_paged(output);
             }
             Command::Continue => {
                 ContinueHandler::new(&mut self.debugger).handle()?;
@@ -445,6 +819,12 @@ impl AppLoop {
             Command::Frame(cmd) => {
                 let result = FrameHandler::new(&mut self.debugger).handle(cmd)?;
                 match result {
+                    FrameResult::FrameInfo(frame) if json::enabled() => {
+                        self.print_json(json::frame_info(&frame))
+                    }
+                    FrameResult::BroughtIntoFocus(num) if json::enabled() => {
+                        self.print_json(json!({ "frame": num }))
+                    }
                     FrameResult::FrameInfo(frame) => {
                         self.printer.println(format!(
                             "frame #{} ({})",
@@ -473,13 +853,51 @@ impl AppLoop {
                 }
             },
             Command::StepInstruction => {
-                step_instruction::Handler::new(&mut self.debugger).handle()?;
+                let asm = step_instruction::Handler::new(&mut self.debugger).handle()?;
+                // assembly is shown only if there is no source code for a new location
+                let asm = asm.filter(|asm| {
+                    asm.instructions
+                        .iter()
+                        .any(|ins| ins.is_pc && ins.place.is_none())
+                });
+                if let Some(asm) = asm {
+                    if json::enabled() {
+                        self.print_json(json::disassembly(&asm));
+                    } else {
+                        Self::render_asm(asm.instructions)
+                            .into_iter()
+                            .for_each(|line| self.printer.println(line));
+                    }
+                }
                 _ = self.update_completer_variables();
             }
             Command::StepInto => {
                 step_into::Handler::new(&mut self.debugger).handle()?;
                 _ = self.update_completer_variables();
             }
+            Command::StepIntoTarget(cmd) => {
+                match step_into::Handler::new(&mut self.debugger).handle_target(cmd)? {
+                    step_into::ExecutionResult::Targets(targets) if json::enabled() => {
+                        targets
+                            .iter()
+                            .for_each(|target| self.print_json(json::call_target(target)));
+                    }
+                    step_into::ExecutionResult::Targets(targets) => {
+                        if targets.is_empty() {
+                            self.printer.println("no calls at current line");
+                        }
+                        for (i, target) in targets.iter().enumerate() {
+                            self.printer.println(format!(
+                                "#{i} {} at {}",
+                                FunctionNameView::from(target.name.as_ref()),
+                                AddressView::from(target.call_addr),
+                            ));
+                        }
+                    }
+                    step_into::ExecutionResult::Done => {}
+                }
+                _ = self.update_completer_variables();
+            }
             Command::StepOut => {
                 step_out::Handler::new(&mut self.debugger).handle()?;
                 _ = self.update_completer_variables();
@@ -488,23 +906,57 @@ impl AppLoop {
                 step_over::Handler::new(&mut self.debugger).handle()?;
                 _ = self.update_completer_variables();
             }
+            Command::Until(cmd) => {
+                until::Handler::new(&mut self.debugger).handle(cmd)?;
+                _ = self.update_completer_variables();
+            }
+            Command::Jump(cmd) => {
+                jump::Handler::new(&mut self.debugger).handle(cmd)?;
+                _ = self.update_completer_variables();
+            }
             Command::Breakpoint(mut brkpt_cmd) => {
-                let print_bp = |action: &str, bp: &debugger::BreakpointView| match &bp.place {
-                    None => {
-                        self.printer.println(format!(
-                            "{action} {} at {}",
-                            bp.number,
-                            AddressView::from(bp.addr),
-                        ));
+                let print_bp = |action: &str, bp: &debugger::BreakpointView| {
+                    if json::enabled() {
+                        let action = action.trim_start_matches("- ").to_lowercase();
+                        self.printer
+                            .println(json::breakpoint(&action, bp).to_string());
+                        return;
                     }
-                    Some(place) => {
-                        self.printer.println(format!(
-                            "{action} {} at {}: {}:{} ",
-                            bp.number,
-                            AddressView::from(place.address),
-                            FilePathView::from(place.file.to_string_lossy()),
-                            place.line_number,
-                        ));
+
+                    let mut options = String::new();
+                    if bp.options.disabled {
+                        options.push_str(" [disabled]");
+                    }
+                    if bp.options.hardware.is_some() {
+                        options.push_str(" [hw]");
+                    }
+                    if let Some(ref condition) = bp.options.condition {
+                        options.push_str(&format!(" if {}", condition.source));
+                    }
+                    if bp.options.hit_count > 0 {
+                        options.push_str(&format!(" (hits: {})", bp.options.hit_count));
+                    }
+                    if bp.options.ignore_count > 0 {
+                        options
+                            .push_str(&format!(" (ignore next {} hits)", bp.options.ignore_count));
+                    }
+
+                    match &bp.place {
+                        None => {
+                            self.printer.println(format!(
+                                "{action} {} at {}{options}",
+                                BreakpointNumberView::from(bp.number),
+                                AddressView::from(bp.addr),
+                            ));
+                        }
+                        Some(place) => {
+                            self.printer.println(format!(
+                                "{action} {} at {}: {} {options}",
+                                BreakpointNumberView::from(bp.number),
+                                AddressView::from(place.address),
+                                PlaceView::new(&place.file, place.line_number),
+                            ));
+                        }
                     }
                 };
 
@@ -514,15 +966,64 @@ impl AppLoop {
                             brkpts
                                 .iter()
                                 .for_each(|brkpt| print_bp("New breakpoint", brkpt));
+                            if brkpts.len() > 1 && !json::enabled() {
+                                self.printer
+                                    .println(format!("Resolved {} locations", brkpts.len()));
+                            }
+
+                            // breakpoint at a line without code is moved to the nearest statement
+                            if let BreakpointCommand::Add(BreakpointIdentity::Line(_, line))
+                            | BreakpointCommand::AddHardware(BreakpointIdentity::Line(
+                                _,
+                                line,
+                            )) = &brkpt_cmd
+                            {
+                                let moved_to = brkpts
+                                    .iter()
+                                    .filter_map(|brkpt| brkpt.place.as_ref())
+                                    .map(|place| place.line_number)
+                                    .find(|l| l != line);
+                                if let Some(moved_to) = moved_to.filter(|_| !json::enabled()) {
+                                    self.printer.println(format!(
+                                        "Line {line} has no code, breakpoint is set at line {moved_to}"
+                                    ));
+                                }
+                            }
                         }
                         Ok(r#break::ExecutionResult::Removed(brkpts)) => {
+                            brkpts.iter().for_each(|brkpt| {
+                                self.breakpoint_scripts.remove(&brkpt.number);
+                                print_bp("Removed breakpoint", brkpt)
+                            });
+                        }
+                        Ok(r#break::ExecutionResult::Dump(brkpts)) => {
+                            brkpts.iter().for_each(|brkpt| {
+                                print_bp("- Breakpoint", brkpt);
+                                if let Some(script) = self.breakpoint_scripts.get(&brkpt.number) {
+                                    self.printer.println(format!("    commands: {script}"));
+                                }
+                            })
+                        }
+                        Ok(r#break::ExecutionResult::Updated(brkpts)) => {
+                            if let BreakpointCommand::Commands(number, script) = &brkpt_cmd {
+                                if brkpts.is_empty() {
+                                    self.printer.println(ErrorView::from(format!(
+                                        "Breakpoint {number} not found"
+                                    )));
+                                }
+                                match script {
+                                    Some(script) if !brkpts.is_empty() => {
+                                        self.breakpoint_scripts.insert(*number, script.clone());
+                                    }
+                                    _ => {
+                                        self.breakpoint_scripts.remove(number);
+                                    }
+                                }
+                            }
                             brkpts
                                 .iter()
-                                .for_each(|brkpt| print_bp("Removed breakpoint", brkpt));
+                                .for_each(|brkpt| print_bp("Updated breakpoint", brkpt));
                         }
-                        Ok(r#break::ExecutionResult::Dump(brkpts)) => brkpts
-                            .iter()
-                            .for_each(|brkpt| print_bp("- Breakpoint", brkpt)),
                         Err(Error::NoSuitablePlace) => {
                             if self.yes("Add deferred breakpoint for future shared library load?") {
                                 brkpt_cmd = BreakpointCommand::AddDeferred(
@@ -533,20 +1034,124 @@ impl AppLoop {
                                 continue;
                             }
                         }
+                        Ok(ExecutionResult::AddDeferred) if json::enabled() => {
+                            self.print_json(json!({ "action": "add deferred breakpoint" }))
+                        }
                         Ok(ExecutionResult::AddDeferred) => {
                             self.printer.println("Add deferred endpoint")
                         }
+                        Ok(ExecutionResult::Saved(count)) if json::enabled() => {
+                            self.printer.println(
+                                json!({ "action": "save breakpoints", "count": count }).to_string(),
+                            )
+                        }
+                        Ok(ExecutionResult::Saved(count)) => {
+                            self.printer.println(format!("Saved {count} breakpoints"))
+                        }
+                        Ok(ExecutionResult::Loaded(brkpts, skipped)) => {
+                            brkpts
+                                .iter()
+                                .for_each(|brkpt| print_bp("New breakpoint", brkpt));
+                            skipped.into_iter().for_each(|line| {
+                                if json::enabled() {
+                                    let skip = json!({ "action": "skip line", "line": line });
+                                    self.printer.println(skip.to_string());
+                                } else {
+                                    self.printer
+                                        .println(ErrorView::from(format!("Skip line {line}")));
+                                }
+                            });
+                        }
                         Err(e) => return Err(e.into()),
                     }
                     break;
                 }
             }
+            Command::Watchpoint(cmd) => {
+                let print_wp = |action: &str, wp: &debugger::watchpoint::Watchpoint| {
+                    if json::enabled() {
+                        let action = action.trim_start_matches("- ").to_lowercase();
+                        self.printer
+                            .println(json::watchpoint(&action, wp).to_string());
+                        return;
+                    }
+                    self.printer.println(format!(
+                        "{action} {} at {}: {} ({} bytes, {}), value: {}",
+                        BreakpointNumberView::from(wp.number),
+                        AddressView::from(wp.addr),
+                        wp.source,
+                        wp.size,
+                        wp.condition,
+                        wp.value,
+                    ));
+                };
+
+                match WatchHandler::new(&mut self.debugger).handle(cmd)? {
+                    WatchResult::New(wp) => print_wp("New watchpoint", &wp),
+                    WatchResult::Removed(Some(wp)) => print_wp("Removed watchpoint", &wp),
+                    WatchResult::Removed(None) if json::enabled() => {}
+                    WatchResult::Removed(None) => self.printer.println("Watchpoint not found"),
+                    WatchResult::Dump(wps) => {
+                        wps.iter().for_each(|wp| print_wp("- Watchpoint", wp))
+                    }
+                }
+            }
             Command::Memory(mem_cmd) => {
                 let read = MemoryHandler::new(&self.debugger).handle(mem_cmd)?;
-                self.printer.println(format!("{:#016X}", read));
+                if json::enabled() {
+                    self.print_json(json!({ "value": read }));
+                } else {
+                    self.printer.println(format!("{:#016X}", read));
+                }
+            }
+            Command::Examine(cmd) => {
+                let format = cmd.format;
+                let rows = ExamineHandler::new(&self.debugger).handle(cmd)?;
+                if json::enabled() {
+                    self.print_json(json!(rows
+                        .iter()
+                        .map(|row| json!({
+                            "address": row.address.to_string(),
+                            "bytes": row.bytes.iter().map(|b| format!("{b:02x}")).collect::<String>(),
+                            "values": row.values.iter().map(|v| format.render(v)).collect::<Vec<_>>(),
+                            "ascii": row.ascii(),
+                        }))
+                        .collect::<Vec<_>>()));
+                    return Ok(());
+                }
+
+                let rendered = rows
+                    .iter()
+                    .map(|row| {
+                        row.values
+                            .iter()
+                            .map(|v| format.render(v))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<_>>();
+                let width = rendered.iter().map(String::len).max().unwrap_or_default();
+                let output = rows
+                    .iter()
+                    .zip(rendered)
+                    .map(|(row, values)| {
+                        format!(
+                            "{}: {values:width$}  |{}|",
+                            AddressView::from(row.address),
+                            row.ascii()
+                        )
+                    })
+                    .collect();
+                self.print_paged(output);
             }
             Command::Register(reg_cmd) => {
                 let response = RegisterHandler::new(&self.debugger).handle(&reg_cmd)?;
+                if json::enabled() {
+                    response
+                        .iter()
+                        .for_each(|register| self.print_json(json::register(register)));
+                    return Ok(());
+                }
                 response.iter().for_each(|register| {
                     self.printer.println(format!(
                         "{:10} {:#016X}",
@@ -554,6 +1159,12 @@ impl AppLoop {
                     ));
                 });
             }
+            Command::Help { reason, command } if json::enabled() => {
+                let help = self
+                    .helper
+                    .help_for_command(&self.debugger, command.as_deref());
+                self.print_json(json!({ "reason": reason, "help": help }));
+            }
             Command::Help { reason, command } => {
                 if let Some(reason) = reason {
                     self.printer.println(reason);
@@ -566,27 +1177,72 @@ impl AppLoop {
             Command::SkipInput => {}
             Command::PrintSymbol(symbol) => {
                 let symbols = SymbolHandler::new(&self.debugger).handle(&symbol)?;
-                for symbol in symbols {
-                    self.printer.println(format!(
-                        "{} - {:?} {}",
-                        symbol.name,
-                        symbol.kind,
-                        AddressView::from(symbol.addr)
-                    ));
+                if json::enabled() {
+                    symbols
+                        .iter()
+                        .for_each(|symbol| self.print_json(json::symbol(symbol)));
+                    return Ok(());
                 }
+                let output = symbols
+                    .into_iter()
+                    .map(|symbol| {
+                        format!(
+                            "{} - {:?} {}",
+                            symbol.display_name(),
+                            symbol.kind,
+                            AddressView::from(symbol.addr)
+                        )
+                    })
+                    .collect();
+                self.print_paged(output);
             }
             Command::Thread(cmd) => {
                 let result = command::thread::Handler::new(&mut self.debugger).handle(cmd)?;
                 match result {
+                    ThreadResult::List(mut list) if json::enabled() => {
+                        list.sort_by(|t1, t2| t1.thread.number.cmp(&t2.thread.number));
+                        list.iter()
+                            .for_each(|thread| self.print_json(json::thread(thread)));
+                    }
+                    ThreadResult::BroughtIntoFocus(thread) if json::enabled() => {
+                        self.print_json(json!({ "thread": thread.number }))
+                    }
                     ThreadResult::List(mut list) => {
                         list.sort_by(|t1, t2| t1.thread.number.cmp(&t2.thread.number));
                         for thread in list {
+                            let name = thread
+                                .name
+                                .as_ref()
+                                .map(|name| format!(" \"{name}\""))
+                                .unwrap_or_default();
+                            let stat = thread
+                                .stat
+                                .as_ref()
+                                .map(|stat| {
+                                    format!(
+                                        " [{}, cpu {}, user {:.2}s, sys {:.2}s]",
+                                        stat.state,
+                                        stat.cpu,
+                                        stat.user_time.as_secs_f64(),
+                                        stat.system_time.as_secs_f64(),
+                                    )
+                                })
+                                .unwrap_or_default();
+
+                            if !thread.thread.is_stopped() {
+                                self.printer.println(format!(
+                                    "#{} thread id: {}{name}, running{stat}",
+                                    thread.thread.number, thread.thread.pid,
+                                ));
+                                continue;
+                            }
+
                             let current_frame = thread.bt.and_then(|mut bt| bt.drain(..).next());
                             let ip = current_frame.as_ref().map(|f| f.ip.to_string());
                             let func = current_frame.and_then(|f| f.func_name);
 
                             let view = format!(
-                                "#{} thread id: {}, {} in {}",
+                                "#{} thread id: {}{name}, {} in {}{stat}",
                                 thread.thread.number,
                                 thread.thread.pid,
                                 AddressView::from(ip),
@@ -607,6 +1263,13 @@ impl AppLoop {
             }
             Command::SharedLib => {
                 let handler = SharedlibHandler::new(&self.debugger);
+                if json::enabled() {
+                    handler
+                        .handle()
+                        .iter()
+                        .for_each(|lib| self.print_json(json::shared_lib(lib)));
+                    return Ok(());
+                }
                 for lib in handler.handle() {
                     let mb_range = lib
                         .range
@@ -620,33 +1283,229 @@ impl AppLoop {
                     ))
                 }
             }
+            Command::Info(cmd) => match InfoHandler::new(&self.debugger).handle(cmd)? {
+                InfoResult::Fds(fds) if json::enabled() => {
+                    fds.iter().for_each(|fd| self.print_json(json::fd(fd)));
+                }
+                InfoResult::Fds(fds) => {
+                    for fd in fds {
+                        self.printer.println(format!(
+                            "{:>4}  {}  pos: {}  {}",
+                            fd.fd,
+                            FilePathView::from(&fd.target),
+                            fd.pos,
+                            fd.flag_names().join("|"),
+                        ))
+                    }
+                }
+                InfoResult::SignalDispositions(dispositions) if json::enabled() => {
+                    dispositions.iter().for_each(|(signal, disposition)| {
+                        self.print_json(json!({
+                            "signal": signal.as_str(),
+                            "disposition": disposition.to_string(),
+                        }))
+                    });
+                }
+                InfoResult::SignalDispositions(dispositions) => {
+                    for (signal, disposition) in dispositions {
+                        self.printer
+                            .println(format!("{:<10} {disposition}", signal.as_str()));
+                    }
+                }
+                InfoResult::SignalHistory(history) if json::enabled() => {
+                    history
+                        .iter()
+                        .for_each(|record| self.print_json(json::signal(record)));
+                }
+                InfoResult::SignalHistory(history) if history.is_empty() => {
+                    self.printer.println("No signals delivered")
+                }
+                InfoResult::SignalHistory(history) => {
+                    for record in history {
+                        let sender = record
+                            .sender
+                            .map(|pid| format!(" from {pid}"))
+                            .unwrap_or_default();
+                        self.printer.println(format!(
+                            "{} {} to thread {}{sender} (code {}): {}",
+                            record.time.format("%H:%M:%S%.3f"),
+                            KeywordView::from(record.signal.as_str()),
+                            record.thread,
+                            record.code,
+                            record.disposition,
+                        ));
+                    }
+                }
+                InfoResult::Functions(functions) if json::enabled() => {
+                    functions
+                        .iter()
+                        .for_each(|func| self.print_json(json::function(func)));
+                }
+                InfoResult::Functions(functions) => {
+                    let output = functions
+                        .into_iter()
+                        .map(|func| {
+                            let ranges = func
+                                .ranges
+                                .iter()
+                                .map(|r| {
+                                    format!(
+                                        "{}-{}",
+                                        AddressView::from(r.start),
+                                        AddressView::from(r.end)
+                                    )
+                                })
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            match func.decl {
+                                Some((file, line)) => format!(
+                                    "{} at {} [{ranges}]",
+                                    FunctionNameView::from(func.name),
+                                    PlaceView::new(&file, line),
+                                ),
+                                None => format!("{} [{ranges}]", FunctionNameView::from(func.name)),
+                            }
+                        })
+                        .collect();
+                    self.print_paged(output);
+                }
+            },
+            Command::Coverage(cmd) => {
+                let result = command::coverage::Handler::new(&mut self.debugger).handle(cmd)?;
+                match result {
+                    CoverageResult::Tracked(lines) if json::enabled() => {
+                        self.print_json(json!({ "action": "coverage", "lines": lines }))
+                    }
+                    CoverageResult::Report(files) if json::enabled() => {
+                        files.iter().for_each(|file| {
+                            self.print_json(json!({
+                                "file": file.file.to_string_lossy(),
+                                "lines": file.lines,
+                                "hits": file.hits,
+                                "untouched": file.untouched().collect::<Vec<_>>(),
+                            }))
+                        });
+                    }
+                    CoverageResult::Cleared if json::enabled() => {
+                        self.print_json(json!({ "action": "coverage clear" }))
+                    }
+                    CoverageResult::Tracked(lines) => self
+                        .printer
+                        .println(format!("Coverage tracking of {lines} new lines started")),
+                    CoverageResult::Report(files) if files.is_empty() => self.printer.println(
+                        "No coverage data, select code with `coverage file` or `coverage fn` first",
+                    ),
+                    CoverageResult::Report(files) => {
+                        for file in files {
+                            self.printer.println(format!(
+                                "{}: {}/{} lines ({:.1}%)",
+                                FilePathView::from(file.file.to_string_lossy()),
+                                file.hits.len(),
+                                file.lines.len(),
+                                file.percent(),
+                            ));
+                            let untouched = command::coverage::line_ranges(file.untouched());
+                            if !untouched.is_empty() {
+                                self.printer.println(format!("  untouched: {untouched}"));
+                            }
+                        }
+                    }
+                    CoverageResult::Cleared => self.printer.println("Coverage data cleared"),
+                }
+            }
+            Command::Record(cmd) => {
+                let result = command::record::Handler::new(&mut self.debugger).handle(cmd)?;
+                match result {
+                    RecordResult::Started if json::enabled() => {
+                        self.print_json(json!({ "action": "record start" }))
+                    }
+                    RecordResult::Stopped if json::enabled() => {
+                        self.print_json(json!({ "action": "record stop" }))
+                    }
+                    RecordResult::Info(info) if json::enabled() => self.print_json(match info {
+                        None => json!({ "recording": false }),
+                        Some(info) => json!({
+                            "recording": true,
+                            "memory_size": info.memory_size,
+                            "snapshots": info.snapshots.iter().map(|snapshot| json!({
+                                "kind": snapshot.kind.to_string(),
+                                "pc": snapshot.pc.to_string(),
+                                "thread": snapshot.pid.as_raw(),
+                            })).collect::<Vec<_>>(),
+                        }),
+                    }),
+                    RecordResult::Started => self.printer.println("Recording started"),
+                    RecordResult::Stopped => self.printer.println("Recording stopped"),
+                    RecordResult::Info(None) => self.printer.println("Recording is not started"),
+                    RecordResult::Info(Some(info)) => {
+                        self.printer.println(format!(
+                            "Recorded {} states, {} KiB of memory",
+                            info.snapshots.len(),
+                            info.memory_size / 1024
+                        ));
+                        let last = info.snapshots.len().saturating_sub(1);
+                        for (i, snapshot) in info.snapshots.into_iter().enumerate() {
+                            self.printer.println(format!(
+                                "#{i} {} at {} (thread {}){}",
+                                snapshot.kind,
+                                AddressView::from(snapshot.pc),
+                                snapshot.pid,
+                                if i == last { " <- current" } else { "" }
+                            ));
+                        }
+                    }
+                    RecordResult::Reversed => {
+                        _ = self.update_completer_variables();
+                    }
+                }
+            }
             Command::SourceCode(inner_cmd) => match inner_cmd {
                 source_code::Command::Range(bounds) => {
                     let handler = FunctionLineRangeHandler::new(&self.debugger);
                     let range = handler.handle()?;
+                    if json::enabled() {
+                        let line = range.stop_place.line_number;
+                        self.print_json(json!({
+                            "function": range.name,
+                            "file": range.stop_place.file.to_string_lossy(),
+                            "line": line,
+                            "start_line": line.saturating_sub(bounds),
+                            "end_line": line + bounds,
+                        }));
+                        return Ok(());
+                    }
 
                     self.printer.println(format!(
-                        "{} at {}:{}",
+                        "{} at {}",
                         FunctionNameView::from(range.name),
-                        FilePathView::from(range.stop_place.file.to_string_lossy()),
-                        range.stop_place.line_number,
+                        PlaceView::new(range.stop_place.file, range.stop_place.line_number),
                     ));
 
+                    let lines = self.debugger.source_context(&range.stop_place, bounds)?;
                     self.printer.print(
                         self.file_view
-                            .render_source(&range.stop_place, bounds)
+                            .render_lines(&lines)
                             .map_err(CommandError::FileRender)?,
                     );
                 }
                 source_code::Command::Function => {
                     let handler = FunctionLineRangeHandler::new(&self.debugger);
                     let range = handler.handle()?;
+                    if json::enabled() {
+                        self.print_json(json!({
+                            "function": range.name,
+                            "file": range.file.to_string_lossy(),
+                            "line": range.stop_place.line_number,
+                            "start_line": range.start_line,
+                            "end_line": range.end_line,
+                        }));
+                        return Ok(());
+                    }
 
                     self.printer.println(format!(
-                        "{} at {}:{}",
+                        "{} at {}",
                         FunctionNameView::from(range.name),
-                        FilePathView::from(range.stop_place.file.to_string_lossy()),
-                        range.stop_place.line_number,
+                        PlaceView::new(range.stop_place.file, range.stop_place.line_number),
                     ));
 
                     self.printer.print(
@@ -658,10 +1517,14 @@ impl AppLoop {
                 source_code::Command::Asm => {
                     let handler = DisAsmHandler::new(&self.debugger);
                     let assembly = handler.handle()?;
-                    self.printer.println(format!(
+                    if json::enabled() {
+                        self.print_json(json::assembly(&assembly));
+                        return Ok(());
+                    }
+                    let mut output = vec![format!(
                         "Assembler code for function {}",
                         FunctionNameView::from(assembly.name)
-                    ));
+                    )];
                     for ins in assembly.instructions {
                         let instruction_view = format!(
                             "{} {} {}",
@@ -671,11 +1534,12 @@ impl AppLoop {
                         );
 
                         if ins.address == assembly.addr_in_focus {
-                            self.printer.println(format!("{}", instruction_view.bold()));
+                            output.push(format!("{}", instruction_view.bold()));
                         } else {
-                            self.printer.println(instruction_view);
+                            output.push(instruction_view);
                         }
                     }
+                    self.print_paged(output);
                 }
             },
             Command::Oracle(name, subcmd) => match self.debugger.get_oracle(&name) {
@@ -684,14 +1548,411 @@ impl AppLoop {
                     .println(ErrorView::from("Oracle not found or not ready")),
                 Some(oracle) => oracle.print(&self.printer, subcmd.as_deref()),
             },
+            Command::Script(path) => {
+                let printer = self.printer.clone();
+                let engine = self
+                    .script_engine
+                    .get_or_insert_with(|| ScriptEngine::new(move |s| printer.println(s)));
+                if let Err(e) = engine.run_file(&mut self.debugger, &path) {
+                    self.printer.println(ErrorView::from(e));
+                }
+                _ = self.update_completer_variables();
+            }
+            Command::Set(set::Command::Style(element, color)) => {
+                style::set_color(element, color);
+            }
+            Command::Set(set::Command::Logging(Some(path))) => {
+                transcript::start(&path).map_err(Error::from)?;
+                self.printer.println(format!(
+                    "Logging session into {}",
+                    FilePathView::from(path.display())
+                ));
+            }
+            Command::Set(set::Command::Logging(None)) => {
+                if let Some(path) = transcript::stop() {
+                    self.printer.println(format!(
+                        "Logging into {} stopped",
+                        FilePathView::from(path.display())
+                    ));
+                }
+            }
+            Command::Set(set::Command::Variable(name, value)) => {
+                let var = self.debugger.write_variable(&name, value)?;
+                if json::enabled() {
+                    self.print_json(json::variable(&var));
+                    return Ok(());
+                }
+                self.printer.println(
+                    render_variable(&var).unwrap_or(print::style::UNKNOWN_PLACEHOLDER.to_string()),
+                );
+            }
+            Command::Set(set::Command::NonStop(enable)) => {
+                self.debugger.set_non_stop(enable)?;
+                if json::enabled() {
+                    self.print_json(json!({ "non_stop": enable }));
+                    return Ok(());
+                }
+                let state = if enable { "enabled" } else { "disabled" };
+                self.printer.println(format!("Non-stop mode {state}"));
+            }
+            Command::Set(set::Command::SubstitutePath(from, to)) => {
+                if json::enabled() {
+                    self.print_json(json!({
+                        "substitute_path": { "from": from.to_string_lossy(), "to": to.to_string_lossy() }
+                    }));
+                } else {
+                    self.printer.println(format!(
+                        "Source paths {} are substituted by {}",
+                        from.display(),
+                        to.display()
+                    ));
+                }
+                source::add_remap(from, to);
+            }
+            Command::Set(set::Command::Demangle(style)) => {
+                symbol::set_name_style(style);
+                let style = match style {
+                    SymbolNameStyle::Demangled => "demangled",
+                    SymbolNameStyle::Raw => "raw",
+                    SymbolNameStyle::Both => "both",
+                };
+                if json::enabled() {
+                    self.print_json(json!({ "demangle": style }));
+                } else {
+                    self.printer.println(format!("Symbol names style: {style}"));
+                }
+            }
+            Command::Set(set::Command::Args(args)) => {
+                self.debugger.set_program_args(args);
+                let args = self.debugger.program_args();
+                if json::enabled() {
+                    self.print_json(json!({ "args": args }));
+                } else {
+                    self.printer.println(format!(
+                        "Program arguments (applied at next run or restart): {}",
+                        args.join(" ")
+                    ));
+                }
+            }
+            Command::Set(set::Command::Env(name, value)) => {
+                self.debugger.set_program_env(&name, value.as_deref());
+                if json::enabled() {
+                    self.print_json(json!({ "env": { "name": name, "value": value } }));
+                } else {
+                    let change = match value {
+                        Some(value) => format!("{name}={value}"),
+                        None => format!("{name} is unset"),
+                    };
+                    self.printer.println(format!(
+                        "Program environment (applied at next run or restart): {change}"
+                    ));
+                }
+            }
+            Command::Call(fn_name, args) => {
+                let result = self.debugger.call_function(&fn_name, &args)?;
+                if json::enabled() {
+                    self.print_json(result.as_ref().map(json::variable).unwrap_or_default());
+                    return Ok(());
+                }
+                match result {
+                    Some(var) => self.printer.println(
+                        render_variable(&var)
+                            .unwrap_or(print::style::UNKNOWN_PLACEHOLDER.to_string()),
+                    ),
+                    None => self.printer.println("void"),
+                }
+            }
+            Command::Return(value) => {
+                self.debugger.force_return(value)?;
+                _ = self.update_completer_variables();
+            }
+            Command::Catch(cmd) => {
+                let result = command::catch::Handler::new(&mut self.debugger).handle(cmd)?;
+                let syscalls = |filter: &SyscallFilter| -> Vec<String> {
+                    match filter {
+                        SyscallFilter::All => vec![],
+                        SyscallFilter::Numbers(numbers) => numbers
+                            .iter()
+                            .map(|nr| syscall_name(*nr).map_or(nr.to_string(), ToString::to_string))
+                            .collect(),
+                    }
+                };
+                match result {
+                    CatchResult::Enabled(filter) | CatchResult::Info(Some(filter))
+                        if json::enabled() =>
+                    {
+                        self.print_json(json!({ "catch": true, "syscalls": syscalls(&filter) }))
+                    }
+                    CatchResult::Disabled | CatchResult::Info(None) if json::enabled() => {
+                        self.print_json(json!({ "catch": false }))
+                    }
+                    CatchResult::Enabled(filter) | CatchResult::Info(Some(filter)) => {
+                        let syscalls = syscalls(&filter);
+                        if syscalls.is_empty() {
+                            self.printer.println("Catch any syscall");
+                        } else {
+                            self.printer
+                                .println(format!("Catch syscalls: {}", syscalls.join(", ")));
+                        }
+                    }
+                    CatchResult::Disabled => self.printer.println("Syscall catchpoint removed"),
+                    CatchResult::Info(None) => {
+                        self.printer.println("Syscall catchpoint is not set")
+                    }
+                }
+            }
+            Command::Skip(cmd) => {
+                let result = command::skip::Handler::new(&mut self.debugger).handle(cmd)?;
+                match result {
+                    SkipResult::Added(num, rule) if json::enabled() => self.print_json(
+                        json!({ "action": "added", "number": num, "rule": rule.to_string() }),
+                    ),
+                    SkipResult::Removed(num, rule) if json::enabled() => self.print_json(
+                        json!({ "action": "removed", "number": num, "rule": rule.to_string() }),
+                    ),
+                    SkipResult::Info(rules) if json::enabled() => {
+                        rules.iter().enumerate().for_each(|(i, rule)| {
+                            self.print_json(json!({ "number": i + 1, "rule": rule.to_string() }))
+                        });
+                    }
+                    SkipResult::Added(num, rule) => {
+                        self.printer.println(format!("New skip rule {num}: {rule}"))
+                    }
+                    SkipResult::Removed(num, rule) => self
+                        .printer
+                        .println(format!("Removed skip rule {num}: {rule}")),
+                    SkipResult::Info(rules) => {
+                        if rules.is_empty() {
+                            self.printer.println("No skip rules");
+                        }
+                        rules.iter().enumerate().for_each(|(i, rule)| {
+                            self.printer.println(format!("{}: {rule}", i + 1))
+                        });
+                    }
+                }
+            }
+            Command::History(cmd) => {
+                let result = command::history::Handler::new(&mut self.debugger).handle(cmd)?;
+                match result {
+                    HistoryResult::Info(records) if json::enabled() => {
+                        records
+                            .iter()
+                            .for_each(|record| self.print_json(json::stop_record(record)));
+                    }
+                    HistoryResult::Info(records) => {
+                        if records.is_empty() {
+                            self.printer.println("No stops in history");
+                        }
+                        records.iter().enumerate().for_each(|(i, record)| {
+                            self.printer.println(format!("{}: {record}", i + 1))
+                        });
+                    }
+                    HistoryResult::Cleared => self.printer.println("History cleared"),
+                    HistoryResult::StackDepth(0) => {
+                        self.printer.println("Stack is not saved in history")
+                    }
+                    HistoryResult::StackDepth(depth) => self
+                        .printer
+                        .println(format!("Top {depth} stack frames are saved in history")),
+                    HistoryResult::Log(Some(path)) => self
+                        .printer
+                        .println(format!("History is logged into {}", path.display())),
+                    HistoryResult::Log(None) => self.printer.println("History logging disabled"),
+                }
+            }
+            Command::Checkpoint(cmd) => {
+                let result = command::checkpoint::Handler::new(&mut self.debugger).handle(cmd)?;
+                let checkpoint_json = |checkpoint: &CheckpointInfo| {
+                    json!({
+                        "id": checkpoint.id,
+                        "pid": checkpoint.pid.as_raw(),
+                        "pc": checkpoint.pc.to_string(),
+                    })
+                };
+                match result {
+                    CheckpointResult::Created(checkpoint) if json::enabled() => {
+                        self.print_json(checkpoint_json(&checkpoint))
+                    }
+                    CheckpointResult::Info(checkpoints) if json::enabled() => {
+                        self.print_json(json!(checkpoints
+                            .iter()
+                            .map(checkpoint_json)
+                            .collect::<Vec<_>>()))
+                    }
+                    CheckpointResult::Deleted if json::enabled() => {
+                        self.print_json(json!({ "action": "checkpoint delete" }))
+                    }
+                    CheckpointResult::Created(checkpoint) => self.printer.println(format!(
+                        "Checkpoint {} at {} (process {})",
+                        checkpoint.id,
+                        AddressView::from(checkpoint.pc),
+                        checkpoint.pid
+                    )),
+                    CheckpointResult::Info(checkpoints) if checkpoints.is_empty() => {
+                        self.printer.println("No checkpoints")
+                    }
+                    CheckpointResult::Info(checkpoints) => {
+                        for checkpoint in checkpoints {
+                            self.printer.println(format!(
+                                "#{} at {} (process {})",
+                                checkpoint.id,
+                                AddressView::from(checkpoint.pc),
+                                checkpoint.pid
+                            ));
+                        }
+                    }
+                    CheckpointResult::Deleted => self.printer.println("Checkpoint deleted"),
+                    CheckpointResult::Restarted => {
+                        _ = self.update_completer_variables();
+                    }
+                }
+            }
+            Command::Disassemble(target) => {
+                let disasm = DisasmHandler::new(&self.debugger).handle(target)?;
+                if json::enabled() {
+                    self.print_json(json::disassembly(&disasm));
+                    return Ok(());
+                }
+
+                let mut output = vec![match disasm.name {
+                    Some(name) => format!(
+                        "Assembler code for function {}",
+                        FunctionNameView::from(name)
+                    ),
+                    None => "Assembler code for memory range".to_string(),
+                }];
+                output.extend(Self::render_asm(disasm.instructions));
+                self.print_paged(output);
+            }
+            Command::Async(cmd) => {
+                match command::r#async::Handler::new(&mut self.debugger).handle(cmd)? {
+                    AsyncResult::Tasks(tasks) if json::enabled() => tasks
+                        .iter()
+                        .for_each(|task| self.print_json(json::async_task(task))),
+                    AsyncResult::Futures(futures) if json::enabled() => futures
+                        .iter()
+                        .for_each(|frames| self.print_json(json::async_frames(frames))),
+                    AsyncResult::Tasks(tasks) if tasks.is_empty() => {
+                        self.printer.println("No async tasks found")
+                    }
+                    AsyncResult::Tasks(tasks) => {
+                        let mut output = vec![];
+                        for task in tasks {
+                            let id = task.id.map_or_else(|| "?".to_string(), |id| id.to_string());
+                            output.push(format!(
+                                "task #{id} {} [{}] {}",
+                                AddressView::from(task.header),
+                                task.state,
+                                FunctionNameView::from(task.future_type),
+                            ));
+                            output.extend(async_frames_output(task.frames, "    "));
+                        }
+                        self.print_paged(output);
+                    }
+                    AsyncResult::Futures(futures) => {
+                        let output = futures
+                            .into_iter()
+                            .flat_map(|frames| async_frames_output(frames, ""))
+                            .collect();
+                        self.print_paged(output);
+                    }
+                }
+            }
         }
 
         Ok(())
     }
 
+    fn handle_error(&self, e: CommandError) {
+        if json::enabled() {
+            self.print_json(json::error(&e));
+            if matches!(e, CommandError::Handle(ref err) if err.is_fatal()) {
+                exit(1);
+            }
+            return;
+        }
+
+        match e {
+            CommandError::Parsing(pretty_error) => {
+                self.printer.println(pretty_error);
+            }
+            CommandError::FileRender(_) => {
+                self.printer
+                    .println(ErrorView::from(format!("Render file error: {e:#}")));
+            }
+            CommandError::Handle(ref err) if err.is_fatal() => {
+                self.printer.println(ErrorView::from("Shutdown debugger"));
+                self.printer
+                    .println(ErrorView::from(format!("Fatal error: {e:#}")));
+                exit(1);
+            }
+            CommandError::Handle(_) if config::current().verbosity == Verbosity::Verbose => {
+                self.printer
+                    .println(ErrorView::from(format!("Error [{}]: {e:#}", e.category())));
+            }
+            CommandError::Handle(_) => {
+                self.printer
+                    .println(ErrorView::from(format!("Error: {e:#}")));
+            }
+        }
+    }
+
+    /// Execute commands from init files. Commands are executed only once, at first application run.
+    fn run_init_script(&mut self) {
+        static INIT_ONCE: Once = Once::new();
+        let mut first_run = false;
+        INIT_ONCE.call_once(|| first_run = true);
+        if !first_run {
+            return;
+        }
+
+        for command in &config::current().init.commands {
+            if let Err(e) = self.handle_command(command) {
+                self.printer.println(ErrorView::from(format!(
+                    "Init file command `{command}` failed"
+                )));
+                self.handle_error(e);
+            }
+        }
+    }
+
+    /// Execute commands one by one, stop at first failed command.
+    ///
+    /// # Errors
+    ///
+    /// Return error if any of commands failed.
+    fn run_batch(mut self, commands: Vec<String>) -> anyhow::Result<supervisor::ControlFlow> {
+        self.run_init_script();
+
+        for command in commands {
+            if !json::enabled() {
+                self.printer.println(format!("{PROMT}{command}"));
+            }
+            if command == "q" || command == "quit" {
+                break;
+            }
+
+            if let Err(e) = self.handle_command(&command) {
+                let category = e.category();
+                self.handle_error(e);
+                return Err(BatchCommandError { command, category }.into());
+            }
+        }
+
+        Ok(supervisor::ControlFlow::Exit)
+    }
+
     fn run(mut self) -> anyhow::Result<supervisor::ControlFlow> {
+        self.run_init_script();
+
+        let mut prompt = true;
         loop {
-            _ = self.ready_to_next_command_tx.send(EditorMode::Default);
+            // remote requests are executed without a new prompt,
+            // the editor still waits for user input
+            if prompt {
+                _ = self.ready_to_next_command_tx.send(EditorMode::Default);
+            }
+            prompt = true;
 
             let Ok(action) = self.user_input_rx.recv() else {
                 return Ok(supervisor::ControlFlow::Exit);
@@ -699,29 +1960,16 @@ impl AppLoop {
 
             match action {
                 UserAction::Cmd(command) => {
+                    transcript::record(&format!("{PROMT}{command}\n"));
                     if let Err(e) = self.handle_command(&command) {
-                        match e {
-                            CommandError::Parsing(pretty_error) => {
-                                self.printer.println(pretty_error);
-                            }
-                            CommandError::FileRender(_) => {
-                                self.printer
-                                    .println(ErrorView::from(format!("Render file error: {e:#}")));
-                            }
-                            CommandError::Handle(ref err) if err.is_fatal() => {
-                                self.printer.println(ErrorView::from("Shutdown debugger"));
-                                self.printer
-                                    .println(ErrorView::from(format!("Fatal error: {e:#}")));
-                                exit(1);
-                            }
-                            CommandError::Handle(_) => {
-                                self.printer
-                                    .println(ErrorView::from(format!("Error: {e:#}")));
-                            }
-                        }
+                        self.handle_error(e);
                     }
                 }
                 UserAction::Nop => {}
+                UserAction::Remote(request) => {
+                    self.handle_remote(request);
+                    prompt = false;
+                }
                 UserAction::Terminate => {
                     return Ok(supervisor::ControlFlow::Exit);
                 }
@@ -0,0 +1,61 @@
+//! Pager for long command outputs.
+//!
+//! If output doesn't fit into the terminal window it is piped into an external pager
+//! (`$PAGER` environment variable or `less` if not set).
+
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+const DEFAULT_PAGER: &str = "less";
+/// Options for `less` pager: quit if output fits on one screen, pass colors as is,
+/// don't clear the screen at exit.
+const LESS_OPTIONS: &str = "FRX";
+
+/// Try to show lines through a pager.
+///
+/// Return `false` if lines fit into the terminal window or pager is not available,
+/// in this case lines must be printed by caller.
+///
+/// # Arguments
+///
+/// * `lines`: output lines
+pub fn page(lines: &[String]) -> bool {
+    if cfg!(feature = "int_test") || !std::io::stdout().is_terminal() {
+        return false;
+    }
+
+    let Ok((_, rows)) = crossterm::terminal::size() else {
+        return false;
+    };
+    let lines_count: usize = lines.iter().map(|l| l.lines().count().max(1)).sum();
+    if lines_count < rows as usize {
+        return false;
+    }
+
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or(DEFAULT_PAGER.to_string());
+
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(&pager).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        cmd.env("LESS", LESS_OPTIONS);
+    }
+
+    let Ok(mut child) = cmd.spawn() else {
+        return false;
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        for line in lines {
+            // pager may be closed by user before all lines are written
+            if writeln!(stdin, "{line}").is_err() {
+                break;
+            }
+        }
+    }
+
+    _ = child.wait();
+    true
+}
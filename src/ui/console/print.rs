@@ -1,8 +1,25 @@
+use crate::ui::console::transcript;
 use rustyline::history::History;
 use rustyline::{Editor, ExternalPrinter as RLExternalPrinter, Helper};
 use std::cell::RefCell;
 use std::fmt::Display;
 
+thread_local! {
+    /// Output of printers used in current thread is collected here (if capturing is enabled).
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Execute a function and return everything printed by [`ExternalPrinter`]'s in current thread
+/// (command results and stop reports) instead of writing it into stdout.
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, String) {
+    CAPTURED.with_borrow_mut(|captured| *captured = Some(String::new()));
+    let result = f();
+    let output = CAPTURED
+        .with_borrow_mut(|captured| captured.take())
+        .unwrap_or_default();
+    (result, output)
+}
+
 /// [`ExternalPrinter`] safe print messages to stdout
 ///
 /// There is a problem with [`ExternalPrinter`] and integration tests, see [this issue](https://github.com/kkawakam/rustyline/issues/703).
@@ -27,8 +44,32 @@ impl ExternalPrinter {
         Ok(Self { printer: None })
     }
 
+    /// Create printer that writes directly into stdout, used when there is no interactive editor.
+    pub fn stdout() -> Self {
+        Self { printer: None }
+    }
+
     pub fn print(&self, msg: impl Display) {
         let msg = msg.to_string();
+        transcript::record(&msg);
+        if !msg.ends_with('\n') {
+            transcript::record("\n");
+        }
+
+        let captured = CAPTURED.with_borrow_mut(|captured| match captured {
+            Some(output) => {
+                output.push_str(&msg);
+                if !msg.ends_with('\n') {
+                    output.push('\n');
+                }
+                true
+            }
+            None => false,
+        });
+        if captured {
+            return;
+        }
+
         match &self.printer {
             None => {
                 println!("{msg}")
@@ -49,14 +90,18 @@ impl ExternalPrinter {
 }
 
 pub mod style {
-    use crossterm::style::{Color, Stylize};
+    use crate::ui::config;
+    use crate::ui::style::{self, StyleElement};
+    use crossterm::style::Stylize;
+    use std::env;
     use std::fmt::{Display, Formatter};
+    use std::path::Path;
 
     pub const UNKNOWN_PLACEHOLDER: &str = "???";
 
     struct View<T: Display> {
         inner: Option<T>,
-        color: Color,
+        element: StyleElement,
     }
 
     impl<T: Display> Display for View<T> {
@@ -67,25 +112,24 @@ pub mod style {
                 .map(ToString::to_string)
                 .unwrap_or_else(|| UNKNOWN_PLACEHOLDER.to_string());
 
-            if cfg!(feature = "int_test") {
-                f.write_str(&addr)
-            } else {
-                f.write_fmt(format_args!("{}", addr.with(self.color)))
+            match style::color(self.element) {
+                None => f.write_str(&addr),
+                Some(color) => f.write_fmt(format_args!("{}", addr.with(color))),
             }
         }
     }
 
     /// Construct structure declaration to display data of the same type (file paths, addresses, etc.).
-    /// A display style will reset if program compile with `int_test` feature.
+    /// A display style will reset if colors are disabled or program compile with `int_test` feature.
     macro_rules! view_struct {
-        ($name: ident, $color: expr) => {
+        ($name: ident, $element: expr) => {
             pub struct $name<T: Display>(View<T>);
 
             impl<T: Display> From<T> for $name<T> {
                 fn from(value: T) -> Self {
                     Self(View {
                         inner: Some(value),
-                        color: $color,
+                        element: $element,
                     })
                 }
             }
@@ -94,7 +138,7 @@ pub mod style {
                 fn from(value: Option<T>) -> Self {
                     Self(View {
                         inner: value,
-                        color: $color,
+                        element: $element,
                     })
                 }
             }
@@ -107,11 +151,54 @@ pub mod style {
         };
     }
 
-    view_struct!(AddressView, Color::Blue);
-    view_struct!(FilePathView, Color::Green);
-    view_struct!(FunctionNameView, Color::Yellow);
-    view_struct!(KeywordView, Color::Magenta);
-    view_struct!(AsmInstructionView, Color::DarkRed);
-    view_struct!(AsmOperandsView, Color::DarkGreen);
-    view_struct!(ErrorView, Color::DarkRed);
+    view_struct!(AddressView, StyleElement::Address);
+    view_struct!(FilePathView, StyleElement::FilePath);
+    view_struct!(FunctionNameView, StyleElement::FunctionName);
+    view_struct!(KeywordView, StyleElement::Keyword);
+    view_struct!(AsmInstructionView, StyleElement::AsmInstruction);
+    view_struct!(AsmOperandsView, StyleElement::AsmOperands);
+    view_struct!(ErrorView, StyleElement::Error);
+    view_struct!(BreakpointNumberView, StyleElement::Breakpoint);
+
+    /// Source location (`file:line`), shown as a terminal hyperlink (OSC-8) if
+    /// hyperlinks are enabled.
+    pub struct PlaceView<'a> {
+        file: &'a Path,
+        line: u64,
+    }
+
+    impl<'a> PlaceView<'a> {
+        pub fn new(file: &'a Path, line: u64) -> Self {
+            Self { file, line }
+        }
+
+        fn url(&self) -> Option<String> {
+            let template = config::current().hyperlinks.as_ref()?;
+            let path = if self.file.is_relative() {
+                env::current_dir().ok()?.join(self.file)
+            } else {
+                self.file.to_path_buf()
+            };
+            let path = path.to_string_lossy().replace(' ', "%20");
+            Some(
+                template
+                    .replace("{path}", &path)
+                    .replace("{line}", &self.line.to_string()),
+            )
+        }
+    }
+
+    impl Display for PlaceView<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            let place = format!(
+                "{}:{}",
+                FilePathView::from(self.file.to_string_lossy()),
+                self.line
+            );
+            match self.url() {
+                None => f.write_str(&place),
+                Some(url) => f.write_fmt(format_args!("\x1b]8;;{url}\x1b\\{place}\x1b]8;;\x1b\\")),
+            }
+        }
+    }
 }
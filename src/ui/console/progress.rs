@@ -0,0 +1,36 @@
+//! Progress bar for long-running debugger operations.
+
+use crate::debugger::progress::ProgressObserver;
+use std::io::{IsTerminal, Write};
+
+const BAR_WIDTH: u64 = 30;
+
+/// Draws a progress bar in the stderr line, the line is cleared when operation is finished.
+pub struct ProgressBar;
+
+impl ProgressBar {
+    /// Create progress bar if stderr is a terminal.
+    pub fn new() -> Option<Self> {
+        (!cfg!(feature = "int_test") && std::io::stderr().is_terminal()).then_some(ProgressBar)
+    }
+}
+
+impl ProgressObserver for ProgressBar {
+    fn on_progress(&self, task: &str, done: u64, total: u64) {
+        let filled = done * BAR_WIDTH / total;
+        let bar = "#".repeat(filled as usize) + &" ".repeat((BAR_WIDTH - filled) as usize);
+        let mut stderr = std::io::stderr().lock();
+        _ = write!(
+            stderr,
+            "\r\x1b[2K{task} [{bar}] {}% (Ctrl-C to cancel)",
+            done * 100 / total
+        );
+        _ = stderr.flush();
+    }
+
+    fn on_finish(&self, _: &str) {
+        let mut stderr = std::io::stderr().lock();
+        _ = write!(stderr, "\r\x1b[2K");
+        _ = stderr.flush();
+    }
+}
@@ -0,0 +1,290 @@
+//! Remote control of an interactive session.
+//!
+//! If the `--remote <path>` argument is set, debugger listens on a unix socket for
+//! JSON-RPC 2.0 requests (one request per line) and writes one response line for each of them.
+//! Requests are executed by the console between user commands, so a request sent while debugee
+//! is running waits until debugee stops.
+
+use crate::debugger::{BreakpointView, ErrorCategory};
+use crate::ui::command::arguments::Handler as ArgumentsHandler;
+use crate::ui::command::backtrace::Command as BacktraceCommand;
+use crate::ui::command::backtrace::Handler as BacktraceHandler;
+use crate::ui::command::parser::{ARG_ALL_KEY, VAR_LOCAL_KEY};
+use crate::ui::command::r#break::ExecutionResult;
+use crate::ui::command::r#break::Handler as BreakpointHandler;
+use crate::ui::command::variables::Handler as VariablesHandler;
+use crate::ui::command::{Command, CommandError};
+use crate::ui::console::{json, print, AppLoop, UserAction};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::SyncSender;
+use std::sync::{mpsc, Mutex};
+use std::{io, thread};
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// Request from a remote client, executed by the console application loop.
+pub(super) struct Request {
+    method: String,
+    params: Value,
+    responder: mpsc::Sender<Response>,
+}
+
+type Response = Result<Value, RemoteError>;
+
+struct RemoteError {
+    code: i64,
+    message: String,
+    category: Option<ErrorCategory>,
+}
+
+impl RemoteError {
+    fn new(code: i64, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            category: None,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut error = json!({ "code": self.code, "message": self.message });
+        if let Some(category) = self.category {
+            error["data"] = json!({ "category": category.to_string() });
+        }
+        error
+    }
+}
+
+impl From<CommandError> for RemoteError {
+    fn from(e: CommandError) -> Self {
+        Self {
+            code: SERVER_ERROR,
+            message: format!("{e:#}"),
+            category: Some(e.category()),
+        }
+    }
+}
+
+impl Request {
+    /// Reply to a client that the debugger can't execute this request right now.
+    pub(super) fn reject(self) {
+        _ = self
+            .responder
+            .send(Err(RemoteError::new(SERVER_ERROR, "debugger is busy")));
+    }
+}
+
+/// Application loop that currently executes remote requests.
+static SESSION: Mutex<Option<SyncSender<UserAction>>> = Mutex::new(None);
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+/// Start listening on a unix socket (only once, at first call),
+/// requests are forwarded into a `session` channel.
+///
+/// # Arguments
+///
+/// * `path`: socket path, an existing socket file is replaced
+/// * `session`: console application loop channel
+pub(super) fn serve(path: &Path, session: SyncSender<UserAction>) -> io::Result<()> {
+    *SESSION.lock().unwrap() = Some(session);
+    if LISTENING.load(Ordering::Acquire) {
+        return Ok(());
+    }
+
+    if fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+        fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    LISTENING.store(true, Ordering::Release);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    thread::spawn(move || handle_connection(stream));
+                }
+                Err(e) => log::warn!(target: "remote", "accept connection: {e}"),
+            }
+        }
+    });
+    Ok(())
+}
+
+fn handle_connection(stream: UnixStream) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(e) => {
+            log::warn!(target: "remote", "clone connection: {e}");
+            return;
+        }
+    };
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if writeln!(writer, "{}", process(&line)).is_err() {
+            return;
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Message {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+fn process(line: &str) -> Value {
+    let message: Message = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return response(Value::Null, Err(RemoteError::new(PARSE_ERROR, e))),
+    };
+
+    let (responder, response_rx) = mpsc::channel();
+    let request = Request {
+        method: message.method,
+        params: message.params,
+        responder,
+    };
+
+    let session = SESSION.lock().unwrap().clone();
+    let result = match session {
+        Some(session) if session.send(UserAction::Remote(request)).is_ok() => response_rx
+            .recv()
+            .unwrap_or_else(|_| Err(RemoteError::new(SERVER_ERROR, "request dropped"))),
+        _ => Err(RemoteError::new(
+            SERVER_ERROR,
+            "console session is not active",
+        )),
+    };
+    response(message.id, result)
+}
+
+fn response(id: Value, result: Response) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error.to_json() }),
+    }
+}
+
+impl AppLoop {
+    /// Execute a remote request and send a response to the client.
+    pub(super) fn handle_remote(&mut self, request: Request) {
+        let result = self.execute_remote(&request.method, &request.params);
+        _ = request.responder.send(result);
+    }
+
+    fn execute_remote(&mut self, method: &str, params: &Value) -> Response {
+        let param = |name: &str| params.get(name).and_then(Value::as_str);
+        let required_param = |name: &str| {
+            param(name).ok_or_else(|| {
+                RemoteError::new(INVALID_PARAMS, format!("missing `{name}` parameter"))
+            })
+        };
+        let invalid_param =
+            |name: &str| RemoteError::new(INVALID_PARAMS, format!("invalid `{name}` parameter"));
+
+        match method {
+            "command" => {
+                let command = required_param("command")?;
+                let capture = match params.get("capture") {
+                    None => false,
+                    Some(capture) => capture.as_bool().ok_or_else(|| invalid_param("capture"))?,
+                };
+
+                // there is no user to answer questions or scroll a pager
+                self.remote = true;
+                let (result, output) = if capture {
+                    let (result, output) = print::capture(|| self.handle_command(command));
+                    (result, Some(output))
+                } else {
+                    (self.handle_command(command), None)
+                };
+                self.remote = false;
+                result?;
+                Ok(output.map_or(Value::Null, |output| json!({ "output": output })))
+            }
+            "break" => {
+                let location = required_param("location")?;
+                let Command::Breakpoint(cmd) = Command::parse(&format!("break {location}"))? else {
+                    return Err(invalid_param("location"));
+                };
+                let result = BreakpointHandler::new(&mut self.debugger)
+                    .handle(&cmd)
+                    .map_err(CommandError::from)?;
+                let breakpoints = |action: &str, brkpts: Vec<BreakpointView>| -> Value {
+                    brkpts
+                        .iter()
+                        .map(|brkpt| json::breakpoint(action, brkpt))
+                        .collect()
+                };
+                Ok(match result {
+                    ExecutionResult::New(brkpts) => breakpoints("new breakpoint", brkpts),
+                    ExecutionResult::Removed(brkpts) => breakpoints("removed breakpoint", brkpts),
+                    ExecutionResult::Dump(brkpts) => breakpoints("breakpoint", brkpts),
+                    ExecutionResult::Updated(brkpts) => breakpoints("updated breakpoint", brkpts),
+                    ExecutionResult::AddDeferred => json!({ "action": "add deferred breakpoint" }),
+                    ExecutionResult::Saved(count) => {
+                        json!({ "action": "save breakpoints", "count": count })
+                    }
+                    ExecutionResult::Loaded(brkpts, skipped) => json!({
+                        "breakpoints": breakpoints("new breakpoint", brkpts),
+                        "skipped": skipped,
+                    }),
+                })
+            }
+            "variables" => {
+                let expression = param("expression").unwrap_or(VAR_LOCAL_KEY);
+                let handler = VariablesHandler::new(&self.debugger);
+                match Command::parse(&format!("var {expression}"))? {
+                    Command::PrintVariables(dqe) => {
+                        Ok(handler.handle(dqe)?.iter().map(json::variable).collect())
+                    }
+                    Command::PrintExpression(expr) => {
+                        Ok(json!([json::variable(&handler.handle_expression(&expr)?)]))
+                    }
+                    _ => Err(invalid_param("expression")),
+                }
+            }
+            "arguments" => {
+                let expression = param("expression").unwrap_or(ARG_ALL_KEY);
+                let Command::PrintArguments(dqe) = Command::parse(&format!("arg {expression}"))?
+                else {
+                    return Err(invalid_param("expression"));
+                };
+                let args = ArgumentsHandler::new(&self.debugger).handle(dqe)?;
+                Ok(args.iter().map(json::variable).collect())
+            }
+            "backtrace" | "threads" => {
+                let cmd = if method == "threads" {
+                    BacktraceCommand::All
+                } else {
+                    BacktraceCommand::CurrentThread
+                };
+                let threads = BacktraceHandler::new(&mut self.debugger).handle(cmd)?;
+                Ok(threads.iter().map(json::thread).collect())
+            }
+            _ => Err(RemoteError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method `{method}`"),
+            )),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Session transcript.
+//!
+//! When logging is enabled (`set logging on <file>` command), every command, its output,
+//! program output and stop events are appended to a file.
+//! Escape sequences (colors, etc.) are removed from recorded text.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+struct Transcript {
+    path: PathBuf,
+    file: File,
+}
+
+static TRANSCRIPT: Lazy<Mutex<Option<Transcript>>> = Lazy::new(Mutex::default);
+
+static ESCAPE_SEQ_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").expect("infallible"));
+
+/// Start writing session transcript into a file, file is created if not exists,
+/// otherwise a new data is appended to it.
+///
+/// # Arguments
+///
+/// * `path`: path to transcript file
+pub fn start(path: &Path) -> std::io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    *TRANSCRIPT.lock().unwrap() = Some(Transcript {
+        path: path.to_path_buf(),
+        file,
+    });
+    Ok(())
+}
+
+/// Stop writing session transcript, return path to transcript file if logging was enabled.
+pub fn stop() -> Option<PathBuf> {
+    TRANSCRIPT.lock().unwrap().take().map(|t| t.path)
+}
+
+/// Append text to a transcript file (if logging enabled).
+pub fn record(text: &str) {
+    let mut transcript = TRANSCRIPT.lock().unwrap();
+    if let Some(transcript) = transcript.as_mut() {
+        let text = ESCAPE_SEQ_RE.replace_all(text, "");
+        if let Err(e) = transcript.file.write_all(text.as_bytes()) {
+            log::warn!(target: "debugger", "write transcript: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_escape_sequences_removed() {
+        let text = "\x1b[31mred\x1b[0m and \x1b[32;1mbold green\x1b[0m";
+        assert_eq!(ESCAPE_SEQ_RE.replace_all(text, ""), "red and bold green");
+    }
+}
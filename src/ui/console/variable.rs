@@ -1,19 +1,19 @@
 use crate::debugger::address::RelocatedAddress;
-use crate::debugger::variable::render::{RenderRepr, ValueLayout};
-use crate::debugger::variable::VariableIR;
+use crate::debugger::variable::render::{ref_counts, RenderRepr, ValueLayout};
+use crate::debugger::variable::{SpecializedVariableIR, VariableIR};
 use crate::ui::syntax;
-use crate::ui::syntax::StylizedLine;
+use crate::ui::syntax::{RenderError, StylizedLine};
 use syntect::util::as_24_bit_terminal_escaped;
 
 const TAB: &str = "\t";
 
-pub fn render_variable(var: &VariableIR) -> anyhow::Result<String> {
+pub fn render_variable(var: &VariableIR) -> Result<String, RenderError> {
     let syntax_renderer = syntax::rust_syntax_renderer();
     let mut line_renderer = syntax_renderer.line_renderer();
     let var_as_string = format!("{} = {}", var.name(), render_variable_ir(var, 0));
     Ok(var_as_string
         .lines()
-        .map(|l| -> anyhow::Result<String> {
+        .map(|l| -> Result<String, RenderError> {
             let line = match line_renderer.render_line(l)? {
                 StylizedLine::NoneStyle(l) => l.to_string(),
                 StylizedLine::Stylized(segments) => {
@@ -23,22 +23,49 @@ pub fn render_variable(var: &VariableIR) -> anyhow::Result<String> {
             };
             Ok(line)
         })
-        .collect::<anyhow::Result<Vec<_>>>()?
+        .collect::<Result<Vec<_>, RenderError>>()?
         .join("\n"))
 }
 
+/// Return `true` if variable is an interpreted `Option` or `Result`,
+/// such variables are rendered like a tuple variant (`Option<i32>::Some(i32(1))`).
+fn is_option_or_result(var: &VariableIR) -> bool {
+    matches!(
+        var,
+        VariableIR::Specialized(
+            SpecializedVariableIR::Option { value: Some(_), .. }
+                | SpecializedVariableIR::Result { value: Some(_), .. }
+        )
+    )
+}
+
 pub fn render_variable_ir(view: &VariableIR, depth: usize) -> String {
     match view.value() {
         Some(value) => match value {
             ValueLayout::PreRendered(rendered_value) => match view {
                 VariableIR::CEnum(_) => format!("{}::{}", view.r#type(), rendered_value),
+                _ if is_option_or_result(view) => {
+                    format!("{}::{}", view.r#type(), rendered_value)
+                }
                 _ => format!("{}({})", view.r#type(), rendered_value),
             },
             ValueLayout::Referential { addr } => {
-                format!(
+                let render = format!(
                     "{} [{}]",
                     view.r#type(),
                     RelocatedAddress::from(addr as usize)
+                );
+                match ref_counts(view) {
+                    Some(counts) => format!("{render} ({counts})"),
+                    None => render,
+                }
+            }
+            ValueLayout::Wrapped(val) if is_option_or_result(view) => {
+                format!(
+                    "{}::{}({})",
+                    view.r#type(),
+                    val.name(),
+                    render_variable_ir(val, depth)
                 )
             }
             ValueLayout::Wrapped(val) => {
@@ -0,0 +1,133 @@
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::catchpoint::SyscallEvent;
+use crate::debugger::watchpoint::WatchValue;
+use crate::debugger::{Error, EventHook, FunctionDie, PlaceDescriptor, SyscallStop, Tracee};
+use crate::ui;
+use crate::ui::dap::protocol::Sender;
+use crate::ui::DebugeeOutReader;
+use crate::version;
+use log::warn;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use serde_json::json;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum time to wait until debugee output sent before a stop is reported.
+const OUTPUT_FLUSH_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Reason of the last debugee stop.
+#[derive(Clone, Copy, Debug)]
+pub enum Stop {
+    Breakpoint(u32),
+    Watchpoint(u32),
+    Step,
+    Signal(Signal),
+    Syscall {
+        nr: u64,
+        name: Option<&'static str>,
+        entry: bool,
+    },
+}
+
+/// Hook remembers a stop reason, `stopped` event is sent by a session when
+/// debugger returns control (a stopped thread is known at this moment).
+pub struct DapHook {
+    sender: Sender,
+    last_stop: Rc<Cell<Option<Stop>>>,
+    debugee_pid: Arc<AtomicI32>,
+    /// Debugee stdout and stderr
+    debugee_streams: [DebugeeOutReader; 2],
+}
+
+impl DapHook {
+    pub fn new(
+        sender: Sender,
+        last_stop: Rc<Cell<Option<Stop>>>,
+        debugee_pid: Arc<AtomicI32>,
+        debugee_streams: [DebugeeOutReader; 2],
+    ) -> Self {
+        Self {
+            sender,
+            last_stop,
+            debugee_pid,
+            debugee_streams,
+        }
+    }
+}
+
+impl EventHook for DapHook {
+    fn on_breakpoint(
+        &self,
+        _: RelocatedAddress,
+        num: u32,
+        _: Option<PlaceDescriptor>,
+        _: Option<&FunctionDie>,
+    ) -> Result<(), Error> {
+        self.last_stop.set(Some(Stop::Breakpoint(num)));
+        Ok(())
+    }
+
+    fn on_step(
+        &self,
+        _: RelocatedAddress,
+        _: Option<PlaceDescriptor>,
+        _: Option<&FunctionDie>,
+    ) -> Result<(), Error> {
+        self.last_stop.set(Some(Stop::Step));
+        Ok(())
+    }
+
+    fn on_watchpoint(
+        &self,
+        _: RelocatedAddress,
+        num: u32,
+        _: Option<PlaceDescriptor>,
+        _: Option<&FunctionDie>,
+        _: &WatchValue,
+        _: &WatchValue,
+    ) -> Result<(), Error> {
+        self.last_stop.set(Some(Stop::Watchpoint(num)));
+        Ok(())
+    }
+
+    fn on_signal(&self, signal: Signal) {
+        self.last_stop.set(Some(Stop::Signal(signal)));
+    }
+
+    fn on_syscall_catch(&self, _: RelocatedAddress, event: &SyscallEvent) {
+        let entry = matches!(event.stop, SyscallStop::Entry { .. });
+        self.last_stop.set(Some(Stop::Syscall {
+            nr: event.nr,
+            name: event.name,
+            entry,
+        }));
+    }
+
+    fn on_thread_stop(&self, _: &Tracee) {}
+
+    fn on_exit(&self, code: i32) {
+        self.last_stop.set(None);
+        self.sender.event("exited", json!({ "exitCode": code }));
+        self.sender.event("terminated", json!({}));
+    }
+
+    fn on_process_install(&self, pid: Pid, object: Option<&object::File>) {
+        if let Some(obj) = object {
+            if !version::probe_file(obj) {
+                let supported_versions = version::supported_versions_to_string();
+                warn!(target: "debugger", "Found unsupported rust version, some of program data may not be displayed correctly. \
+                List of supported rustc versions: {supported_versions}.");
+            }
+        }
+        self.debugee_pid.store(pid.as_raw(), Ordering::SeqCst);
+    }
+
+    fn on_output_flush(&self) {
+        // debugee output is sent as `output` events by a separate threads
+        ui::wait_output_read(&self.debugee_streams, OUTPUT_FLUSH_TIMEOUT);
+    }
+}
@@ -0,0 +1,628 @@
+//! Debug Adapter Protocol (DAP) server.
+//!
+//! Makes it possible to drive BugStalker from VSCode and other editors,
+//! see <https://microsoft.github.io/debug-adapter-protocol/specification>.
+//! Server serves a single debug session over stdio or a tcp connection.
+
+mod hook;
+mod protocol;
+
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::process::{Child, Installed};
+use crate::debugger::variable::render::{RenderRepr, ValueLayout};
+use crate::debugger::variable::select::{VariableSelector, DQE};
+use crate::debugger::variable::VariableIR;
+use crate::debugger::{BreakpointCondition, Debugger, DebuggerBuilder, Error, ErrorCategory};
+use crate::oracle::builtin;
+use crate::ui::command::parser;
+use crate::ui::command::parser::expression;
+use crate::ui::dap::hook::{DapHook, Stop};
+use crate::ui::dap::protocol::{
+    AttachArguments, EvaluateArguments, LaunchArguments, Request, ScopesArguments, Sender,
+    SetBreakpointsArguments, SetFunctionBreakpointsArguments, StackTraceArguments,
+    VariablesArguments,
+};
+use crate::ui::DebugeeOutReader;
+use crate::weak_error;
+use anyhow::{anyhow, bail, Context};
+use chumsky::Parser;
+use log::{info, warn};
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use os_pipe::PipeWriter;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::io;
+use std::io::{BufReader, Read};
+use std::net::TcpListener;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+
+/// A way to communicate with a client.
+pub enum Transport {
+    /// Use stdin and stdout of the debugger process.
+    Stdio,
+    /// Listen on a local tcp port and accept a single connection.
+    Tcp { port: u16 },
+}
+
+/// Serve a debug session. Debugee is created (or attached) by a `launch` (or `attach`) request.
+///
+/// # Arguments
+///
+/// * `transport`: client connection type
+/// * `oracles`: list of oracle names
+pub fn run(transport: Transport, oracles: &[String]) -> anyhow::Result<()> {
+    let (mut reader, sender): (Box<dyn io::BufRead + Send>, _) = match transport {
+        Transport::Stdio => (
+            Box::new(BufReader::new(io::stdin())),
+            Sender::new(io::stdout()),
+        ),
+        Transport::Tcp { port } => {
+            let listener = TcpListener::bind(("127.0.0.1", port)).context("Bind dap port")?;
+            info!(target: "dap", "listen on 127.0.0.1:{port}");
+            let (stream, addr) = listener.accept().context("Accept dap client")?;
+            info!(target: "dap", "client {addr} connected");
+            (
+                Box::new(BufReader::new(stream.try_clone()?)),
+                Sender::new(stream),
+            )
+        }
+    };
+
+    let debugee_pid = Arc::new(AtomicI32::new(-1));
+    let (requests_tx, requests_rx) = mpsc::channel();
+    {
+        let sender = sender.clone();
+        let debugee_pid = debugee_pid.clone();
+        // requests are read in a separate thread, so `pause` may interrupt a running debugee
+        thread::spawn(move || loop {
+            let request = match protocol::read_message(&mut reader) {
+                Ok(Some(request)) => request,
+                Ok(None) => return,
+                Err(e) => {
+                    warn!(target: "dap", "read request: {e}");
+                    return;
+                }
+            };
+
+            if request.command == "pause" {
+                let pid = debugee_pid.load(Ordering::SeqCst);
+                match (pid > 0).then(|| kill(Pid::from_raw(pid), Signal::SIGINT)) {
+                    Some(Ok(_)) => sender.response(&request, json!({})),
+                    _ => sender.error(
+                        &request,
+                        ErrorCategory::Process.exit_code(),
+                        "debugee is not running",
+                    ),
+                }
+                continue;
+            }
+
+            if requests_tx.send(request).is_err() {
+                return;
+            }
+        });
+    }
+
+    Session::new(sender, debugee_pid, oracles).run(requests_rx);
+    Ok(())
+}
+
+/// Parse request arguments.
+fn arguments<T: DeserializeOwned>(request: &Request) -> anyhow::Result<T> {
+    serde_json::from_value(request.arguments.clone())
+        .with_context(|| format!("Invalid `{}` arguments", request.command))
+}
+
+fn debugger(debugger: &mut Option<Debugger>) -> anyhow::Result<&mut Debugger> {
+    debugger
+        .as_mut()
+        .ok_or_else(|| anyhow!("debugee is not launched"))
+}
+
+fn condition(source: Option<&str>) -> anyhow::Result<Option<BreakpointCondition>> {
+    let Some(source) = source.filter(|s| !s.trim().is_empty()) else {
+        return Ok(None);
+    };
+    parser::brkpt_condition()
+        .parse(source)
+        .into_result()
+        .map_err(|_| anyhow!("invalid condition: {source}"))
+}
+
+/// Send debugee output as `output` events.
+fn forward_output(mut stream: DebugeeOutReader, category: &'static str, sender: Sender) {
+    thread::spawn(move || {
+        let mut buf = [0; 4096];
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => return,
+                Ok(n) => sender.event(
+                    "output",
+                    json!({
+                        "category": category,
+                        "output": String::from_utf8_lossy(&buf[..n]),
+                    }),
+                ),
+            }
+        }
+    });
+}
+
+/// Short representation of a variable value, compound values are represented by type name.
+fn short_value(var: &VariableIR) -> String {
+    match var.value() {
+        Some(ValueLayout::PreRendered(value)) => value.to_string(),
+        Some(ValueLayout::Referential { addr }) => {
+            RelocatedAddress::from(addr as usize).to_string()
+        }
+        Some(ValueLayout::Wrapped(inner)) => format!("{}::{}", var.r#type(), short_value(inner)),
+        Some(ValueLayout::List { members, .. }) => {
+            format!("{} [{}]", var.r#type(), members.len())
+        }
+        Some(ValueLayout::Map(kvs)) => format!("{} [{}]", var.r#type(), kvs.len()),
+        Some(ValueLayout::Structure { .. }) => var.r#type().to_string(),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Debug session state.
+struct Session {
+    sender: Sender,
+    oracles: Vec<String>,
+    debugger: Option<Debugger>,
+    /// True if debugee is an already running process.
+    attached: bool,
+    last_stop: Rc<Cell<Option<Stop>>>,
+    debugee_pid: Arc<AtomicI32>,
+    /// Lines with breakpoints for each source file.
+    source_breakpoints: HashMap<String, Vec<u64>>,
+    /// Functions with breakpoints.
+    function_breakpoints: Vec<String>,
+    /// Frames (thread number and frame number) shown to client, frame id is an index + 1.
+    /// Valid until debugee continues execution.
+    frames: Vec<(u32, u32)>,
+    /// Expandable variables shown to client, variables reference is an index + 1.
+    /// Valid until debugee continues execution.
+    variables: Vec<Vec<(String, VariableIR)>>,
+}
+
+impl Session {
+    fn new(sender: Sender, debugee_pid: Arc<AtomicI32>, oracles: &[String]) -> Self {
+        Self {
+            sender,
+            oracles: oracles.to_vec(),
+            debugger: None,
+            attached: false,
+            last_stop: Rc::default(),
+            debugee_pid,
+            source_breakpoints: HashMap::default(),
+            function_breakpoints: vec![],
+            frames: vec![],
+            variables: vec![],
+        }
+    }
+
+    fn run(mut self, requests: Receiver<Request>) {
+        while let Ok(request) = requests.recv() {
+            match request.command.as_str() {
+                "disconnect" => {
+                    self.debugger = None;
+                    self.sender.response(&request, json!({}));
+                    return;
+                }
+                "configurationDone" | "continue" | "next" | "stepIn" | "stepOut" => {
+                    self.execute(&request)
+                }
+                _ => match self.handle(&request) {
+                    Ok(body) => {
+                        self.sender.response(&request, body);
+                        if matches!(request.command.as_str(), "launch" | "attach") {
+                            // client may set breakpoints now
+                            self.sender.event("initialized", json!({}));
+                        }
+                    }
+                    Err(e) => self.send_error(&request, e),
+                },
+            }
+        }
+    }
+
+    fn send_error(&self, request: &Request, error: anyhow::Error) {
+        let category = error
+            .downcast_ref::<Error>()
+            .map(Error::category)
+            .unwrap_or(ErrorCategory::Internal);
+        self.sender
+            .error(request, category.exit_code(), format!("{error:#}"));
+    }
+
+    fn handle(&mut self, request: &Request) -> anyhow::Result<Value> {
+        match request.command.as_str() {
+            "initialize" => Ok(json!({
+                "supportsConfigurationDoneRequest": true,
+                "supportsConditionalBreakpoints": true,
+                "supportsFunctionBreakpoints": true,
+                "supportsEvaluateForHovers": true,
+                "supportsTerminateRequest": true,
+            })),
+            "launch" => {
+                let args: LaunchArguments = arguments(request)?;
+                self.start(|stdout, stderr| {
+                    Child::new(args.program, args.args, stdout, stderr).install()
+                })?;
+                Ok(json!({}))
+            }
+            "attach" => {
+                let args: AttachArguments = arguments(request)?;
+                self.start(|stdout, stderr| {
+                    Child::from_external(Pid::from_raw(args.pid), stdout, stderr)
+                })?;
+                self.attached = true;
+                Ok(json!({}))
+            }
+            "setBreakpoints" => self.set_breakpoints(arguments(request)?),
+            "setFunctionBreakpoints" => self.set_function_breakpoints(arguments(request)?),
+            "setExceptionBreakpoints" => Ok(json!({ "breakpoints": [] })),
+            "threads" => self.threads(),
+            "stackTrace" => self.stack_trace(arguments(request)?),
+            "scopes" => self.scopes(arguments(request)?),
+            "variables" => self.variables(arguments(request)?),
+            "evaluate" => self.evaluate(arguments(request)?),
+            "terminate" => {
+                self.debugger = None;
+                self.sender.event("terminated", json!({}));
+                Ok(json!({}))
+            }
+            cmd => bail!("Unsupported request: {cmd}"),
+        }
+    }
+
+    /// Create a debugger for a debugee process.
+    fn start(
+        &mut self,
+        process: impl FnOnce(PipeWriter, PipeWriter) -> Result<Child<Installed>, Error>,
+    ) -> anyhow::Result<()> {
+        if self.debugger.is_some() {
+            bail!("Debugee already launched");
+        }
+
+        let (stdout_reader, stdout_writer) = os_pipe::pipe()?;
+        let (stderr_reader, stderr_writer) = os_pipe::pipe()?;
+        let process = process(stdout_writer, stderr_writer)?;
+        self.debugee_pid
+            .store(process.pid().as_raw(), Ordering::SeqCst);
+
+        let streams: [DebugeeOutReader; 2] = [stdout_reader.into(), stderr_reader.into()];
+        forward_output(streams[0].clone(), "stdout", self.sender.clone());
+        forward_output(streams[1].clone(), "stderr", self.sender.clone());
+
+        let hook = DapHook::new(
+            self.sender.clone(),
+            self.last_stop.clone(),
+            self.debugee_pid.clone(),
+            streams,
+        );
+        let debugger = DebuggerBuilder::new()
+            .with_oracles(builtin::discover(&self.oracles))
+            .with_hooks(hook)
+            .build(process)
+            .context("Build debugger")?;
+        self.debugger = Some(debugger);
+        Ok(())
+    }
+
+    fn set_breakpoints(&mut self, args: SetBreakpointsArguments) -> anyhow::Result<Value> {
+        let debugger = debugger(&mut self.debugger)?;
+        let path = args.source.path;
+
+        // client always sends all breakpoints of a source file
+        for line in self.source_breakpoints.remove(&path).unwrap_or_default() {
+            weak_error!(debugger.remove_breakpoint_at_line(&path, line));
+        }
+
+        let mut lines = vec![];
+        let mut breakpoints = vec![];
+        for brkpt in args.breakpoints {
+            let result = condition(brkpt.condition.as_deref()).and_then(|condition| {
+                let views = debugger.set_breakpoint_at_line(&path, brkpt.line)?;
+                let numbers: Vec<u32> = views.iter().map(|view| view.number).collect();
+                let line = views
+                    .iter()
+                    .find_map(|view| view.place.as_ref().map(|place| place.line_number))
+                    .unwrap_or(brkpt.line);
+                for &number in &numbers {
+                    debugger.set_breakpoint_condition(number, condition.clone());
+                }
+                Ok((numbers, line))
+            });
+
+            breakpoints.push(match result {
+                Ok((numbers, line)) => {
+                    lines.push(brkpt.line);
+                    json!({ "id": numbers.first(), "verified": true, "line": line })
+                }
+                Err(e) => {
+                    json!({ "verified": false, "line": brkpt.line, "message": e.to_string() })
+                }
+            });
+        }
+
+        self.source_breakpoints.insert(path, lines);
+        Ok(json!({ "breakpoints": breakpoints }))
+    }
+
+    fn set_function_breakpoints(
+        &mut self,
+        args: SetFunctionBreakpointsArguments,
+    ) -> anyhow::Result<Value> {
+        let debugger = debugger(&mut self.debugger)?;
+
+        for function in self.function_breakpoints.drain(..) {
+            weak_error!(debugger.remove_breakpoint_at_fn(&function));
+        }
+
+        let mut breakpoints = vec![];
+        for brkpt in args.breakpoints {
+            let result = condition(brkpt.condition.as_deref()).and_then(|condition| {
+                let views = debugger.set_breakpoint_at_fn(&brkpt.name)?;
+                let numbers: Vec<u32> = views.iter().map(|view| view.number).collect();
+                for &number in &numbers {
+                    debugger.set_breakpoint_condition(number, condition.clone());
+                }
+                Ok(numbers)
+            });
+
+            breakpoints.push(match result {
+                Ok(numbers) => {
+                    self.function_breakpoints.push(brkpt.name);
+                    json!({ "id": numbers.first(), "verified": true })
+                }
+                Err(e) => json!({ "verified": false, "message": e.to_string() }),
+            });
+        }
+
+        Ok(json!({ "breakpoints": breakpoints }))
+    }
+
+    /// Resume debugee execution, send `stopped` event when debugee stops.
+    fn execute(&mut self, request: &Request) {
+        let Some(debugger) = self.debugger.as_mut() else {
+            self.send_error(request, anyhow!("debugee is not launched"));
+            return;
+        };
+
+        let body = match request.command.as_str() {
+            "continue" => json!({ "allThreadsContinued": true }),
+            _ => json!({}),
+        };
+        self.sender.response(request, body);
+
+        self.frames.clear();
+        self.variables.clear();
+        self.last_stop.set(None);
+
+        let result = match request.command.as_str() {
+            "configurationDone" if self.attached => debugger.continue_debugee(),
+            "configurationDone" => debugger.start_debugee(),
+            "continue" => debugger.continue_debugee(),
+            "next" => debugger.step_over(),
+            "stepIn" => debugger.step_into(),
+            "stepOut" => debugger.step_out(),
+            cmd => unreachable!("unexpected execution request: {cmd}"),
+        };
+        if let Err(e) = result {
+            self.sender.event(
+                "output",
+                json!({ "category": "important", "output": format!("{e}\n") }),
+            );
+        }
+
+        self.report_stop();
+    }
+
+    fn report_stop(&self) {
+        let (Some(stop), Some(debugger)) = (self.last_stop.take(), self.debugger.as_ref()) else {
+            return;
+        };
+
+        let thread_id = debugger.exploration_ctx().pid_on_focus().as_raw();
+        let mut body = json!({ "threadId": thread_id, "allThreadsStopped": true });
+        match stop {
+            Stop::Breakpoint(number) => {
+                body["reason"] = "breakpoint".into();
+                body["hitBreakpointIds"] = json!([number]);
+            }
+            Stop::Watchpoint(number) => {
+                body["reason"] = "data breakpoint".into();
+                body["hitBreakpointIds"] = json!([number]);
+            }
+            Stop::Step => body["reason"] = "step".into(),
+            Stop::Signal(Signal::SIGINT) => body["reason"] = "pause".into(),
+            Stop::Signal(signal) => {
+                body["reason"] = "exception".into();
+                body["description"] = format!("Signal {signal} received").into();
+            }
+            Stop::Syscall { nr, name, entry } => {
+                let name = name.map_or(format!("syscall_{nr}"), ToString::to_string);
+                let point = if entry { "entry" } else { "exit" };
+                body["reason"] = "syscall".into();
+                body["description"] = format!("Syscall {name} {point}").into();
+            }
+        }
+        self.sender.event("stopped", body);
+    }
+
+    fn threads(&mut self) -> anyhow::Result<Value> {
+        let debugger = debugger(&mut self.debugger)?;
+        let threads: Vec<_> = debugger
+            .thread_state()?
+            .into_iter()
+            .map(|snapshot| {
+                let name = snapshot
+                    .name
+                    .unwrap_or_else(|| format!("thread {}", snapshot.thread.number));
+                json!({ "id": snapshot.thread.pid.as_raw(), "name": name })
+            })
+            .collect();
+        Ok(json!({ "threads": threads }))
+    }
+
+    fn stack_trace(&mut self, args: StackTraceArguments) -> anyhow::Result<Value> {
+        let debugger = debugger(&mut self.debugger)?;
+        let pid = Pid::from_raw(args.thread_id);
+        let thread_num = debugger
+            .thread_state()?
+            .iter()
+            .find(|snapshot| snapshot.thread.pid == pid)
+            .map(|snapshot| snapshot.thread.number)
+            .ok_or_else(|| anyhow!("thread {pid} not found"))?;
+
+        let mut frames = vec![];
+        for (num, span) in debugger.backtrace(pid)?.iter().enumerate() {
+            self.frames.push((thread_num, num as u32));
+            let mut frame = json!({
+                "id": self.frames.len(),
+                "name": span.func_name.as_deref().unwrap_or("unknown"),
+                "line": 0,
+                "column": 0,
+                "instructionPointerReference": span.ip.to_string(),
+            });
+            if let Some(place) = weak_error!(debugger.find_place(span.ip)).flatten() {
+                frame["source"] = json!({
+                    "name": place.file.file_name().map(|name| name.to_string_lossy()),
+                    "path": place.file.to_string_lossy(),
+                });
+                frame["line"] = place.line_number.into();
+                frame["column"] = place.column_number.into();
+            }
+            frames.push(frame);
+        }
+
+        Ok(json!({ "totalFrames": frames.len(), "stackFrames": frames }))
+    }
+
+    /// Set focus on a frame shown to client.
+    fn focus_frame(&mut self, frame_id: usize) -> anyhow::Result<()> {
+        let (thread_num, frame_num) = *self
+            .frames
+            .get(frame_id.wrapping_sub(1))
+            .ok_or_else(|| anyhow!("unknown frame {frame_id}"))?;
+        let debugger = debugger(&mut self.debugger)?;
+        debugger.set_thread_into_focus(thread_num)?;
+        debugger.set_frame_into_focus(frame_num)?;
+        Ok(())
+    }
+
+    /// Remember variables for a future `variables` request, return a variables reference.
+    fn store_variables(&mut self, variables: Vec<(String, VariableIR)>) -> usize {
+        self.variables.push(variables);
+        self.variables.len()
+    }
+
+    fn scopes(&mut self, args: ScopesArguments) -> anyhow::Result<Value> {
+        self.focus_frame(args.frame_id)?;
+        let debugger = debugger(&mut self.debugger)?;
+        let locals = debugger.read_local_variables()?;
+        let arguments = debugger.read_argument(DQE::Variable(VariableSelector::Any))?;
+
+        let scopes: Vec<_> = [("Locals", locals), ("Arguments", arguments)]
+            .into_iter()
+            .map(|(name, vars)| {
+                let vars = vars.into_iter().map(|var| (var.name(), var)).collect();
+                json!({
+                    "name": name,
+                    "variablesReference": self.store_variables(vars),
+                    "expensive": false,
+                })
+            })
+            .collect();
+        Ok(json!({ "scopes": scopes }))
+    }
+
+    /// Represent a variable in client format.
+    fn variable(&mut self, name: String, var: &VariableIR) -> Value {
+        let children: Vec<(String, VariableIR)> = match var.value() {
+            Some(ValueLayout::Wrapped(inner)) => vec![(inner.name(), inner.clone())],
+            Some(ValueLayout::Structure { members }) => members
+                .iter()
+                .map(|member| (member.name(), member.clone()))
+                .collect(),
+            Some(ValueLayout::List { members, indexed }) => members
+                .iter()
+                .enumerate()
+                .map(|(i, member)| {
+                    let name = if indexed {
+                        member.name()
+                    } else {
+                        format!("[{i}]")
+                    };
+                    (name, member.clone())
+                })
+                .collect(),
+            Some(ValueLayout::Map(kvs)) => kvs
+                .iter()
+                .map(|(key, value)| (short_value(key), value.clone()))
+                .collect(),
+            _ => vec![],
+        };
+
+        let reference = if children.is_empty() {
+            0
+        } else {
+            self.store_variables(children)
+        };
+        json!({
+            "name": name,
+            "value": short_value(var),
+            "type": var.r#type(),
+            "variablesReference": reference,
+        })
+    }
+
+    fn variables(&mut self, args: VariablesArguments) -> anyhow::Result<Value> {
+        let children = self
+            .variables
+            .get(args.variables_reference.wrapping_sub(1))
+            .cloned()
+            .ok_or_else(|| anyhow!("unknown variables reference"))?;
+        let variables: Vec<_> = children
+            .into_iter()
+            .map(|(name, var)| self.variable(name, &var))
+            .collect();
+        Ok(json!({ "variables": variables }))
+    }
+
+    fn evaluate(&mut self, args: EvaluateArguments) -> anyhow::Result<Value> {
+        if let Some(frame_id) = args.frame_id {
+            self.focus_frame(frame_id)?;
+        }
+        let expr = expression::parser()
+            .parse(args.expression.as_str())
+            .into_result()
+            .map_err(|_| anyhow!("invalid expression: {}", args.expression))?;
+
+        let debugger = debugger(&mut self.debugger)?;
+        let mut vars = debugger.read_variable(expr.clone())?;
+        if vars.is_empty() {
+            vars = debugger.read_argument(expr)?;
+        }
+        let var = vars
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("{} not found", args.expression))?;
+
+        let var = self.variable(var.name(), &var);
+        Ok(json!({
+            "result": var["value"],
+            "type": var["type"],
+            "variablesReference": var["variablesReference"],
+        }))
+    }
+}
@@ -0,0 +1,241 @@
+//! Debug Adapter Protocol base messages and wire format.
+//!
+//! Each message is a JSON object prefixed by a header: `Content-Length: <N>\r\n\r\n`.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io;
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+
+const CONTENT_LENGTH_HEADER: &str = "Content-Length:";
+
+/// Request from a client (editor).
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Request {
+    pub seq: i64,
+    pub command: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+/// Read next message from the stream. Return `None` if stream is closed.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Request>> {
+    let mut content_len = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim();
+        if header.is_empty() {
+            if content_len.is_some() {
+                break;
+            }
+            continue;
+        }
+
+        if let Some(len) = header.strip_prefix(CONTENT_LENGTH_HEADER) {
+            let len = len.trim().parse::<usize>().map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("content length: {e}"))
+            })?;
+            content_len = Some(len);
+        }
+    }
+
+    let mut content = vec![0; content_len.expect("infallible")];
+    reader.read_exact(&mut content)?;
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write a single message into the stream.
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        writer,
+        "{CONTENT_LENGTH_HEADER} {}\r\n\r\n{content}",
+        content.len()
+    )?;
+    writer.flush()
+}
+
+/// Sends responses and events to a client, may be shared between threads.
+#[derive(Clone)]
+pub struct Sender {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    seq: Arc<AtomicI64>,
+}
+
+impl Sender {
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+            seq: Arc::new(AtomicI64::new(1)),
+        }
+    }
+
+    fn send(&self, mut message: Value) {
+        message["seq"] = self.seq.fetch_add(1, Ordering::SeqCst).into();
+        let mut writer = self.writer.lock().unwrap();
+        if let Err(e) = write_message(&mut *writer, &message) {
+            log::warn!(target: "dap", "send message: {e}");
+        }
+    }
+
+    /// Send successful response to a request.
+    pub fn response(&self, request: &Request, body: Value) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "command": request.command,
+            "success": true,
+            "body": body,
+        }));
+    }
+
+    /// Send error response to a request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request`: failed request
+    /// * `code`: error code
+    /// * `message`: error description
+    pub fn error(&self, request: &Request, code: i32, message: impl ToString) {
+        let message = message.to_string();
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "command": request.command,
+            "success": false,
+            "message": message,
+            "body": { "error": { "id": code, "format": message } },
+        }));
+    }
+
+    /// Send an event.
+    pub fn event(&self, event: &str, body: Value) {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }));
+    }
+}
+
+/// Arguments of `launch` request.
+#[derive(Deserialize)]
+pub struct LaunchArguments {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Arguments of `attach` request.
+#[derive(Deserialize)]
+pub struct AttachArguments {
+    pub pid: i32,
+}
+
+#[derive(Deserialize)]
+pub struct Source {
+    pub path: String,
+}
+
+#[derive(Deserialize)]
+pub struct SourceBreakpoint {
+    pub line: u64,
+    pub condition: Option<String>,
+}
+
+/// Arguments of `setBreakpoints` request.
+#[derive(Deserialize)]
+pub struct SetBreakpointsArguments {
+    pub source: Source,
+    #[serde(default)]
+    pub breakpoints: Vec<SourceBreakpoint>,
+}
+
+#[derive(Deserialize)]
+pub struct FunctionBreakpoint {
+    pub name: String,
+    pub condition: Option<String>,
+}
+
+/// Arguments of `setFunctionBreakpoints` request.
+#[derive(Deserialize)]
+pub struct SetFunctionBreakpointsArguments {
+    pub breakpoints: Vec<FunctionBreakpoint>,
+}
+
+/// Arguments of `stackTrace` request.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackTraceArguments {
+    pub thread_id: i32,
+}
+
+/// Arguments of `scopes` request.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopesArguments {
+    pub frame_id: usize,
+}
+
+/// Arguments of `variables` request.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariablesArguments {
+    pub variables_reference: usize,
+}
+
+/// Arguments of `evaluate` request.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateArguments {
+    pub expression: String,
+    pub frame_id: Option<usize>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_message_read_write() {
+        let mut buf = vec![];
+        let message = json!({"seq": 1, "type": "request", "command": "initialize"});
+        write_message(&mut buf, &message).unwrap();
+        write_message(
+            &mut buf,
+            &json!({"seq": 2, "type": "request", "command": "threads", "arguments": {"a": 1}}),
+        )
+        .unwrap();
+
+        let text = String::from_utf8(buf.clone()).unwrap();
+        assert!(text.starts_with("Content-Length: "));
+
+        let mut reader = BufReader::new(buf.as_slice());
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            Some(Request {
+                seq: 1,
+                command: "initialize".to_string(),
+                arguments: Value::Null,
+            })
+        );
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            Some(Request {
+                seq: 2,
+                command: "threads".to_string(),
+                arguments: json!({"a": 1}),
+            })
+        );
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+}
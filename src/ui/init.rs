@@ -0,0 +1,255 @@
+//! Init files support.
+//!
+//! Before the debugger starts, BugStalker looks for two init files:
+//! a global one (`$XDG_CONFIG_HOME/bugstalker/init` or `~/.config/bugstalker/init`)
+//! and a project-local `.bugstalker` in the current working directory.
+//! Both files are optional, project-local file is applied after the global one.
+//!
+//! Project-local file may come with a cloned repository, so it's loaded only if
+//! a current directory is inside one of safe paths (see `safe-path` directive of the global
+//! init file) or if loading is explicitly allowed (`--local-init` option).
+//!
+//! Init commands are executed by the console ui, tui applies only settings
+//! (key mode, bindings, hyperlinks and default arguments). If a session starts with tui,
+//! init commands are executed at the first switch to console.
+//!
+//! Init file is a list of lines, every line is one of:
+//! - `# ...` - comment, ignored
+//! - `args <arg1> <arg2> ...` - default debugee arguments,
+//! used if no arguments are passed from command line
+//! - `alias <name> <command>` - command alias
+//! - `keymap emacs|vi` - key mode for console line editor and tui navigation
+//! - `bind <action> <key>` - bind a key (like `F5` or `ctrl+n`) to tui action
+//! (`continue`, `run`, `step_over`, `step_into`, `step_out`, `quit`, `switch_ui`)
+//! - `hyperlinks <template>` - show source locations as terminal hyperlinks,
+//! template is an url with `{path}` and `{line}` placeholders
+//! - `safe-path <dir>` - allow project-local init files in a directory and its subdirectories,
+//! used only in the global init file
+//! - any other line is a debugger command (like `break main.rs:10`)
+
+use crate::ui::keymap::{self, Action, KeyMode, Keymap};
+use log::{info, warn};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{env, fs, io};
+
+const GLOBAL_INIT_DIR: &str = "bugstalker";
+const GLOBAL_INIT_FILE: &str = "init";
+const LOCAL_INIT_FILE: &str = ".bugstalker";
+
+const ARGS_DIRECTIVE: &str = "args";
+const ALIAS_DIRECTIVE: &str = "alias";
+const KEYMAP_DIRECTIVE: &str = "keymap";
+const BIND_DIRECTIVE: &str = "bind";
+const HYPERLINKS_DIRECTIVE: &str = "hyperlinks";
+const SAFE_PATH_DIRECTIVE: &str = "safe-path";
+
+/// Commands and settings collected from init files.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InitScript {
+    /// Default debugee arguments.
+    pub args: Option<Vec<String>>,
+    /// Command aliases in (name, command) form.
+    pub aliases: Vec<(String, String)>,
+    /// Commands that are executed at debugger start.
+    pub commands: Vec<String>,
+    /// Key mode and user defined keybindings.
+    pub keymap: Keymap,
+    /// Url template for source location hyperlinks.
+    pub hyperlinks: Option<String>,
+    /// Directories where project-local init files are allowed.
+    pub safe_paths: Vec<PathBuf>,
+}
+
+impl InitScript {
+    /// Load init script from global and project-local init files. Missing files are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `allow_local`: load project-local init file even if a current directory
+    ///   is not a safe path
+    pub fn load(allow_local: bool) -> Self {
+        let mut script = InitScript::default();
+        if let Some(path) = Self::global_init_file() {
+            script.extend_from_file(&path);
+        }
+
+        let local = Path::new(LOCAL_INIT_FILE);
+        if local.exists() {
+            let cwd = env::current_dir().unwrap_or_default();
+            if allow_local || is_safe_path(&cwd, &script.safe_paths) {
+                script.extend_from_file(local);
+            } else {
+                warn!(
+                    target: "debugger",
+                    "project-local init file {} is ignored, use --local-init option or add `{SAFE_PATH_DIRECTIVE} {}` into the global init file",
+                    cwd.join(local).display(),
+                    cwd.display()
+                );
+            }
+        }
+        script
+    }
+
+    fn global_init_file() -> Option<PathBuf> {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(config_dir.join(GLOBAL_INIT_DIR).join(GLOBAL_INIT_FILE))
+    }
+
+    fn extend_from_file(&mut self, path: &Path) {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                info!(target: "debugger", "load init file {}", path.display());
+                self.extend(&content);
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                warn!(target: "debugger", "read init file {}: {e}", path.display())
+            }
+        }
+    }
+
+    /// Apply init file content to script.
+    fn extend(&mut self, content: &str) {
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (head, tail) = line
+                .split_once(char::is_whitespace)
+                .map(|(h, t)| (h, t.trim()))
+                .unwrap_or((line, ""));
+
+            match head {
+                ARGS_DIRECTIVE => {
+                    self.args = Some(tail.split_whitespace().map(ToString::to_string).collect());
+                }
+                ALIAS_DIRECTIVE => match tail.split_once(char::is_whitespace) {
+                    Some((name, command)) => {
+                        let command = command.trim().to_string();
+                        self.aliases.retain(|(n, _)| n != name);
+                        self.aliases.push((name.to_string(), command));
+                    }
+                    None => warn!(target: "debugger", "invalid alias in init file: {line}"),
+                },
+                KEYMAP_DIRECTIVE => match KeyMode::from_str(tail) {
+                    Ok(mode) => self.keymap.mode = mode,
+                    Err(_) => warn!(target: "debugger", "invalid keymap in init file: {line}"),
+                },
+                BIND_DIRECTIVE => {
+                    let binding = tail.split_once(char::is_whitespace).and_then(|(a, k)| {
+                        Some((Action::from_str(a).ok()?, keymap::parse_key(k.trim())?))
+                    });
+                    match binding {
+                        Some((action, key)) => self.keymap.bind(action, key),
+                        None => warn!(target: "debugger", "invalid binding in init file: {line}"),
+                    }
+                }
+                HYPERLINKS_DIRECTIVE if !tail.is_empty() => {
+                    self.hyperlinks = Some(tail.to_string())
+                }
+                SAFE_PATH_DIRECTIVE if !tail.is_empty() => {
+                    self.safe_paths.push(PathBuf::from(tail))
+                }
+                _ => self.commands.push(line.to_string()),
+            }
+        }
+    }
+
+    /// Replace first word of the command with alias value if alias exists.
+    pub fn expand_alias<'a>(&self, cmd: &'a str) -> Cow<'a, str> {
+        let (head, tail) = cmd.split_once(' ').unwrap_or((cmd, ""));
+        match self.aliases.iter().find(|(name, _)| name == head) {
+            Some((_, expansion)) if tail.is_empty() => expansion.clone().into(),
+            Some((_, expansion)) => format!("{expansion} {tail}").into(),
+            None => cmd.into(),
+        }
+    }
+}
+
+/// Check that a directory is one of safe paths or is inside one of them.
+fn is_safe_path(dir: &Path, safe_paths: &[PathBuf]) -> bool {
+    let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = canonical(dir);
+    safe_paths
+        .iter()
+        .any(|safe_path| dir.starts_with(canonical(safe_path)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ui::init::{is_safe_path, InitScript};
+    use crate::ui::keymap::{Action, KeyMode};
+    use std::path::{Path, PathBuf};
+    use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn test_init_script_parse() {
+        let mut script = InitScript::default();
+        script.extend(
+            r#"
+            # comment line
+            args --foo bar
+            alias bm break main.rs:10
+            keymap vi
+            bind continue F5
+            bind step_over ctrl+n
+            bind unknown F1
+            hyperlinks vscode://file/{path}:{line}
+            safe-path /home/user/projects
+            break fn1
+            "#,
+        );
+        script.extend("alias bm break main.rs:20\nargs");
+
+        assert_eq!(script.args, Some(vec![]));
+        assert_eq!(
+            script.aliases,
+            vec![("bm".to_string(), "break main.rs:20".to_string())]
+        );
+        assert_eq!(script.commands, vec!["break fn1".to_string()]);
+        assert_eq!(
+            script.hyperlinks.as_deref(),
+            Some("vscode://file/{path}:{line}")
+        );
+
+        assert_eq!(
+            script.safe_paths,
+            vec![PathBuf::from("/home/user/projects")]
+        );
+
+        assert_eq!(script.expand_alias("bm"), "break main.rs:20");
+        assert_eq!(script.expand_alias("bm 1"), "break main.rs:20 1");
+        assert_eq!(script.expand_alias("break fn1"), "break fn1");
+
+        assert_eq!(script.keymap.mode, KeyMode::Vi);
+        assert_eq!(
+            script.keymap.keys(Action::Continue),
+            vec![KeyEvent::new(Key::Function(5), KeyModifiers::NONE)]
+        );
+        assert_eq!(
+            script.keymap.keys(Action::StepOver),
+            vec![KeyEvent::new(Key::Char('n'), KeyModifiers::CONTROL)]
+        );
+    }
+
+    #[test]
+    fn test_safe_path() {
+        let safe_paths = vec![PathBuf::from("/home/user/projects")];
+        assert!(is_safe_path(Path::new("/home/user/projects"), &safe_paths));
+        assert!(is_safe_path(
+            Path::new("/home/user/projects/app/src"),
+            &safe_paths
+        ));
+        assert!(!is_safe_path(
+            Path::new("/home/user/projects2"),
+            &safe_paths
+        ));
+        assert!(!is_safe_path(Path::new("/tmp/app"), &safe_paths));
+        assert!(!is_safe_path(Path::new("/home/user/projects"), &[]));
+    }
+}
@@ -0,0 +1,198 @@
+//! Keybindings configuration.
+//!
+//! Key mode (`emacs` or `vi`) affects a console line editor and tui navigation
+//! (in `vi` mode `h`, `j`, `k`, `l` keys may be used instead of arrows).
+//! Keys of tui global actions (continue, steps, etc.) can be rebound.
+//! Both settings are loaded from init files (`keymap` and `bind` directives).
+
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+
+/// Line editing and navigation mode.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, EnumString, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum KeyMode {
+    #[default]
+    Emacs,
+    Vi,
+}
+
+/// Tui actions that can be bound to a key.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, EnumString, Display, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum Action {
+    Continue,
+    Run,
+    StepOver,
+    StepInto,
+    StepOut,
+    Quit,
+    SwitchUi,
+}
+
+impl Action {
+    fn default_keys(self) -> Vec<KeyEvent> {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        match self {
+            Action::Continue => vec![key(Key::Char('c')), key(Key::Function(9))],
+            Action::Run => vec![key(Key::Char('r')), key(Key::Function(10))],
+            Action::StepOver => vec![key(Key::Function(8))],
+            Action::StepInto => vec![key(Key::Function(7))],
+            Action::StepOut => vec![key(Key::Function(6))],
+            Action::Quit => vec![key(Key::Char('q'))],
+            Action::SwitchUi => vec![key(Key::Esc)],
+        }
+    }
+}
+
+/// Parse a key description like `F9`, `ctrl+n`, `alt+Left` or `x`.
+pub fn parse_key(s: &str) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut parts: Vec<&str> = s.split('+').collect();
+    // a single `+` char is a valid key too
+    let key = match parts.pop()? {
+        "" if s.ends_with("++") || s == "+" => {
+            parts.pop();
+            "+"
+        }
+        key => key,
+    };
+
+    for modifier in parts {
+        modifiers |= match modifier.to_lowercase().as_str() {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+
+    let mut chars = key.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(ch), None) => Key::Char(ch),
+        _ => match key.to_lowercase().as_str() {
+            "esc" => Key::Esc,
+            "enter" => Key::Enter,
+            "tab" => Key::Tab,
+            "space" => Key::Char(' '),
+            "backspace" => Key::Backspace,
+            "delete" => Key::Delete,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" => Key::PageUp,
+            "pagedown" => Key::PageDown,
+            f if f.starts_with('f') => Key::Function(u8::from_str(&f[1..]).ok()?),
+            _ => return None,
+        },
+    };
+
+    Some(KeyEvent::new(code, modifiers))
+}
+
+/// User defined keybindings.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Keymap {
+    pub mode: KeyMode,
+    /// Keys bound by user, replace default keys of an action.
+    bindings: Vec<(Action, KeyEvent)>,
+}
+
+impl Keymap {
+    /// Bind a key to an action, default keys of the action are not used anymore.
+    pub fn bind(&mut self, action: Action, key: KeyEvent) {
+        self.bindings.retain(|(_, k)| *k != key);
+        self.bindings.push((action, key));
+    }
+
+    /// Return all keys of an action.
+    pub fn keys(&self, action: Action) -> Vec<KeyEvent> {
+        let keys: Vec<_> = self
+            .bindings
+            .iter()
+            .filter(|(a, _)| *a == action)
+            .map(|(_, k)| *k)
+            .collect();
+        if keys.is_empty() {
+            return action
+                .default_keys()
+                .into_iter()
+                .filter(|key| !self.bindings.iter().any(|(_, k)| k == key))
+                .collect();
+        }
+        keys
+    }
+
+    /// Return action bound to a key.
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        Action::iter().find(|&action| self.keys(action).contains(key))
+    }
+
+    /// Translate vi navigation keys (`h`, `j`, `k`, `l`) into arrows, do nothing in emacs mode.
+    pub fn navigation(&self, key: KeyEvent) -> KeyEvent {
+        if self.mode != KeyMode::Vi || key.modifiers != KeyModifiers::NONE {
+            return key;
+        }
+        let code = match key.code {
+            Key::Char('h') => Key::Left,
+            Key::Char('j') => Key::Down,
+            Key::Char('k') => Key::Up,
+            Key::Char('l') => Key::Right,
+            code => code,
+        };
+        KeyEvent::new(code, key.modifiers)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(
+            parse_key("F9"),
+            Some(KeyEvent::new(Key::Function(9), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("ctrl+n"),
+            Some(KeyEvent::new(Key::Char('n'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            parse_key("alt+Left"),
+            Some(KeyEvent::new(Key::Left, KeyModifiers::ALT))
+        );
+        assert_eq!(
+            parse_key("ctrl++"),
+            Some(KeyEvent::new(Key::Char('+'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(parse_key("super+x"), None);
+        assert_eq!(parse_key("unknown"), None);
+    }
+
+    #[test]
+    fn test_keymap_bindings() {
+        let mut keymap = Keymap::default();
+        let c = KeyEvent::new(Key::Char('c'), KeyModifiers::NONE);
+        let f5 = KeyEvent::new(Key::Function(5), KeyModifiers::NONE);
+        assert_eq!(keymap.action(&c), Some(Action::Continue));
+
+        keymap.bind(Action::Continue, f5);
+        assert_eq!(keymap.action(&f5), Some(Action::Continue));
+        assert_eq!(keymap.action(&c), None);
+
+        // `r` is not a default key of `run` action anymore
+        let r = KeyEvent::new(Key::Char('r'), KeyModifiers::NONE);
+        keymap.bind(Action::StepOver, r);
+        assert_eq!(keymap.action(&r), Some(Action::StepOver));
+        assert_eq!(
+            keymap.keys(Action::Run),
+            vec![KeyEvent::new(Key::Function(10), KeyModifiers::NONE)]
+        );
+    }
+}
@@ -1,15 +1,24 @@
+pub mod cargo;
 pub mod command;
 pub mod config;
 pub mod console;
+pub mod dap;
+pub mod init;
+pub mod keymap;
+pub mod script;
 pub mod short;
+pub mod style;
 pub mod supervisor;
 mod syntax;
 pub mod tui;
 
+use nix::libc;
 use os_pipe::PipeReader;
 use std::io::Read;
 use std::os::fd::{AsRawFd, RawFd};
 use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[derive(Clone)]
 pub struct DebugeeOutReader(pub Arc<PipeReader>);
@@ -20,6 +29,19 @@ impl From<PipeReader> for DebugeeOutReader {
     }
 }
 
+impl DebugeeOutReader {
+    /// Return number of bytes written into the stream but not read yet.
+    pub fn pending_bytes(&self) -> usize {
+        let mut pending: libc::c_int = 0;
+        // SAFETY: FIONREAD writes a single int into `pending`
+        let res = unsafe { libc::ioctl(self.as_raw_fd(), libc::FIONREAD, &mut pending) };
+        if res < 0 {
+            return 0;
+        }
+        pending as usize
+    }
+}
+
 impl AsRawFd for DebugeeOutReader {
     fn as_raw_fd(&self) -> RawFd {
         self.0.as_raw_fd()
@@ -32,6 +54,22 @@ impl Read for DebugeeOutReader {
     }
 }
 
+/// Wait (at most `timeout`) until all data written into debugee streams is read
+/// by output threads.
+pub fn wait_output_read(streams: &[DebugeeOutReader], timeout: Duration) {
+    let has_pending = || streams.iter().any(|s| s.pending_bytes() > 0);
+    if !has_pending() {
+        return;
+    }
+
+    let deadline = Instant::now() + timeout;
+    while has_pending() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(1));
+    }
+    // and give a time to print the last read lines
+    thread::sleep(Duration::from_millis(2));
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum AppState {
     Initial,
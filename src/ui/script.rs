@@ -0,0 +1,453 @@
+//! Embedded scripting engine.
+//!
+//! Scripts are written in [rhai](https://rhai.rs) language and have access to a debugger through
+//! a set of builtin functions:
+//! - `run()`, `cont()`, `step_into()`, `step_over()`, `step_out()`, `stepi()` - control debugee execution
+//! - `break_at(location)` - set breakpoint (location format is the same as in `break` command),
+//! returns a list of new breakpoint numbers
+//! - `break_remove(number)` - remove breakpoint by its number
+//! - `var(expression)`, `arg(expression)` - read variables or arguments by data query expression,
+//! returns a list of values
+//! - `locals()` - read all local variables, returns a map (variable name -> value)
+//! - `bt()` - return list of function names from backtrace of thread in focus
+//! - `reg(name)` - read register value
+//! - `mem(address, n)` - read `n` bytes from debugee memory
+//! - `location()` - return current location, a map with `pc`, `file` and `line` keys
+//! - `pretty_printer(name, type_prefix, function)` - register a pretty-printer, structures which
+//! type name starts with `type_prefix` are rendered by a script `function`
+//! - `print(value)` - print value into debugger output
+//!
+//! Integers that don't fit into a rhai integer (`i64`), like large `u64` values or addresses
+//! in the upper half of address space, are passed to scripts as decimal strings.
+//!
+//! Pretty-printer functions receive a structure as a map and return its text representation.
+//! They are executed by a separate engine without debugger functions, so rendering a variable
+//! never changes a debugee state.
+
+use crate::debugger::variable::pretty::{PrettyPrinter, PrettyPrinterContext, PrettyValue};
+use crate::debugger::variable::render::{RenderRepr, ValueLayout};
+use crate::debugger::variable::select::DQE;
+use crate::debugger::variable::{
+    SpecializedVariableIR, StructVariable, SupportedScalar, VariableIR,
+};
+use crate::debugger::{Debugger, Error};
+use crate::ui::command::parser::{
+    brkpt_at_addr_parser, brkpt_at_fn, brkpt_at_line_parser, brkpt_at_symbol,
+};
+use crate::ui::command::r#break::{BreakpointIdentity, Command as BreakpointCommand};
+use crate::ui::command::{parser, r#break};
+use chumsky::prelude::choice;
+use chumsky::Parser;
+use log::warn;
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, INT};
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ScriptError {
+    #[error("read script file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("script error: {0}")]
+    Eval(#[from] Box<EvalAltResult>),
+}
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Pointer to a debugger, valid only while [`ScriptEngine::run`] is executed.
+#[derive(Clone, Default)]
+struct DebuggerHandle(Rc<Cell<Option<*mut Debugger>>>);
+
+/// Keeps a debugger available for script functions, a previous handle value is restored
+/// on drop, so a handle never outlives a debugger reference, even if script evaluation
+/// returns early or panics.
+struct DebuggerGuard<'a> {
+    handle: &'a DebuggerHandle,
+    prev: Option<*mut Debugger>,
+    _debugger: PhantomData<&'a mut Debugger>,
+}
+
+impl Drop for DebuggerGuard<'_> {
+    fn drop(&mut self) {
+        self.handle.0.set(self.prev);
+    }
+}
+
+impl DebuggerHandle {
+    /// Make a debugger available for script functions until the returned guard is dropped.
+    fn set<'a>(&'a self, debugger: &'a mut Debugger) -> DebuggerGuard<'a> {
+        let prev = self.0.replace(Some(debugger as *mut Debugger));
+        DebuggerGuard {
+            handle: self,
+            prev,
+            _debugger: PhantomData,
+        }
+    }
+
+    fn with<T>(&self, f: impl FnOnce(&mut Debugger) -> Result<T, Error>) -> ScriptResult<T> {
+        let ptr = self
+            .0
+            .get()
+            .ok_or_else(|| Box::<EvalAltResult>::from("debugger is not available"))?;
+        // SAFETY: pointer is set from a mutable reference by `DebuggerHandle::set` and cleared
+        // when a guard (that borrows this reference) is dropped.
+        let debugger = unsafe { &mut *ptr };
+        f(debugger).map_err(|e| e.to_string().into())
+    }
+}
+
+/// Rhai engine with registered debugger functions.
+pub struct ScriptEngine {
+    engine: Engine,
+    handle: DebuggerHandle,
+    /// Source of a script in progress, pretty-printer functions are taken from it.
+    source: Rc<RefCell<String>>,
+}
+
+impl ScriptEngine {
+    /// Create a new engine.
+    ///
+    /// # Arguments
+    ///
+    /// * `printer`: used for script output (`print` and `debug` functions)
+    pub fn new(printer: impl Fn(&str) + Clone + 'static) -> Self {
+        let mut engine = Engine::new();
+        let handle = DebuggerHandle::default();
+        let source = Rc::new(RefCell::new(String::new()));
+
+        let print = printer.clone();
+        engine.on_print(move |s| print(s));
+        engine.on_debug(move |s, _, _| printer(s));
+
+        macro_rules! register_exec {
+            ($name: literal, $method: ident) => {{
+                let h = handle.clone();
+                engine.register_fn($name, move || h.with(|dbg| dbg.$method()));
+            }};
+        }
+        register_exec!("run", start_debugee);
+        register_exec!("cont", continue_debugee);
+        register_exec!("step_into", step_into);
+        register_exec!("step_over", step_over);
+        register_exec!("step_out", step_out);
+        register_exec!("stepi", stepi);
+
+        let h = handle.clone();
+        engine.register_fn("break_at", move |location: &str| -> ScriptResult<Array> {
+            let identity = choice((
+                brkpt_at_addr_parser(),
+                brkpt_at_line_parser(),
+                brkpt_at_symbol(),
+                brkpt_at_fn(),
+            ))
+            .parse(location)
+            .into_result()
+            .map_err(|_| format!("invalid breakpoint location: {location}"))?;
+            h.with(|dbg| {
+                let cmd = BreakpointCommand::Add(identity);
+                let mut handler = r#break::Handler::new(dbg);
+                let r#break::ExecutionResult::New(brkpts) = handler.handle(&cmd)? else {
+                    return Ok(Array::new());
+                };
+                Ok(brkpts
+                    .iter()
+                    .map(|b| Dynamic::from(b.number as INT))
+                    .collect())
+            })
+        });
+
+        let h = handle.clone();
+        engine.register_fn("break_remove", move |number: INT| {
+            h.with(|dbg| {
+                let cmd = BreakpointCommand::Remove(BreakpointIdentity::Number(number as u32));
+                r#break::Handler::new(dbg).handle(&cmd).map(|_| ())
+            })
+        });
+
+        let h = handle.clone();
+        engine.register_fn("var", move |expr: &str| -> ScriptResult<Array> {
+            let dqe = parse_dqe(expr)?;
+            h.with(|dbg| dbg.read_variable(dqe))
+                .map(|vars| vars.iter().map(variable_to_dynamic).collect())
+        });
+
+        let h = handle.clone();
+        engine.register_fn("arg", move |expr: &str| -> ScriptResult<Array> {
+            let dqe = parse_dqe(expr)?;
+            h.with(|dbg| dbg.read_argument(dqe))
+                .map(|args| args.iter().map(variable_to_dynamic).collect())
+        });
+
+        let h = handle.clone();
+        engine.register_fn("locals", move || -> ScriptResult<Map> {
+            h.with(|dbg| dbg.read_local_variables()).map(|vars| {
+                vars.iter()
+                    .map(|v| (v.name().into(), variable_to_dynamic(v)))
+                    .collect()
+            })
+        });
+
+        let h = handle.clone();
+        engine.register_fn("bt", move || -> ScriptResult<Array> {
+            h.with(|dbg| {
+                let pid = dbg.exploration_ctx().pid_on_focus();
+                dbg.backtrace(pid)
+            })
+            .map(|bt| {
+                bt.into_iter()
+                    .map(|frame| frame.func_name.map(Dynamic::from).unwrap_or(Dynamic::UNIT))
+                    .collect()
+            })
+        });
+
+        let h = handle.clone();
+        engine.register_fn("reg", move |name: &str| -> ScriptResult<Dynamic> {
+            h.with(|dbg| dbg.get_register_value(name))
+                .map(int_to_dynamic)
+        });
+
+        let h = handle.clone();
+        engine.register_fn("mem", move |addr: INT, n: INT| -> ScriptResult<Array> {
+            h.with(|dbg| dbg.read_memory(addr as usize, n as usize))
+                .map(|bytes| bytes.into_iter().map(|b| Dynamic::from(b as INT)).collect())
+        });
+
+        let h = handle.clone();
+        engine.register_fn("location", move || -> ScriptResult<Map> {
+            h.with(|dbg| {
+                let pc = dbg.exploration_ctx().location().pc;
+                let mut location = Map::new();
+                location.insert("pc".into(), int_to_dynamic(pc.as_usize()));
+                if let Some(place) = dbg.find_place(pc)? {
+                    let file = place.file.to_string_lossy().to_string();
+                    location.insert("file".into(), Dynamic::from(file));
+                    location.insert("line".into(), Dynamic::from(place.line_number as INT));
+                }
+                Ok(location)
+            })
+        });
+
+        let h = handle.clone();
+        let src = source.clone();
+        engine.register_fn(
+            "pretty_printer",
+            move |name: &str, type_prefix: &str, func: &str| -> ScriptResult<()> {
+                let printer = ScriptPrinter::new(name, type_prefix, &src.borrow(), func)?;
+                h.with(|dbg| {
+                    dbg.register_pretty_printer(Arc::new(printer));
+                    Ok(())
+                })
+            },
+        );
+
+        Self {
+            engine,
+            handle,
+            source,
+        }
+    }
+
+    /// Execute a script.
+    ///
+    /// # Arguments
+    ///
+    /// * `debugger`: debugger instance, available for script functions
+    /// * `script`: script source code
+    pub fn run(&self, debugger: &mut Debugger, script: &str) -> Result<Dynamic, ScriptError> {
+        let _guard = self.handle.set(debugger);
+        *self.source.borrow_mut() = script.to_string();
+        Ok(self.engine.eval::<Dynamic>(script)?)
+    }
+
+    /// Execute a script from file.
+    ///
+    /// # Arguments
+    ///
+    /// * `debugger`: debugger instance, available for script functions
+    /// * `path`: path to script file
+    pub fn run_file(&self, debugger: &mut Debugger, path: &str) -> Result<Dynamic, ScriptError> {
+        let script = std::fs::read_to_string(path)?;
+        self.run(debugger, &script)
+    }
+}
+
+/// Pretty-printer implemented by a script function.
+struct ScriptPrinter {
+    name: &'static str,
+    type_prefix: String,
+    script: String,
+    func: String,
+}
+
+impl ScriptPrinter {
+    fn new(name: &str, type_prefix: &str, script: &str, func: &str) -> ScriptResult<Self> {
+        let ast = Engine::new().compile(script)?;
+        if !ast.iter_functions().any(|f| f.name == func) {
+            return Err(format!("pretty-printer function `{func}` not found").into());
+        }
+
+        Ok(Self {
+            // printer names are static, printers registered by scripts live until debugger exit
+            name: Box::leak(name.to_string().into_boxed_str()),
+            type_prefix: type_prefix.to_string(),
+            script: script.to_string(),
+            func: func.to_string(),
+        })
+    }
+
+    fn call(&self, value: Dynamic) -> ScriptResult<String> {
+        let engine = Engine::new();
+        let ast = engine.compile(&self.script)?;
+        // don't execute top-level statements of a script, only the printer function
+        let options = CallFnOptions::new().eval_ast(false);
+        let result: Dynamic =
+            engine.call_fn_with_options(options, &mut Scope::new(), &ast, &self.func, (value,))?;
+        Ok(result.to_string())
+    }
+}
+
+impl PrettyPrinter for ScriptPrinter {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn matches(&self, type_name: &str, _: &[String]) -> bool {
+        type_name.starts_with(&self.type_prefix)
+    }
+
+    fn render(&self, _: &PrettyPrinterContext, structure: StructVariable) -> SpecializedVariableIR {
+        let value = variable_to_dynamic(&VariableIR::Struct(structure.clone()));
+        let value = match self.call(value) {
+            Ok(text) => Some(PrettyValue::Text(text)),
+            Err(e) => {
+                warn!(target: "debugger", "pretty-printer `{}`: {e}", self.name);
+                None
+            }
+        };
+        SpecializedVariableIR::Custom {
+            value,
+            original: structure,
+        }
+    }
+}
+
+fn parse_dqe(expr: &str) -> ScriptResult<DQE> {
+    parser::expression::parser()
+        .parse(expr)
+        .into_result()
+        .map_err(|_| format!("invalid expression: {expr}").into())
+}
+
+/// Convert an integer into rhai value, integers that don't fit into [`INT`]
+/// are converted into decimal strings instead of wrapping.
+fn int_to_dynamic<T: TryInto<INT> + ToString + Copy>(v: T) -> Dynamic {
+    match v.try_into() {
+        Ok(v) => Dynamic::from(v),
+        Err(_) => Dynamic::from(v.to_string()),
+    }
+}
+
+fn scalar_to_dynamic(scalar: &SupportedScalar) -> Dynamic {
+    match scalar {
+        SupportedScalar::I8(v) => int_to_dynamic(*v),
+        SupportedScalar::I16(v) => int_to_dynamic(*v),
+        SupportedScalar::I32(v) => int_to_dynamic(*v),
+        SupportedScalar::I64(v) => int_to_dynamic(*v),
+        SupportedScalar::I128(v) => int_to_dynamic(*v),
+        SupportedScalar::Isize(v) => int_to_dynamic(*v),
+        SupportedScalar::U8(v) => int_to_dynamic(*v),
+        SupportedScalar::U16(v) => int_to_dynamic(*v),
+        SupportedScalar::U32(v) => int_to_dynamic(*v),
+        SupportedScalar::U64(v) => int_to_dynamic(*v),
+        SupportedScalar::U128(v) => int_to_dynamic(*v),
+        SupportedScalar::Usize(v) => int_to_dynamic(*v),
+        SupportedScalar::F32(v) => Dynamic::from(*v as f64),
+        SupportedScalar::F64(v) => Dynamic::from(*v),
+        SupportedScalar::Bool(v) => Dynamic::from(*v),
+        SupportedScalar::Char(v) => Dynamic::from(*v),
+        SupportedScalar::Empty() => Dynamic::UNIT,
+    }
+}
+
+/// Convert variable into rhai value. Structures converts into maps, lists into arrays,
+/// hashmaps into arrays of key-value pairs, pointers into addresses.
+fn variable_to_dynamic(var: &VariableIR) -> Dynamic {
+    if let VariableIR::Scalar(scalar) = var {
+        return scalar
+            .value
+            .as_ref()
+            .map(scalar_to_dynamic)
+            .unwrap_or(Dynamic::UNIT);
+    }
+
+    match var.value() {
+        None => Dynamic::UNIT,
+        Some(ValueLayout::PreRendered(s)) => Dynamic::from(s.to_string()),
+        Some(ValueLayout::Referential { addr }) => int_to_dynamic(addr as usize),
+        Some(ValueLayout::Wrapped(inner)) => variable_to_dynamic(inner),
+        Some(ValueLayout::Structure { members }) => Dynamic::from_map(
+            members
+                .iter()
+                .map(|m| (m.name().into(), variable_to_dynamic(m)))
+                .collect::<Map>(),
+        ),
+        Some(ValueLayout::List { members, .. }) => {
+            Dynamic::from_array(members.iter().map(variable_to_dynamic).collect())
+        }
+        Some(ValueLayout::Map(kvs)) => Dynamic::from_array(
+            kvs.iter()
+                .map(|(k, v)| {
+                    Dynamic::from_array(vec![variable_to_dynamic(k), variable_to_dynamic(v)])
+                })
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debugger::variable::{ScalarVariable, VariableIdentity};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_script_printer() {
+        let script = r#"
+            fn render_point(p) { `(${p.x}, ${p.y})` }
+            pretty_printer("point", "Point", "render_point");
+        "#;
+        let printer = ScriptPrinter::new("point", "Point", script, "render_point").unwrap();
+        assert!(printer.matches("Point<i32>", &[]));
+        assert!(!printer.matches("Line", &[]));
+        assert!(ScriptPrinter::new("point", "Point", script, "unknown").is_err());
+
+        let member = |name: &str, value: i32| {
+            VariableIR::Scalar(ScalarVariable {
+                identity: VariableIdentity::no_namespace(Some(name.to_string())),
+                type_name: Some("i32".to_string()),
+                value: Some(SupportedScalar::I32(value)),
+            })
+        };
+        let point = StructVariable {
+            identity: VariableIdentity::no_namespace(Some("p".to_string())),
+            type_name: Some("Point<i32>".to_string()),
+            members: vec![member("x", 1), member("y", -2)],
+            type_params: HashMap::new(),
+        };
+        let value = variable_to_dynamic(&VariableIR::Struct(point));
+        assert_eq!(printer.call(value).unwrap(), "(1, -2)");
+    }
+
+    #[test]
+    fn test_large_int_conversion() {
+        let value = scalar_to_dynamic(&SupportedScalar::U64(42));
+        assert_eq!(value.as_int().unwrap(), 42);
+        let value = scalar_to_dynamic(&SupportedScalar::U64(u64::MAX));
+        assert_eq!(value.into_string().unwrap(), "18446744073709551615");
+        let value = scalar_to_dynamic(&SupportedScalar::I128(i128::MIN));
+        assert_eq!(value.into_string().unwrap(), i128::MIN.to_string());
+        let value = scalar_to_dynamic(&SupportedScalar::I64(i64::MIN));
+        assert_eq!(value.as_int().unwrap(), i64::MIN);
+    }
+}
@@ -0,0 +1,162 @@
+//! Colors of ui elements (addresses, file paths, tui panels, etc.).
+//!
+//! Colors can be changed at runtime with `set style` command (or in init file)
+//! and fully disabled with `--no-color` argument or `NO_COLOR` environment variable.
+
+use crossterm::style::Color;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use strum_macros::{Display, EnumIter, EnumString};
+
+/// Styled ui elements.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, EnumString, Display, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum StyleElement {
+    Address,
+    FilePath,
+    FunctionName,
+    Keyword,
+    AsmInstruction,
+    AsmOperands,
+    Error,
+    Breakpoint,
+    /// Borders and highlighted items of tui panels.
+    Panel,
+}
+
+impl StyleElement {
+    fn default_color(self) -> Color {
+        match self {
+            StyleElement::Address => Color::Blue,
+            StyleElement::FilePath => Color::Green,
+            StyleElement::FunctionName => Color::Yellow,
+            StyleElement::Keyword => Color::Magenta,
+            StyleElement::AsmInstruction => Color::DarkRed,
+            StyleElement::AsmOperands => Color::DarkGreen,
+            StyleElement::Error => Color::DarkRed,
+            StyleElement::Breakpoint => Color::Cyan,
+            StyleElement::Panel => Color::Yellow,
+        }
+    }
+}
+
+/// User defined colors, `None` value means that element has no color.
+static STYLES: Lazy<RwLock<HashMap<StyleElement, Option<Color>>>> = Lazy::new(RwLock::default);
+
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+
+/// Disable (or enable) all colors.
+pub fn set_no_color(no_color: bool) {
+    NO_COLOR.store(no_color, Ordering::Relaxed);
+}
+
+/// Return true if colors disabled.
+pub fn is_no_color() -> bool {
+    cfg!(feature = "int_test") || NO_COLOR.load(Ordering::Relaxed)
+}
+
+/// Return write style for loggers, colored output disabled in no-color mode.
+pub fn log_write_style() -> env_logger::WriteStyle {
+    if is_no_color() {
+        env_logger::WriteStyle::Never
+    } else {
+        env_logger::WriteStyle::Auto
+    }
+}
+
+/// Set color for ui element.
+///
+/// # Arguments
+///
+/// * `element`: ui element
+/// * `color`: new color, `None` for remove color from element
+pub fn set_color(element: StyleElement, color: Option<Color>) {
+    STYLES.write().unwrap().insert(element, color);
+}
+
+/// Return color of ui element or `None` if element has no color.
+pub fn color(element: StyleElement) -> Option<Color> {
+    if is_no_color() {
+        return None;
+    }
+
+    STYLES
+        .read()
+        .unwrap()
+        .get(&element)
+        .copied()
+        .unwrap_or(Some(element.default_color()))
+}
+
+/// Parse color name (`red`, `dark_blue`, etc.), hex rgb (`#ff00aa`) or `none`.
+/// Return `None` if color is unknown.
+pub fn parse_color(s: &str) -> Option<Option<Color>> {
+    if s == "none" {
+        return Some(None);
+    }
+
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let rgb = u32::from_str_radix(hex, 16).ok()?;
+        return Some(Some(Color::Rgb {
+            r: (rgb >> 16) as u8,
+            g: (rgb >> 8) as u8,
+            b: rgb as u8,
+        }));
+    }
+
+    Color::try_from(s).ok().map(Some)
+}
+
+/// Return color of ui element for tui components.
+pub fn tui_color(element: StyleElement) -> tuirealm::tui::style::Color {
+    use tuirealm::tui::style::Color as TuiColor;
+
+    match color(element) {
+        None | Some(Color::Reset) => TuiColor::Reset,
+        Some(Color::Black) => TuiColor::Black,
+        Some(Color::DarkGrey) => TuiColor::DarkGray,
+        Some(Color::Red) => TuiColor::LightRed,
+        Some(Color::DarkRed) => TuiColor::Red,
+        Some(Color::Green) => TuiColor::LightGreen,
+        Some(Color::DarkGreen) => TuiColor::Green,
+        Some(Color::Yellow) => TuiColor::LightYellow,
+        Some(Color::DarkYellow) => TuiColor::Yellow,
+        Some(Color::Blue) => TuiColor::LightBlue,
+        Some(Color::DarkBlue) => TuiColor::Blue,
+        Some(Color::Magenta) => TuiColor::LightMagenta,
+        Some(Color::DarkMagenta) => TuiColor::Magenta,
+        Some(Color::Cyan) => TuiColor::LightCyan,
+        Some(Color::DarkCyan) => TuiColor::Cyan,
+        Some(Color::White) => TuiColor::White,
+        Some(Color::Grey) => TuiColor::Gray,
+        Some(Color::Rgb { r, g, b }) => TuiColor::Rgb(r, g, b),
+        Some(Color::AnsiValue(v)) => TuiColor::Indexed(v),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ui::style::parse_color;
+    use crossterm::style::Color;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("none"), Some(None));
+        assert_eq!(parse_color("dark_red"), Some(Some(Color::DarkRed)));
+        assert_eq!(
+            parse_color("#ff0010"),
+            Some(Some(Color::Rgb {
+                r: 0xff,
+                g: 0,
+                b: 0x10
+            }))
+        );
+        assert_eq!(parse_color("#ff00"), None);
+        assert_eq!(parse_color("unknown"), None);
+    }
+}
@@ -5,13 +5,15 @@ use crate::ui::console::TerminalApplication;
 use crate::ui::tui::TuiApplication;
 use crate::ui::{console, tui};
 use anyhow::Context;
-use log::{info, warn};
 use nix::unistd::Pid;
+use std::path::Path;
 
 /// Interface type.
 pub enum Interface {
     TUI,
     Default,
+    /// Non-interactive console interface, executes a list of commands and exit.
+    Batch(Vec<String>),
 }
 
 /// Source from which debugee is created or attached.
@@ -20,6 +22,8 @@ pub enum DebugeeSource<'a> {
     File { path: &'a str, args: &'a [String] },
     /// Create debugee from an already running process by its pid.
     Process { pid: i32 },
+    /// Create debugee from a core file of a dumped process and its executable file.
+    Core { path: &'a str, core: &'a Path },
 }
 
 /// Possible applications.
@@ -71,20 +75,13 @@ impl Supervisor {
                 Child::from_external(Pid::from_raw(pid), stdout_writer, stderr_writer)
                     .context("Attach external process")?
             }
+            DebugeeSource::Core { path, core } => {
+                Child::from_core(path, core, stdout_writer, stderr_writer)
+                    .context("Open core file")?
+            }
         };
 
-        let oracles = oracles
-            .iter()
-            .filter_map(|ora_name| {
-                if let Some(oracle) = builtin::make_builtin(ora_name) {
-                    info!(target: "debugger", "oracle `{ora_name}` discovered");
-                    Some(oracle)
-                } else {
-                    warn!(target: "debugger", "oracle `{ora_name}` not found");
-                    None
-                }
-            })
-            .collect();
+        let oracles = builtin::discover(oracles);
 
         let mut app = match ui {
             Interface::TUI => {
@@ -102,6 +99,15 @@ impl Supervisor {
                     .context("Build debugger")?;
                 Application::Terminal(app)
             }
+            Interface::Batch(commands) => {
+                let app_builder =
+                    console::AppBuilder::new(stdout_reader.into(), stderr_reader.into())
+                        .batch(commands);
+                let app = app_builder
+                    .build(DebuggerBuilder::new().with_oracles(oracles), process)
+                    .context("Build debugger")?;
+                Application::Terminal(app)
+            }
         };
 
         loop {
@@ -1,4 +1,5 @@
 use crate::ui::config;
+use std::io;
 use std::sync::OnceLock;
 use syntect::easy::HighlightLines;
 use syntect::highlighting::{Style, ThemeSet};
@@ -9,6 +10,15 @@ pub struct RustCodeLineRenderer<'a> {
     highlighter: Option<HighlightLines<'a>>,
 }
 
+/// Source code rendering error.
+#[derive(thiserror::Error, Debug)]
+pub enum RenderError {
+    #[error(transparent)]
+    IO(#[from] io::Error),
+    #[error(transparent)]
+    Highlight(#[from] syntect::Error),
+}
+
 /// Stylized line representation.
 pub enum StylizedLine<'a> {
     /// No styling needed.
@@ -19,7 +29,7 @@ pub enum StylizedLine<'a> {
 
 impl<'a> RustCodeLineRenderer<'a> {
     /// Prettify rust code-line if needed.
-    pub fn render_line<'s>(&mut self, line: &'s str) -> anyhow::Result<StylizedLine<'s>> {
+    pub fn render_line<'s>(&mut self, line: &'s str) -> Result<StylizedLine<'s>, RenderError> {
         match &mut self.highlighter {
             None => Ok(StylizedLine::NoneStyle(line)),
             Some(h) => Ok(StylizedLine::Stylized(
@@ -0,0 +1,142 @@
+//! Layout of tui windows (size of windows, split windows, hidden windows and selected tabs).
+//!
+//! Layout is saved into `.bugstalker_tui` file in the current directory, so each project
+//! has its own layout that survives debugger restarts.
+
+use log::warn;
+use std::fs;
+use std::path::Path;
+
+pub const LAYOUT_FILE: &str = ".bugstalker_tui";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TuiLayout {
+    /// Width of the left window (in percents).
+    pub left_width: u16,
+    /// If true - only the right window is shown.
+    pub left_hidden: bool,
+    /// Index of a tab shown below selected tab in the left window.
+    pub left_split: Option<usize>,
+    /// Index of a tab shown below selected tab in the right window.
+    pub right_split: Option<usize>,
+    /// Index of selected tab in the left window.
+    pub left_tab: usize,
+    /// Index of selected tab in the right window.
+    pub right_tab: usize,
+}
+
+impl Default for TuiLayout {
+    fn default() -> Self {
+        Self {
+            left_width: 25,
+            left_hidden: false,
+            left_split: None,
+            right_split: None,
+            left_tab: 0,
+            right_tab: 0,
+        }
+    }
+}
+
+impl TuiLayout {
+    const MIN_WIDTH: u16 = 10;
+    const MAX_WIDTH: u16 = 90;
+
+    /// Load layout from file, return default layout if file not exists or malformed.
+    pub fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Save layout into file.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    fn parse(text: &str) -> Self {
+        let mut layout = Self::default();
+
+        fn parse_split(value: &str) -> Option<Option<usize>> {
+            if value == "none" {
+                return Some(None);
+            }
+            value.parse().ok().map(Some)
+        }
+
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let Some((key, value)) = line.split_once(' ') else {
+                warn!(target: "tui", "malformed layout line: {line}");
+                continue;
+            };
+            let value = value.trim();
+
+            let parsed = match key {
+                "left_width" => value
+                    .parse()
+                    .ok()
+                    .map(|w: u16| layout.left_width = w.clamp(Self::MIN_WIDTH, Self::MAX_WIDTH)),
+                "left_hidden" => value.parse().ok().map(|h| layout.left_hidden = h),
+                "left_split" => parse_split(value).map(|s| layout.left_split = s),
+                "right_split" => parse_split(value).map(|s| layout.right_split = s),
+                "left_tab" => value.parse().ok().map(|t| layout.left_tab = t),
+                "right_tab" => value.parse().ok().map(|t| layout.right_tab = t),
+                _ => None,
+            };
+            if parsed.is_none() {
+                warn!(target: "tui", "malformed layout line: {line}");
+            }
+        }
+
+        layout
+    }
+
+    /// Change width of the left window, the right window takes the rest of space.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta`: width delta in percents
+    pub fn resize(&mut self, delta: i16) {
+        self.left_width = self
+            .left_width
+            .saturating_add_signed(delta)
+            .clamp(Self::MIN_WIDTH, Self::MAX_WIDTH);
+    }
+}
+
+impl std::fmt::Display for TuiLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let split = |s: Option<usize>| s.map(|s| s.to_string()).unwrap_or("none".to_string());
+
+        writeln!(f, "left_width {}", self.left_width)?;
+        writeln!(f, "left_hidden {}", self.left_hidden)?;
+        writeln!(f, "left_split {}", split(self.left_split))?;
+        writeln!(f, "right_split {}", split(self.right_split))?;
+        writeln!(f, "left_tab {}", self.left_tab)?;
+        writeln!(f, "right_tab {}", self.right_tab)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ui::tui::app::layout::TuiLayout;
+
+    #[test]
+    fn test_layout_serialization() {
+        let layout = TuiLayout {
+            left_width: 40,
+            left_hidden: true,
+            left_split: Some(2),
+            right_split: None,
+            left_tab: 1,
+            right_tab: 2,
+        };
+        assert_eq!(TuiLayout::parse(&layout.to_string()), layout);
+
+        let layout = TuiLayout::parse("left_width 200\nunknown 1\nright_split 3\n");
+        assert_eq!(layout.left_width, 90);
+        assert_eq!(layout.right_split, Some(3));
+        assert_eq!(layout.left_tab, 0);
+    }
+}
@@ -1,9 +1,12 @@
+pub mod layout;
 pub mod port;
 
 use crate::debugger::Error;
 use crate::ui::command;
 use crate::ui::command::r#break::BreakpointIdentity;
 use crate::ui::command::{r#break, run, CommandError};
+use crate::ui::config;
+use crate::ui::tui::app::layout::{TuiLayout, LAYOUT_FILE};
 use crate::ui::tui::app::port::{
     AsyncResponsesPort, DebuggerEventQueue, DebuggerEventsPort, LoggerPort, OutputPort, UserEvent,
 };
@@ -12,6 +15,7 @@ use crate::ui::tui::components::breakpoint::Breakpoints;
 use crate::ui::tui::components::control::GlobalControl;
 use crate::ui::tui::components::input::{Input, InputStringType};
 use crate::ui::tui::components::logs::Logs;
+use crate::ui::tui::components::memory::Memory;
 use crate::ui::tui::components::oracle::make_oracle_tab_window;
 use crate::ui::tui::components::output::Output;
 use crate::ui::tui::components::popup::{Popup, YesNoLabels};
@@ -21,11 +25,14 @@ use crate::ui::tui::components::threads::Threads;
 use crate::ui::tui::components::variables::Variables;
 use crate::ui::tui::proto::ClientExchanger;
 use crate::ui::tui::utils::logger::TuiLogLine;
+use crate::ui::tui::utils::mstextarea::MultiSpanTextarea;
 use crate::ui::tui::utils::tab;
 use crate::ui::tui::utils::tab::TabWindow;
+use crate::weak_error;
 use chumsky::Parser;
 use log::warn;
 use std::borrow::Cow;
+use std::path::Path;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -38,7 +45,7 @@ use tuirealm::{
     props, Application, AttrValue, Attribute, EventListenerCfg, Sub, SubClause, SubEventClause,
 };
 
-use super::{BreakpointsAddType, ConfirmedAction, DebugeeStreamBuffer, Id, Msg};
+use super::{BreakpointsAddType, ConfirmedAction, DebugeeStreamBuffer, Id, MemoryInputType, Msg};
 
 pub struct Model {
     /// Application
@@ -53,11 +60,11 @@ pub struct Model {
     exchanger: Arc<ClientExchanger>,
     /// Layout of main tabs
     tabs_layout: [Constraint; 2],
+    /// User defined layout, persisted between debugger runs
+    layout: TuiLayout,
 }
 
 impl Model {
-    const DEFAULT_TABS_LAYOUT: [Constraint; 2] =
-        [Constraint::Percentage(25), Constraint::Percentage(75)];
     const LEFT_TAB_FOCUS_LAYOUT: [Constraint; 2] =
         [Constraint::Percentage(90), Constraint::Percentage(10)];
     const RIGHT_TAB_FOCUS_LAYOUT: [Constraint; 2] =
@@ -70,15 +77,92 @@ impl Model {
         log_buffer: Arc<Mutex<Vec<TuiLogLine>>>,
     ) -> anyhow::Result<Self> {
         let exchanger = Arc::new(client_exchanger);
+        let layout = TuiLayout::load(Path::new(LAYOUT_FILE));
+        let mut app = Self::init_app(output_buf, event_queue, exchanger.clone(), log_buffer)?;
+        Self::apply_layout(&mut app, &layout)?;
+
         Ok(Self {
-            app: Self::init_app(output_buf, event_queue, exchanger.clone(), log_buffer)?,
+            app,
             quit: false,
             redraw: true,
             terminal: TerminalBridge::new().expect("Cannot initialize terminal"),
             exchanger,
-            tabs_layout: Self::DEFAULT_TABS_LAYOUT,
+            tabs_layout: Self::default_tabs_layout(&layout),
+            layout,
         })
     }
+
+    /// Return main tabs layout according to user defined layout.
+    fn default_tabs_layout(layout: &TuiLayout) -> [Constraint; 2] {
+        if layout.left_hidden {
+            return [Constraint::Percentage(0), Constraint::Percentage(100)];
+        }
+        [
+            Constraint::Percentage(layout.left_width),
+            Constraint::Percentage(100 - layout.left_width),
+        ]
+    }
+
+    fn apply_layout(
+        app: &mut Application<Id, Msg, UserEvent>,
+        layout: &TuiLayout,
+    ) -> anyhow::Result<()> {
+        for (id, tab, split) in [
+            (Id::LeftTabs, layout.left_tab, layout.left_split),
+            (Id::RightTabs, layout.right_tab, layout.right_split),
+        ] {
+            app.attr(&id, TabWindow::SELECT_WINDOW_ATTR, AttrValue::Length(tab))?;
+            app.attr(
+                &id,
+                TabWindow::SPLIT_WINDOW_ATTR,
+                AttrValue::Payload(
+                    split
+                        .map(|idx| PropPayload::One(PropValue::Usize(idx)))
+                        .unwrap_or(PropPayload::None),
+                ),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Save current layout into the layout file.
+    fn save_layout(&mut self) {
+        let visible_tab = |app: &Application<Id, Msg, UserEvent>, id: &Id| {
+            app.query(id, TabWindow::VISIBLE_WINDOW_ATTR)
+                .ok()
+                .flatten()
+                .map(|v| v.unwrap_length())
+        };
+        let split_tab = |app: &Application<Id, Msg, UserEvent>, id: &Id| match app
+            .query(id, TabWindow::SPLIT_WINDOW_ATTR)
+            .ok()
+            .flatten()
+        {
+            Some(AttrValue::Payload(PropPayload::One(PropValue::Usize(idx)))) => Some(idx),
+            _ => None,
+        };
+
+        if let Some(idx) = visible_tab(&self.app, &Id::LeftTabs) {
+            self.layout.left_tab = idx;
+        }
+        if let Some(idx) = visible_tab(&self.app, &Id::RightTabs) {
+            self.layout.right_tab = idx;
+        }
+        self.layout.left_split = split_tab(&self.app, &Id::LeftTabs);
+        self.layout.right_split = split_tab(&self.app, &Id::RightTabs);
+
+        weak_error!(self.layout.save(Path::new(LAYOUT_FILE)), "save tui layout");
+    }
+
+    /// Reset view size of main tabs and apply user defined layout.
+    fn reset_tabs_layout(&mut self) -> anyhow::Result<()> {
+        for id in [&Id::LeftTabs, &Id::RightTabs] {
+            self.app
+                .attr(id, TabWindow::VIEW_SIZE_ATTR, tab::ViewSize::Default.into())?;
+        }
+        self.tabs_layout = Self::default_tabs_layout(&self.layout);
+        Ok(())
+    }
 }
 
 impl Model {
@@ -104,7 +188,10 @@ impl Model {
                 .constraints(self.tabs_layout)
                 .split(tabs_rect);
 
-            self.app.view(&Id::LeftTabs, f, tab_chunks[0]);
+            // left tabs may be hidden by user
+            if tab_chunks[0].width > 0 {
+                self.app.view(&Id::LeftTabs, f, tab_chunks[0]);
+            }
             self.app.view(&Id::RightTabs, f, tab_chunks[1]);
 
             if input_in_focus {
@@ -152,10 +239,11 @@ impl Model {
         let pid = exchanger
             .request_sync(|dbg| dbg.process().pid())
             .expect("messaging enabled at tui start");
+        let keymap = &config::current().init.keymap;
         app.mount(
             Id::GlobalControl,
-            Box::new(GlobalControl::new(exchanger.clone(), pid)),
-            GlobalControl::subscriptions(),
+            Box::new(GlobalControl::new(exchanger.clone(), pid, keymap.clone())),
+            GlobalControl::subscriptions(keymap),
         )?;
 
         app.mount(Id::Popup, Box::<Popup>::default(), vec![])?;
@@ -206,16 +294,25 @@ impl Model {
         let mut right_tab_sub = Source::subscriptions();
         right_tab_sub.extend(Asm::subscriptions());
         right_tab_sub.extend(Output::subscriptions());
+        right_tab_sub.extend(Memory::subscriptions());
         right_tab_sub.extend(vec![Sub::new(SubEventClause::Tick, SubClause::Always)]);
 
         let right_tab = TabWindow::new(
             "[2]",
-            &["</> Source", "📃 Output", "🤖 Asm", "🔮 Oracles", "💾 Logs"],
+            &[
+                "</> Source",
+                "📃 Output",
+                "🤖 Asm",
+                "🧮 Memory",
+                "🔮 Oracles",
+                "💾 Logs",
+            ],
             vec![
                 Box::new(Source::new(exchanger.clone())?),
                 Box::new(Output::new(&output)),
                 Box::new(Asm::new(exchanger.clone())?),
-                Box::new(make_oracle_tab_window(&oracles)),
+                Box::new(Memory::new(exchanger.clone())),
+                Box::new(make_oracle_tab_window(exchanger.clone(), &oracles)),
                 Box::<Logs>::default(),
             ],
             Some(|rewind_direction| match rewind_direction {
@@ -240,10 +337,16 @@ impl Model {
 
 impl Model {
     fn update_breakpoints(&mut self) -> anyhow::Result<()> {
-        Ok(self.app.attr(
+        self.app.attr(
             &Id::LeftTabs,
             Attribute::Custom("update_breakpoints"),
             AttrValue::Flag(true),
+        )?;
+        // source window shows breakpoints in a gutter
+        Ok(self.app.attr(
+            &Id::RightTabs,
+            Attribute::Custom("update_breakpoints"),
+            AttrValue::Flag(true),
         )?)
     }
 
@@ -254,6 +357,7 @@ impl Model {
             // Match message
             match msg {
                 Msg::AppClose => {
+                    self.save_layout();
                     self.exchanger.send_exit();
                     self.quit = true;
                 }
@@ -267,6 +371,7 @@ impl Model {
                     )?;
                 }
                 Msg::SwitchUI => {
+                    self.save_layout();
                     self.exchanger.send_switch_ui();
                     self.quit = true;
                 }
@@ -360,6 +465,73 @@ impl Model {
                     self.app.active(&Id::Input)?;
                     self.app.lock_subs();
                 }
+                Msg::MemoryInput(r#type) => {
+                    let (input_validator, input_data_type, title): (fn(&str) -> bool, _, _) =
+                        match r#type {
+                            MemoryInputType::Location => (
+                                Memory::is_valid_location,
+                                InputStringType::MemoryLocation,
+                                "Memory address or expression",
+                            ),
+                            MemoryInputType::Write => (
+                                Memory::is_valid_bytes,
+                                InputStringType::MemoryWrite,
+                                "Write bytes (hex, ex. `ff 0a`)",
+                            ),
+                        };
+
+                    self.app.attr(
+                        &Id::Input,
+                        Attribute::InputType,
+                        AttrValue::InputType(props::InputType::Custom(
+                            input_validator,
+                            |_, _| -> bool { true },
+                        )),
+                    )?;
+                    self.app.attr(
+                        &Id::Input,
+                        Attribute::Title,
+                        AttrValue::Title((title.to_string(), Alignment::Left)),
+                    )?;
+                    self.app.attr(
+                        &Id::Input,
+                        Attribute::Custom("input_data_type"),
+                        AttrValue::String(input_data_type.to_string()),
+                    )?;
+
+                    self.app.active(&Id::Input)?;
+                    self.app.lock_subs();
+                }
+                Msg::SearchInput => {
+                    self.app.attr(
+                        &Id::Input,
+                        Attribute::InputType,
+                        AttrValue::InputType(props::InputType::Text),
+                    )?;
+                    self.app.attr(
+                        &Id::Input,
+                        Attribute::Title,
+                        AttrValue::Title(("Search (empty for reset)".to_string(), Alignment::Left)),
+                    )?;
+                    self.app.attr(
+                        &Id::Input,
+                        Attribute::Custom("input_data_type"),
+                        AttrValue::String(InputStringType::Search.to_string()),
+                    )?;
+
+                    self.app.active(&Id::Input)?;
+                    self.app.lock_subs();
+                }
+                Msg::MemoryHighlight(addr) => {
+                    self.app.attr(
+                        &Id::RightTabs,
+                        Attribute::Custom("memory_highlight"),
+                        AttrValue::Payload(
+                            addr.map(|addr| PropPayload::One(PropValue::Usize(addr)))
+                                .unwrap_or(PropPayload::None),
+                        ),
+                    )?;
+                }
                 Msg::Input(input) => {
                     let input_data_type = InputStringType::from_str(
                         &self
@@ -386,6 +558,7 @@ impl Model {
                                 InputStringType::BreakpointAddAtAddress => {
                                     BreakpointIdentity::Address(input.parse().expect("infallible"))
                                 }
+                                _ => unreachable!(),
                             };
 
                             let cmd = r#break::Command::Add(identity);
@@ -401,6 +574,28 @@ impl Model {
                             self.update_breakpoints()?;
                             Ok(None)
                         }
+                        InputStringType::MemoryLocation | InputStringType::MemoryWrite => {
+                            let attr = if input_data_type == InputStringType::MemoryLocation {
+                                Attribute::Custom("memory_location")
+                            } else {
+                                Attribute::Custom("memory_write")
+                            };
+                            self.app.unlock_subs();
+                            self.app.blur()?;
+                            self.app
+                                .attr(&Id::RightTabs, attr, AttrValue::String(input))?;
+                            Ok(None)
+                        }
+                        InputStringType::Search => {
+                            self.app.unlock_subs();
+                            self.app.blur()?;
+                            self.app.attr(
+                                &Id::RightTabs,
+                                MultiSpanTextarea::SEARCH_ATTR,
+                                AttrValue::String(input),
+                            )?;
+                            Ok(None)
+                        }
                     };
                 }
                 Msg::InputCancel => {
@@ -448,10 +643,21 @@ impl Model {
                         .as_ref()
                         .map(|p| p.line_number.to_string())
                         .unwrap_or("unknown".to_string());
-                    let text = format!(
-                        "Breakpoint #{}\nAt: {:?}:{}\nAddress: {}",
-                        brkpt.number, file, line, brkpt.addr
+                    let mut text = format!(
+                        "Breakpoint #{}\nAt: {:?}:{}\nAddress: {}\nEnabled: {}\nHits: {}",
+                        brkpt.number,
+                        file,
+                        line,
+                        brkpt.addr,
+                        !brkpt.options.disabled,
+                        brkpt.options.hit_count,
                     );
+                    if let Some(ref condition) = brkpt.options.condition {
+                        text.push_str(&format!("\nCondition: {}", condition.source));
+                    }
+                    if brkpt.options.ignore_count > 0 {
+                        text.push_str(&format!("\nIgnore next: {}", brkpt.options.ignore_count));
+                    }
 
                     self.app
                         .attr(&Id::Popup, Attribute::Text, AttrValue::String(text))?;
@@ -498,11 +704,43 @@ impl Model {
                         self.app.blur()?;
                     }
                 },
+                Msg::ThreadFocusChanged => {
+                    // windows that depend on a thread in focus must be updated
+                    self.app.attr(
+                        &Id::LeftTabs,
+                        Attribute::Custom("thread_focus_changed"),
+                        AttrValue::Flag(true),
+                    )?;
+                    self.app.attr(
+                        &Id::RightTabs,
+                        Attribute::Custom("thread_focus_changed"),
+                        AttrValue::Flag(true),
+                    )?;
+                }
+                Msg::BreakpointsChanged => {
+                    self.update_breakpoints()?;
+                }
+                Msg::ShowSource { file, line } => {
+                    self.app.attr(
+                        &Id::RightTabs,
+                        Attribute::Custom("show_source"),
+                        AttrValue::Payload(PropPayload::Tup2((
+                            PropValue::Str(file.to_string_lossy().to_string()),
+                            PropValue::U64(line),
+                        ))),
+                    )?;
+                    // source window is the first in the right tabs
+                    self.app.attr(
+                        &Id::RightTabs,
+                        TabWindow::SELECT_WINDOW_ATTR,
+                        AttrValue::Length(0),
+                    )?;
+                }
                 Msg::ExpandTab(tab_id) => {
                     debug_assert!(tab_id == Id::RightTabs || tab_id == Id::LeftTabs);
                     match tab_id {
                         Id::RightTabs
-                            if self.tabs_layout == Self::DEFAULT_TABS_LAYOUT
+                            if self.tabs_layout == Self::default_tabs_layout(&self.layout)
                                 || self.tabs_layout == Self::LEFT_TAB_FOCUS_LAYOUT =>
                         {
                             self.app.attr(
@@ -519,7 +757,7 @@ impl Model {
                             self.tabs_layout = Self::RIGHT_TAB_FOCUS_LAYOUT;
                         }
                         Id::LeftTabs
-                            if self.tabs_layout == Self::DEFAULT_TABS_LAYOUT
+                            if self.tabs_layout == Self::default_tabs_layout(&self.layout)
                                 || self.tabs_layout == Self::RIGHT_TAB_FOCUS_LAYOUT =>
                         {
                             self.app.attr(
@@ -536,17 +774,34 @@ impl Model {
                             self.tabs_layout = Self::LEFT_TAB_FOCUS_LAYOUT;
                         }
                         _ => {
-                            for id in [&Id::LeftTabs, &Id::RightTabs] {
-                                self.app.attr(
-                                    id,
-                                    TabWindow::VIEW_SIZE_ATTR,
-                                    tab::ViewSize::Default.into(),
-                                )?;
-                            }
-                            self.tabs_layout = Self::DEFAULT_TABS_LAYOUT;
+                            self.reset_tabs_layout()?;
                         }
                     }
                 }
+                Msg::ResizeTabs(delta) => {
+                    self.layout.left_hidden = false;
+                    self.layout.resize(delta);
+                    self.reset_tabs_layout()?;
+                    self.save_layout();
+                }
+                Msg::HideLeftTabs => {
+                    self.layout.left_hidden = !self.layout.left_hidden;
+                    self.reset_tabs_layout()?;
+                    self.save_layout();
+                    if self.layout.left_hidden && self.app.focus() == Some(&Id::LeftTabs) {
+                        return Ok(Some(Msg::RightTabsInFocus { reset_to: None }));
+                    }
+                }
+                Msg::SplitTab => {
+                    let id = if self.app.focus() == Some(&Id::LeftTabs) {
+                        Id::LeftTabs
+                    } else {
+                        Id::RightTabs
+                    };
+                    self.app
+                        .attr(&id, TabWindow::SPLIT_NEXT_ATTR, AttrValue::Flag(true))?;
+                    self.save_layout();
+                }
 
                 Msg::None => {}
             }
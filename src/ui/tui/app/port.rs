@@ -1,10 +1,12 @@
 use crate::debugger::address::RelocatedAddress;
-use crate::debugger::{EventHook, FunctionDie, PlaceDescriptor};
+use crate::debugger::catchpoint::SyscallEvent;
+use crate::debugger::watchpoint::WatchValue;
+use crate::debugger::{Error, EventHook, FunctionDie, PlaceDescriptor, Tracee};
 use crate::ui::tui::output::OutputLine;
 use crate::ui::tui::proto::ClientExchanger;
 use crate::ui::tui::utils::logger::TuiLogLine;
 use crate::version;
-use log::warn;
+use log::{info, warn};
 use nix::sys::signal::Signal;
 use nix::unistd::Pid;
 use std::sync::{Arc, Mutex};
@@ -106,7 +108,7 @@ impl EventHook for TuiHook {
         num: u32,
         place: Option<PlaceDescriptor>,
         function: Option<&FunctionDie>,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), Error> {
         self.event_queue
             .lock()
             .unwrap()
@@ -125,7 +127,27 @@ impl EventHook for TuiHook {
         pc: RelocatedAddress,
         place: Option<PlaceDescriptor>,
         function: Option<&FunctionDie>,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), Error> {
+        self.event_queue.lock().unwrap().push(UserEvent::Step {
+            pc,
+            file: place.as_ref().map(|p| p.file.to_string_lossy().to_string()),
+            line: place.as_ref().map(|p| p.line_number),
+            function: function.and_then(|f| f.base_attributes.name.clone()),
+        });
+        Ok(())
+    }
+
+    fn on_watchpoint(
+        &self,
+        pc: RelocatedAddress,
+        num: u32,
+        place: Option<PlaceDescriptor>,
+        function: Option<&FunctionDie>,
+        old: &WatchValue,
+        new: &WatchValue,
+    ) -> Result<(), Error> {
+        info!(target: "debugger", "watchpoint {num} hit, old value: {old}, new value: {new}");
+        // windows are updated like after a step, a watchpoint hit is reported in logs
         self.event_queue.lock().unwrap().push(UserEvent::Step {
             pc,
             file: place.as_ref().map(|p| p.file.to_string_lossy().to_string()),
@@ -142,6 +164,22 @@ impl EventHook for TuiHook {
             .push(UserEvent::Signal(signal));
     }
 
+    fn on_syscall_catch(&self, pc: RelocatedAddress, event: &SyscallEvent) {
+        let name = event.name.unwrap_or("unknown");
+        info!(target: "debugger", "syscall {name} ({}) caught: {:?}", event.nr, event.stop);
+        // windows are updated like after a step, a syscall is reported in logs
+        self.event_queue.lock().unwrap().push(UserEvent::Step {
+            pc,
+            file: None,
+            line: None,
+            function: None,
+        });
+    }
+
+    fn on_thread_stop(&self, thread: &Tracee) {
+        info!(target: "debugger", "thread {} ({}) stopped, other threads are running", thread.number, thread.pid);
+    }
+
     fn on_exit(&self, code: i32) {
         self.event_queue.lock().unwrap().push(UserEvent::Exit(code));
     }
@@ -160,6 +198,10 @@ impl EventHook for TuiHook {
             .unwrap()
             .push(UserEvent::ProcessInstall(pid));
     }
+
+    fn on_output_flush(&self) {
+        // output window is updated independently of stop events
+    }
 }
 
 pub struct DebuggerEventsPort {
@@ -1,31 +1,58 @@
+use crate::ui::style::{self, StyleElement};
 use crate::ui::tui::app::port::UserEvent;
 use crate::ui::tui::proto::ClientExchanger;
 use crate::ui::tui::utils::mstextarea::MultiSpanTextarea;
 use crate::ui::tui::{Id, Msg};
 use std::sync::Arc;
-use tuirealm::command::{Cmd, Direction, Position};
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::event::{Key, KeyEvent};
 use tuirealm::props::{Borders, Style, TextSpan};
-use tuirealm::tui::layout::Alignment;
+use tuirealm::tui::layout::{Alignment, Rect};
 use tuirealm::tui::prelude::Color;
 use tuirealm::tui::widgets::BorderType;
 use tuirealm::{
-    AttrValue, Attribute, Component, Event, MockComponent, Sub, SubClause, SubEventClause,
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State, Sub, SubClause,
+    SubEventClause,
 };
 
-#[derive(MockComponent)]
 pub struct Asm {
     component: MultiSpanTextarea,
     exchanger: Arc<ClientExchanger>,
 }
 
+impl MockComponent for Asm {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.component.view(frame, area)
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        if matches!(attr, Attribute::Custom("thread_focus_changed")) {
+            return self.update_asm_view();
+        }
+
+        self.component.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
+}
+
 impl Asm {
     pub fn new(exchanger: Arc<ClientExchanger>) -> anyhow::Result<Self> {
         let component = MultiSpanTextarea::default()
             .borders(
                 Borders::default()
                     .modifiers(BorderType::Rounded)
-                    .color(Color::LightYellow),
+                    .color(style::tui_color(StyleElement::Panel)),
             )
             .inactive(Style::default().fg(Color::Gray))
             .title("Assembler code for function", Alignment::Center)
@@ -61,7 +88,8 @@ impl Asm {
             let mut line_in_focus = None;
             let mut lines = vec![];
             for instr in asm.instructions.into_iter() {
-                let addr_span = TextSpan::new(format!("{} ", instr.address)).fg(Color::Blue);
+                let addr_span = TextSpan::new(format!("{} ", instr.address))
+                    .fg(style::tui_color(StyleElement::Address));
                 let mnemonic_span =
                     TextSpan::new(format!("{} ", instr.mnemonic.as_deref().unwrap_or("???")))
                         .fg(Color::Red);
@@ -141,6 +169,24 @@ impl Component<Msg, UserEvent> for Asm {
             Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
                 self.perform(Cmd::GoTo(Position::End));
             }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('/'),
+                ..
+            }) => {
+                return Some(Msg::SearchInput);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n'),
+                ..
+            }) => {
+                self.component.search_next();
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('N'),
+                ..
+            }) => {
+                self.component.search_prev();
+            }
             Event::User(UserEvent::Breakpoint { .. }) | Event::User(UserEvent::Step { .. }) => {
                 self.update_asm_view();
             }
@@ -3,9 +3,11 @@ use crate::ui::command;
 use crate::ui::command::r#break::Command as BreakpointCommand;
 use crate::ui::command::r#break::ExecutionResult;
 use crate::ui::short::Abbreviator;
+use crate::ui::style::{self, StyleElement};
 use crate::ui::tui::app::port::UserEvent;
 use crate::ui::tui::proto::ClientExchanger;
 use crate::ui::tui::{BreakpointsAddType, Msg};
+use crate::weak_error;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tui_realm_stdlib::List;
@@ -111,15 +113,42 @@ impl Breakpoints {
         let abbreviator = Abbreviator::new("/", "/..", 50);
 
         for brkpt in breakpoints.iter() {
-            table_builder.add_col(TextSpan::from(brkpt.number.to_string()).fg(Color::Cyan));
-            table_builder.add_col(TextSpan::from(" "));
+            let number = TextSpan::from(brkpt.number.to_string());
+            let number = if brkpt.options.disabled {
+                number.fg(Color::Gray).italic()
+            } else {
+                number.fg(style::tui_color(StyleElement::Breakpoint))
+            };
+            table_builder.add_col(number);
+            table_builder.add_col(TextSpan::from(if brkpt.options.disabled {
+                " ○ "
+            } else {
+                " ● "
+            }));
             if let Some(ref place) = brkpt.place {
                 let breakpoint_path =
                     format!("{}:{}", place.file.to_string_lossy(), place.line_number);
                 let breakpoint_path = abbreviator.apply(&breakpoint_path);
                 table_builder.add_col(TextSpan::from(breakpoint_path));
             } else {
-                table_builder.add_col(TextSpan::from(format!("{}", brkpt.number)));
+                table_builder.add_col(TextSpan::from(format!("{}", brkpt.addr)));
+            }
+            if brkpt.options.hit_count > 0 {
+                table_builder.add_col(
+                    TextSpan::from(format!(" hits: {}", brkpt.options.hit_count)).fg(Color::Gray),
+                );
+            }
+            if brkpt.options.ignore_count > 0 {
+                table_builder.add_col(
+                    TextSpan::from(format!(" ignore: {}", brkpt.options.ignore_count))
+                        .fg(Color::Gray),
+                );
+            }
+            if let Some(ref condition) = brkpt.options.condition {
+                table_builder.add_col(
+                    TextSpan::from(format!(" if {}", condition.source))
+                        .fg(style::tui_color(StyleElement::Keyword)),
+                );
             }
             table_builder.add_row();
         }
@@ -134,17 +163,46 @@ impl Breakpoints {
 }
 
 impl Breakpoints {
+    /// Return breakpoint at selected row (if row contains a breakpoint).
+    fn selected(&self) -> Option<&BreakpointViewOwned> {
+        let idx = self.component.state().unwrap_one().unwrap_usize();
+        self.row_to_brkpt_map.get(&idx)
+    }
+
+    /// Enable selected breakpoint if it disabled, disable elsewhere.
+    fn toggle_selected(&mut self) {
+        let Some(brkpt) = self.selected() else {
+            return;
+        };
+        let number = brkpt.number;
+        let cmd = if brkpt.options.disabled {
+            BreakpointCommand::Enable(number)
+        } else {
+            BreakpointCommand::Disable(number)
+        };
+
+        if self
+            .exchanger
+            .request_sync(move |dbg| {
+                weak_error!(command::r#break::Handler::new(dbg).handle(&cmd));
+            })
+            .is_ok()
+        {
+            self.update_list();
+        }
+    }
+
     pub fn new(exchanger: Arc<ClientExchanger>) -> Self {
         let list = List::default()
             .borders(
                 Borders::default()
                     .modifiers(BorderType::Rounded)
-                    .color(Color::LightYellow),
+                    .color(style::tui_color(StyleElement::Panel)),
             )
             .title("Breakpoints", Alignment::Center)
             .scroll(true)
             .inactive(Style::default().fg(Color::Gray))
-            .highlighted_color(Color::LightYellow)
+            .highlighted_color(style::tui_color(StyleElement::Panel))
             .highlighted_str("▶")
             .rewind(true)
             .step(4);
@@ -242,6 +300,32 @@ impl Component<Msg, UserEvent> for Breakpoints {
                     return Some(Msg::PopupBreakpoint(brkpt.clone()));
                 }
             }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(' ') | Key::Char('e'),
+                ..
+            }) => {
+                self.toggle_selected();
+                return Some(Msg::BreakpointsChanged);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete | Key::Char('d'),
+                ..
+            }) => {
+                if let Some(brkpt) = self.selected() {
+                    return Some(Msg::PopupBreakpoint(brkpt.clone()));
+                }
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('g'),
+                ..
+            }) => {
+                if let Some(place) = self.selected().and_then(|brkpt| brkpt.place.as_ref()) {
+                    return Some(Msg::ShowSource {
+                        file: place.file.clone(),
+                        line: place.line_number,
+                    });
+                }
+            }
             _ => {}
         };
         Some(Msg::None)
@@ -1,6 +1,8 @@
+use crate::debugger::progress;
 use crate::debugger::Error;
 use crate::ui::command;
 use crate::ui::command::{run, CommandError};
+use crate::ui::keymap::{Action, Keymap};
 use crate::ui::tui::app::port::UserEvent;
 use crate::ui::tui::proto::ClientExchanger;
 use crate::ui::tui::{Id, Msg};
@@ -9,6 +11,7 @@ use nix::sys::signal;
 use nix::sys::signal::Signal;
 use nix::unistd::Pid;
 use std::sync::Arc;
+use strum::IntoEnumIterator;
 use tuirealm::event::{Key, KeyEvent, KeyModifiers};
 use tuirealm::{Component, Event, MockComponent, Sub, SubClause, SubEventClause};
 
@@ -17,19 +20,26 @@ pub struct GlobalControl {
     component: tui_realm_stdlib::Phantom,
     exchanger: Arc<ClientExchanger>,
     last_seen_pid: Pid,
+    keymap: Keymap,
 }
 
 impl GlobalControl {
-    pub fn new(exchanger: Arc<ClientExchanger>, pid: Pid) -> Self {
+    pub fn new(exchanger: Arc<ClientExchanger>, pid: Pid, keymap: Keymap) -> Self {
         Self {
             component: tui_realm_stdlib::Phantom::default(),
             exchanger,
             last_seen_pid: pid,
+            keymap,
         }
     }
 
-    pub fn subscriptions() -> Vec<Sub<Id, UserEvent>> {
-        vec![
+    pub fn subscriptions(keymap: &Keymap) -> Vec<Sub<Id, UserEvent>> {
+        let action_keys = Action::iter().flat_map(|action| keymap.keys(action));
+        let mut subs: Vec<_> = action_keys
+            .map(|key| Sub::new(SubEventClause::Keyboard(key), SubClause::Always))
+            .collect();
+
+        subs.extend([
             Sub::new(
                 SubEventClause::Keyboard(KeyEvent::new(Key::Char('1'), KeyModifiers::ALT)),
                 SubClause::Always,
@@ -39,55 +49,35 @@ impl GlobalControl {
                 SubClause::Always,
             ),
             Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Char('1'), KeyModifiers::NONE)),
-                SubClause::Always,
-            ),
-            Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Char('2'), KeyModifiers::NONE)),
-                SubClause::Always,
-            ),
-            Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Char('c'), KeyModifiers::ALT)),
-                SubClause::Always,
-            ),
-            Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Esc, KeyModifiers::NONE)),
-                SubClause::Always,
-            ),
-            Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Char('q'), KeyModifiers::NONE)),
-                SubClause::Always,
-            ),
-            Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Char('c'), KeyModifiers::NONE)),
+                SubEventClause::Keyboard(KeyEvent::new(Key::Left, KeyModifiers::ALT)),
                 SubClause::Always,
             ),
             Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Char('c'), KeyModifiers::CONTROL)),
+                SubEventClause::Keyboard(KeyEvent::new(Key::Right, KeyModifiers::ALT)),
                 SubClause::Always,
             ),
             Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Char('r'), KeyModifiers::NONE)),
+                SubEventClause::Keyboard(KeyEvent::new(Key::Char('h'), KeyModifiers::ALT)),
                 SubClause::Always,
             ),
             Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Function(6), KeyModifiers::NONE)),
+                SubEventClause::Keyboard(KeyEvent::new(Key::Char('s'), KeyModifiers::ALT)),
                 SubClause::Always,
             ),
             Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Function(7), KeyModifiers::NONE)),
+                SubEventClause::Keyboard(KeyEvent::new(Key::Char('1'), KeyModifiers::NONE)),
                 SubClause::Always,
             ),
             Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Function(8), KeyModifiers::NONE)),
+                SubEventClause::Keyboard(KeyEvent::new(Key::Char('2'), KeyModifiers::NONE)),
                 SubClause::Always,
             ),
             Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Function(9), KeyModifiers::NONE)),
+                SubEventClause::Keyboard(KeyEvent::new(Key::Char('c'), KeyModifiers::ALT)),
                 SubClause::Always,
             ),
             Sub::new(
-                SubEventClause::Keyboard(KeyEvent::new(Key::Function(10), KeyModifiers::NONE)),
+                SubEventClause::Keyboard(KeyEvent::new(Key::Char('c'), KeyModifiers::CONTROL)),
                 SubClause::Always,
             ),
             Sub::new(
@@ -128,52 +118,15 @@ impl GlobalControl {
             ),
             // concrete code doesn't meter
             Sub::new(SubEventClause::User(UserEvent::Exit(0)), SubClause::Always),
-        ]
+        ]);
+        subs
     }
-}
 
-impl Component<Msg, UserEvent> for GlobalControl {
-    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
-        let msg = match ev {
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('1'),
-                modifiers: KeyModifiers::ALT,
-            }) => Msg::ExpandTab(Id::LeftTabs),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('2'),
-                modifiers: KeyModifiers::ALT,
-            }) => Msg::ExpandTab(Id::RightTabs),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('1'),
-                modifiers: KeyModifiers::NONE,
-            }) => Msg::LeftTabsInFocus { reset_to: None },
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('2'),
-                modifiers: KeyModifiers::NONE,
-            }) => Msg::RightTabsInFocus { reset_to: None },
-            Event::Keyboard(KeyEvent {
-                code: Key::Esc,
-                modifiers: KeyModifiers::NONE,
-            }) => Msg::SwitchUI,
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('q'),
-                modifiers: KeyModifiers::NONE,
-            }) => Msg::AppClose,
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('c'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => {
-                _ = signal::kill(self.last_seen_pid, Signal::SIGINT);
-                Msg::None
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('c'),
-                modifiers: KeyModifiers::NONE,
-            })
-            | Event::Keyboard(KeyEvent {
-                code: Key::Function(9),
-                ..
-            }) => {
+    fn on_action(&mut self, action: Action) -> Option<Msg> {
+        let msg = match action {
+            Action::SwitchUi => Msg::SwitchUI,
+            Action::Quit => Msg::AppClose,
+            Action::Continue => {
                 if !self.exchanger.is_messaging_enabled() {
                     warn!(target: "tui", "try start/restart but messaging disabled");
                     return None;
@@ -186,14 +139,7 @@ impl Component<Msg, UserEvent> for GlobalControl {
                 self.exchanger.disable_messaging();
                 Msg::AppRunning
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('r'),
-                ..
-            })
-            | Event::Keyboard(KeyEvent {
-                code: Key::Function(10),
-                ..
-            }) => {
+            Action::Run => {
                 if !self.exchanger.is_messaging_enabled() {
                     warn!(target: "tui", "try start/restart but messaging disabled");
                     return None;
@@ -219,50 +165,81 @@ impl Component<Msg, UserEvent> for GlobalControl {
                     Msg::AppRunning
                 }
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::Function(8),
-                modifiers: KeyModifiers::NONE,
-            }) => {
+            Action::StepOver | Action::StepInto | Action::StepOut => {
                 if !self.exchanger.is_messaging_enabled() {
                     warn!(target: "tui", "try start/restart but messaging disabled");
                     return None;
                 }
 
-                self.exchanger
-                    .request_async(|dbg| Ok(command::step_over::Handler::new(dbg).handle()?))
-                    .expect("messaging enabled");
+                let step_result = match action {
+                    Action::StepOver => self
+                        .exchanger
+                        .request_async(|dbg| Ok(command::step_over::Handler::new(dbg).handle()?)),
+                    Action::StepInto => self
+                        .exchanger
+                        .request_async(|dbg| Ok(command::step_into::Handler::new(dbg).handle()?)),
+                    _ => self
+                        .exchanger
+                        .request_async(|dbg| Ok(command::step_out::Handler::new(dbg).handle()?)),
+                };
+                step_result.expect("messaging enabled");
 
                 Msg::AppRunning
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::Function(7),
-                modifiers: KeyModifiers::NONE,
-            }) => {
-                if !self.exchanger.is_messaging_enabled() {
-                    warn!(target: "tui", "try start/restart but messaging disabled");
-                    return None;
-                }
-
-                self.exchanger
-                    .request_async(|dbg| Ok(command::step_into::Handler::new(dbg).handle()?))
-                    .expect("messaging enabled");
+        };
+        Some(msg)
+    }
+}
 
-                Msg::AppRunning
+impl Component<Msg, UserEvent> for GlobalControl {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        if let Event::Keyboard(key) = &ev {
+            if let Some(action) = self.keymap.action(key) {
+                return self.on_action(action);
             }
+        }
+
+        let msg = match ev {
             Event::Keyboard(KeyEvent {
-                code: Key::Function(6),
+                code: Key::Char('1'),
+                modifiers: KeyModifiers::ALT,
+            }) => Msg::ExpandTab(Id::LeftTabs),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('2'),
+                modifiers: KeyModifiers::ALT,
+            }) => Msg::ExpandTab(Id::RightTabs),
+            Event::Keyboard(KeyEvent {
+                code: Key::Left,
+                modifiers: KeyModifiers::ALT,
+            }) => Msg::ResizeTabs(-5),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right,
+                modifiers: KeyModifiers::ALT,
+            }) => Msg::ResizeTabs(5),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('h'),
+                modifiers: KeyModifiers::ALT,
+            }) => Msg::HideLeftTabs,
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('s'),
+                modifiers: KeyModifiers::ALT,
+            }) => Msg::SplitTab,
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('1'),
                 modifiers: KeyModifiers::NONE,
+            }) => Msg::LeftTabsInFocus { reset_to: None },
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('2'),
+                modifiers: KeyModifiers::NONE,
+            }) => Msg::RightTabsInFocus { reset_to: None },
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
             }) => {
-                if !self.exchanger.is_messaging_enabled() {
-                    warn!(target: "tui", "try start/restart but messaging disabled");
-                    return None;
+                if !progress::cancel() {
+                    _ = signal::kill(self.last_seen_pid, Signal::SIGINT);
                 }
-
-                self.exchanger
-                    .request_async(|dbg| Ok(command::step_out::Handler::new(dbg).handle()?))
-                    .expect("messaging enabled");
-
-                Msg::AppRunning
+                Msg::None
             }
             Event::User(UserEvent::AsyncErrorResponse(err)) => {
                 Msg::ShowOkPopup(Some("Error".to_string()), err)
@@ -1,3 +1,4 @@
+use crate::ui::style::{self, StyleElement};
 use crate::ui::tui::app::port::UserEvent;
 use crate::ui::tui::Msg;
 use strum_macros::{Display, EnumString};
@@ -9,11 +10,14 @@ use tuirealm::tui::style::{Color, Style};
 use tuirealm::tui::widgets::BorderType;
 use tuirealm::{Component, Event, MockComponent, State, StateValue};
 
-#[derive(Debug, Display, EnumString)]
+#[derive(Debug, PartialEq, Display, EnumString)]
 pub enum InputStringType {
     BreakpointAddAtLine,
     BreakpointAddAtFunction,
     BreakpointAddAtAddress,
+    MemoryLocation,
+    MemoryWrite,
+    Search,
 }
 
 #[derive(MockComponent)]
@@ -28,9 +32,9 @@ impl Default for Input {
                 .borders(
                     Borders::default()
                         .modifiers(BorderType::Rounded)
-                        .color(Color::LightYellow),
+                        .color(style::tui_color(StyleElement::Panel)),
                 )
-                .foreground(Color::LightYellow)
+                .foreground(style::tui_color(StyleElement::Panel))
                 .input_type(InputType::Text)
                 .title("", Alignment::Left)
                 .value("")
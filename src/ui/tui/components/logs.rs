@@ -1,3 +1,4 @@
+use crate::ui::style::{self, StyleElement};
 use crate::ui::tui::app::port::UserEvent;
 use crate::ui::tui::utils::mstextarea::MultiSpanTextarea;
 use crate::ui::tui::{Id, Msg};
@@ -23,7 +24,7 @@ impl Default for Logs {
                 .borders(
                     Borders::default()
                         .modifiers(BorderType::Rounded)
-                        .color(Color::LightYellow),
+                        .color(style::tui_color(StyleElement::Panel)),
                 )
                 .inactive(Style::default().fg(Color::Gray))
                 .highlighted_str("▶")
@@ -0,0 +1,404 @@
+use crate::debugger::variable::render::{RenderRepr, ValueLayout};
+use crate::debugger::variable::select::DQE;
+use crate::debugger::variable::VariableIR;
+use crate::ui::command;
+use crate::ui::command::parser::{self, expression};
+use crate::ui::style::{self, StyleElement};
+use crate::ui::tui::app::port::UserEvent;
+use crate::ui::tui::proto::ClientExchanger;
+use crate::ui::tui::utils::mstextarea::MultiSpanTextarea;
+use crate::ui::tui::{Id, MemoryInputType, Msg};
+use anyhow::anyhow;
+use chumsky::Parser;
+use log::warn;
+use std::mem;
+use std::sync::Arc;
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::event::{Key, KeyEvent};
+use tuirealm::props::{Borders, PropPayload, PropValue, Style, TextSpan};
+use tuirealm::tui::layout::{Alignment, Rect};
+use tuirealm::tui::prelude::Color;
+use tuirealm::tui::widgets::BorderType;
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State, Sub, SubClause,
+    SubEventClause,
+};
+
+/// Number of bytes in a single row.
+const ROW_LEN: usize = 16;
+/// Number of rows in a view.
+const ROWS: usize = 32;
+const VIEW_LEN: usize = ROW_LEN * ROWS;
+
+/// Start of viewed memory region.
+#[derive(Clone)]
+enum Location {
+    Address(usize),
+    /// Expression result must be a pointer or an integer.
+    Expression(DQE),
+}
+
+pub struct Memory {
+    component: MultiSpanTextarea,
+    exchanger: Arc<ClientExchanger>,
+    /// Location and its string representation as it was entered by user.
+    location: Option<(Location, String)>,
+    /// Offset from a location address, changed by scrolling.
+    shift: isize,
+    /// Address of the first byte in view.
+    base: Option<usize>,
+    data: Vec<u8>,
+    /// Index of selected byte.
+    cursor: usize,
+    /// Address referenced by currently selected variable.
+    highlighted: Option<usize>,
+}
+
+impl MockComponent for Memory {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.component.view(frame, area)
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        match attr {
+            Attribute::Custom("memory_location") => {
+                let input = value.unwrap_string();
+                if let Some(location) = Self::parse_location(&input) {
+                    self.location = Some((location, input.trim().to_string()));
+                    self.shift = 0;
+                    self.cursor = 0;
+                    self.update();
+                }
+            }
+            Attribute::Custom("memory_write") => {
+                if let Some(bytes) = Self::parse_bytes(&value.unwrap_string()) {
+                    self.write(bytes);
+                }
+            }
+            Attribute::Custom("thread_focus_changed") => self.update(),
+            Attribute::Custom("memory_highlight") => {
+                self.highlighted = match value {
+                    AttrValue::Payload(PropPayload::One(PropValue::Usize(addr))) => Some(addr),
+                    _ => None,
+                };
+                self.render();
+            }
+            _ => self.component.attr(attr, value),
+        }
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
+}
+
+impl Memory {
+    /// Parse memory location, an address (ex. `0x7FFFFFFFD4A0`)
+    /// or data query expression (ex. `*some_ptr` or `some_ref`).
+    fn parse_location(s: &str) -> Option<Location> {
+        if let Ok(addr) = parser::hex().parse(s).into_result() {
+            return Some(Location::Address(addr));
+        }
+        expression::parser()
+            .parse(s)
+            .into_result()
+            .ok()
+            .map(Location::Expression)
+    }
+
+    /// Return true if string is a valid memory location.
+    pub fn is_valid_location(s: &str) -> bool {
+        Self::parse_location(s).is_some()
+    }
+
+    /// Parse whitespace separated hex bytes (ex. `ff 0a 1b`).
+    fn parse_bytes(s: &str) -> Option<Vec<u8>> {
+        let bytes = s
+            .split_whitespace()
+            .map(|b| u8::from_str_radix(b.trim_start_matches("0x"), 16).ok())
+            .collect::<Option<Vec<_>>>()?;
+        (!bytes.is_empty()).then_some(bytes)
+    }
+
+    /// Return true if string is a valid sequence of bytes.
+    pub fn is_valid_bytes(s: &str) -> bool {
+        Self::parse_bytes(s).is_some()
+    }
+
+    /// Return address referenced by variable (pointer value or integer value).
+    pub fn referenced_address(var: &VariableIR) -> Option<usize> {
+        match (var, var.value()?) {
+            (_, ValueLayout::Referential { addr }) => Some(addr as usize),
+            (VariableIR::Scalar(scalar), _) => scalar.try_as_number().map(|n| n as usize),
+            _ => None,
+        }
+    }
+
+    pub fn new(exchanger: Arc<ClientExchanger>) -> Self {
+        let component = MultiSpanTextarea::default()
+            .borders(
+                Borders::default()
+                    .modifiers(BorderType::Rounded)
+                    .color(style::tui_color(StyleElement::Panel)),
+            )
+            .inactive(Style::default().fg(Color::Gray))
+            .title("Memory", Alignment::Center)
+            .step(4)
+            .highlighted_str("▶");
+
+        let mut this = Self {
+            component,
+            exchanger,
+            location: None,
+            shift: 0,
+            base: None,
+            data: vec![],
+            cursor: 0,
+            highlighted: None,
+        };
+        this.render();
+        this
+    }
+
+    /// Resolve location address and read memory.
+    fn update(&mut self) {
+        let Some((location, _)) = self.location.clone() else {
+            return;
+        };
+        let shift = self.shift;
+
+        let Ok(result) =
+            self.exchanger
+                .request_sync(move |dbg| -> anyhow::Result<(usize, Vec<u8>)> {
+                    let addr = match location {
+                        Location::Address(addr) => addr,
+                        Location::Expression(expr) => {
+                            let vars = command::variables::Handler::new(dbg).handle(expr)?;
+                            let var = vars.first().ok_or(anyhow!("variable not found"))?;
+                            Self::referenced_address(var)
+                                .ok_or(anyhow!("variable is not a pointer or an integer"))?
+                        }
+                    };
+                    let addr = addr.saturating_add_signed(shift);
+                    Ok((addr, dbg.read_memory(addr, VIEW_LEN)?))
+                })
+        else {
+            return;
+        };
+
+        match result {
+            Ok((addr, data)) => {
+                self.base = Some(addr);
+                self.data = data;
+            }
+            Err(e) => {
+                warn!(target: "tui", "read memory: {e:#}");
+                self.base = None;
+                self.data.clear();
+            }
+        }
+        self.render();
+    }
+
+    /// Write bytes at selected address.
+    fn write(&mut self, bytes: Vec<u8>) {
+        let Some(addr) = self.base.map(|base| base + self.cursor) else {
+            return;
+        };
+
+        let result = self
+            .exchanger
+            .request_sync(move |dbg| -> anyhow::Result<()> {
+                // ptrace writes a whole word, so read it first and patch only the changed bytes
+                const WORD_LEN: usize = mem::size_of::<usize>();
+                for (i, chunk) in bytes.chunks(WORD_LEN).enumerate() {
+                    let addr = addr + i * WORD_LEN;
+                    let mut word = dbg.read_memory(addr, WORD_LEN)?;
+                    word[..chunk.len()].copy_from_slice(chunk);
+                    let word: [u8; WORD_LEN] = word.try_into().expect("infallible");
+                    dbg.write_memory(addr, usize::from_ne_bytes(word))?;
+                }
+                Ok(())
+            });
+        if let Ok(Err(e)) = result {
+            warn!(target: "tui", "write memory: {e:#}");
+        }
+
+        self.update();
+    }
+
+    fn render(&mut self) {
+        let title = match self.location {
+            Some((_, ref src)) => format!("Memory ({src})"),
+            None => "Memory (press `a` to set address or expression)".to_string(),
+        };
+        self.component.attr(
+            Attribute::Title,
+            AttrValue::Title((title, Alignment::Center)),
+        );
+
+        let Some(base) = self.base else {
+            self.component.text_rows(vec![]);
+            return;
+        };
+
+        let rows = self
+            .data
+            .chunks(ROW_LEN)
+            .enumerate()
+            .map(|(row, chunk)| {
+                let row_addr = base + row * ROW_LEN;
+                let mut line = vec![TextSpan::new(format!("{row_addr:#016X}  "))
+                    .fg(style::tui_color(StyleElement::Address))];
+
+                for (i, byte) in chunk.iter().enumerate() {
+                    let mut span = TextSpan::new(format!("{byte:02x}"));
+                    if Some(row_addr + i) == self.highlighted {
+                        span = span.fg(Color::LightRed).bold();
+                    }
+                    if row * ROW_LEN + i == self.cursor {
+                        span = span.reversed();
+                    }
+                    line.push(span);
+                    line.push(TextSpan::new(if i == ROW_LEN / 2 - 1 { "  " } else { " " }));
+                }
+
+                let ascii: String = chunk
+                    .iter()
+                    .map(|&b| {
+                        if b.is_ascii_graphic() || b == b' ' {
+                            b as char
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect();
+                line.push(TextSpan::new(format!(" |{ascii}|")).fg(Color::Gray));
+                line
+            })
+            .collect();
+
+        self.component.text_rows(rows);
+        self.component.states.list_index = self.cursor / ROW_LEN;
+    }
+
+    /// Move cursor, scroll the view if cursor out of view.
+    fn move_cursor(&mut self, delta: isize) {
+        if self.data.is_empty() {
+            return;
+        }
+
+        // delta is never greater than a row, so scroll by a single row is enough
+        let new_cursor = self.cursor as isize + delta;
+        if new_cursor < 0 {
+            self.cursor = (new_cursor + ROW_LEN as isize) as usize;
+            self.scroll(-(ROW_LEN as isize));
+        } else if new_cursor >= self.data.len() as isize {
+            self.cursor = (new_cursor - ROW_LEN as isize) as usize;
+            self.scroll(ROW_LEN as isize);
+        } else {
+            self.cursor = new_cursor as usize;
+            self.render();
+        }
+    }
+
+    fn scroll(&mut self, delta: isize) {
+        self.shift += delta;
+        self.update();
+    }
+
+    pub fn subscriptions() -> Vec<Sub<Id, UserEvent>> {
+        vec![
+            Sub::new(
+                SubEventClause::User(UserEvent::Breakpoint {
+                    pc: Default::default(),
+                    num: 0,
+                    file: None,
+                    line: None,
+                    function: None,
+                }),
+                SubClause::Always,
+            ),
+            Sub::new(
+                SubEventClause::User(UserEvent::Step {
+                    pc: Default::default(),
+                    file: None,
+                    line: None,
+                    function: None,
+                }),
+                SubClause::Always,
+            ),
+            Sub::new(SubEventClause::User(UserEvent::Exit(0)), SubClause::Always),
+        ]
+    }
+}
+
+impl Component<Msg, UserEvent> for Memory {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.move_cursor(ROW_LEN as isize);
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.move_cursor(-(ROW_LEN as isize));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.move_cursor(1);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.move_cursor(-1);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.scroll(VIEW_LEN as isize / 2);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.scroll(-(VIEW_LEN as isize) / 2);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('a'),
+                ..
+            }) => {
+                if self.exchanger.is_messaging_enabled() {
+                    return Some(Msg::MemoryInput(MemoryInputType::Location));
+                }
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter | Key::Char('w'),
+                ..
+            }) => {
+                if self.exchanger.is_messaging_enabled() && self.base.is_some() {
+                    return Some(Msg::MemoryInput(MemoryInputType::Write));
+                }
+            }
+            Event::User(UserEvent::Breakpoint { .. }) | Event::User(UserEvent::Step { .. }) => {
+                self.update();
+            }
+            Event::User(UserEvent::Exit { .. }) => {
+                self.base = None;
+                self.data.clear();
+                self.render();
+            }
+            _ => {}
+        };
+        Some(Msg::None)
+    }
+}
@@ -1,13 +1,200 @@
 use crate::oracle::Oracle;
+use crate::ui::tui::app::port::UserEvent;
+use crate::ui::tui::proto::ClientExchanger;
 use crate::ui::tui::utils::tab::TabWindow;
+use crate::ui::tui::Msg;
 use std::sync::Arc;
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+use tuirealm::tui::layout::{Constraint, Direction, Layout, Rect};
+use tuirealm::{AttrValue, Attribute, Component, Event, Frame, MockComponent, Props, State};
 
-pub fn make_oracle_tab_window(oracles: &[Arc<dyn Oracle>]) -> TabWindow {
-    let ora_names: Vec<_> = oracles.iter().map(|oracle| oracle.name()).collect();
-    let windows: Vec<_> = oracles
-        .iter()
-        .map(|o| o.clone().make_tui_component())
-        .collect();
+/// Max count of oracle panels in a dashboard column.
+const MAX_PANELS_IN_COLUMN: usize = 3;
+
+/// Live view of all enabled oracles at once. Each oracle panel refreshes itself on a tick, so
+/// data collected while debugee is running is shown without waiting for a debugee stop.
+pub struct OracleDashboard {
+    props: Props,
+    exchanger: Arc<ClientExchanger>,
+    names: Vec<&'static str>,
+    panels: Vec<Box<dyn Component<Msg, UserEvent>>>,
+    /// Oracles ready for install on current debugee, `None` until the first debugee stop
+    /// (in this case all oracles are shown).
+    ready: Option<Vec<bool>>,
+    /// Index of a panel that receives keyboard events.
+    focused_idx: usize,
+}
+
+impl OracleDashboard {
+    pub fn new(exchanger: Arc<ClientExchanger>, oracles: &[Arc<dyn Oracle>]) -> Self {
+        Self {
+            props: Props::default(),
+            exchanger,
+            names: oracles.iter().map(|oracle| oracle.name()).collect(),
+            panels: oracles
+                .iter()
+                .map(|oracle| oracle.clone().make_tui_component())
+                .collect(),
+            ready: None,
+            focused_idx: 0,
+        }
+    }
+
+    /// Ask debugger which oracles are installed.
+    fn update_ready(&mut self) {
+        let names = self.names.clone();
+        if let Ok(ready) = self.exchanger.request_sync(move |dbg| {
+            names
+                .iter()
+                .map(|name| dbg.get_oracle(name).is_some())
+                .collect::<Vec<_>>()
+        }) {
+            self.ready = Some(ready);
+            self.focus_panel(self.visible().first().copied().unwrap_or_default());
+        }
+    }
+
+    /// Return indexes of visible panels.
+    fn visible(&self) -> Vec<usize> {
+        (0..self.panels.len())
+            .filter(|&idx| self.ready.as_ref().map(|ready| ready[idx]).unwrap_or(true))
+            .collect()
+    }
+
+    fn is_focused(&self) -> bool {
+        self.props
+            .get_or(Attribute::Focus, AttrValue::Flag(false))
+            .unwrap_flag()
+    }
+
+    fn focus_panel(&mut self, idx: usize) {
+        let focus = self.is_focused();
+        for (i, panel) in self.panels.iter_mut().enumerate() {
+            panel.attr(Attribute::Focus, AttrValue::Flag(focus && i == idx));
+        }
+        self.focused_idx = idx;
+    }
+
+    /// Move keyboard focus to the next (or previous) visible panel.
+    fn switch_panel(&mut self, forward: bool) {
+        let visible = self.visible();
+        if visible.is_empty() {
+            return;
+        }
+        let pos = visible
+            .iter()
+            .position(|&idx| idx == self.focused_idx)
+            .unwrap_or_default();
+        let pos = if forward {
+            (pos + 1) % visible.len()
+        } else {
+            (pos + visible.len() - 1) % visible.len()
+        };
+        self.focus_panel(visible[pos]);
+    }
+
+    /// Split an area between panels, panels are placed in columns,
+    /// no more than [`MAX_PANELS_IN_COLUMN`] in each.
+    fn panel_areas(area: Rect, count: usize) -> Vec<Rect> {
+        if count == 0 {
+            return vec![];
+        }
+        let columns = count.div_ceil(MAX_PANELS_IN_COLUMN);
+        let column_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+            .split(area);
+
+        let mut areas = Vec::with_capacity(count);
+        for (i, column_area) in column_areas.iter().enumerate() {
+            let in_column = (count - i * MAX_PANELS_IN_COLUMN).min(MAX_PANELS_IN_COLUMN);
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Ratio(1, in_column as u32); in_column])
+                .split(*column_area);
+            areas.extend(rows.iter().copied());
+        }
+        areas
+    }
+}
+
+impl MockComponent for OracleDashboard {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let visible = self.visible();
+        let areas = Self::panel_areas(area, visible.len());
+        for (idx, area) in visible.into_iter().zip(areas) {
+            self.panels[idx].view(frame, area);
+        }
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value);
+        if matches!(attr, Attribute::Focus) {
+            self.focus_panel(self.focused_idx);
+        }
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, _cmd: Cmd) -> CmdResult {
+        CmdResult::None
+    }
+}
+
+impl Component<Msg, UserEvent> for OracleDashboard {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Right,
+                modifiers: KeyModifiers::NONE,
+            }) => self.switch_panel(true),
+            Event::Keyboard(KeyEvent {
+                code: Key::Left,
+                modifiers: KeyModifiers::NONE,
+            }) => self.switch_panel(false),
+            Event::Keyboard(_) => {
+                if let Some(panel) = self.panels.get_mut(self.focused_idx) {
+                    return panel.on(ev);
+                }
+            }
+            Event::User(
+                UserEvent::Breakpoint { .. }
+                | UserEvent::Step { .. }
+                | UserEvent::Signal(_)
+                | UserEvent::Exit(_),
+            ) => {
+                // oracles are installed at debugee start, so readiness is known after stop
+                self.update_ready();
+                for panel in self.panels.iter_mut() {
+                    _ = panel.on(ev.clone());
+                }
+            }
+            _ => {
+                for idx in self.visible() {
+                    _ = self.panels[idx].on(ev.clone());
+                }
+            }
+        }
+        Some(Msg::None)
+    }
+}
+
+pub fn make_oracle_tab_window(
+    exchanger: Arc<ClientExchanger>,
+    oracles: &[Arc<dyn Oracle>],
+) -> TabWindow {
+    let mut ora_names = vec!["dashboard"];
+    ora_names.extend(oracles.iter().map(|oracle| oracle.name()));
+    let mut windows: Vec<Box<dyn Component<Msg, UserEvent>>> =
+        vec![Box::new(OracleDashboard::new(exchanger, oracles))];
+    windows.extend(oracles.iter().map(|o| o.clone().make_tui_component()));
 
     TabWindow::new("Choose your oracle", &ora_names, windows, None)
 }
@@ -1,47 +1,80 @@
+use crate::ui::style::{self, StyleElement};
 use crate::ui::tui::app::port::UserEvent;
 use crate::ui::tui::output::OutputLine;
+use crate::ui::tui::utils::mstextarea::MultiSpanTextarea;
 use crate::ui::tui::{Id, Msg};
-use tui_realm_stdlib::Textarea;
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::event::{Key, KeyEvent};
-use tuirealm::props::{Borders, PropPayload, PropValue, Style, TextSpan};
+use tuirealm::props::{Borders, Style, TextSpan};
 use tuirealm::tui::layout::Alignment;
 use tuirealm::tui::prelude::Color;
 use tuirealm::tui::widgets::BorderType;
-use tuirealm::{
-    AttrValue, Attribute, Component, Event, MockComponent, Sub, SubClause, SubEventClause,
-};
+use tuirealm::{Component, Event, MockComponent, Sub, SubClause, SubEventClause};
 
 #[derive(MockComponent)]
 pub struct Output {
-    component: Textarea,
+    component: MultiSpanTextarea,
+    /// Show time when line was written by the program
+    show_time: bool,
+    /// Program stdout and stderr lines
+    lines: Vec<OutputLine>,
 }
 
 impl Output {
-    pub fn new(output: &[OutputLine]) -> Self {
-        let rows: Vec<_> = output
+    fn rows(&self) -> Vec<Vec<TextSpan>> {
+        self.lines
             .iter()
-            .map(|line| match line {
-                OutputLine::Out(text) => TextSpan::new(text),
-                OutputLine::Err(err_text) => TextSpan::new(err_text).fg(Color::LightRed),
+            .map(|line| {
+                let (text, time, fg) = match line {
+                    OutputLine::Out { text, time } => (text, time, Color::Reset),
+                    OutputLine::Err { text, time } => (text, time, Color::LightRed),
+                };
+
+                let mut row = Vec::with_capacity(2);
+                if self.show_time {
+                    row.push(
+                        TextSpan::new(time.format("[%H:%M:%S%.3f] ").to_string()).fg(Color::Gray),
+                    );
+                }
+                row.push(TextSpan::new(text).fg(fg));
+                row
             })
-            .collect();
+            .collect()
+    }
+
+    /// Redraw output, if the last line was selected then new last line will be selected too
+    /// (so output is followed).
+    fn update_view(&mut self) {
+        let states = &self.component.states;
+        let follow = states.list_len == 0 || states.list_index + 1 >= states.list_len;
 
-        Self {
-            component: Textarea::default()
-                .borders(
-                    Borders::default()
-                        .modifiers(BorderType::Rounded)
-                        .color(Color::LightYellow),
-                )
-                .inactive(Style::default().fg(Color::Gray))
-                .title("Program output", Alignment::Center)
-                .highlighted_str("▶")
-                .step(4)
-                .text_rows(&rows),
+        self.component.text_rows(self.rows());
+        if follow {
+            self.component.states.list_index_at_last();
         }
     }
 
+    pub fn new(output: &[OutputLine]) -> Self {
+        let mut component = MultiSpanTextarea::default()
+            .borders(
+                Borders::default()
+                    .modifiers(BorderType::Rounded)
+                    .color(style::tui_color(StyleElement::Panel)),
+            )
+            .inactive(Style::default().fg(Color::Gray))
+            .title("Program output (t - toggle timestamps)", Alignment::Center)
+            .highlighted_str("▶")
+            .step(4);
+
+        let mut this = Self {
+            component,
+            show_time: true,
+            lines: output.to_vec(),
+        };
+        this.update_view();
+        this
+    }
+
     pub fn subscriptions() -> Vec<Sub<Id, UserEvent>> {
         vec![Sub::new(
             SubEventClause::User(UserEvent::GotOutput(vec![], 0)),
@@ -72,23 +105,35 @@ impl Component<Msg, UserEvent> for Output {
             Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
                 self.perform(Cmd::GoTo(Position::End))
             }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('/'),
+                ..
+            }) => return Some(Msg::SearchInput),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n'),
+                ..
+            }) => {
+                self.component.search_next();
+                CmdResult::None
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('N'),
+                ..
+            }) => {
+                self.component.search_prev();
+                CmdResult::None
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('t'),
+                ..
+            }) => {
+                self.show_time = !self.show_time;
+                self.component.text_rows(self.rows());
+                CmdResult::None
+            }
             Event::User(UserEvent::GotOutput(output, _)) => {
-                let rows: Vec<_> = output
-                    .into_iter()
-                    .map(|line| match line {
-                        OutputLine::Out(text) => TextSpan::new(text),
-                        OutputLine::Err(err_text) => TextSpan::new(err_text).fg(Color::LightRed),
-                    })
-                    .collect();
-
-                self.component.attr(
-                    Attribute::Text,
-                    AttrValue::Payload(PropPayload::Vec(
-                        rows.iter().cloned().map(PropValue::TextSpan).collect(),
-                    )),
-                );
-                self.component.states.list_index_at_last();
-
+                self.lines = output;
+                self.update_view();
                 CmdResult::None
             }
             _ => CmdResult::None,
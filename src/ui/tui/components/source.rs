@@ -1,4 +1,11 @@
+use crate::debugger::source;
+use crate::debugger::PlaceDescriptorOwned;
+use crate::ui::command;
+use crate::ui::command::r#break::{
+    BreakpointIdentity, Command as BreakpointCommand, ExecutionResult,
+};
 use crate::ui::short::Abbreviator;
+use crate::ui::style::{self, StyleElement};
 use crate::ui::syntax;
 use crate::ui::syntax::StylizedLine;
 use crate::ui::tui::app::port::UserEvent;
@@ -15,14 +22,15 @@ use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use syntect::util::LinesWithEndings;
-use tuirealm::command::{Cmd, Direction, Position};
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::event::{Key, KeyEvent};
-use tuirealm::props::{Borders, Style, TextSpan};
-use tuirealm::tui::layout::Alignment;
+use tuirealm::props::{Borders, PropPayload, PropValue, Style, TextSpan};
+use tuirealm::tui::layout::{Alignment, Rect};
 use tuirealm::tui::prelude::Color;
 use tuirealm::tui::widgets::BorderType;
 use tuirealm::{
-    AttrValue, Attribute, Component, Event, MockComponent, Sub, SubClause, SubEventClause,
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State, Sub, SubClause,
+    SubEventClause,
 };
 
 #[derive(Default)]
@@ -36,7 +44,7 @@ impl FileLinesCache {
         let lines = match self.files.entry(file.to_path_buf()) {
             Entry::Occupied(o) => o.into_mut(),
             Entry::Vacant(v) => {
-                let mut file = match fs::File::open(file) {
+                let mut file = match fs::File::open(source::resolve(file)) {
                     Ok(f) => f,
                     Err(e) => {
                         warn!("error while open {file:?}: {e}");
@@ -75,10 +83,54 @@ impl FileLinesCache {
     }
 }
 
-#[derive(MockComponent)]
 pub struct Source {
     component: MultiSpanTextarea,
     file_cache: FileLinesCache,
+    exchanger: Arc<ClientExchanger>,
+    /// Place where program is stopped.
+    stop_place: Option<(PathBuf, u64)>,
+    /// Currently shown file.
+    file: Option<PathBuf>,
+}
+
+impl MockComponent for Source {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.component.view(frame, area)
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        if matches!(attr, Attribute::Custom("show_source")) {
+            if let AttrValue::Payload(PropPayload::Tup2((
+                PropValue::Str(file),
+                PropValue::U64(line),
+            ))) = value
+            {
+                weak_error!(self.show_line(Path::new(&file), line));
+            }
+            return;
+        }
+        if matches!(attr, Attribute::Custom("thread_focus_changed")) {
+            return self.show_place_in_focus();
+        }
+        if matches!(attr, Attribute::Custom("update_breakpoints")) {
+            weak_error!(self.refresh());
+            return;
+        }
+
+        self.component.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
 }
 
 impl Source {
@@ -95,23 +147,31 @@ impl Source {
         }
     }
 
-    pub fn new(exchanger: Arc<ClientExchanger>) -> anyhow::Result<Self> {
-        let mb_threads = exchanger
-            .request_sync(|dbg| dbg.thread_state())
-            .expect("messaging enabled")
-            .ok();
-        let mb_place_in_focus = mb_threads.and_then(|threads| {
+    /// Return place where thread in focus is stopped.
+    fn place_in_focus(exchanger: &ClientExchanger) -> Option<PlaceDescriptorOwned> {
+        let mb_threads = exchanger.request_sync(|dbg| dbg.thread_state()).ok()?.ok();
+        mb_threads.and_then(|threads| {
             threads
                 .into_iter()
                 .find_map(|snap| if snap.in_focus { snap.place } else { None })
-        });
+        })
+    }
+
+    /// Show place where thread in focus is stopped.
+    fn show_place_in_focus(&mut self) {
+        if let Some(place) = Self::place_in_focus(&self.exchanger) {
+            self.stop_place = Some((place.file.clone(), place.line_number));
+            weak_error!(self.update_source_view(place.file.as_path(), Some(place.line_number)));
+        }
+    }
 
+    pub fn new(exchanger: Arc<ClientExchanger>) -> anyhow::Result<Self> {
         let cache = FileLinesCache::default();
         let component = MultiSpanTextarea::default()
             .borders(
                 Borders::default()
                     .modifiers(BorderType::Rounded)
-                    .color(Color::LightYellow),
+                    .color(style::tui_color(StyleElement::Panel)),
             )
             .title("Program source code", Alignment::Center)
             .step(4)
@@ -121,21 +181,45 @@ impl Source {
         let mut this = Self {
             file_cache: cache,
             component,
+            exchanger,
+            stop_place: None,
+            file: None,
         };
-
-        if let Some(place) = mb_place_in_focus {
-            weak_error!(this.update_source_view(place.file.as_path(), Some(place.line_number)));
-        }
+        this.show_place_in_focus();
 
         Ok(this)
     }
 
+    /// Return breakpoints placed in a source file, line number -> true if breakpoint is enabled.
+    fn breakpoints_in_file(&self, file: &Path) -> HashMap<u64, bool> {
+        let file = file.to_path_buf();
+        self.exchanger
+            .request_sync(move |dbg| {
+                let mut cmd = command::r#break::Handler::new(dbg);
+                let Ok(ExecutionResult::Dump(breakpoints)) = cmd.handle(&BreakpointCommand::Info)
+                else {
+                    return HashMap::new();
+                };
+
+                let mut lines: HashMap<u64, bool> = HashMap::new();
+                for brkpt in breakpoints {
+                    if let Some(place) = brkpt.place.filter(|place| place.file == file) {
+                        // a line is enabled if any breakpoint on it is enabled
+                        *lines.entry(place.line_number).or_default() |= !brkpt.options.disabled;
+                    }
+                }
+                lines
+            })
+            .unwrap_or_default()
+    }
+
     fn update_source_view(&mut self, file: &Path, mb_line_num: Option<u64>) -> anyhow::Result<()> {
         self.component.attr(
             Attribute::Title,
             AttrValue::Title((Self::get_title(Some(file)), Alignment::Center)),
         );
 
+        let breakpoints = self.breakpoints_in_file(file);
         let lines = self
             .file_cache
             .lines(file)?
@@ -143,13 +227,21 @@ impl Source {
             .cloned()
             .enumerate()
             .map(|(i, mut line)| {
-                if Some((i + 1) as u64) == mb_line_num {
+                let line_num = (i + 1) as u64;
+                if Some(line_num) == mb_line_num {
                     line.iter_mut().for_each(|text| text.fg = Color::LightRed)
                 }
+                let gutter = match breakpoints.get(&line_num) {
+                    Some(true) => TextSpan::new("●").fg(style::tui_color(StyleElement::Breakpoint)),
+                    Some(false) => TextSpan::new("○").fg(Color::Gray),
+                    None => TextSpan::new(" "),
+                };
+                line.insert(0, gutter);
                 line
             })
             .collect();
         self.component.text_rows(lines);
+        self.file = Some(file.to_path_buf());
 
         if let Some(line) = mb_line_num {
             self.component.states.list_index = (line as usize).saturating_sub(1);
@@ -157,6 +249,67 @@ impl Source {
         Ok(())
     }
 
+    /// Show a line of a source file, a line where program is stopped keeps highlighted.
+    fn show_line(&mut self, file: &Path, line: u64) -> anyhow::Result<()> {
+        let stop_line = self
+            .stop_place
+            .as_ref()
+            .and_then(|(stop_file, stop_line)| (stop_file == file).then_some(*stop_line));
+        self.update_source_view(file, stop_line)?;
+        self.component.states.list_index = (line as usize).saturating_sub(1);
+        Ok(())
+    }
+
+    /// Render shown file again (with actual breakpoints), selected line is kept.
+    fn refresh(&mut self) -> anyhow::Result<()> {
+        let Some(file) = self.file.clone() else {
+            return Ok(());
+        };
+        let selected = self.component.states.list_index;
+        self.show_line(&file, (selected + 1) as u64)
+    }
+
+    /// Remove breakpoints at selected line if there are any, set a new breakpoint elsewhere.
+    fn toggle_breakpoint(&mut self) -> anyhow::Result<()> {
+        let Some(file) = self.file.clone() else {
+            return Ok(());
+        };
+        let line = (self.component.states.list_index + 1) as u64;
+
+        self.exchanger
+            .request_sync(move |dbg| -> anyhow::Result<()> {
+                let mut cmd = command::r#break::Handler::new(dbg);
+                let ExecutionResult::Dump(breakpoints) = cmd.handle(&BreakpointCommand::Info)?
+                else {
+                    unreachable!()
+                };
+                let numbers: Vec<_> = breakpoints
+                    .into_iter()
+                    .filter(|brkpt| {
+                        brkpt
+                            .place
+                            .as_ref()
+                            .is_some_and(|place| place.file == file && place.line_number == line)
+                    })
+                    .map(|brkpt| brkpt.number)
+                    .collect();
+
+                if numbers.is_empty() {
+                    let identity =
+                        BreakpointIdentity::Line(file.to_string_lossy().to_string(), line);
+                    cmd.handle(&BreakpointCommand::Add(identity))?;
+                } else {
+                    for number in numbers {
+                        cmd.handle(&BreakpointCommand::Remove(BreakpointIdentity::Number(
+                            number,
+                        )))?;
+                    }
+                }
+                Ok(())
+            })??;
+        Ok(())
+    }
+
     pub fn subscriptions() -> Vec<Sub<Id, UserEvent>> {
         vec![
             Sub::new(
@@ -213,13 +366,44 @@ impl Component<Msg, UserEvent> for Source {
             Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
                 self.perform(Cmd::GoTo(Position::End));
             }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('/'),
+                ..
+            }) => {
+                return Some(Msg::SearchInput);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n'),
+                ..
+            }) => {
+                self.component.search_next();
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('N'),
+                ..
+            }) => {
+                self.component.search_prev();
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('b'),
+                ..
+            }) => {
+                if let Err(e) = self.toggle_breakpoint() {
+                    return Some(Msg::ShowOkPopup(Some("Error".to_string()), e.to_string()));
+                }
+                return Some(Msg::BreakpointsChanged);
+            }
             Event::User(UserEvent::Breakpoint { file, line, .. })
             | Event::User(UserEvent::Step { file, line, .. }) => {
                 if let Some(file) = file {
-                    weak_error!(self.update_source_view(PathBuf::from(file).as_path(), line));
+                    let file = PathBuf::from(file);
+                    self.stop_place = line.map(|line| (file.clone(), line));
+                    weak_error!(self.update_source_view(file.as_path(), line));
                 }
             }
             Event::User(UserEvent::Exit { .. }) => {
+                self.stop_place = None;
+                self.file = None;
                 self.component.text_rows(vec![]);
             }
             _ => {}
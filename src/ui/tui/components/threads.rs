@@ -1,29 +1,71 @@
+use crate::debugger::{StopType, ThreadSelector, ThreadSnapshot, TraceeStatus};
 use crate::ui::command;
 use crate::ui::command::thread::ExecutionResult as ThreadResult;
+use crate::ui::style::{self, StyleElement};
 use crate::ui::tui::app::port::UserEvent;
 use crate::ui::tui::proto::ClientExchanger;
 use crate::ui::tui::{Id, Msg};
 use nix::sys::signal::Signal;
 use std::sync::Arc;
-use tui_realm_treeview::{Node, Tree, TreeView, TREE_CMD_CLOSE, TREE_CMD_OPEN, TREE_INITIAL_NODE};
-use tuirealm::command::{Cmd, Direction, Position};
+use tui_realm_stdlib::List;
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::event::{Key, KeyEvent};
-use tuirealm::props::{BorderType, Borders, Style};
-use tuirealm::tui::layout::Alignment;
+use tuirealm::props::{BorderType, Borders, PropPayload, PropValue, Style, TableBuilder, TextSpan};
+use tuirealm::tui::layout::{Alignment, Constraint, Layout, Rect};
 use tuirealm::tui::style::Color;
 use tuirealm::{
-    AttrValue, Attribute, Component, Event, MockComponent, Sub, SubClause, SubEventClause,
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State, Sub, SubClause,
+    SubEventClause,
 };
 
-#[derive(MockComponent)]
+/// Thread list and a backtrace of selected thread.
 pub struct Threads {
-    component: TreeView,
+    threads: List,
+    backtrace: List,
     exchanger: Arc<ClientExchanger>,
+    /// Threads state snapshots, updated at each debugee stop.
+    snapshots: Vec<ThreadSnapshot>,
+}
+
+impl MockComponent for Threads {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(area);
+        self.threads.view(frame, chunks[0]);
+        self.backtrace.view(frame, chunks[1]);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.threads.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        if matches!(attr, Attribute::Focus) {
+            self.backtrace.attr(attr, value.clone());
+        }
+        self.threads.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        self.threads.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        let result = self.threads.perform(cmd);
+        self.update_backtrace();
+        result
+    }
 }
 
 impl Threads {
+    fn selected(&self) -> Option<&ThreadSnapshot> {
+        let idx = self.threads.state().unwrap_one().unwrap_usize();
+        self.snapshots.get(idx)
+    }
+
     fn update_threads(&mut self) {
-        let Ok(threads) = self.exchanger.request_sync(|dbg| {
+        let Ok(mut snapshots) = self.exchanger.request_sync(|dbg| {
             let thread_result = command::thread::Handler::new(dbg)
                 .handle(command::thread::Command::Info)
                 .unwrap_or(ThreadResult::List(vec![]));
@@ -36,57 +78,129 @@ impl Threads {
         }) else {
             return;
         };
+        snapshots.sort_by_key(|snap| snap.thread.number);
 
-        let mut root = Node::new("root".to_string(), "threads".to_string());
-        for (i, thread_snap) in threads.iter().enumerate() {
-            let pid = thread_snap.thread.pid;
-            let func_name = thread_snap
+        let mut table_builder = TableBuilder::default();
+        for snap in snapshots.iter() {
+            let func_name = snap
                 .bt
                 .as_ref()
-                .and_then(|bt| bt[0].func_name.clone())
-                .unwrap_or("unknown".to_string());
-            let line = thread_snap
+                .and_then(|bt| bt.first())
+                .and_then(|frame| frame.func_name.as_deref())
+                .unwrap_or("unknown");
+            let line = snap
                 .place
                 .as_ref()
                 .map(|l| l.line_number.to_string())
                 .unwrap_or("???".to_string());
-
-            let value = if thread_snap.in_focus {
-                format!(" (CURRENT) [{pid}] {func_name}(:{line})")
-            } else {
-                format!(" [{pid}] {func_name}(:{line})")
+            let state = match snap.thread.status {
+                TraceeStatus::Running => "running".to_string(),
+                TraceeStatus::Stopped(StopType::SignalStop(sig)) => {
+                    format!("stopped ({sig})")
+                }
+                TraceeStatus::Stopped(_) => "stopped".to_string(),
             };
 
-            let mut thread_node = Node::new(format!("thread_{i}"), value);
+            let number = TextSpan::from(format!("#{} ", snap.thread.number));
+            table_builder.add_col(if snap.in_focus {
+                number.fg(style::tui_color(StyleElement::Panel)).bold()
+            } else {
+                number
+            });
+            table_builder.add_col(TextSpan::from(format!(
+                "[{}] {} ",
+                snap.thread.pid,
+                snap.name.as_deref().unwrap_or("???")
+            )));
+            table_builder.add_col(TextSpan::from(format!("{state} ")).fg(Color::Gray));
+            table_builder.add_col(
+                TextSpan::from(format!("{func_name}(:{line})"))
+                    .fg(style::tui_color(StyleElement::FunctionName)),
+            );
+            table_builder.add_row();
+        }
+        let mut table = table_builder.build();
+        // remove last unused row
+        table.pop();
 
-            if let Some(ref bt) = thread_snap.bt {
-                for (frame_num, frame) in bt.iter().enumerate() {
-                    let fn_ip_or_zero = frame.fn_start_ip.unwrap_or_default();
+        let in_focus_idx = snapshots
+            .iter()
+            .position(|snap| snap.in_focus)
+            .unwrap_or_default();
+        self.snapshots = snapshots;
+        self.threads
+            .attr(Attribute::Content, AttrValue::Table(table));
+        self.threads.attr(
+            Attribute::Value,
+            AttrValue::Payload(PropPayload::One(PropValue::Usize(in_focus_idx))),
+        );
+        self.update_backtrace();
+    }
 
-                    let frame_info = format!(
-                        "#{frame_num} {} ({} + {:#X})",
-                        frame.func_name.as_deref().unwrap_or("???"),
+    /// Show backtrace of selected thread, backtrace is taken from the snapshot, so
+    /// there is no need to unwind a thread stack again.
+    fn update_backtrace(&mut self) {
+        let mut table_builder = TableBuilder::default();
+        if let Some(snap) = self.selected() {
+            let bt = snap.bt.as_deref().unwrap_or_default();
+            for (frame_num, frame) in bt.iter().enumerate() {
+                let fn_ip_or_zero = frame.fn_start_ip.unwrap_or_default();
+                table_builder.add_col(TextSpan::from(format!("#{frame_num} ")));
+                table_builder.add_col(
+                    TextSpan::from(frame.func_name.as_deref().unwrap_or("???"))
+                        .fg(style::tui_color(StyleElement::FunctionName)),
+                );
+                table_builder.add_col(
+                    TextSpan::from(format!(
+                        " ({} + {:#X})",
                         frame
                             .fn_start_ip
                             .map(|addr| addr.to_string())
                             .unwrap_or("???".to_string()),
                         frame.ip.as_u64().saturating_sub(fn_ip_or_zero.as_u64()),
+                    ))
+                    .fg(style::tui_color(StyleElement::Address)),
+                );
+                if let Some(place) = frame.place.as_ref() {
+                    let file = place
+                        .file
+                        .file_name()
+                        .map(|name| name.to_string_lossy())
+                        .unwrap_or_default();
+                    table_builder.add_col(
+                        TextSpan::from(format!(" at {file}:{}", place.line_number)).fg(Color::Gray),
                     );
-                    thread_node.add_child(Node::new(
-                        format!("thread_{i}_frame_{frame_num}"),
-                        frame_info,
-                    ));
                 }
+                table_builder.add_row();
+            }
+            // snapshot contains only top frames of a deep stack
+            if snap.bt_truncated {
+                table_builder.add_col(TextSpan::from("...").fg(Color::Gray));
+                table_builder.add_row();
             }
+        }
+        let mut table = table_builder.build();
+        // remove last unused row
+        table.pop();
+
+        self.backtrace
+            .attr(Attribute::Content, AttrValue::Table(table));
+    }
 
-            root.add_child(thread_node);
+    /// Bring selected thread into focus.
+    fn switch_to_selected(&mut self) -> Option<Msg> {
+        let number = self.selected()?.thread.number;
+        let cmd = command::thread::Command::Switch(ThreadSelector::Number(number));
+        let result = self
+            .exchanger
+            .request_sync(move |dbg| command::thread::Handler::new(dbg).handle(cmd).map(|_| ()))
+            .ok()?;
+        if let Err(e) = result {
+            return Some(Msg::ShowOkPopup(Some("Error".to_string()), e.to_string()));
         }
 
-        self.component.set_tree(Tree::new(root));
-        self.component.attr(
-            Attribute::Custom(TREE_INITIAL_NODE),
-            AttrValue::String("thread_0".to_string()),
-        );
+        self.update_threads();
+        Some(Msg::ThreadFocusChanged)
     }
 
     pub fn subscriptions() -> Vec<Sub<Id, UserEvent>> {
@@ -121,23 +235,32 @@ impl Threads {
     }
 
     pub fn new(exchanger: Arc<ClientExchanger>) -> Self {
-        let tree_view = TreeView::default()
+        let threads = List::default()
             .borders(
                 Borders::default()
-                    .color(Color::LightYellow)
+                    .color(style::tui_color(StyleElement::Panel))
                     .modifiers(BorderType::Rounded),
             )
             .inactive(Style::default().fg(Color::Gray))
-            .indent_size(3)
-            .scroll_step(6)
-            .preserve_state(true)
-            .title("Threads", Alignment::Center)
-            .highlighted_color(Color::LightYellow)
-            .highlight_symbol("▶");
+            .scroll(true)
+            .step(4)
+            .title("Threads (Enter - switch focus)", Alignment::Center)
+            .highlighted_color(style::tui_color(StyleElement::Panel))
+            .highlighted_str("▶");
+        let backtrace = List::default()
+            .borders(
+                Borders::default()
+                    .color(style::tui_color(StyleElement::Panel))
+                    .modifiers(BorderType::Rounded),
+            )
+            .inactive(Style::default().fg(Color::Gray))
+            .title("Backtrace", Alignment::Center);
 
         let mut this = Self {
-            component: tree_view,
+            threads,
+            backtrace,
             exchanger,
+            snapshots: vec![],
         };
         this.update_threads();
         this
@@ -147,16 +270,6 @@ impl Threads {
 impl Component<Msg, UserEvent> for Threads {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         match ev {
-            Event::Keyboard(KeyEvent {
-                code: Key::Left, ..
-            }) => {
-                self.perform(Cmd::Custom(TREE_CMD_CLOSE));
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Right, ..
-            }) => {
-                self.perform(Cmd::Custom(TREE_CMD_OPEN));
-            }
             Event::Keyboard(KeyEvent {
                 code: Key::PageDown,
                 ..
@@ -187,7 +300,9 @@ impl Component<Msg, UserEvent> for Threads {
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
             }) => {
-                self.perform(Cmd::Submit);
+                if let Some(msg) = self.switch_to_selected() {
+                    return Some(msg);
+                }
             }
             Event::User(UserEvent::Breakpoint { .. })
             | Event::User(UserEvent::Exit(_))
@@ -1,120 +1,264 @@
-use crate::debugger::variable::render::{RenderRepr, ValueLayout};
+use crate::debugger::variable::render::{ref_counts, RenderRepr, ValueLayout};
 use crate::debugger::variable::select::{Literal, VariableSelector, DQE};
-use crate::debugger::variable::{select, VariableIR};
+use crate::debugger::variable::{select, ParseLimits, VariableIR};
 use crate::ui::command;
+use crate::ui::style::{self, StyleElement};
 use crate::ui::tui::app::port::UserEvent;
 use crate::ui::tui::proto::ClientExchanger;
+use crate::ui::tui::utils::clipboard;
 use crate::ui::tui::{Id, Msg};
 use nix::sys::signal::Signal;
+use std::collections::HashMap;
+use std::mem;
 use std::sync::Arc;
 use tui_realm_treeview::{Node, Tree, TreeView, TREE_CMD_CLOSE, TREE_CMD_OPEN, TREE_INITIAL_NODE};
-use tuirealm::command::{Cmd, Direction, Position};
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::event::{Key, KeyEvent};
 use tuirealm::props::{BorderType, Borders};
-use tuirealm::tui::layout::Alignment;
+use tuirealm::tui::layout::{Alignment, Rect};
 use tuirealm::tui::style::{Color, Style};
 use tuirealm::{
-    AttrValue, Attribute, Component, Event, MockComponent, Sub, SubClause, SubEventClause,
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State, Sub, SubClause,
+    SubEventClause,
 };
 
-const MAX_RECURSION: u32 = 15;
+const LAZY_PLACEHOLDER: &str = "lazy";
+
+/// Prefix of values that changed since the previous stop.
+const CHANGED_MARKER: &str = "* ";
+
+/// Limits of variable parsing, deeper values are requested from debugger when node is opened.
+const PARSE_LIMITS: ParseLimits = ParseLimits {
+    max_depth: Some(3),
+    max_children: Some(256),
+};
+
+/// Children of a tree node that are not built yet.
+/// Children are built when user open the node first time.
+enum LazyChildren {
+    /// Members of structure, list, map or wrapped variable.
+    Members {
+        var: VariableIR,
+        select_path: Option<DQE>,
+    },
+    /// Result of a pointer dereference (requested from debugger on demand).
+    Deref(DQE),
+    /// Members of a variable that is out of parsing limits (requested from debugger on demand).
+    Unexpanded(DQE),
+}
 
-#[derive(MockComponent)]
 pub struct Variables {
     component: TreeView,
     exchanger: Arc<ClientExchanger>,
+    /// Not expanded nodes, node id -> (variable key, children).
+    lazy: HashMap<String, (String, LazyChildren)>,
+    /// Addresses referenced by pointer nodes, node id -> address.
+    refs: HashMap<String, usize>,
+    /// Address referenced by selected node.
+    selected_ref: Option<usize>,
+    /// Rendered values of nodes, node id -> value, used for copying.
+    node_values: HashMap<String, String>,
+    /// Values at the current stop, variable key (names from the root to variable) -> value.
+    values: HashMap<String, String>,
+    /// Values at the previous stop, variable key -> value.
+    prev_values: HashMap<String, String>,
+}
+
+impl MockComponent for Variables {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.component.view(frame, area)
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        if matches!(attr, Attribute::Custom("thread_focus_changed")) {
+            // values of another thread are not compared
+            self.values.clear();
+            return self.update();
+        }
+
+        self.component.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
 }
 
 impl Variables {
+    /// Create tree node for variable. Variable members (or pointer dereference result)
+    /// are not added into the tree, a placeholder is used instead until node is opened.
     fn node_from_var(
-        &self,
-        recursion: u32,
+        &mut self,
         node_name: &str,
+        parent_key: &str,
         var: &VariableIR,
         select_path: Option<DQE>,
     ) -> Node {
         let name = var.name();
         let typ = var.r#type();
+        let key = format!("{parent_key}.{name}");
 
-        // recursion guard
-        if recursion >= MAX_RECURSION {
-            return Node::new(node_name.to_string(), format!("{name} {typ}(...)"));
+        let (label, children) = match var.value() {
+            _ if matches!(var, VariableIR::Lazy(_)) => match select_path {
+                None => (format!("{name} {typ}(...)"), None),
+                Some(path) => (
+                    format!("{name} {typ}"),
+                    Some(LazyChildren::Unexpanded(path)),
+                ),
+            },
+            None => (format!("{name} {typ}(unknown)"), None),
+            Some(ValueLayout::PreRendered(view)) => {
+                let label =
+                    self.value_label(node_name, &key, format!("{name} {typ}"), view.to_string());
+                (label, None)
+            }
+            Some(ValueLayout::Referential { addr, .. }) => {
+                self.refs.insert(node_name.to_string(), addr as usize);
+                let value = match ref_counts(var) {
+                    Some(counts) => format!("{addr:p}, {counts}"),
+                    None => format!("{addr:p}"),
+                };
+                let label = self.value_label(node_name, &key, format!("{name} {typ}"), value);
+                (
+                    label,
+                    select_path.map(|path| LazyChildren::Deref(DQE::Deref(Box::new(path)))),
+                )
+            }
+            Some(_) => (
+                format!("{name} {typ}"),
+                Some(LazyChildren::Members {
+                    var: var.clone(),
+                    select_path,
+                }),
+            ),
+        };
+
+        let mut node = Node::new(node_name.to_string(), label);
+        if let Some(children) = children {
+            node.add_child(Node::new(
+                format!("{node_name}_{LAZY_PLACEHOLDER}"),
+                "...".to_string(),
+            ));
+            self.lazy.insert(node_name.to_string(), (key, children));
         }
+        node
+    }
+
+    /// Return a label of node with a value, label is marked if the value changed
+    /// since the previous stop.
+    fn value_label(&mut self, node_name: &str, key: &str, prefix: String, value: String) -> String {
+        let changed = self
+            .prev_values
+            .get(key)
+            .is_some_and(|prev_value| *prev_value != value);
+        let marker = if changed { CHANGED_MARKER } else { "" };
+        let label = format!("{marker}{prefix}({value})");
 
-        match var.value() {
-            None => Node::new(node_name.to_string(), format!("{name} {typ}(unknown)")),
-            Some(layout) => match layout {
-                ValueLayout::PreRendered(view) => {
-                    Node::new(node_name.to_string(), format!("{name} {typ}({view})"))
+        self.node_values
+            .insert(node_name.to_string(), value.clone());
+        self.values.insert(key.to_string(), value);
+        label
+    }
+
+    /// Build children of lazy node.
+    fn build_children(
+        &mut self,
+        node_name: &str,
+        var_key: &str,
+        children: LazyChildren,
+    ) -> Vec<Node> {
+        match children {
+            LazyChildren::Deref(deref_expr) => {
+                let variables = {
+                    let deref_expr = deref_expr.clone();
+                    self.exchanger
+                        .request_sync(|dbg| {
+                            let handler = command::variables::Handler::new(dbg);
+                            handler.handle_with_limits(deref_expr, PARSE_LIMITS)
+                        })
+                        .expect("messaging enabled")
+                };
+
+                match variables.ok().as_ref().and_then(|vars| vars.first()) {
+                    Some(var) => vec![self.node_from_var(
+                        format!("{node_name}_deref").as_str(),
+                        var_key,
+                        var,
+                        Some(deref_expr),
+                    )],
+                    None => vec![],
                 }
-                ValueLayout::Referential { addr, .. } => {
-                    let view = format!("{addr:p}");
-                    let mut node =
-                        Node::new(node_name.to_string(), format!("{name} {typ}({view})"));
-
-                    if let Some(path) = select_path {
-                        let deref_expr = DQE::Deref(Box::new(path));
-
-                        let variables = {
-                            let deref_expr = deref_expr.clone();
-                            self.exchanger
-                                .request_sync(|dbg| {
-                                    let handler = command::variables::Handler::new(dbg);
-                                    handler.handle(deref_expr)
-                                })
-                                .expect("messaging enabled")
-                        };
+            }
+            LazyChildren::Unexpanded(expr) => {
+                let variables = {
+                    let expr = expr.clone();
+                    self.exchanger
+                        .request_sync(|dbg| {
+                            let handler = command::variables::Handler::new(dbg);
+                            handler.handle_with_limits(expr, PARSE_LIMITS)
+                        })
+                        .expect("messaging enabled")
+                };
 
-                        if let Ok(variables) = variables {
-                            if let Some(var) = variables.first() {
-                                let deref_node = self.node_from_var(
-                                    recursion + 1,
-                                    format!("{node_name}_deref").as_str(),
-                                    var,
-                                    Some(deref_expr),
-                                );
-                                node.add_child(deref_node);
-                            }
-                        }
-                    }
-
-                    node
+                match variables.ok().and_then(|vars| vars.into_iter().next()) {
+                    Some(var) => self.build_children(
+                        node_name,
+                        var_key,
+                        LazyChildren::Members {
+                            var,
+                            select_path: Some(expr),
+                        },
+                    ),
+                    None => vec![],
+                }
+            }
+            LazyChildren::Members { var, select_path } => match var.value() {
+                None
+                | Some(ValueLayout::PreRendered(_))
+                | Some(ValueLayout::Referential { .. }) => {
+                    vec![]
                 }
-                ValueLayout::Wrapped(other) => {
-                    let mut node = Node::new(node_name.to_string(), format!("{name} {typ}"));
-                    node.add_child(self.node_from_var(
-                        recursion + 1,
+                Some(ValueLayout::Wrapped(other)) => {
+                    vec![self.node_from_var(
                         format!("{node_name}_1").as_str(),
+                        var_key,
                         other,
                         select_path,
-                    ));
-                    node
+                    )]
                 }
-                ValueLayout::Structure { members, .. } => {
-                    let mut node = Node::new(node_name.to_string(), format!("{name} {typ}"));
-                    for (i, member) in members.iter().enumerate() {
-                        node.add_child(
-                            self.node_from_var(
-                                recursion + 1,
-                                format!("{node_name}_{i}").as_str(),
-                                member,
-                                select_path
-                                    .clone()
-                                    .map(|expr| DQE::Field(Box::new(expr), member.name())),
-                            ),
-                        );
-                    }
-                    node
-                }
-                ValueLayout::Map(kvs) => {
-                    let mut node = Node::new(node_name.to_string(), format!("{name} {typ}"));
-                    for (i, (key, val)) in kvs.iter().enumerate() {
+                Some(ValueLayout::Structure { members, .. }) => members
+                    .iter()
+                    .enumerate()
+                    .map(|(i, member)| {
+                        self.node_from_var(
+                            format!("{node_name}_{i}").as_str(),
+                            var_key,
+                            member,
+                            select_path
+                                .clone()
+                                .map(|expr| DQE::Field(Box::new(expr), member.name())),
+                        )
+                    })
+                    .collect(),
+                Some(ValueLayout::Map(kvs)) => kvs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (key, val))| {
                         let mut kv_pair =
                             Node::new(format!("{node_name}_kv_{i}"), format!("kv {i}"));
+                        let kv_key = format!("{var_key}[{i}]");
 
                         kv_pair.add_child(self.node_from_var(
-                            recursion + 1,
                             format!("{node_name}_kv_{i}_key").as_str(),
+                            &kv_key,
                             key,
                             // currently no way to use expressions with keys
                             None,
@@ -122,8 +266,8 @@ impl Variables {
 
                         kv_pair.add_child(
                             self.node_from_var(
-                                recursion + 1,
                                 format!("{node_name}_kv_{i}_val").as_str(),
+                                &kv_key,
                                 val,
                                 // todo works only if key is a String or &str, need better support of field expr on maps
                                 select_path
@@ -131,13 +275,13 @@ impl Variables {
                                     .map(|expr| DQE::Field(Box::new(expr), key.name())),
                             ),
                         );
-                        node.add_child(kv_pair);
-                    }
-                    node
-                }
-                ValueLayout::List { members, indexed } => {
-                    let mut node = Node::new(node_name.to_string(), format!("{name} {typ}"));
-                    for (i, member) in members.iter().enumerate() {
+                        kv_pair
+                    })
+                    .collect(),
+                Some(ValueLayout::List { members, indexed }) => members
+                    .iter()
+                    .enumerate()
+                    .map(|(i, member)| {
                         let el_path = if indexed {
                             select_path.clone().and_then(|expr| {
                                 let mb_idx: Option<u64> = member.name().parse().ok();
@@ -148,24 +292,112 @@ impl Variables {
                             None
                         };
 
-                        node.add_child(self.node_from_var(
-                            recursion + 1,
+                        self.node_from_var(
                             format!("{node_name}_{i}").as_str(),
+                            var_key,
                             member,
                             el_path,
-                        ));
-                    }
-                    node
-                }
+                        )
+                    })
+                    .collect(),
             },
         }
     }
 
+    /// Replace a placeholder of lazy node with real children.
+    fn expand(&mut self, node_name: &str) {
+        let Some((key, children)) = self.lazy.remove(node_name) else {
+            return;
+        };
+        let children = self.build_children(node_name, &key, children);
+
+        if let Some(node) = self
+            .component
+            .tree_mut()
+            .root_mut()
+            .query_mut(&node_name.to_string())
+        {
+            node.remove_child(&format!("{node_name}_{LAZY_PLACEHOLDER}"));
+            children.into_iter().for_each(|child| node.add_child(child));
+        }
+    }
+
+    /// Expand selected node if it is lazy.
+    fn expand_selected(&mut self) {
+        if let Some(selected) = self
+            .component
+            .tree_state()
+            .selected()
+            .map(ToString::to_string)
+        {
+            self.expand(&selected);
+        }
+    }
+
+    /// Expand all lazy nodes that are opened (this may happen when the tree is rebuilt
+    /// and state of opened nodes is preserved).
+    fn expand_opened(&mut self) {
+        loop {
+            let tree = self.component.tree();
+            let opened: Vec<String> = self
+                .lazy
+                .keys()
+                .filter(|&name| {
+                    tree.root()
+                        .query(name)
+                        .map(|node| self.component.tree_state().is_open(node))
+                        .unwrap_or_default()
+                })
+                .cloned()
+                .collect();
+            if opened.is_empty() {
+                break;
+            }
+            opened.iter().for_each(|name| self.expand(name));
+        }
+    }
+
+    /// Copy a value of selected node (or a node text if there is no value) into the clipboard.
+    fn copy_selected(&self) -> std::io::Result<()> {
+        let Some(selected) = self.component.tree_state().selected() else {
+            return Ok(());
+        };
+        let text = match self.node_values.get(selected) {
+            Some(value) => value,
+            None => match self.component.tree().root().query(&selected.to_string()) {
+                Some(node) => node.value(),
+                None => return Ok(()),
+            },
+        };
+        clipboard::copy(text)
+    }
+
+    /// Return a message for highlight the address referenced by selected node
+    /// if this address changed.
+    fn selected_ref_msg(&mut self) -> Option<Msg> {
+        let selected_ref = self
+            .component
+            .tree_state()
+            .selected()
+            .and_then(|node| self.refs.get(node))
+            .copied();
+        if selected_ref == self.selected_ref {
+            return None;
+        }
+        self.selected_ref = selected_ref;
+        Some(Msg::MemoryHighlight(selected_ref))
+    }
+
     fn update(&mut self) {
+        self.lazy.clear();
+        self.refs.clear();
+        self.node_values.clear();
+        self.prev_values = mem::take(&mut self.values);
+
         let Ok(variables) = self.exchanger.request_sync(|dbg| {
             let expr = select::DQE::Variable(VariableSelector::Any);
             let vars = command::variables::Handler::new(dbg)
-                .handle(expr)
+                .handle_with_limits(expr, PARSE_LIMITS)
                 .unwrap_or_default();
             vars
         }) else {
@@ -174,7 +406,7 @@ impl Variables {
         let Ok(arguments) = self.exchanger.request_sync(|dbg| {
             let expr = select::DQE::Variable(VariableSelector::Any);
             let args = command::arguments::Handler::new(dbg)
-                .handle(expr)
+                .handle_with_limits(expr, PARSE_LIMITS)
                 .unwrap_or_default();
             args
         }) else {
@@ -187,8 +419,8 @@ impl Variables {
         for (i, arg) in arguments.iter().enumerate() {
             let node_name = format!("arg_{i}");
             let var_node = self.node_from_var(
-                0,
                 node_name.as_str(),
+                "arguments",
                 arg,
                 Some(DQE::Variable(VariableSelector::Name {
                     var_name: arg.name(),
@@ -203,8 +435,8 @@ impl Variables {
         for (i, var) in variables.iter().enumerate() {
             let node_name = format!("var_{i}");
             let var_node = self.node_from_var(
-                0,
                 node_name.as_str(),
+                "variables",
                 var,
                 Some(DQE::Variable(VariableSelector::Name {
                     var_name: var.name(),
@@ -228,6 +460,7 @@ impl Variables {
                 AttrValue::String("arg_0".to_string()),
             );
         }
+        self.expand_opened();
     }
 
     pub fn subscriptions() -> Vec<Sub<Id, UserEvent>> {
@@ -266,7 +499,7 @@ impl Variables {
             component: TreeView::default()
                 .borders(
                     Borders::default()
-                        .color(Color::LightYellow)
+                        .color(style::tui_color(StyleElement::Panel))
                         .modifiers(BorderType::Rounded),
                 )
                 .inactive(Style::default().fg(Color::Gray))
@@ -274,9 +507,15 @@ impl Variables {
                 .scroll_step(6)
                 .preserve_state(true)
                 .title("Variables", Alignment::Center)
-                .highlighted_color(Color::LightYellow)
+                .highlighted_color(style::tui_color(StyleElement::Panel))
                 .highlight_symbol("▶"),
             exchanger,
+            lazy: HashMap::new(),
+            refs: HashMap::new(),
+            selected_ref: None,
+            node_values: HashMap::new(),
+            values: HashMap::new(),
+            prev_values: HashMap::new(),
         };
         this.update();
         this
@@ -294,6 +533,7 @@ impl Component<Msg, UserEvent> for Variables {
             Event::Keyboard(KeyEvent {
                 code: Key::Right, ..
             }) => {
+                self.expand_selected();
                 self.perform(Cmd::Custom(TREE_CMD_OPEN));
             }
             Event::Keyboard(KeyEvent {
@@ -328,6 +568,14 @@ impl Component<Msg, UserEvent> for Variables {
             }) => {
                 self.perform(Cmd::Submit);
             }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('y'),
+                ..
+            }) => {
+                if let Err(e) = self.copy_selected() {
+                    return Some(Msg::ShowOkPopup(Some("Error".to_string()), e.to_string()));
+                }
+            }
             Event::User(UserEvent::Breakpoint { .. })
             | Event::User(UserEvent::Exit(_))
             | Event::User(UserEvent::Step { .. }) => {
@@ -336,6 +584,6 @@ impl Component<Msg, UserEvent> for Variables {
             }
             _ => {}
         };
-        Some(Msg::None)
+        self.selected_ref_msg().or(Some(Msg::None))
     }
 }
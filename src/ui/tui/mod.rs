@@ -6,12 +6,13 @@ use crate::ui::tui::app::Model;
 use crate::ui::tui::components::popup::Popup;
 use crate::ui::tui::output::{OutputLine, OutputStreamProcessor, StreamType};
 use crate::ui::tui::proto::{exchanger, Request};
-use crate::ui::{console, supervisor, DebugeeOutReader};
+use crate::ui::{config, console, supervisor, DebugeeOutReader};
 use crate::weak_error;
 use anyhow::anyhow;
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::execute;
-use log::error;
+use log::{error, warn};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::{io, thread};
@@ -51,16 +52,49 @@ pub enum BreakpointsAddType {
     AtAddress,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum MemoryInputType {
+    /// Address or expression of viewed memory.
+    Location,
+    /// Bytes for write at selected address.
+    Write,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Msg {
     None,
     AppClose,
     AppRunning,
-    LeftTabsInFocus { reset_to: Option<props::Direction> },
-    RightTabsInFocus { reset_to: Option<props::Direction> },
+    LeftTabsInFocus {
+        reset_to: Option<props::Direction>,
+    },
+    RightTabsInFocus {
+        reset_to: Option<props::Direction>,
+    },
     SwitchUI,
     BreakpointAdd(BreakpointsAddType),
     ExpandTab(Id),
+    /// Change width of the left tabs (in percents), the right tabs take the rest of space.
+    ResizeTabs(i16),
+    /// Hide or show the left tabs.
+    HideLeftTabs,
+    /// Show the next window below the visible window in the focused tabs.
+    SplitTab,
+    /// Breakpoints are added, removed, enabled or disabled, windows that show them must be updated.
+    BreakpointsChanged,
+    /// Show source code file in the source window.
+    ShowSource {
+        file: PathBuf,
+        line: u64,
+    },
+    /// Ask user for a memory location or bytes to write.
+    MemoryInput(MemoryInputType),
+    /// Highlight memory at address (or remove highlighting).
+    MemoryHighlight(Option<usize>),
+    /// Debugger switch focus to another thread.
+    ThreadFocusChanged,
+    /// Ask user for a search pattern in the visible window.
+    SearchInput,
 
     PopupConfirmDebuggerRestart,
     PopupBreakpoint(BreakpointViewOwned),
@@ -149,6 +183,12 @@ impl TuiApplication {
         let logger = utils::logger::TuiLogger::new(log_buffer.clone());
         let filter = logger.filter();
         crate::log::LOGGER_SWITCHER.switch(logger, filter);
+        if !config::current().init.commands.is_empty() {
+            warn!(target: "debugger", "init file commands are executed by console ui only");
+        }
+        // console progress bar breaks tui rendering,
+        // long operations still may be cancelled by Ctrl+c
+        crate::debugger::progress::set_observer(None);
 
         let stream_buf = DebugeeStreamBuffer::default();
 
@@ -1,4 +1,5 @@
 use crate::ui::DebugeeOutReader;
+use chrono::{Local, NaiveTime};
 use std::io::{BufRead, BufReader};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -18,8 +19,8 @@ impl Drop for Handle {
 
 #[derive(PartialEq, Eq, Clone, PartialOrd)]
 pub enum OutputLine {
-    Out(String),
-    Err(String),
+    Out { text: String, time: NaiveTime },
+    Err { text: String, time: NaiveTime },
 }
 
 #[derive(Clone, Copy)]
@@ -67,9 +68,10 @@ impl OutputStreamProcessor {
                     if size == 0 {
                         return;
                     }
+                    let time = Local::now().time();
                     let line = match self.r#type {
-                        StreamType::StdErr => OutputLine::Err(line),
-                        StreamType::StdOut => OutputLine::Out(line),
+                        StreamType::StdErr => OutputLine::Err { text: line, time },
+                        StreamType::StdOut => OutputLine::Out { text: line, time },
                     };
                     output_buf.lock().unwrap().push(line);
                 }
@@ -1,3 +1,4 @@
+pub mod clipboard;
 mod flex_radio;
 pub mod logger;
 pub mod mstextarea;
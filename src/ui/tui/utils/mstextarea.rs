@@ -101,14 +101,24 @@ impl MSTextareaStates {
 /// ## Multi span textarea
 ///
 /// Like a original `textarea` component but support multiple `TextSpan` in single line.
+/// Also support a search with highlighting of matched text.
 #[derive(Default)]
 pub struct MultiSpanTextarea {
     props: Props,
     pub states: MSTextareaStates,
     hg_str: Option<String>,
+    /// Rows without search highlighting
+    rows: Vec<Vec<TextSpan>>,
+    /// Search pattern
+    search: Option<String>,
+    /// Indexes of rows that contain the search pattern
+    matches: Vec<usize>,
 }
 
 impl MultiSpanTextarea {
+    /// Search a pattern (attribute value is a string), an empty pattern resets a search.
+    pub const SEARCH_ATTR: Attribute = Attribute::Custom("search");
+
     pub fn foreground(mut self, fg: Color) -> Self {
         self.attr(Attribute::Foreground, AttrValue::Color(fg));
         self
@@ -156,9 +166,26 @@ impl MultiSpanTextarea {
     }
 
     pub fn text_rows(&mut self, rows: Vec<Vec<TextSpan>>) {
+        self.rows = rows;
+        self.render_rows();
+    }
+
+    fn render_rows(&mut self) {
         let mut ll = LinkedList::new();
+        self.matches.clear();
 
-        for line in rows {
+        for (i, line) in self.rows.iter().enumerate() {
+            let line = match self
+                .search
+                .as_deref()
+                .and_then(|pattern| highlight_matches(line, pattern))
+            {
+                Some(highlighted) => {
+                    self.matches.push(i);
+                    highlighted
+                }
+                None => line.clone(),
+            };
             let line: Vec<_> = line.into_iter().map(PropValue::TextSpan).collect();
             ll.push_back(PropPayload::Vec(line));
         }
@@ -166,6 +193,99 @@ impl MultiSpanTextarea {
         self.states.set_list_len(ll.len());
         self.attr(Attribute::Text, AttrValue::Payload(PropPayload::Linked(ll)));
     }
+
+    /// Highlight all occurrences of a pattern and select the first matched row
+    /// (starting from the selected one).
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: search pattern, empty pattern resets a search
+    pub fn search(&mut self, pattern: &str) {
+        self.search = (!pattern.is_empty()).then(|| pattern.to_string());
+        self.render_rows();
+
+        let current = self.states.list_index;
+        if let Some(&row) = self
+            .matches
+            .iter()
+            .find(|&&row| row >= current)
+            .or(self.matches.first())
+        {
+            self.states.list_index = row;
+        }
+    }
+
+    /// Select the next matched row, rewind to the first match after the last one.
+    pub fn search_next(&mut self) {
+        let current = self.states.list_index;
+        if let Some(&row) = self
+            .matches
+            .iter()
+            .find(|&&row| row > current)
+            .or(self.matches.first())
+        {
+            self.states.list_index = row;
+        }
+    }
+
+    /// Select the previous matched row, rewind to the last match before the first one.
+    pub fn search_prev(&mut self) {
+        let current = self.states.list_index;
+        if let Some(&row) = self
+            .matches
+            .iter()
+            .rev()
+            .find(|&&row| row < current)
+            .or(self.matches.last())
+        {
+            self.states.list_index = row;
+        }
+    }
+}
+
+/// Split spans of a line so that all occurrences of a pattern are highlighted.
+/// Return `None` if the line doesn't contain a pattern.
+fn highlight_matches(line: &[TextSpan], pattern: &str) -> Option<Vec<TextSpan>> {
+    let text: String = line.iter().map(|span| span.content.as_str()).collect();
+    let ranges: Vec<_> = text
+        .match_indices(pattern)
+        .map(|(start, m)| start..start + m.len())
+        .collect();
+    if ranges.is_empty() {
+        return None;
+    }
+
+    let mut result = Vec::with_capacity(line.len());
+    let mut offset = 0;
+    for span in line {
+        let end = offset + span.content.len();
+
+        let mut bounds = vec![offset, end];
+        for range in ranges.iter() {
+            bounds.extend(
+                [range.start, range.end]
+                    .into_iter()
+                    .filter(|&b| b > offset && b < end),
+            );
+        }
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        for piece_bounds in bounds.windows(2) {
+            let (from, to) = (piece_bounds[0], piece_bounds[1]);
+            let mut piece = span.clone();
+            piece.content = text[from..to].to_string();
+            if ranges.iter().any(|r| r.start <= from && to <= r.end) {
+                piece.fg = Color::Black;
+                piece.bg = Color::Yellow;
+            }
+            result.push(piece);
+        }
+
+        offset = end;
+    }
+
+    Some(result)
 }
 
 impl MockComponent for MultiSpanTextarea {
@@ -275,6 +395,10 @@ impl MockComponent for MultiSpanTextarea {
     }
 
     fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        if attr == Self::SEARCH_ATTR {
+            return self.search(&value.unwrap_string());
+        }
+
         self.props.set(attr, value);
         // Update list len and fix index
         self.states.set_list_len(
@@ -326,3 +450,55 @@ impl MockComponent for MultiSpanTextarea {
         CmdResult::None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_highlight_matches() {
+        let line = vec![
+            TextSpan::new("   1 "),
+            TextSpan::new("let").fg(Color::Red),
+            TextSpan::new(" letter = 1;"),
+        ];
+
+        assert!(highlight_matches(&line, "foo").is_none());
+
+        let highlighted = highlight_matches(&line, "let").unwrap();
+        let contents: Vec<_> = highlighted.iter().map(|s| s.content.as_str()).collect();
+        assert_eq!(contents, vec!["   1 ", "let", " ", "let", "ter = 1;"]);
+        assert_eq!(highlighted[1].bg, Color::Yellow);
+        assert_eq!(highlighted[2].bg, Color::Reset);
+        assert_eq!(highlighted[3].bg, Color::Yellow);
+
+        let highlighted = highlight_matches(&line, "1 l").unwrap();
+        let contents: Vec<_> = highlighted.iter().map(|s| s.content.as_str()).collect();
+        assert_eq!(contents, vec!["   ", "1 ", "l", "et", " letter = 1;"]);
+    }
+
+    #[test]
+    fn test_search_navigation() {
+        let mut textarea = MultiSpanTextarea::default();
+        textarea.text_rows(
+            ["foo", "bar", "foo bar", "baz", "bar"]
+                .into_iter()
+                .map(|row| vec![TextSpan::new(row)])
+                .collect(),
+        );
+
+        textarea.search("bar");
+        assert_eq!(textarea.matches, vec![1, 2, 4]);
+        assert_eq!(textarea.states.list_index, 1);
+        textarea.search_next();
+        assert_eq!(textarea.states.list_index, 2);
+        textarea.search_next();
+        textarea.search_next();
+        assert_eq!(textarea.states.list_index, 1);
+        textarea.search_prev();
+        assert_eq!(textarea.states.list_index, 4);
+
+        textarea.search("");
+        assert!(textarea.matches.is_empty());
+    }
+}
@@ -1,9 +1,11 @@
+use crate::ui::config;
 use crate::ui::tui::app::port::UserEvent;
 use crate::ui::tui::utils::flex_radio;
+use crate::ui::tui::utils::mstextarea::MultiSpanTextarea;
 use crate::ui::tui::Msg;
 use strum_macros::FromRepr;
 use tuirealm::command::{Cmd, CmdResult, Direction};
-use tuirealm::event::{Key, KeyEvent};
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
 use tuirealm::props::{
     Alignment, BorderSides, BorderType, Borders, Color, Layout, PropPayload, PropValue,
 };
@@ -40,6 +42,8 @@ pub struct TabWindow {
     active_idx: Option<usize>,
     /// Visible window
     visible_idx: usize,
+    /// Window shown below the visible one (if any)
+    secondary_idx: Option<usize>,
     /// All windows
     windows: Vec<Box<dyn Component<Msg, UserEvent>>>,
     /// Window size in relation to others
@@ -74,6 +78,7 @@ impl TabWindow {
         let this = Self {
             active_idx: None,
             visible_idx: 0,
+            secondary_idx: None,
             choices,
             windows: tabs,
             props: Props::default(),
@@ -100,6 +105,14 @@ impl TabWindow {
 impl TabWindow {
     pub const VIEW_SIZE_ATTR: Attribute = Attribute::Custom("VIEW_SIZE");
     pub const RESET_CHOICE_ATTR: Attribute = Attribute::Custom("RESET_CHOICE");
+    /// Show window by its index (without activation).
+    pub const SELECT_WINDOW_ATTR: Attribute = Attribute::Custom("SELECT_WINDOW");
+    /// Query an index of visible window.
+    pub const VISIBLE_WINDOW_ATTR: Attribute = Attribute::Custom("VISIBLE_WINDOW");
+    /// Show window (by its index) below the visible window, or disable split if payload is none.
+    pub const SPLIT_WINDOW_ATTR: Attribute = Attribute::Custom("SPLIT_WINDOW");
+    /// Show the next window below the visible window, disable split after the last window.
+    pub const SPLIT_NEXT_ATTR: Attribute = Attribute::Custom("SPLIT_NEXT");
 
     pub fn foreground(mut self, fg: Color) -> Self {
         self.attr(Attribute::Foreground, AttrValue::Color(fg));
@@ -164,6 +177,17 @@ impl TabWindow {
             .get_mut(self.visible_idx)
             .map(move |b| &mut **b)
     }
+
+    /// Return index of window shown below the visible window.
+    fn split_idx(&self) -> Option<usize> {
+        self.secondary_idx
+            .filter(|&idx| idx != self.visible_idx && idx < self.windows.len())
+    }
+
+    fn split_next(&mut self) {
+        let start = self.secondary_idx.map(|idx| idx + 1).unwrap_or_default();
+        self.secondary_idx = (start..self.windows.len()).find(|&idx| idx != self.visible_idx);
+    }
 }
 
 impl MockComponent for TabWindow {
@@ -197,15 +221,41 @@ impl MockComponent for TabWindow {
                 let chunks = layout.chunks(area);
                 debug_assert!(chunks.len() == 2);
                 self.choices.view(frame, chunks[0]);
+
+                let (visible_area, secondary_area) = match self.split_idx() {
+                    Some(idx) => {
+                        let window_chunks = tuirealm::tui::layout::Layout::default()
+                            .direction(tuirealm::tui::layout::Direction::Vertical)
+                            .constraints([
+                                tuirealm::tui::layout::Constraint::Percentage(50),
+                                tuirealm::tui::layout::Constraint::Percentage(50),
+                            ])
+                            .split(chunks[1]);
+                        (window_chunks[0], Some((idx, window_chunks[1])))
+                    }
+                    None => (chunks[1], None),
+                };
+
                 if let Some(window) = self.visible_window_mut() {
-                    window.view(frame, chunks[1]);
+                    window.view(frame, visible_area);
+                }
+                if let Some((idx, area)) = secondary_area {
+                    self.windows[idx].view(frame, area);
                 }
             }
         }
     }
 
     fn query(&self, attr: Attribute) -> Option<AttrValue> {
-        self.props.get(attr)
+        match attr {
+            Self::VISIBLE_WINDOW_ATTR => Some(AttrValue::Length(self.visible_idx)),
+            Self::SPLIT_WINDOW_ATTR => Some(AttrValue::Payload(
+                self.secondary_idx
+                    .map(|idx| PropPayload::One(PropValue::Usize(idx)))
+                    .unwrap_or(PropPayload::None),
+            )),
+            _ => self.props.get(attr),
+        }
     }
 
     fn attr(&mut self, attr: Attribute, value: AttrValue) {
@@ -242,6 +292,30 @@ impl MockComponent for TabWindow {
                     _ => {}
                 }
             }
+            Self::SELECT_WINDOW_ATTR => {
+                let idx = value.unwrap_length();
+                if idx < self.windows.len() {
+                    self.choices.states.select(idx);
+                    self.visible_idx = idx;
+                }
+            }
+            Self::SPLIT_WINDOW_ATTR => {
+                self.secondary_idx = match value.unwrap_payload() {
+                    PropPayload::One(PropValue::Usize(idx)) if idx < self.windows.len() => {
+                        Some(idx)
+                    }
+                    _ => None,
+                };
+            }
+            Self::SPLIT_NEXT_ATTR => {
+                self.split_next();
+            }
+            MultiSpanTextarea::SEARCH_ATTR => {
+                // search only in a visible window
+                if let Some(window) = self.visible_window_mut() {
+                    window.attr(attr, value);
+                }
+            }
             Attribute::Custom(_) => {
                 // all other custom attributes redirect to tab windows
                 for comp in self.windows.iter_mut() {
@@ -272,12 +346,19 @@ impl MockComponent for TabWindow {
 
 impl Component<Msg, UserEvent> for TabWindow {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let ev = match ev {
+            Event::Keyboard(key) => Event::Keyboard(config::current().init.keymap.navigation(key)),
+            ev => ev,
+        };
+
         let cmd_res = match ev {
             Event::Keyboard(KeyEvent {
-                code: Key::Left, ..
+                code: Key::Left,
+                modifiers: KeyModifiers::NONE,
             }) => self.perform(Cmd::Move(Direction::Left)),
             Event::Keyboard(KeyEvent {
-                code: Key::Right, ..
+                code: Key::Right,
+                modifiers: KeyModifiers::NONE,
             })
             | Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
                 self.perform(Cmd::Move(Direction::Right))
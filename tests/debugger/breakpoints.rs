@@ -1,9 +1,16 @@
 use crate::common::DebugeeRunInfo;
 use crate::common::TestHooks;
-use crate::{assert_no_proc, FIZZBUZZ_APP, HW_APP, SHARED_LIB_APP, VARS_APP};
+use crate::{
+    assert_no_proc, FIZZBUZZ_APP, HW_APP, INLINE_APP, RECURSION_APP, SHARED_LIB_APP, VARS_APP,
+};
 use crate::{prepare_debugee_process, CALC_APP};
-use bugstalker::debugger::DebuggerBuilder;
+use bugstalker::debugger::address::{Address, RelocatedAddress};
+use bugstalker::debugger::catchpoint::SyscallFilter;
+use bugstalker::debugger::event::{DebuggerEvent, EventStream};
+use bugstalker::debugger::history::StopEvent;
+use bugstalker::debugger::{DebuggerBuilder, SyscallStop};
 use serial_test::serial;
+use std::mem;
 
 #[test]
 #[serial]
@@ -85,6 +92,98 @@ fn test_brkpt_on_function() {
     assert_no_proc!(debugee_pid);
 }
 
+#[test]
+#[serial]
+fn test_brkpt_on_symbol_offset() {
+    let process = prepare_debugee_process(CALC_APP, &["1", "2", "3", "--description", "result"]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    // symbol start is a function declaration line
+    assert_eq!(
+        debugger
+            .set_breakpoint_at_symbol("calc::sum2", 0)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(debugger
+        .set_breakpoint_at_symbol("calc::sum2", 1 << 20)
+        .is_err());
+    assert!(debugger
+        .set_breakpoint_at_symbol("no_such_symbol", 0)
+        .is_err());
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(20));
+    let pc = debugger.exploration_ctx().location().pc;
+
+    // address outside of executable mappings
+    assert!(debugger
+        .set_breakpoint_at_addr(RelocatedAddress::from(0x10_usize))
+        .is_err());
+
+    assert_eq!(
+        debugger
+            .remove_breakpoint_at_symbol("calc::sum2", 0)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(debugger.set_breakpoint_at_addr(pc).is_ok());
+    debugger.remove_breakpoint(Address::Relocated(pc)).unwrap();
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_stop_history() {
+    let process = prepare_debugee_process(CALC_APP, &["1", "2", "3", "--description", "result"]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    let sum3_brkpt = debugger.set_breakpoint_at_line("main.rs", 25).unwrap()[0].number;
+    let sum2_brkpt = debugger.set_breakpoint_at_line("main.rs", 21).unwrap()[0].number;
+    debugger.set_history_stack_depth(2);
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(25));
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(21));
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(21));
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+
+    let history: Vec<_> = debugger.history().collect();
+    let events: Vec<_> = history.iter().map(|record| record.event).collect();
+    assert_eq!(
+        events,
+        vec![
+            StopEvent::Breakpoint(sum3_brkpt),
+            StopEvent::Breakpoint(sum2_brkpt),
+            StopEvent::Breakpoint(sum2_brkpt),
+            StopEvent::Exit(0),
+        ]
+    );
+    let lines: Vec<_> = history
+        .iter()
+        .map(|record| record.place.as_ref().map(|place| place.line_number))
+        .collect();
+    assert_eq!(lines, vec![Some(25), Some(21), Some(21), None]);
+    assert_eq!(history[0].stack.as_ref().map(Vec::len), Some(2));
+    assert!(history.windows(2).all(|w| w[0].time <= w[1].time));
+
+    debugger.clear_history();
+    assert_eq!(debugger.history().len(), 0);
+}
+
 #[test]
 #[serial]
 fn test_brkpt_on_function_name_collision() {
@@ -146,6 +245,36 @@ fn test_brkpt_on_line_collision() {
     assert_eq!(brkpts.len(), 1);
 }
 
+#[test]
+#[serial]
+fn test_restart_keeps_shared_lib_brkpt() {
+    let process = prepare_debugee_process(SHARED_LIB_APP, &[]);
+    let attempt_1_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+    debugger.set_breakpoint_at_line("main.rs", 14).unwrap();
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(14));
+
+    // library is loaded with dlopen, after a program start
+    debugger
+        .set_breakpoint_at_line("printer_lib/src/lib.rs", 3)
+        .unwrap();
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(3));
+
+    let attempt_2_pid = debugger.restart_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(14));
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(3));
+    assert_eq!(debugger.breakpoints_snapshot().len(), 2);
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(attempt_1_pid);
+    assert_no_proc!(attempt_2_pid);
+}
+
 #[test]
 #[serial]
 fn test_brkpt_on_line() {
@@ -193,6 +322,35 @@ fn test_brkpt_on_line2() {
     assert_no_proc!(debugee_pid);
 }
 
+#[test]
+#[serial]
+fn test_brkpt_on_line_without_code() {
+    let process = prepare_debugee_process(HW_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    let lines = debugger.known_lines("hello_world.rs").unwrap();
+    let has_stmt = |line| lines.iter().any(|p| p.line_number == line && p.is_stmt);
+    assert!(has_stmt(5));
+    assert!(has_stmt(15));
+    assert!(!has_stmt(3));
+    assert!(!has_stmt(11));
+
+    // lines 11 and 12 have no code, breakpoint is moved to the nearest statement
+    let brkpts = debugger
+        .set_breakpoint_at_line("hello_world.rs", 11)
+        .unwrap();
+    assert_eq!(brkpts[0].place.as_ref().unwrap().line_number, 10);
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(10));
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
 #[test]
 #[serial]
 fn test_set_breakpoint_idempotence() {
@@ -268,6 +426,61 @@ fn test_breakpoint_at_fn_with_monomorphization() {
     assert_no_proc!(debugee_pid);
 }
 
+#[test]
+#[serial]
+fn test_breakpoint_locations_share_number() {
+    let process = prepare_debugee_process(INLINE_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    // a location for each instantiation of a generic function
+    let generic: Vec<_> = debugger
+        .set_breakpoint_at_fn("twice_plus_one")
+        .unwrap()
+        .iter()
+        .map(|brkpt| brkpt.number)
+        .collect();
+    assert_eq!(generic.len(), 2);
+    assert_eq!(generic[0], generic[1]);
+
+    // a location for each inline expansion
+    let inline: Vec<_> = debugger
+        .set_breakpoint_at_fn("double")
+        .unwrap()
+        .iter()
+        .map(|brkpt| brkpt.number)
+        .collect();
+    assert_eq!(inline.len(), 2);
+    assert_eq!(inline[0], inline[1]);
+    assert_ne!(generic[0], inline[0]);
+
+    debugger.disable_breakpoint(generic[0]).unwrap();
+    assert!(debugger
+        .breakpoints_snapshot()
+        .iter()
+        .filter(|brkpt| brkpt.number == generic[0])
+        .all(|brkpt| brkpt.options.disabled));
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(3));
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(3));
+    assert!(debugger
+        .breakpoints_snapshot()
+        .iter()
+        .filter(|brkpt| brkpt.number == inline[0])
+        .all(|brkpt| brkpt.options.hit_count == 2));
+
+    let removed = debugger.remove_breakpoint_by_number(inline[0]).unwrap();
+    assert_eq!(removed.len(), 2);
+    assert_eq!(debugger.breakpoints_snapshot().len(), 2);
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
 #[test]
 #[serial]
 fn test_breakpoint_at_line_with_monomorphization() {
@@ -290,3 +503,154 @@ fn test_breakpoint_at_line_with_monomorphization() {
     debugger.continue_debugee().unwrap();
     assert_no_proc!(debugee_pid);
 }
+
+#[test]
+#[serial]
+fn test_breakpoint_event_stream() {
+    let process = prepare_debugee_process(HW_APP, &[]);
+    let debugee_pid = process.pid();
+    let (hooks, events) = EventStream::new();
+    let mut debugger = DebuggerBuilder::new()
+        .with_hooks(hooks)
+        .build(process)
+        .unwrap();
+    debugger
+        .set_breakpoint_at_line("hello_world.rs", 5)
+        .unwrap();
+
+    debugger.start_debugee().unwrap();
+    let stop = events.try_iter().find_map(|event| match event {
+        DebuggerEvent::Breakpoint {
+            number,
+            place,
+            function,
+            ..
+        } => Some((number, place.map(|p| p.line_number), function)),
+        _ => None,
+    });
+    let (number, line, function) = stop.unwrap();
+    assert_eq!(number, 1);
+    assert_eq!(line, Some(5));
+    assert!(function.unwrap().ends_with("main"));
+
+    debugger.continue_debugee().unwrap();
+    assert!(events
+        .try_iter()
+        .any(|event| event == DebuggerEvent::Exit(0)));
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_syscall_catchpoint() {
+    let process = prepare_debugee_process(HW_APP, &[]);
+    let debugee_pid = process.pid();
+    let (hooks, events) = EventStream::new();
+    let mut debugger = DebuggerBuilder::new()
+        .with_hooks(hooks)
+        .build(process)
+        .unwrap();
+    debugger
+        .set_breakpoint_at_line("hello_world.rs", 5)
+        .unwrap();
+
+    debugger.start_debugee().unwrap();
+    _ = events.try_iter().count();
+
+    let filter = SyscallFilter::from_names(["write"]).unwrap();
+    debugger.enable_syscall_catchpoint(filter.clone());
+    assert_eq!(debugger.syscall_catchpoint(), Some(&filter));
+
+    let syscall_stop = |events: &std::sync::mpsc::Receiver<DebuggerEvent>| {
+        events.try_iter().find_map(|event| match event {
+            DebuggerEvent::Syscall { event, .. } => Some(event),
+            _ => None,
+        })
+    };
+
+    debugger.continue_debugee().unwrap();
+    let entry = syscall_stop(&events).unwrap();
+    assert_eq!(entry.name, Some("write"));
+    assert!(matches!(entry.stop, SyscallStop::Entry { nr: 1, args } if args[0] == 1));
+    assert_eq!(debugger.exploration_ctx().pid_on_focus(), entry.pid);
+
+    debugger.continue_debugee().unwrap();
+    let exit = syscall_stop(&events).unwrap();
+    assert_eq!(exit.nr, 1);
+    assert_eq!(
+        exit.stop,
+        SyscallStop::Exit {
+            rval: "Hello, world!\n".len() as i64,
+            is_error: false
+        }
+    );
+
+    debugger.disable_syscall_catchpoint();
+    debugger.continue_debugee().unwrap();
+    assert!(events
+        .try_iter()
+        .any(|event| event == DebuggerEvent::Exit(0)));
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_brkpt_ignore_count() {
+    let process = prepare_debugee_process(RECURSION_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    let number = debugger.set_breakpoint_at_fn("infinite_inc").unwrap()[0].number;
+    debugger.set_breakpoint_ignore_count(number, 3).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(11));
+    let brkpt = &debugger.breakpoints_snapshot()[0];
+    assert_eq!(brkpt.options.hit_count, 4);
+    assert_eq!(brkpt.options.ignore_count, 0);
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(11));
+    assert_eq!(debugger.breakpoints_snapshot()[0].options.hit_count, 5);
+
+    mem::drop(debugger);
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_hardware_brkpt() {
+    let process = prepare_debugee_process(HW_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+    let number = debugger
+        .set_breakpoint_at_line("hello_world.rs", 15)
+        .unwrap()[0]
+        .number;
+    let brkpt = debugger
+        .set_breakpoint_hardware(number, true)
+        .unwrap()
+        .unwrap();
+    assert!(brkpt.options.hardware.is_some());
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(15));
+    let pc1 = debugger.exploration_ctx().location().pc;
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(15));
+    assert_eq!(debugger.exploration_ctx().location().pc, pc1);
+
+    let brkpt = debugger
+        .set_breakpoint_hardware(number, false)
+        .unwrap()
+        .unwrap();
+    assert!(brkpt.options.hardware.is_none());
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
@@ -1,8 +1,7 @@
 use bugstalker::debugger::address::RelocatedAddress;
-use bugstalker::debugger::{EventHook, FunctionDie, PlaceDescriptor};
+use bugstalker::debugger::watchpoint::WatchValue;
+use bugstalker::debugger::{Error, EventHook, FunctionDie, PlaceDescriptor};
 use bugstalker::version::Version;
-use nix::sys::signal::Signal;
-use nix::unistd::Pid;
 use object::{Object, ObjectSection};
 use std::cell::Cell;
 use std::fs;
@@ -33,7 +32,7 @@ impl EventHook for TestHooks {
         _: u32,
         place: Option<PlaceDescriptor>,
         _: Option<&FunctionDie>,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), Error> {
         self.info.addr.set(Some(pc));
         let file = &self.info.file;
         file.set(place.as_ref().map(|p| p.file.to_str().unwrap().to_string()));
@@ -46,16 +45,29 @@ impl EventHook for TestHooks {
         pc: RelocatedAddress,
         place: Option<PlaceDescriptor>,
         _: Option<&FunctionDie>,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), Error> {
+        self.info.addr.set(Some(pc));
+        let file = &self.info.file;
+        file.set(place.as_ref().map(|p| p.file.to_str().unwrap().to_string()));
+        self.info.line.set(place.map(|p| p.line_number));
+        Ok(())
+    }
+
+    fn on_watchpoint(
+        &self,
+        pc: RelocatedAddress,
+        _: u32,
+        place: Option<PlaceDescriptor>,
+        _: Option<&FunctionDie>,
+        _: &WatchValue,
+        _: &WatchValue,
+    ) -> Result<(), Error> {
         self.info.addr.set(Some(pc));
         let file = &self.info.file;
         file.set(place.as_ref().map(|p| p.file.to_str().unwrap().to_string()));
         self.info.line.set(place.map(|p| p.line_number));
         Ok(())
     }
-    fn on_signal(&self, _: Signal) {}
-    fn on_exit(&self, _code: i32) {}
-    fn on_process_install(&self, _pid: Pid, _: Option<&object::File>) {}
 }
 
 #[macro_export]
@@ -0,0 +1,195 @@
+use crate::common::{DebugeeRunInfo, TestHooks};
+use crate::VARS_APP;
+use crate::{assert_no_proc, prepare_debugee_process};
+use bugstalker::debugger::process::Child;
+use bugstalker::debugger::variable::render::RenderRepr;
+use bugstalker::debugger::variable::{SupportedScalar, VariableIR};
+use bugstalker::debugger::{DebuggerBuilder, Error};
+use nix::sys::ptrace;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use object::elf::{EM_X86_64, ET_CORE, NT_FILE, NT_PRSTATUS, PT_LOAD, PT_NOTE};
+use serial_test::serial;
+use std::fs::File;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::{env, fs, mem, slice};
+
+const ELF_HEADER_SIZE: usize = 64;
+const PHDR_SIZE: usize = 56;
+const PRSTATUS_SIZE: usize = 336;
+const PAGE_SIZE: u64 = 4096;
+
+fn push_note(buf: &mut Vec<u8>, n_type: u32, desc: &[u8]) {
+    buf.extend(5_u32.to_le_bytes());
+    buf.extend((desc.len() as u32).to_le_bytes());
+    buf.extend(n_type.to_le_bytes());
+    buf.extend(b"CORE\0\0\0\0");
+    buf.extend(desc);
+    buf.resize(buf.len().next_multiple_of(4), 0);
+}
+
+fn push_phdr(buf: &mut Vec<u8>, p_type: u32, offset: usize, vaddr: usize, size: usize) {
+    buf.extend(p_type.to_le_bytes());
+    buf.extend(4_u32.to_le_bytes());
+    for value in [offset, vaddr, vaddr, size, size, 1] {
+        buf.extend((value as u64).to_le_bytes());
+    }
+}
+
+/// Write a core file of a stopped debugee (like `gcore` does): a segment for each readable
+/// mapping, registers of each thread and a list of mapped files.
+/// The first thread is reported as a thread that receives a `SIGSEGV`.
+fn write_core(path: &Path, pid: Pid, threads: &[Pid]) {
+    let mem_file = File::open(format!("/proc/{pid}/mem")).unwrap();
+    let maps = proc_maps::get_process_maps(pid.as_raw()).unwrap();
+    let segments: Vec<_> = maps
+        .iter()
+        .filter(|map| map.is_read())
+        .filter_map(|map| {
+            let mut data = vec![0; map.size()];
+            // some special regions (like `[vvar]`) are unreadable
+            mem_file.read_exact_at(&mut data, map.start() as u64).ok()?;
+            Some((map.start(), data))
+        })
+        .collect();
+
+    let mut notes = vec![];
+    for (i, tid) in threads.iter().enumerate() {
+        let regs = ptrace::getregs(*tid).unwrap();
+        // SAFETY: `user_regs_struct` is a plain structure of integers
+        let regs = unsafe {
+            slice::from_raw_parts(&regs as *const _ as *const u8, mem::size_of_val(&regs))
+        };
+        let signal = if i == 0 { Signal::SIGSEGV as i16 } else { 0 };
+
+        let mut prstatus = vec![0; PRSTATUS_SIZE];
+        prstatus[12..14].copy_from_slice(&signal.to_le_bytes());
+        prstatus[32..36].copy_from_slice(&tid.as_raw().to_le_bytes());
+        prstatus[112..112 + regs.len()].copy_from_slice(regs);
+        push_note(&mut notes, NT_PRSTATUS, &prstatus);
+    }
+
+    let files: Vec<_> = maps
+        .iter()
+        .filter_map(|map| Some((map, map.filename()?)))
+        .filter(|(_, file)| file.is_absolute())
+        .collect();
+    let mut file_note = vec![];
+    file_note.extend((files.len() as u64).to_le_bytes());
+    file_note.extend(PAGE_SIZE.to_le_bytes());
+    for (map, _) in &files {
+        file_note.extend((map.start() as u64).to_le_bytes());
+        file_note.extend(((map.start() + map.size()) as u64).to_le_bytes());
+        file_note.extend((map.offset as u64 / PAGE_SIZE).to_le_bytes());
+    }
+    for (_, file) in &files {
+        file_note.extend(file.to_str().unwrap().as_bytes());
+        file_note.push(0);
+    }
+    push_note(&mut notes, NT_FILE, &file_note);
+
+    let phnum = 1 + segments.len();
+    let notes_offset = ELF_HEADER_SIZE + phnum * PHDR_SIZE;
+
+    let mut core = vec![0x7f, b'E', b'L', b'F', 2, 1, 1];
+    core.resize(16, 0);
+    core.extend(ET_CORE.to_le_bytes());
+    core.extend(EM_X86_64.to_le_bytes());
+    core.extend(1_u32.to_le_bytes());
+    core.extend(0_u64.to_le_bytes());
+    core.extend((ELF_HEADER_SIZE as u64).to_le_bytes());
+    core.extend(0_u64.to_le_bytes());
+    core.extend(0_u32.to_le_bytes());
+    for value in [ELF_HEADER_SIZE, PHDR_SIZE, phnum, 0, 0, 0] {
+        core.extend((value as u16).to_le_bytes());
+    }
+
+    push_phdr(&mut core, PT_NOTE, notes_offset, 0, notes.len());
+    let mut offset = notes_offset + notes.len();
+    for (start, data) in &segments {
+        push_phdr(&mut core, PT_LOAD, offset, *start, data.len());
+        offset += data.len();
+    }
+    core.extend(notes);
+    for (_, data) in segments {
+        core.extend(data);
+    }
+
+    fs::write(path, core).unwrap();
+}
+
+#[test]
+#[serial]
+fn test_core_backtrace_and_variables() {
+    let process = prepare_debugee_process(VARS_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("vars.rs", 30).unwrap();
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(30));
+
+    let mut threads: Vec<Pid> = debugger
+        .thread_state()
+        .unwrap()
+        .iter()
+        .map(|snapshot| snapshot.thread.pid)
+        .collect();
+    threads.sort_by_key(|&tid| tid != debugee_pid);
+    let core_path = env::temp_dir().join(format!("bs-test-core.{debugee_pid}"));
+    write_core(&core_path, debugee_pid, &threads);
+
+    drop(debugger);
+    assert_no_proc!(debugee_pid);
+
+    let (_, writer) = os_pipe::pipe().unwrap();
+    let process =
+        Child::from_core(VARS_APP, &core_path, writer.try_clone().unwrap(), writer).unwrap();
+    assert_eq!(process.pid(), debugee_pid);
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::default());
+    let mut debugger = builder.build(process).unwrap();
+
+    let bt = debugger.backtrace(debugee_pid).unwrap();
+    assert!(bt[0]
+        .func_name
+        .as_ref()
+        .unwrap()
+        .contains("vars::scalar_types"));
+    assert_eq!(bt[1].func_name.as_ref().unwrap(), "vars::main");
+
+    let vars = debugger.read_local_variables().unwrap();
+    let scalar = |idx: usize| {
+        let VariableIR::Scalar(scalar) = &vars[idx] else {
+            panic!("not a scalar");
+        };
+        (vars[idx].name(), scalar.value.clone())
+    };
+    assert_eq!(
+        scalar(0),
+        ("int8".to_string(), Some(SupportedScalar::I8(1)))
+    );
+    assert_eq!(
+        scalar(10),
+        ("uint128".to_string(), Some(SupportedScalar::U128(5)))
+    );
+    assert_eq!(
+        scalar(17),
+        (
+            "char_non_ascii".to_string(),
+            Some(SupportedScalar::Char('😊'))
+        )
+    );
+
+    // core file is read-only, debugee can't be executed
+    assert!(matches!(
+        debugger.continue_debugee(),
+        Err(Error::PostMortem)
+    ));
+    assert!(matches!(debugger.step_over(), Err(Error::PostMortem)));
+
+    drop(debugger);
+    fs::remove_file(core_path).unwrap();
+}
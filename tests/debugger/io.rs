@@ -2,8 +2,10 @@ use crate::common::DebugeeRunInfo;
 use crate::common::TestHooks;
 use crate::HW_APP;
 use crate::{assert_no_proc, prepare_debugee_process, CALC_APP};
+use bugstalker::debugger::capture::{IoCapture, OutputStream};
+use bugstalker::debugger::event::{DebuggerEvent, EventStream};
 use bugstalker::debugger::variable::render::{RenderRepr, ValueLayout};
-use bugstalker::debugger::DebuggerBuilder;
+use bugstalker::debugger::{rust, DebuggerBuilder};
 use serial_test::serial;
 use std::borrow::Cow;
 use std::mem;
@@ -55,6 +57,21 @@ fn test_backtrace() {
     assert_ne!(bt[1].fn_start_ip.unwrap().as_u64(), 0);
     assert_eq!(bt[1].func_name.as_ref().unwrap(), "hello_world::main");
 
+    let top = debugger.backtrace_limited(debugee_pid, 2).unwrap();
+    assert_eq!(top.len(), 2);
+    assert_eq!(top[1].func_name, bt[1].func_name);
+
+    let mut snap = debugger.thread_state().unwrap().pop().unwrap();
+    assert!(!snap.bt_truncated);
+    snap.bt.as_mut().unwrap().truncate(2);
+    snap.bt_truncated = true;
+    snap.fetch_backtrace(&debugger, Some(5)).unwrap();
+    assert_eq!(snap.bt.as_ref().unwrap().len(), 5);
+    assert!(snap.bt_truncated);
+    snap.fetch_backtrace(&debugger, None).unwrap();
+    assert_eq!(snap.bt.as_ref().unwrap().len(), 11);
+    assert!(!snap.bt_truncated);
+
     debugger.continue_debugee().unwrap();
     debugger.continue_debugee().unwrap();
 
@@ -89,3 +106,82 @@ fn test_read_value_u64() {
 
     assert_no_proc!(debugee_pid);
 }
+
+#[test]
+#[serial]
+fn test_debugee_output_capture() {
+    rust::Environment::init(None);
+    let capture = IoCapture::new(false).unwrap();
+    let process = capture
+        .process(CALC_APP, ["1", "2", "3", "--description", "result"])
+        .unwrap()
+        .install()
+        .unwrap();
+    let debugee_pid = process.pid();
+    let (hooks, events) = EventStream::new();
+    let mut debugger = DebuggerBuilder::new()
+        .with_hooks(hooks)
+        .with_io_capture(capture)
+        .build(process)
+        .unwrap();
+    debugger.set_breakpoint_at_line("main.rs", 17).unwrap();
+
+    debugger.start_debugee().unwrap();
+    let stdout: Vec<u8> = events
+        .try_iter()
+        .filter_map(|event| match event {
+            DebuggerEvent::Output {
+                stream: OutputStream::Stdout,
+                data,
+            } => Some(data),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    assert_eq!(String::from_utf8(stdout).unwrap(), "result: 6\n");
+
+    // stdin isn't captured
+    assert!(debugger.write_stdin(b"input").is_err());
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_program_args_and_env() {
+    rust::Environment::init(None);
+    let capture = IoCapture::new(false).unwrap();
+    let process = capture
+        .process(CALC_APP, ["1", "2", "3", "--description", "result"])
+        .unwrap()
+        .install()
+        .unwrap();
+    let (hooks, events) = EventStream::new();
+    let mut debugger = DebuggerBuilder::new()
+        .with_hooks(hooks)
+        .with_io_capture(capture)
+        .build(process)
+        .unwrap();
+
+    debugger.set_program_args(
+        ["10", "20", "30", "--description", "sum"]
+            .map(ToString::to_string)
+            .to_vec(),
+    );
+    debugger.set_program_env("CALC_UNUSED", Some("1"));
+    debugger.set_program_env("CALC_UNUSED", None);
+    assert_eq!(debugger.program_args()[0], "10");
+    assert_eq!(debugger.program_env(), &[("CALC_UNUSED".to_string(), None)]);
+
+    debugger.start_debugee().unwrap();
+    let stdout: Vec<u8> = events
+        .try_iter()
+        .filter_map(|event| match event {
+            DebuggerEvent::Output { data, .. } => Some(data),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    assert_eq!(String::from_utf8(stdout).unwrap(), "sum: 60\n");
+}
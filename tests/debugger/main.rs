@@ -1,6 +1,7 @@
 mod common;
 
 mod breakpoints;
+mod coredump;
 mod io;
 mod multithreaded;
 mod signal;
@@ -11,7 +12,7 @@ mod variables;
 use crate::common::{DebugeeRunInfo, TestHooks};
 use bugstalker::debugger::process::{Child, Installed};
 use bugstalker::debugger::register::{Register, RegisterMap};
-use bugstalker::debugger::{rust, DebuggerBuilder};
+use bugstalker::debugger::{rust, DebuggerBuilder, DisasmTarget};
 use serial_test::serial;
 use std::io::{BufRead, BufReader};
 use std::thread;
@@ -45,6 +46,7 @@ const SIGNALS_APP: &str = "./examples/target/debug/signals";
 const SHARED_LIB_APP: &str = "./examples/target/debug/calc_bin";
 const SLEEPER_APP: &str = "./examples/target/debug/sleeper";
 const FIZZBUZZ_APP: &str = "./examples/target/debug/fizzbuzz";
+const INLINE_APP: &str = "./examples/target/debug/inline";
 
 #[test]
 #[serial]
@@ -166,3 +168,45 @@ fn test_debugger_disassembler() {
     drop(debugger);
     assert_no_proc!(pid);
 }
+
+#[test]
+#[serial]
+fn test_debugger_annotated_disassembly() {
+    let process = prepare_debugee_process(HW_APP, &[]);
+    let pid = process.pid();
+
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::default());
+    let mut debugger = builder.build(process).unwrap();
+    debugger
+        .set_breakpoint_at_line("hello_world.rs", 15)
+        .unwrap();
+    debugger.start_debugee().unwrap();
+
+    let disasm = debugger.disassemble(DisasmTarget::CurrentFunction).unwrap();
+    assert_eq!(disasm.name, Some("hello_world::myprint".to_string()));
+    let pc = debugger.exploration_ctx().location().pc;
+    assert_eq!(disasm.instructions.iter().filter(|i| i.is_pc).count(), 1);
+    let pc_idx = disasm.instructions.iter().position(|i| i.is_pc).unwrap();
+    let current = &disasm.instructions[pc_idx];
+    assert_eq!(current.address, pc);
+    assert_eq!(current.breakpoint, Some(1));
+    let place = current.place.as_ref().unwrap();
+    assert!(place.file.ends_with("hello_world.rs"));
+    assert_eq!(place.line_number, 15);
+
+    let first = disasm.instructions[0].address;
+    let next = disasm.instructions[pc_idx + 1].address;
+    let range = debugger
+        .disassemble(DisasmTarget::Range(first, next))
+        .unwrap();
+    assert_eq!(range.name, None);
+    assert_eq!(range.instructions.last().unwrap().address, pc);
+    assert!(range.instructions.last().unwrap().is_pc);
+
+    assert!(debugger
+        .disassemble(DisasmTarget::Range(pc, first))
+        .is_err());
+
+    drop(debugger);
+    assert_no_proc!(pid);
+}
@@ -2,9 +2,11 @@ use crate::common::DebugeeRunInfo;
 use crate::common::TestHooks;
 use crate::prepare_debugee_process;
 use crate::{assert_no_proc, MT_APP};
+use bugstalker::debugger::event::{DebuggerEvent, EventStream};
 use bugstalker::debugger::unwind::Backtrace;
-use bugstalker::debugger::DebuggerBuilder;
+use bugstalker::debugger::{DebuggerBuilder, ThreadSelector};
 use itertools::Itertools;
+use nix::unistd::Pid;
 use serial_test::serial;
 use std::ffi::OsStr;
 
@@ -20,6 +22,50 @@ fn test_multithreaded_app_running() {
     assert_no_proc!(debugee_pid);
 }
 
+#[test]
+#[serial]
+fn test_thread_and_library_events() {
+    let process = prepare_debugee_process(MT_APP, &[]);
+    let debugee_pid = process.pid();
+    let (hooks, events) = EventStream::new();
+    let mut debugger = DebuggerBuilder::new()
+        .with_hooks(hooks)
+        .build(process)
+        .unwrap();
+
+    // set breakpoint after all threads are joined
+    debugger.set_breakpoint_at_line("mt.rs", 14).unwrap();
+    debugger.start_debugee().unwrap();
+
+    let events: Vec<_> = events.try_iter().collect();
+    let created: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            DebuggerEvent::ThreadCreated(pid) => Some(*pid),
+            _ => None,
+        })
+        .collect();
+    let exited: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            DebuggerEvent::ThreadExited(pid) => Some(*pid),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(created.len(), 4);
+    assert_eq!(
+        created.iter().sorted().collect_vec(),
+        exited.iter().sorted().collect_vec()
+    );
+    assert!(events.iter().any(|event| matches!(
+        event,
+        DebuggerEvent::SharedLibLoaded(path) if path.to_string_lossy().contains("libc")
+    )));
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
 #[test]
 #[serial]
 fn test_multithreaded_breakpoints() {
@@ -123,3 +169,99 @@ fn test_multithreaded_trace() {
     debugger.continue_debugee().unwrap();
     assert_no_proc!(debugee_pid);
 }
+
+#[test]
+#[serial]
+fn test_multithreaded_non_stop() {
+    let process = prepare_debugee_process(MT_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("mt.rs", 6).unwrap();
+    debugger.set_breakpoint_at_line("mt.rs", 24).unwrap();
+    debugger.set_breakpoint_at_line("mt.rs", 36).unwrap();
+    debugger.set_breakpoint_at_line("mt.rs", 14).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(6));
+    debugger.set_non_stop(true).unwrap();
+    assert!(debugger.is_non_stop());
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(36));
+
+    // only a thread at breakpoint is stopped
+    let threads = debugger.thread_state().unwrap();
+    let focus_thread = threads.iter().find(|t| t.in_focus).unwrap();
+    assert!(focus_thread.thread.is_stopped());
+    let running = threads
+        .iter()
+        .find(|t| !t.thread.is_stopped())
+        .expect("other threads must be running");
+    assert!(running.bt.is_none());
+    assert!(debugger
+        .set_thread_into_focus(running.thread.number)
+        .is_err());
+
+    // thread 1 reaches a breakpoint while thread 2 is stopped
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(24));
+
+    debugger.set_non_stop(false).unwrap();
+    let threads = debugger.thread_state().unwrap();
+    assert!(threads.iter().all(|t| t.thread.is_stopped()));
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(14));
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_multithreaded_thread_focus() {
+    let process = prepare_debugee_process(MT_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("mt.rs", 24).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(24));
+    let trap_thread = debugger.exploration_ctx().pid_on_focus();
+
+    let threads = debugger.thread_state().unwrap();
+    let main_thread = threads
+        .iter()
+        .find(|t| backtrace_contains_fn(t.bt.as_ref().unwrap(), "mt::main"))
+        .unwrap();
+
+    let tracee = debugger
+        .set_thread_focus(ThreadSelector::Tid(main_thread.thread.pid))
+        .unwrap();
+    assert_eq!(tracee.number, main_thread.thread.number);
+    assert_eq!(debugger.exploration_ctx().pid_on_focus(), tracee.pid);
+    let bt = debugger
+        .backtrace(debugger.exploration_ctx().pid_on_focus())
+        .unwrap();
+    assert!(backtrace_contains_fn(&bt, "mt::main"));
+
+    let tracee = debugger
+        .set_thread_focus(ThreadSelector::Number(main_thread.thread.number))
+        .unwrap();
+    assert_eq!(tracee.pid, main_thread.thread.pid);
+    assert!(debugger
+        .set_thread_focus(ThreadSelector::Tid(Pid::from_raw(-2)))
+        .is_err());
+    assert_ne!(debugger.exploration_ctx().pid_on_focus(), trap_thread);
+
+    // a thread that lost focus at a breakpoint doesn't hit it again
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), None);
+    assert_no_proc!(debugee_pid);
+}
@@ -2,8 +2,11 @@ use crate::common::DebugeeRunInfo;
 use crate::common::TestHooks;
 use crate::CALC_APP;
 use crate::{assert_no_proc, prepare_debugee_process, HW_APP, RECURSION_APP, VARS_APP};
+use bugstalker::debugger::skip::SkipRule;
+use bugstalker::debugger::variable::render::RenderRepr;
+use bugstalker::debugger::variable::select::Literal;
 use bugstalker::debugger::variable::{SupportedScalar, VariableIR};
-use bugstalker::debugger::{Debugger, DebuggerBuilder};
+use bugstalker::debugger::{Debugger, DebuggerBuilder, TargetPlace};
 use bugstalker::ui::command::parser::expression;
 use chumsky::Parser;
 use serial_test::serial;
@@ -141,6 +144,157 @@ fn test_step_over() {
     assert_no_proc!(debugee_pid);
 }
 
+#[test]
+#[serial]
+fn test_run_until() {
+    let process = prepare_debugee_process(HW_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_fn("main").unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(5));
+
+    debugger
+        .run_until(TargetPlace::Line("hello_world.rs".to_string(), 9))
+        .unwrap();
+    assert_eq!(info.line.take(), Some(9));
+
+    debugger
+        .run_until(TargetPlace::Function("myprint".to_string()))
+        .unwrap();
+    assert_eq!(info.line.take(), Some(15));
+
+    // temporary breakpoints are removed, so program runs until the end
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_step_into_target() {
+    let process = prepare_debugee_process(CALC_APP, &["1", "2", "3", "--description", "result"]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("main.rs", 15).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(15));
+
+    // `print(s, &args[5])` - index operator is called before `print`
+    let targets = debugger.step_targets().unwrap();
+    assert!(targets.len() > 1);
+    assert!(targets
+        .iter()
+        .any(|target| target.name.as_deref() == Some("calc::print")));
+
+    debugger.step_into_target("print").unwrap();
+    assert_eq!(info.line.take(), Some(30));
+
+    assert!(debugger.step_into_target("unknown_fn").is_err());
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_step_into_skipped_function() {
+    let process = prepare_debugee_process(CALC_APP, &["1", "2", "3", "--description", "result"]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("main.rs", 25).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(25));
+
+    // `sum2` is stepped over transparently
+    let num = debugger.add_skip_rule(SkipRule::function("::sum2$").unwrap());
+    debugger.step_into().unwrap();
+    assert_eq!(info.line.take(), Some(26));
+
+    debugger.remove_skip_rule(num).unwrap();
+    assert!(debugger.skip_rules().is_empty());
+    debugger.step_into().unwrap();
+    assert_eq!(info.line.take(), Some(21));
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_jump() {
+    let process = prepare_debugee_process(HW_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_fn("main").unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(5));
+
+    // skip the first print and sleep
+    debugger
+        .jump(TargetPlace::Line("hello_world.rs".to_string(), 9), false)
+        .unwrap();
+    assert_eq!(info.line.take(), Some(9));
+
+    // cross-function jump is refused without force
+    assert!(debugger
+        .jump(TargetPlace::Line("hello_world.rs".to_string(), 15), false)
+        .is_err());
+
+    debugger.step_over().unwrap();
+    assert_eq!(info.line.take(), Some(10));
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_step_over_recursion() {
+    let process = prepare_debugee_process(RECURSION_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_fn("fibonacci").unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(2));
+    debugger.remove_breakpoint_at_fn("fibonacci").unwrap();
+
+    debugger.step_over().unwrap();
+    assert_eq!(info.line.take(), Some(5));
+    // recursive calls don't stop a step
+    debugger.step_over().unwrap();
+    assert_eq!(info.line.take(), Some(6));
+
+    let get_v_expr = expression::parser().parse("v").unwrap();
+    let v_arg = debugger.read_argument(get_v_expr).unwrap().pop().unwrap();
+    let VariableIR::Scalar(scalar) = v_arg else {
+        panic!("not a scalar");
+    };
+    assert_eq!(scalar.value, Some(SupportedScalar::U64(19)));
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
 #[test]
 #[serial]
 fn test_step_over_inline_code() {
@@ -184,3 +338,213 @@ fn test_step_over_on_fn_decl() {
     debugger.continue_debugee().unwrap();
     assert_no_proc!(debugee_pid);
 }
+
+#[test]
+#[serial]
+fn test_call_function() {
+    let process = prepare_debugee_process(CALC_APP, &["1", "2", "3", "--description", "result"]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("main.rs", 10).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(10));
+    let pc = debugger.exploration_ctx().location().pc;
+
+    let result = debugger
+        .call_function("calc::sum2", &[Literal::Int(-5), Literal::Int(2)])
+        .unwrap();
+    let Some(VariableIR::Scalar(scalar)) = result else {
+        panic!("not a scalar");
+    };
+    assert_eq!(scalar.value, Some(SupportedScalar::I64(-3)));
+
+    let result = debugger
+        .call_function("float::sum2", &[Literal::Float(1.5), Literal::Float(2.0)])
+        .unwrap();
+    let Some(VariableIR::Scalar(scalar)) = result else {
+        panic!("not a scalar");
+    };
+    assert_eq!(scalar.value, Some(SupportedScalar::F64(3.5)));
+
+    assert!(debugger
+        .call_function("float::sum2", &[Literal::Float(1.5)])
+        .is_err());
+
+    // thread state is restored after a call
+    assert_eq!(debugger.exploration_ctx().location().pc, pc);
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_force_return() {
+    let process = prepare_debugee_process(CALC_APP, &["1", "2", "3", "--description", "result"]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("main.rs", 25).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(25));
+
+    // return from `sum3` without a sum calculation
+    debugger.force_return(Some(Literal::Int(100))).unwrap();
+    assert_eq!(info.line.take(), Some(10));
+
+    debugger.step_over().unwrap();
+    assert_eq!(info.line.take(), Some(15));
+    let vars = debugger.read_local_variables().unwrap();
+    let s = vars.iter().find(|var| var.name() == "s").unwrap();
+    let VariableIR::Scalar(scalar) = s else {
+        panic!("not a scalar");
+    };
+    assert_eq!(scalar.value, Some(SupportedScalar::I64(100)));
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_reverse_execution() {
+    let process = prepare_debugee_process(CALC_APP, &["1", "2", "3", "--description", "result"]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    fn assert_arg(debugger: &Debugger, expected: i64) {
+        let get_a_expr = expression::parser().parse("a").unwrap();
+        let a_arg = debugger.read_argument(get_a_expr).unwrap().pop().unwrap();
+        let VariableIR::Scalar(scalar) = a_arg else {
+            panic!("not a scalar");
+        };
+        assert_eq!(scalar.value, Some(SupportedScalar::I64(expected)));
+    }
+
+    debugger.set_breakpoint_at_line("main.rs", 10).unwrap();
+    debugger.set_breakpoint_at_line("main.rs", 21).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(10));
+    assert!(debugger.reverse_step().is_err());
+    debugger.start_recording().unwrap();
+    assert!(debugger.reverse_step().is_err());
+
+    debugger.step_into().unwrap();
+    assert_eq!(info.line.take(), Some(25));
+    debugger.reverse_step().unwrap();
+    assert_eq!(info.line.take(), Some(10));
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(21));
+    assert_arg(&debugger, 1);
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(21));
+    assert_arg(&debugger, 3);
+
+    debugger.reverse_continue().unwrap();
+    assert_eq!(info.line.take(), Some(21));
+    assert_arg(&debugger, 1);
+    debugger.reverse_continue().unwrap();
+    assert_eq!(info.line.take(), Some(10));
+    assert_eq!(debugger.record_info().unwrap().snapshots.len(), 1);
+
+    debugger.stop_recording();
+    debugger.continue_debugee().unwrap();
+    debugger.continue_debugee().unwrap();
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_stepi_with_asm() {
+    let process = prepare_debugee_process(HW_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger
+        .set_breakpoint_at_line("hello_world.rs", 15)
+        .unwrap();
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(15));
+
+    let asm = debugger.stepi_with_asm(2).unwrap().unwrap();
+    assert_eq!(asm.name, Some("hello_world::myprint".to_string()));
+    assert!(asm.instructions.len() <= 5);
+    let pc_idx = asm.instructions.iter().position(|i| i.is_pc).unwrap();
+    assert_eq!(pc_idx, 2);
+    assert_eq!(
+        asm.instructions[pc_idx].address,
+        debugger.exploration_ctx().location().pc
+    );
+    // breakpoint is left behind
+    assert!(asm.instructions[..pc_idx]
+        .iter()
+        .any(|i| i.breakpoint == Some(1)));
+
+    debugger.continue_debugee().unwrap();
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_checkpoint_restart() {
+    let process = prepare_debugee_process(CALC_APP, &["1", "2", "3", "--description", "result"]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    fn assert_arg(debugger: &Debugger, expected: i64) {
+        let get_a_expr = expression::parser().parse("a").unwrap();
+        let a_arg = debugger.read_argument(get_a_expr).unwrap().pop().unwrap();
+        let VariableIR::Scalar(scalar) = a_arg else {
+            panic!("not a scalar");
+        };
+        assert_eq!(scalar.value, Some(SupportedScalar::I64(expected)));
+    }
+
+    assert!(debugger.checkpoint().is_err());
+    debugger.set_breakpoint_at_line("main.rs", 21).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(21));
+    assert_arg(&debugger, 1);
+    let checkpoint = debugger.checkpoint().unwrap();
+    assert_eq!(checkpoint.pc, debugger.exploration_ctx().location().pc);
+    assert_eq!(debugger.checkpoints().len(), 1);
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(21));
+    assert_arg(&debugger, 3);
+
+    // checkpoint may be used many times
+    for _ in 0..2 {
+        debugger.restart(checkpoint.id).unwrap();
+        assert_eq!(info.line.take(), Some(21));
+        assert_arg(&debugger, 1);
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(21));
+        assert_arg(&debugger, 3);
+    }
+    assert_no_proc!(debugee_pid);
+
+    assert!(debugger.restart(checkpoint.id + 1).is_err());
+    debugger.delete_checkpoint(checkpoint.id).unwrap();
+    assert!(debugger.checkpoints().is_empty());
+
+    debugger.continue_debugee().unwrap();
+}
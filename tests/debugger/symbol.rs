@@ -1,4 +1,4 @@
-use crate::common::TestHooks;
+use crate::common::{DebugeeRunInfo, TestHooks};
 use crate::prepare_debugee_process;
 use crate::{assert_no_proc, HW_APP};
 use bugstalker::debugger::DebuggerBuilder;
@@ -20,3 +20,72 @@ fn test_symbol() {
     debugger.start_debugee().unwrap();
     assert_no_proc!(debugee_pid);
 }
+
+#[test]
+#[serial]
+fn test_search_functions_and_symbols() {
+    let process = prepare_debugee_process(HW_APP, &[]);
+    let debugee_pid = process.pid();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::default());
+    let mut debugger = builder.build(process).unwrap();
+
+    let functions = debugger
+        .find_functions_info("^hello_world::myprint$")
+        .unwrap();
+    assert_eq!(functions.len(), 1);
+    let (file, line) = functions[0].decl.clone().unwrap();
+    assert!(file.ends_with("hello_world.rs"));
+    assert_eq!(line, 14);
+    assert!(!functions[0].ranges.is_empty());
+    assert!(functions[0].ranges[0].start < functions[0].ranges[0].end);
+
+    let symbols = debugger.search_symbols("hello_world::myprint").unwrap();
+    let myprint = symbols.iter().find(|s| s.kind == SymbolKind::Text).unwrap();
+    assert_eq!(myprint.range.start, functions[0].ranges[0].start);
+    assert!(myprint.file.as_ref().unwrap().ends_with("hello_world.rs"));
+
+    assert!(debugger.find_functions_info("(").is_err());
+
+    debugger.start_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_completion() {
+    let process = prepare_debugee_process(HW_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    let functions = debugger.complete_function("mypr").unwrap();
+    assert_eq!(functions, vec!["hello_world::myprint".to_string()]);
+    let functions = debugger.complete_function("hello_world::my").unwrap();
+    assert_eq!(functions, vec!["hello_world::myprint".to_string()]);
+
+    assert!(debugger
+        .complete_file("hello_")
+        .contains(&"hello_world.rs".to_string()));
+    assert_eq!(
+        debugger.complete_register("r1"),
+        vec!["r10", "r11", "r12", "r13", "r14", "r15"]
+    );
+    assert!(debugger.complete_variable("").is_err());
+
+    debugger
+        .set_breakpoint_at_line("hello_world.rs", 15)
+        .unwrap();
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(15));
+    assert_eq!(
+        debugger.complete_variable("s").unwrap(),
+        vec!["s".to_string()]
+    );
+    assert!(debugger.complete_variable("x").unwrap().is_empty());
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(15));
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
@@ -2,9 +2,10 @@ use crate::common::TestHooks;
 use crate::common::{rust_version, DebugeeRunInfo};
 use crate::VARS_APP;
 use crate::{assert_no_proc, prepare_debugee_process};
-use bugstalker::debugger::variable::render::RenderRepr;
+use bugstalker::debugger::variable::pretty::{PrettyPrinter, PrettyPrinterContext, PrettyValue};
+use bugstalker::debugger::variable::render::{RenderRepr, ValueLayout};
 use bugstalker::debugger::variable::select::{Literal, LiteralOrWildcard, VariableSelector, DQE};
-use bugstalker::debugger::variable::{select, VariableIR};
+use bugstalker::debugger::variable::{select, ParseLimits, StructVariable, VariableIR};
 use bugstalker::debugger::{variable, Debugger, DebuggerBuilder};
 use bugstalker::ui::command::parser::expression;
 use bugstalker::{debugger, version_switch};
@@ -12,6 +13,8 @@ use chumsky::Parser;
 use debugger::variable::SupportedScalar;
 use serial_test::serial;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 fn assert_scalar(
     var: &VariableIR,
@@ -59,6 +62,14 @@ fn assert_array(
     }
 }
 
+fn assert_lazy(var: &VariableIR, exp_name: &str, exp_type: &str) {
+    let VariableIR::Lazy(_) = var else {
+        panic!("not a lazy variable");
+    };
+    assert_eq!(var.name(), exp_name);
+    assert_eq!(var.r#type(), exp_type);
+}
+
 fn assert_c_enum(var: &VariableIR, exp_name: &str, exp_type: &str, exp_value: Option<String>) {
     let VariableIR::CEnum(c_enum) = var else {
         panic!("not a c_enum");
@@ -325,6 +336,24 @@ fn assert_arc(var: &VariableIR, exp_name: &str, exp_type: &str) {
     assert_eq!(var.r#type(), exp_type);
 }
 
+fn assert_ref_counts(var: &VariableIR, exp_strong: usize, exp_weak: usize) {
+    let VariableIR::Specialized(
+        variable::SpecializedVariableIR::Rc {
+            counts: Some(counts),
+            ..
+        }
+        | variable::SpecializedVariableIR::Arc {
+            counts: Some(counts),
+            ..
+        },
+    ) = var
+    else {
+        panic!("no reference counts");
+    };
+    assert_eq!(counts.strong, exp_strong);
+    assert_eq!(counts.weak, exp_weak);
+}
+
 fn assert_uuid(var: &VariableIR, exp_name: &str, exp_type: &str) {
     let VariableIR::Specialized(variable::SpecializedVariableIR::Uuid { .. }) = var else {
         panic!("not an uuid");
@@ -333,6 +362,144 @@ fn assert_uuid(var: &VariableIR, exp_name: &str, exp_type: &str) {
     assert_eq!(var.r#type(), exp_type);
 }
 
+fn assert_atomic(
+    var: &VariableIR,
+    exp_name: &str,
+    exp_type: &str,
+    with_value: impl FnOnce(&VariableIR),
+) {
+    let VariableIR::Specialized(variable::SpecializedVariableIR::Atomic {
+        value: Some(value), ..
+    }) = var
+    else {
+        panic!("not an atomic");
+    };
+    assert_eq!(var.name(), exp_name);
+    assert_eq!(var.r#type(), exp_type);
+    with_value(value);
+}
+
+fn assert_lock(
+    var: &VariableIR,
+    exp_name: &str,
+    exp_type: &str,
+    with_state: impl Fn(usize, &VariableIR),
+) {
+    let value = match var {
+        VariableIR::Specialized(variable::SpecializedVariableIR::Mutex {
+            value: Some(value),
+            ..
+        })
+        | VariableIR::Specialized(variable::SpecializedVariableIR::RwLock {
+            value: Some(value),
+            ..
+        }) => value,
+        _ => panic!("not a lock"),
+    };
+    assert_eq!(var.name(), exp_name);
+    assert_eq!(var.r#type(), exp_type);
+    let VariableIR::Struct(state) = value.as_ref() else {
+        panic!("not a struct")
+    };
+    for (i, member) in state.members.iter().enumerate() {
+        with_state(i, member);
+    }
+}
+
+fn assert_option(
+    var: &VariableIR,
+    exp_name: &str,
+    exp_type: &str,
+    with_payload: impl FnOnce(Option<&VariableIR>),
+) {
+    let VariableIR::Specialized(variable::SpecializedVariableIR::Option {
+        value: Some(value), ..
+    }) = var
+    else {
+        panic!("not an option");
+    };
+    assert_eq!(var.name(), exp_name);
+    assert_eq!(var.r#type(), exp_type);
+    with_payload(value.as_deref());
+}
+
+fn assert_result(
+    var: &VariableIR,
+    exp_name: &str,
+    exp_type: &str,
+    with_payload: impl FnOnce(Result<&VariableIR, &VariableIR>),
+) {
+    let VariableIR::Specialized(variable::SpecializedVariableIR::Result {
+        value: Some(value), ..
+    }) = var
+    else {
+        panic!("not a result");
+    };
+    assert_eq!(var.name(), exp_name);
+    assert_eq!(var.r#type(), exp_type);
+    with_payload(
+        value
+            .as_ref()
+            .map(|ok| ok.as_ref())
+            .map_err(|err| err.as_ref()),
+    );
+}
+
+fn assert_duration(var: &VariableIR, exp_name: &str, exp_value: Duration) {
+    let VariableIR::Specialized(variable::SpecializedVariableIR::Duration {
+        value: Some(value),
+        ..
+    }) = var
+    else {
+        panic!("not a duration");
+    };
+    assert_eq!(var.name(), exp_name);
+    assert_eq!(var.r#type(), "Duration");
+    assert_eq!(*value, exp_value);
+}
+
+fn assert_ffi_string(var: &VariableIR, exp_name: &str, exp_type: &str, exp_value: &str) {
+    let (VariableIR::Specialized(variable::SpecializedVariableIR::CString {
+        string: Some(string),
+        ..
+    })
+    | VariableIR::Specialized(variable::SpecializedVariableIR::OsString {
+        string: Some(string),
+        ..
+    })) = var
+    else {
+        panic!("not a ffi string");
+    };
+    assert_eq!(var.name(), exp_name);
+    assert_eq!(var.r#type(), exp_type);
+    assert_eq!(string.value, exp_value);
+}
+
+fn assert_c_str_pointer(var: &VariableIR, exp_name: &str, exp_value: Option<&str>) {
+    let VariableIR::Specialized(variable::SpecializedVariableIR::CStrPointer { string, .. }) = var
+    else {
+        panic!("not a c string pointer");
+    };
+    assert_eq!(var.name(), exp_name);
+    assert_eq!(var.r#type(), "*const i8");
+    assert_eq!(string.as_ref().map(|s| s.value.as_str()), exp_value);
+}
+
+fn assert_system_time(var: &VariableIR, exp_name: &str, exp_value: &str) {
+    let VariableIR::Specialized(variable::SpecializedVariableIR::SystemTime {
+        value: Some(_), ..
+    }) = var
+    else {
+        panic!("not a system time");
+    };
+    assert_eq!(var.name(), exp_name);
+    assert_eq!(var.r#type(), "SystemTime");
+    let Some(ValueLayout::PreRendered(value)) = var.value() else {
+        panic!("system time must be pre-rendered");
+    };
+    assert_eq!(value, exp_value);
+}
+
 #[test]
 #[serial]
 fn test_read_scalar_variables() {
@@ -391,6 +558,121 @@ fn test_read_scalar_variables() {
     assert_no_proc!(debugee_pid);
 }
 
+#[test]
+#[serial]
+fn test_evaluate_expressions() {
+    let process = prepare_debugee_process(VARS_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("vars.rs", 30).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(30));
+
+    let eval = |debugger: &Debugger, expr: &str| {
+        let expr = expression::expr_parser().parse(expr).into_result().unwrap();
+        debugger.evaluate_expression(&expr).unwrap()
+    };
+
+    let var = eval(&debugger, "int32 + uint8 * 2");
+    assert_scalar(
+        &var,
+        "int32 + uint8 * 2",
+        "i64",
+        Some(SupportedScalar::I64(4)),
+    );
+    let var = eval(&debugger, "(u8)int16");
+    assert_scalar(&var, "(u8)int16", "u8", Some(SupportedScalar::U8(255)));
+    let var = eval(&debugger, "f64 * 2");
+    assert_scalar(&var, "f64 * 2", "f64", Some(SupportedScalar::F64(2.4)));
+    let var = eval(&debugger, "int64 < 0 && boolean_true");
+    assert_scalar(
+        &var,
+        "int64 < 0 && boolean_true",
+        "bool",
+        Some(SupportedScalar::Bool(true)),
+    );
+    let var = eval(&debugger, "*&int32");
+    assert_scalar(&var, "*&int32", "i32", Some(SupportedScalar::I32(2)));
+    let var = eval(&debugger, "uint64");
+    assert_scalar(&var, "uint64", "u64", Some(SupportedScalar::U64(4)));
+
+    let expr = expression::expr_parser()
+        .parse("int32 / (uint8 - 1)")
+        .into_result()
+        .unwrap();
+    assert!(debugger.evaluate_expression(&expr).is_err());
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_write_variables() {
+    let process = prepare_debugee_process(VARS_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("vars.rs", 30).unwrap();
+    debugger.set_breakpoint_at_line("vars.rs", 93).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(30));
+
+    let var = debugger
+        .write_variable("int32", Literal::Int(-100))
+        .unwrap();
+    assert_scalar(&var, "int32", "i32", Some(SupportedScalar::I32(-100)));
+    let var = debugger
+        .write_variable("uint64", Literal::Int(1 << 40))
+        .unwrap();
+    assert_scalar(&var, "uint64", "u64", Some(SupportedScalar::U64(1 << 40)));
+    let var = debugger.write_variable("f64", Literal::Float(2.5)).unwrap();
+    assert_scalar(&var, "f64", "f64", Some(SupportedScalar::F64(2.5)));
+    let var = debugger
+        .write_variable("boolean_true", Literal::Bool(false))
+        .unwrap();
+    assert_scalar(
+        &var,
+        "boolean_true",
+        "bool",
+        Some(SupportedScalar::Bool(false)),
+    );
+    let var = debugger
+        .write_variable("char_ascii", Literal::String("z".to_string()))
+        .unwrap();
+    assert_scalar(&var, "char_ascii", "char", Some(SupportedScalar::Char('z')));
+
+    // value out of type range or with incompatible type
+    assert!(debugger.write_variable("uint8", Literal::Int(256)).is_err());
+    assert!(debugger
+        .write_variable("int8", Literal::Bool(true))
+        .is_err());
+
+    // neighbours are not changed
+    let vars = debugger.read_local_variables().unwrap();
+    assert_scalar(&vars[1], "int16", "i16", Some(SupportedScalar::I16(-1)));
+    assert_scalar(&vars[2], "int32", "i32", Some(SupportedScalar::I32(-100)));
+    assert_scalar(&vars[3], "int64", "i64", Some(SupportedScalar::I64(-2)));
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(93));
+
+    let var = debugger
+        .write_variable("enum_1", Literal::EnumVariant("EnumA::A".to_string(), None))
+        .unwrap();
+    assert_c_enum(&var, "enum_1", "EnumA", Some("A".to_string()));
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
 #[test]
 #[serial]
 fn test_read_scalar_variables_at_place() {
@@ -527,6 +809,97 @@ fn test_read_array() {
     assert_no_proc!(debugee_pid);
 }
 
+#[test]
+#[serial]
+fn test_read_variables_with_limits() {
+    let process = prepare_debugee_process(VARS_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("vars.rs", 53).unwrap();
+    debugger.set_breakpoint_at_line("vars.rs", 61).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(53));
+
+    let foo2 = DQE::Variable(VariableSelector::Name {
+        var_name: "foo2".to_string(),
+        only_local: true,
+    });
+    let limits = ParseLimits {
+        max_depth: Some(1),
+        max_children: None,
+    };
+    let vars = debugger
+        .read_variable_with_limits(foo2.clone(), limits)
+        .unwrap();
+    assert_struct(&vars[0], "foo2", "Foo2", |i, member| match i {
+        0 => assert_lazy(member, "foo", "Foo"),
+        1 => assert_scalar(
+            member,
+            "additional",
+            "bool",
+            Some(SupportedScalar::Bool(true)),
+        ),
+        _ => panic!("2 members expected"),
+    });
+
+    let vars = debugger
+        .read_variable_with_limits(DQE::Field(foo2.boxed(), "foo".to_string()), limits)
+        .unwrap();
+    assert_struct(&vars[0], "foo", "Foo", |i, member| match i {
+        0 => assert_scalar(member, "bar", "i32", Some(SupportedScalar::I32(100))),
+        1 => assert_scalar(member, "baz", "char", Some(SupportedScalar::Char('9'))),
+        _ => panic!("2 members expected"),
+    });
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(61));
+
+    let arr_2 = DQE::Variable(VariableSelector::Name {
+        var_name: "arr_2".to_string(),
+        only_local: true,
+    });
+    let limits = ParseLimits {
+        max_depth: None,
+        max_children: Some(2),
+    };
+    let vars = debugger
+        .read_variable_with_limits(arr_2.clone(), limits)
+        .unwrap();
+    assert_array(&vars[0], "arr_2", "[[i32]]", |i, item| match i {
+        0 => assert_array(item, "0", "[i32]", |i, item| match i {
+            0 => assert_scalar(item, "0", "i32", Some(SupportedScalar::I32(1))),
+            1 => assert_scalar(item, "1", "i32", Some(SupportedScalar::I32(-1))),
+            2..=4 => assert_lazy(item, &i.to_string(), "i32"),
+            _ => panic!("5 items expected"),
+        }),
+        1 => assert_array(item, "1", "[i32]", |i, item| match i {
+            0 => assert_scalar(item, "0", "i32", Some(SupportedScalar::I32(0))),
+            1 => assert_scalar(item, "1", "i32", Some(SupportedScalar::I32(1))),
+            2..=4 => assert_lazy(item, &i.to_string(), "i32"),
+            _ => panic!("5 items expected"),
+        }),
+        2 => assert_lazy(item, "2", "[i32]"),
+        _ => panic!("3 items expected"),
+    });
+
+    let vars = debugger
+        .read_variable_with_limits(DQE::Index(arr_2.boxed(), Literal::Int(2)), limits)
+        .unwrap();
+    assert_array(&vars[0], "2", "[i32]", |i, item| match i {
+        0 => assert_scalar(item, "0", "i32", Some(SupportedScalar::I32(0))),
+        1 => assert_scalar(item, "1", "i32", Some(SupportedScalar::I32(-1))),
+        2..=4 => assert_lazy(item, &i.to_string(), "i32"),
+        _ => panic!("5 items expected"),
+    });
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
 #[test]
 #[serial]
 fn test_read_enum() {
@@ -2134,29 +2507,18 @@ fn test_read_atomic() {
     assert_eq!(info.line.take(), Some(441));
 
     let vars = debugger.read_local_variables().unwrap();
-    assert_struct(&vars[0], "int32_atomic", "AtomicI32", |i, member| match i {
-        0 => assert_struct(member, "v", "UnsafeCell<i32>", |i, member| match i {
-            0 => assert_scalar(member, "value", "i32", Some(SupportedScalar::I32(1))),
-            _ => panic!("1 members expected"),
-        }),
-        _ => panic!("1 members expected"),
+    assert_atomic(&vars[0], "int32_atomic", "AtomicI32", |value| {
+        assert_scalar(value, "value", "i32", Some(SupportedScalar::I32(1)))
     });
 
-    assert_struct(
-        &vars[2],
-        "int32_atomic_ptr",
-        "AtomicPtr<i32>",
-        |i, member| match i {
-            0 => assert_struct(member, "p", "UnsafeCell<*mut i32>", |i, member| match i {
-                0 => assert_pointer(member, "value", "*mut i32"),
-                _ => panic!("1 members expected"),
-            }),
-            _ => panic!("1 members expected"),
-        },
-    );
+    assert_atomic(&vars[2], "int32_atomic_ptr", "AtomicPtr<i32>", |value| {
+        assert_pointer(value, "value", "*mut i32")
+    });
 
     let deref = read_single_var(&debugger, "*int32_atomic_ptr.p.value");
     assert_scalar(&deref, "*value", "i32", Some(SupportedScalar::I32(2)));
+    let deref = read_single_var(&debugger, "*int32_atomic_ptr");
+    assert_scalar(&deref, "*value", "i32", Some(SupportedScalar::I32(2)));
 
     debugger.continue_debugee().unwrap();
     assert_no_proc!(debugee_pid);
@@ -2218,6 +2580,7 @@ fn test_shared_ptr() {
 
     let vars = debugger.read_local_variables().unwrap();
     assert_rc(&vars[0], "rc0", "Rc<i32, alloc::alloc::Global>");
+    assert_ref_counts(&vars[0], 2, 1);
     let deref = read_single_var(&debugger, "*rc0");
     assert_struct(&deref, "*rc0", "RcBox<i32>", |i, member| match i {
         0 => assert_cell(member, "strong", "Cell<usize>", |inner| {
@@ -2230,6 +2593,7 @@ fn test_shared_ptr() {
         _ => panic!("3 members expected"),
     });
     assert_rc(&vars[1], "rc1", "Rc<i32, alloc::alloc::Global>");
+    assert_ref_counts(&vars[1], 2, 1);
     let deref = read_single_var(&debugger, "*rc1");
     assert_struct(&deref, "*rc1", "RcBox<i32>", |i, member| match i {
         0 => assert_cell(member, "strong", "Cell<usize>", |inner| {
@@ -2242,6 +2606,7 @@ fn test_shared_ptr() {
         _ => panic!("3 members expected"),
     });
     assert_rc(&vars[2], "weak_rc2", "Weak<i32, alloc::alloc::Global>");
+    assert_ref_counts(&vars[2], 2, 1);
     let deref = read_single_var(&debugger, "*weak_rc2");
     assert_struct(&deref, "*weak_rc2", "RcBox<i32>", |i, member| match i {
         0 => assert_cell(member, "strong", "Cell<usize>", |inner| {
@@ -2255,55 +2620,40 @@ fn test_shared_ptr() {
     });
 
     assert_arc(&vars[3], "arc0", "Arc<i32, alloc::alloc::Global>");
+    assert_ref_counts(&vars[3], 2, 1);
     let deref = read_single_var(&debugger, "*arc0");
     assert_struct(&deref, "*arc0", "ArcInner<i32>", |i, member| match i {
-        0 => assert_struct(member, "strong", "AtomicUsize", |i, member| match i {
-            0 => assert_struct(member, "v", "UnsafeCell<usize>", |_, member| {
-                assert_scalar(member, "value", "usize", Some(SupportedScalar::Usize(2)))
-            }),
-            _ => panic!("1 member expected"),
+        0 => assert_atomic(member, "strong", "AtomicUsize", |value| {
+            assert_scalar(value, "value", "usize", Some(SupportedScalar::Usize(2)))
         }),
-        1 => assert_struct(member, "weak", "AtomicUsize", |i, member| match i {
-            0 => assert_struct(member, "v", "UnsafeCell<usize>", |_, member| {
-                assert_scalar(member, "value", "usize", Some(SupportedScalar::Usize(2)))
-            }),
-            _ => panic!("1 member expected"),
+        1 => assert_atomic(member, "weak", "AtomicUsize", |value| {
+            assert_scalar(value, "value", "usize", Some(SupportedScalar::Usize(2)))
         }),
         2 => assert_scalar(member, "data", "i32", Some(SupportedScalar::I32(2))),
         _ => panic!("3 members expected"),
     });
     assert_arc(&vars[4], "arc1", "Arc<i32, alloc::alloc::Global>");
+    assert_ref_counts(&vars[4], 2, 1);
     let deref = read_single_var(&debugger, "*arc1");
     assert_struct(&deref, "*arc1", "ArcInner<i32>", |i, member| match i {
-        0 => assert_struct(member, "strong", "AtomicUsize", |i, member| match i {
-            0 => assert_struct(member, "v", "UnsafeCell<usize>", |_, member| {
-                assert_scalar(member, "value", "usize", Some(SupportedScalar::Usize(2)))
-            }),
-            _ => panic!("1 member expected"),
+        0 => assert_atomic(member, "strong", "AtomicUsize", |value| {
+            assert_scalar(value, "value", "usize", Some(SupportedScalar::Usize(2)))
         }),
-        1 => assert_struct(member, "weak", "AtomicUsize", |i, member| match i {
-            0 => assert_struct(member, "v", "UnsafeCell<usize>", |_, member| {
-                assert_scalar(member, "value", "usize", Some(SupportedScalar::Usize(2)))
-            }),
-            _ => panic!("1 member expected"),
+        1 => assert_atomic(member, "weak", "AtomicUsize", |value| {
+            assert_scalar(value, "value", "usize", Some(SupportedScalar::Usize(2)))
         }),
         2 => assert_scalar(member, "data", "i32", Some(SupportedScalar::I32(2))),
         _ => panic!("3 members expected"),
     });
     assert_arc(&vars[5], "weak_arc2", "Weak<i32, alloc::alloc::Global>");
+    assert_ref_counts(&vars[5], 2, 1);
     let deref = read_single_var(&debugger, "*weak_arc2");
     assert_struct(&deref, "*weak_arc2", "ArcInner<i32>", |i, member| match i {
-        0 => assert_struct(member, "strong", "AtomicUsize", |i, member| match i {
-            0 => assert_struct(member, "v", "UnsafeCell<usize>", |_, member| {
-                assert_scalar(member, "value", "usize", Some(SupportedScalar::Usize(2)))
-            }),
-            _ => panic!("1 member expected"),
+        0 => assert_atomic(member, "strong", "AtomicUsize", |value| {
+            assert_scalar(value, "value", "usize", Some(SupportedScalar::Usize(2)))
         }),
-        1 => assert_struct(member, "weak", "AtomicUsize", |i, member| match i {
-            0 => assert_struct(member, "v", "UnsafeCell<usize>", |_, member| {
-                assert_scalar(member, "value", "usize", Some(SupportedScalar::Usize(2)))
-            }),
-            _ => panic!("1 member expected"),
+        1 => assert_atomic(member, "weak", "AtomicUsize", |value| {
+            assert_scalar(value, "value", "usize", Some(SupportedScalar::Usize(2)))
         }),
         2 => assert_scalar(member, "data", "i32", Some(SupportedScalar::I32(2))),
         _ => panic!("3 members expected"),
@@ -2384,11 +2734,13 @@ fn test_zst_types() {
         _ => panic!("1 member expected"),
     });
 
-    assert_rust_enum(&vars[5], "enum_zst", "Option<()>", |member| {
-        assert_struct(member, "Some", "Some", |i, member| match i {
-            0 => assert_scalar(member, "0", "()", Some(SupportedScalar::Empty())),
-            _ => panic!("1 member expected"),
-        })
+    assert_option(&vars[5], "enum_zst", "Option<()>", |payload| {
+        assert_scalar(
+            payload.unwrap(),
+            "Some",
+            "()",
+            Some(SupportedScalar::Empty()),
+        )
     });
 
     assert_vec_deque(
@@ -2693,3 +3045,260 @@ fn test_read_uuid() {
     debugger.continue_debugee().unwrap();
     assert_no_proc!(debugee_pid);
 }
+
+#[test]
+#[serial]
+fn test_read_option_result_time() {
+    let process = prepare_debugee_process(VARS_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("vars.rs", 567).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(567));
+
+    let vars = debugger.read_local_variables().unwrap();
+    assert_option(&vars[0], "opt_some", "Option<i32>", |payload| {
+        assert_scalar(
+            payload.unwrap(),
+            "Some",
+            "i32",
+            Some(SupportedScalar::I32(1)),
+        )
+    });
+    assert_option(&vars[1], "opt_none", "Option<i32>", |payload| {
+        assert!(payload.is_none())
+    });
+    assert_option(&vars[2], "opt_str", "Option<&str>", |payload| {
+        assert_str(payload.unwrap(), "Some", "abc")
+    });
+    assert_result(
+        &vars[3],
+        "res_ok",
+        "Result<u8, alloc::string::String>",
+        |payload| assert_scalar(payload.unwrap(), "Ok", "u8", Some(SupportedScalar::U8(2))),
+    );
+    assert_result(
+        &vars[4],
+        "res_err",
+        "Result<u8, alloc::string::String>",
+        |payload| assert_string(payload.unwrap_err(), "Err", "fail"),
+    );
+    assert_duration(&vars[5], "duration", Duration::from_millis(1500));
+    assert_system_time(&vars[6], "system_time", "2023-11-14T22:13:20Z");
+
+    // variant payload is available by a field access
+    let payload = read_single_var(&debugger, "opt_some.0");
+    assert_scalar(&payload, "0", "i32", Some(SupportedScalar::I32(1)));
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_read_locks() {
+    let process = prepare_debugee_process(VARS_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("vars.rs", 582).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(582));
+
+    let vars = debugger.read_local_variables().unwrap();
+    assert_lock(&vars[0], "mutex", "Mutex<i32>", |i, member| match i {
+        0 => assert_scalar(member, "locked", "bool", Some(SupportedScalar::Bool(true))),
+        1 => assert_scalar(
+            member,
+            "poisoned",
+            "bool",
+            Some(SupportedScalar::Bool(false)),
+        ),
+        2 => assert_scalar(member, "value", "i32", Some(SupportedScalar::I32(1))),
+        _ => panic!("3 members expected"),
+    });
+
+    assert_lock(
+        &vars[2],
+        "rwlock",
+        "RwLock<alloc::vec::Vec<i32, alloc::alloc::Global>>",
+        |i, member| match i {
+            0 => assert_scalar(member, "readers", "u32", Some(SupportedScalar::U32(2))),
+            1 => assert_scalar(
+                member,
+                "write_locked",
+                "bool",
+                Some(SupportedScalar::Bool(false)),
+            ),
+            2 => assert_scalar(
+                member,
+                "poisoned",
+                "bool",
+                Some(SupportedScalar::Bool(false)),
+            ),
+            3 => assert_vec(
+                member,
+                "value",
+                "Vec<i32, alloc::alloc::Global>",
+                2,
+                |buf| {
+                    assert_array(buf, "buf", "[i32]", |i, item| match i {
+                        0 => assert_scalar(item, "0", "i32", Some(SupportedScalar::I32(1))),
+                        1 => assert_scalar(item, "1", "i32", Some(SupportedScalar::I32(2))),
+                        _ => panic!("2 items expected"),
+                    })
+                },
+            ),
+            _ => panic!("4 members expected"),
+        },
+    );
+
+    assert_atomic(&vars[5], "bool_atomic", "AtomicBool", |value| {
+        assert_scalar(value, "value", "bool", Some(SupportedScalar::Bool(true)))
+    });
+    assert_atomic(&vars[6], "usize_atomic", "AtomicUsize", |value| {
+        assert_scalar(value, "value", "usize", Some(SupportedScalar::Usize(3)))
+    });
+
+    // locked value is available by a field access
+    let value = read_single_var(&debugger, "mutex.value");
+    assert_scalar(&value, "value", "i32", Some(SupportedScalar::I32(1)));
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+#[test]
+#[serial]
+fn test_read_ffi_strings() {
+    let process = prepare_debugee_process(VARS_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new().with_hooks(TestHooks::new(info.clone()));
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("vars.rs", 597).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(597));
+
+    let vars = debugger.read_local_variables().unwrap();
+    assert_ffi_string(&vars[0], "c_string", "CString", "c string");
+    let VariableIR::Specialized(variable::SpecializedVariableIR::CString {
+        string: Some(c_str),
+        ..
+    }) = &vars[1]
+    else {
+        panic!("not a &CStr");
+    };
+    assert_eq!(vars[1].name(), "c_str");
+    assert_eq!(c_str.value, "c string");
+    assert_c_str_pointer(&vars[2], "c_ptr", Some("c string"));
+    assert_c_str_pointer(&vars[3], "null_c_ptr", None);
+    assert_ffi_string(&vars[4], "os_string", "OsString", "os string");
+    assert_ffi_string(&vars[5], "path_buf", "PathBuf", "/tmp/file.txt");
+
+    // c string pointer is still a pointer
+    let deref = read_single_var(&debugger, "*c_ptr");
+    assert_scalar(
+        &deref,
+        "*c_ptr",
+        "i8",
+        Some(SupportedScalar::I8(b'c' as i8)),
+    );
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
+
+struct FooPrinter;
+
+impl PrettyPrinter for FooPrinter {
+    fn name(&self) -> &'static str {
+        "foo"
+    }
+
+    fn matches(&self, type_name: &str, _: &[String]) -> bool {
+        type_name == "Foo"
+    }
+
+    fn render(
+        &self,
+        _: &PrettyPrinterContext,
+        structure: StructVariable,
+    ) -> variable::SpecializedVariableIR {
+        let fields: Vec<_> = structure
+            .members
+            .iter()
+            .filter_map(|member| match member {
+                VariableIR::Scalar(scalar) => scalar.value.as_ref().map(ToString::to_string),
+                _ => None,
+            })
+            .collect();
+        variable::SpecializedVariableIR::Custom {
+            value: Some(PrettyValue::Text(format!("Foo({})", fields.join(", ")))),
+            original: structure,
+        }
+    }
+}
+
+fn assert_custom(var: &VariableIR, exp_name: &str, exp_type: &str, exp_text: &str) {
+    let VariableIR::Specialized(variable::SpecializedVariableIR::Custom {
+        value: Some(PrettyValue::Text(text)),
+        ..
+    }) = var
+    else {
+        panic!("not a custom variable");
+    };
+    assert_eq!(var.name(), exp_name);
+    assert_eq!(var.r#type(), exp_type);
+    assert_eq!(text, exp_text);
+}
+
+#[test]
+#[serial]
+fn test_pretty_printers() {
+    let process = prepare_debugee_process(VARS_APP, &[]);
+    let debugee_pid = process.pid();
+    let info = DebugeeRunInfo::default();
+    let builder = DebuggerBuilder::new()
+        .with_hooks(TestHooks::new(info.clone()))
+        .with_pretty_printers(vec![Arc::new(FooPrinter)]);
+    let mut debugger = builder.build(process).unwrap();
+
+    debugger.set_breakpoint_at_line("vars.rs", 53).unwrap();
+
+    debugger.start_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(53));
+
+    let vars = debugger.read_local_variables().unwrap();
+    assert_custom(&vars[3], "foo", "Foo", "Foo(100, 9)");
+    assert_struct(&vars[4], "foo2", "Foo2", |i, member| match i {
+        0 => assert_custom(member, "foo", "Foo", "Foo(100, 9)"),
+        1 => assert_scalar(
+            member,
+            "additional",
+            "bool",
+            Some(SupportedScalar::Bool(true)),
+        ),
+        _ => panic!("2 members expected"),
+    });
+
+    assert!(debugger.unregister_pretty_printer("foo"));
+    let vars = debugger.read_local_variables().unwrap();
+    assert_struct(&vars[3], "foo", "Foo", |i, member| match i {
+        0 => assert_scalar(member, "bar", "i32", Some(SupportedScalar::I32(100))),
+        1 => assert_scalar(member, "baz", "char", Some(SupportedScalar::Char('9'))),
+        _ => panic!("2 members expected"),
+    });
+
+    debugger.continue_debugee().unwrap();
+    assert_no_proc!(debugee_pid);
+}
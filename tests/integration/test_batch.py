@@ -0,0 +1,71 @@
+import json
+import os
+import tempfile
+import unittest
+import pexpect
+
+
+class BatchTestCase(unittest.TestCase):
+    def run_batch(self, commands, options=''):
+        with tempfile.NamedTemporaryFile('w', suffix='.bs', delete=False) as f:
+            f.write(commands)
+            path = f.name
+        try:
+            output, status = pexpect.run(
+                './target/debug/bs -t none --no-init --batch ' + options + ' -x ' + path + ' ./examples/target/debug/hello_world',
+                withexitstatus=True,
+            )
+        finally:
+            os.remove(path)
+        return output.decode(), status
+
+    def test_batch_commands(self):
+        """Execute commands from file and exit"""
+        output, status = self.run_batch(
+            '# stop at line\n'
+            'break hello_world.rs:15\n'
+            'run\n'
+            'continue\n'
+        )
+        self.assertEqual(status, 0)
+        self.assertIn('New breakpoint', output)
+        self.assertIn('Hit breakpoint 1', output)
+        self.assertIn('Hello, world!', output)
+
+    def test_batch_failed_command(self):
+        """Exit with non-zero code if command failed"""
+        output, status = self.run_batch(
+            'break hello_world.rs:15\n'
+            'var not_exists_command (\n'
+            'run\n'
+        )
+        self.assertNotEqual(status, 0)
+        self.assertNotIn('Hit breakpoint 1', output)
+
+    def test_batch_exit_code_by_error_category(self):
+        """Exit code depends on error category of failed command"""
+        _, status = self.run_batch('var not_exists_command (\n')
+        self.assertEqual(status, 2)
+
+    def test_batch_json_output(self):
+        """Command results and debugger events are printed as JSON objects"""
+        output, status = self.run_batch(
+            'break hello_world.rs:15\n'
+            'run\n'
+            'arg all\n',
+            options='--json',
+        )
+        self.assertEqual(status, 0)
+        objects = [json.loads(line) for line in output.splitlines() if line.startswith('{')]
+
+        brkpt = objects[0]
+        self.assertEqual(brkpt['action'], 'new breakpoint')
+        self.assertEqual(brkpt['line'], 15)
+
+        stop = next(o for o in objects if o.get('event') == 'breakpoint')
+        self.assertEqual(stop['number'], 1)
+        self.assertEqual(stop['line'], 15)
+
+        arg = objects[-1]
+        self.assertEqual(arg['name'], 's')
+        self.assertEqual(arg['type'], '&str')
@@ -1,4 +1,6 @@
+import os
 import re
+import tempfile
 import unittest
 import pexpect
 
@@ -48,6 +50,20 @@ class CommandTestCase(unittest.TestCase):
         self.debugger.sendline('continue')
         self.debugger.expect('bye!')
 
+    def test_repeat_last_command(self):
+        """Empty input repeats the last command"""
+        self.debugger.sendline('break hello_world.rs:5')
+        self.debugger.expect('New breakpoint')
+
+        self.debugger.sendline('run')
+        self.debugger.expect_exact('myprint("Hello, world!");')
+        self.debugger.sendline('next')
+        self.debugger.expect_exact('7     sleep(Duration::from_secs(1));')
+        self.debugger.sendline('')
+        self.debugger.expect_exact('9     myprint("bye!")')
+        self.debugger.sendline('')
+        self.debugger.expect_exact('10 }')
+
     def test_multiple_breakpoints_set(self):
         """Sets multiple breakpoints at line"""
         self.debugger.sendline('break hello_world.rs:5')
@@ -299,6 +315,58 @@ class CommandTestCase(unittest.TestCase):
         self.debugger.sendline('run')
         self.debugger.expect('bye!')
 
+    def test_breakpoint_disable(self):
+        """Disabled breakpoint doesn't stop a debugee"""
+        self.debugger.sendline('break main')
+        self.debugger.expect('New breakpoint')
+        self.debugger.sendline('break hello_world.rs:15')
+        self.debugger.expect('New breakpoint')
+
+        self.debugger.sendline('break disable 2')
+        self.debugger.expect_exact('[disabled]')
+
+        self.debugger.sendline('run')
+        self.debugger.expect_exact('Hit breakpoint 1')
+
+        self.debugger.sendline('break enable 2')
+        self.debugger.expect('Updated breakpoint')
+        self.debugger.sendline('continue')
+        self.debugger.expect_exact('Hit breakpoint 2')
+
+        self.debugger.sendline('break disable 2')
+        self.debugger.expect('Updated breakpoint')
+        self.debugger.sendline('continue')
+        self.debugger.expect('bye!')
+
+    def test_breakpoint_save_and_load(self):
+        """Export and import breakpoints as a gdb script"""
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            script = os.path.join(tmp_dir, 'brkpts.gdb')
+            self.debugger.sendline('break hello_world.rs:15')
+            self.debugger.expect('New breakpoint')
+            self.debugger.sendline('break disable 1')
+            self.debugger.expect_exact('[disabled]')
+            self.debugger.sendline('break save ' + script)
+            self.debugger.expect('Saved 1 breakpoints')
+
+            with open(script) as f:
+                content = f.read()
+            self.assertIn('hello_world.rs:15\n', content)
+            self.assertIn('disable $bpnum\n', content)
+
+            self.debugger.sendline('break remove 1')
+            self.debugger.expect('Removed breakpoint')
+            with open(script, 'a') as f:
+                f.write('set pagination off\nbreak main\n')
+            self.debugger.sendline('break load ' + script)
+            self.debugger.expect('New breakpoint')
+            self.debugger.expect_exact('[disabled]')
+            self.debugger.expect('New breakpoint')
+            self.debugger.expect_exact('Skip line 4: set pagination off')
+
+            self.debugger.sendline('run')
+            self.debugger.expect_exact('Hit breakpoint 3')
+
     def test_breakpoint_info(self):
         """View breakpoints list"""
         self.debugger.sendline('break hello_world.rs:9')
@@ -472,6 +540,44 @@ class CommandTestCase(unittest.TestCase):
         self.debugger.expect_exact('4 fn main() {')
         self.debugger.expect_exact('9     myprint("bye!")')
 
+    def test_session_logging(self):
+        """Write session transcript into a file"""
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            log_file = os.path.join(tmp_dir, 'session.log')
+            self.debugger.sendline('set logging on ' + log_file)
+            self.debugger.expect('Logging session into')
+
+            self.debugger.sendline('break hello_world.rs:15')
+            self.debugger.expect('New breakpoint')
+            self.debugger.sendline('run')
+            self.debugger.expect_exact('Hit breakpoint 1 at')
+            self.debugger.expect_exact('15     println!("{}", s)')
+
+            self.debugger.sendline('set logging off')
+            self.debugger.expect('stopped')
+
+            with open(log_file) as f:
+                transcript = f.read()
+            self.assertIn('(bs) break hello_world.rs:15', transcript)
+            self.assertIn('New breakpoint', transcript)
+            self.assertIn('(bs) run', transcript)
+            self.assertIn('Hit breakpoint 1 at', transcript)
+            self.assertIn('(bs) set logging off', transcript)
+
+    def test_coverage(self):
+        """Track line coverage of a file"""
+        self.debugger.sendline('coverage file hello_world.rs')
+        self.debugger.expect(r'Coverage tracking of \d+ new lines started')
+
+        self.debugger.sendline('run')
+        self.debugger.expect('bye!')
+
+        self.debugger.sendline('coverage info')
+        self.debugger.expect(r'hello_world.rs: \d+/\d+ lines')
+
+        self.debugger.sendline('coverage clear')
+        self.debugger.expect('Coverage data cleared')
+
     @staticmethod
     def test_breakpoint_at_rust_panic():
         """Set breakpoint to rust panic handler and catch panics"""
@@ -0,0 +1,87 @@
+import json
+import os
+import subprocess
+import unittest
+
+
+class DapTestCase(unittest.TestCase):
+    def setUp(self):
+        self.seq = 0
+        self.adapter = subprocess.Popen(
+            ['./target/debug/bs', 'dap'],
+            stdin=subprocess.PIPE,
+            stdout=subprocess.PIPE,
+        )
+
+    def tearDown(self):
+        self.adapter.kill()
+        self.adapter.wait()
+
+    def send(self, command, arguments=None):
+        self.seq += 1
+        content = json.dumps({
+            'seq': self.seq,
+            'type': 'request',
+            'command': command,
+            'arguments': arguments or {},
+        }).encode()
+        self.adapter.stdin.write(b'Content-Length: %d\r\n\r\n' % len(content) + content)
+        self.adapter.stdin.flush()
+        return self.seq
+
+    def read(self):
+        content_len = 0
+        while True:
+            header = self.adapter.stdout.readline().strip()
+            if not header:
+                break
+            if header.startswith(b'Content-Length:'):
+                content_len = int(header.split(b':')[1])
+        return json.loads(self.adapter.stdout.read(content_len))
+
+    def expect_event(self, event):
+        while True:
+            message = self.read()
+            if message['type'] == 'event' and message['event'] == event:
+                return message['body']
+
+    def request(self, command, arguments=None):
+        seq = self.send(command, arguments)
+        while True:
+            message = self.read()
+            if message['type'] == 'response' and message['request_seq'] == seq:
+                self.assertTrue(message['success'], message.get('message'))
+                return message['body']
+
+    def test_breakpoint_and_variables(self):
+        """Stop at breakpoint and inspect variables through DAP"""
+        self.request('initialize', {'adapterID': 'bugstalker'})
+        self.request('launch', {'program': os.path.abspath('./examples/target/debug/hello_world')})
+        self.expect_event('initialized')
+
+        body = self.request('setBreakpoints', {
+            'source': {'path': 'hello_world.rs'},
+            'breakpoints': [{'line': 15}],
+        })
+        self.assertTrue(body['breakpoints'][0]['verified'])
+
+        self.request('configurationDone')
+        stopped = self.expect_event('stopped')
+        self.assertEqual(stopped['reason'], 'breakpoint')
+
+        frames = self.request('stackTrace', {'threadId': stopped['threadId']})['stackFrames']
+        self.assertIn('myprint', frames[0]['name'])
+        self.assertEqual(frames[0]['line'], 15)
+
+        scopes = self.request('scopes', {'frameId': frames[0]['id']})['scopes']
+        arguments = next(s for s in scopes if s['name'] == 'Arguments')
+        variables = self.request('variables', {'variablesReference': arguments['variablesReference']})
+        s = next(v for v in variables['variables'] if v['name'] == 's')
+        self.assertIn('Hello, world!', s['value'])
+
+        self.request('continue', {'threadId': stopped['threadId']})
+        output = self.expect_event('output')
+        self.assertEqual(output['output'], 'Hello, world!\n')
+        self.assertEqual(self.expect_event('stopped')['reason'], 'breakpoint')
+
+        self.request('disconnect')
@@ -0,0 +1,69 @@
+import json
+import os
+import socket
+import tempfile
+import unittest
+import pexpect
+
+
+class RemoteTestCase(unittest.TestCase):
+    def setUp(self):
+        self.socket_path = os.path.join(tempfile.mkdtemp(), 'bs.sock')
+        debugger = pexpect.spawn(
+            './target/debug/bs -t none --remote ' + self.socket_path + ' ./examples/target/debug/hello_world')
+        debugger.expect('BugStalker greets')
+        self.debugger = debugger
+        self.seq = 0
+
+        self.client = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+        self.client.connect(self.socket_path)
+        self.responses = self.client.makefile('r')
+
+    def tearDown(self):
+        self.client.close()
+        self.debugger.close()
+
+    def call(self, method, params=None):
+        self.seq += 1
+        request = {'jsonrpc': '2.0', 'id': self.seq, 'method': method, 'params': params or {}}
+        self.client.sendall((json.dumps(request) + '\n').encode())
+        response = json.loads(self.responses.readline())
+        self.assertEqual(response['id'], self.seq)
+        return response
+
+    def test_remote_breakpoint_and_variables(self):
+        """Add breakpoint and read arguments through the remote control socket"""
+        breakpoints = self.call('break', {'location': 'hello_world.rs:15'})['result']
+        self.assertEqual(breakpoints[0]['action'], 'new breakpoint')
+        self.assertEqual(breakpoints[0]['line'], 15)
+
+        self.debugger.sendline('run')
+        self.debugger.expect_exact('Hit breakpoint 1')
+
+        args = self.call('arguments')['result']
+        s = next(arg for arg in args if arg['name'] == 's')
+        self.assertIn('Hello, world!', json.dumps(s))
+
+        threads = self.call('backtrace')['result']
+        self.assertIn('myprint', threads[0]['backtrace'][0]['function'])
+
+    def test_remote_errors(self):
+        """Remote control errors"""
+        error = self.call('unknown')['error']
+        self.assertEqual(error['code'], -32601)
+
+        error = self.call('variables')['error']
+        self.assertIn('category', error['data'])
+
+    def test_remote_command(self):
+        """Console command from a remote client"""
+        self.assertIsNone(self.call('command', {'command': 'break main'})['result'])
+        self.debugger.expect('New breakpoint')
+
+        self.debugger.sendline('run')
+        self.debugger.expect_exact('myprint("Hello, world!");')
+
+    def test_remote_command_capture(self):
+        """Console command output is returned to a remote client"""
+        result = self.call('command', {'command': 'break main', 'capture': True})['result']
+        self.assertIn('New breakpoint', result['output'])
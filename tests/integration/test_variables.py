@@ -1,3 +1,6 @@
+import json
+import os
+import tempfile
 import unittest
 import pexpect
 import re
@@ -129,6 +132,27 @@ class VariablesTestCase(unittest.TestCase):
         self.debugger.expect_exact('}')
         self.debugger.expect_exact('}')
 
+    def test_export_array(self):
+        """Export arrays into csv and json files"""
+        self.debugger.sendline('break vars.rs:61')
+        self.debugger.expect('New breakpoint')
+
+        self.debugger.sendline('run')
+        self.debugger.expect_exact('61     let nop: Option<u8> = None;')
+
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            csv_file = os.path.join(tmp_dir, 'arr.csv')
+            self.debugger.sendline('var arr_1 --export ' + csv_file)
+            self.debugger.expect_exact('Exported 5 items')
+            with open(csv_file) as f:
+                self.assertEqual(f.read(), 'index,value\n0,1\n1,-1\n2,2\n3,-2\n4,3\n')
+
+            json_file = os.path.join(tmp_dir, 'arr')
+            self.debugger.sendline('var arr_2 --export ' + json_file + ' --format json')
+            self.debugger.expect_exact('Exported 3 items')
+            with open(json_file) as f:
+                self.assertEqual(json.load(f)[2], [0, -1, -2, -3, -4])
+
     def test_read_enum(self):
         """Reading rust enums"""
         self.debugger.sendline('break vars.rs:93')
@@ -570,9 +594,7 @@ class VariablesTestCase(unittest.TestCase):
         self.debugger.expect_exact('0: ()(())')
         self.debugger.expect_exact('}')
 
-        self.debugger.expect_exact('enum_zst = Option<()>::Some {')
-        self.debugger.expect_exact('0: ()(())')
-        self.debugger.expect_exact('}')
+        self.debugger.expect_exact('enum_zst = Option<()>::Some(()(()))')
 
         self.debugger.expect_exact(
             'vecdeque_zst = VecDeque<(), alloc::alloc::Global> {')